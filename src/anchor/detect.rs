@@ -568,6 +568,15 @@ pub fn enclosing_function(
 
     trace!("Searching for enclosing function at {}:{}", full_path, line);
 
+    // Innermost extracted function or method containing the line
+    if let Some(hits) = extractor_hits(&full_path, file)?
+    {
+        return Ok(hits
+            .into_iter()
+            .filter(|h| h.start_line <= line && line <= h.end_line)
+            .max_by_key(|h| h.start_line));
+    }
+
     let matcher = FunctionMatcher::new()?;
     let mut sink = FunctionSink {
         hits: IndexMap::new(),
@@ -643,6 +652,44 @@ pub fn nearest_functions(
         root.join(file)
     };
 
+    let mut functions = match extractor_hits(&full_path, file)?
+    {
+        Some(hits) => hits,
+        None => scan_functions(&full_path, file, k)?,
+    };
+
+    // Sort by distance from target line with stable tie-breakers
+    functions.sort_by(|a, b| {
+        let dist_a = calculate_distance(a, line);
+        let dist_b = calculate_distance(b, line);
+
+        dist_a
+            .cmp(&dist_b)
+            .then_with(|| {
+                a.start_line
+                    .cmp(&b.start_line)
+            })
+            .then_with(|| {
+                a.name
+                    .cmp(&b.name)
+            })
+    });
+
+    functions.truncate(k);
+
+    debug!("Found {} nearest functions", functions.len());
+
+    Ok(functions)
+}
+
+/// Regex scan of a Rust file for function signatures, with end lines
+/// refined for the candidates `nearest_functions` is likely to return
+fn scan_functions(
+    full_path: &Utf8Path,
+    file: &Utf8Path,
+    k: usize,
+) -> Result<Vec<FnHit>>
+{
     let matcher = FunctionMatcher::new()?;
     let mut sink = FunctionSink {
         hits: IndexMap::new(),
@@ -650,7 +697,7 @@ pub fn nearest_functions(
         line_offset: 0,
     };
 
-    let file_handle = File::open(&full_path)?;
+    let file_handle = File::open(full_path)?;
     let mut searcher = SearcherBuilder::new()
         .binary_detection(BinaryDetection::quit(b'\x00'))
         .line_number(true)
@@ -672,31 +719,52 @@ pub fn nearest_functions(
         .take(top_candidates)
     {
         // Use Tree-sitter for precise end_line when available
-        hit.end_line = estimate_function_end(&full_path, hit.start_line).unwrap_or(hit.start_line);
+        hit.end_line = estimate_function_end(full_path, hit.start_line).unwrap_or(hit.start_line);
     }
+    Ok(functions)
+}
 
-    // Sort by distance from target line with stable tie-breakers
-    functions.sort_by(|a, b| {
-        let dist_a = calculate_distance(a, line);
-        let dist_b = calculate_distance(b, line);
+/// Functions and methods from the symbol extractor for JVM sources, which
+/// the Rust signature scan does not understand; `None` for other files
+fn extractor_hits(
+    full_path: &Utf8Path,
+    file: &Utf8Path,
+) -> Result<Option<Vec<FnHit>>>
+{
+    let lang = match full_path.extension()
+    {
+        Some("java") => "java",
+        Some("kt" | "kts") => "kotlin",
+        _ => return Ok(None),
+    };
+    let code = std::fs::read_to_string(full_path)
+        .with_context(|| format!("Failed to read {}", full_path))?;
+    let symbols = crate::core::symbols::get_extractor(lang)?
+        .extract_symbols(&code, file.as_std_path())?;
 
-        dist_a
-            .cmp(&dist_b)
-            .then_with(|| {
-                a.start_line
-                    .cmp(&b.start_line)
-            })
-            .then_with(|| {
-                a.name
-                    .cmp(&b.name)
+    let hits = symbols
+        .into_iter()
+        .filter_map(|s| {
+            let kind = match s.kind
+            {
+                crate::core::symbols::SymbolKind::Function => FnKind::Function,
+                crate::core::symbols::SymbolKind::Method => FnKind::Method,
+                _ => return None,
+            };
+            Some(FnHit {
+                name: s.name,
+                qualified_name: s.qualified_name,
+                file: file
+                    .as_str()
+                    .to_owned(),
+                start_line: s.start_line,
+                end_line: s.end_line,
+                kind,
+                confidence: 1.0, // Exact spans from the parse tree
             })
-    });
-
-    functions.truncate(k);
-
-    debug!("Found {} nearest functions", functions.len());
-
-    Ok(functions)
+        })
+        .collect();
+    Ok(Some(hits))
 }
 
 /// Tree-sitter based function end line detection for precise spans.
@@ -706,15 +774,7 @@ fn ts_function_end_line_for_start(
     start_line_1b: usize,
 ) -> Option<usize>
 {
-    let mut parser = tree_sitter::Parser::new();
-    if parser
-        .set_language(lang)
-        .is_err()
-    {
-        return None;
-    }
-
-    let tree = parser.parse(code, None)?;
+    let tree = crate::infra::utils::TsNodeUtils::parse(lang, code).ok()?;
     let root = tree.root_node();
 
     // Walk the tree to find function_item nodes
@@ -774,15 +834,7 @@ fn ts_detect_kind(
     start_line_1b: usize,
 ) -> Option<FnKind>
 {
-    let mut parser = tree_sitter::Parser::new();
-    if parser
-        .set_language(lang)
-        .is_err()
-    {
-        return None;
-    }
-
-    let tree = parser.parse(code, None)?;
+    let tree = crate::infra::utils::TsNodeUtils::parse(lang, code).ok()?;
     let root = tree.root_node();
 
     let mut cursor = root.walk();
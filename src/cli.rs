@@ -53,6 +53,7 @@ pub struct AppContext
     pub quiet: bool,    // global --quiet
     pub no_color: bool, // global --no-color
     pub dry_run: bool,  // global --dry-run
+    pub agent: Option<crate::cli_ext::agent::AgentReport>, // global --agent-mode
 }
 
 #[derive(Debug, Parser)]
@@ -78,9 +79,13 @@ pub struct Cli
     /// Show what would be done without executing
     #[arg(long, global = true)]
     pub dry_run: bool,
+
+    /// Emit a single JSON result envelope on stdout; route human text to stderr
+    #[arg(long, global = true)]
+    pub agent_mode: bool,
 }
 
-#[expect(
+#[allow(
     clippy::large_enum_variant,
     reason = "The `Commands` enum carries several large argument structs (e.g., `ContextArgs`)
 which triggers `clippy::large-enum-variant`. We intentionally keep the direct
@@ -100,6 +105,9 @@ pub enum Commands
     /// Extract symbol information from source files
     Symbols(SymbolsArgs),
 
+    /// Fuzzy-find symbols in the index by name, qualified name, or file
+    Search(SearchArgs),
+
     /// Split extracted content into token-sized chunks
     Chunk(ChunkArgs),
 
@@ -112,6 +120,9 @@ pub enum Commands
     /// Validate edit syntax and check for conflicts
     CheckSyntax(CheckSyntaxArgs),
 
+    /// Convert an edit spec into .patch files without touching the worktree
+    Patch(PatchArgs),
+
     /// Create backup of files before editing
     Backup(BackupArgs),
 
@@ -129,12 +140,43 @@ pub enum Commands
 
     /// Validate anchor positions and suggest improvements
     Anchor(AnchorArgs),
+
+    /// Git integration helpers (hook installation)
+    Git(GitArgs),
+}
+
+impl Commands
+{
+    /// Stable subcommand name, as typed on the command line
+    pub fn name(&self) -> &'static str
+    {
+        match self
+        {
+            Commands::Extract(_) => "extract",
+            Commands::Tree(_) => "tree",
+            Commands::Symbols(_) => "symbols",
+            Commands::Search(_) => "search",
+            Commands::Chunk(_) => "chunk",
+            Commands::Apply(_) => "apply",
+            Commands::Preview(_) => "preview",
+            Commands::CheckSyntax(_) => "check-syntax",
+            Commands::Patch(_) => "patch",
+            Commands::Backup(_) => "backup",
+            Commands::Init(_) => "init",
+            Commands::Completions(_) => "completions",
+            Commands::Context(_) => "context",
+            Commands::Resolve(_) => "resolve",
+            Commands::Anchor(_) => "anchor",
+            Commands::Git(_) => "git",
+        }
+    }
 }
 
 #[derive(Debug, Parser)]
 pub struct ExtractArgs
 {
-    /// Files and line ranges (format: file.rs:10-20,25-30)
+    /// Files and line ranges (format: file.rs:10-20,25-30), or Markdown
+    /// sections by heading (README.md:#installation)
     pub targets: Vec<String>,
 
     /// Output file path
@@ -161,10 +203,40 @@ pub struct ExtractArgs
     #[arg(long, default_value = "0")]
     pub merge_within: usize,
 
-    /// Use this GPT model for token estimation (e.g., gpt-4o, o200k_base)
+    /// Use this model for token estimation (e.g., gpt-4o, o200k_base,
+    /// claude-3-5-sonnet)
     #[arg(long, default_value = "gpt-4o")]
     pub model: String,
 
+    /// Count tokens with this HuggingFace `tokenizer.json` instead of
+    /// --model's tokenizer (Llama, Mistral, Qwen)
+    #[arg(long, value_name = "FILE")]
+    pub tokenizer_file: Option<PathBuf>,
+
+    /// Estimate token counts from byte length while picking what fits the
+    /// budget, then count only the final output exactly; much faster with
+    /// many candidates
+    #[arg(long)]
+    pub approx_tokens: bool,
+
+    /// Neither read nor save token counts in `.rup/cache`, which spares
+    /// re-tokenizing unchanged snippets on later runs
+    #[arg(long)]
+    pub no_token_cache: bool,
+
+    /// Print to stderr every candidate's tokens and priority, whether it
+    /// was kept, trimmed, or dropped, and why (budget, bucket cap, novelty,
+    /// dedupe)
+    #[arg(
+        long,
+        value_enum,
+        value_name = "FORMAT",
+        num_args = 0..=1,
+        default_missing_value = "text",
+        requires = "budget"
+    )]
+    pub explain_budget: Option<ExplainBudgetFormat>,
+
     /// Token budget for the final assembled context
     #[arg(long)]
     pub budget: Option<usize>,
@@ -192,16 +264,97 @@ pub struct TreeArgs
     /// Maximum depth to traverse
     #[arg(short, long)]
     pub depth: Option<usize>,
+
+    /// Also show token counts, per file and summed per directory, for this
+    /// model or encoding, Claude included (`--tokens` alone uses `[chunk] model`)
+    #[arg(long, value_name = "MODEL", num_args = 0..=1, default_missing_value = "")]
+    pub tokens: Option<String>,
+
+    /// Count --tokens with this HuggingFace `tokenizer.json` instead
+    #[arg(long, value_name = "FILE", requires = "tokens")]
+    pub tokenizer_file: Option<PathBuf>,
+
+    /// Mark files changed in `git status` (M, A, R, U, ? for untracked) and
+    /// the directories holding them (*)
+    #[arg(long)]
+    pub git_status: bool,
+
+    /// Only show files of these languages or extensions, comma-separated
+    /// (`rust,python`, `rs,py`)
+    #[arg(long, value_delimiter = ',')]
+    pub lang: Vec<String>,
+
+    /// Only show files matching these globs, relative to the root
+    /// (`src/**`, or a directory such as `src`)
+    #[arg(long, value_name = "GLOB")]
+    pub include: Vec<String>,
+
+    /// Hide files matching these globs, relative to the root
+    #[arg(long, value_name = "GLOB")]
+    pub exclude: Vec<String>,
+
+    /// Print a directory with more than N entries as one line with its
+    /// file count instead of listing it
+    #[arg(long, value_name = "N")]
+    pub collapse_over: Option<usize>,
+
+    /// List each source file's top-level symbols under it, from the index
+    /// (`[symbols] output_file`; run `rup symbols` first)
+    #[arg(long)]
+    pub symbols: bool,
+
+    /// Print the tree as text, or as a Mermaid flowchart or Graphviz DOT
+    /// graph for docs and diagrams (always printed, even with --quiet)
+    #[arg(long, value_enum, default_value = "text")]
+    pub format: TreeFormat,
+
+    /// Order entries by name, or largest, newest, or most tokens first
+    /// (`tokens` needs --tokens)
+    #[arg(long, value_enum, default_value = "name")]
+    pub sort: TreeSort,
+
+    /// Show each file's size and each directory's total size below it
+    #[arg(long)]
+    pub du: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum TreeSort
+{
+    /// Alphabetical
+    Name,
+    /// Bytes on disk, largest first; directories by their total
+    Size,
+    /// Last modified, newest first; directories by their newest file
+    Mtime,
+    /// Token count, largest first
+    Tokens,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum TreeFormat
+{
+    /// Indented tree with colors
+    Text,
+    /// Mermaid `flowchart LR`
+    Mermaid,
+    /// Graphviz `digraph`
+    Dot,
 }
 
 #[derive(Debug, Clone, Parser)]
+#[command(args_conflicts_with_subcommands = true)]
 pub struct SymbolsArgs
 {
+    #[command(subcommand)]
+    pub command: Option<SymbolsSubcommand>,
+
     /// Root directory to scan
     #[arg(default_value = ".")]
     pub path: PathBuf,
 
-    /// Languages to include (rust, python, javascript, typescript, go, cpp)
+    /// Languages to include (rust, python, javascript, typescript, go, java, kotlin, csharp,
+    /// ruby, php, markdown, toml, yaml, json, sql, proto, hcl, bash, vue, svelte)
     #[arg(short, long)]
     pub languages: Vec<String>,
 
@@ -212,34 +365,290 @@ pub struct SymbolsArgs
     /// Include private symbols
     #[arg(long)]
     pub include_private: bool,
+
+    /// Also record call sites, next to the index as `<output stem>.refs.jsonl`
+    #[arg(long)]
+    pub refs: bool,
+
+    /// Also record each declaration's signature: its header up to the body
+    /// (params, return type, generics), on one line. Doc comments are always
+    /// recorded.
+    #[arg(long)]
+    pub signatures: bool,
+
+    /// Also embed each symbol with the embedder under `[symbols.embeddings]`,
+    /// next to the index as `<output stem>.embeddings.jsonl`; `rup context
+    /// --semantic` then ranks by similarity
+    #[arg(long)]
+    pub embeddings: bool,
+
+    /// Also export the symbols: an editor tags file in the scanned root
+    /// (`tags` for vim, `TAGS` for emacs) or a SQLite database next to the
+    /// index (needs the `sqlite3` tool)
+    #[arg(long, value_enum, default_value = "jsonl")]
+    pub format: SymbolsFormat,
+
+    /// Only index these kinds, comma-separated (fn, struct, class, method,
+    /// ...)
+    #[arg(short, long, value_delimiter = ',')]
+    pub kind: Vec<String>,
+
+    /// Only index files matching these globs, relative to the root
+    /// (`src/**`, or a directory such as `src`)
+    #[arg(long, value_name = "GLOB")]
+    pub include: Vec<String>,
+
+    /// Skip files matching these globs, relative to the root
+    #[arg(long, value_name = "GLOB")]
+    pub exclude: Vec<String>,
+
+    /// Keep running and update the index as files change, re-extracting
+    /// only what changed; `rup context` and `rup search` trust a watched
+    /// index without re-checking it
+    #[arg(long)]
+    pub watch: bool,
+
+    /// Write one index per workspace member (or top-level directory) under
+    /// `shards/` next to the output, listed in `<output stem>.shards.json`;
+    /// `rup context` then loads and refreshes only the shards it needs
+    #[arg(long, conflicts_with_all = ["format", "watch"])]
+    pub shard: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum SymbolsFormat
+{
+    /// Only the JSONL index
+    Jsonl,
+    /// JSONL index plus an Exuberant/Universal ctags `tags` file
+    Ctags,
+    /// JSONL index plus an Emacs `TAGS` file
+    Etags,
+    /// JSONL index plus `<output stem>.sqlite`, in the documented schema
+    Sqlite,
+}
+
+#[derive(Debug, Clone, Subcommand)]
+pub enum SymbolsSubcommand
+{
+    /// Report public symbols added, removed, or changed between two git
+    /// revisions, with signature-level diffs
+    Diff(SymbolsDiffArgs),
+}
+
+#[derive(Debug, Clone, Parser)]
+pub struct SymbolsDiffArgs
+{
+    /// Base revision (tag, branch, or commit)
+    pub rev_a: String,
+
+    /// Revision compared against the base
+    pub rev_b: String,
+
+    /// Only compare files under these paths
+    #[arg(last = true)]
+    pub paths: Vec<String>,
+
+    /// Languages to include (defaults to the configured symbol languages)
+    #[arg(short, long)]
+    pub languages: Vec<String>,
+
+    /// Include private symbols
+    #[arg(long)]
+    pub include_private: bool,
+
+    /// Machine-readable JSON output
+    #[arg(long)]
+    pub json: bool,
+}
+
+#[derive(Debug, Parser)]
+pub struct SearchArgs
+{
+    /// Text to fuzzy-match; space-separated terms must all match
+    #[arg(value_name = "QUERY", required = true)]
+    pub query: Vec<String>,
+
+    /// Project root, used to build or refresh the index
+    #[arg(long, default_value = ".")]
+    pub path: PathBuf,
+
+    /// Symbols index path
+    #[arg(long, default_value = ".rup/symbols.jsonl")]
+    pub symbols: PathBuf,
+
+    /// Only these kinds, comma-separated (fn, method, struct, ...)
+    #[arg(short, long, value_delimiter = ',')]
+    pub kind: Vec<String>,
+
+    /// Only these visibilities, comma-separated (public, private, ...)
+    #[arg(long, value_delimiter = ',')]
+    pub visibility: Vec<String>,
+
+    /// Maximum results to show
+    #[arg(short = 'n', long, default_value_t = 20)]
+    pub limit: usize,
+
+    /// Machine-readable JSON output
+    #[arg(long)]
+    pub json: bool,
 }
 
 #[derive(Debug, Parser)]
 pub struct ChunkArgs
 {
-    /// Input file to chunk
+    /// Input file to chunk, or a directory to chunk every file below
     pub input: PathBuf,
 
-    /// Maximum tokens per chunk
-    #[arg(long, default_value = "4000")]
-    pub max_tokens: usize,
+    /// Maximum tokens per chunk [default: 4000, or the preset's]
+    #[arg(long)]
+    pub max_tokens: Option<usize>,
 
-    /// GPT model (gpt-4, gpt-4o, gpt-3.5-turbo) or encoding (o200k_base, cl100k_base)
-    #[arg(short, long, default_value = "gpt-4o")]
-    pub model: String,
+    /// GPT model (gpt-4, gpt-4o, gpt-3.5-turbo) or encoding (o200k_base,
+    /// cl100k_base) [default: gpt-4o, or the preset's]
+    #[arg(short, long)]
+    pub model: Option<String>,
+
+    /// Named bundle of model, --max-tokens, and --overlap: built in are
+    /// `gpt-4o`, `claude-200k`, and `embedding-512`; more under
+    /// `[chunk.presets.NAME]` in config. Flags given as well win
+    #[arg(long, value_name = "NAME")]
+    pub preset: Option<String>,
 
     /// Output directory for chunks
-    #[arg(short, long, default_value = "chunks")]
+    #[arg(short, long, visible_alias = "out-dir", default_value = "chunks")]
     pub output_dir: PathBuf,
 
-    /// Prefer symbol boundaries when chunking
+    /// Write chunk files into subdirectories of --output-dir by language
+    /// (`rust/`) or by top-level directory under the input (`src/`; files
+    /// directly in it go to `_root/`). The manifest stays at the top
+    #[arg(long, value_enum)]
+    pub partition: Option<ChunkPartition>,
+
+    /// Prefer symbol boundaries when chunking; `false` is `--strategy tokens`
     #[arg(long, default_value = "true")]
     #[arg(action = clap::ArgAction::Set)]
     pub by_symbols: bool,
 
-    /// Token overlap between chunks
-    #[arg(long, default_value = "128")]
-    pub overlap: usize,
+    /// Where chunks break: `symbol` splits at function/class boundaries,
+    /// merging small symbols and splitting oversized ones (and text with no
+    /// symbols) at headings, paragraphs, and comment edges; `tokens` cuts
+    /// every --max-tokens. Defaults to `symbol` unless --by-symbols false
+    #[arg(long, value_enum)]
+    pub strategy: Option<ChunkStrategy>,
+
+    /// Context each chunk repeats from the end of the one before: `N` or
+    /// `Ntok` tokens, `Nl` or `Nlines` lines. Symbol chunks take whole
+    /// lines; token overlap counts toward --max-tokens, line overlap is
+    /// added on top. Defaults to 128 tokens for `tokens`, none for `symbol`
+    #[arg(long, value_name = "N[tok|lines]")]
+    pub overlap: Option<ChunkOverlap>,
+
+    /// `files` writes chunk files and a manifest to --output-dir; `jsonl`
+    /// prints one record per chunk to stdout for embedding pipelines
+    #[arg(long, value_enum, default_value = "files")]
+    pub format: ChunkFormat,
+
+    /// Manifest or JSONL output of an earlier run to compare chunk IDs
+    /// with. `files` writes the difference to `chunks_changes.json` (by
+    /// default against the manifest already in --output-dir); `jsonl` then
+    /// prints only new chunks, plus `{id, path, removed: true}` for each
+    /// one that is gone
+    #[arg(long, value_name = "FILE")]
+    pub since: Option<PathBuf>,
+
+    /// Keep the chunks of sources an interrupted run already finished
+    /// (unchanged since, with their chunk files in --output-dir) and chunk
+    /// only the rest
+    #[arg(long)]
+    pub resume: bool,
+
+    /// Only chunk files of these languages or extensions, comma-separated
+    /// (`rust,python`, `rs,py`)
+    #[arg(long, value_delimiter = ',')]
+    pub lang: Vec<String>,
+
+    /// Only chunk files matching these globs, relative to the input
+    /// directory (`src/**`, or a directory such as `src`)
+    #[arg(long, value_name = "GLOB")]
+    pub include: Vec<String>,
+
+    /// Skip files matching these globs, relative to the input directory
+    /// (`vendor`, `**/*.min.js`)
+    #[arg(long, value_name = "GLOB")]
+    pub exclude: Vec<String>,
+
+    /// Skip files with fewer tokens than this
+    #[arg(long, value_name = "N", default_value_t = 0)]
+    pub min_tokens: usize,
+
+    /// Skip files larger than this many bytes (0 disables the limit)
+    #[arg(long, value_name = "BYTES", default_value_t = 0)]
+    pub max_file_bytes: u64,
+
+    /// Source bytes chunked in parallel at once, in MiB; a larger file is
+    /// chunked on its own
+    #[arg(long, value_name = "MIB", default_value_t = 256)]
+    pub in_flight_mb: u64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum ChunkPartition
+{
+    /// One subdirectory per language, `other/` for unrecognized files
+    Lang,
+    /// One subdirectory per top-level directory of the input
+    TopDir,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum ChunkFormat
+{
+    /// `chunk_NNN.txt` files plus `chunks_manifest.jsonl`
+    Files,
+    /// `{id, path, start_line, end_line, tokens, text, lang}` per line
+    Jsonl,
+}
+
+/// Amount of context shared by consecutive chunks
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChunkOverlap
+{
+    Tokens(usize),
+    Lines(usize),
+}
+
+impl FromStr for ChunkOverlap
+{
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err>
+    {
+        let k = s
+            .trim()
+            .to_ascii_lowercase();
+        let digits = k
+            .find(|c: char| !c.is_ascii_digit())
+            .unwrap_or(k.len());
+        let n = k[..digits]
+            .parse::<usize>()
+            .map_err(|_| format!("invalid overlap: {s} (use N, Ntok, or Nlines)"))?;
+        match k[digits..].trim()
+        {
+            "" | "t" | "tok" | "tokens" => Ok(ChunkOverlap::Tokens(n)),
+            "l" | "line" | "lines" => Ok(ChunkOverlap::Lines(n)),
+            _ => Err(format!("invalid overlap: {s} (use N, Ntok, or Nlines)")),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum ChunkStrategy
+{
+    /// At top-level symbol boundaries from the tree-sitter extractor
+    Symbol,
+    /// Fixed token windows
+    Tokens,
 }
 
 #[derive(Debug, Clone, Copy, clap::ValueEnum)]
@@ -251,13 +660,15 @@ pub enum MatchMode
 }
 
 #[derive(Debug, Parser)]
+#[command(group(clap::ArgGroup::new("commits").args(["commit", "branch"]).multiple(true)))]
 pub struct ApplyArgs
 {
-    /// Edit specification file to apply
-    pub edit_file: Option<PathBuf>,
+    /// Edit specification file(s) to apply; several are checked for
+    /// cross-file overlap and applied as one batch with a single backup session
+    pub edit_files: Vec<PathBuf>,
 
     /// Read edit specification from clipboard
-    #[arg(long, conflicts_with = "edit_file")]
+    #[arg(long, conflicts_with = "edit_files")]
     pub from_clipboard: bool,
 
     /// Preview changes without applying them (deprecated, use default behavior)
@@ -280,6 +691,49 @@ pub struct ApplyArgs
     #[arg(long)]
     pub backup: bool,
 
+    /// Label the backup session; `backup restore <LABEL>` then finds it
+    #[arg(long, requires = "backup")]
+    pub label: Option<String>,
+
+    /// Free-form note stored with the backup session
+    #[arg(long, requires = "backup")]
+    pub note: Option<String>,
+
+    /// Files the backup captures: only those written, or everything in the
+    /// directories the edit spec touches (so restores undo formatter hooks)
+    #[arg(long, value_enum, default_value = "written", requires = "backup")]
+    pub backup_scope: BackupScopeMode,
+
+    /// Apply hunks to the index only (`git apply --cached`), leaving the
+    /// worktree untouched; needs --engine git. Patches are built from the
+    /// worktree files, so staged-but-different regions will conflict
+    #[arg(long, requires = "apply", conflicts_with_all = ["commit", "sandbox"])]
+    pub stage: bool,
+
+    /// Commit exactly the files the apply touched
+    #[arg(long, requires = "apply")]
+    pub commit: bool,
+
+    /// Apply to this branch (created from HEAD if missing) through a
+    /// temporary worktree and commit there; the current branch and working
+    /// tree are left untouched. Line numbers refer to the branch's content
+    #[arg(
+        long,
+        value_name = "NAME",
+        conflicts_with_all = ["commit", "stage", "sandbox", "resolve"]
+    )]
+    pub branch: Option<String>,
+
+    /// Commit message template; {summary}, {stats}, {files}, {session}, and
+    /// {engine} are filled in, and lines using {session} are dropped without
+    /// a backup
+    #[arg(short = 'm', long = "message", value_name = "TEMPLATE", requires = "commits")]
+    pub commit_message: Option<String>,
+
+    /// Add a Signed-off-by trailer to the commit
+    #[arg(long, requires = "commits")]
+    pub signoff: bool,
+
     /// Force apply even with conflicts
     #[arg(long)]
     pub force: bool,
@@ -288,19 +742,33 @@ pub struct ApplyArgs
     #[arg(long)]
     pub verbose: bool,
 
-    /// Apply engine: internal (fast, clear errors), git (robust, 3-way merge), auto
-    /// (fallback)
-    #[arg(long, default_value = "internal")]
-    pub engine: ApplyEngine,
+    /// Apply engine by registered name: internal (fast, clear errors), git (robust,
+    /// 3-way merge), auto (fallback), jj (Jujutsu repos), or an engine added via
+    /// the registry
+    #[arg(long, default_value = "internal", value_name = "NAME")]
+    pub engine: String,
+
+    /// List registered apply engines with their capabilities and exit
+    #[arg(long)]
+    pub list_engines: bool,
 
     /// Git apply mode when using git engine
     #[arg(long, default_value = "3way")]
     pub git_mode: GitMode,
 
+    /// Git implementation used by the git engine
+    #[arg(long, value_enum, default_value = "cli")]
+    pub git_backend: GitBackendMode,
+
     /// Context lines for patch generation
     #[arg(long, default_value = "3")]
     pub context_lines: usize,
 
+    /// Context lines for files matching GLOB, e.g. '*.lock=0' for minimal
+    /// patches; repeatable, adds to `[[apply.context]]` rules in the config
+    #[arg(long, value_name = "GLOB=N")]
+    pub context_for: Vec<crate::core::patch::ContextRule>,
+
     /// Whitespace handling for git apply
     #[arg(long, default_value = "nowarn")]
     pub whitespace: WhitespaceMode,
@@ -312,17 +780,38 @@ pub struct ApplyArgs
     /// Auto-resolve conflicts using smart conflict resolution (uses backup)
     #[arg(long)]
     pub resolve: bool,
-}
 
-#[derive(Debug, Clone, ValueEnum)]
-pub enum ApplyEngine
-{
-    /// Fast internal engine with clear error messages
-    Internal,
-    /// Git apply engine with 3-way merge capability  
-    Git,
-    /// Try internal first, fallback to git on conflicts
-    Auto,
+    /// Allow edit-spec paths outside the repository root (absolute, `..`, symlinks)
+    #[arg(long)]
+    pub allow_outside_repo: bool,
+
+    /// Max lines a REPLACE may move from its declared span before it is a conflict
+    /// (unbounded if unset)
+    #[arg(long, value_name = "N")]
+    pub max_drift: Option<usize>,
+
+    /// Line endings for written files: preserve each line's own, normalize to
+    /// lf or crlf, or auto (normalize to the file's dominant ending)
+    #[arg(long, value_enum, default_value = "preserve")]
+    pub eol: crate::core::edit::EolPolicy,
+
+    /// Refuse target files larger than this many bytes (0 disables the limit)
+    #[arg(long, value_name = "BYTES", default_value_t = crate::core::edit::DEFAULT_MAX_FILE_BYTES)]
+    pub max_file_size: u64,
+
+    /// Edit files that contain NUL bytes as text instead of refusing them
+    #[arg(long)]
+    pub force_binary: bool,
+
+    /// Trial-apply in a scratch git worktree first; the real tree is only
+    /// written if the trial (and --verify, when given) succeeds
+    #[arg(long)]
+    pub sandbox: bool,
+
+    /// Command run inside the sandbox worktree after the trial apply, e.g.
+    /// "cargo test"
+    #[arg(long, value_name = "CMD", requires = "sandbox")]
+    pub verify: Option<String>,
 }
 
 #[derive(Debug, Clone, ValueEnum)]
@@ -338,6 +827,24 @@ pub enum GitMode
     Worktree,
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum ColorMode
+{
+    /// Color when stdout is a terminal
+    Auto,
+    Always,
+    Never,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum GitBackendMode
+{
+    /// Run the `git` binary on PATH
+    Cli,
+    /// Use the bundled libgit2 (no `git` binary needed; 3-way merges against the index)
+    Lib,
+}
+
 #[derive(Debug, Clone, Copy, ValueEnum)]
 pub enum WhitespaceMode
 {
@@ -347,6 +854,9 @@ pub enum WhitespaceMode
     Warn,
     /// Fix whitespace issues automatically
     Fix,
+    /// Match NEW content's tabs/spaces to each target file, then ignore
+    /// remaining whitespace issues
+    Auto,
 }
 
 #[derive(Debug, Parser)]
@@ -363,6 +873,18 @@ pub struct PreviewArgs
     #[arg(long, default_value = "true")]
     pub show_diff: bool,
 
+    /// Color the diff with syntax highlighting and word-level emphasis of
+    /// changed text (`--color` alone means always)
+    #[arg(
+        long,
+        value_enum,
+        value_name = "WHEN",
+        default_value = "never",
+        num_args = 0..=1,
+        default_missing_value = "always"
+    )]
+    pub color: ColorMode,
+
     /// Matching strategy: line, token, or auto (token then line)
     #[arg(long, value_enum, default_value = "auto")]
     pub match_mode: MatchMode,
@@ -371,15 +893,24 @@ pub struct PreviewArgs
     #[arg(long)]
     pub repo_root: Option<PathBuf>,
 
-    /// Apply engine: internal (fast, clear errors), git (robust, 3-way merge), auto
-    /// (fallback)
-    #[arg(long, default_value = "internal")]
-    pub engine: ApplyEngine,
+    /// Apply engine by registered name: internal (fast, clear errors), git (robust,
+    /// 3-way merge), auto (fallback), jj (Jujutsu repos), or an engine added via
+    /// the registry
+    #[arg(long, default_value = "internal", value_name = "NAME")]
+    pub engine: String,
+
+    /// List registered apply engines with their capabilities and exit
+    #[arg(long)]
+    pub list_engines: bool,
 
     /// Git apply mode when using git engine
     #[arg(long, default_value = "3way")]
     pub git_mode: GitMode,
 
+    /// Git implementation used by the git engine
+    #[arg(long, value_enum, default_value = "cli")]
+    pub git_backend: GitBackendMode,
+
     /// Whitespace handling for git apply
     #[arg(long, default_value = "nowarn")]
     pub whitespace: WhitespaceMode,
@@ -391,6 +922,32 @@ pub struct PreviewArgs
     /// Context lines for patch generation (matches --apply)
     #[arg(long, default_value = "3")]
     pub context_lines: usize,
+
+    /// Context lines for files matching GLOB (matches --apply)
+    #[arg(long, value_name = "GLOB=N")]
+    pub context_for: Vec<crate::core::patch::ContextRule>,
+
+    /// Allow edit-spec paths outside the repository root (absolute, `..`, symlinks)
+    #[arg(long)]
+    pub allow_outside_repo: bool,
+
+    /// Max lines a REPLACE may move from its declared span before it is a conflict
+    /// (unbounded if unset)
+    #[arg(long, value_name = "N")]
+    pub max_drift: Option<usize>,
+
+    /// Line endings for written files: preserve each line's own, normalize to
+    /// lf or crlf, or auto (normalize to the file's dominant ending)
+    #[arg(long, value_enum, default_value = "preserve")]
+    pub eol: crate::core::edit::EolPolicy,
+
+    /// Refuse target files larger than this many bytes (0 disables the limit)
+    #[arg(long, value_name = "BYTES", default_value_t = crate::core::edit::DEFAULT_MAX_FILE_BYTES)]
+    pub max_file_size: u64,
+
+    /// Edit files that contain NUL bytes as text instead of refusing them
+    #[arg(long)]
+    pub force_binary: bool,
 }
 #[derive(Debug, Parser)]
 pub struct CheckSyntaxArgs
@@ -399,6 +956,119 @@ pub struct CheckSyntaxArgs
     pub edit_file: PathBuf,
 }
 
+#[derive(Debug, Parser)]
+pub struct PatchArgs
+{
+    /// Edit specification file (EBNF, or JSON when it starts with '{')
+    #[arg(required_unless_present = "from_clipboard")]
+    pub edit_file: Option<PathBuf>,
+
+    /// Read edit specification from clipboard
+    #[arg(long, conflicts_with = "edit_file")]
+    pub from_clipboard: bool,
+
+    /// Combined patch file, or the directory for --split (default: stdout,
+    /// or ./patches with --split)
+    #[arg(short, long, value_name = "PATH")]
+    pub output: Option<PathBuf>,
+
+    /// Write one NNNN-<path>.patch per file instead of a combined patch
+    #[arg(long)]
+    pub split: bool,
+
+    /// Context lines around each hunk
+    #[arg(long, default_value = "3")]
+    pub context_lines: usize,
+
+    /// Context lines for files matching GLOB, e.g. '*.lock=0'; repeatable
+    #[arg(long, value_name = "GLOB=N")]
+    pub context_for: Vec<crate::core::patch::ContextRule>,
+
+    /// Skip GUARD-CID checks against the current files
+    #[arg(long)]
+    pub no_guard: bool,
+
+    /// Output format: plain unified diff, or git format-patch emails
+    /// (one per file) that `git am` applies as a series
+    #[arg(long, value_enum, default_value = "diff")]
+    pub format: PatchFormat,
+
+    /// Email subject template (mbox): {path}, {n}, {total}, {added}, {removed}
+    #[arg(long, default_value = "Update {path}")]
+    pub subject: String,
+
+    /// Email author template (mbox): {name} and {email} come from git config
+    #[arg(long, default_value = "{name} <{email}>")]
+    pub author: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum PatchFormat
+{
+    /// Unified diff for `git apply`
+    Diff,
+    /// `git format-patch` compatible emails for `git am`
+    Mbox,
+}
+
+#[derive(Debug, Parser)]
+pub struct GitArgs
+{
+    #[command(subcommand)]
+    pub command: GitSubcommand,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum GitSubcommand
+{
+    /// Install hooks that check staged edit specs and block unresolved
+    /// conflict markers
+    InstallHooks(InstallHooksArgs),
+
+    /// Run a hook's checks (called by the installed hook scripts)
+    #[command(hide = true)]
+    RunHook(RunHookArgs),
+}
+
+#[derive(Debug, Parser)]
+pub struct InstallHooksArgs
+{
+    /// Hooks to install
+    #[arg(long = "hook", value_enum, default_values_t = [GitHook::PreCommit, GitHook::PrePush])]
+    pub hooks: Vec<GitHook>,
+
+    /// Glob for edit-spec files the hooks run `rup check-syntax` on (repeatable)
+    #[arg(long = "spec-glob", value_name = "GLOB", default_value = "*.ebnf")]
+    pub spec_globs: Vec<String>,
+
+    /// Replace existing hooks not installed by rup (kept as <hook>.rup-backup)
+    #[arg(long)]
+    pub force: bool,
+}
+
+#[derive(Debug, Parser)]
+pub struct RunHookArgs
+{
+    /// Hook being run
+    #[arg(value_enum)]
+    pub hook: GitHook,
+
+    /// Glob for edit-spec files to syntax-check (repeatable)
+    #[arg(long = "spec-glob", value_name = "GLOB")]
+    pub spec_globs: Vec<String>,
+
+    /// Arguments git passes to the hook (pre-push: remote name and URL)
+    #[arg(trailing_var_arg = true, allow_hyphen_values = true)]
+    pub hook_args: Vec<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum GitHook
+{
+    PreCommit,
+    PrePush,
+}
+
 #[derive(Debug, Parser)]
 pub struct BackupArgs
 {
@@ -420,6 +1090,27 @@ pub enum BackupSubcommand
 
     /// Clean up old backup sessions
     Cleanup(BackupCleanupArgs),
+
+    /// Apply [backup] retention limits, then remove unreferenced file versions
+    Gc(BackupGcArgs),
+
+    /// Unified diffs between two sessions, or a session and the worktree
+    Diff(BackupDiffArgs),
+
+    /// Write a session to a .tar.zst archive
+    Export(BackupExportArgs),
+
+    /// Verify and add a session from an exported archive
+    Import(BackupImportArgs),
+
+    /// Upload a session to the configured [backup.remote] and wait
+    Push(BackupPushArgs),
+
+    /// Record a session as a git stash entry on top of HEAD
+    ToStash(BackupToStashArgs),
+
+    /// Create a session from the files a git stash entry adds or modifies
+    FromStash(BackupFromStashArgs),
 }
 
 #[derive(Parser, Debug)]
@@ -437,6 +1128,10 @@ pub struct BackupListArgs
     #[arg(long, value_name = "SPAN")]
     pub since: Option<String>,
 
+    /// Filter by label (case-insensitive substring)
+    #[arg(long)]
+    pub label: Option<String>,
+
     /// Limit result count
     #[arg(long, default_value_t = 100)]
     pub limit: usize,
@@ -448,6 +1143,54 @@ pub struct BackupListArgs
     /// Machine-readable JSON output
     #[arg(long)]
     pub json: bool,
+
+    /// Output format; table, csv, and jsonl show only --columns
+    #[arg(long, value_enum, default_value = "text", conflicts_with = "json")]
+    pub format: BackupListFormat,
+
+    /// Comma-separated columns for table, csv, and jsonl output
+    #[arg(
+        long,
+        value_enum,
+        value_delimiter = ',',
+        default_value = "timestamp,id,engine,files,success,label"
+    )]
+    pub columns: Vec<BackupListColumn>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum ExplainBudgetFormat
+{
+    /// A summary line, then one line per candidate
+    Text,
+    /// The report as one JSON object
+    Json,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum BackupListFormat
+{
+    /// One aligned line per session
+    Text,
+    /// Bordered table
+    Table,
+    /// RFC 4180 CSV with a header row
+    Csv,
+    /// One JSON object per line
+    Jsonl,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum BackupListColumn
+{
+    Id,
+    Timestamp,
+    Engine,
+    Success,
+    Files,
+    Label,
+    /// First few backed-up paths
+    Paths,
 }
 
 #[derive(Parser, Debug)]
@@ -468,7 +1211,7 @@ pub struct BackupShowArgs
 #[derive(Args, Debug)]
 pub struct BackupRestoreArgs
 {
-    /// Session ID or alias (e.g., 'latest')
+    /// Session ID, label, or alias (e.g., 'latest')
     pub session: String,
 
     /// Restore only this repo-relative path from the session
@@ -511,6 +1254,10 @@ pub struct BackupCleanupArgs
     #[arg(long)]
     pub keep_latest: Option<usize>,
 
+    /// Remove the oldest sessions beyond this total size: bytes or K/M/G
+    #[arg(long, value_name = "SIZE")]
+    pub max_total_size: Option<String>,
+
     /// Include sessions without DONE marker
     #[arg(long)]
     pub include_incomplete: bool,
@@ -523,6 +1270,122 @@ pub struct BackupCleanupArgs
     #[arg(long)]
     pub json: bool,
 }
+
+#[derive(Args, Debug)]
+pub struct BackupDiffArgs
+{
+    /// Session ID or alias to diff from (the older state)
+    pub from: String,
+
+    /// Session ID or alias to diff to
+    #[arg(required_unless_present = "against", conflicts_with = "against")]
+    pub to: Option<String>,
+
+    /// Compare the session against another target instead of a second session
+    #[arg(long, value_enum, value_name = "TARGET")]
+    pub against: Option<BackupDiffTarget>,
+
+    /// Only diff this repo-relative path
+    #[arg(long)]
+    pub path: Option<PathBuf>,
+
+    /// Emit JSON result instead of human text
+    #[arg(long)]
+    pub json: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum BackupScopeMode
+{
+    /// Only files the apply writes
+    Written,
+    /// Every file in the directories of the edited files
+    Dirs,
+}
+
+#[derive(Debug, Clone, Copy, ValueEnum)]
+pub enum BackupDiffTarget
+{
+    /// Current contents of the working tree
+    Worktree,
+}
+
+#[derive(Args, Debug)]
+pub struct BackupExportArgs
+{
+    /// Session ID or alias (e.g., 'latest')
+    pub session: String,
+
+    /// Archive path (default: <SESSION_ID>.tar.zst)
+    #[arg(short, long, value_name = "FILE")]
+    pub output: Option<PathBuf>,
+
+    /// Emit JSON result instead of human text
+    #[arg(long)]
+    pub json: bool,
+}
+
+#[derive(Args, Debug)]
+pub struct BackupImportArgs
+{
+    /// Archive produced by `rup backup export`
+    pub archive: PathBuf,
+
+    /// Emit JSON result instead of human text
+    #[arg(long)]
+    pub json: bool,
+}
+
+#[derive(Args, Debug)]
+pub struct BackupPushArgs
+{
+    /// Session ID or alias
+    #[arg(default_value = "latest")]
+    pub session: String,
+}
+
+#[derive(Args, Debug)]
+pub struct BackupToStashArgs
+{
+    /// Session ID, label, or alias
+    pub session: String,
+
+    /// Stash message (default: "rup backup <SESSION_ID>")
+    #[arg(short, long)]
+    pub message: Option<String>,
+
+    /// Emit JSON result instead of human text
+    #[arg(long)]
+    pub json: bool,
+}
+
+#[derive(Args, Debug)]
+pub struct BackupFromStashArgs
+{
+    /// Stash entry to convert
+    #[arg(default_value = "stash@{0}")]
+    pub stash: String,
+
+    /// Label for the new session
+    #[arg(long)]
+    pub label: Option<String>,
+
+    /// Emit JSON result instead of human text
+    #[arg(long)]
+    pub json: bool,
+}
+
+#[derive(Args, Debug)]
+pub struct BackupGcArgs
+{
+    /// Report unreferenced objects without deleting them
+    #[arg(long)]
+    pub dry_run: bool,
+
+    /// Emit JSON result instead of human text
+    #[arg(long)]
+    pub json: bool,
+}
 #[derive(Debug, Parser)]
 pub struct InitArgs
 {
@@ -627,15 +1490,45 @@ pub struct ContextArgs
     #[arg(long, default_value = ".rup/symbols.jsonl")]
     pub symbols: PathBuf,
 
-    /// GPT model or encoding for token estimation (e.g., gpt-4o, o200k_base)
+    /// Model or encoding for token estimation (e.g., gpt-4o, o200k_base,
+    /// claude-3-5-sonnet)
     #[arg(long, default_value = "gpt-4o")]
     pub model: Option<String>,
 
+    /// Count tokens with this HuggingFace `tokenizer.json` instead of
+    /// --model's tokenizer (Llama, Mistral, Qwen)
+    #[arg(long, value_name = "FILE")]
+    pub tokenizer_file: Option<PathBuf>,
+
+    /// Estimate token counts from byte length while picking what fits the
+    /// budget, then count only the final output exactly; much faster with
+    /// many candidates
+    #[arg(long)]
+    pub approx_tokens: bool,
+
+    /// Neither read nor save token counts in `.rup/cache`, which spares
+    /// re-tokenizing unchanged snippets on later runs
+    #[arg(long)]
+    pub no_token_cache: bool,
+
+    /// Print to stderr every candidate's tokens and priority, whether it
+    /// was kept, trimmed, or dropped, and why (budget, bucket cap, novelty,
+    /// dedupe)
+    #[arg(
+        long,
+        value_enum,
+        value_name = "FORMAT",
+        num_args = 0..=1,
+        default_missing_value = "text"
+    )]
+    pub explain_budget: Option<ExplainBudgetFormat>,
+
     /// Token budget for the final assembled context
     #[arg(long)]
     pub budget: Option<usize>,
 
-    /// Use fuzzy/semantic matching in addition to exact/substring
+    /// Use fuzzy/semantic matching in addition to exact/substring; by
+    /// embedding similarity when the index was built with `--embeddings`
     #[arg(long)]
     pub semantic: bool,
 
@@ -698,17 +1591,33 @@ pub struct ContextArgs
     pub fail_signal: Option<PathBuf>,
 
     /// Resolve trait/impl surface for a qualified method, e.g. "MyTrait::my_method".
-    /// Adds the trait definition, related impl blocks, and the qualified method to
-    /// lookup.
+    /// Adds the trait definition and the method on every type related to it:
+    /// supertraits and base classes, implementors and subclasses, following the
+    /// impl/inheritance edges `rup symbols` records.
     #[arg(long = "trait-resolve", value_name = "Type::method")]
     pub trait_resolve: Option<String>,
 
-    /// Include lightweight static callgraph neighbors.
-    /// Format: "anchor=path:line depth=N". If anchor omitted here, falls back
-    /// to --anchor/--anchor-line. Depth defaults to 1. Only shallow, fast
-    /// scanning is performed; no full language server is used.
+    /// Include static callgraph neighbors: callees and callers of the anchor
+    /// function. Format: "anchor=path:line depth=N". If anchor omitted here,
+    /// falls back to --anchor/--anchor-line. Depth defaults to 1. Walks the
+    /// caller/callee edges recorded by `rup symbols --refs` (auto-indexing
+    /// with --refs when they are missing); no language server is used.
     #[arg(long = "callgraph", value_name = "anchor=PATH:LINE depth=N")]
     pub callgraph: Option<String>,
+
+    /// Only pick symbols of these kinds, comma-separated (fn, struct,
+    /// class, method, ...)
+    #[arg(short, long, value_delimiter = ',')]
+    pub kind: Vec<String>,
+
+    /// Only pick symbols from files matching these globs, relative to the
+    /// root
+    #[arg(long, value_name = "GLOB")]
+    pub include: Vec<String>,
+
+    /// Skip symbols from files matching these globs
+    #[arg(long, value_name = "GLOB")]
+    pub exclude: Vec<String>,
 }
 
 #[derive(Parser, Debug)]
@@ -718,9 +1627,11 @@ pub struct ResolveArgs
     #[arg(value_name = "PATH", default_value = ".")]
     pub paths: Vec<PathBuf>,
 
-    /// Resolution strategy: take-ours, take-theirs, take-base, interactive, smart
-    #[arg(long, value_enum, default_value = "smart")]
-    pub strategy: crate::core::resolve::ResolveStrategy,
+    /// Resolution strategy: take-ours, take-theirs, take-base, union, interactive,
+    /// smart, semantic. Defaults to smart; only the default replays
+    /// resolutions recorded in `.rup/rerere`
+    #[arg(long, value_enum)]
+    pub strategy: Option<crate::core::resolve::ResolveStrategy>,
 
     /// Only apply resolutions with high confidence (smart strategy only)
     #[arg(long)]
@@ -743,6 +1654,61 @@ pub struct ResolveArgs
     #[arg(long)]
     pub apply: bool,
 
+    /// Side kept when the sides differ only in whitespace or comments
+    /// (smart and semantic strategies; default from `[resolve]`, else ours)
+    #[arg(long, value_enum, value_name = "SIDE")]
+    pub trivial_side: Option<crate::core::resolve::TrivialSide>,
+
+    /// Command run in the repo root after resolutions are written, e.g.
+    /// "cargo check"; on failure the files it names (or all written files)
+    /// are restored from backup with their conflicts (always backs up)
+    #[arg(long, value_name = "CMD", conflicts_with = "preview")]
+    pub verify_cmd: Option<String>,
+
+    /// Neither replay nor record resolutions in `.rup/rerere`
+    #[arg(long)]
+    pub no_rerere: bool,
+
+    /// Skip `git blame` attribution of each conflict side
+    #[arg(long)]
+    pub no_blame: bool,
+
+    /// Show each conflict as ours/theirs/base panes and pick a resolution
+    /// per conflict; picks are written immediately (implies --apply)
+    #[arg(long, conflicts_with = "json")]
+    pub interactive: bool,
+
+    /// Write conflicts left unresolved to FILE as an LLM prompt ending in an
+    /// edit-spec skeleton; feed the model's reply to `rup apply`
+    #[arg(long, value_name = "FILE", conflicts_with = "interactive")]
+    pub emit_prompt: Option<PathBuf>,
+
+    /// Token budget for --emit-prompt (context shrinks, then conflicts drop)
+    #[arg(long, requires = "emit_prompt")]
+    pub budget: Option<usize>,
+
+    /// Model or encoding for --budget token counts (Claude models included)
+    #[arg(long, default_value = "gpt-4o")]
+    pub model: String,
+
+    /// Count --budget tokens with this HuggingFace `tokenizer.json` instead
+    #[arg(long, value_name = "FILE")]
+    pub tokenizer_file: Option<PathBuf>,
+
+    /// Show, per file, the diff each strategy (take-ours, take-theirs,
+    /// union, smart) would produce; writes nothing
+    #[arg(long, conflicts_with_all = ["apply", "interactive", "emit_prompt"])]
+    pub preview: bool,
+
+    /// Color --preview diffs: auto, always, never
+    #[arg(long, value_enum, value_name = "WHEN", default_value = "auto")]
+    pub color: ColorMode,
+
+    /// Show each conflict's scoring factors and why its resolution was
+    /// chosen (adds `explain` objects to --json output)
+    #[arg(long)]
+    pub explain: bool,
+
     /// Machine-readable JSON output
     #[arg(long)]
     pub json: bool,
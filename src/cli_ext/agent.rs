@@ -0,0 +1,316 @@
+//! Agent mode: one machine-readable JSON envelope on stdout per invocation.
+//!
+//! With `--agent-mode`, the process stdout is pointed at stderr for the
+//! lifetime of the command so every human-oriented `println!` lands on
+//! stderr. The original stdout is kept aside and receives exactly one
+//! line: the final [`Envelope`]. Commands record warnings, artifacts, and
+//! token statistics through the [`AgentReport`] handle on `AppContext`.
+
+use std::{
+    io::Write,
+    path::{Path, PathBuf},
+    sync::{Arc, Mutex},
+    time::Instant,
+};
+
+use serde::Serialize;
+
+/// Envelope schema version; bump on breaking field changes
+pub const ENVELOPE_SCHEMA_VERSION: u32 = 1;
+
+/// A file produced or modified by a command
+#[derive(Debug, Clone, Serialize, PartialEq, Eq)]
+pub struct Artifact
+{
+    /// Artifact role, e.g. "output", "modified", "backup"
+    pub kind: String,
+    pub path: PathBuf,
+}
+
+/// Token accounting for commands that budget content
+#[derive(Debug, Clone, Serialize, PartialEq, Eq)]
+pub struct TokenStats
+{
+    pub used: usize,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub budget: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub model: Option<String>,
+}
+
+/// Final result envelope emitted on stdout in agent mode
+#[derive(Debug, Clone, Serialize)]
+pub struct Envelope
+{
+    pub schema_version: u32,
+    pub command: String,
+    /// "ok" or "error"
+    pub status: &'static str,
+    /// Exit code the process terminates with
+    pub exit_code: i32,
+    pub warnings: Vec<String>,
+    pub artifacts: Vec<Artifact>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub token_stats: Option<TokenStats>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    pub duration_ms: u128,
+}
+
+#[derive(Debug, Default)]
+struct ReportData
+{
+    warnings: Vec<String>,
+    artifacts: Vec<Artifact>,
+    token_stats: Option<TokenStats>,
+    exit_code: Option<i32>,
+}
+
+/// Shared, cloneable collector for envelope fields
+#[derive(Debug, Clone, Default)]
+pub struct AgentReport
+{
+    inner: Arc<Mutex<ReportData>>,
+}
+
+impl AgentReport
+{
+    pub fn new() -> Self
+    {
+        Self::default()
+    }
+
+    /// Record a non-fatal warning
+    pub fn warn(
+        &self,
+        msg: impl Into<String>,
+    )
+    {
+        self.with(|d| {
+            d.warnings
+                .push(msg.into())
+        });
+    }
+
+    /// Record a produced or touched file
+    pub fn artifact(
+        &self,
+        kind: &str,
+        path: impl AsRef<Path>,
+    )
+    {
+        let a = Artifact {
+            kind: kind.to_string(),
+            path: path
+                .as_ref()
+                .to_path_buf(),
+        };
+        self.with(|d| {
+            if !d
+                .artifacts
+                .contains(&a)
+            {
+                d.artifacts
+                    .push(a)
+            }
+        });
+    }
+
+    /// Record token usage (last call wins)
+    pub fn tokens(
+        &self,
+        used: usize,
+        budget: Option<usize>,
+        model: Option<&str>,
+    )
+    {
+        let stats = TokenStats { used, budget, model: model.map(str::to_string) };
+        self.with(|d| d.token_stats = Some(stats));
+    }
+
+    /// Override the exit code for commands that succeed with a non-zero outcome
+    /// (e.g. conflicts remaining)
+    pub fn exit_code(
+        &self,
+        code: i32,
+    )
+    {
+        self.with(|d| d.exit_code = Some(code));
+    }
+
+    /// Build the final envelope; a failed command supplies its rendered
+    /// message and mapped exit code
+    pub fn finish(
+        &self,
+        command: &str,
+        outcome: Result<(), (String, i32)>,
+        started: Instant,
+    ) -> Envelope
+    {
+        let mut d = self
+            .inner
+            .lock()
+            .unwrap_or_else(|e| e.into_inner());
+
+        let (exit_code, error) = match outcome
+        {
+            Ok(()) =>
+            {
+                (
+                    d.exit_code
+                        .unwrap_or(0),
+                    None,
+                )
+            }
+            Err((msg, code)) => (code, Some(msg)),
+        };
+
+        Envelope {
+            schema_version: ENVELOPE_SCHEMA_VERSION,
+            command: command.to_string(),
+            status: if exit_code == 0 { "ok" } else { "error" },
+            exit_code,
+            warnings: std::mem::take(&mut d.warnings),
+            artifacts: std::mem::take(&mut d.artifacts),
+            token_stats: d
+                .token_stats
+                .take(),
+            error,
+            duration_ms: started
+                .elapsed()
+                .as_millis(),
+        }
+    }
+
+    fn with(
+        &self,
+        f: impl FnOnce(&mut ReportData),
+    )
+    {
+        // A poisoned lock only means another thread panicked mid-record
+        let mut d = self
+            .inner
+            .lock()
+            .unwrap_or_else(|e| e.into_inner());
+        f(&mut d);
+    }
+}
+
+/// Holds the real stdout while fd 1 is redirected to stderr
+pub struct StdoutRedirect
+{
+    #[cfg(unix)]
+    saved_fd: Option<std::os::fd::OwnedFd>,
+}
+
+impl StdoutRedirect
+{
+    /// Point stdout at stderr, keeping a handle to the original stdout.
+    /// On non-unix targets this is a no-op and the envelope shares stdout.
+    pub fn begin() -> std::io::Result<Self>
+    {
+        #[cfg(unix)]
+        {
+            use std::os::fd::{FromRawFd, OwnedFd};
+
+            std::io::stdout().flush()?;
+            // SAFETY: dup/dup2 on the process's own standard descriptors
+            let saved = unsafe { libc::dup(libc::STDOUT_FILENO) };
+            if saved < 0
+            {
+                return Err(std::io::Error::last_os_error());
+            }
+            // SAFETY: `saved` is a freshly duplicated descriptor we own
+            let saved_fd = unsafe { OwnedFd::from_raw_fd(saved) };
+            if unsafe { libc::dup2(libc::STDERR_FILENO, libc::STDOUT_FILENO) } < 0
+            {
+                return Err(std::io::Error::last_os_error());
+            }
+            Ok(Self { saved_fd: Some(saved_fd) })
+        }
+        #[cfg(not(unix))]
+        {
+            Ok(Self {})
+        }
+    }
+
+    /// Write the envelope as a single JSON line to the original stdout
+    pub fn emit(
+        self,
+        envelope: &Envelope,
+    ) -> std::io::Result<()>
+    {
+        let line = serde_json::to_string(envelope)?;
+
+        // Drain anything buffered for the redirected stream first
+        std::io::stdout().flush()?;
+
+        #[cfg(unix)]
+        if let Some(fd) = self.saved_fd
+        {
+            let mut out = std::fs::File::from(fd);
+            writeln!(out, "{line}")?;
+            return out.flush();
+        }
+
+        let mut out = std::io::stdout().lock();
+        writeln!(out, "{line}")?;
+        out.flush()
+    }
+}
+
+#[cfg(test)]
+mod tests
+{
+    use super::*;
+
+    #[test]
+    fn test_envelope_collects_report_fields()
+    {
+        let report = AgentReport::new();
+        report.warn("1 conflict forced");
+        report.artifact("modified", "src/lib.rs");
+        report.artifact("modified", "src/lib.rs");
+        report.tokens(120, Some(2000), Some("gpt-4o"));
+
+        let env = report.finish("apply", Ok(()), Instant::now());
+        assert_eq!(env.status, "ok");
+        assert_eq!(env.exit_code, 0);
+        assert_eq!(
+            env.artifacts
+                .len(),
+            1
+        );
+
+        let v = serde_json::to_value(&env).unwrap();
+        assert_eq!(v["schema_version"], ENVELOPE_SCHEMA_VERSION);
+        assert_eq!(v["token_stats"]["budget"], 2000);
+        assert_eq!(v["warnings"][0], "1 conflict forced");
+        assert!(
+            v.get("error")
+                .is_none()
+        );
+    }
+
+    #[test]
+    fn test_envelope_error_and_exit_override()
+    {
+        let report = AgentReport::new();
+        report.exit_code(2);
+        let env = report.finish("resolve", Ok(()), Instant::now());
+        assert_eq!((env.status, env.exit_code), ("error", 2));
+
+        let failed = AgentReport::new().finish(
+            "apply",
+            Err(("repository issue: escape".to_string(), 4)),
+            Instant::now(),
+        );
+        assert_eq!(failed.exit_code, 4);
+        assert_eq!(
+            failed
+                .error
+                .as_deref(),
+            Some("repository issue: escape")
+        );
+    }
+}
@@ -8,12 +8,16 @@ use std::path::{Path, PathBuf};
 use anyhow::{Context, Result};
 
 use crate::{
-    cli::{ApplyEngine as EngineChoice, GitMode, WhitespaceMode},
+    cli::WhitespaceMode,
     core::{
         backup::BackupManager,
-        edit::EditSpec,
-        git::{GitConflict, GitEngine, GitOptions},
-        patch::{PatchConfig, generate_patches},
+        edit::{
+            ApplyStats, DEFAULT_MAX_FILE_BYTES, EditConflict, EditSpec, EolPolicy, FileBlock,
+        },
+        engine_registry::EngineConfig,
+        git::{GitConflict, GitEngine, GitOptions, Whitespace},
+        jj,
+        patch::{ContextRule, HunkLine, PatchConfig, PatchSet, generate_patches},
     },
 };
 
@@ -24,6 +28,9 @@ pub enum Engine
     Internal,
     Git,
     Auto,
+    Jj,
+    /// Engine added through the engine registry
+    Custom(String),
 }
 
 /// Apply operation preview
@@ -33,6 +40,8 @@ pub struct Preview
     pub patch_content: String,
     pub summary: String,
     pub conflicts: Vec<String>,
+    /// Human-readable fixes for stale spans, e.g. a corrected REPLACE header
+    pub suggestions: Vec<String>,
     pub engine_used: Engine,
 }
 
@@ -65,6 +74,59 @@ pub struct ApplyReport
     pub backup_session_id: Option<String>,
     pub backup_manifest_path: Option<PathBuf>,
     pub backup_file_count: Option<usize>,
+    /// Diffstat-style summary of the run
+    pub stats: ApplyStats,
+    /// Commit created by `apply --commit`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub commit: Option<String>,
+    /// Engines `--engine auto` tried, in order; empty for a single engine
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub attempts: Vec<EngineAttempt>,
+}
+
+/// One step of the `--engine auto` fallback chain
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct EngineAttempt
+{
+    pub engine: Engine,
+    /// Files this engine wrote
+    pub files: Vec<PathBuf>,
+    /// Why the engine handed off to the next one
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub failure: Option<String>,
+}
+
+impl EngineAttempt
+{
+    fn from_report(report: &ApplyReport) -> Self
+    {
+        let failure = (!report
+            .conflicts
+            .is_empty())
+        .then(|| conflict_failure(&report.conflicts));
+        Self {
+            engine: report
+                .engine_used
+                .clone(),
+            files: report
+                .applied_files
+                .clone(),
+            failure,
+        }
+    }
+
+    fn failed(
+        engine: Engine,
+        failure: String,
+    ) -> Self
+    {
+        Self { engine, files: Vec::new(), failure: Some(failure) }
+    }
+}
+
+fn conflict_failure(conflicts: &[String]) -> String
+{
+    format!("{} conflict(s): {}", conflicts.len(), conflicts.join("; "))
 }
 
 /// Unified apply engine trait
@@ -103,7 +165,7 @@ pub trait ApplyEngine
 
 /// Turns absolute file path into repo-relative path or errors.
 /// Enforces boundary to keep backups inside repo root.
-fn make_relative_to_repo(
+pub(crate) fn make_relative_to_repo(
     file_path: &Path,
     repo_root: &Path,
 ) -> Result<PathBuf>
@@ -136,6 +198,52 @@ fn make_relative_to_repo(
     crate::core::backup_ops::normalize_repo_rel(file_path)
 }
 
+/// Map an internal-engine conflict onto the shared git conflict taxonomy
+fn edit_conflict_to_git(conflict: &EditConflict) -> GitConflict
+{
+    match conflict
+    {
+        EditConflict::FileNotFound(path) =>
+        {
+            GitConflict::Other(format!("file not found: {}", path.display()))
+        }
+        EditConflict::ContentMismatch { file, expected_cid: _, actual_cid: _ } =>
+        {
+            GitConflict::PreimageMismatch {
+                path: file.clone(),
+                hunk: (0, 0),
+                hint: "CID mismatch - content changed",
+            }
+        }
+        EditConflict::SpanOutOfRange { file, span, .. } =>
+        {
+            GitConflict::Other(format!(
+                "span out of range: {}:{}-{}",
+                file.display(),
+                span.0,
+                span.1
+            ))
+        }
+        EditConflict::OldContentMismatch { file, span, .. } =>
+        {
+            #[allow(clippy::cast_possible_truncation)]
+            GitConflict::PreimageMismatch {
+                path: file.clone(),
+                hunk: (span.0 as u32, span.1 as u32),
+                hint: "OLD content mismatch",
+            }
+        }
+        EditConflict::BinaryFile(path) => GitConflict::BinaryOrMode {
+            path: path.clone(),
+            hint: "binary file left untouched; pass --force-binary to edit it as text",
+        },
+        EditConflict::FileTooLarge { file, .. } => GitConflict::FileTooLarge {
+            path: file.clone(),
+            hint: "raise --max-file-size (0 disables the limit)",
+        },
+    }
+}
+
 /// Internal engine implementation
 pub struct InternalEngine
 {
@@ -143,6 +251,10 @@ pub struct InternalEngine
     backup_enabled: bool,
     force_mode: bool,
     context_lines: usize,
+    max_drift: Option<usize>,
+    eol: EolPolicy,
+    max_file_bytes: Option<u64>,
+    allow_binary: bool,
 }
 
 impl InternalEngine
@@ -153,7 +265,47 @@ impl InternalEngine
         context_lines: usize,
     ) -> Self
     {
-        Self { backup_enabled, force_mode, context_lines }
+        Self {
+            backup_enabled,
+            force_mode,
+            context_lines,
+            max_drift: None,
+            eol: EolPolicy::default(),
+            max_file_bytes: Some(DEFAULT_MAX_FILE_BYTES),
+            allow_binary: false,
+        }
+    }
+
+    /// Bound REPLACE relocation to `max_drift` lines from the declared span
+    pub fn with_max_drift(
+        mut self,
+        max_drift: Option<usize>,
+    ) -> Self
+    {
+        self.max_drift = max_drift;
+        self
+    }
+
+    /// Line-ending policy for rewritten files
+    pub fn with_eol(
+        mut self,
+        eol: EolPolicy,
+    ) -> Self
+    {
+        self.eol = eol;
+        self
+    }
+
+    /// Size cap and binary policy for target files
+    pub fn with_file_guards(
+        mut self,
+        max_file_bytes: Option<u64>,
+        allow_binary: bool,
+    ) -> Self
+    {
+        self.max_file_bytes = max_file_bytes;
+        self.allow_binary = allow_binary;
+        self
     }
 }
 
@@ -166,7 +318,10 @@ impl ApplyEngine for InternalEngine
     {
         let engine = crate::core::edit::EditEngine::new()
             .with_preview(true)
-            .with_force(self.force_mode);
+            .with_force(self.force_mode)
+            .with_max_drift(self.max_drift)
+            .with_eol(self.eol)
+            .with_file_guards(self.max_file_bytes, self.allow_binary);
 
         let result = engine.apply(spec)?;
 
@@ -175,52 +330,65 @@ impl ApplyEngine for InternalEngine
             context_lines: self.context_lines,
             ..PatchConfig::default()
         };
-        let patch_set = generate_patches(spec, &config)?;
+        let suggestions: Vec<String> = result
+            .conflicts
+            .iter()
+            .filter_map(|c| {
+                match c
+                {
+                    EditConflict::OldContentMismatch { file, span, suggestion: Some(s) } =>
+                    {
+                        Some(format!(
+                            "{}: lines {}-{} no longer match; closest match at {}-{} ({}% \
+                             similar), use:\n    {}",
+                            file.display(),
+                            span.0,
+                            span.1,
+                            s.span
+                                .0,
+                            s.span
+                                .1,
+                            s.similarity,
+                            s.header
+                        ))
+                    }
+                    _ => None,
+                }
+            })
+            .collect();
+
+        // Diff only conflict-free files: refused ones are never read as text
+        // and stale spans cannot be rendered as hunks
+        let conflicted: Vec<&PathBuf> = result
+            .conflicts
+            .iter()
+            .map(|c| {
+                match c
+                {
+                    EditConflict::FileNotFound(path) | EditConflict::BinaryFile(path) => path,
+                    EditConflict::SpanOutOfRange { file, .. }
+                    | EditConflict::ContentMismatch { file, .. }
+                    | EditConflict::OldContentMismatch { file, .. }
+                    | EditConflict::FileTooLarge { file, .. } => file,
+                }
+            })
+            .collect();
+        let patchable = EditSpec {
+            file_blocks: spec
+                .file_blocks
+                .iter()
+                .filter(|b| !conflicted.contains(&&b.path))
+                .cloned()
+                .collect(),
+        };
+        let patch_set = generate_patches(&patchable, &config)?;
         let patch_content = crate::core::patch::render_unified_diff(&patch_set);
 
         let conflicts = crate::core::git::render_conflict_summary(
             &result
                 .conflicts
                 .iter()
-                .map(|c| {
-                    match c
-                    {
-                        crate::core::edit::EditConflict::FileNotFound(path) =>
-                        {
-                            GitConflict::Other(format!("file not found: {}", path.display()))
-                        }
-                        crate::core::edit::EditConflict::ContentMismatch {
-                            file,
-                            expected_cid: _,
-                            actual_cid: _,
-                        } =>
-                        {
-                            GitConflict::PreimageMismatch {
-                                path: file.clone(),
-                                hunk: (0, 0),
-                                hint: "CID mismatch - content changed",
-                            }
-                        }
-                        crate::core::edit::EditConflict::SpanOutOfRange { file, span, .. } =>
-                        {
-                            GitConflict::Other(format!(
-                                "span out of range: {}:{}-{}",
-                                file.display(),
-                                span.0,
-                                span.1
-                            ))
-                        }
-                        crate::core::edit::EditConflict::OldContentMismatch { file, span } =>
-                        {
-                            #[allow(clippy::cast_possible_truncation)]
-                            GitConflict::PreimageMismatch {
-                                path: file.clone(),
-                                hunk: (span.0 as u32, span.1 as u32),
-                                hint: "OLD content mismatch",
-                            }
-                        }
-                    }
-                })
+                .map(edit_conflict_to_git)
                 .collect::<Vec<_>>(),
         );
 
@@ -244,6 +412,7 @@ impl ApplyEngine for InternalEngine
             patch_content,
             summary,
             conflicts,
+            suggestions,
             engine_used: Engine::Internal,
         })
     }
@@ -279,7 +448,10 @@ impl ApplyEngine for InternalEngine
         // Apply using existing edit engine (without its own backup since we handle centrally)
         let engine = crate::core::edit::EditEngine::new()
             .with_backup(false) // Disable internal backup, we handle it centrally
-            .with_force(ctx.force);
+            .with_force(ctx.force)
+            .with_max_drift(self.max_drift)
+            .with_eol(self.eol)
+            .with_file_guards(self.max_file_bytes, self.allow_binary);
 
         let result = engine.apply(spec)?;
         applied.extend(result.applied_files);
@@ -288,44 +460,7 @@ impl ApplyEngine for InternalEngine
             &result
                 .conflicts
                 .iter()
-                .map(|c| {
-                    match c
-                    {
-                        crate::core::edit::EditConflict::FileNotFound(path) =>
-                        {
-                            GitConflict::Other(format!("file not found: {}", path.display()))
-                        }
-                        crate::core::edit::EditConflict::ContentMismatch {
-                            file,
-                            expected_cid: _,
-                            actual_cid: _,
-                        } =>
-                        {
-                            GitConflict::PreimageMismatch {
-                                path: file.clone(),
-                                hunk: (0, 0),
-                                hint: "CID mismatch - content changed",
-                            }
-                        }
-                        crate::core::edit::EditConflict::SpanOutOfRange { file, span, .. } =>
-                        {
-                            GitConflict::Other(format!(
-                                "span out of range: {}:{}-{}",
-                                file.display(),
-                                span.0,
-                                span.1
-                            ))
-                        }
-                        crate::core::edit::EditConflict::OldContentMismatch { file, span } =>
-                        {
-                            GitConflict::PreimageMismatch {
-                                path: file.clone(),
-                                hunk: (span.0 as u32, span.1 as u32),
-                                hint: "OLD content mismatch",
-                            }
-                        }
-                    }
-                })
+                .map(edit_conflict_to_git)
                 .collect::<Vec<_>>(),
         );
 
@@ -370,6 +505,9 @@ impl ApplyEngine for InternalEngine
                 .as_ref()
                 .map(|d| d.join("manifest.json")),
             backup_file_count: file_count,
+            stats: result.stats,
+            commit: None,
+            attempts: Vec::new(),
         })
     }
 }
@@ -378,14 +516,104 @@ impl ApplyEngine for InternalEngine
 pub struct GitEngineWrapper
 {
     git_engine: GitEngine,
+    context_rules: Vec<ContextRule>,
 }
 
+/// Extra context lines tried, in order, when a generated patch does not
+/// apply cleanly
+const RETRY_EXTRA_CONTEXT: [usize; 2] = [3, 8];
+
 impl GitEngineWrapper
 {
     pub fn new(git_options: GitOptions) -> Result<Self>
     {
         let git_engine = GitEngine::new(git_options)?;
-        Ok(Self { git_engine })
+        Ok(Self { git_engine, context_rules: Vec::new() })
+    }
+
+    /// Per-file context-line overrides for generated patches
+    pub fn with_context_rules(
+        mut self,
+        rules: Vec<ContextRule>,
+    ) -> Self
+    {
+        self.context_rules = rules;
+        self
+    }
+
+    /// Patches for `spec`. When `git apply --check` rejects them, they are
+    /// regenerated with more context (churned files match better with
+    /// more anchoring); the original patches are kept if no retry applies.
+    fn patches_with_retry(
+        &self,
+        spec: &EditSpec,
+        context_lines: usize,
+    ) -> Result<PatchSet>
+    {
+        let spec = self.prepared_spec(spec);
+        let first = generate_patches(&spec, &self.patch_config(context_lines, 0))?;
+        if self.applies_cleanly(&first)
+        {
+            return Ok(first);
+        }
+        for extra in RETRY_EXTRA_CONTEXT
+        {
+            let retry = generate_patches(&spec, &self.patch_config(context_lines, extra))?;
+            if self.applies_cleanly(&retry)
+            {
+                tracing::info!("git apply: patch applies with {extra} extra context line(s)");
+                return Ok(retry);
+            }
+        }
+        Ok(first)
+    }
+
+    fn patch_config(
+        &self,
+        context_lines: usize,
+        extra: usize,
+    ) -> PatchConfig
+    {
+        PatchConfig {
+            context_lines: context_lines + extra,
+            context_rules: self
+                .context_rules
+                .iter()
+                .map(|rule| ContextRule { lines: rule.lines + extra, ..rule.clone() })
+                .collect(),
+            ..PatchConfig::default()
+        }
+    }
+
+    fn applies_cleanly(
+        &self,
+        patch_set: &PatchSet,
+    ) -> bool
+    {
+        self.git_engine
+            .check(patch_set)
+            .is_ok_and(|outcome| {
+                outcome
+                    .conflicts
+                    .is_empty()
+            })
+    }
+
+    /// The spec patches are generated from: re-indented to each file's
+    /// style under `Whitespace::Auto`, as given otherwise
+    fn prepared_spec<'s>(
+        &self,
+        spec: &'s EditSpec,
+    ) -> std::borrow::Cow<'s, EditSpec>
+    {
+        match self
+            .git_engine
+            .options()
+            .whitespace
+        {
+            Whitespace::Auto => std::borrow::Cow::Owned(crate::core::indent::normalize_spec(spec)),
+            _ => std::borrow::Cow::Borrowed(spec),
+        }
     }
 }
 
@@ -396,14 +624,11 @@ impl ApplyEngine for GitEngineWrapper
         spec: &EditSpec,
     ) -> Result<Preview>
     {
-        let config = PatchConfig {
-            context_lines: self
-                .git_engine
-                .options()
-                .context_lines as usize,
-            ..PatchConfig::default()
-        };
-        let patch_set = generate_patches(spec, &config)?;
+        let context_lines = self
+            .git_engine
+            .options()
+            .context_lines as usize;
+        let patch_set = self.patches_with_retry(spec, context_lines)?;
         let patch_content = crate::core::patch::render_unified_diff(&patch_set);
 
         let outcome = self
@@ -426,6 +651,7 @@ impl ApplyEngine for GitEngineWrapper
             patch_content,
             summary,
             conflicts,
+            suggestions: Vec::new(),
             engine_used: Engine::Git,
         })
     }
@@ -436,11 +662,7 @@ impl ApplyEngine for GitEngineWrapper
         mut ctx: ApplyContext<'_>,
     ) -> Result<ApplyReport>
     {
-        let config = PatchConfig {
-            context_lines: ctx.context_lines,
-            ..PatchConfig::default()
-        };
-        let patch_set = generate_patches(spec, &config)?;
+        let patch_set = self.patches_with_retry(spec, ctx.context_lines)?;
 
         // If centralized backup is enabled, back up files that git will modify
         if let Some(backup_manager) = ctx
@@ -455,9 +677,17 @@ impl ApplyEngine for GitEngineWrapper
             }
         }
 
+        let started = std::time::Instant::now();
         let outcome = self
             .git_engine
             .apply(&patch_set)?;
+        let mut stats = patch_stats(&patch_set);
+        stats.files_touched = outcome
+            .applied_files
+            .len();
+        stats.elapsed_ms = started
+            .elapsed()
+            .as_millis() as u64;
 
         let conflicts = crate::core::git::render_conflict_summary(&outcome.conflicts);
 
@@ -502,10 +732,37 @@ impl ApplyEngine for GitEngineWrapper
                 .as_ref()
                 .map(|d| d.join("manifest.json")),
             backup_file_count: file_count,
+            stats,
+            commit: None,
+            attempts: Vec::new(),
         })
     }
 }
 
+/// Count hunks and changed lines in a generated patch set
+fn patch_stats(patch_set: &PatchSet) -> ApplyStats
+{
+    let mut stats = ApplyStats::default();
+    for hunk in patch_set
+        .file_patches
+        .iter()
+        .flat_map(|fp| &fp.hunks)
+    {
+        stats.hunks_applied += 1;
+        for line in &hunk.lines
+        {
+            match line
+            {
+                HunkLine::Add(_) => stats.lines_added += 1,
+                HunkLine::Remove(_) => stats.lines_removed += 1,
+                HunkLine::Context(_) =>
+                {}
+            }
+        }
+    }
+    stats
+}
+
 /// Hybrid engine with automatic fallback
 pub struct HybridEngine
 {
@@ -534,6 +791,55 @@ impl HybridEngine
         };
         Ok(Self { internal, git })
     }
+
+    /// Bound REPLACE relocation for the internal half of the hybrid
+    pub fn with_max_drift(
+        mut self,
+        max_drift: Option<usize>,
+    ) -> Self
+    {
+        self.internal = self
+            .internal
+            .with_max_drift(max_drift);
+        self
+    }
+
+    /// Line-ending policy for the internal half of the hybrid
+    pub fn with_eol(
+        mut self,
+        eol: EolPolicy,
+    ) -> Self
+    {
+        self.internal = self
+            .internal
+            .with_eol(eol);
+        self
+    }
+
+    /// Size cap and binary policy for the internal half of the hybrid
+    pub fn with_file_guards(
+        mut self,
+        max_file_bytes: Option<u64>,
+        allow_binary: bool,
+    ) -> Self
+    {
+        self.internal = self
+            .internal
+            .with_file_guards(max_file_bytes, allow_binary);
+        self
+    }
+
+    /// Per-file context-line overrides for the git half of the hybrid
+    pub fn with_context_rules(
+        mut self,
+        rules: Vec<ContextRule>,
+    ) -> Self
+    {
+        self.git = self
+            .git
+            .map(|git| git.with_context_rules(rules));
+        self
+    }
 }
 
 impl ApplyEngine for HybridEngine
@@ -621,12 +927,13 @@ impl ApplyEngine for HybridEngine
 
         match internal_result
         {
-            Ok(report)
+            Ok(mut report)
                 if report
                     .conflicts
                     .is_empty() =>
             {
                 // Internal succeeded without conflicts
+                report.attempts = vec![EngineAttempt::from_report(&report)];
                 Ok(report)
             }
             Ok(internal_report) =>
@@ -644,11 +951,17 @@ impl ApplyEngine for HybridEngine
                         force,
                     };
 
+                    let internal_attempt = EngineAttempt::failed(
+                        Engine::Internal,
+                        conflict_failure(&internal_report.conflicts),
+                    );
                     match git.apply_with_ctx(spec, git_ctx)
                     {
                         Ok(mut git_report) =>
                         {
                             // Git succeeded - combine reports
+                            git_report.attempts =
+                                vec![internal_attempt, EngineAttempt::from_report(&git_report)];
                             git_report.engine_used = Engine::Auto;
                             // Preserve internal's backup info
                             git_report.backup_paths = internal_report.backup_paths;
@@ -662,11 +975,13 @@ impl ApplyEngine for HybridEngine
                         Err(err) =>
                         {
                             // Both engines failed - return combined error
+                            let reason = format!("git_apply_failed: {err:#}");
                             let combined = crate::core::git::CombinedConflictError::new(
                                 internal_report
                                     .conflicts
                                     .clone(),
-                                format!("git_apply_failed: {err}"),
+                                reason.clone(),
+                                vec![internal_attempt, EngineAttempt::failed(Engine::Git, reason)],
                             );
                             Err(combined.into())
                         }
@@ -680,6 +995,10 @@ impl ApplyEngine for HybridEngine
                             .conflicts
                             .clone(),
                         "git_unavailable".into(),
+                        vec![EngineAttempt::failed(
+                            Engine::Internal,
+                            conflict_failure(&internal_report.conflicts),
+                        )],
                     );
                     Err(combined.into())
                 }
@@ -697,12 +1016,28 @@ impl ApplyEngine for HybridEngine
                         context_lines,
                         force,
                     };
-                    git.apply_with_ctx(spec, fallback_ctx)
-                        .map(|mut r| {
+                    let internal_attempt =
+                        EngineAttempt::failed(Engine::Internal, format!("{e:#}"));
+                    match git.apply_with_ctx(spec, fallback_ctx)
+                    {
+                        Ok(mut r) =>
+                        {
+                            r.attempts = vec![internal_attempt, EngineAttempt::from_report(&r)];
                             r.engine_used = Engine::Auto;
-                            r
-                        })
-                        .or(Err(e))
+                            Ok(r)
+                        }
+                        Err(err) =>
+                        {
+                            // Keep both reasons: internal's error and why git gave up
+                            let reason = format!("git_apply_failed: {err:#}");
+                            let combined = crate::core::git::CombinedConflictError::new(
+                                vec![format!("{e:#}")],
+                                reason.clone(),
+                                vec![internal_attempt, EngineAttempt::failed(Engine::Git, reason)],
+                            );
+                            Err(combined.into())
+                        }
+                    }
                 }
                 else
                 {
@@ -713,62 +1048,242 @@ impl ApplyEngine for HybridEngine
     }
 }
 
-/// Create appropriate engine based on user choice
-pub fn create_engine(
-    engine_choice: &EngineChoice,
-    git_mode: &GitMode,
-    whitespace: &WhitespaceMode,
-    backup_enabled: bool,
-    force_mode: bool,
+/// Engine for jj repositories: internal edits between two jj snapshots, so
+/// `jj undo` reverts the whole apply, with a 3-way merge against `@-` for
+/// files whose spans went stale. Unlike git's 3-way mode, a merge that
+/// would leave conflict markers is not written; the conflict is reported.
+pub struct JjEngine
+{
+    internal: InternalEngine,
     repo_root: PathBuf,
-    context_lines: usize,
-) -> Result<Box<dyn ApplyEngine>>
+}
+
+impl JjEngine
 {
-    let git_options = GitOptions {
-        repo_root,
-        mode: match git_mode
+    pub fn new(
+        internal: InternalEngine,
+        repo_root: &Path,
+    ) -> Result<Self>
+    {
+        let repo_root = jj::detect_repo(repo_root)
+            .with_context(|| format!("Not a jj repository: {}", repo_root.display()))?;
+        Ok(Self { internal, repo_root })
+    }
+
+    /// Blocks with at least one conflict in `conflicts`
+    fn stale_blocks<'s>(
+        spec: &'s EditSpec,
+        conflicts: &[String],
+    ) -> Vec<&'s FileBlock>
+    {
+        spec.file_blocks
+            .iter()
+            .filter(|b| {
+                let prefix = format!(
+                    "{}:",
+                    b.path
+                        .display()
+                );
+                conflicts
+                    .iter()
+                    .any(|c| c.starts_with(&prefix))
+            })
+            .collect()
+    }
+
+    /// Replay `block` on the file's `@-` version and merge the result into
+    /// the working copy, with the replay's stats; `None` when there is no
+    /// usable parent version
+    fn merge_block(
+        &self,
+        block: &FileBlock,
+    ) -> Result<Option<(Vec<u8>, bool, ApplyStats)>>
+    {
+        let rel = make_relative_to_repo(&block.path, &self.repo_root)?;
+        let Some(base) = jj::parent_content(&self.repo_root, &rel)?
+        else
         {
-            GitMode::ThreeWay => crate::core::git::GitMode::ThreeWay,
-            GitMode::Index => crate::core::git::GitMode::Index,
-            GitMode::Worktree => crate::core::git::GitMode::Worktree,
-        },
-        whitespace: match whitespace
+            return Ok(None);
+        };
+        let ours = std::fs::read(&block.path)
+            .with_context(|| format!("read {}", block.path.display()))?;
+
+        let scratch = tempfile::tempdir().context("create merge scratch dir")?;
+        let replay_path = scratch
+            .path()
+            .join("base");
+        std::fs::write(&replay_path, &base)?;
+        let replay = EditSpec {
+            file_blocks: vec![FileBlock {
+                path: replay_path.clone(),
+                operations: block
+                    .operations
+                    .clone(),
+            }],
+        };
+        let result = crate::core::edit::EditEngine::new()
+            .with_backup(false)
+            .with_max_drift(self.internal.max_drift)
+            .with_eol(self.internal.eol)
+            .with_file_guards(self.internal.max_file_bytes, self.internal.allow_binary)
+            .apply(&replay)?;
+        if !result
+            .conflicts
+            .is_empty()
         {
-            WhitespaceMode::Nowarn => crate::core::git::Whitespace::Nowarn,
-            WhitespaceMode::Warn => crate::core::git::Whitespace::Warn,
-            WhitespaceMode::Fix => crate::core::git::Whitespace::Fix,
-        },
-        context_lines: context_lines as u8,
-        allow_outside_repo: false,
-    };
+            return Ok(None);
+        }
+        let theirs = std::fs::read(&replay_path)?;
 
-    match engine_choice
+        let (merged, clean) = jj::merge_three(&rel.to_string_lossy(), &base, &ours, &theirs)?;
+        Ok(Some((merged, clean, result.stats)))
+    }
+}
+
+impl ApplyEngine for JjEngine
+{
+    fn check(
+        &self,
+        spec: &EditSpec,
+    ) -> Result<Preview>
     {
-        EngineChoice::Internal =>
+        let mut preview = self
+            .internal
+            .check(spec)?;
+        preview.engine_used = Engine::Jj;
+        if preview
+            .conflicts
+            .is_empty()
         {
-            Ok(Box::new(InternalEngine::new(
-                backup_enabled,
-                force_mode,
-                context_lines,
-            )))
+            return Ok(preview);
         }
-        EngineChoice::Git => Ok(Box::new(GitEngineWrapper::new(git_options)?)),
-        EngineChoice::Auto =>
+
+        let mut merged = 0;
+        for block in Self::stale_blocks(spec, &preview.conflicts)
         {
-            // Detect repo once here; do NOT fail auto if absent
-            let repo_present = crate::core::git::detect_repo(&git_options.repo_root).is_ok();
-            let mut auto_git_options = git_options;
-            auto_git_options.allow_outside_repo = true; // Allow auto to work outside repos
-            Ok(Box::new(HybridEngine::new(
-                backup_enabled,
-                force_mode,
-                auto_git_options,
-                repo_present,
-            )?))
+            if let Some((_, true, _)) = self.merge_block(block)?
+            {
+                let prefix = format!(
+                    "{}:",
+                    block
+                        .path
+                        .display()
+                );
+                preview
+                    .conflicts
+                    .retain(|c| !c.starts_with(&prefix));
+                merged += 1;
+            }
         }
+        preview
+            .summary
+            .push_str(&format!(" | jj: {} file(s) merge cleanly against @-", merged));
+        Ok(preview)
+    }
+
+    fn apply_with_ctx(
+        &self,
+        spec: &EditSpec,
+        mut ctx: ApplyContext<'_>,
+    ) -> Result<ApplyReport>
+    {
+        let force = ctx.force;
+
+        // The session stays here: the internal engine would finalize it
+        // before the merged files below are written
+        let mut backup = ctx
+            .backup
+            .take();
+        if let Some(backup_manager) = backup.as_mut()
+        {
+            for file_block in spec
+                .file_blocks
+                .iter()
+                .filter(|b| {
+                    !b.operations
+                        .is_empty()
+                })
+            {
+                let rel_path = make_relative_to_repo(&file_block.path, ctx.repo_root)?;
+                backup_manager.backup_file(&rel_path)?;
+            }
+        }
+
+        // Record the pre-apply state so the apply is one jj operation
+        jj::snapshot(&self.repo_root)?;
+
+        let mut report = self
+            .internal
+            .apply_with_ctx(spec, ctx)?;
+        report.engine_used = Engine::Jj;
+
+        // Forced applies already wrote past their conflicts
+        if !force
+        {
+            for block in Self::stale_blocks(spec, &report.conflicts)
+            {
+                if let Some((merged, true, stats)) = self.merge_block(block)?
+                {
+                    std::fs::write(&block.path, merged)
+                        .with_context(|| format!("write {}", block.path.display()))?;
+                    report
+                        .stats
+                        .absorb(&stats);
+                    let prefix = format!(
+                        "{}:",
+                        block
+                            .path
+                            .display()
+                    );
+                    report
+                        .conflicts
+                        .retain(|c| !c.starts_with(&prefix));
+                    report
+                        .applied_files
+                        .push(
+                            block
+                                .path
+                                .clone(),
+                        );
+                }
+            }
+        }
+
+        jj::snapshot(&self.repo_root)?;
+
+        // Finalize once every file, merged ones included, is written
+        if let Some(backup_manager) = backup
+        {
+            backup_manager.finalize(
+                report
+                    .conflicts
+                    .is_empty(),
+            )?;
+            let session_dir = backup_manager
+                .session_dir()
+                .to_path_buf();
+            report.backup_session_id = Some(
+                backup_manager
+                    .session_id()
+                    .to_string(),
+            );
+            report.backup_manifest_path = Some(session_dir.join("manifest.json"));
+            report.backup_paths = vec![session_dir];
+            report.backup_file_count = Some(backup_manager.file_count());
+        }
+        Ok(report)
     }
 }
 
+/// Create engine by registered name (see [`crate::core::engine_registry`])
+pub fn create_engine(
+    name: &str,
+    config: &EngineConfig,
+) -> Result<Box<dyn ApplyEngine>>
+{
+    crate::core::engine_registry::create_registered(name, config)
+}
+
 #[cfg(test)]
 mod tests
 {
@@ -818,4 +1333,43 @@ mod tests
                 .is_empty()
         );
     }
+
+    #[test]
+    fn test_internal_engine_reports_stats()
+    {
+        let mut temp_file = NamedTempFile::new().unwrap();
+        writeln!(temp_file, "line 1").unwrap();
+        writeln!(temp_file, "line 2").unwrap();
+
+        let spec = EditSpec {
+            file_blocks: vec![FileBlock {
+                path: temp_file
+                    .path()
+                    .to_path_buf(),
+                operations: vec![EditOperation::Replace {
+                    start_line: 2,
+                    end_line: 2,
+                    old_content: "line 2".to_string(),
+                    new_content: "two\nlines".to_string(),
+                    guard_cid: None,
+                }],
+            }],
+        };
+
+        let report = InternalEngine::new(false, false, 3)
+            .apply(&spec)
+            .unwrap();
+
+        let stats = &report.stats;
+        assert_eq!(
+            (stats.files_touched, stats.hunks_applied, stats.lines_added, stats.lines_removed),
+            (1, 1, 2, 1)
+        );
+        assert!(
+            report
+                .stats
+                .to_string()
+                .starts_with("1 file changed, 1 hunk applied (0 relocated, 0 skipped), +2 -1")
+        );
+    }
 }
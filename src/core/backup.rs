@@ -2,7 +2,22 @@
 //!
 //! Creates timestamped sessions under `.rup/backups/<ID>` with a manifest and a
 //! DONE marker for crash safety. Writes occur in `.rup/backups/tmp/<ID>` and are
-//! atomically renamed into place on finalize.
+//! atomically renamed into place on finalize. `[backup] dir` relocates the whole
+//! store (see `backups_dir`); every path below is relative to it.
+//!
+//! File contents are also content-addressed under `.rup/backups/objects/`,
+//! keyed by blake3 hash. The mirrored session tree hard-links into that
+//! store, so a file version backed up by many sessions occupies disk once;
+//! `rup backup gc` drops objects no manifest references any more.
+//!
+//! With a backup key configured, stored payloads are encrypted at rest
+//! (see `backup_crypt`). Sealed payloads live in their own namespace,
+//! `objects/sealed/`, named by the hash of their ciphertext, so they never
+//! share an object with a plaintext copy of the same content.
+//!
+//! Finalized sessions are mirrored to `[backup.remote]` when one is
+//! configured (see `backup_remote`), after which the `[backup]` retention
+//! limits prune older sessions.
 
 use std::{
     fs::{self, File, OpenOptions},
@@ -17,6 +32,8 @@ use chrono::Utc;
 use rand::Rng;
 use serde::{Deserialize, Serialize};
 
+use crate::core::backup_crypt::{BackupKey, configured_key};
+
 /// Per-file metadata recorded in the session manifest.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct FileBackupMeta
@@ -26,11 +43,27 @@ pub struct FileBackupMeta
     pub size_bytes: u64,          // backed-up content size
     pub last_modified: u64,       // secs since UNIX_EPOCH (source file)
     pub checksum: Option<String>, // blake3:<hex>
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub blob: Option<String>, // object store key (blake3 hex; of the ciphertext if sealed)
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub encrypted: bool, // payload sealed by backup_crypt
     pub symlink: bool,            // whether source was a symlink
     pub link_target: Option<PathBuf>, /* recorded link target (if any)
                                    * Note: no hashed-fallback needed when mirroring the tree */
 }
 
+/// Directory name of the content-addressed store inside `.rup/backups`.
+pub const OBJECTS_DIR: &str = "objects";
+
+/// Namespace for encrypted payloads inside the object store.
+pub const SEALED_DIR: &str = "sealed";
+
+/// Lock file serializing index appends, inside the backups dir.
+const INDEX_LOCK: &str = "index.lock";
+
+/// Placeholder in `[backup] dir` replaced by `repo_hash`.
+pub const REPO_HASH: &str = "<repo-hash>";
+
 /// Git snapshot captured at session start (best-effort).
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct GitSnapshot
@@ -56,6 +89,12 @@ pub struct SessionManifest
     pub success: bool,        // set on finalize
     pub last_updated: String, // RFC3339
     pub files: Vec<FileBackupMeta>,
+    /// Human label, e.g. "pre-refactor auth"; usable wherever an ID is
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub label: Option<String>,
+    /// Free-form note
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub note: Option<String>,
 }
 
 /// Lightweight index record for quick session listing.
@@ -67,6 +106,30 @@ pub struct SessionIndexEntry
     pub success: bool,
     pub files: usize,
     pub engine: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub label: Option<String>,
+}
+
+/// Which files a session captures besides those backed up explicitly.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub enum BackupScope
+{
+    /// Only files passed to `backup_file` (the ones an apply writes)
+    #[default]
+    Written,
+    /// Also every file directly inside these repo-relative directories,
+    /// captured at `begin`, so a restore also undoes side effects such as
+    /// formatter hooks rewriting neighbouring files. `""` is the repo root.
+    Dirs(Vec<PathBuf>),
+}
+
+/// Manifest fields of a file being backed up that don't depend on its content.
+struct StagedMeta
+{
+    rel: PathBuf,
+    last_modified: u64,
+    symlink: bool,
+    link_target: Option<PathBuf>,
 }
 
 /// Manager creating a single session; stage in tmp, then finalize.
@@ -76,12 +139,13 @@ pub struct BackupManager
     repo_root: PathBuf,
     sessions_dir: PathBuf, // .../.rup/backups
     // tmp_sessions_dir: PathBuf, // .../.rup/backups/tmp
-    locks_dir: PathBuf, // .../.rup/locks
+    objects_dir: PathBuf, // .../.rup/backups/objects
     session_id: String,
     session_tmp_dir: PathBuf,   // .../tmp/<id>
     session_final_dir: PathBuf, // .../backups/<id>
     manifest: SessionManifest,
     finalized: bool,
+    key: Option<BackupKey>, // seals payloads when [backup] encryption is on
 }
 
 impl BackupManager
@@ -92,18 +156,28 @@ impl BackupManager
         engine: &str,
     ) -> Result<Self>
     {
-        let rup_root = repo_root.join(".rup");
-        let sessions_dir = rup_root.join("backups");
+        Self::begin_scoped(repo_root, engine, &BackupScope::Written)
+    }
+
+    /// Start a new session and snapshot the file set `scope` names.
+    pub fn begin_scoped(
+        repo_root: &Path,
+        engine: &str,
+        scope: &BackupScope,
+    ) -> Result<Self>
+    {
+        let sessions_dir = backups_dir(repo_root);
         let tmp_sessions_dir = sessions_dir.join("tmp");
-        let locks_dir = rup_root.join("locks");
+        let objects_dir = sessions_dir.join(OBJECTS_DIR);
 
         fs::create_dir_all(&sessions_dir)
             .with_context(|| format!("create backups dir: {}", sessions_dir.display()))?;
         fs::create_dir_all(&tmp_sessions_dir)
             .with_context(|| format!("create tmp dir: {}", tmp_sessions_dir.display()))?;
-        fs::create_dir_all(&locks_dir)
-            .with_context(|| format!("create locks dir: {}", locks_dir.display()))?;
+        fs::create_dir_all(&objects_dir)
+            .with_context(|| format!("create objects dir: {}", objects_dir.display()))?;
 
+        let key = configured_key(repo_root)?;
         let session_id = generate_session_id();
         let session_tmp_dir = tmp_sessions_dir.join(&session_id);
         let session_final_dir = sessions_dir.join(&session_id);
@@ -124,19 +198,79 @@ impl BackupManager
             success: false,
             last_updated: now,
             files: Vec::new(),
+            label: None,
+            note: None,
         };
 
-        Ok(Self {
+        let mut mgr = Self {
             repo_root: repo_root.to_path_buf(),
             sessions_dir,
             // tmp_sessions_dir,
-            locks_dir,
+            objects_dir,
             session_id,
             session_tmp_dir,
             session_final_dir,
             manifest,
             finalized: false,
-        })
+            key,
+        };
+
+        if let BackupScope::Dirs(dirs) = scope
+        {
+            mgr.snapshot_dirs(dirs)?;
+        }
+        Ok(mgr)
+    }
+
+    /// Back up the files directly inside each directory, honoring ignore
+    /// rules; returns how many were added.
+    fn snapshot_dirs(
+        &mut self,
+        dirs: &[PathBuf],
+    ) -> Result<usize>
+    {
+        let mut files = std::collections::BTreeSet::new();
+        for dir in dirs
+        {
+            let dir = if dir
+                .as_os_str()
+                .is_empty()
+            {
+                PathBuf::new()
+            }
+            else
+            {
+                validate_repo_rel(dir)?
+            };
+            let walker = ignore::WalkBuilder::new(
+                self.repo_root
+                    .join(&dir),
+            )
+            .max_depth(Some(1))
+            .build();
+            for entry in walker.flatten()
+            {
+                if !entry
+                    .file_type()
+                    .is_some_and(|t| t.is_file())
+                {
+                    continue;
+                }
+                if let Ok(rel) = entry
+                    .path()
+                    .strip_prefix(&self.repo_root)
+                {
+                    files.insert(rel.to_path_buf());
+                }
+            }
+        }
+
+        let before = self.file_count();
+        for rel in &files
+        {
+            self.backup_file(rel)?;
+        }
+        Ok(self.file_count() - before)
     }
 
     /// Back up a single repo-relative file; follows symlinks for content.
@@ -146,6 +280,15 @@ impl BackupManager
     ) -> Result<()>
     {
         let rel = validate_repo_rel(rel_path)?;
+        // Already captured, e.g. by a directory snapshot
+        if self
+            .manifest
+            .files
+            .iter()
+            .any(|f| f.original_path == rel)
+        {
+            return Ok(());
+        }
         let source_path = self
             .repo_root
             .join(&rel);
@@ -182,51 +325,185 @@ impl BackupManager
         };
 
         // Copy content: follow symlink to target bytes (policy).
-        if symlink
+        let content_path = if symlink
         {
             // Fix #8: Better error handling for broken symlinks
-            let resolved = fs::canonicalize(&source_path).with_context(|| {
+            fs::canonicalize(&source_path).with_context(|| {
                 format!(
                     "resolve symlink target (broken?): {}",
                     source_path.display()
                 )
-            })?;
-            fs::copy(&resolved, &backup_path)
-                .with_context(|| format!("copy target to backup: {}", backup_path.display()))?;
+            })?
         }
         else
         {
-            fs::copy(&source_path, &backup_path)
-                .with_context(|| format!("copy file to backup: {}", backup_path.display()))?;
-        }
+            source_path.clone()
+        };
 
-        // Content-based accounting from the backup copy.
-        let size_bytes = fs::metadata(&backup_path)
-            .with_context(|| format!("stat backup: {}", backup_path.display()))?
-            .len();
         let last_modified = meta
             .modified()
             .unwrap_or(SystemTime::UNIX_EPOCH)
             .duration_since(UNIX_EPOCH)
             .unwrap_or_default()
             .as_secs();
+        let staged = StagedMeta { rel, last_modified, symlink, link_target };
 
-        let checksum = Some(stream_blake3(&backup_path)?);
+        // Sealed in memory so no plaintext copy reaches the backup store
+        if self
+            .key
+            .is_some()
+        {
+            let plain = fs::read(&content_path)
+                .with_context(|| format!("read file to back up: {}", content_path.display()))?;
+            return self.record_sealed(staged, &backup_path, &plain);
+        }
+        fs::copy(&content_path, &backup_path)
+            .with_context(|| format!("copy file to backup: {}", backup_path.display()))?;
+        self.record_staged(staged, &backup_path)
+    }
 
+    /// Back up `contents` as the version of a repo-relative file, for
+    /// sources other than the worktree (e.g. a git stash).
+    pub fn backup_contents(
+        &mut self,
+        rel_path: &Path,
+        contents: &[u8],
+    ) -> Result<()>
+    {
+        let rel = validate_repo_rel(rel_path)?;
+        if self
+            .manifest
+            .files
+            .iter()
+            .any(|f| f.original_path == rel)
+        {
+            return Ok(());
+        }
+        let backup_path = self
+            .session_tmp_dir
+            .join(&rel);
+        if let Some(parent) = backup_path.parent()
+        {
+            fs::create_dir_all(parent)
+                .with_context(|| format!("create backup parent: {}", parent.display()))?;
+        }
+        let now = Utc::now()
+            .timestamp()
+            .max(0) as u64;
+        let staged = StagedMeta { rel, last_modified: now, symlink: false, link_target: None };
+
+        if self
+            .key
+            .is_some()
+        {
+            return self.record_sealed(staged, &backup_path, contents);
+        }
+        fs::write(&backup_path, contents)
+            .with_context(|| format!("write backup: {}", backup_path.display()))?;
+        self.record_staged(staged, &backup_path)
+    }
+
+    /// Hash and dedup a staged plaintext copy, then add it to the manifest.
+    fn record_staged(
+        &mut self,
+        staged: StagedMeta,
+        backup_path: &Path,
+    ) -> Result<()>
+    {
+        // Content-based accounting from the backup copy.
+        let size_bytes = fs::metadata(backup_path)
+            .with_context(|| format!("stat backup: {}", backup_path.display()))?
+            .len();
+
+        let checksum = stream_blake3(backup_path)?;
+        let blob = checksum
+            .strip_prefix("blake3:")
+            .map(str::to_string);
+        if let Some(hash) = &blob
+        {
+            store_blob(&self.objects_dir, backup_path, hash, false)?;
+        }
+        self.push_file(staged, size_bytes, checksum, blob, false);
+        Ok(())
+    }
+
+    /// Seal `plain`, stage only the ciphertext, and store it in the sealed
+    /// namespace under the hash of the ciphertext; the checksum still
+    /// describes the plaintext so restores can verify it.
+    fn record_sealed(
+        &mut self,
+        staged: StagedMeta,
+        backup_path: &Path,
+        plain: &[u8],
+    ) -> Result<()>
+    {
+        let Some(key) = &self.key
+        else
+        {
+            bail!("no backup key to seal {}", staged.rel.display());
+        };
+        let sealed = key.encrypt(plain)?;
+        fs::write(backup_path, &sealed)
+            .with_context(|| format!("write backup: {}", backup_path.display()))?;
+
+        let checksum = format!("blake3:{}", blake3::hash(plain).to_hex());
+        let blob = blake3::hash(&sealed)
+            .to_hex()
+            .to_string();
+        store_blob(&self.objects_dir, backup_path, &blob, true)?;
+        self.push_file(staged, plain.len() as u64, checksum, Some(blob), true);
+        Ok(())
+    }
+
+    fn push_file(
+        &mut self,
+        staged: StagedMeta,
+        size_bytes: u64,
+        checksum: String,
+        blob: Option<String>,
+        encrypted: bool,
+    )
+    {
         self.manifest
             .files
             .push(FileBackupMeta {
-                original_path: rel.clone(),
-                rel_path: rel,
+                original_path: staged
+                    .rel
+                    .clone(),
+                rel_path: staged.rel,
                 size_bytes,
-                last_modified,
-                checksum,
-                symlink,
-                link_target,
+                last_modified: staged.last_modified,
+                checksum: Some(checksum),
+                blob,
+                encrypted,
+                symlink: staged.symlink,
+                link_target: staged.link_target,
             });
         self.manifest
             .last_updated = Utc::now().to_rfc3339();
-        Ok(())
+    }
+
+    /// Attach a human label and note, recorded in the manifest on finalize.
+    pub fn annotate(
+        &mut self,
+        label: Option<String>,
+        note: Option<String>,
+    )
+    {
+        self.manifest
+            .label = label;
+        self.manifest
+            .note = note;
+    }
+
+    /// Record what created the session (default `apply`).
+    pub fn set_operation(
+        &mut self,
+        operation: &str,
+    )
+    {
+        self.manifest
+            .operation = operation.into();
     }
 
     /// Session identifier.
@@ -320,42 +597,74 @@ impl BackupManager
 
         // Append to index under lock.
         self.append_to_index()?;
+
+        // Mirror off-machine if configured; never fails the session
+        if let Err(e) = crate::core::backup_remote::spawn_mirror(&self.repo_root, &self.session_id)
+        {
+            tracing::warn!("remote backup of {} not started: {e:#}", self.session_id);
+        }
+
+        // Enforce [backup] retention limits; never fails the session
+        if let Err(e) = self.enforce_retention()
+        {
+            tracing::warn!("backup retention after {} failed: {e:#}", self.session_id);
+        }
         Ok(())
     }
 
     fn append_to_index(&self) -> Result<()>
     {
-        let index_path = self
-            .sessions_dir
-            .join("index.jsonl");
-        let lock_path = self
-            .locks_dir
-            .join("backups.lock");
-        let _guard = acquire_lock(&lock_path)?;
-
-        let entry = SessionIndexEntry {
-            id: self
-                .manifest
-                .id
-                .clone(),
-            timestamp: self
-                .manifest
-                .timestamp
-                .clone(),
-            success: self
-                .manifest
-                .success,
-            files: self
-                .manifest
-                .files
-                .len(),
-            engine: self
-                .manifest
-                .engine
-                .clone(),
+        append_index_entry(&self.repo_root, &self.manifest)
+    }
+
+    /// Drop sessions beyond the configured limits, then their orphaned objects
+    fn enforce_retention(&self) -> Result<()>
+    {
+        use crate::core::backup_ops::{GcRequest, enforce_retention, gc_objects};
+
+        let Some(cleanup) = enforce_retention(&self.repo_root, Some(&self.session_id), false)?
+        else
+        {
+            return Ok(());
         };
-        let line = serde_json::to_string(&entry).context("serialize index entry")?;
+        if !cleanup
+            .sessions_removed
+            .is_empty()
+        {
+            gc_objects(&self.repo_root, GcRequest { dry_run: false, retention: false })?;
+        }
+        Ok(())
+    }
+}
 
+/// Append a session's index record under the backups lock.
+pub(crate) fn append_index_entry(
+    repo_root: &Path,
+    manifest: &SessionManifest,
+) -> Result<()>
+{
+    let entry = SessionIndexEntry {
+        id: manifest
+            .id
+            .clone(),
+        timestamp: manifest
+            .timestamp
+            .clone(),
+        success: manifest.success,
+        files: manifest
+            .files
+            .len(),
+        engine: manifest
+            .engine
+            .clone(),
+        label: manifest
+            .label
+            .clone(),
+    };
+    let line = serde_json::to_string(&entry).context("serialize index entry")?;
+
+    with_index_lock(repo_root, |base| {
+        let index_path = base.join("index.jsonl");
         let mut f = OpenOptions::new()
             .create(true)
             .append(true)
@@ -364,9 +673,8 @@ impl BackupManager
         writeln!(f, "{line}").context("append index")?;
         f.sync_all()
             .ok();
-
         Ok(())
-    }
+    })
 }
 
 impl Drop for BackupManager
@@ -380,6 +688,58 @@ impl Drop for BackupManager
     }
 }
 
+/// Move the session copy into the object store, or share the stored
+/// object when identical content is already there. `sealed` copies go to
+/// the sealed namespace, so plaintext and ciphertext are never shared.
+pub(crate) fn store_blob(
+    objects_dir: &Path,
+    backup_path: &Path,
+    hash: &str,
+    sealed: bool,
+) -> Result<()>
+{
+    let object = object_path_in(objects_dir, hash, sealed);
+    if object.exists()
+    {
+        // Swap the fresh copy for a link to the existing object; keep
+        // the copy if the filesystem can't link.
+        let staged = backup_path.with_file_name(format!(
+            ".{}.link",
+            backup_path
+                .file_name()
+                .map(|n| {
+                    n.to_string_lossy()
+                        .into_owned()
+                })
+                .unwrap_or_default()
+        ));
+        if fs::hard_link(&object, &staged).is_ok()
+            && fs::rename(&staged, backup_path).is_err()
+        {
+            let _ = fs::remove_file(&staged);
+        }
+        return Ok(());
+    }
+
+    if let Some(parent) = object.parent()
+    {
+        fs::create_dir_all(parent)
+            .with_context(|| format!("create object dir: {}", parent.display()))?;
+    }
+    match fs::hard_link(backup_path, &object)
+    {
+        Ok(()) => Ok(()),
+        // Another session stored the same content concurrently
+        Err(e) if e.kind() == std::io::ErrorKind::AlreadyExists => Ok(()),
+        Err(_) =>
+        {
+            fs::copy(backup_path, &object)
+                .with_context(|| format!("store object: {}", object.display()))?;
+            Ok(())
+        }
+    }
+}
+
 /// Cross-platform directory fsync helper.
 #[cfg(unix)]
 fn sync_dir(p: &Path) -> std::io::Result<()>
@@ -479,7 +839,7 @@ fn capture_git_snapshot(repo_root: &Path) -> Result<GitSnapshot>
 }
 
 /// Stream a file into a blake3 digest as `blake3:<hex>`.
-fn stream_blake3(path: &Path) -> Result<String>
+pub(crate) fn stream_blake3(path: &Path) -> Result<String>
 {
     let mut f =
         File::open(path).with_context(|| format!("open for checksum: {}", path.display()))?;
@@ -502,82 +862,37 @@ fn stream_blake3(path: &Path) -> Result<String>
     ))
 }
 
-/// Acquire a simple file lock; guard deletes the lock on drop.
-struct LockGuard
-{
-    path: PathBuf,
-    file: File,
-}
-
-impl Drop for LockGuard
-{
-    fn drop(&mut self)
-    {
-        let _ = self
-            .file
-            .sync_all();
-        let _ = fs::remove_file(&self.path);
-    }
-}
-
-fn acquire_lock(lock_path: &Path) -> Result<LockGuard>
+/// Run `f` holding the exclusive lock on the backup index of `repo_root`.
+///
+/// This is an OS advisory lock (`flock` / `LockFileEx`) on a persistent
+/// file: parallel `rup apply` runs queue up behind each other instead of
+/// failing, and a crashed holder releases it automatically.
+pub(crate) fn with_index_lock<T>(
+    repo_root: &Path,
+    f: impl FnOnce(&Path) -> Result<T>,
+) -> Result<T>
 {
-    // First try to create the lock file
-    match OpenOptions::new()
+    let base = backups_dir(repo_root);
+    fs::create_dir_all(&base).with_context(|| format!("create backups dir: {}", base.display()))?;
+    let lock_path = base.join(INDEX_LOCK);
+    let file = OpenOptions::new()
+        .create(true)
+        .truncate(false)
         .write(true)
-        .create_new(true)
-        .open(lock_path)
-    {
-        Ok(mut file) =>
-        {
-            writeln!(file, "pid={}", std::process::id()).ok();
-            file.sync_all()
-                .ok();
-            Ok(LockGuard { path: lock_path.to_path_buf(), file })
-        }
-        Err(e) if e.kind() == std::io::ErrorKind::AlreadyExists =>
-        {
-            // Fix #5: Handle stale locks - check if lock is old (>60 seconds)
-            if let Ok(meta) = fs::metadata(lock_path)
-                && let Ok(modified) = meta.modified()
-                && let Ok(elapsed) = modified.elapsed()
-                && elapsed.as_secs() > 60
-            {
-                // Lock is stale, try to remove and retry once
-                if fs::remove_file(lock_path).is_ok()
-                {
-                    let mut file = OpenOptions::new()
-                        .write(true)
-                        .create_new(true)
-                        .open(lock_path)
-                        .with_context(|| {
-                            format!("acquire lock after stale cleanup: {}", lock_path.display())
-                        })?;
-
-                    writeln!(file, "pid={}", std::process::id()).ok();
-
-                    file.sync_all()
-                        .ok();
-
-                    return Ok(LockGuard { path: lock_path.to_path_buf(), file });
-                }
-            }
-            Err(anyhow::Error::new(e).context(format!("acquire lock: {}", lock_path.display())))
-        }
-        Err(e) =>
-        {
-            Err(anyhow::Error::new(e).context(format!("acquire lock: {}", lock_path.display())))
-        }
-    }
+        .open(&lock_path)
+        .with_context(|| format!("open lock: {}", lock_path.display()))?;
+    let mut lock = fd_lock::RwLock::new(file);
+    let _guard = lock
+        .write()
+        .with_context(|| format!("acquire lock: {}", lock_path.display()))?;
+    f(&base)
 }
 
-/// Read the append-only index; ignores malformed lines.
+/// Read the append-only index; ignores malformed lines. A session listed
+/// twice (appended while a rebuild picked it up from disk) appears once.
 pub fn list_sessions(repo_root: &Path) -> Result<Vec<SessionIndexEntry>>
 {
-    let index_path = repo_root
-        .join(".rup")
-        .join("backups")
-        .join("index.jsonl");
+    let index_path = backups_dir(repo_root).join("index.jsonl");
     if !index_path.exists()
     {
         return Ok(Vec::new());
@@ -604,6 +919,8 @@ pub fn list_sessions(repo_root: &Path) -> Result<Vec<SessionIndexEntry>>
             out.push(e)
         }
     }
+    let mut seen = std::collections::HashSet::new();
+    out.retain(|e| seen.insert(e.id.clone()));
     Ok(out)
 }
 
@@ -613,10 +930,7 @@ pub fn read_session_manifest(
     session_id: &str,
 ) -> Result<SessionManifest>
 {
-    let base = repo_root
-        .join(".rup")
-        .join("backups")
-        .join(session_id);
+    let base = backups_dir(repo_root).join(session_id);
     let done = base.join("DONE");
     if !done.exists()
     {
@@ -630,6 +944,116 @@ pub fn read_session_manifest(
     Ok(m)
 }
 
+/// Root of the content-addressed object store.
+pub fn objects_dir(repo_root: &Path) -> PathBuf
+{
+    backups_dir(repo_root).join(OBJECTS_DIR)
+}
+
+/// Directory holding sessions, the object store, and the index.
+///
+/// `<repo>/.rup/backups` unless `[backup] dir` points elsewhere, e.g.
+/// `~/.cache/roughup/backups/<repo-hash>` to keep `.rup/` out of the
+/// worktree. An unreadable config falls back to the default.
+pub fn backups_dir(repo_root: &Path) -> PathBuf
+{
+    let configured = match crate::infra::config::load_config_in(repo_root)
+    {
+        Ok(cfg) => cfg
+            .backup
+            .dir,
+        Err(e) =>
+        {
+            tracing::warn!("backup dir: config not readable, using .rup/backups: {e:#}");
+            None
+        }
+    };
+    match configured
+    {
+        Some(spec) => resolve_backups_dir(repo_root, &spec),
+        None => repo_root
+            .join(".rup")
+            .join("backups"),
+    }
+}
+
+/// Expand a `[backup] dir` value for `repo_root`
+fn resolve_backups_dir(
+    repo_root: &Path,
+    spec: &str,
+) -> PathBuf
+{
+    let spec = if spec.contains(REPO_HASH)
+    {
+        spec.replace(REPO_HASH, &repo_hash(repo_root))
+    }
+    else
+    {
+        spec.to_string()
+    };
+    let expanded = shellexpand::full(&spec)
+        .map(|s| s.into_owned())
+        .unwrap_or(spec);
+    let path = PathBuf::from(expanded);
+    if path.is_absolute() { path } else { repo_root.join(path) }
+}
+
+/// Short stable hash of the canonical repo root, for per-repo store paths
+pub fn repo_hash(repo_root: &Path) -> String
+{
+    let root = dunce::canonicalize(repo_root).unwrap_or_else(|_| repo_root.to_path_buf());
+    let digest = blake3::hash(
+        root.to_string_lossy()
+            .as_bytes(),
+    );
+    digest.to_hex()[..16].to_string()
+}
+
+/// Location of the object for a blake3 hex digest (`objects/ab/cdef…`, or
+/// `objects/sealed/ab/cdef…` for encrypted payloads).
+pub fn object_path(
+    repo_root: &Path,
+    hash: &str,
+    sealed: bool,
+) -> PathBuf
+{
+    object_path_in(&objects_dir(repo_root), hash, sealed)
+}
+
+fn object_path_in(
+    objects_dir: &Path,
+    hash: &str,
+    sealed: bool,
+) -> PathBuf
+{
+    let split = hash
+        .len()
+        .min(2);
+    let namespace = if sealed { objects_dir.join(SEALED_DIR) } else { objects_dir.to_path_buf() };
+    namespace
+        .join(&hash[..split])
+        .join(&hash[split..])
+}
+
+/// Where to read a file's backed-up bytes: the stored object when present,
+/// else the copy in the mirrored session tree (pre-dedup sessions).
+pub fn payload_path(
+    repo_root: &Path,
+    session_dir: &Path,
+    meta: &FileBackupMeta,
+) -> PathBuf
+{
+    if let Some(hash) = &meta.blob
+    {
+        let object = object_path(repo_root, hash, meta.encrypted);
+        if object.exists()
+        {
+            return object;
+        }
+    }
+    session_dir.join(&meta.rel_path)
+}
+
 /// Validate that the given path is repo-relative and non-escaping.
 fn validate_repo_rel(p: &Path) -> Result<PathBuf>
 {
@@ -695,6 +1119,79 @@ mod tests
         assert_eq!(m.files[0].original_path, Path::new("file.txt"));
     }
 
+    #[test]
+    fn dirs_scope_snapshots_siblings_once()
+    {
+        let tmp = TempDir::new().unwrap();
+        let repo = tmp.path();
+        fs::create_dir_all(repo.join("src/nested")).unwrap();
+        fs::create_dir_all(repo.join("docs")).unwrap();
+        fs::write(repo.join("src/a.rs"), "a").unwrap();
+        fs::write(repo.join("src/b.rs"), "b").unwrap();
+        fs::write(repo.join("src/nested/c.rs"), "c").unwrap();
+        fs::write(repo.join("docs/d.md"), "d").unwrap();
+
+        let scope = BackupScope::Dirs(vec![PathBuf::from("src")]);
+        let mut mgr = BackupManager::begin_scoped(repo, "internal", &scope).unwrap();
+        // The written file was already captured by the snapshot
+        mgr.backup_file(Path::new("src/a.rs"))
+            .unwrap();
+        mgr.finalize(true)
+            .unwrap();
+
+        let m = read_session_manifest(repo, mgr.session_id()).unwrap();
+        let mut paths: Vec<_> = m
+            .files
+            .iter()
+            .map(|f| f.original_path.clone())
+            .collect();
+        paths.sort();
+        assert_eq!(paths, vec![PathBuf::from("src/a.rs"), PathBuf::from("src/b.rs")]);
+    }
+
+    #[test]
+    fn backups_dir_expands_placeholder_and_relative_paths()
+    {
+        let tmp = TempDir::new().unwrap();
+        let repo = tmp.path();
+        assert_eq!(backups_dir(repo), repo.join(".rup/backups"));
+
+        let hashed = resolve_backups_dir(repo, "/var/cache/rup/<repo-hash>");
+        assert_eq!(hashed, Path::new("/var/cache/rup").join(repo_hash(repo)));
+        assert_eq!(resolve_backups_dir(repo, "../store"), repo.join("../store"));
+    }
+
+    #[test]
+    fn parallel_sessions_all_reach_the_index()
+    {
+        let tmp = TempDir::new().unwrap();
+        let repo = tmp.path();
+        for i in 0..8
+        {
+            fs::write(repo.join(format!("f{i}.txt")), format!("{i}")).unwrap();
+        }
+
+        std::thread::scope(|s| {
+            for i in 0..8
+            {
+                s.spawn(move || {
+                    let mut mgr = BackupManager::begin(repo, "internal").unwrap();
+                    mgr.backup_file(Path::new(&format!("f{i}.txt")))
+                        .unwrap();
+                    mgr.finalize(true)
+                        .unwrap();
+                });
+            }
+        });
+
+        let idx = list_sessions(repo).unwrap();
+        assert_eq!(idx.len(), 8);
+        assert!(
+            idx.iter()
+                .all(|e| e.files == 1)
+        );
+    }
+
     #[test]
     fn preserves_mirrored_tree()
     {
@@ -719,4 +1216,53 @@ mod tests
         assert!(backed.exists());
         assert_eq!(fs::read_to_string(backed).unwrap(), "fn main(){}");
     }
+
+    #[test]
+    fn identical_content_stored_once()
+    {
+        let tmp = TempDir::new().unwrap();
+        let repo = tmp.path();
+        fs::write(repo.join("a.txt"), "same bytes").unwrap();
+
+        for _ in 0..2
+        {
+            let mut mgr = BackupManager::begin(repo, "internal").unwrap();
+            mgr.backup_file(Path::new("a.txt"))
+                .unwrap();
+            mgr.finalize(true)
+                .unwrap();
+        }
+
+        let sessions = list_sessions(repo).unwrap();
+        assert_eq!(sessions.len(), 2);
+        let blobs: Vec<_> = sessions
+            .iter()
+            .map(|s| {
+                read_session_manifest(repo, &s.id).unwrap().files[0]
+                    .blob
+                    .clone()
+                    .unwrap()
+            })
+            .collect();
+        assert_eq!(blobs[0], blobs[1]);
+
+        let object = object_path(repo, &blobs[0], false);
+        assert_eq!(fs::read_to_string(&object).unwrap(), "same bytes");
+        let stored: Vec<_> = fs::read_dir(objects_dir(repo))
+            .unwrap()
+            .collect();
+        assert_eq!(stored.len(), 1);
+
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::MetadataExt;
+            // One object plus a link from each session tree
+            assert_eq!(
+                fs::metadata(&object)
+                    .unwrap()
+                    .nlink(),
+                3
+            );
+        }
+    }
 }
@@ -0,0 +1,521 @@
+//! Portable backup archives (`rup backup export` / `rup backup import`)
+//!
+//! A session is written as a zstd-compressed tar with one top-level
+//! directory named after the session ID:
+//!
+//! ```text
+//! <ID>/manifest.json
+//! <ID>/files/<repo-relative path>
+//! ```
+//!
+//! Import re-checks everything the manifest promises before the session
+//! becomes visible: every listed file must be present with the recorded
+//! size and blake3 checksum, nothing else may be in the archive, and no
+//! entry may escape the session tree. A verified session is staged under
+//! `.rup/backups/tmp/`, linked into the object store, and renamed into
+//! place like a locally created one.
+//!
+//! Encrypted payloads are archived as stored, so verifying them on import
+//! needs the same backup key.
+
+use std::{
+    collections::HashMap,
+    fs::{self, File},
+    io::{Read, Write},
+    path::{Component, Path, PathBuf},
+};
+
+use anyhow::{Context, Result, bail};
+use serde::Serialize;
+
+use crate::core::{
+    backup::{
+        OBJECTS_DIR, SessionManifest, append_index_entry, backups_dir, objects_dir, payload_path,
+        read_session_manifest, store_blob, stream_blake3,
+    },
+    backup_crypt::{is_sealed_file, plaintext_digest},
+    backup_ops::{normalize_repo_rel, resolve_session_id},
+};
+
+/// zstd frame magic (little-endian 0xFD2FB528)
+const ZSTD_MAGIC: [u8; 4] = [0x28, 0xB5, 0x2F, 0xFD];
+
+/// Result of exporting a session.
+#[derive(Debug, Serialize)]
+pub struct ExportResult
+{
+    /// Path of the written archive
+    pub archive: PathBuf,
+
+    /// Number of files in the archive
+    pub files: usize,
+
+    /// The exported session ID
+    pub session_id: String,
+}
+
+/// Result of importing an archive.
+#[derive(Debug, Serialize)]
+pub struct ImportResult
+{
+    /// Number of files verified and imported
+    pub files: usize,
+
+    /// The imported session ID
+    pub session_id: String,
+}
+
+/// Write `session` (ID or alias) to a `.tar.zst` archive at `output`.
+pub fn export_session(
+    repo_root: &Path,
+    session: &str,
+    output: &Path,
+) -> Result<ExportResult>
+{
+    let session_id = resolve_session_id(repo_root, session)?;
+    let manifest = read_session_manifest(repo_root, &session_id)?;
+    let session_dir = backups_dir(repo_root).join(&session_id);
+
+    // Write next to the destination and rename, so a failed export never
+    // leaves a truncated archive behind
+    let parent = output
+        .parent()
+        .filter(|p| {
+            !p.as_os_str()
+                .is_empty()
+        })
+        .unwrap_or(Path::new("."));
+    let tmp = tempfile::NamedTempFile::new_in(parent)
+        .with_context(|| format!("create archive in {}", parent.display()))?;
+
+    let encoder = zstd::Encoder::new(
+        tmp.as_file()
+            .try_clone()?,
+        0,
+    )
+    .context("init zstd encoder")?;
+    let mut builder = tar::Builder::new(encoder);
+
+    let root = PathBuf::from(&session_id);
+    let manifest_text = serde_json::to_string_pretty(&manifest).context("serialize manifest")?;
+    append_bytes(
+        &mut builder,
+        &root.join("manifest.json"),
+        manifest_text.as_bytes(),
+    )?;
+
+    for f in &manifest.files
+    {
+        let rel = normalize_repo_rel(&f.rel_path)?;
+        let src = payload_path(repo_root, &session_dir, f);
+        let mut file =
+            File::open(&src).with_context(|| format!("read backup payload: {}", rel.display()))?;
+        builder
+            .append_file(
+                root.join("files")
+                    .join(&rel),
+                &mut file,
+            )
+            .with_context(|| format!("archive {}", rel.display()))?;
+    }
+
+    builder
+        .into_inner()
+        .context("finish tar stream")?
+        .finish()
+        .context("finish zstd stream")?
+        .flush()?;
+    tmp.as_file()
+        .sync_all()
+        .ok();
+    tmp.persist(output)
+        .with_context(|| format!("write archive: {}", output.display()))?;
+
+    Ok(ExportResult {
+        archive: output.to_path_buf(),
+        files: manifest
+            .files
+            .len(),
+        session_id,
+    })
+}
+
+/// Verify an archive produced by [`export_session`] and add its session to
+/// the local backup store.
+pub fn import_session(
+    repo_root: &Path,
+    archive: &Path,
+) -> Result<ImportResult>
+{
+    let mut file =
+        File::open(archive).with_context(|| format!("open archive: {}", archive.display()))?;
+    let mut magic = [0u8; 4];
+    if file
+        .read_exact(&mut magic)
+        .is_err()
+        || magic != ZSTD_MAGIC
+    {
+        bail!("{} is not a zstd-compressed tar archive", archive.display());
+    }
+    drop(file);
+
+    let backups = backups_dir(repo_root);
+    let tmp_root = backups.join("tmp");
+    fs::create_dir_all(&tmp_root)
+        .with_context(|| format!("create tmp dir: {}", tmp_root.display()))?;
+    let staging = tempfile::Builder::new()
+        .prefix("import-")
+        .tempdir_in(&tmp_root)
+        .context("create import staging dir")?;
+
+    let decoder = zstd::Decoder::new(File::open(archive)?).context("init zstd decoder")?;
+    let mut tar = tar::Archive::new(decoder);
+
+    let mut session_id: Option<String> = None;
+    let mut manifest_text: Option<String> = None;
+    let mut payloads = HashMap::<PathBuf, u64>::new();
+
+    for entry in tar
+        .entries()
+        .context("read archive")?
+    {
+        let mut entry = entry.context("read archive entry")?;
+        let path = entry
+            .path()?
+            .into_owned();
+        let kind = entry
+            .header()
+            .entry_type();
+        if kind.is_dir()
+        {
+            continue;
+        }
+        if !kind.is_file()
+        {
+            bail!(
+                "integrity check failed: unsupported entry type in archive: {}",
+                path.display()
+            );
+        }
+
+        let (id, rest) = split_entry(&path)?;
+        match &session_id
+        {
+            Some(seen) if *seen != id =>
+            {
+                bail!(
+                    "integrity check failed: archive mixes sessions {} and {}",
+                    seen,
+                    id
+                )
+            }
+            Some(_) =>
+            {}
+            None => session_id = Some(id),
+        }
+
+        if rest == Path::new("manifest.json")
+        {
+            let mut s = String::new();
+            entry
+                .read_to_string(&mut s)
+                .context("read archived manifest")?;
+            manifest_text = Some(s);
+            continue;
+        }
+
+        let rel = rest
+            .strip_prefix("files")
+            .ok()
+            .and_then(|r| normalize_repo_rel(r).ok())
+            .ok_or_else(|| {
+                anyhow::anyhow!(
+                    "integrity check failed: unexpected entry: {}",
+                    path.display()
+                )
+            })?;
+        let dst = staging
+            .path()
+            .join(&rel);
+        if let Some(parent) = dst.parent()
+        {
+            fs::create_dir_all(parent)?;
+        }
+        let mut out = File::create(&dst)?;
+        let n = std::io::copy(&mut entry, &mut out)
+            .with_context(|| format!("extract {}", rel.display()))?;
+        payloads.insert(rel, n);
+    }
+
+    let session_id = session_id.context("integrity check failed: archive is empty")?;
+    let manifest_text =
+        manifest_text.context("integrity check failed: archive has no manifest.json")?;
+    let mut manifest: SessionManifest = serde_json::from_str(&manifest_text)
+        .context("integrity check failed: manifest.json does not parse")?;
+    if manifest.id != session_id
+    {
+        bail!(
+            "integrity check failed: manifest id {} does not match archive root {}",
+            manifest.id,
+            session_id
+        );
+    }
+
+    let final_dir = backups.join(&session_id);
+    if final_dir.exists()
+    {
+        bail!("session {} already exists; nothing imported", session_id);
+    }
+
+    for f in &mut manifest.files
+    {
+        let rel = normalize_repo_rel(&f.rel_path)
+            .context("integrity check failed: manifest path escapes the session")?;
+        if payloads
+            .remove(&rel)
+            .is_none()
+        {
+            bail!(
+                "integrity check failed: {} is missing from the archive",
+                rel.display()
+            );
+        }
+        let staged = staging
+            .path()
+            .join(&rel);
+        // Encrypted payloads are checked against their plaintext; the
+        // archive's own header decides, as older manifests lack the flag
+        let (size, actual) = plaintext_digest(repo_root, &staged, is_sealed_file(&staged))?;
+        if size != f.size_bytes
+        {
+            bail!(
+                "integrity check failed: {} is {} bytes, manifest says {}",
+                rel.display(),
+                size,
+                f.size_bytes
+            );
+        }
+        if let Some(expected) = &f.checksum
+            && *expected != actual
+        {
+            bail!(
+                "integrity check failed: checksum mismatch for {}",
+                rel.display()
+            );
+        }
+
+        // Sealed payloads are stored apart, under the hash of their ciphertext
+        f.encrypted = is_sealed_file(&staged);
+        let digest = if f.encrypted
+        {
+            stream_blake3(&staged)?
+        }
+        else
+        {
+            actual
+        };
+        let hash = digest
+            .strip_prefix("blake3:")
+            .unwrap_or(&digest)
+            .to_string();
+        store_blob(&objects_dir(repo_root), &staged, &hash, f.encrypted)?;
+        f.blob = Some(hash);
+    }
+    if let Some(extra) = payloads
+        .keys()
+        .next()
+    {
+        bail!(
+            "integrity check failed: {} is not listed in the manifest",
+            extra.display()
+        );
+    }
+
+    let text = serde_json::to_string_pretty(&manifest).context("serialize manifest")?;
+    fs::write(
+        staging
+            .path()
+            .join("manifest.json"),
+        text,
+    )?;
+
+    let staged_dir = staging.keep();
+    fs::rename(&staged_dir, &final_dir)
+        .with_context(|| format!("rename {} → {}", staged_dir.display(), final_dir.display()))?;
+    fs::write(final_dir.join("DONE"), "")
+        .with_context(|| format!("create DONE: {}", final_dir.display()))?;
+    append_index_entry(repo_root, &manifest)?;
+
+    Ok(ImportResult {
+        files: manifest
+            .files
+            .len(),
+        session_id,
+    })
+}
+
+fn append_bytes<W: Write>(
+    builder: &mut tar::Builder<W>,
+    path: &Path,
+    bytes: &[u8],
+) -> Result<()>
+{
+    let mut header = tar::Header::new_gnu();
+    header.set_size(bytes.len() as u64);
+    header.set_mode(0o644);
+    header.set_mtime(
+        chrono::Utc::now()
+            .timestamp()
+            .max(0) as u64,
+    );
+    header.set_cksum();
+    builder
+        .append_data(&mut header, path, bytes)
+        .with_context(|| format!("archive {}", path.display()))
+}
+
+/// Split `<ID>/<rest>` and reject IDs that could escape `.rup/backups`.
+fn split_entry(path: &Path) -> Result<(String, PathBuf)>
+{
+    let mut comps = path.components();
+    let id = match comps.next()
+    {
+        Some(Component::Normal(id)) =>
+        {
+            id.to_string_lossy()
+                .to_string()
+        }
+        _ =>
+        {
+            bail!(
+                "integrity check failed: unexpected entry: {}",
+                path.display()
+            )
+        }
+    };
+    if id == "tmp" || id == OBJECTS_DIR || id.starts_with('.')
+    {
+        bail!("integrity check failed: invalid session id: {}", id);
+    }
+    let rest: PathBuf = comps.collect();
+    if rest
+        .as_os_str()
+        .is_empty()
+    {
+        bail!(
+            "integrity check failed: unexpected entry: {}",
+            path.display()
+        );
+    }
+    Ok((id, rest))
+}
+
+#[cfg(test)]
+mod tests
+{
+    use tempfile::TempDir;
+
+    use super::*;
+    use crate::core::backup::{BackupManager, list_sessions};
+
+    fn session_with(
+        repo: &Path,
+        files: &[(&str, &str)],
+    ) -> String
+    {
+        let mut mgr = BackupManager::begin(repo, "internal").unwrap();
+        for (rel, body) in files
+        {
+            let p = repo.join(rel);
+            fs::create_dir_all(
+                p.parent()
+                    .unwrap(),
+            )
+            .unwrap();
+            fs::write(&p, body).unwrap();
+            mgr.backup_file(Path::new(rel))
+                .unwrap();
+        }
+        mgr.finalize(true)
+            .unwrap();
+        mgr.session_id()
+            .to_string()
+    }
+
+    #[test]
+    fn test_export_import_roundtrip()
+    {
+        let src = TempDir::new().unwrap();
+        let id = session_with(src.path(), &[
+            ("a.txt", "alpha\n"),
+            ("src/b.rs", "fn b() {}\n"),
+        ]);
+        let archive = src
+            .path()
+            .join("s.tar.zst");
+        let exported = export_session(src.path(), "latest", &archive).unwrap();
+        assert_eq!(exported.files, 2);
+
+        let dst = TempDir::new().unwrap();
+        let imported = import_session(dst.path(), &archive).unwrap();
+        assert_eq!(imported.session_id, id);
+        assert_eq!(imported.files, 2);
+
+        let index = list_sessions(dst.path()).unwrap();
+        assert_eq!(index[0].id, id);
+        let manifest = read_session_manifest(dst.path(), &id).unwrap();
+        let session_dir = dst
+            .path()
+            .join(".rup/backups")
+            .join(&id);
+        assert_eq!(
+            fs::read_to_string(payload_path(dst.path(), &session_dir, &manifest.files[1])).unwrap(),
+            "fn b() {}\n"
+        );
+
+        // A second import of the same session is refused
+        assert!(import_session(dst.path(), &archive).is_err());
+    }
+
+    #[test]
+    fn test_import_rejects_tampered_payload()
+    {
+        let src = TempDir::new().unwrap();
+        let id = session_with(src.path(), &[("a.txt", "alpha\n")]);
+        let manifest = read_session_manifest(src.path(), &id).unwrap();
+
+        // Same size, different bytes
+        let archive = src
+            .path()
+            .join("bad.tar.zst");
+        let mut builder =
+            tar::Builder::new(zstd::Encoder::new(File::create(&archive).unwrap(), 0).unwrap());
+        let root = PathBuf::from(&id);
+        append_bytes(
+            &mut builder,
+            &root.join("manifest.json"),
+            serde_json::to_string(&manifest)
+                .unwrap()
+                .as_bytes(),
+        )
+        .unwrap();
+        append_bytes(&mut builder, &root.join("files/a.txt"), b"ALPHA\n").unwrap();
+        builder
+            .into_inner()
+            .unwrap()
+            .finish()
+            .unwrap();
+
+        let dst = TempDir::new().unwrap();
+        let err = import_session(dst.path(), &archive)
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("checksum mismatch"), "{err}");
+        assert!(
+            !dst.path()
+                .join(".rup/backups")
+                .join(&id)
+                .exists()
+        );
+    }
+}
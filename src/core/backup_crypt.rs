@@ -0,0 +1,239 @@
+//! At-rest encryption of backup payloads
+//!
+//! When a key is configured, every file version a session stores is sealed
+//! with XChaCha20-Poly1305 before it lands in the object store:
+//!
+//! ```text
+//! RUPENC1\0 | 24-byte random nonce | ciphertext + 16-byte tag
+//! ```
+//!
+//! The key comes from `ROUGHUP_BACKUP_KEY`, or else `[backup] encryption_key`
+//! / `encryption_key_file`. Any secret string works; it is stretched to 256
+//! bits with blake3's key derivation, so use something with real entropy
+//! (e.g. `openssl rand -hex 32`). Manifests and checksums stay in plaintext
+//! so listing and gc work without the key; only reading file contents back
+//! needs it. Sealed objects are named by the hash of their ciphertext, which
+//! the random nonce makes unique, so object names reveal nothing about which
+//! files share content.
+
+use std::{
+    fs::{self, File},
+    io::Read,
+    path::Path,
+};
+
+use anyhow::{Context, Result, bail};
+use chacha20poly1305::{
+    KeyInit, XChaCha20Poly1305, XNonce,
+    aead::{Aead, Payload},
+};
+
+use crate::{core::backup::stream_blake3, infra::config::load_config_in};
+
+/// Header identifying an encrypted payload
+pub const MAGIC: &[u8; 8] = b"RUPENC1\0";
+
+/// Environment variable holding the backup key; wins over config
+pub const KEY_ENV: &str = "ROUGHUP_BACKUP_KEY";
+
+const NONCE_LEN: usize = 24;
+const KDF_CONTEXT: &str = "roughup 2025-08 backup payload encryption";
+
+/// Symmetric key for sealing backup payloads
+#[derive(Clone)]
+pub struct BackupKey(chacha20poly1305::Key);
+
+impl std::fmt::Debug for BackupKey
+{
+    fn fmt(
+        &self,
+        f: &mut std::fmt::Formatter<'_>,
+    ) -> std::fmt::Result
+    {
+        f.write_str("BackupKey(..)")
+    }
+}
+
+impl BackupKey
+{
+    /// Derive a key from a user-supplied secret
+    pub fn from_secret(secret: &str) -> Result<Self>
+    {
+        let secret = secret.trim();
+        if secret.is_empty()
+        {
+            bail!("backup encryption key is empty");
+        }
+        let key = blake3::derive_key(KDF_CONTEXT, secret.as_bytes());
+        Ok(Self(key.into()))
+    }
+
+    /// Seal `plain` as `MAGIC | nonce | ciphertext`
+    pub fn encrypt(
+        &self,
+        plain: &[u8],
+    ) -> Result<Vec<u8>>
+    {
+        let nonce: [u8; NONCE_LEN] = rand::random();
+        let sealed = XChaCha20Poly1305::new(&self.0)
+            .encrypt(XNonce::from_slice(&nonce), Payload {
+                msg: plain,
+                aad: MAGIC,
+            })
+            .map_err(|_| anyhow::anyhow!("encrypt backup payload"))?;
+
+        let mut out = Vec::with_capacity(MAGIC.len() + NONCE_LEN + sealed.len());
+        out.extend_from_slice(MAGIC);
+        out.extend_from_slice(&nonce);
+        out.extend_from_slice(&sealed);
+        Ok(out)
+    }
+
+    /// Open a payload produced by `encrypt`
+    pub fn decrypt(
+        &self,
+        data: &[u8],
+    ) -> Result<Vec<u8>>
+    {
+        if !is_encrypted(data) || data.len() < MAGIC.len() + NONCE_LEN
+        {
+            bail!("not an encrypted backup payload");
+        }
+        let (nonce, sealed) = data[MAGIC.len()..].split_at(NONCE_LEN);
+        XChaCha20Poly1305::new(&self.0)
+            .decrypt(XNonce::from_slice(nonce), Payload {
+                msg: sealed,
+                aad: MAGIC,
+            })
+            .map_err(|_| anyhow::anyhow!("decrypt backup payload: wrong key or corrupted data"))
+    }
+}
+
+/// Whether `data` starts with the encrypted-payload header
+pub fn is_encrypted(data: &[u8]) -> bool
+{
+    data.starts_with(MAGIC)
+}
+
+/// Whether the file at `path` starts with the encrypted-payload header
+pub fn is_sealed_file(path: &Path) -> bool
+{
+    let mut head = [0u8; MAGIC.len()];
+    File::open(path)
+        .and_then(|mut f| f.read_exact(&mut head))
+        .is_ok()
+        && is_encrypted(&head)
+}
+
+/// The key configured for `repo_root`, if encryption is enabled
+pub fn configured_key(repo_root: &Path) -> Result<Option<BackupKey>>
+{
+    if let Ok(secret) = std::env::var(KEY_ENV)
+        && !secret.is_empty()
+    {
+        return BackupKey::from_secret(&secret).map(Some);
+    }
+
+    let cfg = load_config_in(repo_root)?.backup;
+    if let Some(secret) = cfg.encryption_key
+    {
+        return BackupKey::from_secret(&secret).map(Some);
+    }
+    if let Some(file) = cfg.encryption_key_file
+    {
+        let path = shellexpand::tilde(&file.to_string_lossy()).into_owned();
+        let secret =
+            fs::read_to_string(&path).with_context(|| format!("read backup key file: {path}"))?;
+        return BackupKey::from_secret(&secret).map(Some);
+    }
+    Ok(None)
+}
+
+/// Read a stored payload, decrypting it when the manifest marks it
+/// `encrypted`; a plaintext file that happens to start with `MAGIC` is
+/// returned as-is
+pub fn read_payload(
+    repo_root: &Path,
+    path: &Path,
+    encrypted: bool,
+) -> Result<Vec<u8>>
+{
+    let data =
+        fs::read(path).with_context(|| format!("read backup payload: {}", path.display()))?;
+    if !encrypted
+    {
+        return Ok(data);
+    }
+
+    let Some(key) = configured_key(repo_root)?
+    else
+    {
+        bail!(
+            "{} is encrypted; set {} or [backup] encryption_key to read it",
+            path.display(),
+            KEY_ENV
+        );
+    };
+    key.decrypt(&data)
+        .with_context(|| {
+            path.display()
+                .to_string()
+        })
+}
+
+/// Plaintext size and `blake3:<hex>` checksum of a stored payload, for
+/// comparing against the manifest; plaintext payloads are streamed
+pub fn plaintext_digest(
+    repo_root: &Path,
+    path: &Path,
+    encrypted: bool,
+) -> Result<(u64, String)>
+{
+    if !encrypted
+    {
+        let size = fs::metadata(path)
+            .with_context(|| format!("stat backup payload: {}", path.display()))?
+            .len();
+        return Ok((size, stream_blake3(path)?));
+    }
+
+    let plain = read_payload(repo_root, path, true)?;
+    Ok((
+        plain.len() as u64,
+        format!("blake3:{}", blake3::hash(&plain).to_hex()),
+    ))
+}
+
+#[cfg(test)]
+mod tests
+{
+    use super::*;
+
+    #[test]
+    fn test_round_trip_and_wrong_key()
+    {
+        let key = BackupKey::from_secret("correct horse").unwrap();
+        let sealed = key
+            .encrypt(b"API_TOKEN=hunter2")
+            .unwrap();
+        assert!(is_encrypted(&sealed));
+        assert!(
+            !sealed
+                .windows(7)
+                .any(|w| w == b"hunter2")
+        );
+        assert_eq!(
+            key.decrypt(&sealed)
+                .unwrap(),
+            b"API_TOKEN=hunter2"
+        );
+
+        let other = BackupKey::from_secret("battery staple").unwrap();
+        assert!(
+            other
+                .decrypt(&sealed)
+                .is_err()
+        );
+        assert!(BackupKey::from_secret("  ").is_err());
+    }
+}
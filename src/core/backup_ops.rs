@@ -14,10 +14,17 @@
 //! - **Session Restoration:** Restore files from a backup session, with options for
 //!   dry-run, force overwrite, path filtering, checksum verification, and unified diff
 //!   reporting for conflicts.
-//! - **Session Cleanup:** Remove old or incomplete sessions based on age or count, with
-//!   dry-run support and error reporting.
+//! - **Session Cleanup:** Remove old or incomplete sessions based on age, count, or total
+//!   size, with dry-run support and error reporting.
+//! - **Retention:** Apply the `[backup]` `keep_latest` / `max_age` / `max_total_size`
+//!   limits automatically after each session and before object GC.
+//! - **Session Diff:** Unified diffs between the files of two sessions, or between a
+//!   session and the working tree, to trace what a sequence of applies changed.
+//! - **Object GC:** Remove blobs from the content-addressed store that no session
+//!   manifest references any more.
 //! - **Session ID Resolution:** Robust resolution of session IDs, supporting full IDs,
-//!   short suffixes, date prefixes, and aliases (`latest`, `last-successful`).
+//!   short suffixes, date prefixes, aliases (`latest`, `last-successful`), and
+//!   session labels.
 //! - **Unified Diff Generation:** Generate unified diffs between current files and backup
 //!   versions for conflict analysis.
 //! - **Checksum Verification:** Stream-based Blake3 checksum verification for backup
@@ -32,6 +39,8 @@
 //! - `ShowRequest`, `ShowResponse`: Structures for showing session details.
 //! - `RestoreRequest`, `RestoreResult`: Structures for restoring files from a session.
 //! - `CleanupRequest`, `CleanupResult`: Structures for cleaning up sessions.
+//! - `DiffRequest`, `DiffResult`: Structures for diffing sessions.
+//! - `GcRequest`, `GcResult`: Structures for garbage-collecting stored objects.
 //! - `FileDiff`: Structure representing a unified diff for a file.
 //!
 //! ## Helper Functions
@@ -42,7 +51,6 @@
 //! - `normalize_repo_rel`: Normalizes and validates repo-relative paths.
 //! - `parse_time_bound`: Parses time bounds for filtering and cleanup (supports RFC3339
 //!   and relative specs like "7d", "24h").
-//! - `stream_blake3`: Computes streaming Blake3 checksums for files.
 //!
 //! ## Error Handling
 //!
@@ -64,7 +72,7 @@
 //! with safe defaults and comprehensive error handling.
 use std::{
     fs,
-    io::{Read, Write},
+    io::Write,
     path::{Component, Path, PathBuf},
 };
 
@@ -72,9 +80,16 @@ use anyhow::{Context, Result, bail};
 use chrono::{DateTime, TimeZone, Utc};
 use serde::Serialize;
 
-use crate::core::backup::{
-    BackupManager, FileBackupMeta, SessionIndexEntry, SessionManifest, list_sessions,
-    read_session_manifest,
+use crate::{
+    core::{
+        backup::{
+            BackupManager, FileBackupMeta, OBJECTS_DIR, SEALED_DIR, SessionIndexEntry,
+            SessionManifest, backups_dir, list_sessions, objects_dir, payload_path,
+            read_session_manifest, with_index_lock,
+        },
+        backup_crypt::{plaintext_digest, read_payload},
+    },
+    infra::config::load_config_in,
 };
 
 /// Session ID resolution result
@@ -99,6 +114,8 @@ pub struct SessionInfo
     pub success: bool,
     pub files: usize,
     pub sample_paths: Vec<String>, // First 3 files for quick scanning
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub label: Option<String>,
 }
 
 /// Request structure for listing sessions
@@ -110,6 +127,8 @@ pub struct ListRequest
     pub since: Option<String>,
     pub limit: usize,
     pub sort_desc: bool,
+    /// Case-insensitive substring the session label must contain
+    pub label: Option<String>,
 }
 
 /// Request structure for showing session details
@@ -207,8 +226,15 @@ pub struct CleanupRequest
     /// Number of newest sessions to keep (if specified)
     pub keep_latest: Option<usize>,
 
+    /// Remove the oldest sessions once the newest ones add up to more than
+    /// this many bytes; the newest session is always kept
+    pub max_total_size: Option<u64>,
+
     /// Remove sessions older than this RFC3339 or relative spec (e.g., "7d", "24h")
     pub older_than: Option<String>,
+
+    /// Session never removed, e.g. the one just finalized
+    pub protect: Option<String>,
 }
 
 /// Result of cleanup.
@@ -225,6 +251,74 @@ pub struct CleanupResult
     pub sessions_removed: Vec<String>,
 }
 
+/// Request for diffing a session against another session or the worktree.
+#[derive(Debug)]
+pub struct DiffRequest
+{
+    /// Session ID or alias to diff from
+    pub from: String,
+
+    /// Optional repo-relative path filter
+    pub path: Option<PathBuf>,
+
+    /// Session ID or alias to diff to; `None` diffs against the worktree
+    pub to: Option<String>,
+}
+
+/// Result of a session diff.
+#[derive(Debug, Serialize)]
+pub struct DiffResult
+{
+    /// Unified diffs for files whose contents differ
+    pub diffs: Vec<FileDiff>,
+
+    /// Resolved session ID diffed from
+    pub from: String,
+
+    /// Files recorded only in the `from` session
+    pub only_in_from: Vec<PathBuf>,
+
+    /// Files recorded only in the `to` session
+    pub only_in_to: Vec<PathBuf>,
+
+    /// Resolved session ID diffed to, or "worktree"
+    pub to: String,
+
+    /// Number of files with identical contents on both sides
+    pub unchanged: usize,
+}
+
+/// Request for object garbage collection.
+#[derive(Debug)]
+pub struct GcRequest
+{
+    /// If true, only report unreferenced objects (do not delete them)
+    pub dry_run: bool,
+
+    /// If true, first remove sessions outside the configured retention limits
+    pub retention: bool,
+}
+
+/// Result of object garbage collection.
+#[derive(Debug, Serialize)]
+pub struct GcResult
+{
+    /// Total bytes freed (or that would be freed) by removing objects
+    pub bytes_freed: u64,
+
+    /// Errors encountered while removing objects
+    pub errors: Vec<String>,
+
+    /// Number of objects still referenced by a session
+    pub objects_kept: usize,
+
+    /// Hashes of unreferenced objects that were removed
+    pub objects_removed: Vec<String>,
+
+    /// Sessions removed by the retention policy before collecting
+    pub sessions_removed: Vec<String>,
+}
+
 /// List sessions with filters, minimizing manifest IO
 /// Filters include success status, engine type, and time bounds.
 pub fn list_sessions_filtered(
@@ -246,7 +340,8 @@ pub fn list_sessions_filtered(
     let mut entries = list_sessions(repo_root)?;
 
     // Keep only completed sessions
-    entries.retain(|e| session_is_complete(repo_root, &e.id).unwrap_or(false));
+    let dir = backups_dir(repo_root);
+    entries.retain(|e| session_is_complete(&dir, &e.id));
 
     // Apply filters that require only index data
     if req.successful
@@ -265,6 +360,19 @@ pub fn list_sessions_filtered(
         });
     }
 
+    if let Some(ref label_filter) = req.label
+    {
+        let needle = label_filter.to_lowercase();
+        entries.retain(|e| {
+            e.label
+                .as_deref()
+                .is_some_and(|l| {
+                    l.to_lowercase()
+                        .contains(&needle)
+                })
+        });
+    }
+
     if let Some(since) = since_time
     {
         // Drop sessions older than the bound
@@ -340,6 +448,7 @@ pub fn list_sessions_filtered(
             success: e.success,
             files: e.files,
             sample_paths,
+            label: e.label,
         });
     }
 
@@ -354,10 +463,7 @@ pub fn show_session(
 {
     let session_id = resolve_session_id(repo_root, &req.id)?;
     let manifest = read_session_manifest(repo_root, &session_id)?;
-    let session_path = repo_root
-        .join(".rup")
-        .join("backups")
-        .join(&session_id);
+    let session_path = backups_dir(repo_root).join(&session_id);
 
     // Calculate total size if verbose
     let total_size = if req.verbose
@@ -372,7 +478,7 @@ pub fn show_session(
     Ok(ShowResponse { manifest, session_path, total_size })
 }
 
-/// Resolve session ID (supports full, short, and aliases)
+/// Resolve session ID (supports full, short, aliases, and labels)
 pub fn resolve_session_id(
     repo_root: &Path,
     query: &str,
@@ -411,8 +517,10 @@ fn resolve_session_id_internal(
     let sessions = list_sessions(repo_root)?;
 
     // Helper to check completion
-    // (Avoid re-reading manifests; DONE marker is enough)
-    let is_complete = |id: &str| session_is_complete(repo_root, id).unwrap_or(false);
+    // (Avoid re-reading manifests; DONE marker is enough, and the backups
+    // dir is resolved once rather than per session)
+    let dir = backups_dir(repo_root);
+    let is_complete = |id: &str| session_is_complete(&dir, id);
 
     // Precompute parsed timestamps (skip invalid safely)
     // and carry completion status to avoid repeated IO.
@@ -506,6 +614,32 @@ fn resolve_session_id_internal(
         }
     }
 
+    // Fall back to labels; a reused label means its newest completed session
+    if matches.is_empty()
+    {
+        let mut labeled: Vec<_> = sessions
+            .iter()
+            .zip(&entries)
+            .filter(|(s, (id, ..))| {
+                s.label
+                    .as_deref()
+                    == Some(query)
+                    && is_complete(id)
+            })
+            .map(|(_, e)| e)
+            .collect();
+        labeled.sort_by(|a, b| {
+            b.3.cmp(&a.3)
+                .then_with(|| {
+                    b.1.cmp(&a.1)
+                })
+        });
+        if let Some((id, ..)) = labeled.first()
+        {
+            return Ok(SessionIdResolution::Single(id.clone()));
+        }
+    }
+
     // Sort matches newest-first for better ambiguity messages
     matches.sort_by(|a, b| {
         b.1.cmp(&a.1)
@@ -538,18 +672,16 @@ fn resolve_session_id_internal(
     })
 }
 
-/// Check if session is complete (has DONE marker)
+/// Check if session is complete (has DONE marker) under `backups_dir`
 fn session_is_complete(
-    repo_root: &Path,
+    backups_dir: &Path,
     session_id: &str,
-) -> Result<bool>
+) -> bool
 {
-    let done_path = repo_root
-        .join(".rup")
-        .join("backups")
+    backups_dir
         .join(session_id)
-        .join("DONE");
-    Ok(done_path.exists())
+        .join("DONE")
+        .exists()
 }
 
 /// Compute size of backed-up payload (exclude manifest and DONE)
@@ -612,10 +744,7 @@ pub fn restore_session(
 {
     let session_id = resolve_session_id(repo_root, &req.session_id)?;
     let manifest = read_session_manifest(repo_root, &session_id)?;
-    let session_dir = repo_root
-        .join(".rup")
-        .join("backups")
-        .join(&session_id);
+    let session_dir = backups_dir(repo_root).join(&session_id);
 
     let targets = select_targets(
         &manifest,
@@ -630,8 +759,8 @@ pub fn restore_session(
         {
             if let Some(expected) = &f.checksum
             {
-                let p = session_dir.join(&f.rel_path);
-                let actual = stream_blake3(&p)?;
+                let p = payload_path(repo_root, &session_dir, f);
+                let (_, actual) = plaintext_digest(repo_root, &p, f.encrypted)?;
                 if &actual != expected
                 {
                     bail!(
@@ -651,7 +780,12 @@ pub fn restore_session(
     for f in &targets
     {
         let dst = repo_root.join(&f.original_path);
-        let backup_bytes = fs::read(session_dir.join(&f.rel_path)).with_context(|| {
+        let backup_bytes = read_payload(
+            repo_root,
+            &payload_path(repo_root, &session_dir, f),
+            f.encrypted,
+        )
+        .with_context(|| {
             format!(
                 "read backup payload: {}",
                 f.rel_path
@@ -783,13 +917,14 @@ pub fn cleanup_sessions(
         && req
             .keep_latest
             .is_none()
+        && req
+            .max_total_size
+            .is_none()
     {
-        bail!("specify --older-than and/or --keep-latest");
+        bail!("specify --older-than, --keep-latest and/or --max-total-size");
     }
 
-    let base = repo_root
-        .join(".rup")
-        .join("backups");
+    let base = backups_dir(repo_root);
     if !base.exists()
     {
         return Ok(CleanupResult {
@@ -810,10 +945,8 @@ pub fn cleanup_sessions(
         {
             continue;
         }
-        if ent
-            .file_name()
-            .to_string_lossy()
-            == "tmp"
+        let name = ent.file_name();
+        if name == "tmp" || name == OBJECTS_DIR
         {
             continue;
         }
@@ -875,10 +1008,30 @@ pub fn cleanup_sessions(
         }
     }
 
+    if let Some(limit) = req.max_total_size
+    {
+        // Newest sessions fill the budget; everything past it goes
+        let mut total = 0u64;
+        for (i, (id, p, _, _)) in rows
+            .iter()
+            .enumerate()
+        {
+            total = total.saturating_add(dir_size(p).unwrap_or(0));
+            if i > 0 && total > limit
+            {
+                to_delete.push((id.clone(), p.clone()));
+            }
+        }
+    }
+
     // Fix #6: Proper deduplication using HashSet to handle non-adjacent duplicates
     use std::collections::HashSet;
     let mut seen = HashSet::new();
     to_delete.retain(|(id, _)| seen.insert(id.clone()));
+    if let Some(keep) = &req.protect
+    {
+        to_delete.retain(|(id, _)| id != keep);
+    }
 
     let mut bytes = 0u64;
     for (_, p) in &to_delete
@@ -921,8 +1074,367 @@ pub fn cleanup_sessions(
     })
 }
 
+/// Diff the backed-up files of one session against another session or the
+/// current working tree.
+///
+/// Against a session, only files recorded by both sides can be compared; the
+/// rest are listed in `only_in_from` / `only_in_to`. Against the worktree,
+/// every file of the session is compared, a missing worktree file reading as
+/// empty.
+pub fn diff_sessions(
+    repo_root: &Path,
+    req: DiffRequest,
+) -> Result<DiffResult>
+{
+    let from_id = resolve_session_id(repo_root, &req.from)?;
+    let from_manifest = read_session_manifest(repo_root, &from_id)?;
+    let from_dir = session_path(repo_root, &from_id);
+    let from_files = select_targets(
+        &from_manifest,
+        req.path
+            .as_deref(),
+    )?;
+
+    let mut result = DiffResult {
+        diffs: vec![],
+        from: from_id.clone(),
+        only_in_from: vec![],
+        only_in_to: vec![],
+        to: "worktree".into(),
+        unchanged: 0,
+    };
+
+    let Some(to) = &req.to
+    else
+    {
+        for f in &from_files
+        {
+            let old = read_payload(
+                repo_root,
+                &payload_path(repo_root, &from_dir, f),
+                f.encrypted,
+            )
+            .with_context(|| {
+                format!(
+                    "read backup payload: {}",
+                    f.rel_path
+                        .display()
+                )
+            })?;
+            let new = fs::read(repo_root.join(&f.original_path)).unwrap_or_default();
+            push_diff(&mut result, &f.original_path, &old, &new);
+        }
+        return Ok(result);
+    };
+
+    let to_id = resolve_session_id(repo_root, to)?;
+    let to_manifest = read_session_manifest(repo_root, &to_id)?;
+    let to_dir = session_path(repo_root, &to_id);
+    // A path filter only has to match one side
+    let to_files = select_targets(
+        &to_manifest,
+        req.path
+            .as_deref(),
+    )
+    .unwrap_or_default();
+    result.to = to_id;
+
+    let mut to_by_path = std::collections::BTreeMap::new();
+    for f in &to_files
+    {
+        to_by_path.insert(normalize_repo_rel(&f.original_path)?, f);
+    }
+
+    for f in &from_files
+    {
+        let key = normalize_repo_rel(&f.original_path)?;
+        let Some(other) = to_by_path.remove(&key)
+        else
+        {
+            result
+                .only_in_from
+                .push(key);
+            continue;
+        };
+        let old = read_payload(
+            repo_root,
+            &payload_path(repo_root, &from_dir, f),
+            f.encrypted,
+        )
+        .with_context(|| format!("read backup payload: {}", key.display()))?;
+        let new = read_payload(
+            repo_root,
+            &payload_path(repo_root, &to_dir, other),
+            other.encrypted,
+        )
+        .with_context(|| format!("read backup payload: {}", key.display()))?;
+        push_diff(&mut result, &key, &old, &new);
+    }
+    result
+        .only_in_to
+        .extend(to_by_path.into_keys());
+
+    Ok(result)
+}
+
+/// Remove stored objects that no session manifest references.
+///
+/// Manifests of finalized and in-progress (`tmp/`) sessions both count. On
+/// Unix an object that still has other hard links is kept as well, which
+/// covers sessions that are mid-backup and have not written a manifest yet.
+pub fn gc_objects(
+    repo_root: &Path,
+    req: GcRequest,
+) -> Result<GcResult>
+{
+    let objects = objects_dir(repo_root);
+    let mut result = GcResult {
+        bytes_freed: 0,
+        errors: vec![],
+        objects_kept: 0,
+        objects_removed: vec![],
+        sessions_removed: vec![],
+    };
+
+    if req.retention
+        && let Some(cleanup) = enforce_retention(repo_root, None, req.dry_run)?
+    {
+        result.sessions_removed = cleanup.sessions_removed;
+        result
+            .errors
+            .extend(cleanup.errors);
+    }
+
+    if !objects.exists()
+    {
+        return Ok(result);
+    }
+
+    let referenced = referenced_blobs(repo_root)?;
+    sweep_objects(&objects, "", &referenced, &req, &mut result)?;
+    let sealed = objects.join(SEALED_DIR);
+    if sealed.exists()
+    {
+        sweep_objects(&sealed, &format!("{SEALED_DIR}/"), &referenced, &req, &mut result)?;
+    }
+
+    result
+        .objects_removed
+        .sort();
+    Ok(result)
+}
+
+/// Remove the unreferenced objects of one namespace of the store; objects
+/// are named `<namespace><hash>` in `referenced` and the result.
+fn sweep_objects(
+    dir: &Path,
+    namespace: &str,
+    referenced: &std::collections::HashSet<String>,
+    req: &GcRequest,
+    result: &mut GcResult,
+) -> Result<()>
+{
+    for fan in fs::read_dir(dir)?
+    {
+        let fan = fan?;
+        if !fan
+            .file_type()?
+            .is_dir()
+            || fan.file_name() == SEALED_DIR
+        {
+            continue;
+        }
+        let prefix = fan
+            .file_name()
+            .to_string_lossy()
+            .to_string();
+
+        for obj in fs::read_dir(fan.path())?
+        {
+            let obj = obj?;
+            let hash =
+                format!("{}{}{}", namespace, prefix, obj.file_name().to_string_lossy());
+            let md = obj.metadata()?;
+            if referenced.contains(&hash) || still_linked(&md)
+            {
+                result.objects_kept += 1;
+                continue;
+            }
+
+            if !req.dry_run
+                && let Err(e) = fs::remove_file(obj.path())
+            {
+                result
+                    .errors
+                    .push(format!("{}: {}", hash, e));
+                continue;
+            }
+            result.bytes_freed = result
+                .bytes_freed
+                .saturating_add(md.len());
+            result
+                .objects_removed
+                .push(hash);
+        }
+
+        if !req.dry_run
+        {
+            // Drop emptied fan-out directories; fails harmlessly otherwise
+            let _ = fs::remove_dir(fan.path());
+        }
+    }
+
+    Ok(())
+}
+
+/// Remove sessions outside the `[backup]` retention limits of `repo_root`.
+///
+/// Returns `None` when no limit is configured. Only completed sessions are
+/// considered; `protect` names a session that survives regardless. Objects
+/// freed by removed sessions are left to `gc_objects`.
+pub fn enforce_retention(
+    repo_root: &Path,
+    protect: Option<&str>,
+    dry_run: bool,
+) -> Result<Option<CleanupResult>>
+{
+    let cfg = load_config_in(repo_root)?.backup;
+    if cfg
+        .keep_latest
+        .is_none()
+        && cfg
+            .max_age
+            .is_none()
+        && cfg
+            .max_total_size
+            .is_none()
+    {
+        return Ok(None);
+    }
+
+    let max_total_size = match &cfg.max_total_size
+    {
+        Some(spec) => Some(parse_size(spec).context("invalid [backup] max_total_size")?),
+        None => None,
+    };
+    let req = CleanupRequest {
+        dry_run,
+        include_incomplete: false,
+        keep_latest: cfg.keep_latest,
+        max_total_size,
+        older_than: cfg.max_age,
+        protect: protect.map(str::to_string),
+    };
+    cleanup_sessions(repo_root, req).map(Some)
+}
+
 // ---------- helpers ----------
 
+fn session_path(
+    repo_root: &Path,
+    session_id: &str,
+) -> PathBuf
+{
+    backups_dir(repo_root).join(session_id)
+}
+
+/// Record a diff for `path` if the two versions differ.
+fn push_diff(
+    result: &mut DiffResult,
+    path: &Path,
+    old: &[u8],
+    new: &[u8],
+)
+{
+    if old == new
+    {
+        result.unchanged += 1;
+        return;
+    }
+    result
+        .diffs
+        .push(diff_bytes(path, old, new));
+}
+
+/// Unified diff of two byte buffers, or a placeholder when either is binary.
+fn diff_bytes(
+    path: &Path,
+    old: &[u8],
+    new: &[u8],
+) -> FileDiff
+{
+    let unified = match (std::str::from_utf8(old), std::str::from_utf8(new))
+    {
+        (Ok(a), Ok(b)) => unified_diff(a, b, path),
+        _ => String::from("[binary files differ; no text diff]"),
+    };
+    FileDiff { path: path.to_path_buf(), unified }
+}
+
+/// Collect blob hashes referenced by any session manifest on disk, sealed
+/// ones as `sealed/<hash>`.
+fn referenced_blobs(repo_root: &Path) -> Result<std::collections::HashSet<String>>
+{
+    let base = backups_dir(repo_root);
+    let mut dirs = Vec::<PathBuf>::new();
+    for root in [base.clone(), base.join("tmp")]
+    {
+        if !root.exists()
+        {
+            continue;
+        }
+        for ent in fs::read_dir(&root)?
+        {
+            let ent = ent?;
+            let name = ent.file_name();
+            if ent
+                .file_type()?
+                .is_dir()
+                && name != "tmp"
+                && name != OBJECTS_DIR
+            {
+                dirs.push(ent.path());
+            }
+        }
+    }
+
+    let mut out = std::collections::HashSet::new();
+    for dir in dirs
+    {
+        let m = dir.join("manifest.json");
+        let Ok(s) = fs::read_to_string(&m)
+        else
+        {
+            continue;
+        };
+        // An unreadable manifest must not let gc drop what it points at
+        let man: SessionManifest = serde_json::from_str(&s)
+            .with_context(|| format!("parse manifest: {}", m.display()))?;
+        out.extend(
+            man.files
+                .into_iter()
+                .filter_map(|f| {
+                    let blob = f.blob?;
+                    Some(if f.encrypted { format!("{SEALED_DIR}/{blob}") } else { blob })
+                }),
+        );
+    }
+    Ok(out)
+}
+
+#[cfg(unix)]
+fn still_linked(md: &fs::Metadata) -> bool
+{
+    use std::os::unix::fs::MetadataExt;
+    md.nlink() > 1
+}
+
+#[cfg(not(unix))]
+fn still_linked(_md: &fs::Metadata) -> bool
+{
+    false
+}
+
 /// Select target files from a session manifest, optionally filtering by a repo-relative
 /// path. Returns a vector of matching FileBackupMeta entries.
 /// If a filter is provided, only files matching the normalized path are returned.
@@ -987,38 +1499,15 @@ fn build_diffs(
     let mut out = Vec::new();
     for t in targets
     {
-        // Fix #10: Handle binary files by checking UTF-8 validity
+        // Fix #10: Binary files get a placeholder instead of a text diff
         let cur_bytes = fs::read(repo_root.join(&t.original_path)).unwrap_or_default();
-        let bak_bytes = fs::read(session_dir.join(&t.rel_path)).unwrap_or_default();
-
-        let (cur, bak) = match (
-            std::str::from_utf8(&cur_bytes),
-            std::str::from_utf8(&bak_bytes),
+        let bak_bytes = read_payload(
+            repo_root,
+            &payload_path(repo_root, session_dir, t),
+            t.encrypted,
         )
-        {
-            (Ok(c), Ok(b)) => (c.to_owned(), b.to_owned()),
-            _ =>
-            {
-                out.push(FileDiff {
-                    path: t
-                        .original_path
-                        .clone(),
-                    unified: String::from("[binary files differ; no text diff]"),
-                });
-                continue;
-            }
-        };
-
-        // Generate unified diff between current and backup contents.
-        let diff = unified_diff(&cur, &bak, &t.original_path);
-
-        // Collect the diff result.
-        out.push(FileDiff {
-            path: t
-                .original_path
-                .clone(),
-            unified: diff,
-        });
+        .unwrap_or_default();
+        out.push(diff_bytes(&t.original_path, &cur_bytes, &bak_bytes));
     }
 
     Ok(out)
@@ -1110,6 +1599,30 @@ fn parse_time_bound(spec: &str) -> Result<DateTime<Utc>>
     Ok(dt)
 }
 
+/// Parse a byte count: plain bytes or a K/M/G/T suffix (powers of 1024),
+/// optionally followed by `B` or `iB`
+pub fn parse_size(spec: &str) -> Result<u64>
+{
+    let spec = spec.trim();
+    let upper = spec.to_ascii_uppercase();
+    let digits = upper.trim_end_matches(|c: char| c.is_ascii_alphabetic());
+    let shift = match upper[digits.len()..].trim_end_matches("IB").trim_end_matches('B')
+    {
+        "" => 0,
+        "K" => 10,
+        "M" => 20,
+        "G" => 30,
+        "T" => 40,
+        _ => bail!("unsupported size unit (use K, M, G, T): {spec}"),
+    };
+    let n: u64 = digits
+        .trim()
+        .parse()
+        .with_context(|| format!("invalid size: {spec}"))?;
+    n.checked_mul(1u64 << shift)
+        .with_context(|| format!("size out of range: {spec}"))
+}
+
 fn dir_size(path: &Path) -> Result<u64>
 {
     fn walk(
@@ -1137,19 +1650,27 @@ fn dir_size(path: &Path) -> Result<u64>
     Ok(total)
 }
 
+/// Rewrite the index from the manifests on disk, under the index lock so
+/// a concurrent append is not lost.
 fn rebuild_index(repo_root: &Path) -> Result<()>
 {
-    let base = repo_root
-        .join(".rup")
-        .join("backups");
-    let index = base.join("index.jsonl");
-    if !base.exists()
+    if !backups_dir(repo_root).exists()
     {
         return Ok(());
     }
+    with_index_lock(repo_root, |base| {
+        let index = base.join("index.jsonl");
+        write_index(base, &index)
+    })
+}
 
+fn write_index(
+    base: &Path,
+    index: &Path,
+) -> Result<()>
+{
     let mut lines = Vec::<String>::new();
-    for ent in fs::read_dir(&base)?
+    for ent in fs::read_dir(base)?
     {
         let ent = ent?;
         if !ent
@@ -1158,10 +1679,8 @@ fn rebuild_index(repo_root: &Path) -> Result<()>
         {
             continue;
         }
-        if ent
-            .file_name()
-            .to_string_lossy()
-            == "tmp"
+        let name = ent.file_name();
+        if name == "tmp" || name == OBJECTS_DIR
         {
             continue;
         }
@@ -1187,6 +1706,7 @@ fn rebuild_index(repo_root: &Path) -> Result<()>
             engine: man
                 .engine
                 .clone(),
+            label: man.label,
         };
         lines.push(serde_json::to_string(&rec)?);
     }
@@ -1200,8 +1720,8 @@ fn rebuild_index(repo_root: &Path) -> Result<()>
         }
         let _ = f.sync_all();
     }
-    fs::rename(&tmp, &index)?;
-    if let Ok(d) = fs::File::open(&base)
+    fs::rename(&tmp, index)?;
+    if let Ok(d) = fs::File::open(base)
     {
         let _ = d.sync_all();
     }
@@ -1507,34 +2027,6 @@ fn trim(
     ops
 }
 
-// ----- streaming blake3 -----
-
-fn stream_blake3(path: &Path) -> Result<String>
-{
-    use blake3::Hasher as Blake3;
-    let mut f = std::fs::File::open(path)?;
-    let mut h = Blake3::new();
-    let mut buf = [0u8; 64 * 1024];
-
-    loop
-    {
-        let n = f.read(&mut buf)?;
-
-        if n == 0
-        {
-            break;
-        }
-
-        h.update(&buf[..n]);
-    }
-
-    Ok(format!(
-        "blake3:{}",
-        h.finalize()
-            .to_hex()
-    ))
-}
-
 #[cfg(test)]
 mod tests
 {
@@ -1577,6 +2069,68 @@ mod tests
         assert!(parse_time_bound("").is_err());
     }
 
+    #[test]
+    fn test_parse_size()
+    {
+        assert_eq!(parse_size("1024").unwrap(), 1024);
+        assert_eq!(parse_size("4K").unwrap(), 4096);
+        assert_eq!(parse_size("500MB").unwrap(), 500 << 20);
+        assert_eq!(parse_size("2GiB").unwrap(), 2 << 30);
+        assert!(parse_size("12X").is_err());
+        assert!(parse_size("M").is_err());
+    }
+
+    #[test]
+    fn test_gc_removes_only_unreferenced_objects()
+    {
+        let tmp = tempfile::TempDir::new().unwrap();
+        let repo = tmp.path();
+        fs::write(repo.join("a.txt"), "keep me").unwrap();
+
+        let mut mgr = BackupManager::begin(repo, "internal").unwrap();
+        mgr.backup_file(Path::new("a.txt"))
+            .unwrap();
+        mgr.finalize(true)
+            .unwrap();
+
+        // An orphan left behind by a deleted session
+        let orphan = crate::core::backup::object_path(repo, "ffff0000", false);
+        fs::create_dir_all(
+            orphan
+                .parent()
+                .unwrap(),
+        )
+        .unwrap();
+        fs::write(&orphan, "stale").unwrap();
+
+        let dry = gc_objects(repo, GcRequest { dry_run: true, retention: false }).unwrap();
+        assert_eq!(dry.objects_removed, vec!["ffff0000".to_string()]);
+        assert!(orphan.exists());
+
+        let res = gc_objects(repo, GcRequest { dry_run: false, retention: false }).unwrap();
+        assert_eq!(res.objects_removed, vec!["ffff0000".to_string()]);
+        assert_eq!(res.objects_kept, 1);
+        assert_eq!(res.bytes_freed, 5);
+        assert!(!orphan.exists());
+
+        // The surviving object still backs a restore
+        fs::write(repo.join("a.txt"), "changed").unwrap();
+        restore_session(
+            repo,
+            RestoreRequest {
+                session_id: "latest".into(),
+                path: None,
+                dry_run: false,
+                force: true,
+                show_diff: false,
+                verify_checksum: true,
+                backup_current: false,
+            },
+        )
+        .unwrap();
+        assert_eq!(fs::read_to_string(repo.join("a.txt")).unwrap(), "keep me");
+    }
+
     #[test]
     fn test_session_id_resolution()
     {
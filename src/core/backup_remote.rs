@@ -0,0 +1,213 @@
+//! Off-machine mirroring of backup sessions (`[backup.remote]`)
+//!
+//! When a remote is configured, every finalized session is copied to it in
+//! a detached child process so `rup apply` never waits on the network. The
+//! upload shells out to `rsync` (local paths or `host:path` over SSH) or
+//! the `aws` CLI (any S3-compatible endpoint), so credentials and transport
+//! settings come from the usual places for those tools. Output of
+//! background uploads is appended to `.rup/backups/remote.log`.
+//!
+//! CI jobs in short-lived containers should run `rup backup push` as a
+//! final step, which uploads synchronously and fails loudly.
+
+use std::{
+    fs::OpenOptions,
+    path::{Path, PathBuf},
+    process::{Command, Stdio},
+};
+
+use anyhow::{Context, Result, bail};
+
+use crate::{
+    core::backup::{backups_dir, read_session_manifest},
+    infra::config::{RemoteConfig, load_config_in},
+};
+
+/// Log file for background uploads, under `.rup/backups`
+pub const REMOTE_LOG: &str = "remote.log";
+
+/// The remote configured for `repo_root`, if any
+pub fn configured_remote(repo_root: &Path) -> Result<Option<RemoteConfig>>
+{
+    Ok(load_config_in(repo_root)?
+        .backup
+        .remote)
+}
+
+/// Command that copies `session_dir` to `<remote>/<session_id>/`
+pub fn mirror_command(
+    remote: &RemoteConfig,
+    session_dir: &Path,
+    session_id: &str,
+) -> Command
+{
+    match remote
+    {
+        RemoteConfig::Rsync { target } =>
+        {
+            let mut cmd = Command::new("rsync");
+            // Trailing slash: copy the directory's contents, not the directory
+            let mut src = session_dir
+                .as_os_str()
+                .to_owned();
+            src.push("/");
+            cmd.arg("-a")
+                .arg(src)
+                .arg(format!("{}/{}/", target.trim_end_matches('/'), session_id));
+            cmd
+        }
+        RemoteConfig::S3 { url, endpoint } =>
+        {
+            let mut cmd = Command::new("aws");
+            cmd.arg("s3")
+                .arg("sync")
+                .arg("--only-show-errors")
+                .arg(session_dir)
+                .arg(format!("{}/{}/", url.trim_end_matches('/'), session_id));
+            if let Some(endpoint) = endpoint
+            {
+                cmd.arg("--endpoint-url")
+                    .arg(endpoint);
+            }
+            cmd
+        }
+    }
+}
+
+fn session_dir(
+    repo_root: &Path,
+    session_id: &str,
+) -> PathBuf
+{
+    backups_dir(repo_root).join(session_id)
+}
+
+/// Upload a finalized session and wait for the transfer to finish
+pub fn push_session(
+    repo_root: &Path,
+    remote: &RemoteConfig,
+    session_id: &str,
+) -> Result<()>
+{
+    // Only complete sessions are worth mirroring
+    read_session_manifest(repo_root, session_id)?;
+
+    let mut cmd = mirror_command(remote, &session_dir(repo_root, session_id), session_id);
+    let out = cmd
+        .output()
+        .with_context(|| format!("failed to run {:?}", cmd.get_program()))?;
+    if !out
+        .status
+        .success()
+    {
+        bail!(
+            "remote backup of {} failed: {}",
+            session_id,
+            String::from_utf8_lossy(&out.stderr).trim()
+        );
+    }
+    Ok(())
+}
+
+/// Start a background upload of `session_id` if a remote is configured.
+///
+/// Returns whether an upload was started. The child is not waited on; it
+/// outlives this process if needed.
+pub fn spawn_mirror(
+    repo_root: &Path,
+    session_id: &str,
+) -> Result<bool>
+{
+    let Some(remote) = configured_remote(repo_root)?
+    else
+    {
+        return Ok(false);
+    };
+
+    let dir = session_dir(repo_root, session_id);
+    let log_path = backups_dir(repo_root).join(REMOTE_LOG);
+    let log = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(&log_path)
+        .with_context(|| format!("open {}", log_path.display()))?;
+
+    let mut cmd = mirror_command(&remote, &dir, session_id);
+    cmd.stdin(Stdio::null())
+        .stdout(log.try_clone()?)
+        .stderr(log);
+    #[cfg(unix)]
+    {
+        // Own process group, so Ctrl-C on the parent doesn't cut the upload
+        use std::os::unix::process::CommandExt;
+        cmd.process_group(0);
+    }
+    cmd.spawn()
+        .with_context(|| format!("failed to start {:?}", cmd.get_program()))?;
+    Ok(true)
+}
+
+#[cfg(test)]
+mod tests
+{
+    use super::*;
+
+    fn args(cmd: &Command) -> Vec<String>
+    {
+        cmd.get_args()
+            .map(|a| {
+                a.to_string_lossy()
+                    .into_owned()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_rsync_and_s3_commands()
+    {
+        let dir = Path::new("/repo/.rup/backups/S1");
+
+        let rsync = RemoteConfig::Rsync { target: "bk@host:/srv/rup/".into() };
+        let cmd = mirror_command(&rsync, dir, "S1");
+        assert_eq!(cmd.get_program(), "rsync");
+        assert_eq!(args(&cmd), vec![
+            "-a",
+            "/repo/.rup/backups/S1/",
+            "bk@host:/srv/rup/S1/"
+        ]);
+
+        let s3 = RemoteConfig::S3 {
+            url: "s3://bucket/rup".into(),
+            endpoint: Some("http://minio:9000".into()),
+        };
+        let cmd = mirror_command(&s3, dir, "S1");
+        assert_eq!(cmd.get_program(), "aws");
+        assert_eq!(args(&cmd), vec![
+            "s3",
+            "sync",
+            "--only-show-errors",
+            "/repo/.rup/backups/S1",
+            "s3://bucket/rup/S1/",
+            "--endpoint-url",
+            "http://minio:9000",
+        ]);
+    }
+
+    #[test]
+    fn test_remote_read_from_repo_config()
+    {
+        let tmp = tempfile::TempDir::new().unwrap();
+        assert_eq!(configured_remote(tmp.path()).unwrap(), None);
+
+        std::fs::write(
+            tmp.path()
+                .join("roughup.toml"),
+            "[backup.remote]\nkind = \"rsync\"\ntarget = \"/mnt/backups\"\n",
+        )
+        .unwrap();
+        assert_eq!(
+            configured_remote(tmp.path()).unwrap(),
+            Some(RemoteConfig::Rsync { target: "/mnt/backups".into() })
+        );
+    }
+}
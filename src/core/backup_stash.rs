@@ -0,0 +1,368 @@
+//! Bridge between backup sessions and `git stash`
+//!
+//! `rup backup to-stash` records a session's file versions as a stash entry
+//! on top of HEAD, so `git stash apply` puts them back in the worktree and
+//! the entry travels with normal git tooling. The stash is built the way
+//! `git stash create` builds one: a worktree commit whose parents are HEAD
+//! and an index commit (here identical to HEAD), registered with
+//! `git stash store`.
+//!
+//! `rup backup from-stash` goes the other way: every file a stash entry adds
+//! or modifies, including its untracked-files commit, becomes part of a new
+//! session. Deleted files have no content to keep and are skipped.
+
+use std::{
+    collections::HashMap,
+    io::Write,
+    path::Path,
+    process::{Command, Stdio},
+};
+
+use anyhow::{Context, Result, bail};
+use serde::Serialize;
+
+use crate::core::{
+    BackupManager,
+    backup::{backups_dir, payload_path, read_session_manifest},
+    backup_crypt::read_payload,
+    backup_ops::resolve_session_id,
+};
+
+/// Result of converting a session to a stash entry.
+#[derive(Debug, Serialize)]
+pub struct ToStashResult
+{
+    /// The converted session ID
+    pub session_id: String,
+
+    /// Commit of the new stash entry
+    pub stash_commit: String,
+
+    /// Stash reflog message
+    pub message: String,
+
+    /// Number of files recorded in the stash
+    pub files: usize,
+}
+
+/// Result of converting a stash entry to a session.
+#[derive(Debug, Serialize)]
+pub struct FromStashResult
+{
+    /// The new session ID
+    pub session_id: String,
+
+    /// Commit of the source stash entry
+    pub stash_commit: String,
+
+    /// Number of files backed up
+    pub files: usize,
+}
+
+/// Store `session` (ID, label, or alias) as a new `refs/stash` entry.
+pub fn session_to_stash(
+    repo_root: &Path,
+    session: &str,
+    message: Option<&str>,
+) -> Result<ToStashResult>
+{
+    let session_id = resolve_session_id(repo_root, session)?;
+    let manifest = read_session_manifest(repo_root, &session_id)?;
+    let session_dir = backups_dir(repo_root).join(&session_id);
+
+    let head = git(
+        repo_root,
+        &["rev-parse", "--verify", "-q", "HEAD^{commit}"],
+        &[],
+    )
+    .context("to-stash needs a commit to stash on top of")?;
+    let head_tree = git(repo_root, &["rev-parse", "HEAD^{tree}"], &[])?;
+
+    // Build the worktree tree in a scratch index so the user's index is untouched
+    let scratch = tempfile::tempdir().context("create scratch index dir")?;
+    let index = scratch
+        .path()
+        .join("index");
+    let env = [("GIT_INDEX_FILE", index.as_path())];
+    git(repo_root, &["read-tree", "HEAD"], &env)?;
+    let modes = index_modes(repo_root, &env)?;
+
+    for meta in &manifest.files
+    {
+        let bytes = read_payload(
+            repo_root,
+            &payload_path(repo_root, &session_dir, meta),
+            meta.encrypted,
+        )?;
+        let blob = git_with_input(repo_root, &["hash-object", "-w", "--stdin"], &bytes)?;
+        let path = git_path(&meta.rel_path);
+        let mode = match modes.get(&path)
+        {
+            Some(m) if m == "100755" => "100755",
+            _ => "100644",
+        };
+        git(
+            repo_root,
+            &["update-index", "--add", "--cacheinfo", &format!("{mode},{blob},{path}")],
+            &env,
+        )?;
+    }
+
+    let tree = git(repo_root, &["write-tree"], &env)?;
+    if tree == head_tree
+    {
+        bail!("session {} matches HEAD; nothing to stash", session_id);
+    }
+
+    let branch = git(repo_root, &["rev-parse", "--abbrev-ref", "HEAD"], &[])
+        .ok()
+        .filter(|b| b != "HEAD")
+        .unwrap_or_else(|| "(no branch)".into());
+    let head_line = git(repo_root, &["log", "-1", "--format=%h %s", "HEAD"], &[])?;
+    let message = format!(
+        "On {}: {}",
+        branch,
+        message
+            .map(str::to_string)
+            .unwrap_or_else(|| {
+                match &manifest.label
+                {
+                    Some(label) => format!("rup backup {session_id} ({label})"),
+                    None => format!("rup backup {session_id}"),
+                }
+            })
+    );
+
+    let index_commit = git(
+        repo_root,
+        &["commit-tree", &head_tree, "-p", &head, "-m", &format!("index on {branch}: {head_line}")],
+        &[],
+    )?;
+    let stash_commit = git(
+        repo_root,
+        &["commit-tree", &tree, "-p", &head, "-p", &index_commit, "-m", &message],
+        &[],
+    )?;
+    git(
+        repo_root,
+        &["stash", "store", "-m", &message, &stash_commit],
+        &[],
+    )?;
+
+    Ok(ToStashResult {
+        session_id,
+        stash_commit,
+        message,
+        files: manifest
+            .files
+            .len(),
+    })
+}
+
+/// Back up the files `stash` adds or modifies into a new session.
+pub fn stash_to_session(
+    repo_root: &Path,
+    stash: &str,
+    label: Option<String>,
+) -> Result<FromStashResult>
+{
+    let stash_commit = git(
+        repo_root,
+        &["rev-parse", "--verify", "-q", &format!("{stash}^{{commit}}")],
+        &[],
+    )
+    .with_context(|| format!("no such stash: {stash}"))?;
+    if git(
+        repo_root,
+        &["rev-parse", "--verify", "-q", &format!("{stash_commit}^2")],
+        &[],
+    )
+    .is_err()
+    {
+        bail!("{} is not a stash entry (expected a second parent)", stash);
+    }
+
+    // (revision holding the content, path)
+    let mut files: Vec<(String, String)> = Vec::new();
+    let changed = git(
+        repo_root,
+        &[
+            "diff-tree",
+            "-r",
+            "-z",
+            "--no-renames",
+            "--name-status",
+            &format!("{stash_commit}^1"),
+            &stash_commit,
+        ],
+        &[],
+    )?;
+    let mut fields = changed
+        .split('\0')
+        .filter(|f| !f.is_empty());
+    while let (Some(status), Some(path)) = (fields.next(), fields.next())
+    {
+        if status != "D"
+        {
+            files.push((stash_commit.clone(), path.to_string()));
+        }
+    }
+
+    // `git stash -u` keeps untracked files in a third parent
+    let untracked = format!("{stash_commit}^3");
+    if git(repo_root, &["rev-parse", "--verify", "-q", &untracked], &[]).is_ok()
+    {
+        let listed = git(
+            repo_root,
+            &["ls-tree", "-r", "-z", "--name-only", &untracked],
+            &[],
+        )?;
+        files.extend(
+            listed
+                .split('\0')
+                .filter(|p| !p.is_empty())
+                .map(|p| (untracked.clone(), p.to_string())),
+        );
+    }
+
+    if files.is_empty()
+    {
+        bail!("{} adds or modifies no files", stash);
+    }
+
+    let subject = git(repo_root, &["log", "-1", "--format=%s", &stash_commit], &[])?;
+    let mut mgr = BackupManager::begin(repo_root, "git")?;
+    mgr.set_operation("from-stash");
+    mgr.annotate(label, Some(format!("{stash}: {subject}")));
+    for (rev, path) in &files
+    {
+        let bytes = git_bytes(repo_root, &["cat-file", "blob", &format!("{rev}:{path}")])?;
+        mgr.backup_contents(Path::new(path), &bytes)?;
+    }
+    mgr.finalize(true)?;
+
+    Ok(FromStashResult {
+        session_id: mgr
+            .session_id()
+            .to_string(),
+        stash_commit,
+        files: mgr.file_count(),
+    })
+}
+
+/// File modes recorded in the index named by `env`, keyed by git path
+fn index_modes(
+    repo_root: &Path,
+    env: &[(&str, &Path)],
+) -> Result<HashMap<String, String>>
+{
+    let staged = git(repo_root, &["ls-files", "--stage", "-z"], env)?;
+    Ok(staged
+        .split('\0')
+        .filter_map(|line| {
+            // <mode> <object> <stage>\t<path>
+            let (info, path) = line.split_once('\t')?;
+            let mode = info
+                .split(' ')
+                .next()?;
+            Some((path.to_string(), mode.to_string()))
+        })
+        .collect())
+}
+
+/// Repo-relative path in git's `/`-separated form
+fn git_path(rel: &Path) -> String
+{
+    rel.components()
+        .map(|c| {
+            c.as_os_str()
+                .to_string_lossy()
+                .into_owned()
+        })
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+/// Run git and return its trimmed stdout
+fn git(
+    repo_root: &Path,
+    args: &[&str],
+    env: &[(&str, &Path)],
+) -> Result<String>
+{
+    let mut cmd = Command::new("git");
+    cmd.args(args)
+        .current_dir(repo_root);
+    for (key, val) in env
+    {
+        cmd.env(key, val);
+    }
+    let out = cmd
+        .output()
+        .with_context(|| format!("run git {}", args[0]))?;
+    check(args, &out)?;
+    Ok(String::from_utf8_lossy(&out.stdout)
+        .trim_end()
+        .to_string())
+}
+
+/// Run git and return its raw stdout
+fn git_bytes(
+    repo_root: &Path,
+    args: &[&str],
+) -> Result<Vec<u8>>
+{
+    let out = Command::new("git")
+        .args(args)
+        .current_dir(repo_root)
+        .output()
+        .with_context(|| format!("run git {}", args[0]))?;
+    check(args, &out)?;
+    Ok(out.stdout)
+}
+
+/// Run git with `input` on stdin and return its trimmed stdout
+fn git_with_input(
+    repo_root: &Path,
+    args: &[&str],
+    input: &[u8],
+) -> Result<String>
+{
+    let mut child = Command::new("git")
+        .args(args)
+        .current_dir(repo_root)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .with_context(|| format!("run git {}", args[0]))?;
+    child
+        .stdin
+        .take()
+        .context("git stdin")?
+        .write_all(input)
+        .with_context(|| format!("write to git {}", args[0]))?;
+    let out = child.wait_with_output()?;
+    check(args, &out)?;
+    Ok(String::from_utf8_lossy(&out.stdout)
+        .trim_end()
+        .to_string())
+}
+
+fn check(
+    args: &[&str],
+    out: &std::process::Output,
+) -> Result<()>
+{
+    if !out
+        .status
+        .success()
+    {
+        bail!(
+            "git {} failed: {}",
+            args.join(" "),
+            String::from_utf8_lossy(&out.stderr).trim()
+        );
+    }
+    Ok(())
+}
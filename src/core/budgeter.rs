@@ -1,11 +1,20 @@
 //! Filepath: src/core/budgeter.rs
 
-use std::collections::{HashMap, HashSet};
+use std::{
+    collections::{HashMap, HashSet},
+    fs,
+    io::Write,
+    path::{Path, PathBuf},
+    sync::atomic::{AtomicBool, Ordering},
+};
 
 use anyhow::{Context, Result, anyhow};
 use moka::sync::Cache;
-use tiktoken_rs::{CoreBPE, cl100k_base, get_bpe_from_model, o200k_base};
-use xxhash_rust::xxh64::Xxh64;
+use serde::Serialize;
+use tiktoken_rs::{cl100k_base, get_bpe_from_model, o200k_base};
+use xxhash_rust::xxh64::{Xxh64, xxh64};
+
+use crate::core::tokenizer::{HfTokenizer, Tokenizer};
 
 /// Enhanced priority system with fine-grained scoring
 #[derive(Debug, Clone, Copy, PartialEq)]
@@ -160,23 +169,288 @@ pub struct FittedItem
     pub tokens: usize,
 }
 
-/// Budget manager backed by tiktoken-rs with token caching
+/// What became of one candidate in a fit
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum BudgetDecision
+{
+    Kept,
+    Trimmed,
+    Dropped,
+}
+
+/// Why a candidate was trimmed or dropped
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum BudgetReason
+{
+    /// The overall budget ran out
+    Budget,
+    /// Its bucket's cap ran out
+    BucketCap,
+    /// Below the novelty floor
+    Novelty,
+    /// Near-duplicate of a better candidate
+    Dedupe,
+}
+
+impl BudgetReason
+{
+    pub fn label(self) -> &'static str
+    {
+        match self
+        {
+            BudgetReason::Budget => "budget",
+            BudgetReason::BucketCap => "bucket-cap",
+            BudgetReason::Novelty => "novelty",
+            BudgetReason::Dedupe => "dedupe",
+        }
+    }
+}
+
+/// One candidate of a fit and what became of it
+#[derive(Debug, Clone, Serialize)]
+pub struct BudgetEntry
+{
+    pub id: String,
+
+    /// Tokens of the whole candidate
+    pub tokens: usize,
+
+    /// Tokens it takes in the output; 0 when dropped
+    pub kept_tokens: usize,
+
+    /// Priority level, 0-255
+    pub priority: u8,
+
+    pub decision: BudgetDecision,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reason: Option<BudgetReason>,
+
+    /// Bucket of a bucketed fit: code, interfaces, or tests
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub bucket: Option<&'static str>,
+}
+
+/// Every candidate of a fit, in priority order, for `--explain-budget`
+#[derive(Debug, Clone, Serialize)]
+pub struct BudgetReport
+{
+    pub budget: usize,
+    pub total_tokens: usize,
+    pub entries: Vec<BudgetEntry>,
+}
+
+impl BudgetReport
+{
+    /// Entries for `candidates` (id, content, priority, bucket) given what
+    /// `fit` kept; a trimmed item is put down to `trimmed`, a dropped one to
+    /// `dropped(id)`
+    fn build<'a>(
+        budgeter: &Budgeter,
+        budget: usize,
+        fit: &FitResult,
+        candidates: impl Iterator<Item = (&'a str, &'a str, Priority, Option<&'static str>)>,
+        trimmed: BudgetReason,
+        dropped: impl Fn(&str) -> BudgetReason,
+    ) -> Self
+    {
+        let fitted: HashMap<&str, &FittedItem> = fit
+            .items
+            .iter()
+            .map(|fi| (fi.id.as_str(), fi))
+            .collect();
+        let entries = candidates
+            .map(|(id, content, priority, bucket)| {
+                let (decision, reason, kept_tokens) = match fitted.get(id)
+                {
+                    Some(fi) if fi.content == fi.full_content =>
+                    {
+                        (BudgetDecision::Kept, None, fi.tokens)
+                    }
+                    Some(fi) => (BudgetDecision::Trimmed, Some(trimmed), fi.tokens),
+                    None => (BudgetDecision::Dropped, Some(dropped(id)), 0),
+                };
+                BudgetEntry {
+                    id: id.to_string(),
+                    tokens: budgeter.count(content),
+                    kept_tokens,
+                    priority: priority.level,
+                    decision,
+                    reason,
+                    bucket,
+                }
+            })
+            .collect();
+        Self { budget, total_tokens: fit.total_tokens, entries }
+    }
+
+    /// Print to stderr in the `--explain-budget` format
+    pub fn print(
+        &self,
+        format: crate::cli::ExplainBudgetFormat,
+    ) -> Result<()>
+    {
+        match format
+        {
+            crate::cli::ExplainBudgetFormat::Text => eprint!("{}", self.render()),
+            crate::cli::ExplainBudgetFormat::Json => eprintln!("{}", serde_json::to_string(self)?),
+        }
+        Ok(())
+    }
+
+    /// One summary line, then one line per candidate
+    pub fn render(&self) -> String
+    {
+        let count = |d: BudgetDecision| {
+            self.entries
+                .iter()
+                .filter(|e| e.decision == d)
+                .count()
+        };
+        let mut out = format!(
+            "Budget: {} of {} tokens; {} kept, {} trimmed, {} dropped\n",
+            self.total_tokens,
+            self.budget,
+            count(BudgetDecision::Kept),
+            count(BudgetDecision::Trimmed),
+            count(BudgetDecision::Dropped),
+        );
+        for e in &self.entries
+        {
+            let decision = match e.decision
+            {
+                BudgetDecision::Kept => "kept",
+                BudgetDecision::Trimmed => "trimmed",
+                BudgetDecision::Dropped => "dropped",
+            };
+            let tokens = if e.decision == BudgetDecision::Kept
+            {
+                e.tokens
+                    .to_string()
+            }
+            else
+            {
+                format!("{}/{}", e.kept_tokens, e.tokens)
+            };
+            let why = match (e.reason, e.bucket)
+            {
+                (Some(r), Some(b)) => format!("  ({}, {b})", r.label()),
+                (Some(r), None) => format!("  ({})", r.label()),
+                (None, _) => String::new(),
+            };
+            out.push_str(&format!(
+                "  {decision:<8} {tokens:>11}  p{:<3}  {}{why}\n",
+                e.priority, e.id
+            ));
+        }
+        out
+    }
+}
+
+/// Claude tokens per 100 `cl100k_base` tokens. Anthropic publishes no
+/// tokenizer for Claude 3 and later; its counts run 10-20% above cl100k on
+/// English and code, so this errs high to keep budgets from overflowing
+pub const CLAUDE_TOKENS_PER_100_CL100K: usize = 120;
+
+/// Budget manager backed by a [`Tokenizer`] with token caching
 pub struct Budgeter
 {
-    /// Byte Pair Encoding (BPE) tokenizer for counting tokens
-    bpe: CoreBPE,
+    /// Tokenizer for counting and trimming
+    tokenizer: Box<dyn Tokenizer>,
 
-    /// Token count cache for fast repeated queries
+    /// Model tokens per 100 tokenizer tokens: 100 when the tokenizer is the
+    /// model's own, more for models counted by approximation
+    per_100: usize,
+
+    /// Fewest bytes a tokenizer token covers in source code, for estimates
+    bytes_per_token: f64,
+
+    /// Estimate counts from byte length while fitting (`--approx-tokens`)
+    approx: bool,
+
+    /// Tokenizer token counts by content hash, before scaling
     cache: Cache<u64, usize>,
+
+    /// Name of the encoding counted with, keying the on-disk cache; `None`
+    /// for tokenizers passed in as is, which are never persisted
+    encoding: Option<String>,
+
+    /// File the cache is loaded from and saved to, if persisted
+    cache_file: Option<PathBuf>,
+
+    /// Whether counts were added since the cache was loaded
+    cache_dirty: AtomicBool,
 }
 
+/// Bytes per token the estimates divide by, a little under what each
+/// encoding reaches on its least compressible source files so that
+/// estimates run high: cl100k and o200k reach 3.75, p50k 2.86, and r50k,
+/// which does not merge indentation, 1.76
+fn calibrated_bytes_per_token(model_or_encoding: &str) -> f64
+{
+    use tiktoken_rs::tokenizer::{Tokenizer as Encoding, get_tokenizer};
+
+    match get_tokenizer(model_or_encoding)
+    {
+        Some(Encoding::P50kBase | Encoding::P50kEdit) => 2.75,
+        Some(Encoding::R50kBase | Encoding::Gpt2) => 1.75,
+        _ => 3.5,
+    }
+}
+
+/// Name tiktoken gives the encoding of a model or encoding name
+fn encoding_name(model_or_encoding: &str) -> String
+{
+    use tiktoken_rs::tokenizer::{Tokenizer as Encoding, get_tokenizer};
+
+    match get_tokenizer(model_or_encoding)
+    {
+        Some(Encoding::O200kBase) => "o200k_base",
+        Some(Encoding::Cl100kBase) => "cl100k_base",
+        Some(Encoding::P50kBase) => "p50k_base",
+        Some(Encoding::P50kEdit) => "p50k_edit",
+        Some(Encoding::R50kBase) => "r50k_base",
+        Some(Encoding::Gpt2) => "gpt2",
+        None => model_or_encoding,
+    }
+    .to_string()
+}
+
+/// Where `extract` and `context` keep token counts between runs, relative
+/// to the repository root
+pub const TOKEN_CACHE_DIR: &str = ".rup/cache";
+
+/// [`TOKEN_CACHE_DIR`] of the repository around the current directory, or
+/// of the current directory outside one
+pub fn token_cache_dir() -> PathBuf
+{
+    let cwd = std::env::current_dir().unwrap_or_else(|_| PathBuf::from("."));
+    crate::core::edit::discover_repo_root(None, &cwd)
+        .ok()
+        .flatten()
+        .unwrap_or(cwd)
+        .join(TOKEN_CACHE_DIR)
+}
+
+/// First bytes of an on-disk token cache, bumped when the layout changes
+const CACHE_MAGIC: &[u8; 8] = b"ruptok1\n";
+
+/// Bytes per token assumed for tokenizers without a calibration
+const DEFAULT_BYTES_PER_TOKEN: f64 = 3.0;
+
+/// Marker appended to trimmed content
+const ELLIPSIS: &str = "\n…\n";
+
 impl Budgeter
 {
     /// Create a new Budgeter for a given model or encoding name.
     ///
     /// Supported values include model names (e.g., "gpt-3.5-turbo", "gpt-4") or encoding
     /// names ("cl100k_base", "o200k_base"). Falls back to encoding names if model
-    /// lookup fails.
+    /// lookup fails. Claude models ("claude-3-5-sonnet", "claude-sonnet-4") are
+    /// counted with cl100k scaled by [`CLAUDE_TOKENS_PER_100_CL100K`].
     ///
     /// # Arguments
     /// * `model_or_encoding` - Model or encoding name (case-insensitive).
@@ -187,6 +461,17 @@ impl Budgeter
     {
         let lower = model_or_encoding.to_ascii_lowercase();
 
+        if lower.starts_with("claude")
+        {
+            let bpe = cl100k_base().context("load cl100k_base")?;
+            return Ok(Self {
+                per_100: CLAUDE_TOKENS_PER_100_CL100K,
+                bytes_per_token: calibrated_bytes_per_token("cl100k_base"),
+                encoding: Some("cl100k_base".to_string()),
+                ..Self::with_tokenizer(Box::new(bpe))
+            });
+        }
+
         // Try to get BPE from model name first, fallback to encoding name.
         let bpe = match get_bpe_from_model(&lower)
         {
@@ -203,15 +488,166 @@ impl Budgeter
         };
 
         // Create Budgeter with a token count cache of 100,000 entries.
-        Ok(Self { bpe, cache: Cache::new(100_000) })
+        Ok(Self {
+            bytes_per_token: calibrated_bytes_per_token(&lower),
+            encoding: Some(encoding_name(&lower)),
+            ..Self::with_tokenizer(Box::new(bpe))
+        })
+    }
+
+    /// Budgeter counting with `tokenizer` as is
+    pub fn with_tokenizer(tokenizer: Box<dyn Tokenizer>) -> Self
+    {
+        Self {
+            tokenizer,
+            per_100: 100,
+            bytes_per_token: DEFAULT_BYTES_PER_TOKEN,
+            approx: false,
+            cache: Cache::new(100_000),
+            encoding: None,
+            cache_file: None,
+            cache_dirty: AtomicBool::new(false),
+        }
+    }
+
+    /// Estimate counts from byte length instead of tokenizing while items
+    /// are selected and trimmed; the fitted result is then counted exactly,
+    /// and trimmed further in the rare case the estimate ran low
+    pub fn approximate(mut self) -> Self
+    {
+        self.approx = true;
+        self
+    }
+
+    /// Budgeter for `--model`, or for the HuggingFace `tokenizer.json` at
+    /// `tokenizer_file` when one is given (the model then only labels it)
+    pub fn for_model(
+        model_or_encoding: &str,
+        tokenizer_file: Option<&Path>,
+    ) -> Result<Self>
+    {
+        match tokenizer_file
+        {
+            Some(path) =>
+            {
+                let bytes =
+                    fs::read(path).with_context(|| format!("read {}", path.display()))?;
+                Ok(Self {
+                    encoding: Some(format!("hf-{:016x}", xxh64(&bytes, 0))),
+                    ..Self::with_tokenizer(Box::new(HfTokenizer::from_file(path)?))
+                })
+            }
+            None => Self::new(model_or_encoding),
+        }
+    }
+
+    /// Keep exact counts in `dir` (`.rup/cache`) between runs, one file per
+    /// encoding keyed by content hash, loading what earlier runs saved.
+    /// A missing or unreadable cache file starts an empty cache. Counts are
+    /// written back by [`save_cache`](Self::save_cache).
+    pub fn with_disk_cache(
+        mut self,
+        dir: &Path,
+    ) -> Self
+    {
+        let Some(encoding) = &self.encoding
+        else
+        {
+            return self;
+        };
+        let file = dir.join(format!("tokens-{encoding}.bin"));
+        if let Ok(bytes) = fs::read(&file)
+            && let Some(records) = bytes.strip_prefix(CACHE_MAGIC)
+        {
+            for record in records.chunks_exact(12)
+            {
+                let (key, count) = record.split_at(8);
+                self.cache
+                    .insert(
+                        u64::from_le_bytes(key.try_into().unwrap()),
+                        u32::from_le_bytes(count.try_into().unwrap()) as usize,
+                    );
+            }
+        }
+        self.cache_file = Some(file);
+        self
+    }
+
+    /// Write the cache to the file given to
+    /// [`with_disk_cache`](Self::with_disk_cache) if counts were added since
+    /// it was loaded; a no-op otherwise. Each run writes its own temporary
+    /// file and renames it into place, so concurrent runs never interleave.
+    pub fn save_cache(&self) -> Result<()>
+    {
+        let Some(file) = &self.cache_file
+        else
+        {
+            return Ok(());
+        };
+        if !self
+            .cache_dirty
+            .swap(false, Ordering::Relaxed)
+        {
+            return Ok(());
+        }
+        let dir = file
+            .parent()
+            .unwrap_or(Path::new("."));
+        fs::create_dir_all(dir).with_context(|| format!("create {}", dir.display()))?;
+
+        let mut bytes = CACHE_MAGIC.to_vec();
+        for (key, count) in &self.cache
+        {
+            bytes.extend_from_slice(&key.to_le_bytes());
+            bytes.extend_from_slice(&(count as u32).to_le_bytes());
+        }
+        let mut tmp = tempfile::NamedTempFile::new_in(dir)
+            .with_context(|| format!("create temp file in {}", dir.display()))?;
+        tmp.write_all(&bytes)
+            .with_context(|| format!("write {}", file.display()))?;
+        tmp.persist(file)
+            .with_context(|| format!("write {}", file.display()))?;
+        Ok(())
+    }
+
+    /// Model tokens for `n` BPE tokens, rounded up
+    fn scaled(
+        &self,
+        n: usize,
+    ) -> usize
+    {
+        (n * self.per_100).div_ceil(100)
     }
 
     /// Count the number of tokens in the given string, using cache for efficiency.
-    /// Uses xxhash64 to hash the string as cache key.
+    /// Uses xxhash64 to hash the string as cache key. Estimated when
+    /// [`approximate`](Self::approximate) is set.
     pub fn count(
         &self,
         s: &str,
     ) -> usize
+    {
+        if self.approx
+        {
+            return self.scaled(self.estimate(s));
+        }
+        self.exact_count(s)
+    }
+
+    /// Tokenizer tokens `s` would take at the calibrated bytes per token
+    fn estimate(
+        &self,
+        s: &str,
+    ) -> usize
+    {
+        (s.len() as f64 / self.bytes_per_token).ceil() as usize
+    }
+
+    /// Token count from the tokenizer itself, cached
+    fn exact_count(
+        &self,
+        s: &str,
+    ) -> usize
     {
         // Create a new xxhash64 hasher with seed 0
         let mut hasher = Xxh64::new(0);
@@ -227,20 +663,22 @@ impl Budgeter
             .cache
             .get(&key)
         {
-            return t;
+            return self.scaled(t);
         }
 
         // Otherwise, encode and count tokens
         let t = self
-            .bpe
-            .encode_ordinary(s)
+            .tokenizer
+            .encode(s)
             .len();
 
         // Insert the result into cache
         self.cache
             .insert(key, t);
+        self.cache_dirty
+            .store(true, Ordering::Relaxed);
 
-        t
+        self.scaled(t)
     }
 
     /// Fit items into `budget_tokens` deterministically with trimming.
@@ -262,6 +700,43 @@ impl Budgeter
         self.fit_with_dedupe(items, budget_tokens, None)
     }
 
+    /// [`fit_with_dedupe`](Self::fit_with_dedupe), plus a report of what
+    /// became of every candidate and why
+    pub fn fit_with_report(
+        &self,
+        items: Vec<Item>,
+        budget_tokens: usize,
+        dedupe_config: Option<DedupeConfig>,
+    ) -> Result<(FitResult, BudgetReport)>
+    {
+        let candidates = sort_items_stable(items);
+        let survivors = match dedupe_config
+        {
+            Some(config) => DedupeEngine::with_config(config)
+                .dedupe_items_with_budgeter(candidates.clone(), self),
+            None => candidates.clone(),
+        };
+        let survived: HashSet<String> = survivors
+            .iter()
+            .map(|it| {
+                it.id
+                    .clone()
+            })
+            .collect();
+        let fit = self.fit_with_dedupe(survivors, budget_tokens, None)?;
+        let report = BudgetReport::build(
+            self,
+            budget_tokens,
+            &fit,
+            candidates
+                .iter()
+                .map(|it| (it.id.as_str(), it.content.as_str(), it.priority, None)),
+            BudgetReason::Budget,
+            |id| if survived.contains(id) { BudgetReason::Budget } else { BudgetReason::Dedupe },
+        );
+        Ok((fit, report))
+    }
+
     /// Fit items with optional deduplication applied first
     pub fn fit_with_dedupe(
         &self,
@@ -637,17 +1112,99 @@ impl Budgeter
 
         let total_tokens = total_tokens;
 
+        if self.approx
+        {
+            return Ok(self.recount_exactly(out, budget_tokens));
+        }
         Ok(FitResult { items: out, total_tokens })
     }
 
+    /// Exact counts for items fitted by estimate, trimming from the
+    /// lowest-priority tail should they exceed the budget after all
+    fn recount_exactly(
+        &self,
+        mut out: Vec<FittedItem>,
+        budget_tokens: usize,
+    ) -> FitResult
+    {
+        for it in &mut out
+        {
+            it.tokens = self.exact_count(&it.content);
+        }
+        let mut total_tokens: usize = out
+            .iter()
+            .map(|it| it.tokens)
+            .sum();
+        while total_tokens > budget_tokens
+            && let Some(last) = out.last_mut()
+        {
+            let excess = total_tokens - budget_tokens;
+            if last.tokens <= excess
+            {
+                total_tokens -= last.tokens;
+                out.pop();
+                continue;
+            }
+            let (text, tok) = self.exact_prefix(&last.full_content, last.tokens - excess);
+            total_tokens = total_tokens - last.tokens + tok;
+            last.content = text;
+            last.tokens = tok;
+        }
+        FitResult { items: out, total_tokens }
+    }
+
     /// Return a prefix with at most `max_tokens` tokens, with a clean ellipsis boundary
-    /// Reserve 1 "token slot" for ellipsis if we must trim, so our
-    /// emitted tokens never exceed max_tokens due to the "…\n".
     fn take_prefix(
         &self,
         s: &str,
         max_tokens: usize,
     ) -> (String, usize)
+    {
+        if self.approx
+        {
+            self.estimated_prefix(s, max_tokens)
+        }
+        else
+        {
+            self.exact_prefix(s, max_tokens)
+        }
+    }
+
+    /// Prefix cut at the byte length estimated to take `max_tokens`
+    fn estimated_prefix(
+        &self,
+        s: &str,
+        max_tokens: usize,
+    ) -> (String, usize)
+    {
+        let whole = self.scaled(self.estimate(s));
+        if whole <= max_tokens
+        {
+            return (s.to_string(), whole);
+        }
+        let max_raw = max_tokens * 100 / self.per_100;
+        let ellipsis = self.estimate(ELLIPSIS);
+        if max_raw <= ellipsis
+        {
+            return (String::new(), 0);
+        }
+        let mut end = (((max_raw - ellipsis) as f64 * self.bytes_per_token) as usize).min(s.len());
+        while !s.is_char_boundary(end)
+        {
+            end -= 1;
+        }
+        let out = format!("{}{ELLIPSIS}", &s[..end]);
+        let tokens = self.scaled(self.estimate(&out));
+        (out, tokens)
+    }
+
+    /// Prefix cut by the tokenizer. Reserve 1 "token slot" for ellipsis if we
+    /// must trim, so our emitted tokens never exceed max_tokens due to the "…\n".
+    fn exact_prefix(
+        &self,
+        s: &str,
+        max_tokens: usize,
+    ) -> (String, usize)
     {
         if max_tokens == 0
         {
@@ -656,42 +1213,43 @@ impl Budgeter
 
         // tokenize once
         let ids = self
-            .bpe
-            .encode_ordinary(s);
+            .tokenizer
+            .encode(s);
 
         // fits without trim
-        if ids.len() <= max_tokens
+        if self.scaled(ids.len()) <= max_tokens
         {
-            return (s.to_string(), ids.len());
+            return (s.to_string(), self.scaled(ids.len()));
         }
 
+        // BPE tokens whose scaled count stays within the cap
+        let max_ids = max_tokens * 100 / self.per_100;
+
         // sentinel ensures a hard boundary; newline guards against BPE merges
         let ellipsis_ids = self
-            .bpe
-            .encode_ordinary("\n…\n");
+            .tokenizer
+            .encode(ELLIPSIS);
         let e = ellipsis_ids.len();
 
-        if max_tokens <= e
+        if max_ids <= e
         {
             // we can't afford any prefix; show as much of the sentinel as fits
-            let take = &ellipsis_ids[..max_tokens];
+            let take = &ellipsis_ids[..max_ids];
             let out = self
-                .bpe
-                .decode(take.to_vec())
-                .unwrap_or_default();
-            return (out, max_tokens);
+                .tokenizer
+                .decode(take);
+            return (out, self.scaled(max_ids));
         }
 
-        let cap = max_tokens - e;
+        let cap = max_ids - e;
         let mut combined = Vec::with_capacity(cap + e);
         combined.extend_from_slice(&ids[..cap]);
         combined.extend_from_slice(&ellipsis_ids);
 
         let out = self
-            .bpe
-            .decode(combined.clone())
-            .unwrap_or_default();
-        (out, cap + e)
+            .tokenizer
+            .decode(&combined);
+        (out, self.scaled(cap + e))
     }
 }
 
@@ -1726,6 +2284,45 @@ pub struct BucketFit
     pub refusals: Vec<Refusal>,
 }
 
+impl BucketFit
+{
+    /// What became of each of `candidates`, the items passed to
+    /// [`fit_with_buckets`], and why
+    pub fn report(
+        &self,
+        budgeter: &Budgeter,
+        candidates: &[TaggedItem],
+        caps: &BucketCaps,
+    ) -> BudgetReport
+    {
+        let refused: HashMap<&str, &Refusal> = self
+            .refusals
+            .iter()
+            .map(|r| (r.id.as_str(), r))
+            .collect();
+        BudgetReport::build(
+            budgeter,
+            caps.code + caps.interfaces + caps.tests,
+            &self.fitted,
+            candidates
+                .iter()
+                .map(|it| (it.id.as_str(), it.content.as_str(), it.priority, Some(bucket_of(it)))),
+            BudgetReason::BucketCap,
+            |id| match refused.get(id)
+            {
+                Some(r)
+                    if r.reason
+                        .starts_with("novelty") =>
+                {
+                    BudgetReason::Novelty
+                }
+                Some(_) => BudgetReason::BucketCap,
+                None => BudgetReason::Budget,
+            },
+        )
+    }
+}
+
 /// SpanTag for AST-aware item classification
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum SpanTag
@@ -2028,27 +2625,38 @@ fn partition_by_tags(items: Vec<TaggedItem>)
 
     for item in items
     {
-        if item
-            .tags
-            .contains(&SpanTag::Test)
+        match bucket_of(&item)
         {
-            test_items.push(item);
-        }
-        else if item
-            .tags
-            .contains(&SpanTag::Interface)
-        {
-            interface_items.push(item);
-        }
-        else
-        {
-            code_items.push(item);
+            "tests" => test_items.push(item),
+            "interfaces" => interface_items.push(item),
+            _ => code_items.push(item),
         }
     }
 
     (code_items, interface_items, test_items)
 }
 
+/// Bucket an item is fitted in: tests, then interfaces, else code
+fn bucket_of(item: &TaggedItem) -> &'static str
+{
+    if item
+        .tags
+        .contains(&SpanTag::Test)
+    {
+        "tests"
+    }
+    else if item
+        .tags
+        .contains(&SpanTag::Interface)
+    {
+        "interfaces"
+    }
+    else
+    {
+        "code"
+    }
+}
+
 /// Parse bucket specification string like "code=60,interfaces=20,tests=20"
 pub fn parse_bucket_caps(spec: &str) -> Result<BucketCaps>
 {
@@ -21,9 +21,14 @@
 //!   tiktoken-rs = "0.7"
 //! ----------------------------------------------------------
 
-use std::path::PathBuf; // path types
+use std::{
+    collections::{HashMap, HashSet},
+    ops::Range,
+    path::{Path, PathBuf},
+}; // path types
 
 use anyhow::{Context, Result, anyhow, bail}; // error types
+use serde::Serialize; // change manifest
 use tiktoken_rs::{
     CoreBPE, // model->BPE
     // BPE helpers
@@ -34,7 +39,11 @@ use tiktoken_rs::{
     r50k_base, // encoding fns
 };
 
-use crate::core::symbols::Symbol; // symbol type
+use crate::{
+    cli::{ChunkFormat, ChunkOverlap, ChunkPartition, ChunkStrategy},
+    core::symbols::{FileFilter, Symbol, detect_language, is_supported_language, top_level},
+    infra::config::ChunkPreset,
+}; // symbol types
 
 /// Chunker specialized for GPT models using `tiktoken_rs`.
 pub struct GptChunker
@@ -409,91 +418,945 @@ fn extract_symbol_text(
     Ok(out)
 }
 
+/// Contiguous span of the source, packed into chunks by
+/// `chunk_at_boundaries`
+#[derive(Debug)]
+struct Piece
+{
+    range: Range<usize>,
+    name: String,
+    tokens: usize,
+}
+
+/// Split a file at its top-level symbol boundaries. Each symbol takes the
+/// text since the previous one (imports, comments, attributes) along, so
+/// without `overlap` the chunks cover the file without gaps or overlap.
+/// Consecutive symbols are merged while they fit `max_tokens`; an
+/// oversized one is split at its nested symbols (methods of an impl or
+/// class), else between lines, as is text with no symbols. With `overlap`,
+/// each chunk also starts with the last lines of the one before.
+pub fn chunk_at_boundaries(
+    content: &str,
+    symbols: &[Symbol],
+    max_tokens: usize,
+    overlap: Option<ChunkOverlap>,
+    chunker: &GptChunker,
+) -> Result<Vec<ChunkInfo>>
+{
+    if max_tokens == 0
+    {
+        bail!("max_tokens must be greater than 0");
+    }
+    // Token overlap comes out of the budget, as with token windows
+    let max_tokens = match overlap
+    {
+        Some(ChunkOverlap::Tokens(n)) => max_tokens - n.min(max_tokens - 1),
+        _ => max_tokens,
+    };
+    let file = symbols
+        .first()
+        .map(|s| {
+            s.file
+                .clone()
+        })
+        .unwrap_or_default();
+    let symbols: Vec<&Symbol> = symbols
+        .iter()
+        .filter(|s| s.byte_start < s.byte_end && content.get(s.byte_start..s.byte_end).is_some())
+        .collect();
+
+    let mut pieces = Vec::new();
+    split_at_symbols(content, 0..content.len(), &symbols, max_tokens, chunker, &mut pieces)?;
+
+    // Greedily pack consecutive pieces up to the budget
+    let mut groups: Vec<Vec<Piece>> = Vec::new();
+    let mut group_tokens = 0usize;
+    for piece in pieces
+    {
+        match groups.last_mut()
+        {
+            Some(group) if group_tokens + piece.tokens <= max_tokens =>
+            {
+                group_tokens += piece.tokens;
+                group.push(piece);
+            }
+            _ =>
+            {
+                group_tokens = piece.tokens;
+                groups.push(vec![piece]);
+            }
+        }
+    }
+
+    let mut out = Vec::with_capacity(groups.len());
+    for (i, group) in groups
+        .iter()
+        .enumerate()
+    {
+        let mut names: Vec<&str> = group
+            .iter()
+            .map(|p| p.name.as_str())
+            .collect();
+        names.dedup();
+        let mut range = group[0]
+            .range
+            .start..group[group.len() - 1]
+            .range
+            .end;
+        if i > 0
+            && let Some(overlap) = overlap
+        {
+            range.start = overlap_start(content, range.start, overlap, chunker);
+        }
+        out.push(span_chunk(content, range, names.join(", "), &file, chunker));
+    }
+    Ok(out)
+}
+
+/// Start of the whole lines before `start` that `overlap` repeats
+fn overlap_start(
+    content: &str,
+    start: usize,
+    overlap: ChunkOverlap,
+    chunker: &GptChunker,
+) -> usize
+{
+    let mut from = start;
+    let mut lines = 0usize;
+    let mut tokens = 0usize;
+    while from > 0
+    {
+        // Start of the line ending just before `from`
+        let prev = content[..from - 1]
+            .rfind('\n')
+            .map_or(0, |i| i + 1);
+        match overlap
+        {
+            ChunkOverlap::Lines(n) if lines >= n => break,
+            ChunkOverlap::Tokens(n) =>
+            {
+                tokens += chunker.count_tokens(&content[prev..from]);
+                if tokens > n
+                {
+                    break;
+                }
+            }
+            _ =>
+            {}
+        }
+        lines += 1;
+        from = prev;
+    }
+    from
+}
+
+/// Pieces of `range`, one per top-level symbol in `symbols` unless it
+/// exceeds `max_tokens`
+fn split_at_symbols(
+    content: &str,
+    range: Range<usize>,
+    symbols: &[&Symbol],
+    max_tokens: usize,
+    chunker: &GptChunker,
+    out: &mut Vec<Piece>,
+) -> Result<()>
+{
+    let top = top_level(symbols);
+    if top.is_empty()
+    {
+        return split_at_lines(content, range, "chunk", max_tokens, chunker, out);
+    }
+
+    let mut start = range.start;
+    for (i, sym) in top
+        .iter()
+        .enumerate()
+    {
+        // A symbol's span runs to the end of its last line; the last one
+        // also takes whatever trails it
+        let end = if i + 1 == top.len()
+        {
+            range.end
+        }
+        else
+        {
+            end_of_line(content, sym.byte_end).min(range.end)
+        };
+        if end <= start
+        {
+            continue;
+        }
+        let span = start..end;
+        start = end;
+
+        let tokens = chunker.count_tokens(&content[span.clone()]);
+        if tokens <= max_tokens
+        {
+            out.push(Piece {
+                range: span,
+                name: sym
+                    .qualified_name
+                    .clone(),
+                tokens,
+            });
+            continue;
+        }
+        let nested: Vec<&Symbol> = symbols
+            .iter()
+            .copied()
+            .filter(|s| {
+                sym.byte_start <= s.byte_start
+                    && s.byte_end <= sym.byte_end
+                    && (s.byte_start, s.byte_end) != (sym.byte_start, sym.byte_end)
+            })
+            .collect();
+        if nested.is_empty()
+        {
+            split_at_lines(content, span, &sym.qualified_name, max_tokens, chunker, out)?;
+        }
+        else
+        {
+            split_at_symbols(content, span, &nested, max_tokens, chunker, out)?;
+        }
+    }
+    Ok(())
+}
+
+/// Pieces of an oversized `range` with no symbols inside, broken between
+/// lines; a single line over budget is cut by tokens. Each break is the
+/// best-scoring line boundary (see `boundary_score`) in the back half of
+/// what fits, so prose splits at headings and paragraphs, not mid-sentence.
+fn split_at_lines(
+    content: &str,
+    range: Range<usize>,
+    name: &str,
+    max_tokens: usize,
+    chunker: &GptChunker,
+    out: &mut Vec<Piece>,
+) -> Result<()>
+{
+    let mut lines: Vec<(usize, &str)> = Vec::new();
+    let mut at = range.start;
+    for line in content[range].split_inclusive('\n')
+    {
+        lines.push((at, line));
+        at += line.len();
+    }
+    let tokens: Vec<usize> = lines
+        .iter()
+        .map(|(_, line)| chunker.count_tokens(line))
+        .collect();
+
+    let mut parts: Vec<(Range<usize>, usize)> = Vec::new();
+    let mut i = 0;
+    while i < lines.len()
+    {
+        if tokens[i] > max_tokens
+        {
+            // Decoded windows concatenate back to the line
+            let mut from = lines[i].0;
+            for window in chunker.chunk_with_overlap(lines[i].1, max_tokens, 0)?
+            {
+                parts.push((from..from + window.len(), chunker.count_tokens(&window)));
+                from += window.len();
+            }
+            i += 1;
+            continue;
+        }
+
+        // Lines i..fit fit the budget; break at the best boundary among
+        // those past half of it
+        let (mut fit, mut sum) = (i, 0usize);
+        while fit < lines.len() && sum + tokens[fit] <= max_tokens
+        {
+            sum += tokens[fit];
+            fit += 1;
+        }
+        let mut cut = fit;
+        if fit < lines.len()
+        {
+            let (mut best, mut prefix) = (0u8, 0usize);
+            for k in i + 1..=fit
+            {
+                prefix += tokens[k - 1];
+                let score = boundary_score(lines[k - 1].1, lines[k].1);
+                if prefix * 2 >= sum && score >= best
+                {
+                    (best, cut) = (score, k);
+                }
+            }
+        }
+        let (start, (last, text)) = (lines[i].0, lines[cut - 1]);
+        parts.push((start..last + text.len(), tokens[i..cut].iter().sum()));
+        i = cut;
+    }
+
+    let numbered = parts.len() > 1;
+    for (i, (range, tokens)) in parts
+        .into_iter()
+        .enumerate()
+    {
+        let name = if numbered { format!("{name}[part_{}]", i + 1) } else { name.to_string() };
+        out.push(Piece { range, name, tokens });
+    }
+    Ok(())
+}
+
+/// How good a break is between line `before` and line `after`: 4 before a
+/// Markdown heading, 3 at a paragraph start, 2 after a sentence or before
+/// a comment block, 1 anywhere else between lines
+fn boundary_score(
+    before: &str,
+    after: &str,
+) -> u8
+{
+    let (before, after) = (before.trim(), after.trim_start());
+    let is_comment = |line: &str| {
+        ["//", "/*", "\"\"\"", "'''", "--", "<!--", "#"]
+            .iter()
+            .any(|p| line.starts_with(p))
+            && !line.starts_with("#[")
+            && !line.starts_with("#!")
+    };
+    let heading = after.starts_with('#')
+        && after
+            .trim_start_matches('#')
+            .starts_with(' ')
+        && !before.starts_with('#');
+
+    if heading
+    {
+        4
+    }
+    else if before.is_empty() && !after.is_empty()
+    {
+        3
+    }
+    else if before.ends_with(['.', '!', '?', ':']) || (is_comment(after) && !is_comment(before))
+    {
+        2
+    }
+    else
+    {
+        1
+    }
+}
+
+/// Byte just past the line holding `byte`, or `byte` itself when it
+/// already starts a line
+fn end_of_line(
+    content: &str,
+    byte: usize,
+) -> usize
+{
+    if byte == 0 || content.as_bytes()[byte - 1] == b'\n'
+    {
+        return byte;
+    }
+    content[byte..]
+        .find('\n')
+        .map_or(content.len(), |i| byte + i + 1)
+}
+
+/// Chunk of the source text in `range`
+fn span_chunk(
+    content: &str,
+    range: Range<usize>,
+    symbol_path: String,
+    file: &Path,
+    chunker: &GptChunker,
+) -> ChunkInfo
+{
+    let text = &content[range.clone()];
+    let (start_line, end_line) = line_span(content, range);
+    ChunkInfo {
+        content: text.to_string(),
+        symbol_path,
+        file: file.to_path_buf(),
+        start_line,
+        end_line,
+        token_count: chunker.count_tokens(text),
+    }
+}
+
+/// 1-based first and last line of `range`; a trailing newline does not
+/// start another line
+fn line_span(
+    content: &str,
+    range: Range<usize>,
+) -> (usize, usize)
+{
+    let newlines = |s: &str| {
+        s.bytes()
+            .filter(|&b| b == b'\n')
+            .count()
+    };
+    let start_line = newlines(&content[..range.start]) + 1;
+    (start_line, start_line + newlines(content[range].trim_end_matches('\n')))
+}
+
+/// Model used when neither `--model` nor a preset names one
+const DEFAULT_MODEL: &str = "gpt-4o";
+
+/// Chunk size used when neither `--max-tokens` nor a preset sets one
+const DEFAULT_MAX_TOKENS: usize = 4000;
+
+/// Names of the presets shipped with roughup
+const BUILTIN_PRESETS: [&str; 3] = ["gpt-4o", "claude-200k", "embedding-512"];
+
+/// Presets shipped with roughup. `claude-200k` counts with `o200k_base`,
+/// the nearest public encoding, so its sizes are estimates
+fn builtin_preset(name: &str) -> Option<ChunkPreset>
+{
+    let (model, max_tokens, overlap) = match name
+    {
+        "gpt-4o" => ("gpt-4o", 4000, "128tok"),
+        "claude-200k" => ("o200k_base", 16000, "256tok"),
+        "embedding-512" => ("text-embedding-3-small", 512, "64tok"),
+        _ => return None,
+    };
+    Some(ChunkPreset {
+        model: Some(model.to_string()),
+        max_tokens: Some(max_tokens),
+        overlap: Some(overlap.to_string()),
+    })
+}
+
+/// The configured preset `name`, else the built-in one; names ignore case
+fn find_preset(
+    name: &str,
+    configured: &std::collections::BTreeMap<String, ChunkPreset>,
+) -> Result<ChunkPreset>
+{
+    configured
+        .iter()
+        .find(|(k, _)| k.eq_ignore_ascii_case(name))
+        .map(|(_, preset)| preset.clone())
+        .or_else(|| builtin_preset(&name.to_ascii_lowercase()))
+        .ok_or_else(|| {
+            let mut names = BUILTIN_PRESETS.to_vec();
+            names.extend(
+                configured
+                    .keys()
+                    .map(String::as_str),
+            );
+            names.sort_unstable();
+            names.dedup();
+            anyhow!("unknown chunk preset '{name}' (available: {})", names.join(", "))
+        })
+}
+
+/// Fill the model, --max-tokens, and --overlap the command line left unset
+fn apply_preset(
+    args: &mut crate::cli::ChunkArgs,
+    preset: &ChunkPreset,
+) -> Result<()>
+{
+    if args
+        .model
+        .is_none()
+    {
+        args.model = preset
+            .model
+            .clone();
+    }
+    if args
+        .max_tokens
+        .is_none()
+    {
+        args.max_tokens = preset.max_tokens;
+    }
+    if args
+        .overlap
+        .is_none()
+        && let Some(overlap) = &preset.overlap
+    {
+        args.overlap = Some(
+            overlap
+                .parse()
+                .map_err(|e: String| anyhow!("preset overlap '{overlap}': {e}"))?,
+        );
+    }
+    Ok(())
+}
+
 /// CLI run function - integrates GptChunker with command-line interface
 pub fn run(
-    args: crate::cli::ChunkArgs,
+    mut args: crate::cli::ChunkArgs,
     ctx: &crate::cli::AppContext,
 ) -> anyhow::Result<()>
 {
+    if let Some(name) = &args.preset
+    {
+        let config = crate::infra::config::load_config().unwrap_or_default();
+        let preset = find_preset(name, &config.chunk.presets)?;
+        apply_preset(&mut args, &preset)?;
+    }
+
     // Initialize the GPT chunker with specified model
-    let chunker = GptChunker::new(&args.model)
-        .with_context(|| format!("Failed to initialize chunker for model '{}'", args.model))?;
+    let model = args
+        .model
+        .as_deref()
+        .unwrap_or(DEFAULT_MODEL);
+    let chunker = GptChunker::new(model)
+        .with_context(|| format!("Failed to initialize chunker for model '{model}'"))?;
+
+    if args.resume && args.format != ChunkFormat::Files
+    {
+        bail!("--resume needs --format files");
+    }
+    if args
+        .partition
+        .is_some()
+        && args.format != ChunkFormat::Files
+    {
+        bail!("--partition needs --format files");
+    }
 
     // Create output directory if it doesn't exist
-    std::fs::create_dir_all(&args.output_dir).with_context(|| {
-        format!(
-            "Failed to create output directory: {}",
-            args.output_dir
-                .display()
-        )
-    })?;
+    if args.format == ChunkFormat::Files
+    {
+        std::fs::create_dir_all(&args.output_dir).with_context(|| {
+            format!(
+                "Failed to create output directory: {}",
+                args.output_dir
+                    .display()
+            )
+        })?;
+    }
+
+    let sources = chunk_sources(&args)?;
+    match args.format
+    {
+        ChunkFormat::Files => run_files(&args, &sources, &chunker, !ctx.quiet),
+        ChunkFormat::Jsonl => run_jsonl(&args, &sources, &chunker),
+    }
+}
+
+/// The input file, or every file below an input directory except the
+/// output directory, narrowed by `--lang`, `--include`, `--exclude`, and
+/// `--max-file-bytes`
+fn chunk_sources(args: &crate::cli::ChunkArgs) -> Result<Vec<PathBuf>>
+{
+    let mut files = if args
+        .input
+        .is_dir()
+    {
+        let config = crate::infra::config::load_config().unwrap_or_default();
+        let mut files = crate::infra::walk::FileWalker::new(&config.ignore_patterns)?
+            .walk_files(&args.input);
+        if let Ok(out) = std::fs::canonicalize(&args.output_dir)
+        {
+            files.retain(|f| {
+                !f.canonicalize()
+                    .is_ok_and(|f| f.starts_with(&out))
+            });
+        }
+        files
+    }
+    else
+    {
+        vec![
+            args.input
+                .clone(),
+        ]
+    };
 
-    // Read input file content
-    let content = crate::infra::io::read_file_smart(&args.input)?;
-    let content_str = content.as_ref();
+    let filter = FileFilter::new(&args.lang, &args.include, &args.exclude)?;
+    files.retain(|f| {
+        // A lone input file is matched by the path it was given as
+        let rel = f
+            .strip_prefix(&args.input)
+            .ok()
+            .filter(|r| {
+                !r.as_os_str()
+                    .is_empty()
+            })
+            .unwrap_or(f);
+        filter.allows(rel)
+            && (args.max_file_bytes == 0
+                || std::fs::metadata(f).is_ok_and(|m| m.len() <= args.max_file_bytes))
+    });
+    Ok(files)
+}
 
-    // Determine chunking strategy - try symbols first, fallback to tokens
-    let chunks = match extract_symbols_for_chunking(&args.input)
+/// Text of one source; below a directory, files that are not UTF-8 text
+/// are skipped
+fn read_source(
+    args: &crate::cli::ChunkArgs,
+    path: &Path,
+) -> Result<Option<crate::infra::io::FileContent>>
+{
+    match crate::infra::io::read_file_smart(path)
     {
-        Ok(symbols) if !symbols.is_empty() =>
+        Ok(content) => Ok(Some(content)),
+        Err(_)
+            if args
+                .input
+                .is_dir() =>
         {
-            if !ctx.quiet
+            Ok(None)
+        }
+        Err(e) => Err(e),
+    }
+}
+
+/// Chunks of one source under the requested strategy
+fn chunk_source(
+    args: &crate::cli::ChunkArgs,
+    path: &Path,
+    content: &str,
+    chunker: &GptChunker,
+    verbose: bool,
+) -> Result<Vec<ChunkInfo>>
+{
+    let max_tokens = args
+        .max_tokens
+        .unwrap_or(DEFAULT_MAX_TOKENS);
+
+    // Determine chunking strategy - symbols first, else prose boundaries
+    let strategy = args
+        .strategy
+        .unwrap_or(if args.by_symbols { ChunkStrategy::Symbol } else { ChunkStrategy::Tokens });
+    let mut chunks = match strategy
+    {
+        ChunkStrategy::Symbol =>
+        {
+            let symbols = extract_symbols_for_chunking(path).unwrap_or_default();
+            if verbose && symbols.is_empty()
+            {
+                println!("No symbols found, splitting at headings and paragraphs");
+            }
+            else if verbose
             {
                 println!(
                     "Found {} symbols, chunking by symbol boundaries",
                     symbols.len()
                 );
             }
-            chunk_by_symbols(content_str, &symbols, args.max_tokens, &chunker)?
+            chunk_at_boundaries(content, &symbols, max_tokens, args.overlap, chunker)?
         }
-        _ =>
+        ChunkStrategy::Tokens =>
+        {
+            let overlap = args
+                .overlap
+                .unwrap_or(ChunkOverlap::Tokens(128));
+            chunk_by_tokens(chunker, content, max_tokens, overlap)?
+        }
+    };
+
+    // Token windows carry no path of their own
+    for chunk in &mut chunks
+    {
+        if chunk
+            .file
+            .as_os_str()
+            .is_empty()
         {
-            if !ctx.quiet
+            chunk.file = path.to_path_buf();
+        }
+    }
+    Ok(chunks)
+}
+
+/// One source chunked, with the IDs and digest its manifest records need
+struct Chunked
+{
+    chunks: Vec<ChunkInfo>,
+    ids: Vec<String>,
+    digest: String,
+}
+
+/// Read and chunk `sources` in parallel, a batch of at most
+/// `--in-flight-mb` source bytes at a time, handing each result to `sink`
+/// in source order
+fn chunk_in_flight(
+    args: &crate::cli::ChunkArgs,
+    sources: &[&PathBuf],
+    chunker: &GptChunker,
+    verbose: bool,
+    mut sink: impl FnMut(&Path, Chunked) -> Result<()>,
+) -> Result<()>
+{
+    use rayon::prelude::*;
+
+    let sizes: Vec<u64> = sources
+        .iter()
+        .map(|s| std::fs::metadata(s).map_or(0, |m| m.len()))
+        .collect();
+    let budget = args
+        .in_flight_mb
+        .max(1)
+        * 1024
+        * 1024;
+    for batch in in_flight_batches(&sizes, budget)
+    {
+        let results: Vec<Result<Option<Chunked>>> = sources[batch.clone()]
+            .par_iter()
+            .map(|source| {
+                let Some(content) = read_source(args, source)?
+                else
+                {
+                    return Ok(None);
+                };
+                if args.min_tokens > 0 && chunker.count_tokens(content.as_ref()) < args.min_tokens
+                {
+                    return Ok(None);
+                }
+                let chunks = chunk_source(args, source, content.as_ref(), chunker, verbose)?;
+                let ids = stable_ids(&chunks);
+                let digest = source_digest(
+                    content
+                        .as_ref()
+                        .as_bytes(),
+                );
+                Ok(Some(Chunked { chunks, ids, digest }))
+            })
+            .collect();
+        for (source, result) in sources[batch]
+            .iter()
+            .zip(results)
+        {
+            if let Some(chunked) = result?
             {
-                println!("No symbols found, using token-based chunking");
+                sink(source, chunked)?;
             }
-            chunk_by_tokens(&chunker, content_str, args.max_tokens)?
         }
+    }
+    Ok(())
+}
+
+/// Consecutive runs of sources whose sizes add up to at most `budget`;
+/// a source larger than that gets a run of its own
+fn in_flight_batches(
+    sizes: &[u64],
+    budget: u64,
+) -> Vec<Range<usize>>
+{
+    let mut out = Vec::new();
+    let (mut start, mut bytes) = (0, 0u64);
+    for (i, &size) in sizes
+        .iter()
+        .enumerate()
+    {
+        if i > start && bytes + size > budget
+        {
+            out.push(start..i);
+            (start, bytes) = (i, 0);
+        }
+        bytes += size;
+    }
+    if start < sizes.len()
+    {
+        out.push(start..sizes.len());
+    }
+    out
+}
+
+/// `--format files`: chunk files plus a manifest appended one source at a
+/// time, so an interrupted run leaves only whole sources in it
+fn run_files(
+    args: &crate::cli::ChunkArgs,
+    sources: &[PathBuf],
+    chunker: &GptChunker,
+    verbose: bool,
+) -> Result<()>
+{
+    use std::io::Write;
+
+    // Compare with the last run before its manifest is replaced
+    let manifest = args
+        .output_dir
+        .join("chunks_manifest.jsonl");
+    let previous = match &args.since
+    {
+        Some(since) => previous_ids(since, &args.input)?,
+        None if manifest.exists() => previous_ids(&manifest, &args.input)?,
+        None => Vec::new(),
     };
 
-    // Write chunks to individual files and create manifest
-    write_chunks_and_manifest(&chunks, &args.output_dir)?;
+    // With --resume, sources finished earlier keep their chunks
+    let kept = if args.resume && manifest.exists()
+    {
+        resumable_records(&manifest, &args.output_dir)?
+    }
+    else
+    {
+        Vec::new()
+    };
+    let done: HashSet<&str> = kept
+        .iter()
+        .filter_map(|r| r["source_file"].as_str())
+        .collect();
+    let mut ids: Vec<String> = kept
+        .iter()
+        .filter_map(|r| {
+            r["id"]
+                .as_str()
+                .map(str::to_string)
+        })
+        .collect();
+    let mut next = kept
+        .iter()
+        .filter_map(|r| r["chunk_id"].as_u64())
+        .max()
+        .map_or(1, |n| n as usize + 1);
+
+    let mut writer = std::io::BufWriter::new(
+        std::fs::File::create(&manifest)
+            .with_context(|| format!("Failed to create {}", manifest.display()))?,
+    );
+    for record in &kept
+    {
+        writeln!(writer, "{}", serde_json::to_string(record)?)?;
+    }
+    writer.flush()?;
+
+    let single = sources.len() == 1
+        && !args
+            .input
+            .is_dir();
+    let (mut created, mut total_tokens) = (0usize, 0usize);
+    let (todo, finished): (Vec<&PathBuf>, Vec<&PathBuf>) = sources
+        .iter()
+        .partition(|s| {
+            !done.contains(
+                s.to_string_lossy()
+                    .as_ref(),
+            )
+        });
+    let resumed = finished.len();
+    chunk_in_flight(args, &todo, chunker, verbose && single, |source, chunked| {
+        let subdir = args
+            .partition
+            .map(|p| partition_of(p, &args.input, source))
+            .unwrap_or_default();
+        write_chunks_and_manifest(
+            &chunked.chunks,
+            &chunked.ids,
+            &chunked.digest,
+            next,
+            &args.output_dir.join(&subdir),
+            &subdir,
+            &mut writer,
+        )?;
+        writer.flush()?;
+
+        next += chunked
+            .chunks
+            .len();
+        created += chunked
+            .chunks
+            .len();
+        total_tokens += chunked
+            .chunks
+            .iter()
+            .map(|c| c.token_count)
+            .sum::<usize>();
+        ids.extend(chunked.ids);
+        Ok(())
+    })?;
+
+    let changes = ChunkChanges::between(&previous, &ids);
+    let changes_file = args
+        .output_dir
+        .join("chunks_changes.json");
+    std::fs::write(&changes_file, serde_json::to_string_pretty(&changes)? + "\n")
+        .with_context(|| format!("Failed to write {}", changes_file.display()))?;
 
     // Print success summary
-    if !ctx.quiet
+    if verbose
     {
         println!(
             "✓ Created {} chunks in {}",
-            chunks.len(),
+            created,
             args.output_dir
                 .display()
         );
-        let total_tokens: usize = chunks
-            .iter()
-            .map(|c| c.token_count)
-            .sum();
+        if resumed > 0
+        {
+            println!("  Resumed: {} sources already chunked", resumed);
+        }
         println!("  Total tokens: {}", total_tokens);
+        println!(
+            "  Changes: {} added, {} removed, {} unchanged",
+            changes
+                .added
+                .len(),
+            changes
+                .removed
+                .len(),
+            changes.unchanged
+        );
     }
 
     Ok(())
 }
 
+/// `--format jsonl`: one record per chunk on stdout, or with --since only
+/// the new ones and then a removal record for each chunk that is gone
+fn run_jsonl(
+    args: &crate::cli::ChunkArgs,
+    sources: &[PathBuf],
+    chunker: &GptChunker,
+) -> Result<()>
+{
+    use std::io::Write;
+
+    let previous = match &args.since
+    {
+        Some(since) => Some(previous_ids(since, &args.input)?),
+        None => None,
+    };
+    let known: HashSet<&String> = previous
+        .iter()
+        .flatten()
+        .collect();
+
+    let mut out = std::io::BufWriter::new(std::io::stdout().lock());
+    let mut ids = Vec::new();
+    let sources: Vec<&PathBuf> = sources
+        .iter()
+        .collect();
+    chunk_in_flight(args, &sources, chunker, false, |_, chunked| {
+        for (chunk, id) in chunked
+            .chunks
+            .iter()
+            .zip(&chunked.ids)
+        {
+            if !known.contains(id)
+            {
+                writeln!(out, "{}", serde_json::to_string(&jsonl_record(chunk, id))?)?;
+            }
+        }
+        ids.extend(chunked.ids);
+        Ok(())
+    })?;
+    if let Some(previous) = &previous
+    {
+        for id in ChunkChanges::between(previous, &ids).removed
+        {
+            let record = serde_json::json!({ "id": id, "path": args.input, "removed": true });
+            writeln!(out, "{}", serde_json::to_string(&record)?)?;
+        }
+    }
+    out.flush()?;
+    Ok(())
+}
+
 /// Extract symbols for chunking (simplified interface)
 fn extract_symbols_for_chunking(file_path: &std::path::Path) -> Result<Vec<Symbol>>
 {
     let content = std::fs::read_to_string(file_path)?;
 
-    // Detect language from file extension
-    let lang = match file_path
-        .extension()
-        .and_then(|e| e.to_str())
+    // Unsupported file types chunk by tokens alone
+    let Some(lang) = detect_language(file_path).filter(|lang| is_supported_language(lang))
+    else
     {
-        Some("rs") => "rust",
-        Some("py") => "python",
-        _ => return Ok(Vec::new()), // Unsupported file type
+        return Ok(Vec::new());
     };
 
-    let extractor = crate::core::symbols::get_extractor(lang)?;
+    let extractor = crate::core::symbols::get_extractor(&lang)?;
     extractor.extract_symbols(&content, file_path)
 }
 
@@ -502,22 +1365,57 @@ fn chunk_by_tokens(
     chunker: &GptChunker,
     content: &str,
     max_tokens: usize,
+    overlap: ChunkOverlap,
 ) -> Result<Vec<ChunkInfo>>
 {
-    let text_chunks = chunker.chunk_with_overlap(content, max_tokens, 128)?; // 128-token overlap
+    let text_chunks = match overlap
+    {
+        ChunkOverlap::Tokens(n) => chunker.chunk_with_overlap(content, max_tokens, n)?,
+        ChunkOverlap::Lines(n) =>
+        {
+            // Windows without overlap tile the text; each then reaches
+            // back over the lines before it
+            let mut start = 0usize;
+            let mut out = Vec::new();
+            for window in chunker.chunk_with_overlap(content, max_tokens, 0)?
+            {
+                let from = overlap_start(content, start, ChunkOverlap::Lines(n), chunker);
+                out.push(content[from..start + window.len()].to_string());
+                start += window.len();
+            }
+            out
+        }
+    };
 
     let mut chunks = Vec::new();
+    let mut cursor = 0usize;
     for (i, chunk_text) in text_chunks
         .into_iter()
         .enumerate()
     {
+        // Windows run forward through the text, so each is found past
+        // the start of the one before
+        let (start_line, end_line) = match content[cursor..].find(&chunk_text)
+        {
+            Some(at) =>
+            {
+                let at = cursor + at;
+                cursor = at
+                    + content[at..]
+                        .chars()
+                        .next()
+                        .map_or(0, char::len_utf8);
+                line_span(content, at..at + chunk_text.len())
+            }
+            None => (0, 0),
+        };
         let token_count = chunker.count_tokens(&chunk_text);
         chunks.push(ChunkInfo {
             content: chunk_text,
             symbol_path: format!("chunk_{}", i + 1),
             file: std::path::PathBuf::new(),
-            start_line: 0,
-            end_line: 0,
+            start_line,
+            end_line,
             token_count,
         });
     }
@@ -525,48 +1423,485 @@ fn chunk_by_tokens(
     Ok(chunks)
 }
 
-/// Write chunks to files and create JSONL manifest
-fn write_chunks_and_manifest(
-    chunks: &[ChunkInfo],
-    output_dir: &std::path::Path,
-) -> Result<()>
+/// IDs that stay put while a chunk's path and text do: xxh64 of both, with
+/// repeats of one text in a file told apart by their order
+pub fn stable_ids(chunks: &[ChunkInfo]) -> Vec<String>
 {
-    use std::{
-        fs,
-        io::{BufWriter, Write},
-    };
-
-    // Write individual chunk files
-    for (i, chunk) in chunks
+    let mut seen: HashSet<String> = HashSet::new();
+    chunks
         .iter()
-        .enumerate()
+        .map(|chunk| {
+            let key = format!(
+                "{}\0{}",
+                chunk
+                    .file
+                    .display(),
+                chunk.content
+            );
+            let mut id = format!("{:016x}", xxhash_rust::xxh64::xxh64(key.as_bytes(), 0));
+            let mut repeat = 1;
+            while !seen.insert(id.clone())
+            {
+                let key = format!("{key}\0{repeat}");
+                id = format!("{:016x}", xxhash_rust::xxh64::xxh64(key.as_bytes(), 0));
+                repeat += 1;
+            }
+            id
+        })
+        .collect()
+}
+
+/// Chunk IDs of an earlier run that differ from this one's
+#[derive(Debug, PartialEq, Eq, Serialize)]
+pub struct ChunkChanges
+{
+    /// New or edited chunks, to embed
+    pub added: Vec<String>,
+    /// Chunks no longer present, to drop
+    pub removed: Vec<String>,
+    /// Chunks kept as they were
+    pub unchanged: usize,
+}
+
+impl ChunkChanges
+{
+    pub fn between(
+        previous: &[String],
+        current: &[String],
+    ) -> Self
     {
-        let chunk_file = output_dir.join(format!("chunk_{:03}.txt", i + 1));
-        fs::write(&chunk_file, &chunk.content)
-            .with_context(|| format!("Failed to write chunk file: {}", chunk_file.display()))?;
+        let before: HashSet<&String> = previous
+            .iter()
+            .collect();
+        let now: HashSet<&String> = current
+            .iter()
+            .collect();
+        let added: Vec<String> = current
+            .iter()
+            .filter(|id| !before.contains(id))
+            .cloned()
+            .collect();
+        let removed = previous
+            .iter()
+            .filter(|id| !now.contains(id))
+            .cloned()
+            .collect();
+        Self { unchanged: current.len() - added.len(), added, removed }
+    }
+}
+
+/// IDs of the chunks of `input`, or of files below it, in an earlier
+/// manifest or JSONL output; older manifests without IDs count as empty
+fn previous_ids(
+    file: &Path,
+    input: &Path,
+) -> Result<Vec<String>>
+{
+    let text = std::fs::read_to_string(file)
+        .with_context(|| format!("Failed to read {}", file.display()))?;
+    let mut ids = Vec::new();
+    for line in text
+        .lines()
+        .filter(|l| {
+            !l.trim()
+                .is_empty()
+        })
+    {
+        let record: serde_json::Value = serde_json::from_str(line)
+            .with_context(|| format!("Bad chunk record in {}", file.display()))?;
+        let path = record
+            .get("path")
+            .or_else(|| record.get("source_file"))
+            .and_then(|p| p.as_str());
+        if path.is_some_and(|p| Path::new(p).starts_with(input))
+            && record
+                .get("removed")
+                .is_none()
+            && let Some(id) = record
+                .get("id")
+                .and_then(|id| id.as_str())
+        {
+            ids.push(id.to_string());
+        }
+    }
+    Ok(ids)
+}
+
+/// JSONL record of one chunk, for embedding and ingestion jobs
+fn jsonl_record(
+    chunk: &ChunkInfo,
+    id: &str,
+) -> serde_json::Value
+{
+    serde_json::json!({
+        "id": id,
+        "path": chunk.file,
+        "start_line": chunk.start_line,
+        "end_line": chunk.end_line,
+        "tokens": chunk.token_count,
+        "text": chunk.content,
+        "lang": detect_language(&chunk.file),
+    })
+}
+
+/// xxh64 of a source's bytes, recorded so `--resume` can tell it is
+/// unchanged
+fn source_digest(bytes: &[u8]) -> String
+{
+    format!("{:016x}", xxhash_rust::xxh64::xxh64(bytes, 0))
+}
+
+/// Manifest records of sources an earlier run finished: each source still
+/// has its recorded digest and every chunk file it lists exists
+fn resumable_records(
+    manifest: &Path,
+    output_dir: &Path,
+) -> Result<Vec<serde_json::Value>>
+{
+    let text = std::fs::read_to_string(manifest)
+        .with_context(|| format!("Failed to read {}", manifest.display()))?;
+    let records: Vec<serde_json::Value> = text
+        .lines()
+        .filter_map(|l| serde_json::from_str(l).ok())
+        .collect();
+
+    // A source is whole when unchanged and all its chunk files exist
+    let mut whole: HashMap<&str, bool> = HashMap::new();
+    for record in &records
+    {
+        let Some(source) = record["source_file"].as_str()
+        else
+        {
+            continue;
+        };
+        let written = record["file"]
+            .as_str()
+            .is_some_and(|f| {
+                output_dir
+                    .join(f)
+                    .exists()
+            });
+        let ok = whole
+            .entry(source)
+            .or_insert_with(|| {
+                let digest = record["source_digest"].as_str();
+                std::fs::read(source).is_ok_and(|b| digest == Some(source_digest(&b).as_str()))
+            });
+        *ok &= written;
     }
+    Ok(records
+        .iter()
+        .filter(|r| {
+            r["source_file"]
+                .as_str()
+                .is_some_and(|s| whole.get(s) == Some(&true))
+        })
+        .cloned()
+        .collect())
+}
 
-    // Create JSONL manifest with metadata
-    let manifest_file = output_dir.join("chunks_manifest.jsonl");
-    let mut writer = BufWriter::new(std::fs::File::create(&manifest_file)?);
+/// Subdirectory of the output that `source`'s chunks go to
+fn partition_of(
+    partition: ChunkPartition,
+    input: &Path,
+    source: &Path,
+) -> String
+{
+    match partition
+    {
+        ChunkPartition::Lang => detect_language(source).unwrap_or_else(|| "other".into()),
+        ChunkPartition::TopDir =>
+        {
+            let rel = source
+                .strip_prefix(input)
+                .unwrap_or(source);
+            let mut parts = rel.components();
+            match (parts.next(), parts.next())
+            {
+                (Some(top), Some(_)) => top
+                    .as_os_str()
+                    .to_string_lossy()
+                    .into_owned(),
+                _ => "_root".into(),
+            }
+        }
+    }
+}
 
-    for (i, chunk) in chunks
+/// Write one source's chunks to numbered files from `first` in `dir`, and
+/// append their manifest records naming them relative to the output
+/// directory through `subdir`
+fn write_chunks_and_manifest(
+    chunks: &[ChunkInfo],
+    ids: &[String],
+    digest: &str,
+    first: usize,
+    dir: &Path,
+    subdir: &str,
+    writer: &mut impl std::io::Write,
+) -> Result<()>
+{
+    std::fs::create_dir_all(dir)
+        .with_context(|| format!("Failed to create output directory: {}", dir.display()))?;
+    for (i, (chunk, id)) in chunks
         .iter()
+        .zip(ids)
         .enumerate()
     {
+        let name = format!("chunk_{:03}.txt", first + i);
+        let chunk_file = dir.join(&name);
+        std::fs::write(&chunk_file, &chunk.content)
+            .with_context(|| format!("Failed to write chunk file: {}", chunk_file.display()))?;
+
         let metadata = serde_json::json!({
-            "chunk_id": i + 1,
-            "file": format!("chunk_{:03}.txt", i + 1),
+            "chunk_id": first + i,
+            "id": id,
+            "file": if subdir.is_empty() { name } else { format!("{subdir}/{name}") },
             "symbol_path": chunk.symbol_path,
             "source_file": chunk.file,
+            "source_digest": digest,
             "start_line": chunk.start_line,
             "end_line": chunk.end_line,
             "token_count": chunk.token_count
         });
-
         writeln!(writer, "{}", serde_json::to_string(&metadata)?)?;
     }
-
-    writer.flush()?;
     Ok(())
 }
+
+#[cfg(test)]
+mod tests
+{
+    use super::*;
+    use crate::core::symbols::get_extractor;
+
+    fn chunks(
+        source: &str,
+        max_tokens: usize,
+        overlap: Option<ChunkOverlap>,
+    ) -> Vec<ChunkInfo>
+    {
+        let symbols = get_extractor("rust")
+            .unwrap()
+            .extract_symbols(source, Path::new("src/lib.rs"))
+            .unwrap();
+        let chunker = GptChunker::new("gpt-4o").unwrap();
+        chunk_at_boundaries(source, &symbols, max_tokens, overlap, &chunker).unwrap()
+    }
+
+    #[test]
+    fn chunks_break_between_symbols_and_cover_the_file()
+    {
+        let source = "use std::fmt;\n\n\
+                      /// One\nfn one() -> u32\n{\n    1\n}\n\n\
+                      fn two() -> u32\n{\n    2\n}\n\n\
+                      struct Cache;\n\n\
+                      impl Cache\n{\n    fn get(&self) -> u32\n    {\n        3\n    }\n\n    \
+                      fn put(&self) -> u32\n    {\n        4\n    }\n}\n";
+        let whole = chunks(source, 4000, None);
+        assert_eq!(whole.len(), 1);
+        assert_eq!(whole[0].content, source);
+        assert_eq!((whole[0].start_line, whole[0].end_line), (1, 27));
+
+        // Small symbols merge, the impl splits at its methods, nothing is lost
+        let small = chunks(source, 24, None);
+        assert!(small.len() > 2, "{small:#?}");
+        assert_eq!(small.iter().map(|c| c.content.as_str()).collect::<String>(), source);
+        assert!(small[0].content.starts_with("use std::fmt;\n\n/// One\nfn one()"));
+        assert!(small.iter().all(|c| c.content.ends_with('\n')), "{small:#?}");
+        assert!(small.iter().any(|c| c.symbol_path.contains("get")), "{small:#?}");
+        for body in ["    1\n", "    2\n", "        3\n", "        4\n"]
+        {
+            assert!(small.iter().any(|c| c.content.contains(body)), "{body}: {small:#?}");
+        }
+    }
+
+    #[test]
+    fn oversized_symbols_split_between_lines()
+    {
+        let body: String = (0..40)
+            .map(|i| format!("    let v{i} = {i} * 7;\n"))
+            .collect();
+        let source = format!("fn big()\n{{\n{body}}}\n");
+        let parts = chunks(&source, 60, None);
+        assert!(parts.len() > 1);
+        assert_eq!(parts[0].symbol_path, "big[part_1]");
+        assert!(parts.iter().all(|c| c.token_count <= 60), "{parts:#?}");
+        assert!(parts.iter().all(|c| c.content.ends_with('\n')));
+        assert_eq!(parts.iter().map(|c| c.content.as_str()).collect::<String>(), source);
+        assert_eq!(parts[1].start_line, parts[0].end_line + 1);
+    }
+
+    #[test]
+    fn chunks_repeat_the_lines_before_them()
+    {
+        let source: String = (1..=6)
+            .map(|i| format!("fn f{i}() -> u32\n{{\n    {i}\n}}\n"))
+            .collect();
+        let plain = chunks(&source, 30, None);
+        let lines = chunks(&source, 30, Some(ChunkOverlap::Lines(2)));
+        assert_eq!(plain.len(), lines.len());
+        assert!(plain.len() > 1);
+        assert_eq!(lines[0].content, plain[0].content);
+        assert_eq!(lines[1].start_line, plain[1].start_line - 2);
+        let repeated = lines[1]
+            .content
+            .strip_suffix(&plain[1].content)
+            .expect("overlap comes first");
+        assert!(plain[0].content.ends_with(repeated));
+        assert_eq!(repeated.matches('\n').count(), 2);
+
+        // Token overlap takes whole lines and stays within the budget
+        let tokens = chunks(&source, 30, Some(ChunkOverlap::Tokens(6)));
+        assert!(tokens.iter().all(|c| c.token_count <= 30), "{tokens:#?}");
+        assert!(tokens[1].start_line <= tokens[0].end_line, "{tokens:#?}");
+
+        // Token windows reach back over whole lines too
+        let chunker = GptChunker::new("gpt-4o").unwrap();
+        let windows = chunk_by_tokens(&chunker, &source, 20, ChunkOverlap::Lines(1)).unwrap();
+        assert!(windows.len() > 1);
+        assert!(windows.iter().all(|w| source.contains(&w.content)));
+        assert!(windows.iter().map(|w| w.content.len()).sum::<usize>() > source.len());
+
+        assert_eq!("40".parse(), Ok(ChunkOverlap::Tokens(40)));
+        assert_eq!("40tok".parse(), Ok(ChunkOverlap::Tokens(40)));
+        assert_eq!("3lines".parse(), Ok(ChunkOverlap::Lines(3)));
+        assert_eq!("3L".parse(), Ok(ChunkOverlap::Lines(3)));
+        assert!("lines".parse::<ChunkOverlap>().is_err());
+        assert!("3 pages".parse::<ChunkOverlap>().is_err());
+    }
+
+    #[test]
+    fn jsonl_records_carry_text_lines_and_language()
+    {
+        let source = "fn one() -> u32\n{\n    1\n}\n\nfn two() -> u32\n{\n    2\n}\n";
+        let parts = chunks(source, 16, None);
+        assert_eq!(parts.len(), 2);
+
+        let ids = stable_ids(&parts);
+        let record = jsonl_record(&parts[1], &ids[1]);
+        assert_eq!(record["id"], ids[1]);
+        assert_eq!(record["path"], "src/lib.rs");
+        assert_eq!(record["lang"], "rust");
+        assert_eq!(record["start_line"], 5);
+        assert_eq!(record["end_line"], 9);
+        assert_eq!(record["text"], "\nfn two() -> u32\n{\n    2\n}\n");
+        assert_eq!(record["tokens"], parts[1].token_count);
+
+        // Token windows know their lines too
+        let chunker = GptChunker::new("gpt-4o").unwrap();
+        let windows = chunk_by_tokens(&chunker, source, 8, ChunkOverlap::Tokens(2)).unwrap();
+        assert_eq!(windows[0].start_line, 1);
+        assert!(windows.iter().all(|w| w.start_line >= 1 && w.end_line >= w.start_line));
+    }
+
+    #[test]
+    fn unchanged_chunks_keep_their_ids()
+    {
+        let before = "fn one() -> u32\n{\n    1\n}\n\nfn two() -> u32\n{\n    2\n}\n";
+        let after = "fn one() -> u32\n{\n    1\n}\n\nfn two() -> u32\n{\n    22\n}\n";
+        let old = stable_ids(&chunks(before, 16, None));
+        let new = stable_ids(&chunks(after, 16, None));
+        assert_eq!(old.len(), 2);
+        assert_eq!(old[0], new[0]);
+        assert_ne!(old[1], new[1]);
+        assert_eq!(old[0].len(), 16);
+
+        let changes = ChunkChanges::between(&old, &new);
+        assert_eq!(changes.added, [new[1].clone()]);
+        assert_eq!(changes.removed, [old[1].clone()]);
+        assert_eq!(changes.unchanged, 1);
+
+        // The same text twice in a file still gets two IDs
+        let mut twice = chunks(before, 16, None);
+        twice[1].content = twice[0]
+            .content
+            .clone();
+        let ids = stable_ids(&twice);
+        assert_ne!(ids[0], ids[1]);
+
+        // Earlier IDs are read for the input and the files below it
+        let dir = tempfile::tempdir().unwrap();
+        let earlier = dir.path().join("earlier.jsonl");
+        let lines = [
+            format!("{{\"id\":\"{}\",\"path\":\"src/lib.rs\"}}", new[1]),
+            format!("{{\"id\":\"{}\",\"path\":\"src/lib.rs\",\"removed\":true}}", old[1]),
+            "{\"id\":\"x\",\"source_file\":\"tests/cli.rs\"}".to_string(),
+            "{\"chunk_id\":1,\"source_file\":\"src/old.rs\"}".to_string(),
+        ];
+        std::fs::write(&earlier, lines.join("\n")).unwrap();
+        assert_eq!(previous_ids(&earlier, Path::new("src/lib.rs")).unwrap(), [new[1].clone()]);
+        assert_eq!(previous_ids(&earlier, Path::new("src")).unwrap(), [new[1].clone()]);
+        assert_eq!(previous_ids(&earlier, Path::new("tests")).unwrap(), ["x"]);
+    }
+
+    #[test]
+    fn prose_breaks_at_headings_and_paragraphs()
+    {
+        let para = |word: &str| format!("{word} one two three. {word} four five six.\n");
+        let doc = format!(
+            "# Intro\n\n{}{}\n{}\n## Usage\n\n{}{}",
+            para("alpha"),
+            para("beta"),
+            para("gamma"),
+            para("delta"),
+            para("epsilon")
+        );
+        let chunker = GptChunker::new("gpt-4o").unwrap();
+        let parts = chunk_at_boundaries(&doc, &[], 40, None, &chunker).unwrap();
+        assert!(parts.len() > 1, "{parts:#?}");
+        assert_eq!(parts.iter().map(|c| c.content.as_str()).collect::<String>(), doc);
+        assert!(parts.iter().all(|c| c.token_count <= 40), "{parts:#?}");
+        assert!(parts.iter().any(|c| c.content.starts_with("## Usage")), "{parts:#?}");
+        assert!(parts.iter().all(|c| c.content.ends_with(".\n") || c.content.ends_with("\n\n")));
+
+        assert_eq!(boundary_score("\n", "## Usage\n"), 4);
+        assert_eq!(boundary_score("\n", "Text\n"), 3);
+        assert_eq!(boundary_score("It ends.\n", "Next\n"), 2);
+        assert_eq!(boundary_score("    x += 1;\n", "    /// Doc\n"), 2);
+        assert_eq!(boundary_score("    /// Doc\n", "    /// more\n"), 1);
+        assert_eq!(boundary_score("# Title\n", "## Sub\n"), 1);
+        assert_eq!(boundary_score("let x =\n", "    1;\n"), 1);
+    }
+
+    #[test]
+    fn in_flight_batches_stay_within_the_byte_budget()
+    {
+        assert_eq!(in_flight_batches(&[40, 50, 20, 90, 10], 100), [0..2, 2..3, 3..5]);
+        assert_eq!(in_flight_batches(&[300, 10, 10], 100), [0..1, 1..3]);
+        assert_eq!(in_flight_batches(&[10, 300], 100), [0..1, 1..2]);
+        assert!(in_flight_batches(&[], 100).is_empty());
+    }
+
+    #[test]
+    fn presets_fill_what_the_command_line_leaves_unset() -> Result<()>
+    {
+        use clap::Parser;
+
+        for name in BUILTIN_PRESETS
+        {
+            let preset = find_preset(name, &Default::default())?;
+            GptChunker::new(&preset.model.unwrap())?;
+        }
+
+        let configured = [(
+            "Docs".to_string(),
+            ChunkPreset { max_tokens: Some(900), overlap: Some("2lines".into()), model: None },
+        )]
+        .into();
+        let mut args = crate::cli::ChunkArgs::parse_from(["chunk", "src", "--max-tokens", "300"]);
+        apply_preset(&mut args, &find_preset("Embedding-512", &configured)?)?;
+        assert_eq!(args.model.as_deref(), Some("text-embedding-3-small"));
+        assert_eq!(args.max_tokens, Some(300));
+        assert_eq!(args.overlap, Some(ChunkOverlap::Tokens(64)));
+
+        let mut args = crate::cli::ChunkArgs::parse_from(["chunk", "src"]);
+        apply_preset(&mut args, &find_preset("docs", &configured)?)?;
+        assert_eq!((args.model, args.max_tokens), (None, Some(900)));
+        assert_eq!(args.overlap, Some(ChunkOverlap::Lines(2)));
+
+        let err = find_preset("huge", &configured).unwrap_err().to_string();
+        assert!(err.contains("Docs, claude-200k, embedding-512, gpt-4o"), "{err}");
+        Ok(())
+    }
+}
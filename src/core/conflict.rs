@@ -7,10 +7,15 @@
 //! - O(N) single-pass parsing with memchr optimization
 //! - Deterministic confidence scoring for auto-resolution
 
-use std::{io::BufRead, path::PathBuf};
+use std::{
+    io::BufRead,
+    path::{Path, PathBuf},
+};
 
 use anyhow::Result;
 
+use crate::core::conflict_blame::Attribution;
+
 /// Source of the conflict for provenance and error reporting
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum ConflictOrigin
@@ -73,6 +78,8 @@ pub struct ConflictMarker
     pub base: Option<String>,
     /// Confidence score for auto-resolution [0.0, 1.0]
     pub confidence: f32,
+    /// Commits behind each side, filled in by `conflict_blame::attribute`
+    pub attribution: Option<Attribution>,
 }
 
 /// State machine for parsing Git conflict markers
@@ -85,18 +92,52 @@ enum ParseState
     InTheirs, // Accumulating theirs section
 }
 
+/// Problem found while parsing conflict markers; the affected block is left
+/// in the file as is rather than guessed at
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct ParseDiagnostic
+{
+    pub file: PathBuf,
+    /// 1-based line of the marker that gave the problem away
+    pub line: usize,
+    pub message: String,
+}
+
 /// Parse all conflicts in a file from a buffered reader
 ///
 /// Uses O(N) single-pass state machine with byte-level scanning.
 /// Handles non-UTF-8 content gracefully with lossy decoding.
+/// Diagnostics for malformed blocks are logged; see
+/// `parse_conflicts_with_diagnostics` to collect them.
 ///
 /// Performance: <100ms for 100KB files with 10+ conflicts
 pub fn parse_conflicts<R: BufRead>(
     file: PathBuf,
-    mut reader: R,
+    reader: R,
 ) -> Result<Vec<ConflictMarker>>
+{
+    let (conflicts, diagnostics) = parse_conflicts_with_diagnostics(file, reader)?;
+    for d in &diagnostics
+    {
+        tracing::warn!("{}:{}: {}", d.file.display(), d.line, d.message);
+    }
+    Ok(conflicts)
+}
+
+/// Parse conflicts, returning diagnostics for blocks that were not usable.
+///
+/// Markers only count at the header's marker size (7 unless git was told
+/// otherwise), so longer `=======` rulers in content are not separators.
+/// A header of the same size inside a conflict opens a nested block that is
+/// kept, markers and all, as content of the enclosing side. Blocks closed
+/// out of order or left open at EOF are reported and skipped.
+pub fn parse_conflicts_with_diagnostics<R: BufRead>(
+    file: PathBuf,
+    mut reader: R,
+) -> Result<(Vec<ConflictMarker>, Vec<ParseDiagnostic>)>
 {
     let mut conflicts = Vec::new();
+    let mut diagnostics = Vec::new();
     let mut buffer = Vec::new();
     let mut state = ParseState::Scanning;
 
@@ -113,6 +154,13 @@ pub fn parse_conflicts<R: BufRead>(
     let mut ours_meta = String::new();
     let mut theirs_meta;
     let mut has_base = false;
+    // Marker size of the open conflict, and how deep in nested blocks we are
+    let mut size = 0usize;
+    let mut depth = 0usize;
+
+    let mut diagnose = |line: usize, message: String| {
+        diagnostics.push(ParseDiagnostic { file: file.clone(), line, message });
+    };
 
     loop
     {
@@ -129,14 +177,47 @@ pub fn parse_conflicts<R: BufRead>(
         // Detection is column-0 anchored; operate on raw bytes
         let lb = line_bytes; // alias for clarity
 
+        // Inside a nested block every line is content of the current side
+        if state != ParseState::Scanning
+            && (depth > 0 || marker_run(lb, b'<') == Some(size))
+        {
+            if marker_run(lb, b'<') == Some(size)
+            {
+                if depth == 0
+                {
+                    diagnose(
+                        line_no,
+                        format!(
+                            "nested conflict markers kept as content of the conflict at line {}",
+                            conflict_start_line
+                        ),
+                    );
+                }
+                depth += 1;
+            }
+            else if is_trl_b(lb, size)
+            {
+                depth -= 1;
+            }
+            match state
+            {
+                ParseState::InOurs => ours_content.extend_from_slice(lb),
+                ParseState::InBase => base_content.extend_from_slice(lb),
+                _ => theirs_content.extend_from_slice(lb),
+            }
+            byte_pos += bytes_read;
+            continue;
+        }
+
         match state
         {
             ParseState::Scanning =>
             {
                 // Look for conflict start marker: ≥7 consecutive '<' at column 0
-                if is_hdr_b(lb)
+                if let Some(run) = marker_run(lb, b'<')
                 {
                     state = ParseState::InOurs;
+                    size = run;
                     conflict_start_byte = byte_pos;
                     conflict_start_line = line_no;
                     ours_content.clear();
@@ -151,17 +232,29 @@ pub fn parse_conflicts<R: BufRead>(
 
             ParseState::InOurs =>
             {
-                if is_base_b(lb)
+                if is_base_b(lb, size)
                 {
-                    // Start of base section (3-way conflict)
+                    // Start of base section (3-way conflict); the label may be
+                    // anything, e.g. `merged common ancestors`
                     state = ParseState::InBase;
                     has_base = true;
                 }
-                else if is_sep_b(lb)
+                else if is_sep_b(lb, size)
                 {
                     // Start of theirs section (skip base for 2-way)
                     state = ParseState::InTheirs;
                 }
+                else if is_trl_b(lb, size)
+                {
+                    diagnose(
+                        line_no,
+                        format!(
+                            "conflict at line {} closes without a ======= separator; skipped",
+                            conflict_start_line
+                        ),
+                    );
+                    state = ParseState::Scanning;
+                }
                 else
                 {
                     // Accumulate ours content
@@ -171,11 +264,22 @@ pub fn parse_conflicts<R: BufRead>(
 
             ParseState::InBase =>
             {
-                if is_sep_b(lb)
+                if is_sep_b(lb, size)
                 {
                     // Start of theirs section
                     state = ParseState::InTheirs;
                 }
+                else if is_base_b(lb, size) || is_trl_b(lb, size)
+                {
+                    diagnose(
+                        line_no,
+                        format!(
+                            "conflict at line {} has a malformed base section; skipped",
+                            conflict_start_line
+                        ),
+                    );
+                    state = ParseState::Scanning;
+                }
                 else
                 {
                     // Accumulate base content
@@ -185,7 +289,7 @@ pub fn parse_conflicts<R: BufRead>(
 
             ParseState::InTheirs =>
             {
-                if is_trl_b(lb)
+                if is_trl_b(lb, size)
                 {
                     // End of conflict - extract metadata and create marker
                     theirs_meta = meta_bytes(lb, b'>');
@@ -205,8 +309,15 @@ pub fn parse_conflicts<R: BufRead>(
                         None
                     };
 
-                    // Calculate confidence score
-                    let confidence = score_conflict(&ours_str, &theirs_str, base_str.as_deref());
+                    // Calculate confidence score; meaningless differences are certain
+                    let confidence = if trivial_diff(&file, &ours_str, &theirs_str).is_some()
+                    {
+                        1.0
+                    }
+                    else
+                    {
+                        score_conflict(&ours_str, &theirs_str, base_str.as_deref())
+                    };
 
                     let marker = ConflictMarker {
                         file: file.clone(),
@@ -222,11 +333,23 @@ pub fn parse_conflicts<R: BufRead>(
                         theirs: theirs_str,
                         base: base_str,
                         confidence,
+                        attribution: None,
                     };
 
                     conflicts.push(marker);
                     state = ParseState::Scanning;
                 }
+                else if is_sep_b(lb, size) || is_base_b(lb, size)
+                {
+                    diagnose(
+                        line_no,
+                        format!(
+                            "conflict at line {} has a second separator; skipped",
+                            conflict_start_line
+                        ),
+                    );
+                    state = ParseState::Scanning;
+                }
                 else
                 {
                     // Accumulate theirs content
@@ -238,47 +361,67 @@ pub fn parse_conflicts<R: BufRead>(
         byte_pos += bytes_read;
     }
 
-    Ok(conflicts)
+    if state != ParseState::Scanning
+    {
+        diagnose(
+            conflict_start_line,
+            "conflict is never closed with >>>>>>>; skipped".to_string(),
+        );
+    }
+
+    Ok((conflicts, diagnostics))
 }
 
-/// Returns true if line starts with ≥7 of the given byte (column-0 anchored)
-fn starts_with_n(
+/// Length of the run of `ch` opening `line` when it forms a marker: at least
+/// 7 of them (column-0 anchored), then a space, a line ending, or nothing
+fn marker_run(
     line: &[u8],
     ch: u8,
-) -> bool
+) -> Option<usize>
 {
-    if line.len() < 7
-    {
-        return false;
-    } // fast fail
-    // Check first 7 bytes equal to ch
-    line.iter()
-        .take(7)
-        .all(|&b| b == ch)
-}
-
-/// Check for conflict start header: "<<<<<<<<"
-fn is_hdr_b(line: &[u8]) -> bool
-{
-    starts_with_n(line, b'<')
+    let run = line
+        .iter()
+        .take_while(|&&b| b == ch)
+        .count();
+    if run < 7
+    {
+        return None;
+    }
+    match line.get(run)
+    {
+        None | Some(b' ' | b'\t' | b'\r' | b'\n') => Some(run),
+        _ => None,
+    }
 }
 
-/// Check for base section marker: "|||||||"
-fn is_base_b(line: &[u8]) -> bool
+/// Check for base section marker of the open conflict's size: "|||||||"
+fn is_base_b(
+    line: &[u8],
+    size: usize,
+) -> bool
 {
-    starts_with_n(line, b'|')
+    marker_run(line, b'|') == Some(size)
 }
 
-/// Check for separator marker: "======="
-fn is_sep_b(line: &[u8]) -> bool
+/// Check for separator marker: "=======", with nothing after it
+fn is_sep_b(
+    line: &[u8],
+    size: usize,
+) -> bool
 {
-    starts_with_n(line, b'=')
+    marker_run(line, b'=') == Some(size)
+        && line[size..]
+            .iter()
+            .all(u8::is_ascii_whitespace)
 }
 
 /// Check for conflict end trailer: ">>>>>>>"
-fn is_trl_b(line: &[u8]) -> bool
+fn is_trl_b(
+    line: &[u8],
+    size: usize,
+) -> bool
 {
-    starts_with_n(line, b'>')
+    marker_run(line, b'>') == Some(size)
 }
 
 /// Extract trailing metadata after the marker run (and one optional space)
@@ -323,16 +466,36 @@ pub fn score_conflict(
     base: Option<&str>,
 ) -> f32
 {
-    let mut score = 0.0;
+    score_breakdown(ours, theirs, base).total
+}
+
+/// Weighted factors behind `score_conflict`, for `resolve --explain`
+#[derive(Debug, Clone, Copy, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct ScoreBreakdown
+{
+    pub whitespace_only: f32,
+    pub addition_only: f32,
+    /// Line-set Jaccard similarity of the two sides, before weighting
+    pub similarity: f32,
+    pub context_agreement: f32,
+    pub balanced_delimiters: f32,
+    pub base_lineage: f32,
+    /// Sum of the weighted factors, clamped to 1.0
+    pub total: f32,
+}
 
+/// Score `ours` against `theirs`, keeping each factor's contribution
+pub fn score_breakdown(
+    ours: &str,
+    theirs: &str,
+    base: Option<&str>,
+) -> ScoreBreakdown
+{
     // Factor 1: Whitespace-only differences (0.40 weight)
     let ours_normalized = normalize_for_scoring(ours);
     let theirs_normalized = normalize_for_scoring(theirs);
 
-    if ours_normalized == theirs_normalized
-    {
-        score += 0.40;
-    }
+    let whitespace_only = if ours_normalized == theirs_normalized { 0.40 } else { 0.0 };
 
     // Factor 2: Addition-only changes (0.25 weight)
     let ours_empty = ours_normalized
@@ -342,40 +505,62 @@ pub fn score_conflict(
         .trim()
         .is_empty();
 
-    if ours_empty || theirs_empty
-    {
-        score += 0.25; // One side empty - clear addition
-    }
-    else if is_superset(&ours_normalized, &theirs_normalized)
+    // One side empty (clear addition), or one side contains the other
+    let addition_only = if ours_empty
+        || theirs_empty
+        || is_superset(&ours_normalized, &theirs_normalized)
         || is_superset(&theirs_normalized, &ours_normalized)
     {
-        score += 0.25; // One side contains the other
+        0.25
     }
+    else
+    {
+        0.0
+    };
 
     // Factor 3: Context agreement (0.20 weight)
     // Simple similarity metric - can be enhanced with Levenshtein distance
     let similarity = calculate_similarity(&ours_normalized, &theirs_normalized);
-    score += 0.20 * similarity;
+    let context_agreement = 0.20 * similarity;
 
     // Factor 4: Balanced delimiters (0.10 weight) - proxy until AST integration
-    if has_balanced_delimiters(ours) && has_balanced_delimiters(theirs)
-    {
-        score += 0.10;
-    }
+    let balanced_delimiters =
+        if has_balanced_delimiters(ours) && has_balanced_delimiters(theirs) { 0.10 } else { 0.0 };
 
-    // Consider base content if available
-    if let Some(base_content) = base
+    // Consider base content if available: one side matching base exactly is
+    // a small boost for clear lineage
+    let base_lineage = match base
     {
-        let base_normalized = normalize_for_scoring(base_content);
-
-        // If one side matches base exactly, boost confidence
-        if ours_normalized == base_normalized || theirs_normalized == base_normalized
+        Some(base_content) =>
         {
-            score += 0.05; // Small boost for clear lineage
+            let base_normalized = normalize_for_scoring(base_content);
+            if ours_normalized == base_normalized || theirs_normalized == base_normalized
+            {
+                0.05
+            }
+            else
+            {
+                0.0
+            }
         }
+        None => 0.0,
+    };
+
+    let total = (whitespace_only
+        + addition_only
+        + context_agreement
+        + balanced_delimiters
+        + base_lineage)
+        .min(1.0); // Clamp to [0.0, 1.0]
+    ScoreBreakdown {
+        whitespace_only,
+        addition_only,
+        similarity,
+        context_agreement,
+        balanced_delimiters,
+        base_lineage,
+        total,
     }
-
-    score.min(1.0) // Clamp to [0.0, 1.0]
 }
 
 /// Normalize content for scoring by removing insignificant whitespace
@@ -390,6 +575,137 @@ fn normalize_for_scoring(content: &str) -> String
         .join("\n")
 }
 
+/// How two conflict sides can differ without changing what the code does
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TrivialDiff
+{
+    /// Indentation, trailing spaces, blank lines
+    Whitespace,
+    /// Comments (and whitespace), for languages with known comment syntax
+    Comments,
+}
+
+/// Whether `ours` and `theirs` differ only in whitespace or only in
+/// comments. Leading indentation counts for indentation-sensitive files.
+pub fn trivial_diff(
+    file: &Path,
+    ours: &str,
+    theirs: &str,
+) -> Option<TrivialDiff>
+{
+    let ext = file
+        .extension()
+        .and_then(|e| e.to_str())
+        .unwrap_or("");
+    let normalize = |text: &str| {
+        if matches!(ext, "py" | "pyi" | "yaml" | "yml")
+        {
+            text.lines()
+                .map(str::trim_end)
+                .filter(|line| !line.is_empty())
+                .collect::<Vec<_>>()
+                .join("\n")
+        }
+        else
+        {
+            normalize_for_scoring(text)
+        }
+    };
+
+    if normalize(ours) == normalize(theirs)
+    {
+        return Some(TrivialDiff::Whitespace);
+    }
+    let syntax = comment_syntax(ext)?;
+    (normalize(&strip_comments(ours, &syntax)) == normalize(&strip_comments(theirs, &syntax)))
+        .then_some(TrivialDiff::Comments)
+}
+
+/// Comment markers of a language, plus the quotes that open string literals
+struct CommentSyntax
+{
+    line: &'static str,
+    block: Option<(&'static str, &'static str)>,
+    quotes: &'static [char],
+}
+
+fn comment_syntax(ext: &str) -> Option<CommentSyntax>
+{
+    let (line, block, quotes): (_, _, &'static [char]) = match ext
+    {
+        "rs" | "go" | "c" | "h" | "cc" | "cpp" | "cxx" | "hpp" | "hh" | "java" | "kt" | "cs"
+        | "swift" | "scala" => ("//", Some(("/*", "*/")), &['"']),
+        "js" | "jsx" | "mjs" | "cjs" | "ts" | "tsx" =>
+        {
+            ("//", Some(("/*", "*/")), &['"', '\'', '`'])
+        }
+        "py" | "pyi" | "sh" | "bash" | "rb" | "toml" | "yaml" | "yml" => ("#", None, &['"', '\'']),
+        "sql" | "lua" | "hs" => ("--", None, &['\'']),
+        _ => return None,
+    };
+    Some(CommentSyntax { line, block, quotes })
+}
+
+/// `text` with comments removed; string literals are left alone so a
+/// `//` inside a URL is not mistaken for a comment
+fn strip_comments(
+    text: &str,
+    syntax: &CommentSyntax,
+) -> String
+{
+    let mut out = String::with_capacity(text.len());
+    let mut rest = text;
+    let mut quote: Option<char> = None;
+    while let Some(c) = rest
+        .chars()
+        .next()
+    {
+        let mut len = c.len_utf8();
+        match quote
+        {
+            Some(_) if c == '\\' =>
+            {
+                len += rest[len..]
+                    .chars()
+                    .next()
+                    .map_or(0, char::len_utf8);
+            }
+            Some(q) if c == q => quote = None,
+            Some(_) =>
+            {}
+            None =>
+            {
+                if let Some((open, close)) = syntax.block
+                    && rest.starts_with(open)
+                {
+                    let body = &rest[open.len()..];
+                    rest = body
+                        .find(close)
+                        .map_or("", |i| &body[i + close.len()..]);
+                    out.push(' ');
+                    continue;
+                }
+                if rest.starts_with(syntax.line)
+                {
+                    rest = rest
+                        .find('\n')
+                        .map_or("", |i| &rest[i..]);
+                    continue;
+                }
+                if syntax
+                    .quotes
+                    .contains(&c)
+                {
+                    quote = Some(c);
+                }
+            }
+        }
+        out.push_str(&rest[..len]);
+        rest = &rest[len..];
+    }
+    out
+}
+
 /// Check if big contains small as contiguous substring (order-aware)
 fn is_superset(
     big: &str,
@@ -490,21 +806,91 @@ mod tests
     fn test_conflict_marker_detection()
     {
         // Test byte-level detection functions
-        assert!(is_hdr_b(b"<<<<<<<"));
-        assert!(is_hdr_b(b"<<<<<<< HEAD"));
-        assert!(!is_hdr_b(b"<<<<<< not enough"));
-        assert!(!is_hdr_b(b" <<<<<<< indented")); // Column-0 requirement
+        assert!(marker_run(b"<<<<<<<", b'<').is_some());
+        assert!(marker_run(b"<<<<<<< HEAD", b'<').is_some());
+        assert!(marker_run(b"<<<<<< not enough", b'<').is_none());
+        assert!(marker_run(b" <<<<<<< indented", b'<').is_none()); // Column-0 requirement
+        assert!(marker_run(b"<<<<<<<<x", b'<').is_none()); // Run must end the marker
+
+        assert!(is_base_b(b"|||||||", 7));
+        assert!(is_base_b(b"||||||| base", 7));
+        assert!(is_base_b(b"||||||| merged common ancestors\r\n", 7));
+        assert!(!is_base_b(b"|||||| not enough", 7));
+
+        assert!(is_sep_b(b"=======", 7));
+        assert!(is_sep_b(b"=======\r\n", 7));
+        assert!(!is_sep_b(b"====== not enough", 7));
+        assert!(!is_sep_b(b"==========", 7)); // Ruler, not the marker size
+        assert!(!is_sep_b(b"======= text", 7));
+
+        assert!(is_trl_b(b">>>>>>>", 7));
+        assert!(is_trl_b(b">>>>>>> feature/x", 7));
+        assert!(!is_trl_b(b">>>>>> not enough", 7));
+    }
 
-        assert!(is_base_b(b"|||||||"));
-        assert!(is_base_b(b"||||||| base"));
-        assert!(!is_base_b(b"|||||| not enough"));
+    fn parse_diag(input: &str) -> (Vec<ConflictMarker>, Vec<ParseDiagnostic>)
+    {
+        parse_conflicts_with_diagnostics(PathBuf::from("t.txt"), Cursor::new(input.as_bytes()))
+            .unwrap()
+    }
+
+    #[test]
+    fn test_nested_conflict_stays_inside_outer_side()
+    {
+        let input = "<<<<<<< HEAD\na\n<<<<<<< inner\nb\n=======\nc\n>>>>>>> inner\n=======\n\
+                     d\n>>>>>>> topic\ntail\n";
+        let (conflicts, diags) = parse_diag(input);
+        assert_eq!(conflicts.len(), 1);
+        assert_eq!(conflicts[0].ours, "a\n<<<<<<< inner\nb\n=======\nc\n>>>>>>> inner\n");
+        assert_eq!(conflicts[0].theirs, "d\n");
+        assert_eq!(conflicts[0].line_range, (1, 10));
+        assert_eq!(diags.len(), 1);
+        assert_eq!(diags[0].line, 3);
+    }
 
-        assert!(is_sep_b(b"======="));
-        assert!(!is_sep_b(b"====== not enough"));
+    #[test]
+    fn test_crlf_rulers_and_ancestor_labels()
+    {
+        let input = "<<<<<<< HEAD\r\na\r\n==========\r\n||||||| merged common ancestors\r\n\
+                     b\r\n=======\r\nc\r\n>>>>>>> topic\r\n";
+        let (conflicts, diags) = parse_diag(input);
+        assert!(diags.is_empty());
+        assert_eq!(conflicts[0].ours, "a\r\n==========\r\n");
+        assert_eq!(conflicts[0].base.as_deref(), Some("b\r\n"));
+        assert_eq!(conflicts[0].theirs, "c\r\n");
+        assert_eq!(conflicts[0].byte_range.1, input.len());
+
+        // Marker size follows the header (git's conflict-marker-size)
+        let (conflicts, _) = parse_diag("<<<<<<<<< HEAD\n=======\na\n=========\nb\n>>>>>>>>> x\n");
+        assert_eq!(
+            (
+                conflicts[0]
+                    .ours
+                    .as_str(),
+                conflicts[0]
+                    .theirs
+                    .as_str()
+            ),
+            ("=======\na\n", "b\n")
+        );
+    }
 
-        assert!(is_trl_b(b">>>>>>>"));
-        assert!(is_trl_b(b">>>>>>> feature/x"));
-        assert!(!is_trl_b(b">>>>>> not enough"));
+    #[test]
+    fn test_malformed_blocks_are_reported_not_misparsed()
+    {
+        // Closed without a separator, then a good conflict
+        let input = "<<<<<<< HEAD\na\n>>>>>>> topic\n<<<<<<< HEAD\nb\n=======\nc\n>>>>>>> topic\n";
+        let (conflicts, diags) = parse_diag(input);
+        assert_eq!(conflicts.len(), 1);
+        assert_eq!(conflicts[0].line_range, (4, 8));
+        assert_eq!(diags[0].line, 3);
+
+        // Never closed
+        let (conflicts, diags) = parse_diag("x\n<<<<<<< HEAD\na\n=======\nb\n");
+        assert!(conflicts.is_empty());
+        assert_eq!(diags.len(), 1);
+        assert_eq!(diags[0].line, 2);
+        assert!(diags[0].message.contains("never closed"));
     }
 
     #[test]
@@ -633,6 +1019,38 @@ fn greet(name: &str) {
         );
     }
 
+    #[test]
+    fn test_score_breakdown_sums_to_score()
+    {
+        let b = score_breakdown("a\nb\n", "a\n", Some("a\n"));
+        assert_eq!(b.whitespace_only, 0.0);
+        assert_eq!(b.addition_only, 0.25);
+        assert_eq!(b.similarity, 0.5);
+        assert_eq!(b.base_lineage, 0.05);
+        assert_eq!(b.total, score_conflict("a\nb\n", "a\n", Some("a\n")));
+        assert!((b.total - 0.50).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_trivial_diff()
+    {
+        let rs = Path::new("a.rs");
+        assert_eq!(trivial_diff(rs, "  x();\n", "x();\n\n"), Some(TrivialDiff::Whitespace));
+        assert_eq!(
+            trivial_diff(rs, "x(); // old\n/* a\n b */\n", "x(); // new\n"),
+            Some(TrivialDiff::Comments)
+        );
+        // Comment markers inside strings are code
+        assert_eq!(trivial_diff(rs, "u(\"http://a\");\n", "u(\"http://b\");\n"), None);
+        assert_eq!(trivial_diff(rs, "x(1); // a\n", "x(2); // a\n"), None);
+
+        let py = Path::new("a.py");
+        assert_eq!(trivial_diff(py, "x = 1  # one\n", "x = 1\n"), Some(TrivialDiff::Comments));
+        assert_eq!(trivial_diff(py, "    return 1\n", "return 1\n"), None);
+        // No known comment syntax: only whitespace counts
+        assert_eq!(trivial_diff(Path::new("a.txt"), "a // b\n", "a\n"), None);
+    }
+
     #[test]
     fn test_balanced_delimiters()
     {
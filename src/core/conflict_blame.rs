@@ -0,0 +1,296 @@
+//! Who changed what in a conflict, for `rup resolve`
+//!
+//! Each side of a conflict block is found in the commit it came from (the
+//! marker label when it names one; otherwise HEAD for ours and the
+//! in-progress MERGE_HEAD, CHERRY_PICK_HEAD, REVERT_HEAD or REBASE_HEAD for
+//! theirs) and blamed there, so every block carries the commits and authors
+//! behind each side. Attribution is best effort: outside a repository, or
+//! when a side no longer appears verbatim in its commit, that side is empty.
+
+use std::{collections::HashMap, fmt, path::Path, process::Command};
+
+use serde::{Deserialize, Serialize};
+
+use crate::core::conflict::{ConflictMarker, ConflictType};
+
+/// Refs tried for the incoming side when its label does not name a commit
+const THEIRS_HEADS: &[&str] = &["MERGE_HEAD", "CHERRY_PICK_HEAD", "REVERT_HEAD", "REBASE_HEAD"];
+
+/// A commit that last touched some lines of one side
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BlameCommit
+{
+    pub commit: String,
+    pub author: String,
+    /// Author date, `YYYY-MM-DD`
+    pub date: String,
+    pub summary: String,
+    /// Lines of the side this commit accounts for
+    pub lines: usize,
+}
+
+impl fmt::Display for BlameCommit
+{
+    fn fmt(
+        &self,
+        f: &mut fmt::Formatter<'_>,
+    ) -> fmt::Result
+    {
+        let short = self
+            .commit
+            .get(..8)
+            .unwrap_or(&self.commit);
+        write!(f, "{short} {} {}: {}", self.date, self.author, self.summary)
+    }
+}
+
+/// Commits behind each side of a conflict, most lines first
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Attribution
+{
+    pub ours: Vec<BlameCommit>,
+    pub theirs: Vec<BlameCommit>,
+}
+
+/// Blame both sides of every conflict in `rel` (a path relative to
+/// `repo_root`) and store the result on each conflict
+pub fn attribute(
+    repo_root: &Path,
+    rel: &Path,
+    conflicts: &mut [ConflictMarker],
+)
+{
+    let Some(path) = rel.to_str()
+    else
+    {
+        return;
+    };
+    let mut revs: HashMap<String, Option<String>> = HashMap::new();
+    let mut blobs: HashMap<String, Option<String>> = HashMap::new();
+
+    for conflict in conflicts
+    {
+        let (ours_label, theirs_label) = match &conflict.conflict_type
+        {
+            ConflictType::GitMarkers { ours_meta, theirs_meta, .. } =>
+            {
+                (ours_meta.clone(), theirs_meta.clone())
+            }
+            _ => continue,
+        };
+        let line = conflict
+            .line_range
+            .0;
+        let mut side = |label: &str, fallbacks: &[&str], text: &str| {
+            let rev = revs
+                .entry(label.to_string())
+                .or_insert_with(|| resolve_rev(repo_root, label, fallbacks))
+                .clone()?;
+            let content = blobs
+                .entry(rev.clone())
+                .or_insert_with(|| git(repo_root, &["show", &format!("{rev}:{path}")]))
+                .as_deref()?;
+            let (start, len) = locate(content, text, line)?;
+            let range = format!("{},{}", start, start + len - 1);
+            let porcelain = git(repo_root, &[
+                "blame",
+                "--porcelain",
+                "-L",
+                &range,
+                &rev,
+                "--",
+                path,
+            ])?;
+            Some(parse_porcelain(&porcelain))
+        };
+        let attribution = Attribution {
+            ours: side(&ours_label, &["HEAD"], &conflict.ours).unwrap_or_default(),
+            theirs: side(&theirs_label, THEIRS_HEADS, &conflict.theirs).unwrap_or_default(),
+        };
+        if !attribution
+            .ours
+            .is_empty()
+            || !attribution
+                .theirs
+                .is_empty()
+        {
+            conflict.attribution = Some(attribution);
+        }
+    }
+}
+
+/// Commit named by the label's first word, else the first fallback ref
+/// that exists
+fn resolve_rev(
+    repo_root: &Path,
+    label: &str,
+    fallbacks: &[&str],
+) -> Option<String>
+{
+    label
+        .split_whitespace()
+        .next()
+        .into_iter()
+        .chain(
+            fallbacks
+                .iter()
+                .copied(),
+        )
+        .find_map(|name| {
+            git(repo_root, &[
+                "rev-parse",
+                "--verify",
+                "--quiet",
+                &format!("{name}^{{commit}}"),
+            ])
+        })
+        .map(|out| {
+            out.trim()
+                .to_string()
+        })
+}
+
+/// 1-based first line and line count of `side` within `content`, taking the
+/// occurrence nearest `near` when it appears more than once
+fn locate(
+    content: &str,
+    side: &str,
+    near: usize,
+) -> Option<(usize, usize)>
+{
+    let needle: Vec<&str> = side
+        .lines()
+        .collect();
+    let hay: Vec<&str> = content
+        .lines()
+        .collect();
+    if needle.is_empty() || needle.len() > hay.len()
+    {
+        return None;
+    }
+    (0..=hay.len() - needle.len())
+        .filter(|&i| hay[i..i + needle.len()] == needle[..])
+        .min_by_key(|&i| (i + 1).abs_diff(near))
+        .map(|i| (i + 1, needle.len()))
+}
+
+/// Commits in `git blame --porcelain` output, most lines first
+fn parse_porcelain(out: &str) -> Vec<BlameCommit>
+{
+    let mut commits: Vec<BlameCommit> = Vec::new();
+    let mut current = 0;
+    for line in out.lines()
+    {
+        if line.starts_with('\t')
+        {
+            if let Some(commit) = commits.get_mut(current)
+            {
+                commit.lines += 1;
+            }
+            continue;
+        }
+        let (key, value) = line
+            .split_once(' ')
+            .unwrap_or((line, ""));
+        if key.len() == 40
+            && key
+                .bytes()
+                .all(|b| b.is_ascii_hexdigit())
+        {
+            current = match commits
+                .iter()
+                .position(|c| c.commit == key)
+            {
+                Some(i) => i,
+                None =>
+                {
+                    commits.push(BlameCommit { commit: key.to_string(), ..Default::default() });
+                    commits.len() - 1
+                }
+            };
+            continue;
+        }
+        let Some(commit) = commits.get_mut(current)
+        else
+        {
+            continue;
+        };
+        match key
+        {
+            "author" => commit.author = value.to_string(),
+            "summary" => commit.summary = value.to_string(),
+            "author-time" =>
+            {
+                commit.date = value
+                    .parse()
+                    .ok()
+                    .and_then(|t| chrono::DateTime::from_timestamp(t, 0))
+                    .map(|d| {
+                        d.format("%Y-%m-%d")
+                            .to_string()
+                    })
+                    .unwrap_or_default();
+            }
+            _ =>
+            {}
+        }
+    }
+    commits.sort_by(|a, b| {
+        b.lines
+            .cmp(&a.lines)
+    });
+    commits
+}
+
+/// Stdout of a successful git run in `repo_root`
+fn git(
+    repo_root: &Path,
+    args: &[&str],
+) -> Option<String>
+{
+    let out = Command::new("git")
+        .args(args)
+        .current_dir(repo_root)
+        .output()
+        .ok()?;
+    out.status
+        .success()
+        .then(|| String::from_utf8_lossy(&out.stdout).into_owned())
+}
+
+#[cfg(test)]
+mod tests
+{
+    use super::*;
+
+    const SHA_A: &str = "1111111111111111111111111111111111111111";
+    const SHA_B: &str = "2222222222222222222222222222222222222222";
+
+    #[test]
+    fn test_parse_porcelain_counts_lines_per_commit()
+    {
+        let out = format!(
+            "{SHA_A} 3 3 1\nauthor Ann\nauthor-time 1700000000\nsummary First\nfilename \
+             a.rs\n\tx\n{SHA_B} 4 4 2\nauthor Bo\nauthor-time 1700086400\nsummary \
+             Second\nfilename a.rs\n\ty\n{SHA_B} 5 5\n\tz\n"
+        );
+        let commits = parse_porcelain(&out);
+        assert_eq!(commits.len(), 2);
+        assert_eq!(commits[0].author, "Bo");
+        assert_eq!(commits[0].lines, 2);
+        assert_eq!(commits[0].date, "2023-11-15");
+        assert_eq!(commits[1].summary, "First");
+        assert_eq!(commits[0].to_string(), "22222222 2023-11-15 Bo: Second");
+    }
+
+    #[test]
+    fn test_locate_prefers_nearest_occurrence()
+    {
+        let content = "a\nx\nb\nx\nc\n";
+        assert_eq!(locate(content, "x\n", 4), Some((4, 1)));
+        assert_eq!(locate(content, "x\n", 1), Some((2, 1)));
+        assert_eq!(locate(content, "x\nb\n", 9), Some((2, 2)));
+        assert_eq!(locate(content, "y\n", 1), None);
+        assert_eq!(locate(content, "", 1), None);
+    }
+}
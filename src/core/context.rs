@@ -41,11 +41,20 @@ use crate::core::symbol_index::{
     LookupOptions, // search
     RankedSymbol,
     SymbolIndex,
+    exact_containers,
+    refs_path,
 };
-use crate::core::symbols::Symbol; // symbol def
+use crate::core::symbol_embeddings::{embedder, embeddings_path}; // --semantic vectors
+use crate::core::symbol_shards::{
+    index_exists,
+    manifest_path,
+    probe_path,
+    refresh_stale,
+}; // sharded index
+use crate::core::symbols::{Symbol, SymbolFilter}; // symbol def
 use crate::{
     cli_ext::anchor_cmd::{AnchorArgs, OutputFormat, validate_anchor_with_hints},
-    infra::io::read_file_smart,
+    infra::{io::read_file_smart, suppress::Suppressions},
 };
 use camino::Utf8Path;
 use crate::{
@@ -58,6 +67,7 @@ use crate::{
             TaggedItem, // budget tags
             fit_with_buckets,
             parse_bucket_caps,
+            token_cache_dir,
         },
         fail_signal::FailSignal,
     },
@@ -69,6 +79,7 @@ const DEFAULT_SCAN_WINDOW: usize = 120;
 const DEFAULT_FILES_PER_HOP: usize = 20;
 const DEFAULT_EDGES_LIMIT: usize = 500;
 const MAX_CALLGRAPH_DEPTH: u8 = 6;
+const CALL_DISTANCE_DEPTH: u8 = 2;
 const DEFAULT_CALL_SCAN_WINDOW: usize = 128;
 const MAX_FRESHNESS_DEPTH: usize = 5;
 const LOCKFILE_POLL_INTERVAL_MS: u64 = 200;
@@ -163,6 +174,8 @@ struct Collected
     fail_signals: Vec<FailSignal>,
     anchor_file: Option<PathBuf>,
     anchor_line: Option<usize>,
+    /// Function -> hop distance over the stored call edges, when indexed
+    call_hops: Option<BTreeMap<String, u8>>,
 }
 
 /// Final assembly output (pre-rendered string + token count).
@@ -818,12 +831,75 @@ impl ContextAssembler
 
     // =========================== Helper Methods =======================
 
+    /// Remove symbols suppressed by `rup: ignore-file` or
+    /// `rup: ignore-next-symbol` in their current source text
+    fn retain_unsuppressed(
+        root: &Path,
+        chosen: &mut Vec<RankedSymbol>,
+    )
+    {
+        // Scan each file once; unreadable files keep their hits
+        let mut cache: HashMap<PathBuf, (String, Suppressions)> = HashMap::new();
+
+        chosen.retain(|r| {
+            let abs = Self::resolve_path(root, &r.symbol.file);
+            let entry = cache
+                .entry(abs.clone())
+                .or_insert_with(|| {
+                    let text = StdFs::read_to_string(&abs).unwrap_or_default();
+                    let sup = Suppressions::scan(&text);
+                    (text, sup)
+                });
+
+            let (text, sup) = &*entry;
+            if sup.is_empty()
+            {
+                return true;
+            }
+            if sup.ignores_file()
+            {
+                return false;
+            }
+
+            let lines: Vec<&str> = text
+                .lines()
+                .collect();
+            !sup.suppresses_line(&lines, r.symbol.start_line)
+        });
+    }
+
     /// Check if symbols index is fresh compared to source files
-    fn index_is_fresh(
+    pub(crate) fn index_is_fresh(
+        root: &Path,
+        symbols_path: &Path,
+    ) -> bool
+    {
+        Self::index_fresh_from(root, symbols_path, 0)
+    }
+
+    /// Like [`Self::index_is_fresh`], but only for the files directly in
+    /// `root`, not its subdirectories
+    pub(crate) fn top_level_fresh(
         root: &Path,
         symbols_path: &Path,
     ) -> bool
     {
+        Self::index_fresh_from(root, symbols_path, MAX_FRESHNESS_DEPTH)
+    }
+
+    /// Freshness walk of `root` starting at `depth`
+    fn index_fresh_from(
+        root: &Path,
+        symbols_path: &Path,
+        depth: usize,
+    ) -> bool
+    {
+        // A running `rup symbols --watch` keeps it fresh without a walk
+        if crate::core::symbols_watch::watched_fresh(symbols_path)
+        {
+            return true;
+        }
+
         let symbols_metadata = match StdFs::metadata(symbols_path)
         {
             Ok(m) => m,
@@ -834,7 +910,7 @@ impl ContextAssembler
             .modified()
             .unwrap_or(std::time::UNIX_EPOCH);
 
-        Self::is_dir_fresh_recursive(root, symbols_mtime, 0)
+        Self::is_dir_fresh_recursive(root, symbols_mtime, depth)
     }
 
     /// Helper for recursive directory freshness checking
@@ -911,7 +987,7 @@ impl ContextAssembler
             else if let Some(ext) = path
                 .extension()
                 .and_then(|e| e.to_str())
-                && matches!(ext, "rs" | "py" | "js" | "ts" | "tsx" | "go" | "cpp" | "h")
+                && crate::core::symbols::language_for(ext).is_some()
                 && let Ok(mtime) = metadata.modified()
                 && mtime > symbols_mtime
             {
@@ -922,14 +998,44 @@ impl ContextAssembler
         true
     }
 
+    /// An existing, fresh index with the call sites `args` asks for
+    fn index_satisfies(
+        args: &crate::cli::SymbolsArgs,
+        symbols_path: &Path,
+    ) -> bool
+    {
+        symbols_path.exists()
+            && Self::index_is_fresh(&args.path, symbols_path)
+            && (!args.refs || refs_path(symbols_path).exists())
+    }
+
     /// Race-free symbols generation with lockfile and timeout
-    fn ensure_symbols_with_lock(
+    pub(crate) fn ensure_symbols_with_lock(
         args: &crate::cli::SymbolsArgs,
         ctx: &AppContext,
         symbols_path: &Path,
     ) -> Result<()>
+    {
+        Self::regenerate_with_lock(
+            symbols_path,
+            &|| Self::index_satisfies(args, symbols_path),
+            &|| crate::core::symbols::run(args.clone(), ctx),
+        )
+    }
+
+    /// Run `generate` under the lockfile next to `symbols_path` unless
+    /// `satisfied` already holds; waits out another holder of the lock
+    pub(crate) fn regenerate_with_lock(
+        symbols_path: &Path,
+        satisfied: &dyn Fn() -> bool,
+        generate: &dyn Fn() -> Result<()>,
+    ) -> Result<()>
     {
         let lock_path = symbols_path.with_extension("lock");
+        if let Some(dir) = lock_path.parent()
+        {
+            let _ = StdFs::create_dir_all(dir);
+        }
 
         // Try to create lockfile atomically
         match StdFs::OpenOptions::new()
@@ -940,7 +1046,7 @@ impl ContextAssembler
             Ok(_) =>
             {
                 // We got the lock, check freshness after acquiring
-                if symbols_path.exists() && Self::index_is_fresh(&args.path, symbols_path)
+                if satisfied()
                 {
                     // Already fresh, no need to regenerate
                     let _ = StdFs::remove_file(&lock_path);
@@ -948,7 +1054,7 @@ impl ContextAssembler
                 }
 
                 // Generate symbols
-                let result = crate::core::symbols::run(args.clone(), ctx);
+                let result = generate();
 
                 // Always clean up lock
                 let _ = StdFs::remove_file(&lock_path);
@@ -965,7 +1071,7 @@ impl ContextAssembler
                     std::thread::sleep(Duration::from_millis(LOCKFILE_POLL_INTERVAL_MS));
 
                     // Check if symbols appeared or lock disappeared
-                    if symbols_path.exists() && Self::index_is_fresh(&args.path, symbols_path)
+                    if satisfied()
                     {
                         return Ok(());
                     }
@@ -990,11 +1096,39 @@ impl ContextAssembler
                 }
 
                 // Lock disappeared but no symbols - retry once
-                Self::ensure_symbols_with_lock(args, ctx, symbols_path)
+                Self::regenerate_with_lock(symbols_path, satisfied, generate)
             }
         }
     }
 
+    /// Paths under the root a run is scoped to, which pick the shards of a
+    /// sharded index it reads: the anchor file and plain `--include` paths
+    fn shard_focus(args: &ContextArgs) -> Vec<PathBuf>
+    {
+        let root = &args.path;
+        let anchor = args
+            .anchor
+            .iter()
+            .map(|a| {
+                a.strip_prefix(root)
+                    .unwrap_or(a)
+                    .to_path_buf()
+            });
+        let include = args
+            .include
+            .iter()
+            .filter(|g| !g.contains(['*', '?', '[', '{']))
+            .map(PathBuf::from);
+        anchor
+            .chain(include)
+            .map(|p| {
+                p.components()
+                    .filter(|c| !matches!(c, std::path::Component::CurDir))
+                    .collect()
+            })
+            .collect()
+    }
+
     // =========================== Phase Implementation ================
 
     // =========================== Phase 1 ================================
@@ -1104,7 +1238,52 @@ impl ContextAssembler
 
         // Auto-index (race-free) if missing or stale
         let no_auto = std::env::var("ROUGHUP_NO_AUTO_INDEX").is_ok();
-        if !Path::new(&symbols_path).exists() && !no_auto
+        // A sharded index records call sites and vectors per shard
+        let sharded = manifest_path(&symbols_path).exists();
+        let probe = probe_path(&symbols_path);
+        // --callgraph walks the call edges recorded with the symbols
+        let has_refs = refs_path(&probe).exists();
+        // Keep existing embeddings in step with the symbols too
+        let has_embeddings = embeddings_path(&probe).exists();
+        let wants_refs = has_refs
+            || args
+                .callgraph
+                .is_some();
+        let sym_args = crate::cli::SymbolsArgs {
+            command: None,
+            path: args
+                .path
+                .clone(),
+            // Empty selects the configured languages plus external ones
+            languages: Vec::new(),
+            output: symbols_path.clone(),
+            include_private: cfg
+                .symbols
+                .include_private,
+            // Keep an existing call-site index in step with the symbols
+            refs: wants_refs,
+            signatures: cfg
+                .symbols
+                .signatures,
+            embeddings: has_embeddings,
+            format: crate::cli::SymbolsFormat::Jsonl,
+            watch: false,
+            shard: false,
+            kind: Vec::new(),
+            include: Vec::new(),
+            exclude: Vec::new(),
+        };
+        if sharded && !no_auto
+        {
+            // Only the shards this run reads are checked and rebuilt
+            let focus = Self::shard_focus(&args);
+            if let Err(e) = refresh_stale(&sym_args, &focus, ctx)
+                && !ctx.quiet
+            {
+                eprintln!("(warn) symbols shard regeneration failed: {e}");
+            }
+        }
+        else if !Path::new(&symbols_path).exists() && !no_auto
         {
             if let Some(parent) = symbols_path.parent()
                 && !parent
@@ -1120,19 +1299,6 @@ impl ContextAssembler
                     symbols_path.display()
                 );
             }
-            let sym_args = crate::cli::SymbolsArgs {
-                path: args
-                    .path
-                    .clone(),
-                languages: cfg
-                    .symbols
-                    .languages
-                    .clone(),
-                output: symbols_path.clone(),
-                include_private: cfg
-                    .symbols
-                    .include_private,
-            };
             if let Err(e) = Self::ensure_symbols_with_lock(&sym_args, ctx, &symbols_path)
                 && !ctx.quiet
             {
@@ -1140,26 +1306,20 @@ impl ContextAssembler
             }
         }
         else if Path::new(&symbols_path).exists()
-            && !Self::index_is_fresh(&args.path, &symbols_path)
+            && (!Self::index_is_fresh(&args.path, &symbols_path) || wants_refs && !has_refs)
             && !no_auto
         {
             if !ctx.quiet
             {
-                eprintln!("(info) symbols index stale; regenerating");
+                if has_refs || !wants_refs
+                {
+                    eprintln!("(info) symbols index stale; regenerating");
+                }
+                else
+                {
+                    eprintln!("(info) call graph missing; regenerating symbols with --refs");
+                }
             }
-            let sym_args = crate::cli::SymbolsArgs {
-                path: args
-                    .path
-                    .clone(),
-                languages: cfg
-                    .symbols
-                    .languages
-                    .clone(),
-                output: symbols_path.clone(),
-                include_private: cfg
-                    .symbols
-                    .include_private,
-            };
             let _ = Self::ensure_symbols_with_lock(&sym_args, ctx, &symbols_path);
         }
 
@@ -1187,7 +1347,8 @@ impl ContextAssembler
         // Guard: if symbols are missing, keep going; assemble/output phase
         // will format a consistent error JSON or bail in text mode.
         // Load index now; if missing, we return an empty chosen list.
-        let index = match SymbolIndex::load(&env.symbols_path)
+        let focus = Self::shard_focus(&env.args);
+        let index = match SymbolIndex::load_focused(&env.symbols_path, &focus)
         {
             Ok(ix) => ix,
             Err(_) =>
@@ -1203,6 +1364,7 @@ impl ContextAssembler
                     anchor_line: env
                         .args
                         .anchor_line,
+                    call_hops: None,
                 });
             }
         };
@@ -1272,15 +1434,39 @@ impl ContextAssembler
             .queries
             .clone();
 
+        // A re-exported alias also finds the item it names
+        for q in &env
+            .args
+            .queries
+        {
+            let name = q
+                .rsplit("::")
+                .next()
+                .unwrap_or(q);
+            effective_queries.extend(index.reexport_targets(name));
+        }
+
         if let Some(q) = env
             .args
             .trait_resolve
             .as_ref()
             && let Some((ty, method)) = Self::parse_trait_resolve(q)
         {
-            effective_queries.push(format!("trait {}", ty));
-            effective_queries.push(format!("impl {} for", ty));
-            effective_queries.push(format!("{}::{}", ty, method));
+            if index.has_relations()
+            {
+                // The type itself, then the method on every type related to it
+                effective_queries.push(ty.clone());
+                for t in index.type_family(&ty)
+                {
+                    effective_queries.push(format!("{}::{}", t, method));
+                }
+            }
+            else
+            {
+                effective_queries.push(format!("trait {}", ty));
+                effective_queries.push(format!("impl {} for", ty));
+                effective_queries.push(format!("{}::{}", ty, method));
+            }
         }
 
         if let Some(spec) = CallGraph::parse_callgraph_arg(
@@ -1292,10 +1478,18 @@ impl ContextAssembler
                 .as_ref(),
             env.args
                 .anchor_line,
-        ) && let Some((ref apath, aline)) = spec.anchor
-            && let Some(_func) = CallGraph::extract_function_name_at(&env.root, apath, aline)
+        )
         {
-            let names = CallGraph::collect_callgraph_names_bounded(&env.root, &spec);
+            let names = match CallGraph::collect_callgraph_hops_indexed(&env.root, &index, &spec)
+            {
+                // Stored edges: callees and callers alike, minus the anchor
+                Some(hops) => hops
+                    .into_iter()
+                    .filter(|&(_, hop)| hop > 0)
+                    .map(|(name, _)| name)
+                    .collect(),
+                None => CallGraph::collect_callgraph_names_bounded(&env.root, &spec),
+            };
 
             for n in names
             {
@@ -1342,6 +1536,8 @@ impl ContextAssembler
         let anchor_line = env
             .args
             .anchor_line;
+        let scope = SymbolFilter::new(&env.args.kind, &env.args.include, &env.args.exclude)?;
+        let kinds = scope.kinds();
         let opts = LookupOptions {
             semantic: env
                 .args
@@ -1350,14 +1546,52 @@ impl ContextAssembler
             anchor_line,
             history: Some(&env.hist_set),
             limit: env.effective_limit,
-            kinds: None,
+            kinds: kinds.as_deref(),
+            scope: Some(&scope),
+            embedding: None,
+            // Boost the anchor file's own package
+            package: None,
         };
 
+        // Embed the queries when semantic lookup can rank by similarity
+        let mut query_vectors: Vec<Vec<f32>> = Vec::new();
+        if opts.semantic
+            && index.has_embeddings()
+            && let Some(embedder) = embedder()
+        {
+            match embedder.embed(&deduped)
+            {
+                Ok(vectors) => query_vectors = vectors,
+                Err(e) if !env.ctx.quiet =>
+                {
+                    eprintln!("(warn) embedding queries failed, matching names instead: {e:#}")
+                }
+                Err(_) =>
+                {}
+            }
+        }
+
         // Accumulate chosen
         let mut chosen: Vec<RankedSymbol> = Vec::new();
-        for q in &deduped
+        for (i, q) in deduped
+            .iter()
+            .enumerate()
         {
-            let mut hits = index.lookup(q, opts.clone());
+            let embedding = query_vectors
+                .get(i)
+                .map(Vec::as_slice);
+            let mut hits = index.lookup(q, LookupOptions { embedding, ..opts.clone() });
+            let containers = exact_containers(q, &hits);
+            if containers.len() > 1 && !env.ctx.quiet
+            {
+                let more = if containers.len() > 3 { ", …" } else { "" };
+                eprintln!(
+                    "(info) '{q}' is defined in {} places ({}{more}); query {}::{q} for one",
+                    containers.len(),
+                    containers[..containers.len().min(3)].join(", "),
+                    containers[0]
+                );
+            }
             if env.effective_top_per_query > 0 && hits.len() > env.effective_top_per_query
             {
                 hits.truncate(env.effective_top_per_query);
@@ -1368,12 +1602,32 @@ impl ContextAssembler
         }
         pb.finish_and_clear();
 
+        // Drop hits that opted out via inline `rup:` directives (guards stale indexes)
+        Self::retain_unsuppressed(&env.root, &mut chosen);
+
+        // Hop distances for call-distance scoring, from the stored call edges
+        let call_hops = match (&anchor_file, anchor_line)
+        {
+            (Some(path), Some(line)) => CallGraph::collect_callgraph_hops_indexed(
+                &env.root,
+                &index,
+                &CallgraphSpec {
+                    anchor: Some((path.clone(), line)),
+                    depth: CALL_DISTANCE_DEPTH,
+                    files_per_hop: DEFAULT_FILES_PER_HOP,
+                    edges_limit: DEFAULT_EDGES_LIMIT,
+                },
+            ),
+            _ => None,
+        };
+
         Ok(Collected {
             deduped_queries: deduped,
             chosen,
             fail_signals,
             anchor_file,
             anchor_line,
+            call_hops,
         })
     }
 
@@ -1388,7 +1642,7 @@ impl ContextAssembler
         if col
             .chosen
             .is_empty()
-            && !Path::new(&env.symbols_path).exists()
+            && !index_exists(&env.symbols_path)
         {
             return Ok(Assembled {
                 final_content: String::new(),
@@ -1554,13 +1808,18 @@ impl ContextAssembler
             && let Some(anchor_fn) =
                 CallGraph::extract_function_name_at(&env.root, anchor_path, anchor_line)
         {
-            let hops = CallGraphHopper::collect_callgraph_hops(
-                &env.root,
-                anchor_path,
-                anchor_line,
-                &anchor_fn,
-                2,
-            );
+            let hops = col
+                .call_hops
+                .clone()
+                .unwrap_or_else(|| {
+                    CallGraphHopper::collect_callgraph_hops(
+                        &env.root,
+                        anchor_path,
+                        anchor_line,
+                        &anchor_fn,
+                        CALL_DISTANCE_DEPTH,
+                    )
+                });
 
             let w_call = 0.12f32; // Keep ≤ 0.15 for bounded contribution
             for item in &mut all_items
@@ -1588,7 +1847,24 @@ impl ContextAssembler
         }
 
         // Budget
-        let budgeter = Budgeter::new(&env.model)?;
+        let mut budgeter = Budgeter::for_model(
+            &env.model,
+            env.args
+                .tokenizer_file
+                .as_deref(),
+        )?;
+        if env
+            .args
+            .approx_tokens
+        {
+            budgeter = budgeter.approximate();
+        }
+        if !env
+            .args
+            .no_token_cache
+        {
+            budgeter = budgeter.with_disk_cache(&token_cache_dir());
+        }
 
         // Fit with or without buckets
         let fit = if let Some(bucket_spec) = &env
@@ -1622,14 +1898,24 @@ impl ContextAssembler
                     t
                 })
                 .collect();
-            fit_with_buckets(
+            let candidates = env
+                .args
+                .explain_budget
+                .map(|_| tagged_items.clone());
+            let bucket_fit = fit_with_buckets(
                 &budgeter,
                 tagged_items,
-                bucket_caps,
+                bucket_caps.clone(),
                 env.args
                     .novelty_min,
-            )?
-            .fitted
+            )?;
+            if let (Some(format), Some(candidates)) = (env.args.explain_budget, candidates)
+            {
+                bucket_fit
+                    .report(&budgeter, &candidates, &bucket_caps)
+                    .print(format)?;
+            }
+            bucket_fit.fitted
         }
         else
         {
@@ -1642,8 +1928,25 @@ impl ContextAssembler
                         ..Default::default()
                     }
                 });
-            budgeter.fit_with_dedupe(all_items, env.budget, dedupe_config)?
+            match env
+                .args
+                .explain_budget
+            {
+                Some(format) =>
+                {
+                    let (fit, report) =
+                        budgeter.fit_with_report(all_items, env.budget, dedupe_config)?;
+                    report.print(format)?;
+                    fit
+                }
+                None => budgeter.fit_with_dedupe(all_items, env.budget, dedupe_config)?,
+            }
         };
+        // The cache only saves time; never fail the run over it
+        if let Err(e) = budgeter.save_cache()
+        {
+            eprintln!("⚠ Token cache not saved: {e:#}");
+        }
 
         // Build final content (JSON or plain)
         let final_content = if env
@@ -1720,7 +2023,7 @@ impl ContextAssembler
     ) -> Result<()>
     {
         // Missing index: emit consistent JSON or bail
-        if !Path::new(&env.symbols_path).exists() && asm.total_tokens == 0
+        if !index_exists(&env.symbols_path) && asm.total_tokens == 0
         {
             if env
                 .args
@@ -1748,6 +2051,12 @@ impl ContextAssembler
                     "reason": "no_symbols"
                 });
                 println!("{}", out);
+                if let Some(agent) = &env
+                    .ctx
+                    .agent
+                {
+                    agent.warn("no_symbols");
+                }
                 return Ok(());
             }
 
@@ -1790,6 +2099,12 @@ impl ContextAssembler
                     "reason": "no_matches"
                 });
                 println!("{}", out);
+                if let Some(agent) = &env
+                    .ctx
+                    .agent
+                {
+                    agent.warn("no_matches");
+                }
                 return Ok(());
             }
             bail!(
@@ -1799,8 +2114,39 @@ impl ContextAssembler
             );
         }
 
-        // Emit
-        print!("{}", asm.final_content);
+        // Emit; agent mode persists the content as an artifact instead
+        if let Some(agent) = &env
+            .ctx
+            .agent
+        {
+            let ext = if env
+                .args
+                .json
+            {
+                "json"
+            }
+            else
+            {
+                "md"
+            };
+            let out = env
+                .root
+                .join(".rup/agent")
+                .join(format!("context.{ext}"));
+            if let Some(parent) = out.parent()
+            {
+                std::fs::create_dir_all(parent)
+                    .with_context(|| format!("mkdir -p {}", parent.display()))?;
+            }
+            std::fs::write(&out, &asm.final_content)
+                .with_context(|| format!("write {}", out.display()))?;
+            agent.artifact("context", &out);
+            agent.tokens(asm.total_tokens, Some(env.budget), Some(&env.model));
+        }
+        else
+        {
+            print!("{}", asm.final_content);
+        }
 
         // Token summary
         if !env
@@ -1912,7 +2258,82 @@ pub struct CallGraph;
 
 impl CallGraph
 {
-    /// Bounded callgraph collection with constraints and file caching
+    /// Hop distances of the functions reachable from the anchor over the
+    /// caller/callee edges stored in the index (`rup symbols --refs`), within
+    /// the spec's depth, files-per-hop, and edge bounds. Edges are followed
+    /// both ways, so callers land next to callees.
+    ///
+    /// Returns `None` when the index carries no call graph or the anchor line
+    /// is not inside an indexed function; callers then fall back to scanning.
+    pub fn collect_callgraph_hops_indexed(
+        root: &Path,
+        index: &SymbolIndex,
+        spec: &CallgraphSpec,
+    ) -> Option<BTreeMap<String, u8>>
+    {
+        let (anchor_path, anchor_line) = spec
+            .anchor
+            .as_ref()?;
+        if !index.has_call_graph()
+        {
+            return None;
+        }
+        let rel: PathBuf = ContextAssembler::rel(root, anchor_path)
+            .components()
+            .filter(|c| !matches!(c, std::path::Component::CurDir))
+            .collect();
+        let anchor = index.function_at(&rel, *anchor_line)?;
+
+        let mut hops = BTreeMap::from([(
+            anchor
+                .name
+                .clone(),
+            0u8,
+        )]);
+        let mut seen: HashSet<(&Path, &str)> =
+            HashSet::from([(anchor.file.as_path(), anchor.qualified_name.as_str())]);
+        let mut frontier = vec![anchor];
+        let mut edges_used = 0usize;
+
+        'walk: for d in 1..=spec.depth
+        {
+            let mut next = Vec::new();
+            let mut files: BTreeSet<&Path> = BTreeSet::new();
+            for func in frontier
+            {
+                // Expand at most `files_per_hop` distinct files per hop
+                if !files.contains(func.file.as_path()) && files.len() >= spec.files_per_hop
+                {
+                    continue;
+                }
+                files.insert(&func.file);
+
+                for n in index.call_neighbors(func)
+                {
+                    if edges_used >= spec.edges_limit
+                    {
+                        break 'walk;
+                    }
+                    if seen.insert((n.file.as_path(), n.qualified_name.as_str()))
+                    {
+                        edges_used += 1;
+                        hops.entry(
+                            n.name
+                                .clone(),
+                        )
+                        .or_insert(d);
+                        next.push(n);
+                    }
+                }
+            }
+            frontier = next;
+        }
+
+        Some(hops)
+    }
+
+    /// Bounded callgraph collection with constraints and file caching; the
+    /// text-scanning fallback for indexes without call edges
     pub fn collect_callgraph_names_bounded(
         root: &Path,
         spec: &CallgraphSpec,
@@ -0,0 +1,462 @@
+//! Colored diff rendering for `rup preview --color`
+//!
+//! Every line keeps its unified-diff prefix and gets syntect highlighting
+//! picked by the file's extension. A run of removed lines directly followed
+//! by added lines is paired up line by line and diffed word by word, so the
+//! words a REPLACE block actually changes stand out on a brighter background
+//! instead of the whole block reading as a wall of red and green.
+
+use std::sync::OnceLock;
+
+use similar::{ChangeTag, TextDiff};
+use syntect::{
+    easy::HighlightLines,
+    highlighting::{Theme, ThemeSet},
+    parsing::SyntaxSet,
+};
+
+type Rgb = (u8, u8, u8);
+
+const THEME: &str = "base16-ocean.dark";
+const MINUS_BG: Rgb = (0x3f, 0x00, 0x01);
+const MINUS_EMPH_BG: Rgb = (0x90, 0x10, 0x11);
+const PLUS_BG: Rgb = (0x00, 0x28, 0x00);
+const PLUS_EMPH_BG: Rgb = (0x00, 0x60, 0x00);
+const PLAIN_FG: Rgb = (0xc0, 0xc5, 0xce);
+
+/// Line pairs less similar than this are shown as whole-line changes
+const MIN_PAIR_SIMILARITY: f32 = 0.4;
+
+const RESET: &str = "\x1b[0m";
+
+fn syntaxes() -> &'static SyntaxSet
+{
+    static SET: OnceLock<SyntaxSet> = OnceLock::new();
+    SET.get_or_init(SyntaxSet::load_defaults_newlines)
+}
+
+fn theme() -> &'static Theme
+{
+    static THEME_CELL: OnceLock<Theme> = OnceLock::new();
+    THEME_CELL.get_or_init(|| {
+        ThemeSet::load_defaults()
+            .themes
+            .remove(THEME)
+            .unwrap_or_default()
+    })
+}
+
+/// Highlighters for the old and new side of the file being rendered
+struct Sides
+{
+    old: HighlightLines<'static>,
+    new: HighlightLines<'static>,
+}
+
+impl Sides
+{
+    fn for_path(path: &str) -> Self
+    {
+        let set = syntaxes();
+        let syntax = path
+            .rsplit('.')
+            .next()
+            .and_then(|ext| set.find_syntax_by_extension(ext))
+            .unwrap_or_else(|| set.find_syntax_plain_text());
+        Self {
+            old: HighlightLines::new(syntax, theme()),
+            new: HighlightLines::new(syntax, theme()),
+        }
+    }
+}
+
+/// Render a unified diff with ANSI syntax highlighting and word-level
+/// emphasis of changed text within paired `-`/`+` lines
+pub fn render_colored_diff(diff: &str) -> String
+{
+    let lines: Vec<&str> = diff
+        .lines()
+        .collect();
+    let mut out = String::new();
+    let mut sides: Option<Sides> = None;
+    // Lines still expected in the current hunk (old side, new side)
+    let (mut old_left, mut new_left) = (0usize, 0usize);
+
+    let mut i = 0;
+    while i < lines.len()
+    {
+        let line = lines[i];
+        let in_hunk = old_left > 0 || new_left > 0;
+
+        if !in_hunk
+        {
+            if let Some((old, new)) = hunk_counts(line)
+            {
+                (old_left, new_left) = (old, new);
+                out.push_str(&format!("\x1b[36m{}{}\n", line, RESET));
+            }
+            else if line.starts_with("# ")
+            {
+                out.push_str(&format!("\x1b[2m{}{}\n", line, RESET));
+            }
+            else
+            {
+                if let Some(path) = line.strip_prefix("+++ ")
+                {
+                    let path = path
+                        .strip_prefix("b/")
+                        .unwrap_or(path);
+                    sides = Some(Sides::for_path(path));
+                }
+                out.push_str(&format!("\x1b[1m{}{}\n", line, RESET));
+            }
+            i += 1;
+            continue;
+        }
+
+        match line
+            .chars()
+            .next()
+        {
+            Some('-') =>
+            {
+                // Pair the removed run with the added run that follows it
+                let removed = take_run(&lines, &mut i, '-', &mut old_left);
+                let added = take_run(&lines, &mut i, '+', &mut new_left);
+                render_changed_runs(&mut out, &mut sides, &removed, &added);
+            }
+            Some('+') =>
+            {
+                let added = take_run(&lines, &mut i, '+', &mut new_left);
+                render_changed_runs(&mut out, &mut sides, &[], &added);
+            }
+            Some('\\') =>
+            {
+                // "\ No newline at end of file" is not a hunk line
+                out.push_str(&format!("\x1b[2m{}{}\n", line, RESET));
+                i += 1;
+            }
+            _ =>
+            {
+                let content = line
+                    .get(1..)
+                    .unwrap_or("");
+                if let Some(s) = sides.as_mut()
+                {
+                    // Keep the old side's parse state in step
+                    let _ = s
+                        .old
+                        .highlight_line(&format!("{content}\n"), syntaxes());
+                }
+                paint_line(
+                    &mut out,
+                    ' ',
+                    content,
+                    sides
+                        .as_mut()
+                        .map(|s| &mut s.new),
+                    None,
+                    &[],
+                );
+                old_left = old_left.saturating_sub(1);
+                new_left = new_left.saturating_sub(1);
+                i += 1;
+            }
+        }
+    }
+
+    out
+}
+
+/// Collect consecutive hunk lines starting with `prefix` (without it)
+fn take_run<'a>(
+    lines: &[&'a str],
+    i: &mut usize,
+    prefix: char,
+    left: &mut usize,
+) -> Vec<&'a str>
+{
+    let mut run = Vec::new();
+    while *left > 0
+        && let Some(content) = lines
+            .get(*i)
+            .and_then(|l| l.strip_prefix(prefix))
+    {
+        run.push(content);
+        *left -= 1;
+        *i += 1;
+    }
+    run
+}
+
+fn render_changed_runs(
+    out: &mut String,
+    sides: &mut Option<Sides>,
+    removed: &[&str],
+    added: &[&str],
+)
+{
+    let pairs: Vec<Option<(Vec<bool>, Vec<bool>)>> = removed
+        .iter()
+        .zip(added)
+        .map(|(old, new)| word_emphasis(old, new))
+        .collect();
+
+    for (idx, content) in removed
+        .iter()
+        .enumerate()
+    {
+        let emph = pairs
+            .get(idx)
+            .and_then(|p| p.as_ref())
+            .map(|(old, _)| old.as_slice())
+            .unwrap_or(&[]);
+        let hl = sides
+            .as_mut()
+            .map(|s| &mut s.old);
+        paint_line(out, '-', content, hl, Some((MINUS_BG, MINUS_EMPH_BG)), emph);
+    }
+    for (idx, content) in added
+        .iter()
+        .enumerate()
+    {
+        let emph = pairs
+            .get(idx)
+            .and_then(|p| p.as_ref())
+            .map(|(_, new)| new.as_slice())
+            .unwrap_or(&[]);
+        let hl = sides
+            .as_mut()
+            .map(|s| &mut s.new);
+        paint_line(out, '+', content, hl, Some((PLUS_BG, PLUS_EMPH_BG)), emph);
+    }
+}
+
+/// Per-byte masks of the words that differ between `old` and `new`, or
+/// `None` when the lines are too different for word emphasis to help
+fn word_emphasis(
+    old: &str,
+    new: &str,
+) -> Option<(Vec<bool>, Vec<bool>)>
+{
+    let diff = TextDiff::from_words(old, new);
+    if diff.ratio() < MIN_PAIR_SIMILARITY
+    {
+        return None;
+    }
+
+    let mut old_mask = Vec::with_capacity(old.len());
+    let mut new_mask = Vec::with_capacity(new.len());
+    for change in diff.iter_all_changes()
+    {
+        let len = change
+            .value()
+            .len();
+        match change.tag()
+        {
+            ChangeTag::Equal =>
+            {
+                old_mask.extend(std::iter::repeat_n(false, len));
+                new_mask.extend(std::iter::repeat_n(false, len));
+            }
+            ChangeTag::Delete => old_mask.extend(std::iter::repeat_n(true, len)),
+            ChangeTag::Insert => new_mask.extend(std::iter::repeat_n(true, len)),
+        }
+    }
+    Some((old_mask, new_mask))
+}
+
+/// Write one diff line: syntax colors as foreground, the line background
+/// from `bg`, and the emphasis background where `emph` is set
+fn paint_line(
+    out: &mut String,
+    prefix: char,
+    content: &str,
+    hl: Option<&mut HighlightLines<'static>>,
+    bg: Option<(Rgb, Rgb)>,
+    emph: &[bool],
+)
+{
+    let with_newline = format!("{content}\n");
+    let spans: Vec<(Rgb, &str)> = match hl.and_then(|h| {
+        h.highlight_line(&with_newline, syntaxes())
+            .ok()
+    })
+    {
+        Some(ranges) =>
+        {
+            ranges
+                .into_iter()
+                .map(|(style, text)| {
+                    let fg = style.foreground;
+                    ((fg.r, fg.g, fg.b), text)
+                })
+                .collect()
+        }
+        None => vec![(PLAIN_FG, with_newline.as_str())],
+    };
+
+    let (line_bg, emph_bg) = match bg
+    {
+        Some((line_bg, emph_bg)) => (Some(line_bg), Some(emph_bg)),
+        None => (None, None),
+    };
+    let prefix_fg = match prefix
+    {
+        '-' => (0xff, 0x60, 0x60),
+        '+' => (0x60, 0xff, 0x60),
+        _ => PLAIN_FG,
+    };
+    push_styled(out, prefix_fg, line_bg, &prefix.to_string());
+
+    let mut offset = 0;
+    for (fg, text) in spans
+    {
+        let text = text.trim_end_matches('\n');
+        // Split the span wherever emphasis switches on or off
+        let mut start = 0;
+        let mut current = None;
+        for (pos, _) in text.char_indices()
+        {
+            let on = emph
+                .get(offset + pos)
+                .copied()
+                .unwrap_or(false);
+            if current.is_some_and(|c| c != on)
+            {
+                let bg = if current == Some(true)
+                {
+                    emph_bg
+                }
+                else
+                {
+                    line_bg
+                };
+                push_styled(out, fg, bg, &text[start..pos]);
+                start = pos;
+            }
+            current = Some(on);
+        }
+        if start < text.len()
+        {
+            let bg = if current == Some(true)
+            {
+                emph_bg
+            }
+            else
+            {
+                line_bg
+            };
+            push_styled(out, fg, bg, &text[start..]);
+        }
+        offset += text.len();
+    }
+    out.push('\n');
+}
+
+fn push_styled(
+    out: &mut String,
+    fg: Rgb,
+    bg: Option<Rgb>,
+    text: &str,
+)
+{
+    out.push_str(&format!("\x1b[38;2;{};{};{}m", fg.0, fg.1, fg.2));
+    if let Some((r, g, b)) = bg
+    {
+        out.push_str(&format!("\x1b[48;2;{};{};{}m", r, g, b));
+    }
+    out.push_str(text);
+    out.push_str(RESET);
+}
+
+/// Old and new line counts from a `@@ -a,b +c,d @@` header
+fn hunk_counts(line: &str) -> Option<(usize, usize)>
+{
+    let rest = line.strip_prefix("@@ -")?;
+    let (old, rest) = rest.split_once(" +")?;
+    let (new, _) = rest.split_once(" @@")?;
+    let count = |range: &str| {
+        range
+            .split_once(',')
+            .map_or(Some(1), |(_, n)| {
+                n.parse()
+                    .ok()
+            })
+    };
+    Some((count(old)?, count(new)?))
+}
+
+#[cfg(test)]
+mod tests
+{
+    use super::*;
+
+    /// Drop ANSI escape sequences
+    fn strip_ansi(s: &str) -> String
+    {
+        let mut out = String::new();
+        let mut chars = s.chars();
+        while let Some(c) = chars.next()
+        {
+            if c == '\x1b'
+            {
+                for c in chars.by_ref()
+                {
+                    if c == 'm'
+                    {
+                        break;
+                    }
+                }
+            }
+            else
+            {
+                out.push(c);
+            }
+        }
+        out
+    }
+
+    const DIFF: &str = "diff --git a/src/lib.rs b/src/lib.rs\n--- a/src/lib.rs\n+++ \
+                        b/src/lib.rs\n@@ -1,3 +1,3 @@\n fn main() {\n-    let total = count + \
+                        1;\n+    let total = count + 2;\n }\n";
+
+    #[test]
+    fn test_colored_diff_keeps_text()
+    {
+        let colored = render_colored_diff(DIFF);
+        assert!(colored.contains("\x1b[38;2;"));
+        assert_eq!(strip_ansi(&colored), DIFF);
+    }
+
+    #[test]
+    fn test_word_emphasis_marks_only_changed_word()
+    {
+        let colored = render_colored_diff(DIFF);
+        let (r, g, b) = PLUS_EMPH_BG;
+        let emph = format!("\x1b[48;2;{};{};{}m", r, g, b);
+        let marked: Vec<String> = colored
+            .split(&emph)
+            .skip(1)
+            .map(|rest| {
+                strip_ansi(
+                    &rest[..rest
+                        .find(RESET)
+                        .unwrap()],
+                )
+            })
+            .collect();
+        assert_eq!(marked.concat(), "2;");
+
+        // Unrelated lines get whole-line coloring only
+        assert!(word_emphasis("alpha beta", "completely different text").is_none());
+    }
+
+    #[test]
+    fn test_removed_line_that_looks_like_a_header()
+    {
+        let diff = "--- a/x.txt\n+++ b/x.txt\n@@ -1,1 +1,1 @@\n--- old\n+++ new\n";
+        assert_eq!(strip_ansi(&render_colored_diff(diff)), diff);
+        assert_eq!(hunk_counts("@@ -3 +3,2 @@ fn x"), Some((1, 2)));
+    }
+}
@@ -15,16 +15,24 @@ use anyhow::{Context, Result};
 
 use crate::{
     cli::{
-        AppContext, ApplyArgs, BackupArgs, BackupCleanupArgs, BackupListArgs, BackupRestoreArgs,
-        BackupShowArgs, BackupSubcommand, CheckSyntaxArgs, PreviewArgs,
+        AppContext, ApplyArgs, BackupArgs, BackupCleanupArgs, BackupDiffArgs, BackupExportArgs,
+        BackupFromStashArgs, BackupGcArgs, BackupImportArgs, BackupListArgs, BackupListColumn,
+        BackupListFormat, BackupPushArgs, BackupRestoreArgs, BackupScopeMode, BackupShowArgs,
+        BackupSubcommand, BackupToStashArgs, CheckSyntaxArgs, ColorMode, PatchArgs, PatchFormat,
+        PreviewArgs,
     },
     core::{
         BackupManager,
-        apply_engine::create_engine,
+        apply_engine::{create_engine, make_relative_to_repo},
+        backup::BackupScope,
+        backup_archive::{export_session, import_session},
         backup_ops::{
-            CleanupRequest, ListRequest, RestoreRequest, SessionInfo, ShowRequest,
-            cleanup_sessions, list_sessions_filtered, restore_session, show_session,
+            CleanupRequest, DiffRequest, GcRequest, ListRequest, RestoreRequest, SessionInfo,
+            ShowRequest, cleanup_sessions, diff_sessions, gc_objects, list_sessions_filtered,
+            parse_size, restore_session, show_session,
         },
+        backup_stash::{session_to_stash, stash_to_session},
+        engine_registry::{EngineConfig, engine_capabilities, engine_names},
         resolve::run as resolve_run,
     },
 };
@@ -40,6 +48,21 @@ pub enum MatchMode
     Auto,
 }
 
+/// Line-ending policy for files written by apply
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, clap::ValueEnum)]
+pub enum EolPolicy
+{
+    /// Keep each line's own ending; new lines follow their neighbours
+    #[default]
+    Preserve,
+    /// Normalize the whole file to LF
+    Lf,
+    /// Normalize the whole file to CRLF
+    Crlf,
+    /// Normalize the whole file to its dominant ending
+    Auto,
+}
+
 /// Shared normalizer for both CID and OLD comparisons  
 pub fn normalize_for_cid(s: &str) -> String
 {
@@ -58,8 +81,9 @@ pub fn generate_cid(content: &str) -> ContentId
     format!("{:016x}", h)
 }
 
-/// Edit operation types
-#[derive(Debug, Clone, PartialEq)]
+/// Edit operation types; JSON specs tag each one as `"op": "replace"` etc.
+#[derive(Debug, Clone, PartialEq, serde::Deserialize)]
+#[serde(tag = "op", rename_all = "lowercase")]
 pub enum EditOperation
 {
     Replace
@@ -74,16 +98,18 @@ pub enum EditOperation
     {
         at_line: usize, // 1-based, insert after this line (0 = beginning)
         new_content: String,
+        after_content: Option<String>, // lines expected to end at `at_line` (AFTER:)
     },
     Delete
     {
-        start_line: usize, // 1-based inclusive
-        end_line: usize,   // 1-based inclusive
+        start_line: usize,           // 1-based inclusive
+        end_line: usize,             // 1-based inclusive
+        old_content: Option<String>, // doomed lines, enables relocation (OLD:)
     },
 }
 
 /// File block containing path and operations
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, serde::Deserialize)]
 pub struct FileBlock
 {
     pub path: PathBuf,
@@ -91,7 +117,7 @@ pub struct FileBlock
 }
 
 /// Complete edit specification
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, serde::Deserialize)]
 pub struct EditSpec
 {
     pub file_blocks: Vec<FileBlock>,
@@ -137,9 +163,37 @@ pub enum EditConflict
     {
         file: PathBuf,
         span: (usize, usize),
+        /// Closest-matching span found by the token matcher, if any
+        suggestion: Option<SpanSuggestion>,
     },
+
+    /// Looks binary (NUL bytes or invalid UTF-8); never rewritten as text
+    BinaryFile(PathBuf),
+
+    /// Larger than the engine's size cap
+    FileTooLarge
+    {
+        file: PathBuf,
+        size: u64,
+        limit: u64,
+    },
+}
+
+/// Where stale anchor content most likely moved to
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SpanSuggestion
+{
+    /// 1-based inclusive span of the closest match
+    pub span: (usize, usize),
+    /// Token overlap with the expected content, 0-100
+    pub similarity: u8,
+    /// Ready-to-paste operation header targeting `span`
+    pub header: String,
 }
 
+/// Minimum token overlap (percent) for a span to be suggested
+const SUGGESTION_MIN_SIMILARITY: u8 = 60;
+
 /// Edit application result
 #[derive(Debug)]
 pub struct EditResult
@@ -147,6 +201,62 @@ pub struct EditResult
     pub applied_files: Vec<PathBuf>,
     pub conflicts: Vec<EditConflict>,
     pub backup_paths: Vec<PathBuf>,
+    pub stats: ApplyStats,
+}
+
+/// Diffstat-style counters for one apply run
+#[derive(Debug, Clone, Default, PartialEq, Eq, serde::Serialize)]
+pub struct ApplyStats
+{
+    pub files_touched: usize,
+    pub hunks_applied: usize,
+    /// Applied hunks that moved away from their declared lines
+    pub hunks_relocated: usize,
+    /// Hunks dropped because their file was missing
+    pub hunks_skipped: usize,
+    pub lines_added: usize,
+    pub lines_removed: usize,
+    pub elapsed_ms: u64,
+}
+
+impl ApplyStats
+{
+    /// Fold another run's counters into this one (elapsed is kept)
+    pub fn absorb(
+        &mut self,
+        other: &ApplyStats,
+    )
+    {
+        self.files_touched += other.files_touched;
+        self.hunks_applied += other.hunks_applied;
+        self.hunks_relocated += other.hunks_relocated;
+        self.hunks_skipped += other.hunks_skipped;
+        self.lines_added += other.lines_added;
+        self.lines_removed += other.lines_removed;
+    }
+}
+
+impl std::fmt::Display for ApplyStats
+{
+    fn fmt(
+        &self,
+        f: &mut std::fmt::Formatter<'_>,
+    ) -> std::fmt::Result
+    {
+        write!(
+            f,
+            "{} file{} changed, {} hunk{} applied ({} relocated, {} skipped), +{} -{} in {}ms",
+            self.files_touched,
+            if self.files_touched == 1 { "" } else { "s" },
+            self.hunks_applied,
+            if self.hunks_applied == 1 { "" } else { "s" },
+            self.hunks_relocated,
+            self.hunks_skipped,
+            self.lines_added,
+            self.lines_removed,
+            self.elapsed_ms
+        )
+    }
 }
 
 /// Domain-specific error taxonomy for exit-code mapping
@@ -257,9 +367,465 @@ pub fn exit_code_for_typed(e: &ApplyErr) -> i32
     }
 }
 
-/// Discover the git repo root with multiple fallback strategies
+/// Line span an operation claims; INSERT claims only its insertion point
+fn op_span(op: &EditOperation) -> (usize, usize)
+{
+    match op
+    {
+        EditOperation::Replace { start_line, end_line, .. }
+        | EditOperation::Delete { start_line, end_line, .. } => (*start_line, *end_line),
+        EditOperation::Insert { at_line, .. } => (*at_line, *at_line),
+    }
+}
+
+/// Merge a batch of specs into one, grouping blocks by target file and
+/// refusing operations from different specs that touch the same lines.
+/// Overlaps inside a single spec are left to the engine as before.
+fn merge_specs(specs: Vec<(PathBuf, EditSpec)>) -> Result<EditSpec, ApplyCliError>
+{
+    /// One target file with operations tagged by their source spec index
+    struct Merged
+    {
+        key: PathBuf,
+        path: PathBuf,
+        ops: Vec<(usize, EditOperation)>,
+    }
+
+    let mut merged: Vec<Merged> = Vec::new();
+    for (source, (_, spec)) in specs
+        .iter()
+        .enumerate()
+    {
+        for block in &spec.file_blocks
+        {
+            let key = lexical_normalize(&block.path);
+            let ops = block
+                .operations
+                .iter()
+                .map(|op| (source, op.clone()));
+            match merged
+                .iter_mut()
+                .find(|m| m.key == key)
+            {
+                Some(existing) => existing
+                    .ops
+                    .extend(ops),
+                None => merged.push(Merged {
+                    key,
+                    path: block
+                        .path
+                        .clone(),
+                    ops: ops.collect(),
+                }),
+            }
+        }
+    }
+
+    let mut problems = Vec::new();
+    for Merged { path, ops, .. } in &merged
+    {
+        for (i, (a_src, a)) in ops
+            .iter()
+            .enumerate()
+        {
+            for (b_src, b) in &ops[i + 1..]
+            {
+                if a_src == b_src
+                {
+                    continue;
+                }
+                let ((a_s, a_e), (b_s, b_e)) = (op_span(a), op_span(b));
+                // Two INSERTs at the same point are fine; they stack in spec order
+                let both_inserts = matches!(a, EditOperation::Insert { .. })
+                    && matches!(b, EditOperation::Insert { .. });
+                if !both_inserts && a_s <= b_e && b_s <= a_e
+                {
+                    problems.push(format!(
+                        "{}: {} lines {}-{} overlaps {} lines {}-{}",
+                        path.display(),
+                        specs[*a_src]
+                            .0
+                            .display(),
+                        a_s,
+                        a_e,
+                        specs[*b_src]
+                            .0
+                            .display(),
+                        b_s,
+                        b_e
+                    ));
+                }
+            }
+        }
+    }
+    if !problems.is_empty()
+    {
+        return Err(ApplyCliError::InvalidInput(format!(
+            "Cross-spec overlap: {}",
+            problems.join("; ")
+        )));
+    }
+
+    Ok(EditSpec {
+        file_blocks: merged
+            .into_iter()
+            .map(|Merged { path, ops, .. }| {
+                FileBlock {
+                    path,
+                    operations: ops
+                        .into_iter()
+                        .map(|(_, op)| op)
+                        .collect(),
+                }
+            })
+            .collect(),
+    })
+}
+
+/// Resolve `--engine` through the registry. `auto` degrades to `internal`
+/// outside a repository; engines that require one are refused there.
+fn select_engine(
+    name: &str,
+    repo_root: Option<&Path>,
+    cwd: &Path,
+    mut config: EngineConfig,
+    ctx: &AppContext,
+) -> Result<Box<dyn crate::core::apply_engine::ApplyEngine>, ApplyCliError>
+{
+    let caps = engine_capabilities(name).ok_or_else(|| {
+        ApplyCliError::InvalidInput(format!(
+            "unknown engine '{}' (available: {})",
+            name,
+            engine_names().join(", ")
+        ))
+    })?;
+
+    let name = match repo_root
+    {
+        Some(root) =>
+        {
+            config.repo_root = root.to_path_buf();
+            name
+        }
+        None if name == "auto" =>
+        {
+            // Degrade gracefully to internal-only auto
+            if !ctx.quiet
+            {
+                eprintln!("No git repository found, using internal engine for --engine=auto");
+            }
+            config.repo_root = cwd.to_path_buf();
+            "internal"
+        }
+        None if caps.requires_repo =>
+        {
+            return Err(ApplyCliError::Repo(format!(
+                "{} engine requires a repository. Use --engine=internal or init a repo.",
+                name
+            )));
+        }
+        None =>
+        {
+            config.repo_root = cwd.to_path_buf();
+            name
+        }
+    };
+
+    create_engine(name, &config)
+        .map_err(|e| ApplyCliError::Internal(format!("Engine creation failed: {}", e)))
+}
+
+/// Repo-relative parent directories of every file `spec` edits, for
+/// `--backup-scope dirs`; files outside the repo are skipped
+fn spec_dirs(
+    spec: &EditSpec,
+    repo_root: &Path,
+) -> Vec<PathBuf>
+{
+    let mut dirs = Vec::new();
+    for block in &spec.file_blocks
+    {
+        let Ok(rel) = make_relative_to_repo(&block.path, repo_root)
+        else
+        {
+            continue;
+        };
+        let dir = rel
+            .parent()
+            .map(Path::to_path_buf)
+            .unwrap_or_default();
+        if !dirs.contains(&dir)
+        {
+            dirs.push(dir);
+        }
+    }
+    dirs
+}
+
+/// Apply `spec` inside a scratch worktree and run `--verify` there. Any
+/// error leaves the real tree untouched.
+fn run_sandbox_trial(
+    args: &ApplyArgs,
+    spec: &EditSpec,
+    repo_root: Option<&Path>,
+    cwd: &Path,
+    mut config: EngineConfig,
+    ctx: &AppContext,
+) -> Result<(), ApplyCliError>
+{
+    let root = repo_root.ok_or_else(|| {
+        ApplyCliError::Repo("--sandbox requires a git repository".to_string())
+    })?;
+    let scratch = crate::core::sandbox::ScratchWorktree::create(root)
+        .map_err(|e| ApplyCliError::Repo(format!("{:#}", e)))?;
+    let trial_spec = scratch
+        .stage(spec, cwd)
+        .map_err(|e| ApplyCliError::Internal(format!("Sandbox setup failed: {:#}", e)))?;
+
+    // Trial apply never writes backups; the real apply does
+    config.backup_enabled = false;
+    let engine = select_engine(&args.engine, Some(scratch.path()), cwd, config, ctx)?;
+    let report = engine
+        .apply_with_ctx(
+            &trial_spec,
+            crate::core::apply_engine::ApplyContext {
+                repo_root: scratch.path(),
+                backup: None,
+                whitespace: args.whitespace,
+                context_lines: args.context_lines,
+                force: args.force,
+            },
+        )
+        .map_err(|e| {
+            let (kind, _code) = normalize_err_typed(e);
+            ApplyCliError::from(kind)
+        })?;
+    if !ctx.quiet
+    {
+        eprintln!("Sandbox: {}", report.stats);
+    }
+
+    if let Some(command) = &args.verify
+    {
+        if !ctx.quiet
+        {
+            eprintln!("Sandbox: running `{}`", command);
+        }
+        let output = scratch
+            .verify(command)
+            .map_err(|e| ApplyCliError::Internal(format!("{:#}", e)))?;
+        if !output
+            .status
+            .success()
+        {
+            // Keep the failure message readable: last lines of both streams
+            let combined = format!(
+                "{}{}",
+                String::from_utf8_lossy(&output.stdout),
+                String::from_utf8_lossy(&output.stderr)
+            );
+            let lines: Vec<&str> = combined
+                .lines()
+                .collect();
+            let tail = lines[lines
+                .len()
+                .saturating_sub(20)..]
+                .join("\n");
+            return Err(ApplyCliError::Conflicts(format!(
+                "sandbox verification `{}` failed ({}); real tree left untouched\n{}",
+                command, output.status, tail
+            )));
+        }
+        if !ctx.quiet
+        {
+            eprintln!("Sandbox: verification passed");
+        }
+    }
+    Ok(())
+}
+
+/// Apply `spec` to `--branch` (created from HEAD if missing) in a scratch
+/// worktree and commit it there, then move the branch to that commit. The
+/// current branch and working tree are never written.
+fn run_branch_apply(
+    args: &ApplyArgs,
+    spec: &EditSpec,
+    repo_root: Option<&Path>,
+    cwd: &Path,
+    run_mode: RunMode,
+    mut config: EngineConfig,
+    ctx: &AppContext,
+) -> Result<(), ApplyCliError>
+{
+    let branch = args
+        .branch
+        .as_deref()
+        .unwrap_or_default();
+    let root = repo_root.ok_or_else(|| {
+        ApplyCliError::Repo("--branch requires a git repository".to_string())
+    })?;
+    let repo_err = |e: anyhow::Error| ApplyCliError::Repo(format!("{:#}", e));
+
+    let old_tip = crate::core::git::branch_tip(root, branch).map_err(repo_err)?;
+    let scratch =
+        crate::core::sandbox::ScratchWorktree::create_at(root, old_tip.as_deref().unwrap_or("HEAD"))
+            .map_err(repo_err)?;
+    let branch_spec = scratch
+        .retarget(spec, cwd)
+        .map_err(|e| ApplyCliError::Internal(format!("Worktree setup failed: {:#}", e)))?;
+
+    // The real tree is untouched, so there is nothing to back up
+    config.backup_enabled = false;
+    let engine = select_engine(&args.engine, Some(scratch.path()), cwd, config, ctx)?;
+    let to_cli = |e: anyhow::Error| {
+        let (kind, _code) = normalize_err_typed(e);
+        ApplyCliError::from(kind)
+    };
+
+    let preview = engine
+        .check(&branch_spec)
+        .map_err(to_cli)?;
+    if !ctx.quiet
+        && !preview
+            .patch_content
+            .is_empty()
+    {
+        println!("{}", preview.patch_content);
+    }
+    if !preview
+        .conflicts
+        .is_empty()
+    {
+        if !ctx.quiet
+        {
+            for conflict in &preview.conflicts
+            {
+                eprintln!("  • {}", conflict);
+            }
+        }
+        if !args.force
+        {
+            return Err(ApplyCliError::Conflicts(format!(
+                "{} conflicts detected on branch {}. Use --force to apply despite conflicts.",
+                preview
+                    .conflicts
+                    .len(),
+                branch
+            )));
+        }
+    }
+    if run_mode == RunMode::Preview
+    {
+        return Ok(());
+    }
+
+    let mut report = engine
+        .apply_with_ctx(
+            &branch_spec,
+            crate::core::apply_engine::ApplyContext {
+                repo_root: scratch.path(),
+                backup: None,
+                whitespace: args.whitespace,
+                context_lines: args.context_lines,
+                force: args.force,
+            },
+        )
+        .map_err(to_cli)?;
+
+    let files: Vec<PathBuf> = report
+        .applied_files
+        .iter()
+        .map(|f| scratch.path().join(f))
+        .collect();
+    let template = args
+        .commit_message
+        .as_deref()
+        .unwrap_or(DEFAULT_COMMIT_TEMPLATE);
+    let message = render_commit_message(template, &report, &files, scratch.path());
+    let hash = crate::core::git::commit_files(scratch.path(), &files, &message, args.signoff)
+        .map_err(|e| ApplyCliError::Repo(format!("commit failed: {e}")))?;
+    crate::core::git::update_branch(root, branch, &hash, old_tip.as_deref()).map_err(repo_err)?;
+    report.commit = Some(hash);
+    // Worktree paths are gone once the scratch tree drops
+    report.applied_files = files
+        .iter()
+        .map(|f| make_relative_to_repo(f, scratch.path()).unwrap_or_else(|_| f.clone()))
+        .collect();
+
+    if args.json
+    {
+        let json_output = serde_json::to_string(&report)
+            .map_err(|e| ApplyCliError::Internal(format!("JSON serialization failed: {}", e)))?;
+        println!("{}", json_output);
+    }
+    else if !ctx.quiet
+    {
+        println!("Summary: {}", report.stats);
+        println!(
+            "Branch: {} {} at {}",
+            branch,
+            if old_tip.is_some() { "updated" } else { "created" },
+            report
+                .commit
+                .as_deref()
+                .unwrap_or_default()
+        );
+    }
+    Ok(())
+}
+
+/// Patch context rules: `[[apply.context]]` from the config, then
+/// `--context-for`, so command-line rules win
+fn context_rules(
+    cli: &[crate::core::patch::ContextRule],
+    root: &Path,
+) -> Vec<crate::core::patch::ContextRule>
+{
+    let mut rules = match crate::infra::config::load_config_in(root)
+    {
+        Ok(cfg) => cfg
+            .apply
+            .context,
+        Err(e) =>
+        {
+            tracing::warn!("context rules: config not readable, ignoring it: {e:#}");
+            Vec::new()
+        }
+    };
+    rules.extend_from_slice(cli);
+    rules
+}
+
+/// Print registered engines and their capability flags for `--list-engines`
+fn print_engine_list()
+{
+    let registry = crate::core::engine_registry::registry_snapshot();
+    for engine in registry.iter()
+    {
+        let caps = engine.capabilities;
+        let mut flags = Vec::new();
+        if caps.three_way_merge
+        {
+            flags.push("3way");
+        }
+        if caps.binary_files
+        {
+            flags.push("binary");
+        }
+        if caps.requires_repo
+        {
+            flags.push("repo");
+        }
+        let flags = if flags.is_empty() { "-".to_string() } else { flags.join(",") };
+        println!("{:<10} {:<16} {}", engine.name, flags, engine.description);
+    }
+}
+
+/// Discover the git (or jj) repo root with multiple fallback strategies
 /// Returns Ok(None) when no repo is found. Callers must decide
-/// whether None is acceptable based on engine choice.
+/// whether None is acceptable based on engine choice. Inside a submodule
+/// this is the submodule's root; from the superproject, the git engine
+/// routes paths below a submodule to it (see `git::split_by_repo`).
 pub fn discover_repo_root(
     explicit: Option<PathBuf>,
     start: &Path,
@@ -296,12 +862,16 @@ pub fn discover_repo_root(
         }
     }
 
-    // 3) ascend to find .git (directory or worktree file), canonicalized
+    // 3) ascend to find .git (directory or worktree file) or a jj repo's
+    //    .jj directory, canonicalized
     let mut cur = Some(start);
     while let Some(dir) = cur
     {
         let git_path = dir.join(".git");
-        if git_path.exists() && (git_path.is_dir() || git_path.is_file())
+        if (git_path.exists() && (git_path.is_dir() || git_path.is_file()))
+            || dir
+                .join(".jj")
+                .is_dir()
         {
             let d = dir.to_path_buf();
             return Ok(Some(
@@ -315,6 +885,104 @@ pub fn discover_repo_root(
     Ok(None)
 }
 
+/// Reject edit-spec paths that leave `root`: absolute paths, `..` escapes,
+/// and symlinks pointing outside. Relative paths resolve against `cwd`.
+pub fn enforce_path_sandbox(
+    spec: &EditSpec,
+    root: &Path,
+    cwd: &Path,
+) -> std::result::Result<(), ApplyCliError>
+{
+    let root_canon = root
+        .canonicalize()
+        .unwrap_or_else(|_| lexical_normalize(root));
+    let cwd_canon = cwd
+        .canonicalize()
+        .unwrap_or_else(|_| lexical_normalize(cwd));
+
+    for block in &spec.file_blocks
+    {
+        let p = &block.path;
+        let reject = |why: &str| {
+            ApplyCliError::Repo(format!(
+                "{} {} (root: {}). Use --allow-outside-repo to override.",
+                p.display(),
+                why,
+                root_canon.display()
+            ))
+        };
+
+        // Absolute paths are never accepted from a spec
+        if p.has_root()
+        {
+            return Err(reject("is an absolute path"));
+        }
+
+        // Lexical check catches `..` escapes, even for files not yet created
+        let joined = lexical_normalize(&cwd_canon.join(p));
+        if !joined.starts_with(&root_canon)
+        {
+            return Err(reject("escapes the repository via '..'"));
+        }
+
+        // Resolve symlinks through the deepest existing ancestor
+        let mut existing = joined.as_path();
+        let mut tail = Vec::new();
+        while !existing.exists()
+        {
+            match (existing.parent(), existing.file_name())
+            {
+                (Some(parent), Some(name)) =>
+                {
+                    tail.push(name.to_os_string());
+                    existing = parent;
+                }
+                _ => break,
+            }
+        }
+        let mut resolved = existing
+            .canonicalize()
+            .unwrap_or_else(|_| existing.to_path_buf());
+        for name in tail
+            .iter()
+            .rev()
+        {
+            resolved.push(name);
+        }
+        if !resolved.starts_with(&root_canon)
+        {
+            return Err(reject("resolves outside the repository through a symlink"));
+        }
+    }
+
+    Ok(())
+}
+
+/// Collapse `.` and `..` components without touching the filesystem
+pub(crate) fn lexical_normalize(p: &Path) -> PathBuf
+{
+    use std::path::Component;
+
+    let mut out = PathBuf::new();
+    for comp in p.components()
+    {
+        match comp
+        {
+            Component::CurDir =>
+            {}
+            Component::ParentDir =>
+            {
+                if !out.pop()
+                {
+                    out.push("..");
+                }
+            }
+            other => out.push(other.as_os_str()),
+        }
+    }
+    out
+}
+
 /// Map crate/engine errors to ApplyCliError
 pub fn normalize_err(e: anyhow::Error) -> ApplyCliError
 {
@@ -339,9 +1007,41 @@ pub fn normalize_err(e: anyhow::Error) -> ApplyCliError
     }
 }
 
+/// Classify a failed apply; with `--json`, first print the failure as one
+/// JSON line carrying the `--engine auto` attempt chain when there is one
+fn apply_failure(
+    e: anyhow::Error,
+    json: bool,
+) -> ApplyCliError
+{
+    if json
+        && let Some(cc) = e.downcast_ref::<crate::core::git::CombinedConflictError>()
+    {
+        let failure = serde_json::json!({
+            "error": format!("{e:#}"),
+            "conflicts": cc.internal_conflicts,
+            "attempts": cc.attempts,
+        });
+        println!("{failure}");
+    }
+    let (kind, _code) = normalize_err_typed(e);
+    ApplyCliError::from(kind)
+}
+
 /// Enhanced error normalization with proper type classification
 pub fn normalize_err_typed(e: anyhow::Error) -> (ApplyErr, i32)
 {
+    // Already typed, e.g. by check-syntax
+    let e = match e.downcast::<ApplyErr>()
+    {
+        Ok(typed) =>
+        {
+            let code = exit_code_for_typed(&typed);
+            return (typed, code);
+        }
+        Err(e) => e,
+    };
+
     // Parse error classification
     let msg = format!("{e:#}");
 
@@ -375,13 +1075,38 @@ pub fn normalize_err_typed(e: anyhow::Error) -> (ApplyErr, i32)
     (err, 5)
 }
 
+/// Default cap on files the edit engine will load (8 MiB)
+pub const DEFAULT_MAX_FILE_BYTES: u64 = 8 * 1024 * 1024;
+
+/// How many leading bytes are scanned for NUL when sniffing binaries
+const BINARY_SNIFF_BYTES: usize = 8000;
+
 /// Core edit engine
-#[derive(Default)]
 pub struct EditEngine
 {
     preview_mode: bool,
     backup_enabled: bool,
     force_mode: bool,
+    max_drift: Option<usize>,
+    eol: EolPolicy,
+    max_file_bytes: Option<u64>,
+    allow_binary: bool,
+}
+
+impl Default for EditEngine
+{
+    fn default() -> Self
+    {
+        Self {
+            preview_mode: false,
+            backup_enabled: false,
+            force_mode: false,
+            max_drift: None,
+            eol: EolPolicy::default(),
+            max_file_bytes: Some(DEFAULT_MAX_FILE_BYTES),
+            allow_binary: false,
+        }
+    }
 }
 
 impl EditEngine
@@ -418,6 +1143,40 @@ impl EditEngine
         self
     }
 
+    /// Bound how many lines a REPLACE may move from its declared span.
+    /// `None` keeps the legacy unbounded relocation.
+    pub fn with_max_drift(
+        mut self,
+        max_drift: Option<usize>,
+    ) -> Self
+    {
+        self.max_drift = max_drift;
+        self
+    }
+
+    /// Choose how line endings are written back
+    pub fn with_eol(
+        mut self,
+        eol: EolPolicy,
+    ) -> Self
+    {
+        self.eol = eol;
+        self
+    }
+
+    /// Refuse files over `max_file_bytes` (`None` disables the cap) and,
+    /// unless `allow_binary`, files containing NUL bytes
+    pub fn with_file_guards(
+        mut self,
+        max_file_bytes: Option<u64>,
+        allow_binary: bool,
+    ) -> Self
+    {
+        self.max_file_bytes = max_file_bytes;
+        self.allow_binary = allow_binary;
+        self
+    }
+
     /// Parse edit specification from text
     pub fn parse_edit_spec(
         &self,
@@ -749,10 +1508,23 @@ impl EditEngine
             .map_err(|_| ParseError::InvalidLineNumber(line_part.to_string()))?;
         *i += 1;
 
+        // Optional AFTER block anchors the insertion point for relocation
+        let after_content = if *i < lines.len()
+            && lines[*i]
+                .trim()
+                .starts_with("AFTER:")
+        {
+            Some(self.parse_content_block(lines, i, "AFTER:")?)
+        }
+        else
+        {
+            None
+        };
+
         // Parse NEW block
         let new_content = self.parse_content_block(lines, i, "NEW:")?;
 
-        Ok(Some(EditOperation::Insert { at_line, new_content }))
+        Ok(Some(EditOperation::Insert { at_line, new_content, after_content }))
     }
 
     /// Parse DELETE operation
@@ -775,7 +1547,20 @@ impl EditEngine
         let (start_line, end_line) = self.parse_span(span_part)?;
         *i += 1;
 
-        Ok(Some(EditOperation::Delete { start_line, end_line }))
+        // Optional OLD block carries the doomed content for relocation
+        let old_content = if *i < lines.len()
+            && lines[*i]
+                .trim()
+                .starts_with("OLD:")
+        {
+            Some(self.parse_content_block(lines, i, "OLD:")?)
+        }
+        else
+        {
+            None
+        };
+
+        Ok(Some(EditOperation::Delete { start_line, end_line, old_content }))
     }
 
     /// Parse line span "10-15" or single line "10"
@@ -947,6 +1732,8 @@ impl EditEngine
         let mut applied_files = Vec::new();
         let mut conflicts = Vec::new();
         let mut backup_paths = Vec::new();
+        // Loaded on first mismatch only; the BPE tables are not free
+        let matcher = std::cell::OnceCell::new();
 
         // First pass: validate all operations
         for file_block in &spec.file_blocks
@@ -963,9 +1750,16 @@ impl EditEngine
                 continue;
             }
 
-            // Load file content for validation
-            let content = fs::read_to_string(&file_block.path)
-                .with_context(|| format!("Failed to read file: {:?}", file_block.path))?;
+            // Load file content for validation, refusing binary/oversized files
+            let content = match self.load_text(&file_block.path)?
+            {
+                Ok(content) => content,
+                Err(conflict) =>
+                {
+                    conflicts.push(conflict);
+                    continue;
+                }
+            };
             let file_lines: Vec<&str> = content
                 .lines()
                 .collect();
@@ -977,6 +1771,15 @@ impl EditEngine
                 {
                     Ok(()) =>
                     {}
+                    Err(EditConflict::OldContentMismatch { file, span, .. }) =>
+                    {
+                        // Point stale specs at where their content went
+                        let suggestion = matcher
+                            .get_or_init(|| TokenMatcher::new().ok())
+                            .as_ref()
+                            .and_then(|m| suggest_span(m, op, &file_lines));
+                        conflicts.push(EditConflict::OldContentMismatch { file, span, suggestion });
+                    }
                     Err(conflict) =>
                     {
                         conflicts.push(conflict);
@@ -988,17 +1791,29 @@ impl EditEngine
         // Stop if conflicts found and not in force mode
         if !conflicts.is_empty() && !self.force_mode
         {
-            return Ok(EditResult { applied_files, conflicts, backup_paths });
+            return Ok(EditResult {
+                applied_files,
+                conflicts,
+                backup_paths,
+                stats: ApplyStats::default(),
+            });
         }
 
         // Preview mode: just show what would be done
         if self.preview_mode
         {
             // TODO: Generate and display unified diff
-            return Ok(EditResult { applied_files, conflicts, backup_paths });
+            return Ok(EditResult {
+                applied_files,
+                conflicts,
+                backup_paths,
+                stats: ApplyStats::default(),
+            });
         }
 
         // Apply operations to each file
+        let started = std::time::Instant::now();
+        let mut stats = ApplyStats::default();
         for file_block in &spec.file_blocks
         {
             if conflicts
@@ -1006,12 +1821,20 @@ impl EditEngine
                 .any(|c| {
                     match c
                     {
-                        EditConflict::FileNotFound(path) => path == &file_block.path,
+                        EditConflict::FileNotFound(path) | EditConflict::BinaryFile(path) =>
+                        {
+                            path == &file_block.path
+                        }
+                        EditConflict::FileTooLarge { file, .. } => file == &file_block.path,
                         _ => false,
                     }
                 })
             {
-                continue; // Skip files that don't exist
+                // Skip files that are missing or refused, even under --force
+                stats.hunks_skipped += file_block
+                    .operations
+                    .len();
+                continue;
             }
 
             // Create backup if requested
@@ -1022,15 +1845,52 @@ impl EditEngine
             }
 
             // Apply operations to this file
-            self.apply_file_operations(&file_block.path, &file_block.operations)?;
+            let file_stats =
+                self.apply_file_operations(&file_block.path, &file_block.operations)?;
+            stats.absorb(&file_stats);
             applied_files.push(
                 file_block
                     .path
                     .clone(),
             );
         }
+        stats.elapsed_ms = started
+            .elapsed()
+            .as_millis() as u64;
+
+        Ok(EditResult { applied_files, conflicts, backup_paths, stats })
+    }
+
+    /// Read a file as text, or the conflict explaining why it is refused
+    fn load_text(
+        &self,
+        path: &Path,
+    ) -> Result<Result<String, EditConflict>>
+    {
+        let size = fs::metadata(path)
+            .with_context(|| format!("Failed to stat file: {:?}", path))?
+            .len();
+        if let Some(limit) = self.max_file_bytes
+            && size > limit
+        {
+            return Ok(Err(EditConflict::FileTooLarge { file: path.to_path_buf(), size, limit }));
+        }
 
-        Ok(EditResult { applied_files, conflicts, backup_paths })
+        let bytes = fs::read(path).with_context(|| format!("Failed to read file: {:?}", path))?;
+        let has_nul = bytes[..bytes
+            .len()
+            .min(BINARY_SNIFF_BYTES)]
+            .contains(&0);
+        if has_nul && !self.allow_binary
+        {
+            return Ok(Err(EditConflict::BinaryFile(path.to_path_buf())));
+        }
+        match String::from_utf8(bytes)
+        {
+            Ok(text) => Ok(Ok(text)),
+            // Not text in any encoding we can round-trip; even --force-binary can't help
+            Err(_) => Ok(Err(EditConflict::BinaryFile(path.to_path_buf()))),
+        }
     }
 
     /// Validate single operation against file content
@@ -1078,17 +1938,19 @@ impl EditEngine
                 }
                 else
                 {
-                    // No guard: normalize and compare the OLD payload
-                    if normalize_for_cid(old_content) != normalize_for_cid(&actual_content)
+                    // No guard: normalize and compare the OLD payload, tolerating
+                    // a bounded drift when --max-drift is set
+                    if !self.anchor_holds(file_lines, (*start_line, *end_line), old_content)
                     {
                         return Err(EditConflict::OldContentMismatch {
                             file: file_path.to_path_buf(),
                             span: (*start_line, *end_line),
+                            suggestion: None,
                         });
                     }
                 }
             }
-            EditOperation::Insert { at_line, .. } =>
+            EditOperation::Insert { at_line, after_content, .. } =>
             {
                 // Check line bounds (0 is valid for insert at beginning)
                 if *at_line > file_lines.len()
@@ -1099,8 +1961,23 @@ impl EditEngine
                         file_lines: file_lines.len(),
                     });
                 }
+
+                // AFTER context must still precede the insertion point
+                if let Some(ctx) = after_content
+                    && !ctx.is_empty()
+                {
+                    let span = after_span(*at_line, ctx);
+                    if !self.anchor_holds(file_lines, span, ctx)
+                    {
+                        return Err(EditConflict::OldContentMismatch {
+                            file: file_path.to_path_buf(),
+                            span,
+                            suggestion: None,
+                        });
+                    }
+                }
             }
-            EditOperation::Delete { start_line, end_line } =>
+            EditOperation::Delete { start_line, end_line, old_content } =>
             {
                 // Check span bounds
                 if *start_line == 0
@@ -1114,11 +1991,37 @@ impl EditEngine
                         file_lines: file_lines.len(),
                     });
                 }
+
+                // OLD payload, when given, must match the doomed lines
+                if let Some(old) = old_content
+                    && !self.anchor_holds(file_lines, (*start_line, *end_line), old)
+                {
+                    return Err(EditConflict::OldContentMismatch {
+                        file: file_path.to_path_buf(),
+                        span: (*start_line, *end_line),
+                        suggestion: None,
+                    });
+                }
             }
         }
         Ok(())
     }
 
+    /// Whether `expected` sits at `declared`, or within `max_drift` of it
+    fn anchor_holds(
+        &self,
+        file_lines: &[&str],
+        declared: (usize, usize),
+        expected: &str,
+    ) -> bool
+    {
+        span_matches(file_lines, declared, expected)
+            || self
+                .max_drift
+                .and_then(|n| locate_within_drift(file_lines, expected, declared.0, n))
+                .is_some()
+    }
+
     /// Create backup file with timestamp, preserving original extension
     fn create_backup(
         &self,
@@ -1160,54 +2063,73 @@ impl EditEngine
         Ok(backup_path)
     }
 
-    /// Apply operations to a single file
+    /// Apply operations to a single file, returning its diffstat
     fn apply_file_operations(
         &self,
         file_path: &Path,
         operations: &[EditOperation],
-    ) -> Result<()>
+    ) -> Result<ApplyStats>
     {
         // Load file content
         let content = fs::read_to_string(file_path)
             .with_context(|| format!("Failed to read file: {:?}", file_path))?;
 
-        // Detect original newline style and EOF newline presence
-        fn detect_nl(s: &str) -> (&'static str, bool)
+        // Split into lines, remembering each line's own terminator
+        let had_final_nl = content.ends_with('\n');
+        let (mut file_lines, mut eols) = split_lines_with_eol(&content);
+        let dominant = dominant_eol(&eols);
+
+        // Re-anchor every operation against the current file before ordering
+        let matcher = TokenMatcher::new().ok(); // optional; None => line mode only
+        let relocated: Vec<EditOperation> = operations
+            .iter()
+            .map(|op| self.relocate_operation(op, &file_lines, matcher.as_ref()))
+            .collect();
+
+        // Tally the diffstat from final positions
+        let mut stats = ApplyStats {
+            files_touched: 1,
+            hunks_applied: relocated.len(),
+            hunks_relocated: relocated
+                .iter()
+                .zip(operations)
+                .filter(|(moved, orig)| moved != orig)
+                .count(),
+            ..ApplyStats::default()
+        };
+        for op in &relocated
         {
-            for w in s
-                .as_bytes()
-                .windows(2)
+            match op
             {
-                if w[1] == b'\n'
+                EditOperation::Replace { start_line, end_line, new_content, .. } =>
                 {
-                    return (
-                        if w[0] == b'\r' { "\r\n" } else { "\n" },
-                        s.ends_with('\n') || s.ends_with("\r\n"),
-                    );
+                    stats.lines_added += new_content
+                        .lines()
+                        .count();
+                    stats.lines_removed += (end_line + 1).saturating_sub(*start_line);
+                }
+                EditOperation::Insert { new_content, .. } =>
+                {
+                    stats.lines_added += new_content
+                        .lines()
+                        .count();
+                }
+                EditOperation::Delete { start_line, end_line, .. } =>
+                {
+                    stats.lines_removed += (end_line + 1).saturating_sub(*start_line);
                 }
             }
-            ("\n", s.ends_with('\n') || s.ends_with("\r\n"))
         }
-        let (nl, had_final_nl) = detect_nl(&content);
-        let use_crlf = nl == "\r\n";
-
-        // Build mutable lines without trailing '\r'
-        let mut file_lines: Vec<String> = content
-            .lines()
-            .map(|s| {
-                s.trim_end_matches('\r')
-                    .to_string()
-            })
-            .collect();
+        let operations = relocated;
 
         // Check for overlapping operations
         let mut ranges = Vec::new();
-        for op in operations
+        for op in &operations
         {
             match op
             {
                 EditOperation::Replace { start_line, end_line, .. }
-                | EditOperation::Delete { start_line, end_line } =>
+                | EditOperation::Delete { start_line, end_line, .. } =>
                 {
                     ranges.push((*start_line, *end_line));
                 }
@@ -1234,7 +2156,7 @@ impl EditEngine
         }
 
         // INSERTs inside any range
-        for op in operations
+        for op in &operations
         {
             if let EditOperation::Insert { at_line, .. } = op
             {
@@ -1260,12 +2182,15 @@ impl EditEngine
         }
 
         // Stable sort with tie-breakers
-        let mut sorted_ops = operations.to_vec();
+        let mut sorted_ops = operations;
         sorted_ops.sort_by(|a, b| {
             let key = |op: &EditOperation| -> (usize, u8, usize) {
                 match op
                 {
-                    EditOperation::Delete { start_line, end_line } => (*start_line, 0, *end_line),
+                    EditOperation::Delete { start_line, end_line, .. } =>
+                    {
+                        (*start_line, 0, *end_line)
+                    }
                     EditOperation::Replace { start_line, end_line, .. } =>
                     {
                         (*start_line, 1, *end_line)
@@ -1281,79 +2206,304 @@ impl EditEngine
                 .then(be.cmp(&ae))
         });
 
-        // Apply operations
-        // Apply operations with token-aware relocation
-        let matcher = TokenMatcher::new().ok(); // optional; None => line mode only
-
+        // Apply operations at their relocated positions; new lines take the
+        // ending of the line they replace or follow
+        let fill = |eols: &[&'static str], idx: Option<usize>| -> &'static str {
+            idx.and_then(|i| eols.get(i))
+                .copied()
+                .filter(|e| !e.is_empty())
+                .unwrap_or(dominant)
+        };
         for op in sorted_ops
         {
             match op
             {
-                EditOperation::Replace {
-                    start_line, end_line, old_content, new_content, ..
-                } =>
+                EditOperation::Replace { start_line, end_line, new_content, .. } =>
                 {
-                    // Try to re-locate by tokens first (exact token subsequence),
-                    // then fall back to original line span.
-                    let (s, e) = if let Some(m) = &matcher
-                    {
-                        m.locate_exact(&file_lines, &old_content)
-                            .or(Some((start_line, end_line)))
-                    }
-                    else
-                    {
-                        Some((start_line, end_line))
-                    }
-                    .unwrap();
-
-                    let start_idx = s.saturating_sub(1);
-                    let end_idx = e;
+                    let start_idx = start_line.saturating_sub(1);
+                    let end_idx = end_line;
 
                     let new_lines: Vec<String> = new_content
                         .lines()
                         .map(|s| s.to_string())
                         .collect();
+                    let eol = fill(&eols, Some(start_idx));
+                    eols.splice(start_idx..end_idx, vec![eol; new_lines.len()]);
                     file_lines.splice(start_idx..end_idx, new_lines);
                 }
-                EditOperation::Insert { at_line, new_content } =>
+                EditOperation::Insert { at_line, new_content, .. } =>
                 {
-                    // Insert after re-located token match of the previous line if possible.
-                    // For v1 we keep the given at_line to avoid guessy behavior.
                     let insert_idx = at_line;
                     let new_lines: Vec<String> = new_content
                         .lines()
                         .map(|s| s.to_string())
                         .collect();
+                    let eol = fill(&eols, insert_idx.checked_sub(1));
                     for (i, line) in new_lines
                         .into_iter()
                         .enumerate()
                     {
                         file_lines.insert(insert_idx + i, line);
+                        eols.insert(insert_idx + i, eol);
                     }
                 }
-                EditOperation::Delete { start_line, end_line } =>
+                EditOperation::Delete { start_line, end_line, .. } =>
                 {
-                    // For deletes, stick to provided span in v1 (token delete can come next)
                     let start_idx = start_line - 1;
                     let end_idx = end_line;
                     file_lines.drain(start_idx..end_idx);
+                    eols.drain(start_idx..end_idx);
                 }
             }
         }
 
-        // Reassemble with original newline style
-        let nl = if use_crlf { "\r\n" } else { "\n" };
-        let mut updated_content = file_lines.join(nl);
-        if had_final_nl
+        // Reassemble under the EOL policy; only the last line may lack one
+        let mut updated_content = String::with_capacity(content.len());
+        let last = file_lines
+            .len()
+            .saturating_sub(1);
+        for (i, (line, eol)) in file_lines
+            .iter()
+            .zip(&eols)
+            .enumerate()
         {
-            updated_content.push_str(nl);
+            updated_content.push_str(line);
+            if i < last || had_final_nl
+            {
+                updated_content.push_str(match self.eol
+                {
+                    EolPolicy::Preserve if !eol.is_empty() => eol,
+                    EolPolicy::Preserve | EolPolicy::Auto => dominant,
+                    EolPolicy::Lf => "\n",
+                    EolPolicy::Crlf => "\r\n",
+                });
+            }
         }
 
         // Atomic write with robust temp file strategy
         write_atomic(file_path, updated_content.as_bytes())?;
 
-        Ok(())
+        Ok(stats)
+    }
+
+    /// Move an operation to where its anchor content actually sits. REPLACE
+    /// anchors on OLD, DELETE on its optional OLD, INSERT on its optional
+    /// AFTER context; operations without anchor content keep their lines.
+    fn relocate_operation(
+        &self,
+        op: &EditOperation,
+        file_lines: &[String],
+        matcher: Option<&TokenMatcher>,
+    ) -> EditOperation
+    {
+        let mut moved = op.clone();
+        match &mut moved
+        {
+            EditOperation::Replace { start_line, end_line, old_content, .. }
+            | EditOperation::Delete {
+                start_line, end_line, old_content: Some(old_content), ..
+            } =>
+            {
+                (*start_line, *end_line) =
+                    self.relocate_span(file_lines, (*start_line, *end_line), old_content, matcher);
+            }
+            EditOperation::Insert { at_line, after_content: Some(ctx), .. } if !ctx.is_empty() =>
+            {
+                let declared = after_span(*at_line, ctx);
+                let (_, end) = self.relocate_span(file_lines, declared, ctx, matcher);
+                // Unresolvable context keeps the declared insertion point
+                if end >= 1
+                {
+                    *at_line = end;
+                }
+            }
+            _ =>
+            {}
+        }
+        moved
+    }
+
+    /// Pick the span anchor content actually occupies. The declared span wins
+    /// when it still matches; otherwise relocate, bounded by `max_drift` if set.
+    fn relocate_span(
+        &self,
+        file_lines: &[String],
+        declared: (usize, usize),
+        old_content: &str,
+        matcher: Option<&TokenMatcher>,
+    ) -> (usize, usize)
+    {
+        let start_line = declared.0;
+        if span_matches(file_lines, declared, old_content)
+        {
+            return declared;
+        }
+
+        match self.max_drift
+        {
+            // Nearest line-aligned match in the window, then a bounded token match
+            Some(n) => locate_within_drift(file_lines, old_content, start_line, n)
+                .or_else(|| {
+                    matcher
+                        .and_then(|m| m.locate_exact(file_lines, old_content))
+                        .filter(|(s, _)| s.abs_diff(start_line) <= n)
+                })
+                .unwrap_or(declared),
+            // Legacy: unbounded token relocation
+            None => matcher
+                .and_then(|m| m.locate_exact(file_lines, old_content))
+                .unwrap_or(declared),
+        }
+    }
+}
+
+/// Whether the 1-based inclusive `span` holds `expected` (CID-normalized)
+fn span_matches<S: AsRef<str>>(
+    file_lines: &[S],
+    span: (usize, usize),
+    expected: &str,
+) -> bool
+{
+    let (start, end) = span;
+    if start == 0 || start > end || end > file_lines.len()
+    {
+        return false;
     }
+    let window: Vec<&str> = file_lines[start - 1..end]
+        .iter()
+        .map(|l| l.as_ref())
+        .collect();
+    normalize_for_cid(&window.join("\n")) == normalize_for_cid(expected)
+}
+
+/// Declared span of an INSERT's AFTER context: the lines ending at `at_line`.
+/// Start is 0 (never matching) when the context is taller than the prefix.
+fn after_span(
+    at_line: usize,
+    after_content: &str,
+) -> (usize, usize)
+{
+    let height = after_content
+        .lines()
+        .count()
+        .max(1);
+    ((at_line + 1).saturating_sub(height), at_line)
+}
+
+/// Closest span for a mismatched operation's anchor, with a corrected header
+fn suggest_span(
+    matcher: &TokenMatcher,
+    op: &EditOperation,
+    file_lines: &[&str],
+) -> Option<SpanSuggestion>
+{
+    let (anchor, near) = match op
+    {
+        EditOperation::Replace { start_line, old_content, .. } =>
+        {
+            (old_content.as_str(), *start_line)
+        }
+        EditOperation::Delete { start_line, old_content: Some(old), .. } =>
+        {
+            (old.as_str(), *start_line)
+        }
+        EditOperation::Insert { at_line, after_content: Some(ctx), .. } => (ctx.as_str(), *at_line),
+        _ => return None,
+    };
+    let (span, similarity) = matcher.locate_closest(file_lines, anchor, near)?;
+    if similarity < SUGGESTION_MIN_SIMILARITY
+    {
+        return None;
+    }
+
+    let header = match op
+    {
+        EditOperation::Replace { .. } => format!("REPLACE lines {}-{}:", span.0, span.1),
+        EditOperation::Delete { .. } => format!("DELETE lines {}-{}", span.0, span.1),
+        EditOperation::Insert { .. } => format!("INSERT at {}:", span.1),
+    };
+    Some(SpanSuggestion { span, similarity, header })
+}
+
+/// Split content into lines without terminators plus each line's own
+/// ending ("" for an unterminated last line)
+fn split_lines_with_eol(content: &str) -> (Vec<String>, Vec<&'static str>)
+{
+    content
+        .split_inclusive('\n')
+        .map(|piece| {
+            if let Some(text) = piece.strip_suffix("\r\n")
+            {
+                (text.to_string(), "\r\n")
+            }
+            else if let Some(text) = piece.strip_suffix('\n')
+            {
+                (text.to_string(), "\n")
+            }
+            else
+            {
+                (piece.to_string(), "")
+            }
+        })
+        .unzip()
+}
+
+/// Most frequent line ending; ties and files without endings use LF
+fn dominant_eol(eols: &[&str]) -> &'static str
+{
+    let crlf = eols
+        .iter()
+        .filter(|e| **e == "\r\n")
+        .count();
+    let lf = eols
+        .iter()
+        .filter(|e| **e == "\n")
+        .count();
+    if crlf > lf { "\r\n" } else { "\n" }
+}
+
+/// Windowed search for `needle` starting within `max_drift` lines of
+/// `anchor` (1-based). Nearest candidates are tried first; earlier wins ties.
+fn locate_within_drift<S: AsRef<str>>(
+    file_lines: &[S],
+    needle: &str,
+    anchor: usize,
+    max_drift: usize,
+) -> Option<(usize, usize)>
+{
+    let want = normalize_for_cid(needle);
+    let height = needle
+        .lines()
+        .count()
+        .max(1);
+    if height > file_lines.len()
+    {
+        return None;
+    }
+    let last_start = file_lines.len() - height + 1;
+
+    let matches_at = |start: usize| -> bool {
+        let window: Vec<&str> = file_lines[start - 1..start - 1 + height]
+            .iter()
+            .map(|l| l.as_ref())
+            .collect();
+        normalize_for_cid(&window.join("\n")) == want
+    };
+
+    for d in 0..=max_drift
+    {
+        for start in [anchor.checked_sub(d), anchor.checked_add(d)]
+            .into_iter()
+            .flatten()
+        {
+            if (1..=last_start).contains(&start) && matches_at(start)
+            {
+                return Some((start, start + height - 1));
+            }
+        }
+    }
+
+    None
 }
 
 /// Command handlers for CLI integration
@@ -1363,31 +2513,69 @@ pub fn apply_run(
     ctx: &AppContext,
 ) -> Result<()>
 {
-    // 1) Parse input (file or clipboard)
-    let ebnf = if let Some(file_path) = &args.edit_file
-    {
-        fs::read_to_string(file_path)
-            .with_context(|| format!("Failed to read edit file: {:?}", file_path))?
-    }
-    else if args.from_clipboard
+    if args.list_engines
     {
-        get_clipboard_content()?
+        print_engine_list();
+        return Ok(());
     }
-    else
+
+    // Only git can target the index without touching the worktree
+    if args.stage && args.engine != "git"
     {
         return Err(ApplyCliError::InvalidInput(
-            "Must specify either --edit-file or --from-clipboard".to_string(),
+            "--stage applies through git's index; use it with --engine git".to_string(),
         )
         .into());
-    };
+    }
 
-    // 2) Build edit specification
+    // 1) Parse input (one or more files, or clipboard)
     let legacy_engine = EditEngine::new();
-    let input = normalize_edit_spec_text(&ebnf);
-
-    let spec = legacy_engine
-        .parse_edit_spec(&input)
-        .map_err(|e| ApplyCliError::InvalidInput(format!("Parse error: {}", e)))?;
+    let parse = |text: &str, origin: &str| {
+        legacy_engine
+            .parse_edit_spec(&normalize_edit_spec_text(text))
+            .map_err(|e| ApplyCliError::InvalidInput(format!("Parse error{}: {}", origin, e)))
+    };
+    let spec = if !args
+        .edit_files
+        .is_empty()
+    {
+        // 2) Build edit specification, merging a batch into one spec
+        let mut specs = Vec::with_capacity(
+            args.edit_files
+                .len(),
+        );
+        for file_path in &args.edit_files
+        {
+            let ebnf = fs::read_to_string(file_path)
+                .with_context(|| format!("Failed to read edit file: {:?}", file_path))?;
+            // Name the offending file only when there is more than one
+            let origin = if args
+                .edit_files
+                .len()
+                > 1
+            {
+                format!(" in {}", file_path.display())
+            }
+            else
+            {
+                String::new()
+            };
+            let spec = parse(&ebnf, &origin)?;
+            specs.push((file_path.clone(), spec));
+        }
+        merge_specs(specs)?
+    }
+    else if args.from_clipboard
+    {
+        parse(&get_clipboard_content()?, "")?
+    }
+    else
+    {
+        return Err(ApplyCliError::InvalidInput(
+            "Must specify either --edit-file or --from-clipboard".to_string(),
+        )
+        .into());
+    };
 
     // 3) Decide run mode: safe default is preview unless --apply was passed
     let run_mode = if args.apply
@@ -1412,52 +2600,58 @@ pub fn apply_run(
     )
     .context("Failed to detect repository root")?;
 
-    // 5) Create engine via factory with auto-fallback support
-    let engine: Box<dyn crate::core::apply_engine::ApplyEngine> =
-        match (&args.engine, repo_root.clone())
-        {
-            (crate::cli::ApplyEngine::Git, None) =>
-            {
-                return Err(ApplyCliError::Repo(
-                    "Git engine requires a repository. Use --engine=internal or init a repo."
-                        .to_string(),
-                )
-                .into());
-            }
-            (crate::cli::ApplyEngine::Auto, None) =>
-            {
-                // Degrade gracefully to internal-only auto
-                if !ctx.quiet
-                {
-                    eprintln!("No git repository found, using internal engine for --engine=auto");
-                }
-                create_engine(
-                    &crate::cli::ApplyEngine::Internal,
-                    &args.git_mode,
-                    &args.whitespace,
-                    args.backup,
-                    args.force,
-                    cwd.clone(),
-                    args.context_lines,
-                )
-                .map_err(|e| ApplyCliError::Internal(format!("Engine creation failed: {}", e)))?
-            }
-            _ =>
-            {
-                create_engine(
-                    &args.engine,
-                    &args.git_mode,
-                    &args.whitespace,
-                    args.backup,
-                    args.force,
-                    repo_root
-                        .clone()
-                        .unwrap_or_else(|| cwd.clone()),
-                    args.context_lines,
-                )
-                .map_err(|e| ApplyCliError::Internal(format!("Engine creation failed: {}", e)))?
-            }
-        };
+    // 4b) Keep every target path inside the repo unless explicitly allowed
+    if !args.allow_outside_repo
+    {
+        enforce_path_sandbox(
+            &spec,
+            repo_root
+                .as_ref()
+                .unwrap_or(&cwd),
+            &cwd,
+        )?;
+    }
+
+    // 5) Create engine via registry with auto-fallback support
+    let engine_config = EngineConfig {
+        repo_root: cwd.clone(),
+        git_mode: args
+            .git_mode
+            .clone(),
+        git_backend: args.git_backend,
+        whitespace: args.whitespace,
+        backup_enabled: args.backup,
+        force_mode: args.force,
+        context_lines: args.context_lines,
+        max_drift: args.max_drift,
+        eol: args.eol,
+        max_file_bytes: (args.max_file_size > 0).then_some(args.max_file_size),
+        allow_binary: args.force_binary,
+        stage: args.stage,
+        context_rules: context_rules(
+            &args.context_for,
+            repo_root
+                .as_deref()
+                .unwrap_or(&cwd),
+        ),
+    };
+
+    // 5a) --branch applies in a worktree of the branch, never the real tree
+    if args
+        .branch
+        .is_some()
+    {
+        run_branch_apply(&args, &spec, repo_root.as_deref(), &cwd, run_mode, engine_config, ctx)?;
+        return Ok(());
+    }
+
+    let engine = select_engine(
+        &args.engine,
+        repo_root.as_deref(),
+        &cwd,
+        engine_config.clone(),
+        ctx,
+    )?;
 
     // 6) Always check() first for consistent preview
     let preview = engine
@@ -1499,6 +2693,10 @@ pub fn apply_run(
             {
                 eprintln!("  • {}", conflict);
             }
+            for suggestion in &preview.suggestions
+            {
+                eprintln!("  ↳ {}", suggestion);
+            }
             if args.resolve
             {
                 eprintln!("Attempting auto-resolution with smart conflict detection...");
@@ -1533,12 +2731,24 @@ pub fn apply_run(
                                 .path
                                 .clone(),
                         ],
-                        strategy: crate::core::resolve::ResolveStrategy::Smart,
+                        strategy: None,
                         auto_resolve_safe: true,
                         show_diff: false,
                         repo_root: repo_root.clone(),
                         backup: true, // Ensure safety: resolver creates session
                         apply: true,
+                        trivial_side: None,
+                        no_rerere: false,
+                        no_blame: false,
+                        verify_cmd: None,
+                        interactive: false,
+                        emit_prompt: None,
+                        budget: None,
+                        model: "gpt-4o".to_string(),
+                        tokenizer_file: None,
+                        explain: false,
+                        preview: false,
+                        color: crate::cli::ColorMode::Never,
                         json: false,
                     };
 
@@ -1620,28 +2830,43 @@ pub fn apply_run(
         }
     }
 
+    // 8b) Trial run in a scratch worktree before the real tree is touched
+    if args.sandbox
+    {
+        run_sandbox_trial(&args, &spec, repo_root.as_deref(), &cwd, engine_config, ctx)?;
+    }
+
     // 9) Stop here if Preview mode
     if run_mode == RunMode::Preview
     {
+        if let Some(agent) = &ctx.agent
+        {
+            agent.warn("preview only: no files written (pass --apply)");
+        }
         return Ok(());
     }
 
     // 10) Apply for real - set up backup session if enabled
     let report = if args.backup
     {
+        let backup_root = repo_root
+            .as_ref()
+            .unwrap_or(&cwd);
+        let scope = match args.backup_scope
+        {
+            BackupScopeMode::Written => BackupScope::Written,
+            BackupScopeMode::Dirs => BackupScope::Dirs(spec_dirs(&spec, backup_root)),
+        };
+
         // Create backup manager and use contextual API
-        let mut backup_manager = BackupManager::begin(
-            repo_root
-                .as_ref()
-                .unwrap_or(&cwd),
-            match args.engine
-            {
-                crate::cli::ApplyEngine::Internal => "internal",
-                crate::cli::ApplyEngine::Git => "git",
-                crate::cli::ApplyEngine::Auto => "auto",
-            },
-        )
-        .map_err(|e| ApplyCliError::Internal(format!("Backup setup failed: {}", e)))?;
+        let mut backup_manager = BackupManager::begin_scoped(backup_root, &args.engine, &scope)
+            .map_err(|e| ApplyCliError::Internal(format!("Backup setup failed: {}", e)))?;
+        backup_manager.annotate(
+            args.label
+                .clone(),
+            args.note
+                .clone(),
+        );
 
         let apply_ctx = crate::core::apply_engine::ApplyContext {
             repo_root: repo_root
@@ -1655,23 +2880,60 @@ pub fn apply_run(
 
         engine
             .apply_with_ctx(&spec, apply_ctx)
-            .map_err(|e| {
-                let (kind, _code) = normalize_err_typed(e);
-                ApplyCliError::from(kind)
-            })?
+            .map_err(|e| apply_failure(e, args.json))?
     }
     else
     {
         // No backup - use legacy API
         engine
             .apply(&spec)
-            .map_err(|e| {
-                let (kind, _code) = normalize_err_typed(e);
-                ApplyCliError::from(kind)
-            })?
+            .map_err(|e| apply_failure(e, args.json))?
     };
 
+    // 10b) Commit exactly the touched files
+    let mut report = report;
+    if args.commit
+    {
+        let git_root = repo_root
+            .as_deref()
+            .ok_or_else(|| ApplyCliError::Repo("--commit needs a git repository".into()))?;
+        let files: Vec<PathBuf> = report
+            .applied_files
+            .iter()
+            .map(|f| cwd.join(f))
+            .collect();
+        let template = args
+            .commit_message
+            .as_deref()
+            .unwrap_or(DEFAULT_COMMIT_TEMPLATE);
+        let message = render_commit_message(template, &report, &files, git_root);
+        let hash = crate::core::git::commit_files(git_root, &files, &message, args.signoff)
+            .map_err(|e| ApplyCliError::Repo(format!("commit failed: {e}")))?;
+        report.commit = Some(hash);
+    }
+
     // 11) Report results with session-based backup info
+    if let Some(agent) = &ctx.agent
+    {
+        for file in &report.applied_files
+        {
+            agent.artifact("modified", file);
+        }
+        if let Some(session_dir) = report
+            .backup_paths
+            .first()
+            && report
+                .backup_session_id
+                .is_some()
+        {
+            agent.artifact("backup", session_dir);
+        }
+        for conflict in &report.conflicts
+        {
+            agent.warn(format!("forced past conflict: {conflict}"));
+        }
+    }
+
     if args.json
     {
         // JSON output (single line for machine parsing)
@@ -1702,6 +2964,58 @@ pub fn apply_run(
             }
         }
 
+        // Which engine of an --engine auto fallback chain did the work
+        if report
+            .attempts
+            .len()
+            > 1
+        {
+            for attempt in &report.attempts
+            {
+                match &attempt.failure
+                {
+                    Some(why) => println!("Fallback: {:?} gave up: {}", attempt.engine, why),
+                    None => println!(
+                        "Fallback: {:?} applied {} file(s)",
+                        attempt.engine,
+                        attempt
+                            .files
+                            .len()
+                    ),
+                }
+            }
+        }
+
+        // Diffstat-style summary for CI logs
+        match &report.backup_session_id
+        {
+            Some(id) => println!("Summary: {}, backup {}", report.stats, id),
+            None => println!("Summary: {}", report.stats),
+        }
+        if let Some(hash) = &report.commit
+        {
+            println!("Commit: {}", hash);
+        }
+        if args
+            .edit_files
+            .len()
+            > 1
+        {
+            println!(
+                "Batch: {} specs ({})",
+                args.edit_files
+                    .len(),
+                args.edit_files
+                    .iter()
+                    .map(|p| {
+                        p.display()
+                            .to_string()
+                    })
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            );
+        }
+
         // Show session-based backup info
         if let Some(_session_id) = &report.backup_session_id
             && let Some(session_dir) = report
@@ -1720,6 +3034,86 @@ pub fn apply_run(
     Ok(())
 }
 
+/// Commit message used by `apply --commit` without `-m`
+pub const DEFAULT_COMMIT_TEMPLATE: &str =
+    "rup apply: {summary}\n\n{stats}\n\n{files}\n\nBackup-Session: {session}";
+
+/// Fill an `apply --commit` message template. Lines that use `{session}`
+/// are dropped when the apply ran without a backup.
+fn render_commit_message(
+    template: &str,
+    report: &crate::core::apply_engine::ApplyReport,
+    files: &[PathBuf],
+    repo_root: &Path,
+) -> String
+{
+    let rel: Vec<String> = files
+        .iter()
+        .map(|f| {
+            make_relative_to_repo(f, repo_root)
+                .unwrap_or_else(|_| f.clone())
+                .display()
+                .to_string()
+        })
+        .collect();
+    let summary = match rel.as_slice()
+    {
+        [one] => format!("update {one}"),
+        many => format!("update {} files", many.len()),
+    };
+    let stats = format!(
+        "{} file{} changed, +{} -{}",
+        report
+            .stats
+            .files_touched,
+        if report
+            .stats
+            .files_touched
+            == 1
+        {
+            ""
+        }
+        else
+        {
+            "s"
+        },
+        report
+            .stats
+            .lines_added,
+        report
+            .stats
+            .lines_removed
+    );
+    let file_list = rel
+        .iter()
+        .map(|f| format!("- {f}"))
+        .collect::<Vec<_>>()
+        .join("\n");
+    let engine = format!("{:?}", report.engine_used).to_lowercase();
+
+    template
+        .lines()
+        .filter_map(|line| {
+            let session = match &report.backup_session_id
+            {
+                Some(id) => id.as_str(),
+                None if line.contains("{session}") => return None,
+                None => "",
+            };
+            Some(
+                line.replace("{summary}", &summary)
+                    .replace("{stats}", &stats)
+                    .replace("{files}", &file_list)
+                    .replace("{session}", session)
+                    .replace("{engine}", &engine),
+            )
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+        .trim_end()
+        .to_string()
+}
+
 struct TokenMatcher
 {
     bpe: tiktoken_rs::CoreBPE,
@@ -1734,6 +3128,111 @@ impl TokenMatcher
         Ok(Self { bpe })
     }
 
+    /// Find the window of `needle_text`'s line count whose tokens overlap it
+    /// most (Dice coefficient over token multisets). Ties go to the window
+    /// nearest `near`. Returns the 1-based span and similarity in percent.
+    fn locate_closest(
+        &self,
+        file_lines: &[&str],
+        needle_text: &str,
+        near: usize,
+    ) -> Option<((usize, usize), u8)>
+    {
+        use std::collections::HashMap;
+
+        let width = needle_text
+            .lines()
+            .count();
+        if width == 0 || width > file_lines.len()
+        {
+            return None;
+        }
+
+        let encode = |s: &str| {
+            self.bpe
+                .encode_ordinary(&normalize_for_cid(s))
+        };
+        // Encode line by line on both sides so a moved block scores 100
+        let mut needle: HashMap<u32, usize> = HashMap::new();
+        let needle_ids: Vec<u32> = needle_text
+            .lines()
+            .flat_map(encode)
+            .collect();
+        for id in &needle_ids
+        {
+            *needle
+                .entry(*id)
+                .or_default() += 1;
+        }
+        let line_ids: Vec<Vec<u32>> = file_lines
+            .iter()
+            .map(|l| encode(l))
+            .collect();
+
+        // Slide a window of `width` lines, updating token counts incrementally
+        let mut window: HashMap<u32, usize> = HashMap::new();
+        let mut window_len = 0usize;
+        let mut best: Option<(usize, f64)> = None;
+        for (i, ids) in line_ids
+            .iter()
+            .enumerate()
+        {
+            for id in ids
+            {
+                *window
+                    .entry(*id)
+                    .or_default() += 1;
+            }
+            window_len += ids.len();
+            if i + 1 < width
+            {
+                continue;
+            }
+            if i >= width
+            {
+                for id in &line_ids[i - width]
+                {
+                    if let Some(c) = window.get_mut(id)
+                    {
+                        *c -= 1;
+                    }
+                }
+                window_len -= line_ids[i - width].len();
+            }
+
+            let overlap: usize = needle
+                .iter()
+                .map(|(id, n)| {
+                    (*n).min(
+                        window
+                            .get(id)
+                            .copied()
+                            .unwrap_or(0),
+                    )
+                })
+                .sum();
+            let total = needle_ids.len() + window_len;
+            let score = if total == 0 { 0.0 } else { 2.0 * overlap as f64 / total as f64 };
+
+            let start = i + 2 - width; // 1-based
+            let better = match best
+            {
+                None => true,
+                Some((b_start, b_score)) =>
+                {
+                    score > b_score
+                        || (score == b_score && start.abs_diff(near) < b_start.abs_diff(near))
+                }
+            };
+            if better
+            {
+                best = Some((start, score));
+            }
+        }
+
+        best.map(|(start, score)| ((start, start + width - 1), (score * 100.0).round() as u8))
+    }
+
     /// Find the first exact token-subsequence match of `needle_text` in `file_lines`.
     /// Returns 1-based (start_line, end_line) on success.
     fn locate_exact(
@@ -1808,6 +3307,12 @@ pub fn preview_run(
     ctx: &AppContext,
 ) -> Result<()>
 {
+    if args.list_engines
+    {
+        print_engine_list();
+        return Ok(());
+    }
+
     let input = if args.from_clipboard
     {
         get_clipboard_content()?
@@ -1837,49 +3342,47 @@ pub fn preview_run(
     )
     .context("Failed to detect repository root")?;
 
+    // Same path sandbox as apply_run
+    if !args.allow_outside_repo
+    {
+        enforce_path_sandbox(
+            &spec,
+            repo_root
+                .as_ref()
+                .unwrap_or(&cwd),
+            &cwd,
+        )?;
+    }
+
     // Use same engine logic as apply_run for consistency
-    let engine: Box<dyn crate::core::apply_engine::ApplyEngine> =
-        match (&args.engine, repo_root.clone())
-        {
-            (crate::cli::ApplyEngine::Git, None) =>
-            {
-                return Err(ApplyCliError::Repo(
-                    "Git engine requires a repository. Use --engine=internal or init a repo."
-                        .to_string(),
-                )
-                .into());
-            }
-            (crate::cli::ApplyEngine::Auto, None) =>
-            {
-                if !ctx.quiet
-                {
-                    eprintln!("No git repository found, using internal engine for --engine=auto");
-                }
-                create_engine(
-                    &crate::cli::ApplyEngine::Internal,
-                    &args.git_mode,
-                    &args.whitespace,
-                    false, // backup
-                    args.force,
-                    cwd.clone(),
-                    args.context_lines,
-                )
-                .map_err(|e| ApplyCliError::Internal(format!("Engine creation failed: {}", e)))?
-            }
-            _ =>
-            {
-                create_engine(
-                    &args.engine,
-                    &args.git_mode,
-                    &args.whitespace,
-                    false, // backup
-                    args.force,
-                    repo_root.unwrap_or_else(|| cwd.clone()),
-                    args.context_lines,
-                )
-                .map_err(|e| ApplyCliError::Internal(format!("Engine creation failed: {}", e)))?
-            }
-        };
+    let engine = select_engine(
+        &args.engine,
+        repo_root.as_deref(),
+        &cwd,
+        EngineConfig {
+            repo_root: cwd.clone(),
+            git_mode: args
+                .git_mode
+                .clone(),
+            git_backend: args.git_backend,
+            whitespace: args.whitespace,
+            backup_enabled: false,
+            force_mode: args.force,
+            context_lines: args.context_lines,
+            max_drift: args.max_drift,
+            eol: args.eol,
+            max_file_bytes: (args.max_file_size > 0).then_some(args.max_file_size),
+            allow_binary: args.force_binary,
+            stage: false,
+            context_rules: context_rules(
+                &args.context_for,
+                repo_root
+                    .as_deref()
+                    .unwrap_or(&cwd),
+            ),
+        },
+        ctx,
+    )?;
 
     let preview = engine
         .check(&spec)
@@ -1895,7 +3398,21 @@ pub fn preview_run(
                 .patch_content
                 .is_empty()
         {
-            println!("{}", preview.patch_content);
+            let color = !ctx.no_color
+                && match args.color
+                {
+                    ColorMode::Always => true,
+                    ColorMode::Never => false,
+                    ColorMode::Auto => std::io::IsTerminal::is_terminal(&std::io::stdout()),
+                };
+            if color
+            {
+                print!("{}", crate::core::diff_color::render_colored_diff(&preview.patch_content));
+            }
+            else
+            {
+                println!("{}", preview.patch_content);
+            }
         }
         println!("{}", preview.summary);
         if !preview
@@ -1912,11 +3429,184 @@ pub fn preview_run(
             {
                 eprintln!("  • {}", conflict);
             }
+            for suggestion in &preview.suggestions
+            {
+                eprintln!("  ↳ {}", suggestion);
+            }
             eprintln!("Suggestion: Use --engine=git --mode=3way for robust conflict resolution");
         }
     }
 
-    Ok(())
+    Ok(())
+}
+
+/// Write the patches an edit spec produces, leaving the worktree alone
+pub fn patch_run(
+    args: PatchArgs,
+    ctx: &AppContext,
+) -> Result<()>
+{
+    let input = if args.from_clipboard
+    {
+        get_clipboard_content()?
+    }
+    else if let Some(file_path) = &args.edit_file
+    {
+        fs::read_to_string(file_path)
+            .with_context(|| format!("Failed to read edit file: {:?}", file_path))?
+    }
+    else
+    {
+        anyhow::bail!("Must specify either --from-clipboard or provide edit file");
+    };
+    let spec = parse_spec_text(&input)?;
+
+    let cwd = std::env::current_dir().context("Failed to get current directory")?;
+    let root = discover_repo_root(None, &cwd)?.unwrap_or(cwd);
+    let config = crate::core::patch::PatchConfig {
+        context_lines: args.context_lines,
+        validate_guards: !args.no_guard,
+        context_rules: context_rules(&args.context_for, &root),
+        ..Default::default()
+    };
+    let patch_set = crate::core::patch::generate_patches(&spec, &config)?;
+    if patch_set
+        .file_patches
+        .is_empty()
+    {
+        anyhow::bail!("edit spec produces no changes");
+    }
+
+    // One rendered unit per file: a diff or a format-patch email
+    let units: Vec<String> = match args.format
+    {
+        PatchFormat::Diff => patch_set
+            .file_patches
+            .iter()
+            .map(|file_patch| {
+                crate::core::patch::render_unified_diff(&crate::core::patch::PatchSet {
+                    file_patches: vec![file_patch.clone()],
+                })
+            })
+            .collect(),
+        PatchFormat::Mbox =>
+        {
+            let opts = crate::core::patch::MailOptions {
+                author: render_patch_author(&args.author),
+                subject: args
+                    .subject
+                    .clone(),
+                date: chrono::Local::now().fixed_offset(),
+            };
+            crate::core::patch::render_mbox(&patch_set, &opts)
+        }
+    };
+
+    if !args.split
+    {
+        let combined = units.concat();
+        match &args.output
+        {
+            Some(path) =>
+            {
+                fs::write(path, combined)
+                    .with_context(|| format!("Failed to write patch: {}", path.display()))?;
+                if !ctx.quiet
+                {
+                    eprintln!("Wrote {} file patch(es) to {}", units.len(), path.display());
+                }
+            }
+            None => print!("{}", combined),
+        }
+        return Ok(());
+    }
+
+    let dir = args
+        .output
+        .clone()
+        .unwrap_or_else(|| PathBuf::from("patches"));
+    fs::create_dir_all(&dir)
+        .with_context(|| format!("Failed to create patch directory: {}", dir.display()))?;
+    for (i, (file_patch, unit)) in patch_set
+        .file_patches
+        .iter()
+        .zip(&units)
+        .enumerate()
+    {
+        let name = format!("{:04}-{}.patch", i + 1, patch_file_stem(&file_patch.path));
+        let path = dir.join(name);
+        fs::write(&path, unit)
+            .with_context(|| format!("Failed to write patch: {}", path.display()))?;
+        if !ctx.quiet
+        {
+            println!("{}", path.display());
+        }
+    }
+    Ok(())
+}
+
+/// Fill `{name}` and `{email}` in an author template from git config
+fn render_patch_author(template: &str) -> String
+{
+    let git_config = |key: &str, fallback: &str| {
+        std::process::Command::new("git")
+            .args(["config", key])
+            .output()
+            .ok()
+            .filter(|o| {
+                o.status
+                    .success()
+            })
+            .map(|o| {
+                String::from_utf8_lossy(&o.stdout)
+                    .trim()
+                    .to_string()
+            })
+            .filter(|v| !v.is_empty())
+            .unwrap_or_else(|| fallback.to_string())
+    };
+    let mut author = template.to_string();
+    if author.contains("{name}")
+    {
+        author = author.replace("{name}", &git_config("user.name", "rup"));
+    }
+    if author.contains("{email}")
+    {
+        author = author.replace("{email}", &git_config("user.email", "rup@localhost"));
+    }
+    author
+}
+
+/// Parse an EBNF edit spec, or a JSON one when the text starts with `{`
+fn parse_spec_text(input: &str) -> Result<EditSpec>
+{
+    if input
+        .trim_start()
+        .starts_with('{')
+    {
+        return serde_json::from_str(input).context("Failed to parse JSON edit specification");
+    }
+    EditEngine::new()
+        .parse_edit_spec(&normalize_edit_spec_text(input))
+        .context("Failed to parse edit specification")
+}
+
+/// Flatten a repo path into a patch file name: `src/a.rs` -> `src-a.rs`
+fn patch_file_stem(path: &str) -> String
+{
+    path.trim_start_matches("./")
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || c == '.' || c == '_' || c == '-'
+            {
+                c
+            }
+            else
+            {
+                '-'
+            }
+        })
+        .collect()
 }
 
 /// Validate edit syntax without applying changes
@@ -1965,18 +3655,17 @@ pub fn check_syntax_run(
 
             if !missing_files.is_empty()
             {
-                println!("Referenced files not found:");
-                for file in missing_files
-                {
-                    println!("   • {}", file.display());
-                }
-                std::process::exit(1);
+                let list: String = missing_files
+                    .iter()
+                    .map(|file| format!("\n   • {}", file.display()))
+                    .collect();
+                let msg = format!("Referenced files not found:{list}");
+                return Err(ApplyErr::InvalidSpec(msg).into());
             }
         }
         Err(e) =>
         {
-            eprintln!("Edit syntax error: {}", e);
-            std::process::exit(1);
+            return Err(ApplyErr::InvalidSpec(format!("Edit syntax error: {e}")).into());
         }
     }
 
@@ -1998,6 +3687,13 @@ pub fn backup_run(
         BackupSubcommand::Show(show_args) => backup_show(&repo_root, &show_args, ctx),
         BackupSubcommand::Restore(restore_args) => backup_restore(&repo_root, &restore_args, ctx),
         BackupSubcommand::Cleanup(cleanup_args) => backup_cleanup(&repo_root, &cleanup_args, ctx),
+        BackupSubcommand::Gc(gc_args) => backup_gc(&repo_root, &gc_args, ctx),
+        BackupSubcommand::Diff(diff_args) => backup_diff(&repo_root, &diff_args, ctx),
+        BackupSubcommand::Export(export_args) => backup_export(&repo_root, &export_args, ctx),
+        BackupSubcommand::Import(import_args) => backup_import(&repo_root, &import_args, ctx),
+        BackupSubcommand::Push(push_args) => backup_push(&repo_root, &push_args, ctx),
+        BackupSubcommand::ToStash(stash_args) => backup_to_stash(&repo_root, &stash_args, ctx),
+        BackupSubcommand::FromStash(stash_args) => backup_from_stash(&repo_root, &stash_args, ctx),
     }
 }
 
@@ -2020,6 +3716,9 @@ fn backup_list(
             .clone(),
         limit: a.limit,
         sort_desc,
+        label: a
+            .label
+            .clone(),
     };
 
     let sessions = list_sessions_filtered(repo_root, req)?;
@@ -2030,6 +3729,63 @@ fn backup_list(
         return Ok(());
     }
 
+    match a.format
+    {
+        BackupListFormat::Text =>
+        {}
+        BackupListFormat::Table =>
+        {
+            let mut builder = tabled::builder::Builder::default();
+            builder.push_record(
+                a.columns
+                    .iter()
+                    .map(|c| column_name(*c)),
+            );
+            for s in &sessions
+            {
+                builder.push_record(
+                    a.columns
+                        .iter()
+                        .map(|c| cell_text(&column_value(s, *c))),
+                );
+            }
+            println!("{}", builder.build());
+            return Ok(());
+        }
+        BackupListFormat::Csv =>
+        {
+            let header: Vec<_> = a
+                .columns
+                .iter()
+                .map(|c| column_name(*c))
+                .collect();
+            println!("{}", header.join(","));
+            for s in &sessions
+            {
+                let row: Vec<_> = a
+                    .columns
+                    .iter()
+                    .map(|c| csv_field(&cell_text(&column_value(s, *c))))
+                    .collect();
+                println!("{}", row.join(","));
+            }
+            return Ok(());
+        }
+        BackupListFormat::Jsonl =>
+        {
+            for s in &sessions
+            {
+                let row: serde_json::Map<_, _> = a
+                    .columns
+                    .iter()
+                    .map(|c| (column_name(*c).to_string(), column_value(s, *c)))
+                    .collect();
+                println!("{}", serde_json::Value::Object(row));
+            }
+            return Ok(());
+        }
+    }
+
     if sessions.is_empty()
     {
         if !ctx.quiet
@@ -2046,6 +3802,85 @@ fn backup_list(
     Ok(())
 }
 
+fn column_name(c: BackupListColumn) -> &'static str
+{
+    match c
+    {
+        BackupListColumn::Id => "id",
+        BackupListColumn::Timestamp => "timestamp",
+        BackupListColumn::Engine => "engine",
+        BackupListColumn::Success => "success",
+        BackupListColumn::Files => "files",
+        BackupListColumn::Label => "label",
+        BackupListColumn::Paths => "paths",
+    }
+}
+
+/// Typed value of one column; jsonl keeps the types, table/csv flatten them
+fn column_value(
+    s: &SessionInfo,
+    c: BackupListColumn,
+) -> serde_json::Value
+{
+    match c
+    {
+        BackupListColumn::Id => s
+            .id
+            .clone()
+            .into(),
+        BackupListColumn::Timestamp => s
+            .timestamp
+            .clone()
+            .into(),
+        BackupListColumn::Engine => s
+            .engine
+            .clone()
+            .into(),
+        BackupListColumn::Success => s
+            .success
+            .into(),
+        BackupListColumn::Files => s
+            .files
+            .into(),
+        BackupListColumn::Label => s
+            .label
+            .clone()
+            .into(),
+        BackupListColumn::Paths => s
+            .sample_paths
+            .clone()
+            .into(),
+    }
+}
+
+fn cell_text(v: &serde_json::Value) -> String
+{
+    match v
+    {
+        serde_json::Value::Null => String::new(),
+        serde_json::Value::String(s) => s.clone(),
+        serde_json::Value::Array(items) => items
+            .iter()
+            .map(cell_text)
+            .collect::<Vec<_>>()
+            .join(";"),
+        other => other.to_string(),
+    }
+}
+
+/// Quote a CSV field when it contains a delimiter, quote, or line break
+fn csv_field(s: &str) -> String
+{
+    if s.contains([',', '"', '\n', '\r'])
+    {
+        format!("\"{}\"", s.replace('"', "\"\""))
+    }
+    else
+    {
+        s.to_string()
+    }
+}
+
 fn print_session_line(s: &SessionInfo)
 {
     let status = if s.success { "success" } else { "failed" };
@@ -2063,13 +3898,19 @@ fn print_session_line(s: &SessionInfo)
                 .join(", ")
         )
     };
+    let label = s
+        .label
+        .as_deref()
+        .map(|l| format!("  \"{l}\""))
+        .unwrap_or_default();
     println!(
-        "{timestamp:<19} {id:<12} {engine:<10} files={files:>4} {status:<7}{samples}",
+        "{timestamp:<19} {id:<12} {engine:<10} files={files:>4} {status:<7}{label}{samples}",
         timestamp = s.timestamp,
         id = s.id,
         engine = s.engine,
         files = s.files,
         status = status,
+        label = label,
         samples = samples
     );
 }
@@ -2098,6 +3939,14 @@ fn backup_show(
     println!("timestamp: {}", m.timestamp);
     println!("engine: {}", m.engine);
     println!("success: {}", m.success);
+    if let Some(label) = &m.label
+    {
+        println!("label: {}", label);
+    }
+    if let Some(note) = &m.note
+    {
+        println!("note: {}", note);
+    }
     println!(
         "files: {}",
         m.files
@@ -2119,10 +3968,11 @@ fn backup_show(
         for f in &m.files
         {
             println!(
-                "  - {} ({} bytes)",
+                "  - {} ({} bytes{})",
                 f.rel_path
                     .display(),
-                f.size_bytes
+                f.size_bytes,
+                if f.encrypted { ", encrypted" } else { "" }
             );
         }
     }
@@ -2238,52 +4088,316 @@ fn backup_restore(
         return Ok(());
     }
 
-    // Show restored files
-    if !result
-        .restored
-        .is_empty()
-        && !ctx.quiet
+    // Show restored files
+    if !result
+        .restored
+        .is_empty()
+        && !ctx.quiet
+    {
+        println!(
+            "Restored {} file(s):",
+            result
+                .restored
+                .len()
+        );
+        for restored_path in &result.restored
+        {
+            println!("  - {}", restored_path.display());
+        }
+    }
+
+    // Show backup information if current files were backed up
+    if result.backed_up_current
+        && let Some(ref backup_session) = result.backup_session_id
+        && !ctx.quiet
+    {
+        println!("Current files backed up to session: {}", backup_session);
+    }
+
+    Ok(())
+}
+
+fn backup_cleanup(
+    repo_root: &Path,
+    a: &BackupCleanupArgs,
+    ctx: &AppContext,
+) -> Result<()>
+{
+    let is_dry_run = a.dry_run || ctx.dry_run; // Honor global dry-run flag
+    let req = CleanupRequest {
+        older_than: a
+            .older_than
+            .clone(),
+        keep_latest: a.keep_latest,
+        max_total_size: a
+            .max_total_size
+            .as_deref()
+            .map(parse_size)
+            .transpose()?,
+        include_incomplete: a.include_incomplete,
+        dry_run: is_dry_run,
+        protect: None,
+    };
+
+    let result = cleanup_sessions(repo_root, req)?;
+
+    if a.json
+    {
+        println!("{}", serde_json::to_string_pretty(&result)?);
+        return Ok(());
+    }
+
+    // Human-friendly output
+    if is_dry_run && !ctx.quiet
+    {
+        println!("DRY RUN - no sessions were deleted");
+    }
+
+    if result
+        .sessions_removed
+        .is_empty()
+    {
+        if !ctx.quiet
+        {
+            println!("No sessions matched cleanup criteria");
+        }
+        return Ok(());
+    }
+
+    if !ctx.quiet
+    {
+        let action = if is_dry_run
+        {
+            "Would remove"
+        }
+        else
+        {
+            "Removed"
+        };
+        println!(
+            "{} {} session(s):",
+            action,
+            result
+                .sessions_removed
+                .len()
+        );
+        for session_id in &result.sessions_removed
+        {
+            println!("  - {}", session_id);
+        }
+
+        if result.bytes_freed > 0
+        {
+            println!("Space freed: {} bytes", result.bytes_freed);
+        }
+    }
+
+    Ok(())
+}
+
+fn backup_diff(
+    repo_root: &Path,
+    a: &BackupDiffArgs,
+    ctx: &AppContext,
+) -> Result<()>
+{
+    // `--against worktree` is the only alternative to a second session
+    let result = diff_sessions(repo_root, DiffRequest {
+        from: a
+            .from
+            .clone(),
+        to: a
+            .to
+            .clone(),
+        path: a
+            .path
+            .clone(),
+    })?;
+
+    if a.json
+    {
+        println!("{}", serde_json::to_string_pretty(&result)?);
+        return Ok(());
+    }
+
+    for diff in &result.diffs
+    {
+        println!(
+            "{}",
+            diff.unified
+                .trim_end()
+        );
+    }
+
+    if !ctx.quiet
+    {
+        for p in &result.only_in_from
+        {
+            eprintln!("only in {}: {}", result.from, p.display());
+        }
+        for p in &result.only_in_to
+        {
+            eprintln!("only in {}: {}", result.to, p.display());
+        }
+        eprintln!(
+            "{} → {}: {} changed, {} unchanged",
+            result.from,
+            result.to,
+            result
+                .diffs
+                .len(),
+            result.unchanged
+        );
+    }
+
+    Ok(())
+}
+
+fn backup_export(
+    repo_root: &Path,
+    a: &BackupExportArgs,
+    ctx: &AppContext,
+) -> Result<()>
+{
+    let output = match &a.output
+    {
+        Some(p) => p.clone(),
+        None =>
+        {
+            let id = crate::core::backup_ops::resolve_session_id(repo_root, &a.session)?;
+            PathBuf::from(format!("{id}.tar.zst"))
+        }
+    };
+    let result = export_session(repo_root, &a.session, &output)?;
+
+    if a.json
+    {
+        println!("{}", serde_json::to_string_pretty(&result)?);
+    }
+    else if !ctx.quiet
+    {
+        println!(
+            "Exported session {} ({} file(s)) to {}",
+            result.session_id,
+            result.files,
+            result
+                .archive
+                .display()
+        );
+    }
+    Ok(())
+}
+
+fn backup_import(
+    repo_root: &Path,
+    a: &BackupImportArgs,
+    ctx: &AppContext,
+) -> Result<()>
+{
+    let result = import_session(repo_root, &a.archive)?;
+
+    if a.json
+    {
+        println!("{}", serde_json::to_string_pretty(&result)?);
+    }
+    else if !ctx.quiet
+    {
+        println!(
+            "Imported session {} ({} file(s), checksums verified)",
+            result.session_id, result.files
+        );
+    }
+    Ok(())
+}
+
+fn backup_push(
+    repo_root: &Path,
+    a: &BackupPushArgs,
+    ctx: &AppContext,
+) -> Result<()>
+{
+    use crate::core::backup_remote::{configured_remote, push_session};
+
+    let remote = configured_remote(repo_root)?
+        .context("no [backup.remote] configured in roughup.toml")?;
+    let session_id = crate::core::backup_ops::resolve_session_id(repo_root, &a.session)?;
+    push_session(repo_root, &remote, &session_id)?;
+
+    if !ctx.quiet
+    {
+        println!("Pushed session {}", session_id);
+    }
+    Ok(())
+}
+
+fn backup_to_stash(
+    repo_root: &Path,
+    a: &BackupToStashArgs,
+    ctx: &AppContext,
+) -> Result<()>
+{
+    let result = session_to_stash(
+        repo_root,
+        &a.session,
+        a.message
+            .as_deref(),
+    )?;
+
+    if a.json
+    {
+        println!("{}", serde_json::to_string_pretty(&result)?);
+    }
+    else if !ctx.quiet
     {
         println!(
-            "Restored {} file(s):",
-            result
-                .restored
+            "Stored session {} ({} file(s)) as stash {}: {}",
+            result.session_id,
+            result.files,
+            &result.stash_commit[..result
+                .stash_commit
                 .len()
+                .min(12)],
+            result.message
         );
-        for restored_path in &result.restored
-        {
-            println!("  - {}", restored_path.display());
-        }
     }
+    Ok(())
+}
 
-    // Show backup information if current files were backed up
-    if result.backed_up_current
-        && let Some(ref backup_session) = result.backup_session_id
-        && !ctx.quiet
+fn backup_from_stash(
+    repo_root: &Path,
+    a: &BackupFromStashArgs,
+    ctx: &AppContext,
+) -> Result<()>
+{
+    let result = stash_to_session(
+        repo_root,
+        &a.stash,
+        a.label
+            .clone(),
+    )?;
+
+    if a.json
     {
-        println!("Current files backed up to session: {}", backup_session);
+        println!("{}", serde_json::to_string_pretty(&result)?);
+    }
+    else if !ctx.quiet
+    {
+        println!(
+            "Created session {} ({} file(s)) from {}",
+            result.session_id, result.files, a.stash
+        );
     }
-
     Ok(())
 }
 
-fn backup_cleanup(
+fn backup_gc(
     repo_root: &Path,
-    a: &BackupCleanupArgs,
+    a: &BackupGcArgs,
     ctx: &AppContext,
 ) -> Result<()>
 {
     let is_dry_run = a.dry_run || ctx.dry_run; // Honor global dry-run flag
-    let req = CleanupRequest {
-        older_than: a
-            .older_than
-            .clone(),
-        keep_latest: a.keep_latest,
-        include_incomplete: a.include_incomplete,
-        dry_run: is_dry_run,
-    };
-
-    let result = cleanup_sessions(repo_root, req)?;
+    let result = gc_objects(repo_root, GcRequest { dry_run: is_dry_run, retention: true })?;
 
     if a.json
     {
@@ -2291,35 +4405,25 @@ fn backup_cleanup(
         return Ok(());
     }
 
-    // Human-friendly output
-    if is_dry_run && !ctx.quiet
+    if ctx.quiet
     {
-        println!("DRY RUN - no sessions were deleted");
+        return Ok(());
     }
 
-    if result
-        .sessions_removed
-        .is_empty()
+    let action = if is_dry_run
     {
-        if !ctx.quiet
-        {
-            println!("No sessions matched cleanup criteria");
-        }
-        return Ok(());
+        "Would remove"
     }
-
-    if !ctx.quiet
+    else
+    {
+        "Removed"
+    };
+    if !result
+        .sessions_removed
+        .is_empty()
     {
-        let action = if is_dry_run
-        {
-            "Would remove"
-        }
-        else
-        {
-            "Removed"
-        };
         println!(
-            "{} {} session(s):",
+            "{} {} session(s) outside retention limits:",
             action,
             result
                 .sessions_removed
@@ -2329,11 +4433,19 @@ fn backup_cleanup(
         {
             println!("  - {}", session_id);
         }
-
-        if result.bytes_freed > 0
-        {
-            println!("Space freed: {} bytes", result.bytes_freed);
-        }
+    }
+    println!(
+        "{} {} unreferenced object(s), {} bytes ({} kept)",
+        action,
+        result
+            .objects_removed
+            .len(),
+        result.bytes_freed,
+        result.objects_kept
+    );
+    for err in &result.errors
+    {
+        eprintln!("  error: {}", err);
     }
 
     Ok(())
@@ -2350,7 +4462,7 @@ fn get_clipboard_content() -> Result<String>
 }
 
 /// Atomic write with robust temp file strategy
-fn write_atomic(
+pub(crate) fn write_atomic(
     path: &Path,
     data: &[u8],
 ) -> Result<()>
@@ -2492,6 +4604,19 @@ mod tests
 
     use super::*;
 
+    #[test]
+    fn test_csv_field_quoting()
+    {
+        assert_eq!(csv_field("plain"), "plain");
+        assert_eq!(csv_field("a,b"), "\"a,b\"");
+        assert_eq!(csv_field("say \"hi\""), "\"say \"\"hi\"\"\"");
+        assert_eq!(
+            cell_text(&serde_json::json!(["src/a.rs", "src/b.rs"])),
+            "src/a.rs;src/b.rs"
+        );
+        assert_eq!(cell_text(&serde_json::Value::Null), "");
+    }
+
     #[test]
     fn test_generate_cid()
     {
@@ -2721,6 +4846,164 @@ fn test() {
         assert!(result.contains("modified line2"));
     }
 
+    #[test]
+    fn test_mixed_eol_policies()
+    {
+        use tempfile::tempdir;
+        let dir = tempdir().unwrap();
+        let file_path = dir
+            .path()
+            .join("mixed.txt");
+
+        // LF first, CRLF after: "first newline wins" used to rewrite line3
+        let mixed = "line1\nline2\r\nline3\r\n";
+        let operations = vec![EditOperation::Insert {
+            at_line: 2,
+            new_content: "added".to_string(),
+            after_content: None,
+        }];
+
+        let cases = [
+            (EolPolicy::Preserve, "line1\nline2\r\nadded\r\nline3\r\n"),
+            (EolPolicy::Auto, "line1\r\nline2\r\nadded\r\nline3\r\n"),
+            (EolPolicy::Lf, "line1\nline2\nadded\nline3\n"),
+            (EolPolicy::Crlf, "line1\r\nline2\r\nadded\r\nline3\r\n"),
+        ];
+        for (policy, expected) in cases
+        {
+            fs::write(&file_path, mixed).unwrap();
+            EditEngine::new()
+                .with_eol(policy)
+                .apply_file_operations(&file_path, &operations)
+                .unwrap();
+            assert_eq!(fs::read_to_string(&file_path).unwrap(), expected, "{policy:?}");
+        }
+    }
+
+    #[test]
+    fn test_insert_after_unterminated_last_line()
+    {
+        use tempfile::tempdir;
+        let dir = tempdir().unwrap();
+        let file_path = dir
+            .path()
+            .join("tail.txt");
+        fs::write(&file_path, "a\r\nb").unwrap();
+
+        let operations = vec![EditOperation::Insert {
+            at_line: 2,
+            new_content: "c".to_string(),
+            after_content: None,
+        }];
+        EditEngine::new()
+            .apply_file_operations(&file_path, &operations)
+            .unwrap();
+
+        // The old last line gains the file's ending; EOF stays unterminated
+        assert_eq!(fs::read_to_string(&file_path).unwrap(), "a\r\nb\r\nc");
+    }
+
+    #[test]
+    fn test_binary_and_large_file_guards()
+    {
+        use tempfile::tempdir;
+        let dir = tempdir().unwrap();
+        let binary = dir
+            .path()
+            .join("blob.bin");
+        let large = dir
+            .path()
+            .join("large.txt");
+        fs::write(&binary, b"a\0b\n").unwrap();
+        fs::write(&large, "x\n".repeat(64)).unwrap();
+
+        let delete_first = |path: &Path| FileBlock {
+            path: path.to_path_buf(),
+            operations: vec![EditOperation::Delete {
+                start_line: 1,
+                end_line: 1,
+                old_content: None,
+            }],
+        };
+        let spec = EditSpec { file_blocks: vec![delete_first(&binary), delete_first(&large)] };
+
+        // Refused even under --force: contents stay byte-for-byte intact
+        let result = EditEngine::new()
+            .with_force(true)
+            .with_file_guards(Some(100), false)
+            .apply(&spec)
+            .unwrap();
+        assert!(matches!(result.conflicts[0], EditConflict::BinaryFile(_)));
+        assert!(matches!(
+            result.conflicts[1],
+            EditConflict::FileTooLarge { size: 128, limit: 100, .. }
+        ));
+        assert!(
+            result
+                .applied_files
+                .is_empty()
+        );
+        assert_eq!(fs::read(&binary).unwrap(), b"a\0b\n");
+
+        // --force-binary and no size cap let both through
+        let result = EditEngine::new()
+            .with_file_guards(None, true)
+            .apply(&spec)
+            .unwrap();
+        assert!(
+            result
+                .conflicts
+                .is_empty()
+        );
+        assert_eq!(fs::read(&binary).unwrap(), b"");
+        assert_eq!(fs::read_to_string(&large).unwrap(), "x\n".repeat(63));
+    }
+
+    #[test]
+    fn test_old_mismatch_suggests_closest_span()
+    {
+        use tempfile::tempdir;
+        let dir = tempdir().unwrap();
+        let file_path = dir
+            .path()
+            .join("lib.rs");
+        // Target moved down three lines and gained a small edit
+        fs::write(
+            &file_path,
+            "// a\n// b\n// c\nfn one() {}\nfn two(x: u32) -> u32 {\n    x + 1\n}\n",
+        )
+        .unwrap();
+
+        let spec = EditSpec {
+            file_blocks: vec![FileBlock {
+                path: file_path.clone(),
+                operations: vec![EditOperation::Replace {
+                    start_line: 2,
+                    end_line: 4,
+                    old_content: "fn two(x: u32) -> u32 {\n    x + 2\n}".to_string(),
+                    new_content: "fn two() {}".to_string(),
+                    guard_cid: None,
+                }],
+            }],
+        };
+
+        let result = EditEngine::new()
+            .with_preview(true)
+            .apply(&spec)
+            .unwrap();
+        match &result.conflicts[0]
+        {
+            EditConflict::OldContentMismatch { span, suggestion: Some(s), .. } =>
+            {
+                assert_eq!(*span, (2, 4));
+                assert_eq!(s.span, (5, 7));
+                assert_eq!(s.header, "REPLACE lines 5-7:");
+                assert!(s.similarity >= SUGGESTION_MIN_SIMILARITY && s.similarity < 100);
+            }
+            other => panic!("expected suggestion, got {other:?}"),
+        }
+    }
+
     #[test]
     fn test_deterministic_cid()
     {
@@ -2761,4 +5044,216 @@ new code
                 .contains("Unknown directive: UPDATE")
         );
     }
+
+    #[test]
+    fn test_path_sandbox_rejects_escapes()
+    {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        fs::create_dir_all(root.join("src")).unwrap();
+        let spec_for = |p: &str| EditSpec {
+            file_blocks: vec![FileBlock { path: PathBuf::from(p), operations: vec![] }],
+        };
+
+        // Paths inside the root are fine, including ones not yet created
+        assert!(enforce_path_sandbox(&spec_for("src/lib.rs"), root, root).is_ok());
+        assert!(enforce_path_sandbox(&spec_for("new/dir/a.rs"), root, root).is_ok());
+        assert!(enforce_path_sandbox(&spec_for("../crate/x.rs"), root, &root.join("src")).is_ok());
+
+        // Absolute and `..` escapes are rejected as repo errors
+        for bad in ["/etc/passwd", "../outside.rs", "missing/../../x.rs"]
+        {
+            let err = enforce_path_sandbox(&spec_for(bad), root, root).unwrap_err();
+            assert!(matches!(err, ApplyCliError::Repo(_)), "{bad}: {err}");
+        }
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_path_sandbox_rejects_symlink_escape()
+    {
+        let outside = tempfile::tempdir().unwrap();
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        std::os::unix::fs::symlink(outside.path(), root.join("link")).unwrap();
+
+        let spec = EditSpec {
+            file_blocks: vec![FileBlock { path: PathBuf::from("link/a.rs"), operations: vec![] }],
+        };
+        let err = enforce_path_sandbox(&spec, root, root).unwrap_err();
+        assert!(
+            err.to_string()
+                .contains("symlink")
+        );
+    }
+
+    fn replace_spec(
+        path: &Path,
+        start_line: usize,
+        old: &str,
+    ) -> EditSpec
+    {
+        EditSpec {
+            file_blocks: vec![FileBlock {
+                path: path.to_path_buf(),
+                operations: vec![EditOperation::Replace {
+                    start_line,
+                    end_line: start_line,
+                    old_content: old.to_string(),
+                    new_content: "NEW".to_string(),
+                    guard_cid: None,
+                }],
+            }],
+        }
+    }
+
+    #[test]
+    fn test_max_drift_bounds_relocation()
+    {
+        let mut f = NamedTempFile::new().unwrap();
+        write!(f, "a\nb\nc\ntarget\nd\n").unwrap();
+
+        // Declared at line 2, actual at line 4: drift of 2
+        let spec = replace_spec(f.path(), 2, "target");
+
+        let strict = EditEngine::new()
+            .with_max_drift(Some(1))
+            .apply(&spec)
+            .unwrap();
+        assert_eq!(
+            strict
+                .conflicts
+                .len(),
+            1
+        );
+        assert_eq!(fs::read_to_string(f.path()).unwrap(), "a\nb\nc\ntarget\nd\n");
+
+        let tolerant = EditEngine::new()
+            .with_max_drift(Some(2))
+            .apply(&spec)
+            .unwrap();
+        assert!(
+            tolerant
+                .conflicts
+                .is_empty()
+        );
+        assert_eq!(fs::read_to_string(f.path()).unwrap(), "a\nb\nc\nNEW\nd\n");
+    }
+
+    #[test]
+    fn test_declared_span_preferred_over_earlier_duplicate()
+    {
+        let mut f = NamedTempFile::new().unwrap();
+        write!(f, "dup\nx\ndup\n").unwrap();
+
+        EditEngine::new()
+            .apply(&replace_spec(f.path(), 3, "dup"))
+            .unwrap();
+        assert_eq!(fs::read_to_string(f.path()).unwrap(), "dup\nx\nNEW\n");
+    }
+
+    #[test]
+    fn test_locate_within_drift_prefers_nearest()
+    {
+        let lines = ["x", "y", "x", "z", "x"];
+        assert_eq!(locate_within_drift(&lines, "x", 4, 3), Some((3, 3)));
+        assert_eq!(locate_within_drift(&lines, "y", 5, 2), None);
+        assert_eq!(locate_within_drift(&lines, "z\nx", 1, 3), Some((4, 5)));
+    }
+
+    #[test]
+    fn test_parse_insert_after_and_delete_old()
+    {
+        let input = "FILE: a.rs\nINSERT at 2:\nAFTER:\n```\nfn a() {}\n```\nNEW:\n```\n// \
+                     note\n```\nDELETE lines 4-4\nOLD:\n```\nfn gone() {}\n```\n";
+        let spec = EditEngine::new()
+            .parse_edit_spec(input)
+            .unwrap();
+        let ops = &spec.file_blocks[0].operations;
+
+        assert_eq!(
+            ops[0],
+            EditOperation::Insert {
+                at_line: 2,
+                new_content: "// note".to_string(),
+                after_content: Some("fn a() {}".to_string()),
+            }
+        );
+        assert_eq!(
+            ops[1],
+            EditOperation::Delete {
+                start_line: 4,
+                end_line: 4,
+                old_content: Some("fn gone() {}".to_string()),
+            }
+        );
+    }
+
+    #[test]
+    fn test_insert_and_delete_follow_drifted_anchors()
+    {
+        let mut f = NamedTempFile::new().unwrap();
+        // Two lines were added upstream after the spec was written
+        write!(f, "new1\nnew2\nhead\nanchor\ngone\ntail\n").unwrap();
+
+        let spec = EditSpec {
+            file_blocks: vec![FileBlock {
+                path: f
+                    .path()
+                    .to_path_buf(),
+                operations: vec![
+                    EditOperation::Insert {
+                        at_line: 2,
+                        new_content: "inserted".to_string(),
+                        after_content: Some("anchor".to_string()),
+                    },
+                    EditOperation::Delete {
+                        start_line: 3,
+                        end_line: 3,
+                        old_content: Some("gone".to_string()),
+                    },
+                ],
+            }],
+        };
+
+        // Strict validation still reports the drift
+        let strict = EditEngine::new()
+            .with_preview(true)
+            .apply(&spec)
+            .unwrap();
+        assert_eq!(
+            strict
+                .conflicts
+                .len(),
+            2
+        );
+
+        let result = EditEngine::new()
+            .with_max_drift(Some(2))
+            .apply(&spec)
+            .unwrap();
+        assert!(
+            result
+                .conflicts
+                .is_empty()
+        );
+        assert_eq!(
+            fs::read_to_string(f.path()).unwrap(),
+            "new1\nnew2\nhead\nanchor\ninserted\ntail\n"
+        );
+        assert_eq!(
+            (
+                result
+                    .stats
+                    .hunks_relocated,
+                result
+                    .stats
+                    .lines_added,
+                result
+                    .stats
+                    .lines_removed
+            ),
+            (2, 1, 1)
+        );
+    }
 }
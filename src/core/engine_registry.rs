@@ -0,0 +1,531 @@
+//! Named registry of apply engines selected with `--engine=<name>`
+//!
+//! The built-in `internal`, `git`, and `auto` engines are registered on
+//! first use. Crates that embed roughup (or wrapper binaries) can add
+//! their own engines, e.g. a `patch(1)` or jj backend, by calling
+//! [`register_engine`] before dispatching the CLI. Each registration
+//! carries capability flags so callers can reject unsupported requests
+//! up front instead of failing mid-apply.
+
+use std::{
+    fmt,
+    path::PathBuf,
+    sync::{Arc, OnceLock, RwLock},
+};
+
+use anyhow::{Result, bail};
+use serde::Serialize;
+
+use crate::{
+    cli::{GitBackendMode, GitMode, WhitespaceMode},
+    core::{
+        apply_engine::{ApplyEngine, GitEngineWrapper, HybridEngine, InternalEngine, JjEngine},
+        edit::EolPolicy,
+        git::{self, GitOptions},
+        jj,
+        patch::ContextRule,
+    },
+};
+
+/// What an engine can do beyond plain line edits
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+pub struct EngineCapabilities
+{
+    /// Can fall back to a 3-way merge when context has drifted
+    pub three_way_merge: bool,
+    /// Can apply edits to binary files
+    pub binary_files: bool,
+    /// Needs a repository; refused outside one
+    pub requires_repo: bool,
+}
+
+/// Runtime settings handed to an engine factory
+#[derive(Debug, Clone)]
+pub struct EngineConfig
+{
+    pub repo_root: PathBuf,
+    pub git_mode: GitMode,
+    pub git_backend: GitBackendMode,
+    pub whitespace: WhitespaceMode,
+    pub backup_enabled: bool,
+    pub force_mode: bool,
+    pub context_lines: usize,
+    pub max_drift: Option<usize>,
+    pub eol: EolPolicy,
+    /// Size cap for target files; `None` disables it
+    pub max_file_bytes: Option<u64>,
+    /// Edit files that contain NUL bytes as text
+    pub allow_binary: bool,
+    /// Git engine applies to the index only (`--stage`)
+    pub stage: bool,
+    /// Per-file context-line overrides for generated git patches
+    pub context_rules: Vec<ContextRule>,
+}
+
+impl EngineConfig
+{
+    /// Map the CLI-level settings onto git apply options
+    pub fn git_options(&self) -> GitOptions
+    {
+        GitOptions {
+            repo_root: self
+                .repo_root
+                .clone(),
+            mode: match self.git_mode
+            {
+                GitMode::ThreeWay => git::GitMode::ThreeWay,
+                GitMode::Index => git::GitMode::Index,
+                GitMode::Worktree => git::GitMode::Worktree,
+            },
+            whitespace: match self.whitespace
+            {
+                WhitespaceMode::Nowarn => git::Whitespace::Nowarn,
+                WhitespaceMode::Warn => git::Whitespace::Warn,
+                WhitespaceMode::Fix => git::Whitespace::Fix,
+                WhitespaceMode::Auto => git::Whitespace::Auto,
+            },
+            context_lines: self.context_lines as u8,
+            allow_outside_repo: false,
+            backend: match self.git_backend
+            {
+                GitBackendMode::Cli => git::GitBackend::Cli,
+                GitBackendMode::Lib => git::GitBackend::Lib,
+            },
+            cached: self.stage,
+        }
+    }
+}
+
+/// Builds an engine instance from runtime settings
+pub type EngineFactory = Arc<dyn Fn(&EngineConfig) -> Result<Box<dyn ApplyEngine>> + Send + Sync>;
+
+/// A named engine and how to build it
+#[derive(Clone)]
+pub struct EngineRegistration
+{
+    pub name: String,
+    pub description: String,
+    pub capabilities: EngineCapabilities,
+    factory: EngineFactory,
+}
+
+impl EngineRegistration
+{
+    pub fn new(
+        name: impl Into<String>,
+        description: impl Into<String>,
+        capabilities: EngineCapabilities,
+        factory: impl Fn(&EngineConfig) -> Result<Box<dyn ApplyEngine>> + Send + Sync + 'static,
+    ) -> Self
+    {
+        Self {
+            name: name.into(),
+            description: description.into(),
+            capabilities,
+            factory: Arc::new(factory),
+        }
+    }
+
+    /// Construct an engine instance
+    pub fn build(
+        &self,
+        config: &EngineConfig,
+    ) -> Result<Box<dyn ApplyEngine>>
+    {
+        (self.factory)(config)
+    }
+}
+
+impl fmt::Debug for EngineRegistration
+{
+    fn fmt(
+        &self,
+        f: &mut fmt::Formatter<'_>,
+    ) -> fmt::Result
+    {
+        f.debug_struct("EngineRegistration")
+            .field("name", &self.name)
+            .field("description", &self.description)
+            .field("capabilities", &self.capabilities)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Ordered set of engines keyed by unique name
+#[derive(Debug, Clone, Default)]
+pub struct EngineRegistry
+{
+    engines: Vec<EngineRegistration>,
+}
+
+impl EngineRegistry
+{
+    /// Empty registry (no built-ins)
+    pub fn new() -> Self
+    {
+        Self::default()
+    }
+
+    /// Registry preloaded with `internal`, `git`, and `auto`
+    pub fn with_builtins() -> Self
+    {
+        let mut registry = Self::new();
+        for registration in builtin_engines()
+        {
+            registry
+                .register(registration)
+                .expect("built-in engine names are unique");
+        }
+        registry
+    }
+
+    /// Add an engine; names are unique and case-sensitive
+    pub fn register(
+        &mut self,
+        registration: EngineRegistration,
+    ) -> Result<()>
+    {
+        if registration
+            .name
+            .trim()
+            .is_empty()
+        {
+            bail!("engine name must not be empty");
+        }
+        if self
+            .get(&registration.name)
+            .is_some()
+        {
+            bail!("engine '{}' is already registered", registration.name);
+        }
+        self.engines
+            .push(registration);
+        Ok(())
+    }
+
+    pub fn get(
+        &self,
+        name: &str,
+    ) -> Option<&EngineRegistration>
+    {
+        self.engines
+            .iter()
+            .find(|e| e.name == name)
+    }
+
+    /// Registered names in registration order
+    pub fn names(&self) -> Vec<&str>
+    {
+        self.engines
+            .iter()
+            .map(|e| {
+                e.name
+                    .as_str()
+            })
+            .collect()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &EngineRegistration>
+    {
+        self.engines
+            .iter()
+    }
+
+    /// Build the engine registered under `name`
+    pub fn create(
+        &self,
+        name: &str,
+        config: &EngineConfig,
+    ) -> Result<Box<dyn ApplyEngine>>
+    {
+        match self.get(name)
+        {
+            Some(registration) => registration.build(config),
+            None =>
+            {
+                bail!(
+                    "unknown engine '{}' (available: {})",
+                    name,
+                    self.names()
+                        .join(", ")
+                )
+            }
+        }
+    }
+}
+
+fn builtin_engines() -> Vec<EngineRegistration>
+{
+    vec![
+        EngineRegistration::new(
+            "internal",
+            "Fast internal engine with clear error messages",
+            EngineCapabilities::default(),
+            |cfg| Ok(Box::new(internal_engine(cfg))),
+        ),
+        EngineRegistration::new(
+            "git",
+            "Git apply engine with 3-way merge capability",
+            EngineCapabilities {
+                three_way_merge: true,
+                binary_files: false,
+                requires_repo: true,
+            },
+            |cfg| {
+                Ok(Box::new(
+                    GitEngineWrapper::new(cfg.git_options())?.with_context_rules(
+                        cfg.context_rules
+                            .clone(),
+                    ),
+                ))
+            },
+        ),
+        EngineRegistration::new(
+            "auto",
+            "Try internal first, fallback to git on conflicts",
+            EngineCapabilities {
+                three_way_merge: true,
+                binary_files: false,
+                requires_repo: false,
+            },
+            |cfg| {
+                // Detect repo once here; do NOT fail auto if absent
+                let repo_present = git::detect_repo(&cfg.repo_root).is_ok();
+                // A jj repo without a git worktree still gets a 3-way fallback
+                if !repo_present && jj::detect_repo(&cfg.repo_root).is_some()
+                {
+                    return Ok(Box::new(JjEngine::new(
+                        internal_engine(cfg),
+                        &cfg.repo_root,
+                    )?));
+                }
+                let mut git_options = cfg.git_options();
+                git_options.allow_outside_repo = true; // Allow auto to work outside repos
+                Ok(Box::new(
+                    HybridEngine::new(
+                        cfg.backup_enabled,
+                        cfg.force_mode,
+                        git_options,
+                        repo_present,
+                    )?
+                    .with_max_drift(cfg.max_drift)
+                    .with_eol(cfg.eol)
+                    .with_file_guards(cfg.max_file_bytes, cfg.allow_binary)
+                    .with_context_rules(
+                        cfg.context_rules
+                            .clone(),
+                    ),
+                ))
+            },
+        ),
+        EngineRegistration::new(
+            "jj",
+            "Jujutsu engine: snapshots around the apply, 3-way merge against @-",
+            EngineCapabilities {
+                three_way_merge: true,
+                binary_files: false,
+                requires_repo: true,
+            },
+            |cfg| {
+                Ok(Box::new(JjEngine::new(
+                    internal_engine(cfg),
+                    &cfg.repo_root,
+                )?))
+            },
+        ),
+    ]
+}
+
+/// Internal engine configured from the CLI-level settings
+fn internal_engine(cfg: &EngineConfig) -> InternalEngine
+{
+    InternalEngine::new(cfg.backup_enabled, cfg.force_mode, cfg.context_lines)
+        .with_max_drift(cfg.max_drift)
+        .with_eol(cfg.eol)
+        .with_file_guards(cfg.max_file_bytes, cfg.allow_binary)
+}
+
+fn global() -> &'static RwLock<EngineRegistry>
+{
+    static REGISTRY: OnceLock<RwLock<EngineRegistry>> = OnceLock::new();
+    REGISTRY.get_or_init(|| RwLock::new(EngineRegistry::with_builtins()))
+}
+
+/// Register an engine in the process-wide registry used by the CLI
+pub fn register_engine(registration: EngineRegistration) -> Result<()>
+{
+    global()
+        .write()
+        .unwrap_or_else(|e| e.into_inner())
+        .register(registration)
+}
+
+/// Capabilities of a registered engine, if any
+pub fn engine_capabilities(name: &str) -> Option<EngineCapabilities>
+{
+    global()
+        .read()
+        .unwrap_or_else(|e| e.into_inner())
+        .get(name)
+        .map(|e| e.capabilities)
+}
+
+/// Names of all engines in the process-wide registry
+pub fn engine_names() -> Vec<String>
+{
+    global()
+        .read()
+        .unwrap_or_else(|e| e.into_inner())
+        .names()
+        .into_iter()
+        .map(str::to_string)
+        .collect()
+}
+
+/// Copy of the process-wide registry, e.g. for listing
+pub fn registry_snapshot() -> EngineRegistry
+{
+    global()
+        .read()
+        .unwrap_or_else(|e| e.into_inner())
+        .clone()
+}
+
+/// Build an engine from the process-wide registry
+pub fn create_registered(
+    name: &str,
+    config: &EngineConfig,
+) -> Result<Box<dyn ApplyEngine>>
+{
+    global()
+        .read()
+        .unwrap_or_else(|e| e.into_inner())
+        .create(name, config)
+}
+
+#[cfg(test)]
+mod tests
+{
+    use super::*;
+
+    fn config() -> EngineConfig
+    {
+        EngineConfig {
+            repo_root: std::env::temp_dir(),
+            git_mode: GitMode::ThreeWay,
+            git_backend: GitBackendMode::Cli,
+            whitespace: WhitespaceMode::Nowarn,
+            backup_enabled: false,
+            force_mode: false,
+            context_lines: 3,
+            max_drift: None,
+            eol: EolPolicy::Preserve,
+            max_file_bytes: None,
+            allow_binary: false,
+            stage: false,
+            context_rules: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_builtins_registered_in_order()
+    {
+        let registry = EngineRegistry::with_builtins();
+        assert_eq!(registry.names(), vec!["internal", "git", "auto", "jj"]);
+        assert!(
+            registry
+                .get("git")
+                .unwrap()
+                .capabilities
+                .requires_repo
+        );
+        assert!(
+            registry
+                .create("internal", &config())
+                .is_ok()
+        );
+    }
+
+    #[test]
+    fn test_register_custom_engine_and_reject_duplicates()
+    {
+        let mut registry = EngineRegistry::with_builtins();
+        let caps = EngineCapabilities {
+            three_way_merge: false,
+            binary_files: true,
+            requires_repo: false,
+        };
+        registry
+            .register(EngineRegistration::new(
+                "patch",
+                "patch(1) backend",
+                caps,
+                |cfg| {
+                    Ok(Box::new(InternalEngine::new(
+                        cfg.backup_enabled,
+                        cfg.force_mode,
+                        cfg.context_lines,
+                    )))
+                },
+            ))
+            .unwrap();
+
+        assert_eq!(
+            registry
+                .get("patch")
+                .map(|e| e.capabilities),
+            Some(caps)
+        );
+        assert!(
+            registry
+                .create("patch", &config())
+                .is_ok()
+        );
+
+        let dup = EngineRegistration::new("git", "again", caps, |_| bail!("unused"));
+        assert!(
+            registry
+                .register(dup)
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn test_unknown_engine_lists_available()
+    {
+        let err = EngineRegistry::with_builtins()
+            .create("hg", &config())
+            .err()
+            .unwrap();
+        let msg = err.to_string();
+        assert!(msg.contains("unknown engine 'hg'"));
+        assert!(msg.contains("internal, git, auto, jj"));
+    }
+
+    #[test]
+    fn test_jj_engine_requires_jj_repo()
+    {
+        let tmp = tempfile::tempdir().unwrap();
+        let mut cfg = config();
+        cfg.repo_root = tmp
+            .path()
+            .to_path_buf();
+        let registry = EngineRegistry::with_builtins();
+        assert!(
+            registry
+                .create("jj", &cfg)
+                .is_err()
+        );
+
+        std::fs::create_dir(
+            tmp.path()
+                .join(".jj"),
+        )
+        .unwrap();
+        assert!(
+            registry
+                .create("jj", &cfg)
+                .is_ok()
+        );
+    }
+}
@@ -6,6 +6,7 @@
 //! - whitespace compaction (--dedent, --squeeze-blank)
 //! - token budgeting (--budget, --model) using core::budgeter
 //! - hard/priority ranges via "!" prefix in the targets spec
+//! - Markdown sections by heading anchor ("README.md:#installation")
 //! - honors --annotate, --fence, --clipboard
 
 pub mod target;
@@ -21,8 +22,9 @@ pub use target::ExtractionTarget;
 
 use crate::{
     cli::{AppContext, ExtractArgs},
-    core::budgeter::{Budgeter, Item as BudgetItem, Priority},
+    core::budgeter::{Budgeter, Item as BudgetItem, Priority, token_cache_dir},
     infra::io::read_file_smart,
+    parsers::markdown_parser::find_section,
 };
 
 #[derive(Debug, Clone)]
@@ -33,11 +35,20 @@ struct Span
     hard: bool,   // true when user prefixed with '!'
 }
 
+/// Markdown heading anchor, resolved to a span once the file is read
+#[derive(Debug, Clone)]
+struct SectionRef
+{
+    anchor: String,
+    hard: bool,
+}
+
 #[derive(Debug, Clone)]
 struct FileSpec
 {
     path: PathBuf,
     spans: Vec<Span>,
+    sections: Vec<SectionRef>,
 }
 
 /// Runs the extraction process based on the provided arguments and application context.
@@ -76,10 +87,13 @@ pub fn run(
     {
         let parsed =
             parse_target_spec(spec).with_context(|| format!("invalid target spec: '{spec}'"))?;
-        by_file
+        let sections = resolve_sections(&parsed.path, &parsed.sections)
+            .with_context(|| format!("invalid target spec: '{spec}'"))?;
+        let spans = by_file
             .entry(parsed.path)
-            .or_default()
-            .extend(parsed.spans);
+            .or_default();
+        spans.extend(parsed.spans);
+        spans.extend(sections);
     }
 
     // Expand context & merge per-file
@@ -141,9 +155,35 @@ pub fn run(
     // Token budgeting
     let final_text = if let Some(budget) = args.budget
     {
-        let b = Budgeter::new(&args.model)
+        let mut b = Budgeter::for_model(&args.model, args.tokenizer_file.as_deref())
             .with_context(|| format!("loading tokenizer for '{}'", args.model))?;
-        let fit = b.fit(items, budget)?;
+        if args.approx_tokens
+        {
+            b = b.approximate();
+        }
+        if !args.no_token_cache
+        {
+            b = b.with_disk_cache(&token_cache_dir());
+        }
+        let fit = match args.explain_budget
+        {
+            Some(format) =>
+            {
+                let (fit, report) = b.fit_with_report(items, budget, None)?;
+                report.print(format)?;
+                fit
+            }
+            None => b.fit(items, budget)?,
+        };
+        // The cache only saves time; never fail the extraction over it
+        if let Err(e) = b.save_cache()
+        {
+            eprintln!("⚠ Token cache not saved: {e:#}");
+        }
+        if let Some(agent) = &ctx.agent
+        {
+            agent.tokens(fit.total_tokens, Some(budget), Some(&args.model));
+        }
         join_fitted(&fit.items)
     }
     else
@@ -191,6 +231,10 @@ pub fn run(
                 .display()
         )
     })?;
+    if let Some(agent) = &ctx.agent
+    {
+        agent.artifact("output", &args.output);
+    }
 
     // Optional clipboard
     if args.clipboard
@@ -237,6 +281,7 @@ fn parse_target_spec(s: &str) -> Result<FileSpec>
     }
 
     let mut spans = Vec::new();
+    let mut sections = Vec::new();
     for raw in ranges_part.split(',')
     {
         let t = raw.trim();
@@ -253,6 +298,13 @@ fn parse_target_spec(s: &str) -> Result<FileSpec>
             (false, t)
         };
 
+        // "#anchor" names a Markdown section
+        if t.starts_with('#')
+        {
+            sections.push(SectionRef { anchor: t.to_string(), hard });
+            continue;
+        }
+
         // forms: "A-B", "A", "A+N"
         let (start, end) = if let Some(p) = t.find('-')
         {
@@ -287,12 +339,33 @@ fn parse_target_spec(s: &str) -> Result<FileSpec>
         spans.push(Span { start: start.min(end), end: start.max(end), hard });
     }
 
-    if spans.is_empty()
+    if spans.is_empty() && sections.is_empty()
     {
         return Err(anyhow!("no spans found"));
     }
 
-    Ok(FileSpec { path, spans })
+    Ok(FileSpec { path, spans, sections })
+}
+
+/// Look up each section's heading in the Markdown file and return its lines
+fn resolve_sections(
+    path: &Path,
+    sections: &[SectionRef],
+) -> Result<Vec<Span>>
+{
+    if sections.is_empty()
+    {
+        return Ok(Vec::new());
+    }
+    let content = read_file_smart(path).with_context(|| format!("reading {}", path.display()))?;
+    sections
+        .iter()
+        .map(|s| {
+            let (start, end) = find_section(content.as_ref(), &s.anchor)?
+                .ok_or_else(|| anyhow!("no section '{}' in {}", s.anchor, path.display()))?;
+            Ok(Span { start, end, hard: s.hard })
+        })
+        .collect()
 }
 
 fn expand_context(
@@ -515,6 +588,7 @@ fn annot_line(
         {
             format!("# {text}")
         }
+        "md" | "markdown" => format!("<!-- {text} -->"),
         _ => format!("// {text}"),
     }
 }
@@ -559,6 +633,20 @@ mod tests
         assert_eq!((f.spans[2].start, f.spans[2].end), (9, 9));
     }
 
+    #[test]
+    fn parse_markdown_sections()
+    {
+        let f = parse_target_spec("README.md:#installation,!#usage,3-4").unwrap();
+        assert_eq!(
+            f.spans
+                .len(),
+            1
+        );
+        assert_eq!(f.sections[0].anchor, "#installation");
+        assert!(!f.sections[0].hard);
+        assert!(f.sections[1].hard);
+    }
+
     #[test]
     fn merging_and_context()
     {
@@ -28,6 +28,8 @@ pub struct CombinedConflictError
 {
     pub internal_conflicts: Vec<String>,
     pub git_failure_reason: String,
+    /// Engines `--engine auto` tried, in order, each with why it gave up
+    pub attempts: Vec<crate::core::apply_engine::EngineAttempt>,
 }
 
 impl CombinedConflictError
@@ -35,9 +37,10 @@ impl CombinedConflictError
     pub fn new(
         internal_conflicts: Vec<String>,
         git_reason: String,
+        attempts: Vec<crate::core::apply_engine::EngineAttempt>,
     ) -> Self
     {
-        Self { internal_conflicts, git_failure_reason: git_reason }
+        Self { internal_conflicts, git_failure_reason: git_reason, attempts }
     }
 }
 
@@ -54,7 +57,15 @@ impl std::fmt::Display for CombinedConflictError
             self.internal_conflicts
                 .len(),
             self.git_failure_reason
-        )
+        )?;
+        for attempt in &self.attempts
+        {
+            if let Some(why) = &attempt.failure
+            {
+                write!(f, "; {:?} gave up: {why}", attempt.engine)?;
+            }
+        }
+        Ok(())
     }
 }
 
@@ -188,6 +199,22 @@ pub enum Whitespace
     Warn,
     /// Fix whitespace issues automatically
     Fix,
+    /// Re-indent NEW content to each target file's tabs/spaces style before
+    /// generating patches, then apply like `Nowarn`
+    Auto,
+}
+
+/// Which implementation runs `git apply`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum GitBackend
+{
+    /// Shell out to the `git` binary on PATH
+    #[default]
+    Cli,
+    /// Bundled libgit2; works without a `git` binary. Its 3-way fallback
+    /// merges against the index blob, since generated patches record no
+    /// blob IDs for `git apply --3way` to use
+    Lib,
 }
 
 /// Git apply configuration
@@ -199,6 +226,9 @@ pub struct GitOptions
     pub whitespace: Whitespace,
     pub context_lines: u8,
     pub allow_outside_repo: bool,
+    pub backend: GitBackend,
+    /// Apply to the index only (`git apply --cached`); the worktree is untouched
+    pub cached: bool,
 }
 
 impl Default for GitOptions
@@ -211,6 +241,8 @@ impl Default for GitOptions
             whitespace: Whitespace::Nowarn,
             context_lines: 3,
             allow_outside_repo: false,
+            backend: GitBackend::Cli,
+            cached: false,
         }
     }
 }
@@ -255,9 +287,198 @@ pub enum GitConflict
         path: PathBuf,
         hint: &'static str,
     },
+    FileTooLarge
+    {
+        path: PathBuf,
+        hint: &'static str,
+    },
     Other(String),
 }
 
+impl GitConflict
+{
+    /// Re-root the conflict's path under `prefix`
+    fn with_prefix(
+        self,
+        prefix: &Path,
+    ) -> Self
+    {
+        if prefix
+            .as_os_str()
+            .is_empty()
+        {
+            return self;
+        }
+        match self
+        {
+            GitConflict::PreimageMismatch { path, hunk, hint } =>
+            {
+                GitConflict::PreimageMismatch { path: prefix.join(path), hunk, hint }
+            }
+            GitConflict::PathOutsideRepo { path, hint } =>
+            {
+                GitConflict::PathOutsideRepo { path: prefix.join(path), hint }
+            }
+            GitConflict::WhitespaceError { path, hint } =>
+            {
+                GitConflict::WhitespaceError { path: prefix.join(path), hint }
+            }
+            GitConflict::IndexRequired { path, hint } =>
+            {
+                GitConflict::IndexRequired { path: prefix.join(path), hint }
+            }
+            GitConflict::BinaryOrMode { path, hint } =>
+            {
+                GitConflict::BinaryOrMode { path: prefix.join(path), hint }
+            }
+            GitConflict::FileTooLarge { path, hint } =>
+            {
+                GitConflict::FileTooLarge { path: prefix.join(path), hint }
+            }
+            other => other,
+        }
+    }
+}
+
+/// The part of a patch set owned by one repository
+#[derive(Debug)]
+pub struct RepoPatch
+{
+    /// Directory git apply runs in
+    pub dir: PathBuf,
+    /// Maps the group's repo-relative paths back to edit-spec paths; empty
+    /// for the repository `repo_root` belongs to
+    pub prefix: PathBuf,
+    pub patch_set: PatchSet,
+}
+
+/// Group `patch_set` by the repository owning each file. Files inside a
+/// submodule or nested repo under `repo_root`'s repository get their own
+/// group with paths relative to that repo, since the superproject's
+/// `git apply` rejects them. Files in a submodule that is not checked out
+/// are an error.
+pub fn split_by_repo(
+    repo_root: &Path,
+    patch_set: &PatchSet,
+) -> Result<Vec<RepoPatch>>
+{
+    let unchanged = || {
+        vec![RepoPatch {
+            dir: repo_root.to_path_buf(),
+            prefix: PathBuf::new(),
+            patch_set: patch_set.clone(),
+        }]
+    };
+    let Ok(top) = resolve_top_level(repo_root)
+    else
+    {
+        return Ok(unchanged());
+    };
+    let top = top
+        .canonicalize()
+        .unwrap_or(top);
+    let base = repo_root
+        .canonicalize()
+        .unwrap_or_else(|_| repo_root.to_path_buf());
+    let gitlinks = submodule_paths(&top)?;
+
+    let mut groups: Vec<RepoPatch> = Vec::new();
+    for file_patch in &patch_set.file_patches
+    {
+        let abs = crate::core::edit::lexical_normalize(&base.join(&file_patch.path));
+        let mut owner = None;
+        for dir in abs
+            .ancestors()
+            .skip(1)
+            .take_while(|d| d.starts_with(&top) && *d != top)
+        {
+            if dir
+                .join(".git")
+                .exists()
+            {
+                owner = Some(dir.to_path_buf());
+                break;
+            }
+            if let Ok(rel) = dir.strip_prefix(&top)
+                && gitlinks.contains(&rel.to_path_buf())
+            {
+                bail!(
+                    "{} is inside submodule {}, which is not checked out; run `git submodule \
+                     update --init {}` first",
+                    file_patch.path,
+                    rel.display(),
+                    rel.display()
+                );
+            }
+        }
+
+        let (dir, prefix, path) = match owner
+        {
+            None => (repo_root.to_path_buf(), PathBuf::new(), file_patch.path.clone()),
+            Some(sub) =>
+            {
+                let prefix = sub
+                    .strip_prefix(&base)
+                    .map(Path::to_path_buf)
+                    .unwrap_or_else(|_| sub.clone());
+                let path = abs
+                    .strip_prefix(&sub)
+                    .unwrap_or(&abs)
+                    .to_string_lossy()
+                    .replace('\\', "/");
+                (sub, prefix, path)
+            }
+        };
+        let mut rebased = file_patch.clone();
+        rebased.path = path;
+        match groups
+            .iter_mut()
+            .find(|g| g.dir == dir)
+        {
+            Some(group) => group
+                .patch_set
+                .file_patches
+                .push(rebased),
+            None => groups.push(RepoPatch {
+                dir,
+                prefix,
+                patch_set: PatchSet { file_patches: vec![rebased] },
+            }),
+        }
+    }
+
+    if groups.is_empty()
+    {
+        return Ok(unchanged());
+    }
+    Ok(groups)
+}
+
+/// Submodule paths (gitlinks) recorded in `top`'s index, relative to `top`
+fn submodule_paths(top: &Path) -> Result<Vec<PathBuf>>
+{
+    if !top
+        .join(".gitmodules")
+        .exists()
+    {
+        return Ok(Vec::new());
+    }
+    let out = Command::new("git")
+        .args(["ls-files", "--stage", "-z"])
+        .current_dir(top)
+        .output()
+        .context("Failed to list submodules")?;
+    Ok(String::from_utf8_lossy(&out.stdout)
+        .split('\0')
+        .filter_map(|entry| {
+            // <mode> <object> <stage>\t<path>
+            let (info, path) = entry.split_once('\t')?;
+            info.starts_with("160000 ")
+                .then(|| PathBuf::from(path))
+        })
+        .collect())
+}
+
 /// Git apply engine implementation
 pub struct GitEngine
 {
@@ -270,8 +491,12 @@ impl GitEngine
     /// Create new Git engine with options
     pub fn new(options: GitOptions) -> Result<Self>
     {
-        let git_executable = detect_git_executable()?;
-        Ok(Self { options, git_executable: Some(git_executable) })
+        let git_executable = match options.backend
+        {
+            GitBackend::Cli => Some(detect_git_executable()?),
+            GitBackend::Lib => None,
+        };
+        Ok(Self { options, git_executable })
     }
 
     /// Get engine options
@@ -286,8 +511,7 @@ impl GitEngine
         patch_set: &PatchSet,
     ) -> Result<GitOutcome>
     {
-        let patch_content = crate::core::patch::render_unified_diff(patch_set);
-        self.run_git_apply(&patch_content, true)
+        self.apply_split(patch_set, true)
     }
 
     /// Apply patch set to repository
@@ -313,35 +537,131 @@ impl GitEngine
             }
         }
 
-        let patch_content = crate::core::patch::render_unified_diff(patch_set);
-        self.run_git_apply(&patch_content, false)
+        self.apply_split(patch_set, false)
     }
 
-    /// Run git apply with specified options
+    /// Run git apply once per owning repository (see [`split_by_repo`]) and
+    /// merge the outcomes, with paths mapped back to edit-spec paths
+    fn apply_split(
+        &self,
+        patch_set: &PatchSet,
+        check_only: bool,
+    ) -> Result<GitOutcome>
+    {
+        let mut merged = GitOutcome {
+            applied_files: Vec::new(),
+            conflicts: Vec::new(),
+            left_markers: Vec::new(),
+            stderr_raw: String::new(),
+        };
+        for group in split_by_repo(
+            &self
+                .options
+                .repo_root,
+            patch_set,
+        )?
+        {
+            let patch_content = crate::core::patch::render_unified_diff(&group.patch_set);
+            // Minimal-context patches need git's explicit opt-in
+            let zero_context = group
+                .patch_set
+                .file_patches
+                .iter()
+                .any(|p| {
+                    p.metadata
+                        .context_lines
+                        == 0
+                });
+            let outcome =
+                self.run_git_apply(&group.dir, &patch_content, check_only, zero_context)?;
+            merged
+                .applied_files
+                .extend(
+                    outcome
+                        .applied_files
+                        .into_iter()
+                        .map(|f| {
+                            group
+                                .prefix
+                                .join(f)
+                        }),
+                );
+            merged
+                .conflicts
+                .extend(
+                    outcome
+                        .conflicts
+                        .into_iter()
+                        .map(|c| c.with_prefix(&group.prefix)),
+                );
+            merged
+                .left_markers
+                .extend(
+                    outcome
+                        .left_markers
+                        .into_iter()
+                        .map(|f| {
+                            group
+                                .prefix
+                                .join(f)
+                        }),
+                );
+            merged
+                .stderr_raw
+                .push_str(&outcome.stderr_raw);
+        }
+
+        // Check for conflict markers in applied files
+        if !check_only
+            && !self
+                .options
+                .cached
+            && self
+                .options
+                .backend
+                == GitBackend::Cli
+            && matches!(
+                self.options
+                    .mode,
+                GitMode::ThreeWay
+            )
+        {
+            merged.left_markers = find_conflict_markers(&merged.applied_files)?;
+        }
+        Ok(merged)
+    }
+
+    /// Run git apply in `dir` with specified options
     fn run_git_apply(
         &self,
+        dir: &Path,
         patch_content: &str,
         check_only: bool,
+        zero_context: bool,
     ) -> Result<GitOutcome>
     {
+        if self
+            .options
+            .backend
+            == GitBackend::Lib
+        {
+            return self.run_libgit2_apply(dir, patch_content, check_only);
+        }
+
         let git_path = self
             .git_executable
             .as_ref()
             .ok_or_else(|| anyhow::anyhow!("Git executable not found"))?;
 
         let mut cmd = Command::new(git_path);
-        cmd.current_dir(
-            &self
-                .options
-                .repo_root,
-        );
+        cmd.current_dir(dir);
 
         // Set whitespace handling
         let whitespace_mode = match self
             .options
             .whitespace
         {
-            Whitespace::Nowarn => "nowarn",
+            Whitespace::Nowarn | Whitespace::Auto => "nowarn",
             Whitespace::Warn => "warn",
             Whitespace::Fix => "fix",
         };
@@ -360,6 +680,13 @@ impl GitEngine
             .options
             .mode
         {
+            // Index-only applies have no worktree copy to merge into
+            _ if self
+                .options
+                .cached =>
+            {
+                cmd.arg("--cached");
+            }
             GitMode::Index =>
             {
                 cmd.arg("--index");
@@ -383,6 +710,11 @@ impl GitEngine
             }
         }
 
+        if zero_context
+        {
+            cmd.arg("--unidiff-zero");
+        }
+
         // Add verbose output for better error parsing
         cmd.arg("--verbose");
         cmd.arg("--reject");
@@ -428,28 +760,287 @@ impl GitEngine
             Vec::new()
         };
 
-        // Check for conflict markers in applied files
-        let left_markers = if !check_only
-            && matches!(
-                self.options
-                    .mode,
-                GitMode::ThreeWay
-            )
+        Ok(GitOutcome {
+            applied_files,
+            conflicts,
+            left_markers: Vec::new(),
+            stderr_raw: stderr.to_string(),
+        })
+    }
+
+    /// `run_git_apply` through libgit2: the same index/worktree targets as
+    /// `git apply --index`, falling back to [`Self::run_libgit2_three_way`]
+    /// in 3-way mode
+    fn run_libgit2_apply(
+        &self,
+        dir: &Path,
+        patch_content: &str,
+        check_only: bool,
+    ) -> Result<GitOutcome>
+    {
+        if matches!(
+            self.options
+                .mode,
+            GitMode::Worktree
+        )
         {
-            find_conflict_markers(&applied_files)?
+            bail!(
+                "GitMode::Worktree is not implemented yet. Use --git-mode 3way or --git-mode index."
+            );
+        }
+
+        let repo = git2::Repository::discover(dir)
+            .context("Failed to open repository with libgit2")?;
+        let diff = git2::Diff::from_buffer(patch_content.as_bytes())
+            .context("libgit2 could not parse the generated patch")?;
+        let files: Vec<PathBuf> = diff
+            .deltas()
+            .filter_map(|d| {
+                d.new_file()
+                    .path()
+                    .map(Path::to_path_buf)
+            })
+            .collect();
+
+        // libgit2 announces each file before applying it, so the last one
+        // seen is the one that failed
+        let current = std::cell::RefCell::new(None::<PathBuf>);
+        let mut opts = git2::ApplyOptions::new();
+        opts.check(check_only);
+        opts.delta_callback(|delta| {
+            *current.borrow_mut() = delta.and_then(|d| {
+                d.new_file()
+                    .path()
+                    .map(Path::to_path_buf)
+            });
+            true
+        });
+
+        let location = if self
+            .options
+            .cached
+        {
+            git2::ApplyLocation::Index
         }
         else
         {
-            Vec::new()
+            git2::ApplyLocation::Both
         };
+        match repo.apply(&diff, location, Some(&mut opts))
+        {
+            Ok(()) => Ok(GitOutcome {
+                applied_files: files,
+                conflicts: Vec::new(),
+                left_markers: Vec::new(),
+                stderr_raw: String::new(),
+            }),
+            Err(e) =>
+            {
+                let three_way = e.class() == git2::ErrorClass::Patch
+                    && matches!(
+                        self.options
+                            .mode,
+                        GitMode::ThreeWay
+                    )
+                    && !self
+                        .options
+                        .cached;
+                if three_way
+                    && let Some(outcome) =
+                        Self::run_libgit2_three_way(&repo, &diff, &files, check_only)?
+                {
+                    return Ok(outcome);
+                }
 
-        Ok(GitOutcome {
-            applied_files,
-            conflicts,
-            left_markers,
-            stderr_raw: stderr.to_string(),
-        })
+                let path = current
+                    .borrow()
+                    .clone()
+                    .unwrap_or_else(|| PathBuf::from("unknown"));
+                let conflict = if e.class() == git2::ErrorClass::Patch
+                {
+                    GitConflict::PreimageMismatch {
+                        path,
+                        hunk: (0, 0),
+                        hint: "Target lines changed since suggestion. Try `--engine auto` or \
+                               regenerate.",
+                    }
+                }
+                else if e.class() == git2::ErrorClass::Index
+                {
+                    GitConflict::IndexRequired {
+                        path,
+                        hint: "Requires clean index. Commit or stash changes, or use `--git-mode \
+                               3way`.",
+                    }
+                }
+                else
+                {
+                    GitConflict::Other(
+                        e.message()
+                            .to_string(),
+                    )
+                };
+                Ok(GitOutcome {
+                    applied_files: Vec::new(),
+                    conflicts: vec![conflict],
+                    left_markers: Vec::new(),
+                    stderr_raw: e
+                        .message()
+                        .to_string(),
+                })
+            }
+        }
     }
+
+    /// 3-way fallback for libgit2: apply the patch to each file's index
+    /// blob, then merge that into the worktree copy with the index blob as
+    /// the base. The index takes the patched blob, so unstaged edits stay
+    /// unstaged; overlapping edits leave conflict markers in the worktree.
+    /// `None` when the patch does not fit the index version either.
+    fn run_libgit2_three_way(
+        repo: &git2::Repository,
+        diff: &git2::Diff<'_>,
+        files: &[PathBuf],
+        check_only: bool,
+    ) -> Result<Option<GitOutcome>>
+    {
+        let Some(workdir) = repo.workdir()
+        else
+        {
+            return Ok(None);
+        };
+        let mut index = repo
+            .index()
+            .context("Failed to read the index with libgit2")?;
+        let tree = repo.find_tree(
+            index
+                .write_tree()
+                .context("Failed to write the index tree")?,
+        )?;
+        let Ok(patched) = repo.apply_to_tree(&tree, diff, None)
+        else
+        {
+            return Ok(None);
+        };
+
+        let mut outcome = GitOutcome {
+            applied_files: files.to_vec(),
+            conflicts: Vec::new(),
+            left_markers: Vec::new(),
+            stderr_raw: String::new(),
+        };
+        if check_only
+        {
+            return Ok(Some(outcome));
+        }
+
+        let blob = |id: git2::Oid| -> Result<Vec<u8>> {
+            Ok(repo
+                .find_blob(id)?
+                .content()
+                .to_vec())
+        };
+        for rel in files
+        {
+            let abs = workdir.join(rel);
+            let base = index
+                .get_path(rel, 0)
+                .map(|e| blob(e.id))
+                .transpose()?;
+            let ours = std::fs::read(&abs).ok();
+            match patched.get_path(rel, 0)
+            {
+                // The patch deletes the file: only safe when it is unedited
+                None if ours == base =>
+                {
+                    if ours.is_some()
+                    {
+                        std::fs::remove_file(&abs)
+                            .with_context(|| format!("Failed to remove {}", abs.display()))?;
+                    }
+                    index.remove_path(rel)?;
+                }
+                None =>
+                {
+                    outcome
+                        .conflicts
+                        .push(GitConflict::PreimageMismatch {
+                            path: rel.clone(),
+                            hunk: (0, 0),
+                            hint: "File edited locally but deleted by the patch. Resolve by hand.",
+                        })
+                }
+                Some(entry) =>
+                {
+                    let theirs = blob(entry.id)?;
+                    let (merged, clean) = merge_file(
+                        &rel.to_string_lossy(),
+                        "index",
+                        base.as_deref()
+                            .unwrap_or_default(),
+                        ours.as_deref()
+                            .unwrap_or_default(),
+                        &theirs,
+                    )?;
+                    if let Some(parent) = abs.parent()
+                    {
+                        std::fs::create_dir_all(parent)?;
+                    }
+                    std::fs::write(&abs, merged)
+                        .with_context(|| format!("Failed to write {}", abs.display()))?;
+                    if clean
+                    {
+                        index.add(&entry)?;
+                    }
+                    else
+                    {
+                        outcome
+                            .left_markers
+                            .push(rel.clone());
+                    }
+                }
+            }
+        }
+        index
+            .write()
+            .context("Failed to write the index with libgit2")?;
+        Ok(Some(outcome))
+    }
+}
+
+/// Three-way merge of one file with libgit2; returns the merged bytes and
+/// whether the merge was clean (no conflict markers). `ancestor` labels
+/// the base in conflict markers
+pub fn merge_file(
+    path: &str,
+    ancestor: &str,
+    base: &[u8],
+    ours: &[u8],
+    theirs: &[u8],
+) -> Result<(Vec<u8>, bool)>
+{
+    // merge_file doesn't initialize libgit2 itself; creating a Buf does
+    drop(git2::Buf::new());
+    let input = |content| {
+        let mut input = git2::MergeFileInput::new();
+        input
+            .content(content)
+            .path(path)
+            .mode(Some(git2::FileMode::Blob));
+        input
+    };
+    let mut opts = git2::MergeFileOptions::new();
+    opts.ancestor_label(ancestor)
+        .our_label("working copy")
+        .their_label("edit spec");
+    let merged = git2::merge_file(&input(base), &input(ours), &input(theirs), Some(&mut opts))
+        .with_context(|| format!("3-way merge failed: {path}"))?;
+    Ok((
+        merged
+            .content()
+            .to_vec(),
+        merged.is_automergeable(),
+    ))
 }
 
 /// Detect git executable and verify minimum version
@@ -618,6 +1209,150 @@ fn find_conflict_markers(files: &[PathBuf]) -> Result<Vec<PathBuf>>
     Ok(files_with_markers)
 }
 
+/// Stage exactly `files` and commit only them; other staged changes stay
+/// staged but out of the commit. Returns the new commit hash.
+pub fn commit_files(
+    repo_root: &Path,
+    files: &[PathBuf],
+    message: &str,
+    signoff: bool,
+) -> Result<String>
+{
+    if files.is_empty()
+    {
+        bail!("nothing to commit: the apply touched no files");
+    }
+
+    let run = |args: &[&std::ffi::OsStr]| -> Result<String> {
+        let out = Command::new("git")
+            .args(args)
+            .current_dir(repo_root)
+            .output()
+            .context("Failed to run git")?;
+        if !out
+            .status
+            .success()
+        {
+            bail!(
+                "git {} failed: {}",
+                args[0].to_string_lossy(),
+                String::from_utf8_lossy(&out.stderr).trim()
+            );
+        }
+        Ok(String::from_utf8_lossy(&out.stdout)
+            .trim()
+            .to_string())
+    };
+
+    let mut add: Vec<&std::ffi::OsStr> = vec!["add".as_ref(), "-A".as_ref(), "--".as_ref()];
+    add.extend(
+        files
+            .iter()
+            .map(|f| f.as_os_str()),
+    );
+    run(&add)?;
+
+    let mut commit: Vec<&std::ffi::OsStr> =
+        vec!["commit".as_ref(), "--only".as_ref(), "-m".as_ref(), message.as_ref()];
+    if signoff
+    {
+        commit.push("--signoff".as_ref());
+    }
+    commit.push("--".as_ref());
+    commit.extend(
+        files
+            .iter()
+            .map(|f| f.as_os_str()),
+    );
+    run(&commit)?;
+
+    run(&["rev-parse".as_ref(), "HEAD".as_ref()])
+}
+
+/// Tip of local branch `name`, or `None` if it does not exist yet. Fails on
+/// invalid names and on branches checked out in some worktree, since moving
+/// those behind git's back would desync their working tree.
+pub fn branch_tip(
+    repo_root: &Path,
+    name: &str,
+) -> Result<Option<String>>
+{
+    let git = |args: &[&str]| {
+        Command::new("git")
+            .args(args)
+            .current_dir(repo_root)
+            .output()
+            .context("Failed to run git")
+    };
+
+    if !git(&["check-ref-format", "--branch", name])?
+        .status
+        .success()
+    {
+        bail!("invalid branch name: {name}");
+    }
+
+    let refname = format!("refs/heads/{name}");
+    let worktrees = git(&["worktree", "list", "--porcelain"])?;
+    let mut current: Option<&str> = None;
+    for line in String::from_utf8_lossy(&worktrees.stdout).lines()
+    {
+        if let Some(path) = line.strip_prefix("worktree ")
+        {
+            current = Some(path);
+        }
+        else if line.strip_prefix("branch ") == Some(refname.as_str())
+        {
+            bail!(
+                "branch {name} is checked out in {}; apply there instead",
+                current.unwrap_or("another worktree")
+            );
+        }
+    }
+
+    let out = git(&["rev-parse", "--verify", "--quiet", &format!("{refname}^{{commit}}")])?;
+    if !out
+        .status
+        .success()
+    {
+        return Ok(None);
+    }
+    Ok(Some(
+        String::from_utf8_lossy(&out.stdout)
+            .trim()
+            .to_string(),
+    ))
+}
+
+/// Point branch `name` at `commit`, failing if it moved away from `old`
+/// (`None`: the branch must not exist yet) in the meantime
+pub fn update_branch(
+    repo_root: &Path,
+    name: &str,
+    commit: &str,
+    old: Option<&str>,
+) -> Result<()>
+{
+    let out = Command::new("git")
+        .args(["update-ref", "-m", "rup apply --branch"])
+        .arg(format!("refs/heads/{name}"))
+        .arg(commit)
+        .arg(old.unwrap_or(""))
+        .current_dir(repo_root)
+        .output()
+        .context("Failed to run git update-ref")?;
+    if !out
+        .status
+        .success()
+    {
+        bail!(
+            "git update-ref failed: {}",
+            String::from_utf8_lossy(&out.stderr).trim()
+        );
+    }
+    Ok(())
+}
+
 /// Render conflicts in a predictable, script-friendly way
 pub fn render_conflict_summary(conflicts: &[GitConflict]) -> Vec<String>
 {
@@ -646,6 +1381,10 @@ pub fn render_conflict_summary(conflicts: &[GitConflict]) -> Vec<String>
                 {
                     format!("{}:0:0 binary_or_mode", path.display())
                 }
+                GitConflict::FileTooLarge { path, .. } =>
+                {
+                    format!("{}:0:0 file_too_large", path.display())
+                }
                 GitConflict::Other(msg) =>
                 {
                     format!("unknown:0:0 {}", msg.replace(':', ";"))
@@ -709,6 +1448,14 @@ pub fn render_conflict_summary_human(conflicts: &[GitConflict]) -> String
                     hint
                 ));
             }
+            GitConflict::FileTooLarge { path, hint } =>
+            {
+                output.push_str(&format!(
+                    "  • {}: file too large\n    Remedy: {}\n",
+                    path.display(),
+                    hint
+                ));
+            }
             GitConflict::Other(msg) =>
             {
                 output.push_str(&format!(
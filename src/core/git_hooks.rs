@@ -0,0 +1,394 @@
+//! `rup git install-hooks`: pre-commit/pre-push guards for edit specs
+//!
+//! The installed hooks are thin shell scripts that call back into
+//! `rup git run-hook <name>`. For every file a commit (or push) adds or
+//! modifies, the hook blocks on unresolved conflict markers found by
+//! [`parse_conflicts`], and runs `rup check-syntax` on files matching the
+//! configured edit-spec globs. Content is read from the index (pre-commit)
+//! or the pushed commit (pre-push), not the worktree.
+
+use std::{
+    io::Cursor,
+    path::{Path, PathBuf},
+    process::Command,
+};
+
+use anyhow::{Context, Result, bail};
+use globset::{Glob, GlobSet, GlobSetBuilder};
+
+use crate::{
+    cli::{AppContext, GitArgs, GitHook, GitSubcommand, InstallHooksArgs, RunHookArgs},
+    core::{conflict::parse_conflicts, edit::discover_repo_root},
+};
+
+/// Second line of every hook script; marks the hook as ours to overwrite
+const MARKER: &str = "# Installed by `rup git install-hooks`";
+
+/// Object ID git uses for "no commit" in pre-push input
+const ZERO_OID: &str = "0000000000000000000000000000000000000000";
+
+impl GitHook
+{
+    /// File name of the hook in the hooks directory
+    pub fn name(self) -> &'static str
+    {
+        match self
+        {
+            GitHook::PreCommit => "pre-commit",
+            GitHook::PrePush => "pre-push",
+        }
+    }
+}
+
+pub fn run(
+    args: GitArgs,
+    ctx: &AppContext,
+) -> Result<()>
+{
+    let cwd = std::env::current_dir().context("Failed to get current directory")?;
+    let Some(repo_root) = discover_repo_root(None, &cwd)?
+    else
+    {
+        bail!("not inside a git repository");
+    };
+
+    match args.command
+    {
+        GitSubcommand::InstallHooks(args) => install_hooks_cmd(&repo_root, args, ctx),
+        GitSubcommand::RunHook(args) => run_hook_cmd(&repo_root, args),
+    }
+}
+
+fn install_hooks_cmd(
+    repo_root: &Path,
+    args: InstallHooksArgs,
+    ctx: &AppContext,
+) -> Result<()>
+{
+    let installed = install_hooks(repo_root, &args.hooks, &args.spec_globs, args.force)?;
+    if !ctx.quiet
+    {
+        for path in installed
+        {
+            println!("Installed {}", path.display());
+        }
+    }
+    Ok(())
+}
+
+fn run_hook_cmd(
+    repo_root: &Path,
+    args: RunHookArgs,
+) -> Result<()>
+{
+    let push_refs = if args.hook == GitHook::PrePush
+    {
+        std::io::read_to_string(std::io::stdin()).context("read pre-push refs from stdin")?
+    }
+    else
+    {
+        String::new()
+    };
+
+    let problems = check_hook(repo_root, args.hook, &args.spec_globs, &push_refs)?;
+    if problems.is_empty()
+    {
+        return Ok(());
+    }
+    for problem in &problems
+    {
+        eprintln!("  {}", problem);
+    }
+    bail!(
+        "{} hook found {} problem(s); fix them or bypass with --no-verify",
+        args.hook
+            .name(),
+        problems.len()
+    );
+}
+
+/// Write the hook scripts into the repo's hooks directory (honoring
+/// `core.hooksPath`). Hooks not written by rup are left alone unless
+/// `force`, which keeps the old script as `<name>.rup-backup`.
+pub fn install_hooks(
+    repo_root: &Path,
+    hooks: &[GitHook],
+    spec_globs: &[String],
+    force: bool,
+) -> Result<Vec<PathBuf>>
+{
+    let hooks_dir = repo_root.join(git(repo_root, &["rev-parse", "--git-path", "hooks"])?);
+    std::fs::create_dir_all(&hooks_dir)
+        .with_context(|| format!("create hooks dir: {}", hooks_dir.display()))?;
+
+    let mut installed = Vec::new();
+    for &hook in hooks
+    {
+        let path = hooks_dir.join(hook.name());
+        if let Ok(existing) = std::fs::read_to_string(&path)
+            && !existing.contains(MARKER)
+        {
+            if !force
+            {
+                bail!(
+                    "{} already exists and was not installed by rup; rerun with --force to \
+                     replace it",
+                    path.display()
+                );
+            }
+            let backup = hooks_dir.join(format!("{}.rup-backup", hook.name()));
+            std::fs::rename(&path, &backup)
+                .with_context(|| format!("back up {}", path.display()))?;
+        }
+
+        std::fs::write(&path, hook_script(hook, spec_globs))
+            .with_context(|| format!("write {}", path.display()))?;
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o755))?;
+        }
+        installed.push(path);
+    }
+    Ok(installed)
+}
+
+fn hook_script(
+    hook: GitHook,
+    spec_globs: &[String],
+) -> String
+{
+    let globs: String = spec_globs
+        .iter()
+        .map(|g| format!(" --spec-glob '{}'", g.replace('\'', "'\\''")))
+        .collect();
+    format!(
+        "#!/bin/sh\n{MARKER}; rerun it to update, or delete this file to remove.\nif ! command -v \
+         rup >/dev/null 2>&1; then\n\techo \"rup not found on PATH; skipping {name} checks\" \
+         >&2\n\texit 0\nfi\nexec rup --quiet git run-hook {name}{globs} \"$@\"\n",
+        name = hook.name()
+    )
+}
+
+/// Problems (one line each) that should block the commit or push
+pub fn check_hook(
+    repo_root: &Path,
+    hook: GitHook,
+    spec_globs: &[String],
+    push_refs: &str,
+) -> Result<Vec<String>>
+{
+    let specs = spec_matcher(spec_globs)?;
+
+    // (revision prefix for `git cat-file`, path)
+    let mut files: Vec<(String, String)> = Vec::new();
+    match hook
+    {
+        GitHook::PreCommit =>
+        {
+            let staged = git(repo_root, &[
+                "diff",
+                "--cached",
+                "--name-only",
+                "-z",
+                "--diff-filter=ACMR",
+            ])?;
+            files.extend(split_z(&staged).map(|p| (String::new(), p.to_string())));
+        }
+        GitHook::PrePush =>
+        {
+            // <local ref> <local oid> <remote ref> <remote oid>
+            for line in push_refs.lines()
+            {
+                let fields: Vec<&str> = line
+                    .split_whitespace()
+                    .collect();
+                let [_, local, _, remote] = fields[..]
+                else
+                {
+                    continue;
+                };
+                if local == ZERO_OID
+                {
+                    continue; // branch deletion
+                }
+                for path in pushed_files(repo_root, local, remote)?
+                {
+                    if !files
+                        .iter()
+                        .any(|(rev, p)| rev == local && *p == path)
+                    {
+                        files.push((local.to_string(), path));
+                    }
+                }
+            }
+        }
+    }
+
+    let mut problems = Vec::new();
+    for (rev, path) in &files
+    {
+        // Files deleted again later in a pushed range have nothing to check
+        let Ok(bytes) = git_bytes(repo_root, &["cat-file", "blob", &format!("{rev}:{path}")])
+        else
+        {
+            continue;
+        };
+        if bytes.contains(&0)
+        {
+            continue; // binary
+        }
+
+        for marker in parse_conflicts(PathBuf::from(path), Cursor::new(&bytes))?
+        {
+            problems.push(format!(
+                "{}:{}: unresolved conflict markers",
+                path,
+                marker
+                    .line_range
+                    .0
+            ));
+        }
+
+        if specs.is_match(path)
+            && let Some(problem) = check_spec(repo_root, path, &bytes)?
+        {
+            problems.push(problem);
+        }
+    }
+    Ok(problems)
+}
+
+/// Run `rup check-syntax` on a spec's committed content
+fn check_spec(
+    repo_root: &Path,
+    path: &str,
+    bytes: &[u8],
+) -> Result<Option<String>>
+{
+    let mut tmp = tempfile::NamedTempFile::new().context("create temp edit spec")?;
+    std::io::Write::write_all(&mut tmp, bytes)?;
+    let exe = std::env::current_exe().context("locate rup executable")?;
+    let out = Command::new(exe)
+        .args(["--quiet", "check-syntax"])
+        .arg(tmp.path())
+        .current_dir(repo_root)
+        .output()
+        .context("run rup check-syntax")?;
+    if out
+        .status
+        .success()
+    {
+        return Ok(None);
+    }
+
+    let detail = [out.stderr, out.stdout]
+        .iter()
+        .map(|b| {
+            String::from_utf8_lossy(b)
+                .trim()
+                .to_string()
+        })
+        .find(|s| !s.is_empty())
+        .unwrap_or_default();
+    let first = detail
+        .lines()
+        .next()
+        .unwrap_or("check-syntax failed");
+    Ok(Some(format!("{}: {}", path, first)))
+}
+
+/// Files added or modified by commits in a pushed range
+fn pushed_files(
+    repo_root: &Path,
+    local: &str,
+    remote: &str,
+) -> Result<Vec<String>>
+{
+    // New branches: everything not already on a remote
+    let commits = if remote == ZERO_OID
+    {
+        git(repo_root, &["rev-list", local, "--not", "--remotes"])?
+    }
+    else
+    {
+        git(repo_root, &["rev-list", &format!("{remote}..{local}")])?
+    };
+
+    let mut files: Vec<String> = Vec::new();
+    for commit in commits.lines()
+    {
+        let changed = git(repo_root, &[
+            "diff-tree",
+            "--root",
+            "--no-commit-id",
+            "--name-only",
+            "-r",
+            "-z",
+            "--diff-filter=ACMR",
+            commit,
+        ])?;
+        for path in split_z(&changed)
+        {
+            if !files
+                .iter()
+                .any(|f| f == path)
+            {
+                files.push(path.to_string());
+            }
+        }
+    }
+    Ok(files)
+}
+
+fn spec_matcher(globs: &[String]) -> Result<GlobSet>
+{
+    let mut builder = GlobSetBuilder::new();
+    for glob in globs
+    {
+        builder.add(Glob::new(glob).with_context(|| format!("invalid --spec-glob: {glob}"))?);
+    }
+    Ok(builder.build()?)
+}
+
+fn split_z(s: &str) -> impl Iterator<Item = &str>
+{
+    s.split('\0')
+        .filter(|p| !p.is_empty())
+}
+
+/// Run git and return its trimmed stdout
+fn git(
+    repo_root: &Path,
+    args: &[&str],
+) -> Result<String>
+{
+    let out = git_bytes(repo_root, args)?;
+    Ok(String::from_utf8_lossy(&out)
+        .trim_end()
+        .to_string())
+}
+
+/// Run git and return its raw stdout
+fn git_bytes(
+    repo_root: &Path,
+    args: &[&str],
+) -> Result<Vec<u8>>
+{
+    let out = Command::new("git")
+        .args(args)
+        .current_dir(repo_root)
+        .output()
+        .with_context(|| format!("run git {}", args[0]))?;
+    if !out
+        .status
+        .success()
+    {
+        bail!(
+            "git {} failed: {}",
+            args.join(" "),
+            String::from_utf8_lossy(&out.stderr).trim()
+        );
+    }
+    Ok(out.stdout)
+}
@@ -0,0 +1,198 @@
+//! Indentation detection and tab/space normalization for `--whitespace auto`
+//!
+//! Models often answer with spaces for a tab-indented file (or the reverse),
+//! which turns an otherwise clean edit into a wall of whitespace conflicts.
+//! Before patches are generated, the NEW content of each operation is
+//! re-indented to the target file's style. Only leading whitespace changes,
+//! and only when the file's style is clear.
+
+use std::fs;
+
+use crate::core::edit::{EditOperation, EditSpec};
+
+/// Indentation used when a file or snippet gives no clear signal
+const DEFAULT_WIDTH: usize = 4;
+
+/// How a file indents its lines
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum IndentStyle
+{
+    Tabs,
+    /// Spaces, with the width of one level
+    Spaces(usize),
+}
+
+/// Dominant indentation of `text`, or `None` when nothing is indented
+pub fn detect_indent(text: &str) -> Option<IndentStyle>
+{
+    let (mut tabs, mut spaces) = (0usize, 0usize);
+    // How often each positive step in space indentation occurs
+    let mut steps = [0usize; 9];
+    let mut prev = 0usize;
+
+    for line in text.lines()
+    {
+        if line
+            .trim()
+            .is_empty()
+        {
+            continue;
+        }
+        if line.starts_with('\t')
+        {
+            tabs += 1;
+            continue;
+        }
+        let width = line.len()
+            - line
+                .trim_start_matches(' ')
+                .len();
+        if width > 0
+        {
+            spaces += 1;
+        }
+        if width > prev && width - prev < steps.len()
+        {
+            steps[width - prev] += 1;
+        }
+        prev = width;
+    }
+
+    if tabs == 0 && spaces == 0
+    {
+        return None;
+    }
+    if tabs > spaces
+    {
+        return Some(IndentStyle::Tabs);
+    }
+    let width = (1..steps.len())
+        .filter(|&w| steps[w] > 0)
+        .max_by_key(|&w| (steps[w], w))
+        .unwrap_or(DEFAULT_WIDTH);
+    Some(IndentStyle::Spaces(width))
+}
+
+/// Re-indent the leading whitespace of every line in `text` to `target`.
+/// `source_width` is how many spaces made one level in `text`.
+pub fn reindent(
+    text: &str,
+    target: IndentStyle,
+    source_width: usize,
+) -> String
+{
+    text.split_inclusive('\n')
+        .map(|line| {
+            let body = line.trim_start_matches([' ', '\t']);
+            let lead = &line[..line.len() - body.len()];
+            let tab_count = lead
+                .chars()
+                .filter(|&c| c == '\t')
+                .count();
+            let space_count = lead.len() - tab_count;
+            let indent = match target
+            {
+                IndentStyle::Tabs if space_count >= source_width.max(1) =>
+                {
+                    let width = source_width.max(1);
+                    format!(
+                        "{}{}",
+                        "\t".repeat(tab_count + space_count / width),
+                        " ".repeat(space_count % width)
+                    )
+                }
+                IndentStyle::Spaces(width) if tab_count > 0 =>
+                {
+                    " ".repeat(tab_count * width + space_count)
+                }
+                _ => lead.to_string(),
+            };
+            format!("{indent}{body}")
+        })
+        .collect()
+}
+
+/// Re-indent `new` to match `file_style` when the two disagree on tabs vs
+/// spaces; otherwise return it unchanged
+fn normalize_new(
+    new: &str,
+    file_style: IndentStyle,
+) -> String
+{
+    match (file_style, detect_indent(new))
+    {
+        (IndentStyle::Tabs, Some(IndentStyle::Spaces(width))) =>
+        {
+            reindent(new, IndentStyle::Tabs, width)
+        }
+        (IndentStyle::Spaces(width), Some(IndentStyle::Tabs)) =>
+        {
+            reindent(new, IndentStyle::Spaces(width), width)
+        }
+        _ => new.to_string(),
+    }
+}
+
+/// Copy of `spec` with every NEW block re-indented to its target file's
+/// style. Files that cannot be read or show no indentation are left as is.
+pub fn normalize_spec(spec: &EditSpec) -> EditSpec
+{
+    let mut spec = spec.clone();
+    for block in &mut spec.file_blocks
+    {
+        let Some(style) = fs::read_to_string(&block.path)
+            .ok()
+            .and_then(|content| detect_indent(&content))
+        else
+        {
+            continue;
+        };
+        for op in &mut block.operations
+        {
+            match op
+            {
+                EditOperation::Replace { new_content, .. }
+                | EditOperation::Insert { new_content, .. } =>
+                {
+                    *new_content = normalize_new(new_content, style);
+                }
+                EditOperation::Delete { .. } =>
+                {}
+            }
+        }
+    }
+    spec
+}
+
+#[cfg(test)]
+mod tests
+{
+    use super::*;
+
+    #[test]
+    fn test_detect_indent()
+    {
+        assert_eq!(
+            detect_indent("fn a() {\n\tb();\n\tif c {\n\t\td();\n\t}\n}\n"),
+            Some(IndentStyle::Tabs)
+        );
+        assert_eq!(
+            detect_indent("a:\n  b:\n    c: 1\n  d: 2\n"),
+            Some(IndentStyle::Spaces(2))
+        );
+        assert_eq!(detect_indent("flat\ntext\n"), None);
+    }
+
+    #[test]
+    fn test_reindent_between_tabs_and_spaces()
+    {
+        let spaces = "if x {\n    y();\n        z();\n}\n";
+        let tabs = "if x {\n\ty();\n\t\tz();\n}\n";
+        assert_eq!(normalize_new(spaces, IndentStyle::Tabs), tabs);
+        assert_eq!(normalize_new(tabs, IndentStyle::Spaces(4)), spaces);
+
+        // Matching styles and sub-level alignment are left alone
+        assert_eq!(normalize_new(spaces, IndentStyle::Spaces(2)), spaces);
+        assert_eq!(reindent("  x\n", IndentStyle::Tabs, 4), "  x\n");
+    }
+}
@@ -0,0 +1,143 @@
+//! Jujutsu (jj) integration for the `jj` apply engine
+//!
+//! jj records the working copy as a commit (`@`) whenever a jj command
+//! runs. The jj engine leans on that: it snapshots before and after an
+//! apply so each apply is a single jj operation (`jj undo` reverts it), and
+//! a file whose spans went stale is merged three ways against its version
+//! in the parent revision `@-`, the way `git apply --3way` uses the index.
+
+use std::{
+    path::{Path, PathBuf},
+    process::Command,
+};
+
+use anyhow::{Context, Result, bail};
+
+/// Nearest ancestor of `start` (inclusive) holding a `.jj` directory
+pub fn detect_repo(start: &Path) -> Option<PathBuf>
+{
+    start
+        .ancestors()
+        .find(|dir| {
+            dir.join(".jj")
+                .is_dir()
+        })
+        .map(Path::to_path_buf)
+}
+
+/// Snapshot the working copy; returns the commit ID of `@` afterwards
+pub fn snapshot(repo_root: &Path) -> Result<String>
+{
+    let out = jj(repo_root, &[
+        "log",
+        "-r",
+        "@",
+        "--no-graph",
+        "-T",
+        "commit_id",
+    ])?;
+    Ok(String::from_utf8_lossy(&out)
+        .trim()
+        .to_string())
+}
+
+/// Contents of repo-relative `rel` in the parent revision `@-`, or `None`
+/// when the file does not exist there
+pub fn parent_content(
+    repo_root: &Path,
+    rel: &Path,
+) -> Result<Option<Vec<u8>>>
+{
+    // Quote the path so spaces and glob characters are taken literally
+    let path = rel
+        .to_string_lossy()
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"");
+    let fileset = format!("root-file:\"{path}\"");
+    let out = Command::new("jj")
+        .args(["--no-pager", "--color", "never", "file", "show", "-r", "@-", &fileset])
+        .current_dir(repo_root)
+        .output()
+        .context("Failed to run jj (is it on PATH?)")?;
+    Ok(out
+        .status
+        .success()
+        .then_some(out.stdout))
+}
+
+/// Three-way merge of one file; returns the merged bytes and whether the
+/// merge was clean (no conflict markers)
+pub fn merge_three(
+    path: &str,
+    base: &[u8],
+    ours: &[u8],
+    theirs: &[u8],
+) -> Result<(Vec<u8>, bool)>
+{
+    crate::core::git::merge_file(path, "@-", base, ours, theirs)
+}
+
+/// Run jj in `repo_root` and return its stdout
+fn jj(
+    repo_root: &Path,
+    args: &[&str],
+) -> Result<Vec<u8>>
+{
+    let out = Command::new("jj")
+        .args(["--no-pager", "--color", "never"])
+        .args(args)
+        .current_dir(repo_root)
+        .output()
+        .context("Failed to run jj (is it on PATH?)")?;
+    if !out
+        .status
+        .success()
+    {
+        bail!(
+            "jj {} failed: {}",
+            args.join(" "),
+            String::from_utf8_lossy(&out.stderr).trim()
+        );
+    }
+    Ok(out.stdout)
+}
+
+#[cfg(test)]
+mod tests
+{
+    use super::*;
+
+    #[test]
+    fn test_detect_repo_walks_up_to_dot_jj()
+    {
+        let tmp = tempfile::tempdir().unwrap();
+        let nested = tmp
+            .path()
+            .join("a/b");
+        std::fs::create_dir_all(&nested).unwrap();
+        assert!(detect_repo(&nested).is_none());
+
+        std::fs::create_dir(
+            tmp.path()
+                .join(".jj"),
+        )
+        .unwrap();
+        assert_eq!(detect_repo(&nested).as_deref(), Some(tmp.path()));
+    }
+
+    #[test]
+    fn test_merge_three_clean_and_conflicting()
+    {
+        let base = b"one\ntwo\nthree\nfour\nfive\n";
+        let ours = b"zero\none\ntwo\nthree\nfour\nfive\n";
+        let theirs = b"one\ntwo\nthree\nfour\nFIVE\n";
+        let (merged, clean) = merge_three("f.txt", base, ours, theirs).unwrap();
+        assert!(clean);
+        assert_eq!(merged, b"zero\none\ntwo\nthree\nfour\nFIVE\n");
+
+        let ours = b"one\ntwo\nthree\nfour\nfive!\n";
+        let (merged, clean) = merge_three("f.txt", base, ours, theirs).unwrap();
+        assert!(!clean);
+        assert!(String::from_utf8_lossy(&merged).contains("<<<<<<< working copy"));
+    }
+}
@@ -59,11 +59,87 @@ pub struct PatchSet
 }
 
 /// Patch generation configuration
+#[derive(Debug, Clone)]
 pub struct PatchConfig
 {
     pub context_lines: usize,
     pub validate_guards: bool,
     pub merge_adjacent: bool,
+    /// Per-file overrides of `context_lines`; the last matching rule wins
+    pub context_rules: Vec<ContextRule>,
+}
+
+impl PatchConfig
+{
+    /// Context lines for the patch of `path`
+    pub fn context_for(
+        &self,
+        path: &str,
+    ) -> usize
+    {
+        self.context_rules
+            .iter()
+            .rev()
+            .find(|rule| rule.matches(path))
+            .map_or(self.context_lines, |rule| rule.lines)
+    }
+}
+
+/// Context lines for files matching a glob: `--context-for GLOB=N` or
+///
+/// ```toml
+/// [[apply.context]]
+/// glob = "*.lock"
+/// lines = 0
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct ContextRule
+{
+    pub glob: String,
+    pub lines: usize,
+}
+
+impl ContextRule
+{
+    /// Whether `path` (or, for patterns without `/`, its file name) matches
+    pub fn matches(
+        &self,
+        path: &str,
+    ) -> bool
+    {
+        let Ok(glob) = globset::Glob::new(&self.glob)
+        else
+        {
+            return false;
+        };
+        let matcher = glob.compile_matcher();
+        let path = Path::new(path);
+        matcher.is_match(path)
+            || (!self
+                .glob
+                .contains('/')
+                && path
+                    .file_name()
+                    .is_some_and(|name| matcher.is_match(name)))
+    }
+}
+
+impl std::str::FromStr for ContextRule
+{
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err>
+    {
+        let (glob, lines) = s
+            .rsplit_once('=')
+            .ok_or_else(|| format!("expected GLOB=LINES, got '{s}'"))?;
+        globset::Glob::new(glob).map_err(|e| format!("invalid glob '{glob}': {e}"))?;
+        let lines = lines
+            .trim()
+            .parse()
+            .map_err(|_| format!("invalid line count '{lines}'"))?;
+        Ok(Self { glob: glob.to_string(), lines })
+    }
 }
 
 impl Default for PatchConfig
@@ -74,6 +150,7 @@ impl Default for PatchConfig
             context_lines: 3,
             validate_guards: true,
             merge_adjacent: true,
+            context_rules: Vec::new(),
         }
     }
 }
@@ -152,7 +229,11 @@ pub fn generate_patches(
     // Generate patch for each file
     for (path_str, operations) in ops_by_file
     {
-        let file_patch = generate_file_patch(&path_str, operations, config)
+        let file_config = PatchConfig {
+            context_lines: config.context_for(&path_str),
+            ..config.clone()
+        };
+        let file_patch = generate_file_patch(&path_str, operations, &file_config)
             .with_context(|| format!("Failed to generate patch for {}", path_str))?;
         file_patches.push(file_patch);
     }
@@ -356,7 +437,7 @@ fn operation_to_hunk(
                 lines: hunk_lines,
             })
         }
-        EditOperation::Insert { at_line, new_content } =>
+        EditOperation::Insert { at_line, new_content, .. } =>
         {
             let insert_pos = *at_line; // 0 means beginning, N means after line N
             let new_lines: Vec<&str> = new_content
@@ -422,7 +503,7 @@ fn operation_to_hunk(
                 lines: hunk_lines,
             })
         }
-        EditOperation::Delete { start_line, end_line } =>
+        EditOperation::Delete { start_line, end_line, .. } =>
         {
             let delete_count = end_line - start_line + 1;
 
@@ -685,6 +766,141 @@ pub fn render_unified_diff(patch_set: &PatchSet) -> String
     output
 }
 
+/// Headers for [`render_mbox`] emails
+#[derive(Debug, Clone)]
+pub struct MailOptions
+{
+    /// `From:` header, e.g. `Jane Doe <jane@example.com>`
+    pub author: String,
+    /// Subject template: {path}, {n}, {total}, {added}, {removed}
+    pub subject: String,
+    /// `Date:` header
+    pub date: chrono::DateTime<chrono::FixedOffset>,
+}
+
+/// Render each file patch as one `git format-patch` email, in order, so
+/// the concatenation is an mbox that `git am` applies as a series
+pub fn render_mbox(
+    patch_set: &PatchSet,
+    opts: &MailOptions,
+) -> Vec<String>
+{
+    let total = patch_set
+        .file_patches
+        .len();
+    patch_set
+        .file_patches
+        .iter()
+        .enumerate()
+        .map(|(i, file_patch)| render_mail(file_patch, i + 1, total, opts))
+        .collect()
+}
+
+/// Render one file patch as a `git format-patch` email
+fn render_mail(
+    file_patch: &FilePatch,
+    n: usize,
+    total: usize,
+    opts: &MailOptions,
+) -> String
+{
+    let (added, removed) = line_counts(file_patch);
+    let subject = opts
+        .subject
+        .replace("{path}", &file_patch.path)
+        .replace("{n}", &n.to_string())
+        .replace("{total}", &total.to_string())
+        .replace("{added}", &added.to_string())
+        .replace("{removed}", &removed.to_string())
+        .lines()
+        .map(str::trim)
+        .filter(|l| !l.is_empty())
+        .collect::<Vec<_>>()
+        .join(" ");
+    let prefix = if total == 1 { "[PATCH]".to_string() } else { format!("[PATCH {n}/{total}]") };
+
+    let mut output = String::new();
+    // Fixed magic line format-patch uses as the mbox separator
+    output.push_str("From 0000000000000000000000000000000000000000 Mon Sep 17 00:00:00 2001\n");
+    output.push_str(&format!("From: {}\n", opts.author));
+    output.push_str(&format!(
+        "Date: {}\n",
+        opts.date
+            .to_rfc2822()
+    ));
+    output.push_str(&format!("Subject: {} {}\n\n", prefix, subject));
+    output.push_str("---\n");
+    output.push_str(&diffstat(&file_patch.path, added, removed));
+    output.push('\n');
+    render_file_patch(&mut output, file_patch);
+    output.push_str(&format!("-- \nrup {}\n\n", env!("CARGO_PKG_VERSION")));
+    output
+}
+
+/// Added and removed line counts of a file patch
+fn line_counts(file_patch: &FilePatch) -> (usize, usize)
+{
+    let lines = file_patch
+        .hunks
+        .iter()
+        .flat_map(|h| &h.lines);
+    lines.fold((0, 0), |(a, r), line| match line
+    {
+        HunkLine::Add(_) => (a + 1, r),
+        HunkLine::Remove(_) => (a, r + 1),
+        HunkLine::Context(_) => (a, r),
+    })
+}
+
+/// `git diff --stat` style summary of one file
+fn diffstat(
+    path: &str,
+    added: usize,
+    removed: usize,
+) -> String
+{
+    const WIDTH: usize = 60;
+    let changed = added + removed;
+    // Scale the bar down like git does when it would overflow
+    let (plus, minus) = if changed > WIDTH
+    {
+        let plus = added * WIDTH / changed;
+        (plus, WIDTH - plus)
+    }
+    else
+    {
+        (added, removed)
+    };
+    let plural = |n: usize, one: &str, many: &str| {
+        if n == 1
+        {
+            one.to_string()
+        }
+        else
+        {
+            many.to_string()
+        }
+    };
+
+    let mut summary = " 1 file changed".to_string();
+    if added > 0
+    {
+        summary.push_str(&format!(", {} {}(+)", added, plural(added, "insertion", "insertions")));
+    }
+    if removed > 0
+    {
+        summary.push_str(&format!(", {} {}(-)", removed, plural(removed, "deletion", "deletions")));
+    }
+    format!(
+        " {} | {} {}{}\n{}\n",
+        path,
+        changed,
+        "+".repeat(plus),
+        "-".repeat(minus),
+        summary
+    )
+}
+
 /// Render a single file patch
 fn render_file_patch(
     output: &mut String,
@@ -818,6 +1034,7 @@ mod tests
                 operations: vec![EditOperation::Insert {
                     at_line: 1,
                     new_content: "inserted line".to_string(),
+                    after_content: None,
                 }],
             }],
         };
@@ -828,4 +1045,26 @@ mod tests
 
         assert!(diff.contains("+inserted line"));
     }
+
+    #[test]
+    fn test_context_rules()
+    {
+        let config = PatchConfig {
+            context_rules: vec![
+                "*.lock=0".parse().unwrap(),
+                "src/gen/**=1".parse().unwrap(),
+                "src/gen/keep.rs=5".parse().unwrap(),
+            ],
+            ..PatchConfig::default()
+        };
+        assert_eq!(config.context_for("Cargo.lock"), 0);
+        assert_eq!(config.context_for("web/yarn.lock"), 0);
+        assert_eq!(config.context_for("src/gen/a.rs"), 1);
+        assert_eq!(config.context_for("src/gen/keep.rs"), 5);
+        assert_eq!(config.context_for("src/main.rs"), 3);
+
+        assert!("no-equals".parse::<ContextRule>().is_err());
+        assert!("*.rs=lots".parse::<ContextRule>().is_err());
+        assert!("a[.rs=1".parse::<ContextRule>().is_err());
+    }
 }
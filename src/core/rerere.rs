@@ -0,0 +1,225 @@
+//! Conflict resolution memory for `rup resolve` (like `git rerere`)
+//!
+//! Every resolution `resolve --apply` writes is recorded under
+//! `.rup/rerere/<id>/` as a `preimage` (the conflict hunk) and `postimage`
+//! (what replaced it). The id hashes the two sides in sorted order, ignoring
+//! the base and marker labels, so the same conflict seen again (in another
+//! session, file, or with the sides swapped) replays the recorded text.
+//! Replays happen only under the default strategy; an explicit `--strategy`
+//! is applied as asked.
+
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use anyhow::{Context, Result};
+
+use crate::core::conflict::ConflictMarker;
+
+/// Recorded resolutions for one repository
+pub struct Rerere
+{
+    dir: PathBuf,
+}
+
+impl Rerere
+{
+    /// Cache rooted at `<repo_root>/.rup/rerere`; nothing is created until
+    /// the first recording
+    pub fn open(repo_root: &Path) -> Self
+    {
+        Self { dir: repo_root.join(".rup/rerere") }
+    }
+
+    /// Recorded resolution for a conflict with the same sides, if any
+    pub fn lookup(
+        &self,
+        conflict: &ConflictMarker,
+    ) -> Option<String>
+    {
+        fs::read_to_string(
+            self.dir
+                .join(conflict_id(conflict))
+                .join("postimage"),
+        )
+        .ok()
+    }
+
+    /// Remember `resolved` as the resolution of `conflict`, replacing any
+    /// earlier recording
+    pub fn record(
+        &self,
+        conflict: &ConflictMarker,
+        resolved: &str,
+    ) -> Result<()>
+    {
+        let entry = self
+            .dir
+            .join(conflict_id(conflict));
+        fs::create_dir_all(&entry)
+            .with_context(|| format!("Failed to create {}", entry.display()))?;
+        write_atomic(&entry.join("preimage"), &preimage(conflict))?;
+        write_atomic(&entry.join("postimage"), resolved)
+    }
+
+    /// Drop the recording for `conflict`, e.g. after its replay failed
+    /// verification
+    pub fn forget(
+        &self,
+        conflict: &ConflictMarker,
+    ) -> Result<()>
+    {
+        let entry = self
+            .dir
+            .join(conflict_id(conflict));
+        match fs::remove_dir_all(&entry)
+        {
+            Err(e) if e.kind() != std::io::ErrorKind::NotFound =>
+            {
+                Err(e).with_context(|| format!("Failed to remove {}", entry.display()))
+            }
+            _ => Ok(()),
+        }
+    }
+}
+
+/// Stable id of a conflict: blake3 of its two sides in sorted order
+pub fn conflict_id(conflict: &ConflictMarker) -> String
+{
+    let (a, b) = sorted_sides(conflict);
+    let mut hasher = blake3::Hasher::new();
+    hasher.update(a.as_bytes());
+    hasher.update(&[0]);
+    hasher.update(b.as_bytes());
+    hasher
+        .finalize()
+        .to_hex()[..32]
+        .to_string()
+}
+
+fn sorted_sides(conflict: &ConflictMarker) -> (&str, &str)
+{
+    let (ours, theirs) = (
+        conflict
+            .ours
+            .as_str(),
+        conflict
+            .theirs
+            .as_str(),
+    );
+    if ours <= theirs
+    {
+        (ours, theirs)
+    }
+    else
+    {
+        (theirs, ours)
+    }
+}
+
+/// Conflict hunk in canonical form, for humans browsing the cache
+fn preimage(conflict: &ConflictMarker) -> String
+{
+    let (a, b) = sorted_sides(conflict);
+    let line = |s: &str| {
+        if s.is_empty() || s.ends_with('\n')
+        {
+            s.to_string()
+        }
+        else
+        {
+            format!("{s}\n")
+        }
+    };
+    format!("<<<<<<<\n{}=======\n{}>>>>>>>\n", line(a), line(b))
+}
+
+fn write_atomic(
+    path: &Path,
+    content: &str,
+) -> Result<()>
+{
+    let tmp = path.with_extension("tmp");
+    fs::write(&tmp, content).with_context(|| format!("Failed to write {}", tmp.display()))?;
+    fs::rename(&tmp, path).with_context(|| format!("Failed to write {}", path.display()))
+}
+
+#[cfg(test)]
+mod tests
+{
+    use super::*;
+    use crate::core::conflict::{ConflictOrigin, ConflictType};
+
+    fn conflict(
+        ours: &str,
+        theirs: &str,
+    ) -> ConflictMarker
+    {
+        ConflictMarker {
+            file: PathBuf::from("a.rs"),
+            origin: ConflictOrigin::GitMarkers,
+            conflict_type: ConflictType::GitMarkers {
+                ours_meta: "HEAD".into(),
+                theirs_meta: "topic".into(),
+                has_base: false,
+            },
+            byte_range: (0, 0),
+            line_range: (1, 5),
+            ours: ours.into(),
+            theirs: theirs.into(),
+            base: None,
+            confidence: 0.0,
+            attribution: None,
+        }
+    }
+
+    #[test]
+    fn test_record_and_replay_across_side_order()
+    {
+        let dir = tempfile::tempdir().unwrap();
+        let cache = Rerere::open(dir.path());
+        let seen = conflict("let x = 1;\n", "let x = 2;\n");
+        assert_eq!(cache.lookup(&seen), None);
+
+        cache
+            .record(&seen, "let x = 3;\n")
+            .unwrap();
+        assert_eq!(
+            cache
+                .lookup(&seen)
+                .as_deref(),
+            Some("let x = 3;\n")
+        );
+
+        let swapped = conflict("let x = 2;\n", "let x = 1;\n");
+        assert_eq!(conflict_id(&swapped), conflict_id(&seen));
+        assert_eq!(
+            cache
+                .lookup(&swapped)
+                .as_deref(),
+            Some("let x = 3;\n")
+        );
+
+        assert_eq!(
+            cache.lookup(&conflict("let x = 1;\n", "let x = 4;\n")),
+            None
+        );
+        let pre = fs::read_to_string(
+            dir.path()
+                .join(".rup/rerere")
+                .join(conflict_id(&seen))
+                .join("preimage"),
+        )
+        .unwrap();
+        assert_eq!(pre, "<<<<<<<\nlet x = 1;\n=======\nlet x = 2;\n>>>>>>>\n");
+
+        cache
+            .forget(&swapped)
+            .unwrap();
+        assert_eq!(cache.lookup(&seen), None);
+        cache
+            .forget(&seen)
+            .unwrap();
+    }
+}
@@ -1,7 +1,7 @@
 //! Conflict resolution engine with deterministic SmartMerge strategies
 //!
 //! Implements ordered resolution pipeline:
-//! 1. Whitespace-only → normalized merge
+//! 1. Whitespace- or comment-only → keep one side, at any confidence
 //! 2. One side empty → take non-empty side
 //! 3. Superset → choose larger side deterministically
 //! 4. Disjoint insertions → stable concatenation (ours→theirs)
@@ -17,10 +17,17 @@ use anyhow::{Context, Result};
 use serde::{Deserialize, Serialize};
 
 use crate::{
-    cli::{AppContext, ResolveArgs},
+    cli::{AppContext, ColorMode, ResolveArgs},
     core::{
         backup::BackupManager,
-        conflict::{ConflictMarker, parse_conflicts},
+        conflict::{
+            ConflictMarker, ConflictOrigin, ConflictType, ParseDiagnostic, ScoreBreakdown,
+            TrivialDiff, parse_conflicts_with_diagnostics, score_breakdown, trivial_diff,
+        },
+        conflict_blame::{self, Attribution},
+        rerere::Rerere,
+        resolve_interactive::Session,
+        resolve_prompt::build_prompt,
     },
 };
 
@@ -34,12 +41,34 @@ pub enum ResolveStrategy
     TakeTheirs,
     /// Take base content (3-way conflicts only)
     TakeBase,
+    /// Keep both sides, ours first
+    Union,
     /// Interactive resolution via TUI
     Interactive,
     /// Smart auto-resolution with high confidence threshold
     Smart,
+    /// Merge whole items (functions, impls, classes) from both sides via
+    /// tree-sitter; falls back to Smart when both sides change one item
+    Semantic,
 }
 
+/// Side kept when the two sides differ only in whitespace or comments
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, clap::ValueEnum, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TrivialSide
+{
+    #[default]
+    Ours,
+    Theirs,
+}
+
+/// Confidence the Smart strategy requires before auto-applying
+const SMART_THRESHOLD: f32 = 0.95;
+
+/// Reasons for conflicts settled because the sides mean the same thing
+const WHITESPACE_ONLY: &str = "whitespace-only-resolved";
+const COMMENT_ONLY: &str = "comment-only-resolved";
+
 /// Result of a conflict resolution attempt
 #[derive(Debug, Clone)]
 pub struct Resolution
@@ -126,6 +155,17 @@ where
             }
         }
 
+        ResolveStrategy::Union =>
+        {
+            Ok(Resolution {
+                chosen: strategy,
+                resolved_text: Some(format!("{}{}", conflict.ours, conflict.theirs)),
+                auto_applied: true,
+                confidence: 1.0,
+                reason: "Explicit choice: keep both sides".to_string(),
+            })
+        }
+
         ResolveStrategy::Interactive =>
         {
             Ok(Resolution {
@@ -138,6 +178,24 @@ where
         }
 
         ResolveStrategy::Smart => resolve_smart(conflict, syntax_check),
+
+        ResolveStrategy::Semantic =>
+        {
+            match crate::core::semantic_merge::merge_items(conflict)
+            {
+                Some(merged) => finalize_resolution(
+                    Resolution {
+                        chosen: strategy,
+                        resolved_text: Some(merged),
+                        auto_applied: true,
+                        confidence: 1.0,
+                        reason: "semantic-items-merged".to_string(),
+                    },
+                    syntax_check,
+                ),
+                None => resolve_smart(conflict, syntax_check),
+            }
+        }
     }
 }
 
@@ -149,30 +207,32 @@ fn resolve_smart<F>(
 where
     F: Fn(&str) -> bool,
 {
+    // Sides that differ only in whitespace or comments are safe at any score
+    if let Some(resolution) = try_trivial_resolution(conflict, TrivialSide::Ours)
+    {
+        return finalize_resolution(resolution, syntax_check);
+    }
+
     // Enforce confidence threshold first
-    if conflict.confidence < 0.95
+    if conflict.confidence < SMART_THRESHOLD
     {
         return Ok(Resolution {
             chosen: ResolveStrategy::Interactive,
             resolved_text: None,
             auto_applied: false,
             confidence: conflict.confidence,
-            reason: format!("Confidence {} below 0.95 threshold", conflict.confidence),
+            reason: format!(
+                "Confidence {} below {SMART_THRESHOLD} threshold",
+                conflict.confidence
+            ),
         });
     }
 
     // Cache normalized forms once to avoid recomputation
-    let ours_ws = normalize_whitespace(&conflict.ours);
-    let theirs_ws = normalize_whitespace(&conflict.theirs);
     let ours_sup = normalize_for_superset(&conflict.ours);
     let theirs_sup = normalize_for_superset(&conflict.theirs);
 
     // Apply resolution pipeline in order
-    if let Some(resolution) = try_whitespace_only_resolution(conflict, &ours_ws, &theirs_ws)
-    {
-        return finalize_resolution(resolution, syntax_check);
-    }
-
     if let Some(resolution) = try_addition_only_resolution(conflict)
     {
         return finalize_resolution(resolution, syntax_check);
@@ -200,30 +260,31 @@ where
     })
 }
 
-/// Try resolution for whitespace-only differences
-fn try_whitespace_only_resolution(
+/// Resolve sides that differ only in whitespace or comments by keeping
+/// `side`; whitespace-only picks get trailing spaces trimmed
+pub fn try_trivial_resolution(
     conflict: &ConflictMarker,
-    ours_normalized: &str,
-    theirs_normalized: &str,
+    side: TrivialSide,
 ) -> Option<Resolution>
 {
-    if ours_normalized == theirs_normalized
+    let kind = trivial_diff(&conflict.file, &conflict.ours, &conflict.theirs)?;
+    let kept = match side
     {
-        // Content is identical after normalization - prefer ours with clean formatting
-        let clean_content = clean_whitespace_formatting(&conflict.ours);
-
-        Some(Resolution {
-            chosen: ResolveStrategy::Smart,
-            resolved_text: Some(clean_content),
-            auto_applied: true,
-            confidence: conflict.confidence,
-            reason: "whitespace-only-resolved".to_string(),
-        })
-    }
-    else
+        TrivialSide::Ours => &conflict.ours,
+        TrivialSide::Theirs => &conflict.theirs,
+    };
+    let (resolved_text, reason) = match kind
     {
-        None
-    }
+        TrivialDiff::Whitespace => (clean_whitespace_formatting(kept), WHITESPACE_ONLY),
+        TrivialDiff::Comments => (kept.clone(), COMMENT_ONLY),
+    };
+    Some(Resolution {
+        chosen: ResolveStrategy::Smart,
+        resolved_text: Some(resolved_text),
+        auto_applied: true,
+        confidence: 1.0,
+        reason: reason.to_string(),
+    })
 }
 
 /// Try resolution for addition-only changes (one side empty)
@@ -419,17 +480,6 @@ fn detect_eol(s: &str) -> &'static str
     if crlf > 0 && crlf >= lf { "\r\n" } else { "\n" }
 }
 
-/// Normalize whitespace for comparison (same as conflict.rs scoring)
-fn normalize_whitespace(content: &str) -> String
-{
-    content
-        .lines()
-        .map(|line| line.trim())
-        .filter(|line| !line.is_empty())
-        .collect::<Vec<_>>()
-        .join("\n")
-}
-
 /// Strip blank lines and syntactically empty braces-only lines for superset checking
 fn normalize_for_superset(s: &str) -> String
 {
@@ -444,11 +494,16 @@ fn normalize_for_superset(s: &str) -> String
 fn clean_whitespace_formatting(content: &str) -> String
 {
     let eol = detect_eol(content);
-    content
+    let mut cleaned = content
         .lines()
         .map(|line| line.trim_end()) // Trim trailing whitespace
         .collect::<Vec<_>>()
-        .join(eol) // Preserve native EOL
+        .join(eol); // Preserve native EOL
+    if content.ends_with('\n')
+    {
+        cleaned.push_str(eol);
+    }
+    cleaned
 }
 
 /// Convenience wrapper to avoid None::<fn> type hints at call sites
@@ -504,9 +559,25 @@ mod tests
             theirs: theirs.to_string(),
             base: base.map(|s| s.to_string()),
             confidence,
+            attribution: None,
         }
     }
 
+    #[test]
+    fn test_semantic_strategy_merges_items_or_falls_back()
+    {
+        let conflict = make_test_conflict("fn a() {}\n", "fn b() {}\n", None, 0.5);
+        let resolution = resolve_no_check(&conflict, ResolveStrategy::Semantic).unwrap();
+        assert_eq!(resolution.chosen, ResolveStrategy::Semantic);
+        assert_eq!(resolution.resolved_text.as_deref(), Some("fn a() {}\nfn b() {}\n"));
+
+        // Both sides changed the same function: SmartMerge's verdict stands
+        let conflict = make_test_conflict("fn a() { 1 }\n", "fn a() { 2 }\n", None, 0.5);
+        let resolution = resolve_no_check(&conflict, ResolveStrategy::Semantic).unwrap();
+        assert_eq!(resolution.chosen, ResolveStrategy::Interactive);
+        assert!(!resolution.auto_applied);
+    }
+
     #[test]
     fn test_explicit_strategies()
     {
@@ -606,6 +677,25 @@ mod tests
         );
     }
 
+    #[test]
+    fn test_comment_only_resolution_prefers_side()
+    {
+        let ours = "let x = 1; // counter\n";
+        let theirs = "// the counter\nlet x = 1;\n";
+        let conflict = make_test_conflict(ours, theirs, None, 0.3);
+
+        let resolution = resolve_no_check(&conflict, ResolveStrategy::Smart).unwrap();
+        assert!(resolution.auto_applied);
+        assert_eq!(resolution.reason, COMMENT_ONLY);
+        assert_eq!(resolution.resolved_text.as_deref(), Some(ours));
+
+        let resolution = try_trivial_resolution(&conflict, TrivialSide::Theirs).unwrap();
+        assert_eq!(resolution.resolved_text.as_deref(), Some(theirs));
+
+        let conflict = make_test_conflict("let x = 1;\n", "let x = 2; // two\n", None, 0.3);
+        assert!(try_trivial_resolution(&conflict, TrivialSide::Ours).is_none());
+    }
+
     #[test]
     fn test_addition_only_resolution()
     {
@@ -827,6 +917,9 @@ mod tests
     }
 }
 
+/// Reason given for resolutions replayed from `.rup/rerere`
+const REPLAYED: &str = "rerere: replayed recorded resolution";
+
 /// Stable strategy tag for JSON output
 fn strategy_tag(s: ResolveStrategy) -> &'static str
 {
@@ -835,8 +928,10 @@ fn strategy_tag(s: ResolveStrategy) -> &'static str
         ResolveStrategy::TakeOurs => "take-ours",
         ResolveStrategy::TakeTheirs => "take-theirs",
         ResolveStrategy::TakeBase => "take-base",
+        ResolveStrategy::Union => "union",
         ResolveStrategy::Interactive => "interactive",
         ResolveStrategy::Smart => "smart",
+        ResolveStrategy::Semantic => "semantic",
     }
 }
 
@@ -847,7 +942,13 @@ pub struct ConflictSummary
     pub file: PathBuf,
     pub total_conflicts: usize,
     pub auto_resolved: usize,
+    /// Auto-resolved conflicts whose sides differed only in whitespace or comments
+    #[serde(default)]
+    pub trivial_resolved: usize,
     pub interactive_required: usize,
+    /// Resolutions were written, then undone because --verify-cmd failed
+    #[serde(default)]
+    pub rolled_back: bool,
     pub resolutions: Vec<ResolutionSummary>,
 }
 
@@ -859,6 +960,123 @@ pub struct ResolutionSummary
     pub auto_applied: bool,
     pub confidence: f32,
     pub reason: String,
+    /// Conflict kind, e.g. `git-markers`
+    #[serde(default)]
+    pub conflict_type: String,
+    /// How the conflict was detected, e.g. `git-markers`
+    #[serde(default)]
+    pub origin: String,
+    /// Scoring factors behind the resolution, with `--explain`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub explain: Option<Explanation>,
+    /// Commits behind each side, from `git blame`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub attribution: Option<Attribution>,
+}
+
+/// Audit trail for one conflict's resolution (`resolve --explain`)
+#[derive(Serialize, Deserialize, Debug)]
+pub struct Explanation
+{
+    /// Marker labels (ours, theirs) for git conflicts
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub labels: Option<(String, String)>,
+    pub has_base: bool,
+    /// `whitespace` or `comments` when the sides differ only in those
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub trivial: Option<String>,
+    pub scores: ScoreBreakdown,
+    /// Confidence the smart strategy requires
+    pub threshold: f32,
+    /// Why this resolution was chosen, in order
+    pub factors: Vec<String>,
+}
+
+fn origin_tag(origin: ConflictOrigin) -> &'static str
+{
+    match origin
+    {
+        ConflictOrigin::GitMarkers => "git-markers",
+        ConflictOrigin::EditEngine => "edit-engine",
+    }
+}
+
+fn type_tag(kind: &ConflictType) -> &'static str
+{
+    match kind
+    {
+        ConflictType::GitMarkers { .. } => "git-markers",
+        ConflictType::PreimageMismatch { .. } => "preimage-mismatch",
+        ConflictType::OverlappingEdits { .. } => "overlapping-edits",
+        ConflictType::PathConflict { .. } => "path-conflict",
+    }
+}
+
+/// Scores and decision trail for `resolution` of `conflict`
+fn explain(
+    conflict: &ConflictMarker,
+    resolution: &Resolution,
+) -> Explanation
+{
+    let scores = score_breakdown(&conflict.ours, &conflict.theirs, conflict.base.as_deref());
+    let trivial = trivial_diff(&conflict.file, &conflict.ours, &conflict.theirs).map(|kind| {
+        match kind
+        {
+            TrivialDiff::Whitespace => "whitespace",
+            TrivialDiff::Comments => "comments",
+        }
+        .to_string()
+    });
+    let (labels, has_base) = match &conflict.conflict_type
+    {
+        ConflictType::GitMarkers { ours_meta, theirs_meta, has_base } =>
+        {
+            (Some((ours_meta.clone(), theirs_meta.clone())), *has_base)
+        }
+        _ => (None, conflict.base.is_some()),
+    };
+
+    let mut factors = Vec::new();
+    if let Some(kind) = &trivial
+    {
+        factors.push(format!("sides differ only in {kind}"));
+    }
+    for (name, weight) in [
+        ("whitespace-only", scores.whitespace_only),
+        ("addition-only", scores.addition_only),
+        ("balanced delimiters", scores.balanced_delimiters),
+        ("matches base", scores.base_lineage),
+    ]
+    {
+        if weight > 0.0
+        {
+            factors.push(format!("{name} +{weight:.2}"));
+        }
+    }
+    factors.push(format!(
+        "similarity {:.2} +{:.2}",
+        scores.similarity, scores.context_agreement
+    ));
+    factors.push(format!(
+        "confidence {:.2} {} threshold {SMART_THRESHOLD}",
+        conflict.confidence,
+        if conflict.confidence >= SMART_THRESHOLD { "meets" } else { "below" }
+    ));
+    factors.push(format!(
+        "{}: {}{}",
+        strategy_tag(resolution.chosen),
+        resolution.reason,
+        if resolution.auto_applied { "" } else { " (left for manual resolution)" }
+    ));
+
+    Explanation {
+        labels,
+        has_base,
+        trivial,
+        scores,
+        threshold: SMART_THRESHOLD,
+        factors,
+    }
 }
 
 /// CLI entry point for resolve command
@@ -869,6 +1087,7 @@ pub fn run(
 {
     let mut all_conflicts = Vec::new();
     let mut file_summaries = Vec::new();
+    let mut diagnostics = Vec::new();
 
     // Scan for conflicts in all specified paths
     for path in &args.paths
@@ -876,7 +1095,7 @@ pub fn run(
         if path.is_file()
         {
             // Single file
-            let mut conflicts = scan_file_for_conflicts(path)?;
+            let mut conflicts = scan_file_for_conflicts(path, &mut diagnostics)?;
             if !conflicts.is_empty()
             {
                 file_summaries.push(summarize_file_conflicts(path, &conflicts));
@@ -889,7 +1108,7 @@ pub fn run(
             let files = find_conflicted_files(path)?;
             for file in files
             {
-                let mut conflicts = scan_file_for_conflicts(&file)?;
+                let mut conflicts = scan_file_for_conflicts(&file, &mut diagnostics)?;
                 if !conflicts.is_empty()
                 {
                     file_summaries.push(summarize_file_conflicts(&file, &conflicts));
@@ -899,13 +1118,22 @@ pub fn run(
         }
     }
 
+    if !args.json
+    {
+        report_diagnostics(&diagnostics, ctx);
+    }
     if all_conflicts.is_empty()
     {
         if !ctx.quiet
         {
             if args.json
             {
-                println!("{{\"message\": \"No conflicts found\", \"files\": []}}");
+                let output = serde_json::json!({
+                    "message": "No conflicts found",
+                    "files": [],
+                    "diagnostics": diagnostics,
+                });
+                println!("{}", serde_json::to_string(&output)?);
             }
             else
             {
@@ -915,6 +1143,61 @@ pub fn run(
         return Ok(());
     }
 
+    // Recorded resolutions and backups live at the repo root
+    let cwd = std::env::current_dir().context("Failed to get current directory")?;
+    let discovered = crate::core::edit::discover_repo_root(
+        args.repo_root
+            .clone(),
+        &cwd,
+    )?;
+    let blame = !args.no_blame && discovered.is_some();
+    let root = discovered.unwrap_or_else(|| cwd.clone());
+    let rerere = (!args.no_rerere).then(|| Rerere::open(&root));
+    // Recorded resolutions stand in for the default strategy only
+    let strategy = args
+        .strategy
+        .unwrap_or(ResolveStrategy::Smart);
+    let replay = rerere
+        .as_ref()
+        .filter(|_| {
+            args.strategy
+                .is_none()
+        });
+    let trivial_side = match args.trivial_side
+    {
+        Some(side) => side,
+        None => crate::infra::config::load_config_in(&root)
+            .map(|cfg| {
+                cfg.resolve
+                    .trivial_side
+            })
+            .unwrap_or_default(),
+    };
+
+    // Picks are made at the terminal and written straight away
+    let mut session = args
+        .interactive
+        .then(|| {
+            let color = !ctx.no_color && std::io::IsTerminal::is_terminal(&std::io::stdout());
+            Session::new(std::io::stdin().lock(), std::io::stdout(), color)
+        });
+    let apply = args.apply || args.interactive;
+    let total = all_conflicts.len();
+    let mut seen = 0;
+    let mut backup: Option<BackupManager> = None;
+    let mut unresolved: Vec<ConflictMarker> = Vec::new();
+    let mut to_record: Vec<(ConflictMarker, String)> = Vec::new();
+    let mut replayed: Vec<ConflictMarker> = Vec::new();
+
+    if args.preview
+    {
+        return print_preview(&file_summaries, &all_conflicts, trivial_side, &args, ctx);
+    }
+    if blame
+    {
+        attribute_conflicts(&mut all_conflicts, &root, &cwd);
+    }
+
     // Resolve conflicts using specified strategy
     let mut resolved_files = Vec::new();
 
@@ -925,10 +1208,56 @@ pub fn run(
             .filter(|c| c.file == summary.file)
             .collect();
 
-        let resolutions = resolve_batch(&file_conflicts, args.strategy, None::<fn(&str) -> bool>)?;
+        // A recorded resolution for the same conflict wins over the default
+        let resolutions = file_conflicts
+            .iter()
+            .map(|conflict| {
+                seen += 1;
+                match replay.and_then(|r| r.lookup(conflict))
+                {
+                    Some(text) => Ok(Resolution {
+                        chosen: strategy,
+                        resolved_text: Some(text),
+                        auto_applied: true,
+                        confidence: 1.0,
+                        reason: REPLAYED.to_string(),
+                    }),
+                    None => match &mut session
+                    {
+                        Some(session) =>
+                        {
+                            let pick = session.pick(conflict, seen, total)?;
+                            let resolved_text = pick.text(conflict);
+                            Ok(Resolution {
+                                chosen: ResolveStrategy::Interactive,
+                                auto_applied: resolved_text.is_some(),
+                                resolved_text,
+                                confidence: 1.0,
+                                reason: pick
+                                    .reason()
+                                    .to_string(),
+                            })
+                        }
+                        None => match strategy
+                        {
+                            ResolveStrategy::Smart | ResolveStrategy::Semantic =>
+                            {
+                                match try_trivial_resolution(conflict, trivial_side)
+                                {
+                                    Some(resolution) => Ok(resolution),
+                                    None => resolve_no_check(conflict, strategy),
+                                }
+                            }
+                            _ => resolve_no_check(conflict, strategy),
+                        },
+                    },
+                }
+            })
+            .collect::<Result<Vec<_>>>()?;
 
         // Process resolutions
         let mut auto_resolved = 0;
+        let mut trivial_resolved = 0;
         let mut interactive_required = 0;
         let mut resolution_summaries = Vec::new();
 
@@ -939,6 +1268,10 @@ pub fn run(
             if resolution.auto_applied
             {
                 auto_resolved += 1;
+                if [WHITESPACE_ONLY, COMMENT_ONLY].contains(&resolution.reason.as_str())
+                {
+                    trivial_resolved += 1;
+                }
             }
             else
             {
@@ -953,26 +1286,186 @@ pub fn run(
                 reason: resolution
                     .reason
                     .clone(),
+                conflict_type: type_tag(&conflict.conflict_type).to_string(),
+                origin: origin_tag(conflict.origin).to_string(),
+                explain: args
+                    .explain
+                    .then(|| explain(conflict, resolution)),
+                attribution: conflict
+                    .attribution
+                    .clone(),
             });
         }
 
         summary.auto_resolved = auto_resolved;
+        summary.trivial_resolved = trivial_resolved;
         summary.interactive_required = interactive_required;
         summary.resolutions = resolution_summaries;
 
         // Apply resolved changes if requested
-        if args.apply && auto_resolved > 0
+        if apply && auto_resolved > 0
         {
-            // TODO: Create BackupManager when backup is requested
-            // For now, pass None - will integrate with centralized backup system
-            apply_resolutions_to_file(&summary.file, &file_conflicts, &resolutions, None)?;
+            // One backup session covers every file this run rewrites
+            if (args.backup
+                || args
+                    .verify_cmd
+                    .is_some())
+                && backup.is_none()
+            {
+                let mut mgr = BackupManager::begin(&root, "resolve")?;
+                mgr.set_operation("resolve");
+                backup = Some(mgr);
+            }
+            let rel = repo_relative(&summary.file, &root, &cwd)?;
+            apply_resolutions_to_file(
+                &summary.file,
+                &rel,
+                &file_conflicts,
+                &resolutions,
+                backup.as_mut(),
+            )?;
+            // Recorded once the file survives --verify-cmd
+            if rerere.is_some()
+            {
+                for (conflict, resolution) in file_conflicts
+                    .iter()
+                    .zip(&resolutions)
+                {
+                    if resolution.reason == REPLAYED
+                    {
+                        replayed.push((*conflict).clone());
+                    }
+                    else if let Some(text) = &resolution.resolved_text
+                    {
+                        to_record.push(((*conflict).clone(), text.clone()));
+                    }
+                }
+            }
+            if let Some(agent) = &ctx.agent
+            {
+                agent.artifact("modified", &summary.file);
+            }
             resolved_files.push(
                 summary
                     .file
                     .clone(),
             );
         }
+
+        // Rescan written files so the prompt quotes current line numbers
+        if args
+            .emit_prompt
+            .is_some()
+            && interactive_required > 0
+        {
+            if resolved_files.contains(&summary.file)
+            {
+                let mut rescanned = scan_file_for_conflicts(&summary.file, &mut Vec::new())?;
+                if blame
+                {
+                    attribute_conflicts(&mut rescanned, &root, &cwd);
+                }
+                unresolved.append(&mut rescanned);
+            }
+            else
+            {
+                unresolved.extend(
+                    file_conflicts
+                        .iter()
+                        .zip(&resolutions)
+                        .filter(|(_, r)| !r.auto_applied)
+                        .map(|(c, _)| (*c).clone()),
+                );
+            }
+        }
+    }
+
+    if let Some(backup) = &mut backup
+    {
+        backup.finalize(true)?;
+    }
+
+    // Files the verification command blames go back to their conflicted state
+    let mut verify = None;
+    if let Some(command) = &args.verify_cmd
+        && let Some(backup) = &backup
+        && !resolved_files.is_empty()
+    {
+        let (report, rolled_back) =
+            verify_resolutions(command, &root, &cwd, backup.session_id(), &resolved_files, ctx)?;
+        for file in &rolled_back
+        {
+            resolved_files.retain(|f| f != file);
+            to_record.retain(|(c, _)| &c.file != file);
+            // A replay that failed verification must not replay again
+            if let Some(rerere) = &rerere
+            {
+                for conflict in replayed
+                    .iter()
+                    .filter(|c| &c.file == file)
+                {
+                    rerere.forget(conflict)?;
+                }
+            }
+            if let Some(summary) = file_summaries
+                .iter_mut()
+                .find(|s| &s.file == file)
+            {
+                mark_rolled_back(summary);
+            }
+            if args
+                .emit_prompt
+                .is_some()
+            {
+                unresolved.retain(|c| &c.file != file);
+                let mut rescanned = scan_file_for_conflicts(file, &mut Vec::new())?;
+                if blame
+                {
+                    attribute_conflicts(&mut rescanned, &root, &cwd);
+                }
+                unresolved.append(&mut rescanned);
+            }
+        }
+        verify = Some(report);
     }
+    if let Some(rerere) = &rerere
+    {
+        for (conflict, text) in &to_record
+        {
+            rerere.record(conflict, text)?;
+        }
+    }
+
+    let prompt = match &args.emit_prompt
+    {
+        Some(path) if !unresolved.is_empty() =>
+        {
+            let refs: Vec<&ConflictMarker> = unresolved
+                .iter()
+                .collect();
+            let bundle = build_prompt(
+                &refs,
+                args.budget,
+                &args.model,
+                args.tokenizer_file
+                    .as_deref(),
+            )?;
+            fs::write(path, &bundle.text)
+                .with_context(|| format!("Failed to write prompt: {}", path.display()))?;
+            if let Some(agent) = &ctx.agent
+            {
+                agent.artifact("output", path);
+            }
+            Some(serde_json::json!({
+                "path": path,
+                "conflicts": bundle.included,
+                "omitted": bundle.omitted,
+                "context_lines": bundle.context_lines,
+                "tokens": bundle.tokens,
+            }))
+        }
+        _ => None,
+    };
 
     // Output results
     if args.json
@@ -985,20 +1478,48 @@ pub fn run(
             "total_files": file_summaries.len(),
             "resolved_files": resolved_files,
             "exit_code": if has_unresolved { 2 } else { 0 },
-            "files": file_summaries
+            "files": file_summaries,
+            "diagnostics": diagnostics,
+            "prompt": prompt,
+            "verify": verify,
         });
         println!("{}", serde_json::to_string(&output)?);
     }
     else
     {
         print_resolution_summary(&file_summaries, &resolved_files, ctx);
+        if let Some(prompt) = &prompt
+            && !ctx.quiet
+        {
+            println!(
+                "Wrote prompt for {} conflict(s) to {}{}",
+                prompt["conflicts"],
+                args.emit_prompt
+                    .as_deref()
+                    .unwrap_or(Path::new(""))
+                    .display(),
+                match prompt["omitted"].as_u64()
+                {
+                    Some(0) | None => String::new(),
+                    Some(n) => format!(" ({n} left out to fit --budget)"),
+                }
+            );
+            println!("  Paste it to a model, then run `rup apply` on the reply");
+        }
     }
 
     // Exit code semantics following apply command pattern
     let has_unresolved = file_summaries
         .iter()
         .any(|s| s.interactive_required > 0);
-    if has_unresolved && !args.json
+    if has_unresolved
+        && let Some(agent) = &ctx.agent
+    {
+        // Agent mode reports through the envelope instead of exiting here
+        agent.warn("conflicts remain that require manual resolution");
+        agent.exit_code(2);
+    }
+    else if has_unresolved && !args.json
     {
         std::process::exit(2); // Exit code 2 = conflicts remaining
     }
@@ -1006,13 +1527,39 @@ pub fn run(
     Ok(())
 }
 
-/// Scan a single file for Git conflict markers
-fn scan_file_for_conflicts(file: &PathBuf) -> Result<Vec<ConflictMarker>>
+/// Scan a single file for Git conflict markers, collecting diagnostics for
+/// malformed blocks
+fn scan_file_for_conflicts(
+    file: &PathBuf,
+    diagnostics: &mut Vec<ParseDiagnostic>,
+) -> Result<Vec<ConflictMarker>>
 {
     let content =
         fs::File::open(file).with_context(|| format!("Failed to open file: {}", file.display()))?;
     let reader = std::io::BufReader::new(content);
-    parse_conflicts(file.clone(), reader)
+    let (conflicts, mut found) = parse_conflicts_with_diagnostics(file.clone(), reader)?;
+    diagnostics.append(&mut found);
+    Ok(conflicts)
+}
+
+/// Warn about conflict blocks that were skipped or parsed with recovery
+fn report_diagnostics(
+    diagnostics: &[ParseDiagnostic],
+    ctx: &AppContext,
+)
+{
+    for d in diagnostics
+    {
+        let message = format!("{}:{}: {}", d.file.display(), d.line, d.message);
+        if let Some(agent) = &ctx.agent
+        {
+            agent.warn(&message);
+        }
+        else if !ctx.quiet
+        {
+            eprintln!("warning: {message}");
+        }
+    }
 }
 
 /// Fast byte-safe check for conflict markers without full parsing
@@ -1124,14 +1671,174 @@ fn summarize_file_conflicts(
         file: file.to_path_buf(),
         total_conflicts: conflicts.len(),
         auto_resolved: 0,        // Will be filled by caller
+        trivial_resolved: 0,     // Will be filled by caller
         interactive_required: 0, // Will be filled by caller
+        rolled_back: false,
         resolutions: Vec::new(), // Will be filled by caller
     }
 }
 
+/// `file` relative to `root`, as backup sessions record it
+/// Run `command` in `root` after resolutions were written. On failure, the
+/// written files its output mentions (all of them when it names none) are
+/// restored from the backup session; returns the JSON report and those files.
+fn verify_resolutions(
+    command: &str,
+    root: &Path,
+    cwd: &Path,
+    session_id: &str,
+    written: &[PathBuf],
+    ctx: &AppContext,
+) -> Result<(serde_json::Value, Vec<PathBuf>)>
+{
+    if !ctx.quiet
+    {
+        eprintln!("Verifying resolutions: running `{command}`");
+    }
+    let output = crate::core::sandbox::run_verify_command(command, root)?;
+    if output
+        .status
+        .success()
+    {
+        return Ok((
+            serde_json::json!({ "command": command, "success": true, "rolled_back": [] }),
+            Vec::new(),
+        ));
+    }
+
+    let combined = format!(
+        "{}{}",
+        String::from_utf8_lossy(&output.stdout),
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let mut failing = Vec::new();
+    for file in written
+    {
+        let rel = repo_relative(file, root, cwd)?;
+        let mentioned = [rel.as_path(), file.as_path()]
+            .iter()
+            .any(|p| combined.contains(&*p.to_string_lossy()));
+        failing.push((file.clone(), rel, mentioned));
+    }
+    if failing
+        .iter()
+        .any(|(_, _, mentioned)| *mentioned)
+    {
+        failing.retain(|(_, _, mentioned)| *mentioned);
+    }
+
+    let mut rolled_back = Vec::new();
+    for (file, rel, _) in failing
+    {
+        crate::core::backup_ops::restore_session(
+            root,
+            crate::core::backup_ops::RestoreRequest {
+                backup_current: false,
+                dry_run: false,
+                force: true,
+                path: Some(rel),
+                session_id: session_id.to_string(),
+                show_diff: false,
+                verify_checksum: false,
+            },
+        )
+        .with_context(|| format!("Failed to roll back {}", file.display()))?;
+        rolled_back.push(file);
+    }
+
+    if !ctx.quiet
+    {
+        // Last lines of the output are usually the ones that matter
+        let lines: Vec<&str> = combined
+            .lines()
+            .collect();
+        eprintln!(
+            "Verification `{command}` failed ({}); rolled back {} file(s):",
+            output.status,
+            rolled_back.len()
+        );
+        for file in &rolled_back
+        {
+            eprintln!("  {}", file.display());
+        }
+        eprintln!(
+            "{}",
+            lines[lines
+                .len()
+                .saturating_sub(20)..]
+                .join("\n")
+        );
+    }
+    if let Some(agent) = &ctx.agent
+    {
+        agent.warn(format!(
+            "verification `{command}` failed; rolled back {} file(s)",
+            rolled_back.len()
+        ));
+    }
+    let report = serde_json::json!({
+        "command": command,
+        "success": false,
+        "status": output.status.code(),
+        "rolled_back": rolled_back,
+    });
+    Ok((report, rolled_back))
+}
+
+/// Count a rolled-back file's conflicts as unresolved again
+fn mark_rolled_back(summary: &mut ConflictSummary)
+{
+    summary.rolled_back = true;
+    summary.interactive_required = summary.total_conflicts;
+    summary.auto_resolved = 0;
+    summary.trivial_resolved = 0;
+    for resolution in &mut summary.resolutions
+    {
+        if resolution.auto_applied
+        {
+            resolution.auto_applied = false;
+            resolution
+                .reason
+                .push_str(" (rolled back: verification failed)");
+        }
+    }
+}
+
+/// Attach `git blame` attribution to each run of conflicts from one file
+fn attribute_conflicts(
+    conflicts: &mut [ConflictMarker],
+    root: &Path,
+    cwd: &Path,
+)
+{
+    for group in conflicts.chunk_by_mut(|a, b| a.file == b.file)
+    {
+        if let Ok(rel) = repo_relative(&group[0].file, root, cwd)
+        {
+            conflict_blame::attribute(root, &rel, group);
+        }
+    }
+}
+
+fn repo_relative(
+    file: &Path,
+    root: &Path,
+    cwd: &Path,
+) -> Result<PathBuf>
+{
+    let abs = dunce::canonicalize(cwd.join(file))
+        .with_context(|| format!("Failed to resolve path: {}", file.display()))?;
+    let root = dunce::canonicalize(root)
+        .with_context(|| format!("Failed to resolve repo root: {}", root.display()))?;
+    abs.strip_prefix(&root)
+        .map(Path::to_path_buf)
+        .with_context(|| format!("{} is outside the repository root", file.display()))
+}
+
 /// Apply resolved conflicts to file with BackupManager integration and syntax validation
 fn apply_resolutions_to_file(
     file: &PathBuf,
+    rel: &Path,
     conflicts: &[&ConflictMarker],
     resolutions: &[Resolution],
     backup_manager: Option<&mut BackupManager>,
@@ -1145,14 +1852,43 @@ fn apply_resolutions_to_file(
     if let Some(backup) = backup_manager
     {
         backup
-            .backup_file(file)
+            .backup_file(rel)
             .with_context(|| format!("Failed to create backup for: {}", file.display()))?;
     }
 
+    let texts: Vec<Option<&str>> = resolutions
+        .iter()
+        .map(|r| r.resolved_text.as_deref())
+        .collect();
+    splice_resolutions(&mut original, conflicts, &texts)
+        .with_context(|| format!("Failed to resolve {}", file.display()))?;
+
+    // Optional syntax validation before write (can be added later)
+    // if let Some(syntax_validator) = syntax_validator {
+    //     if !syntax_validator(&original) {
+    //         anyhow::bail!("Syntax validation failed for {}", file.display());
+    //     }
+    // }
+
+    // Atomic write back preserving all original encoding outside edited ranges
+    crate::core::edit::write_atomic(file, &original)
+        .with_context(|| format!("Failed to write resolved file: {}", file.display()))?;
+
+    Ok(())
+}
+
+/// Replace each conflict's bytes in `content` with its resolved text;
+/// conflicts without one keep their markers
+fn splice_resolutions(
+    content: &mut Vec<u8>,
+    conflicts: &[&ConflictMarker],
+    texts: &[Option<&str>],
+) -> Result<()>
+{
     // Build descending list by start offset to preserve indices during replacement
     let mut pairs: Vec<_> = conflicts
         .iter()
-        .zip(resolutions.iter())
+        .zip(texts)
         .collect();
     pairs.sort_by_key(|(c, _)| {
         std::cmp::Reverse(
@@ -1162,44 +1898,170 @@ fn apply_resolutions_to_file(
     });
 
     // Apply edits from right to left on bytes to preserve offsets
-    for (conflict, resolution) in pairs
+    for (conflict, text) in pairs
     {
-        if let Some(ref resolved_text) = resolution.resolved_text
+        if let Some(resolved_text) = text
         {
             let (start, end) = conflict.byte_range;
-            let replacement_bytes = resolved_text.as_bytes(); // UTF-8 resolved text to bytes
 
             // Validate bounds to prevent panic
-            if start > end || end > original.len()
+            if start > end || end > content.len()
             {
-                anyhow::bail!(
-                    "Invalid byte range {:?} for {}",
-                    conflict.byte_range,
-                    file.display()
-                );
+                anyhow::bail!("Invalid byte range {:?}", conflict.byte_range);
             }
 
             // Replace in-place using Vec<u8> splice - safe for any byte content
-            original.splice(
+            content.splice(
                 start..end,
-                replacement_bytes
-                    .iter()
-                    .copied(),
+                resolved_text
+                    .bytes(),
             );
         }
     }
+    Ok(())
+}
 
-    // Optional syntax validation before write (can be added later)
-    // if let Some(syntax_validator) = syntax_validator {
-    //     if !syntax_validator(&original) {
-    //         anyhow::bail!("Syntax validation failed for {}", file.display());
-    //     }
-    // }
+/// Strategies `--preview` compares, in display order
+const PREVIEW_STRATEGIES: [ResolveStrategy; 4] = [
+    ResolveStrategy::TakeOurs,
+    ResolveStrategy::TakeTheirs,
+    ResolveStrategy::Union,
+    ResolveStrategy::Smart,
+];
+
+/// What `strategy` would put in place of `conflict`; `None` when it leaves
+/// the conflict for a human
+fn preview_text(
+    strategy: ResolveStrategy,
+    conflict: &ConflictMarker,
+    trivial_side: TrivialSide,
+) -> Option<String>
+{
+    let trivial = match strategy
+    {
+        ResolveStrategy::Smart | ResolveStrategy::Semantic =>
+        {
+            try_trivial_resolution(conflict, trivial_side)
+        }
+        _ => None,
+    };
+    trivial
+        .or_else(|| resolve_no_check(conflict, strategy).ok())
+        .filter(|r| r.auto_applied)
+        .and_then(|r| r.resolved_text)
+}
 
-    // Atomic write back preserving all original encoding outside edited ranges
-    fs::write(file, &original)
-        .with_context(|| format!("Failed to write resolved file: {}", file.display()))?;
+/// One file under one strategy, for `--preview`
+#[derive(Serialize, Debug)]
+struct PreviewEntry
+{
+    file: PathBuf,
+    strategy: &'static str,
+    resolved: usize,
+    unresolved: usize,
+    diff: String,
+}
+
+/// Show what each preview strategy would make of every conflicted file,
+/// as unified diffs against the file on disk; nothing is written
+fn print_preview(
+    summaries: &[ConflictSummary],
+    conflicts: &[ConflictMarker],
+    trivial_side: TrivialSide,
+    args: &ResolveArgs,
+    ctx: &AppContext,
+) -> Result<()>
+{
+    let mut entries = Vec::new();
+    for summary in summaries
+    {
+        let file_conflicts: Vec<&ConflictMarker> = conflicts
+            .iter()
+            .filter(|c| c.file == summary.file)
+            .collect();
+        let original = fs::read(&summary.file)
+            .with_context(|| format!("Failed to read file: {}", summary.file.display()))?;
+        let old = String::from_utf8_lossy(&original);
+        for strategy in PREVIEW_STRATEGIES
+        {
+            let texts: Vec<Option<String>> = file_conflicts
+                .iter()
+                .map(|c| preview_text(strategy, c, trivial_side))
+                .collect();
+            let refs: Vec<Option<&str>> = texts
+                .iter()
+                .map(Option::as_deref)
+                .collect();
+            let mut content = original.clone();
+            splice_resolutions(&mut content, &file_conflicts, &refs)?;
+            let new = String::from_utf8_lossy(&content);
+            let name = summary
+                .file
+                .display()
+                .to_string();
+            let diff = similar::TextDiff::from_lines(old.as_ref(), new.as_ref())
+                .unified_diff()
+                .context_radius(3)
+                .header(&format!("a/{name}"), &format!("b/{name}"))
+                .to_string();
+            let resolved = texts
+                .iter()
+                .filter(|t| t.is_some())
+                .count();
+            entries.push(PreviewEntry {
+                file: summary
+                    .file
+                    .clone(),
+                strategy: strategy_tag(strategy),
+                resolved,
+                unresolved: texts.len() - resolved,
+                diff,
+            });
+        }
+    }
 
+    if args.json
+    {
+        let output = serde_json::json!({
+            "schema_version": "1",
+            "preview": entries,
+        });
+        println!("{}", serde_json::to_string(&output)?);
+        return Ok(());
+    }
+    if ctx.quiet
+    {
+        return Ok(());
+    }
+    let color = !ctx.no_color
+        && match args.color
+        {
+            ColorMode::Always => true,
+            ColorMode::Never => false,
+            ColorMode::Auto => std::io::IsTerminal::is_terminal(&std::io::stdout()),
+        };
+    for entry in &entries
+    {
+        println!(
+            "== {} [{}]: {} resolved, {} left as conflicts ==",
+            entry
+                .file
+                .display(),
+            entry.strategy,
+            entry.resolved,
+            entry.unresolved
+        );
+        if color
+        {
+            print!("{}", crate::core::diff_color::render_colored_diff(&entry.diff));
+        }
+        else
+        {
+            print!("{}", entry.diff);
+        }
+        println!();
+    }
+    println!("Preview only: no files were changed. Pick one with --strategy and --apply.");
     Ok(())
 }
 
@@ -1223,6 +2085,10 @@ fn print_resolution_summary(
         .iter()
         .map(|s| s.auto_resolved)
         .sum();
+    let total_trivial: usize = summaries
+        .iter()
+        .map(|s| s.trivial_resolved)
+        .sum();
     let total_interactive: usize = summaries
         .iter()
         .map(|s| s.interactive_required)
@@ -1232,6 +2098,7 @@ fn print_resolution_summary(
     println!("  Files with conflicts: {}", summaries.len());
     println!("  Total conflicts: {}", total_conflicts);
     println!("  Auto-resolved: {}", total_resolved);
+    println!("    Whitespace/comment-only: {}", total_trivial);
     println!("  Interactive required: {}", total_interactive);
     println!();
 
@@ -1248,10 +2115,50 @@ fn print_resolution_summary(
             summary.total_conflicts, summary.auto_resolved, summary.interactive_required
         );
 
+        for resolution in &summary.resolutions
+        {
+            if resolution
+                .explain
+                .is_none()
+                && resolution
+                    .attribution
+                    .is_none()
+            {
+                continue;
+            }
+            let (start, end) = resolution.line_range;
+            println!(
+                "  Lines {start}-{end} ({}, confidence {:.2}):",
+                resolution.conflict_type, resolution.confidence
+            );
+            if let Some(explain) = &resolution.explain
+            {
+                for factor in &explain.factors
+                {
+                    println!("    - {factor}");
+                }
+            }
+            if let Some(attribution) = &resolution.attribution
+            {
+                for commit in &attribution.ours
+                {
+                    println!("    ours:   {commit}");
+                }
+                for commit in &attribution.theirs
+                {
+                    println!("    theirs: {commit}");
+                }
+            }
+        }
+
         if resolved_files.contains(&summary.file)
         {
             println!("  Applied resolutions to file");
         }
+        else if summary.rolled_back
+        {
+            println!("  Rolled back: verification failed, conflicts left in place");
+        }
         println!();
     }
 
@@ -1269,6 +2176,6 @@ fn print_resolution_summary(
             "{} conflicts require interactive resolution",
             total_interactive
         );
-        println!("  Run with --strategy=interactive for manual resolution");
+        println!("  Run with --interactive to resolve them one by one");
     }
 }
@@ -0,0 +1,494 @@
+//! Terminal prompt behind `rup resolve --interactive`
+//!
+//! Each conflict is drawn as side-by-side ours/theirs/base panes, followed
+//! by a one-key prompt: take a side, keep both, type the resolution inline,
+//! or skip. Quitting (or closing stdin) skips everything still pending, so
+//! picks made so far are still written.
+
+use std::io::{BufRead, Write};
+
+use anyhow::Result;
+use owo_colors::OwoColorize;
+
+use crate::core::conflict::ConflictMarker;
+
+/// Pane width used when `COLUMNS` is unset or unparsable
+const DEFAULT_WIDTH: usize = 100;
+
+/// Gap between two panes
+const GUTTER: &str = " │ ";
+
+/// Keys accepted at the prompt; `u` keeps both sides (ours, then theirs)
+const HELP: &str = "[o]urs  [t]heirs  [b]ase  [u]nion  [e]dit  [s]kip  [q]uit  [?]redraw";
+
+/// What the user chose for one conflict
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Pick
+{
+    Ours,
+    Theirs,
+    Base,
+    /// Ours followed by theirs
+    Both,
+    /// Text typed at the prompt
+    Edited(String),
+    Skip,
+}
+
+impl Pick
+{
+    /// Replacement text for `conflict`, or `None` when skipped
+    pub fn text(
+        &self,
+        conflict: &ConflictMarker,
+    ) -> Option<String>
+    {
+        match self
+        {
+            Pick::Ours =>
+            {
+                Some(
+                    conflict
+                        .ours
+                        .clone(),
+                )
+            }
+            Pick::Theirs =>
+            {
+                Some(
+                    conflict
+                        .theirs
+                        .clone(),
+                )
+            }
+            Pick::Base =>
+            {
+                conflict
+                    .base
+                    .clone()
+            }
+            Pick::Both => Some(format!("{}{}", conflict.ours, conflict.theirs)),
+            Pick::Edited(text) => Some(text.clone()),
+            Pick::Skip => None,
+        }
+    }
+
+    /// Reason recorded in the resolve summary
+    pub fn reason(&self) -> &'static str
+    {
+        match self
+        {
+            Pick::Ours => "interactive: took ours",
+            Pick::Theirs => "interactive: took theirs",
+            Pick::Base => "interactive: took base",
+            Pick::Both => "interactive: kept both sides",
+            Pick::Edited(_) => "interactive: edited inline",
+            Pick::Skip => "interactive: skipped",
+        }
+    }
+}
+
+/// One prompt session over `input`/`output` (stdin/stdout in the CLI)
+pub struct Session<R, W>
+{
+    input: R,
+    output: W,
+    color: bool,
+    width: usize,
+    quit: bool,
+}
+
+impl<R: BufRead, W: Write> Session<R, W>
+{
+    pub fn new(
+        input: R,
+        output: W,
+        color: bool,
+    ) -> Self
+    {
+        let width = std::env::var("COLUMNS")
+            .ok()
+            .and_then(|c| {
+                c.parse()
+                    .ok()
+            })
+            .unwrap_or(DEFAULT_WIDTH);
+        Self { input, output, color, width, quit: false }
+    }
+
+    /// Show `conflict` (number `index` of `total`) and read the user's pick.
+    /// After a quit every later conflict is skipped without prompting.
+    pub fn pick(
+        &mut self,
+        conflict: &ConflictMarker,
+        index: usize,
+        total: usize,
+    ) -> Result<Pick>
+    {
+        if self.quit
+        {
+            return Ok(Pick::Skip);
+        }
+        self.draw(conflict, index, total)?;
+        loop
+        {
+            write!(self.output, "{HELP}\n> ")?;
+            self.output
+                .flush()?;
+            let Some(line) = self.read_line()?
+            else
+            {
+                self.quit = true;
+                return Ok(Pick::Skip);
+            };
+            let pick = match line.trim()
+            {
+                "o" => Pick::Ours,
+                "t" => Pick::Theirs,
+                "b" if conflict
+                    .base
+                    .is_some() =>
+                {
+                    Pick::Base
+                }
+                "b" =>
+                {
+                    writeln!(self.output, "No base section in this conflict")?;
+                    continue;
+                }
+                "u" => Pick::Both,
+                "e" => Pick::Edited(self.read_edit()?),
+                "s" => Pick::Skip,
+                "q" =>
+                {
+                    self.quit = true;
+                    Pick::Skip
+                }
+                "?" =>
+                {
+                    self.draw(conflict, index, total)?;
+                    continue;
+                }
+                _ => continue,
+            };
+            return Ok(pick);
+        }
+    }
+
+    fn read_line(&mut self) -> Result<Option<String>>
+    {
+        let mut line = String::new();
+        if self
+            .input
+            .read_line(&mut line)?
+            == 0
+        {
+            return Ok(None);
+        }
+        Ok(Some(line))
+    }
+
+    /// Lines up to a lone `.`; the resolution keeps their newlines
+    fn read_edit(&mut self) -> Result<String>
+    {
+        writeln!(
+            self.output,
+            "Type the resolution; end with a line containing only '.'"
+        )?;
+        self.output
+            .flush()?;
+        let mut text = String::new();
+        while let Some(line) = self.read_line()?
+        {
+            if line.trim_end_matches(['\r', '\n']) == "."
+            {
+                break;
+            }
+            text.push_str(&line);
+        }
+        Ok(text)
+    }
+
+    fn draw(
+        &mut self,
+        conflict: &ConflictMarker,
+        index: usize,
+        total: usize,
+    ) -> Result<()>
+    {
+        let mut panes = vec![
+            (
+                "ours",
+                conflict
+                    .ours
+                    .as_str(),
+            ),
+            (
+                "theirs",
+                conflict
+                    .theirs
+                    .as_str(),
+            ),
+        ];
+        if let Some(base) = &conflict.base
+        {
+            panes.push(("base", base));
+        }
+
+        let header = format!(
+            "{}:{}-{} (conflict {index}/{total})",
+            conflict
+                .file
+                .display(),
+            conflict
+                .line_range
+                .0,
+            conflict
+                .line_range
+                .1
+        );
+        writeln!(self.output)?;
+        if self.color
+        {
+            writeln!(self.output, "{}", header.bold())?;
+        }
+        else
+        {
+            writeln!(self.output, "{header}")?;
+        }
+        for line in render_panes(&panes, self.width, self.color)
+        {
+            writeln!(self.output, "{line}")?;
+        }
+        Ok(())
+    }
+}
+
+/// Lay `panes` (title, text) out side by side within `width` columns,
+/// truncating long lines with `…`
+fn render_panes(
+    panes: &[(&str, &str)],
+    width: usize,
+    color: bool,
+) -> Vec<String>
+{
+    let gutters = GUTTER
+        .chars()
+        .count()
+        * panes
+            .len()
+            .saturating_sub(1);
+    let col = (width.saturating_sub(gutters)
+        / panes
+            .len()
+            .max(1))
+    .max(8);
+    let bodies: Vec<Vec<String>> = panes
+        .iter()
+        .map(|(_, text)| {
+            text.lines()
+                .map(|line| fit(&line.replace('\t', "    "), col))
+                .collect()
+        })
+        .collect();
+    let height = bodies
+        .iter()
+        .map(Vec::len)
+        .max()
+        .unwrap_or(0);
+
+    let mut rows = Vec::with_capacity(height + 2);
+    let titles: Vec<String> = panes
+        .iter()
+        .map(|(title, _)| {
+            let cell = fit(title, col);
+            if !color
+            {
+                return cell;
+            }
+            match *title
+            {
+                "ours" =>
+                {
+                    cell.green()
+                        .to_string()
+                }
+                "theirs" =>
+                {
+                    cell.blue()
+                        .to_string()
+                }
+                _ =>
+                {
+                    cell.yellow()
+                        .to_string()
+                }
+            }
+        })
+        .collect();
+    rows.push(
+        titles
+            .join(GUTTER)
+            .trim_end()
+            .to_string(),
+    );
+    rows.push(vec!["─".repeat(col); panes.len()].join("─┼─"));
+    for i in 0..height
+    {
+        let cells: Vec<String> = bodies
+            .iter()
+            .map(|body| {
+                body.get(i)
+                    .cloned()
+                    .unwrap_or_else(|| " ".repeat(col))
+            })
+            .collect();
+        rows.push(
+            cells
+                .join(GUTTER)
+                .trim_end()
+                .to_string(),
+        );
+    }
+    rows
+}
+
+/// `text` cut or padded to exactly `width` characters
+fn fit(
+    text: &str,
+    width: usize,
+) -> String
+{
+    let len = text
+        .chars()
+        .count();
+    if len > width
+    {
+        let cut: String = text
+            .chars()
+            .take(width.saturating_sub(1))
+            .collect();
+        format!("{cut}…")
+    }
+    else
+    {
+        format!("{text}{}", " ".repeat(width - len))
+    }
+}
+
+#[cfg(test)]
+mod tests
+{
+    use std::{io::Cursor, path::PathBuf};
+
+    use super::*;
+    use crate::core::conflict::{ConflictOrigin, ConflictType};
+
+    fn conflict(base: Option<&str>) -> ConflictMarker
+    {
+        ConflictMarker {
+            file: PathBuf::from("a.rs"),
+            origin: ConflictOrigin::GitMarkers,
+            conflict_type: ConflictType::GitMarkers {
+                ours_meta: "HEAD".into(),
+                theirs_meta: "topic".into(),
+                has_base: base.is_some(),
+            },
+            byte_range: (0, 0),
+            line_range: (2, 6),
+            ours: "run(1);\n".into(),
+            theirs: "run(2);\n".into(),
+            base: base.map(Into::into),
+            confidence: 0.0,
+            attribution: None,
+        }
+    }
+
+    fn session(input: &str) -> Session<Cursor<Vec<u8>>, Vec<u8>>
+    {
+        let mut session = Session::new(
+            Cursor::new(
+                input
+                    .as_bytes()
+                    .to_vec(),
+            ),
+            Vec::new(),
+            false,
+        );
+        session.width = 40;
+        session
+    }
+
+    #[test]
+    fn test_picks_sides_and_edits()
+    {
+        let c = conflict(None);
+        let mut s = session("x\nb\nt\nu\ne\nrun(3);\nrun(4);\n.\n");
+        assert_eq!(
+            s.pick(&c, 1, 3)
+                .unwrap(),
+            Pick::Theirs
+        );
+        assert_eq!(
+            s.pick(&c, 2, 3)
+                .unwrap()
+                .text(&c)
+                .as_deref(),
+            Some("run(1);\nrun(2);\n")
+        );
+        assert_eq!(
+            s.pick(&c, 3, 3)
+                .unwrap(),
+            Pick::Edited("run(3);\nrun(4);\n".into())
+        );
+
+        let out = String::from_utf8(s.output).unwrap();
+        assert!(out.contains("a.rs:2-6 (conflict 1/3)"));
+        assert!(out.contains("No base section"));
+        assert!(out.contains("ours               │ theirs"));
+        assert!(out.contains("run(1);            │ run(2);"));
+    }
+
+    #[test]
+    fn test_quit_and_eof_skip_the_rest()
+    {
+        let c = conflict(Some("run(0);\n"));
+        let mut s = session("b\nq\n");
+        assert_eq!(
+            s.pick(&c, 1, 3)
+                .unwrap()
+                .text(&c)
+                .as_deref(),
+            Some("run(0);\n")
+        );
+        assert_eq!(
+            s.pick(&c, 2, 3)
+                .unwrap(),
+            Pick::Skip
+        );
+        assert_eq!(
+            s.pick(&c, 3, 3)
+                .unwrap(),
+            Pick::Skip
+        );
+        assert_eq!(
+            session("")
+                .pick(&c, 1, 1)
+                .unwrap(),
+            Pick::Skip
+        );
+    }
+
+    #[test]
+    fn test_render_truncates_to_width()
+    {
+        let rows = render_panes(
+            &[("ours", "abcdefghijkl\n"), ("theirs", "x\ny\n")],
+            23,
+            false,
+        );
+        assert_eq!(rows[0], "ours       │ theirs");
+        assert_eq!(rows[2], "abcdefghi… │ x");
+        assert_eq!(rows[3], "           │ y");
+    }
+}
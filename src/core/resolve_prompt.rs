@@ -0,0 +1,362 @@
+//! LLM hand-off bundle for `rup resolve --emit-prompt`
+//!
+//! Conflicts the strategy could not settle are written as one paste-ready
+//! markdown prompt: a section per conflict (both sides with the commits that
+//! last changed them, the base, and the code around it) followed by an
+//! edit-spec skeleton with a REPLACE block over each conflict. The model
+//! fills in the NEW blocks and its reply goes straight to `rup apply`.
+//! Under a token budget the surrounding context shrinks first, then
+//! trailing conflicts are left out.
+
+use std::{
+    collections::HashMap,
+    fs,
+    path::{Path, PathBuf},
+};
+
+use anyhow::{Context, Result};
+
+use crate::core::{
+    budgeter::Budgeter,
+    conflict::{ConflictMarker, ConflictType},
+    conflict_blame::BlameCommit,
+};
+
+/// Lines of surrounding code to try, widest first, when fitting a budget
+const CONTEXT_STEPS: &[usize] = &[10, 5, 2, 0];
+
+/// Rendered prompt and what made it in
+#[derive(Debug)]
+pub struct PromptBundle
+{
+    pub text: String,
+    /// Conflicts included, from the front of the input
+    pub included: usize,
+    /// Conflicts left out to stay within the budget
+    pub omitted: usize,
+    /// Lines of context kept around each conflict
+    pub context_lines: usize,
+    /// Token count, when a budget was given
+    pub tokens: Option<usize>,
+}
+
+/// Build the prompt for `conflicts`, in order. With `budget`, the prompt is
+/// shrunk to fit `model`'s token count where possible; at least one
+/// conflict is always included.
+pub fn build_prompt(
+    conflicts: &[&ConflictMarker],
+    budget: Option<usize>,
+    model: &str,
+    tokenizer_file: Option<&Path>,
+) -> Result<PromptBundle>
+{
+    let mut files: HashMap<&PathBuf, String> = HashMap::new();
+    for conflict in conflicts
+    {
+        if !files.contains_key(&conflict.file)
+        {
+            let content = fs::read_to_string(&conflict.file).with_context(|| {
+                format!(
+                    "Failed to read {}",
+                    conflict
+                        .file
+                        .display()
+                )
+            })?;
+            files.insert(&conflict.file, content);
+        }
+    }
+
+    let Some(budget) = budget
+    else
+    {
+        return Ok(PromptBundle {
+            text: render(conflicts, &files, CONTEXT_STEPS[0]),
+            included: conflicts.len(),
+            omitted: 0,
+            context_lines: CONTEXT_STEPS[0],
+            tokens: None,
+        });
+    };
+
+    let budgeter = Budgeter::for_model(model, tokenizer_file)?;
+    let fitted = |count: usize, context: usize| {
+        let text = render(&conflicts[..count], &files, context);
+        let tokens = budgeter.count(&text);
+        PromptBundle {
+            text,
+            included: count,
+            omitted: conflicts.len() - count,
+            context_lines: context,
+            tokens: Some(tokens),
+        }
+    };
+    for &context in CONTEXT_STEPS
+    {
+        let bundle = fitted(conflicts.len(), context);
+        if bundle
+            .tokens
+            .is_some_and(|t| t <= budget)
+        {
+            return Ok(bundle);
+        }
+    }
+    let mut count = conflicts.len();
+    loop
+    {
+        count -= 1;
+        let bundle = fitted(count.max(1), 0);
+        if count <= 1
+            || bundle
+                .tokens
+                .is_some_and(|t| t <= budget)
+        {
+            return Ok(bundle);
+        }
+    }
+}
+
+fn render(
+    conflicts: &[&ConflictMarker],
+    files: &HashMap<&PathBuf, String>,
+    context: usize,
+) -> String
+{
+    let mut out = String::from(
+        "# Resolve merge conflicts\n\nEach section below is one unresolved merge conflict: our \
+         side, their side, the common base when known, and the code around it. Combine the intent \
+         of both sides; where they truly disagree, prefer the one that fits the surrounding \
+         code.\n\nReply with only the edit spec at the end, with every NEW block replaced by the \
+         resolved code (no conflict markers). Keep the FILE lines, line numbers and OLD blocks \
+         exactly as given; the reply is applied with `rup apply`.\n",
+    );
+
+    for (n, conflict) in conflicts
+        .iter()
+        .enumerate()
+    {
+        let lang = fence_lang(conflict);
+        let (start, end) = conflict.line_range;
+        let (ours_meta, theirs_meta) = match &conflict.conflict_type
+        {
+            ConflictType::GitMarkers { ours_meta, theirs_meta, .. } =>
+            {
+                (ours_meta.as_str(), theirs_meta.as_str())
+            }
+            _ => ("", ""),
+        };
+        let lines: Vec<&str> = files[&conflict.file]
+            .lines()
+            .collect();
+
+        out.push_str(&format!(
+            "\n## Conflict {}: `{}` lines {start}-{end}\n",
+            n + 1,
+            conflict
+                .file
+                .display()
+        ));
+        let before = &lines[(start - 1).saturating_sub(context)..start - 1];
+        push_block(&mut out, "Before", lang, &before.join("\n"));
+        let (ours_blame, theirs_blame) = match &conflict.attribution
+        {
+            Some(a) => (blamed(&a.ours), blamed(&a.theirs)),
+            None => (String::new(), String::new()),
+        };
+        push_block(
+            &mut out,
+            &(labelled("Ours", ours_meta) + &ours_blame),
+            lang,
+            &conflict.ours,
+        );
+        push_block(
+            &mut out,
+            &(labelled("Theirs", theirs_meta) + &theirs_blame),
+            lang,
+            &conflict.theirs,
+        );
+        if let Some(base) = &conflict.base
+        {
+            push_block(&mut out, "Base", lang, base);
+        }
+        let after = &lines[end.min(lines.len())..(end + context).min(lines.len())];
+        push_block(&mut out, "After", lang, &after.join("\n"));
+    }
+
+    out.push_str("\n## Edit spec\n\n````\n");
+    out.push_str(&skeleton(conflicts, files));
+    out.push_str("````\n");
+    out
+}
+
+/// Edit spec with one REPLACE per conflict, grouped by file in input order
+fn skeleton(
+    conflicts: &[&ConflictMarker],
+    files: &HashMap<&PathBuf, String>,
+) -> String
+{
+    let mut order: Vec<&PathBuf> = Vec::new();
+    for conflict in conflicts
+    {
+        if !order.contains(&&conflict.file)
+        {
+            order.push(&conflict.file);
+        }
+    }
+
+    let mut out = String::new();
+    for file in order
+    {
+        out.push_str(&format!("FILE: {}\n", file.display()));
+        for (n, conflict) in conflicts
+            .iter()
+            .enumerate()
+            .filter(|(_, c)| &c.file == file)
+        {
+            let (start, end) = conflict.byte_range;
+            let old = files[file]
+                .get(start..end)
+                .unwrap_or_default();
+            out.push_str(&format!(
+                "REPLACE lines {}-{}:\nOLD:\n```\n{}```\nNEW:\n```\n<resolved code for conflict \
+                 {}>\n```\n",
+                conflict
+                    .line_range
+                    .0,
+                conflict
+                    .line_range
+                    .1,
+                with_newline(old),
+                n + 1
+            ));
+        }
+        out.push('\n');
+    }
+    out
+}
+
+fn push_block(
+    out: &mut String,
+    title: &str,
+    lang: &str,
+    body: &str,
+)
+{
+    if body.is_empty()
+    {
+        return;
+    }
+    out.push_str(&format!(
+        "\n{title}:\n```{lang}\n{}```\n",
+        with_newline(body)
+    ));
+}
+
+fn labelled(
+    side: &str,
+    meta: &str,
+) -> String
+{
+    if meta.is_empty()
+    {
+        side.to_string()
+    }
+    else
+    {
+        format!("{side} (`{meta}`)")
+    }
+}
+
+/// `, last changed in …` for a side's commits, or nothing when unknown
+fn blamed(commits: &[BlameCommit]) -> String
+{
+    if commits.is_empty()
+    {
+        return String::new();
+    }
+    let list: Vec<String> = commits
+        .iter()
+        .map(ToString::to_string)
+        .collect();
+    format!(", last changed in {}", list.join("; "))
+}
+
+fn with_newline(text: &str) -> String
+{
+    if text.ends_with('\n')
+    {
+        text.to_string()
+    }
+    else
+    {
+        format!("{text}\n")
+    }
+}
+
+/// Markdown fence language for the conflict's file, by extension
+fn fence_lang(conflict: &ConflictMarker) -> &str
+{
+    conflict
+        .file
+        .extension()
+        .and_then(|ext| ext.to_str())
+        .unwrap_or("")
+}
+
+#[cfg(test)]
+mod tests
+{
+    use super::*;
+    use crate::core::conflict::parse_conflicts;
+
+    const FILE: &str = "fn main() {\n    setup();\n<<<<<<< HEAD\n    run(1);\n=======\n    \
+                        run(2);\n>>>>>>> topic\n    done();\n}\n";
+
+    fn conflicts(dir: &std::path::Path) -> Vec<ConflictMarker>
+    {
+        let path = dir.join("a.rs");
+        fs::write(&path, FILE).unwrap();
+        parse_conflicts(path, FILE.as_bytes()).unwrap()
+    }
+
+    #[test]
+    fn test_prompt_sections_and_skeleton()
+    {
+        let dir = tempfile::tempdir().unwrap();
+        let conflicts = conflicts(dir.path());
+        let refs: Vec<_> = conflicts
+            .iter()
+            .collect();
+        let bundle = build_prompt(&refs, None, "gpt-4o", None).unwrap();
+        let text = &bundle.text;
+
+        assert_eq!((bundle.included, bundle.omitted), (1, 0));
+        assert!(text.contains("a.rs` lines 3-7\n"), "{text}");
+        assert!(text.contains("Before:\n```rs\nfn main() {\n    setup();\n```"));
+        assert!(text.contains("Ours (`HEAD`):\n```rs\n    run(1);\n```"));
+        assert!(text.contains("Theirs (`topic`):\n```rs\n    run(2);\n```"));
+        assert!(text.contains("After:\n```rs\n    done();\n}\n```"));
+        assert!(text.contains(
+            "REPLACE lines 3-7:\nOLD:\n```\n<<<<<<< HEAD\n    run(1);\n=======\n    \
+             run(2);\n>>>>>>> topic\n```\nNEW:\n"
+        ));
+    }
+
+    #[test]
+    fn test_budget_trims_context_first()
+    {
+        let dir = tempfile::tempdir().unwrap();
+        let conflicts = conflicts(dir.path());
+        let refs: Vec<_> = conflicts
+            .iter()
+            .collect();
+        let bundle = build_prompt(&refs, Some(1), "gpt-4o", None).unwrap();
+        assert_eq!(bundle.context_lines, 0);
+        assert_eq!(bundle.included, 1);
+        assert!(
+            !bundle
+                .text
+                .contains("setup()")
+        );
+    }
+}
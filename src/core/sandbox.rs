@@ -0,0 +1,334 @@
+//! Scratch `git worktree` for trial applies (`rup apply --sandbox`) and
+//! branch-targeted applies (`rup apply --branch`)
+//!
+//! For a sandbox trial the spec is first applied to a detached worktree of
+//! HEAD that has been seeded with the current contents of every target file,
+//! so uncommitted work is part of the trial. An optional verification
+//! command (tests, build) runs there, and only a passing trial lets apply
+//! touch the real tree. For `--branch` the worktree starts at the branch tip
+//! and is not seeded: the spec applies to the branch's committed content.
+//! The worktree is unregistered and deleted on drop.
+
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    process::{Command, Output},
+};
+
+use anyhow::{Context, Result, bail};
+use tempfile::TempDir;
+
+use crate::core::edit::{EditSpec, FileBlock};
+
+/// A detached worktree living in a temporary directory
+pub struct ScratchWorktree
+{
+    repo_root: PathBuf,
+    path: PathBuf,
+    // Keeps the parent temp dir alive until the worktree is removed
+    _dir: TempDir,
+}
+
+impl ScratchWorktree
+{
+    /// Add a detached worktree of HEAD for `repo_root`
+    pub fn create(repo_root: &Path) -> Result<Self>
+    {
+        Self::create_at(repo_root, "HEAD")
+    }
+
+    /// Add a detached worktree of `rev` for `repo_root`
+    pub fn create_at(
+        repo_root: &Path,
+        rev: &str,
+    ) -> Result<Self>
+    {
+        let dir = tempfile::Builder::new()
+            .prefix("rup-sandbox-")
+            .tempdir()
+            .context("Failed to create sandbox directory")?;
+        // git wants a fresh path, not the (existing) temp dir itself
+        let path = dir
+            .path()
+            .join("tree");
+
+        let out = Command::new("git")
+            .arg("worktree")
+            .arg("add")
+            .arg("--detach")
+            .arg(&path)
+            .arg(rev)
+            .current_dir(repo_root)
+            .output()
+            .context("Failed to run git worktree add")?;
+        if !out
+            .status
+            .success()
+        {
+            bail!(
+                "git worktree add failed: {}",
+                String::from_utf8_lossy(&out.stderr).trim()
+            );
+        }
+
+        Ok(Self {
+            repo_root: repo_root.to_path_buf(),
+            path,
+            _dir: dir,
+        })
+    }
+
+    pub fn path(&self) -> &Path
+    {
+        &self.path
+    }
+
+    /// Copy each target's current contents into the worktree and return the
+    /// spec with its paths rewritten to point there
+    pub fn stage(
+        &self,
+        spec: &EditSpec,
+        cwd: &Path,
+    ) -> Result<EditSpec>
+    {
+        let retargeted = self.retarget(spec, cwd)?;
+        for (block, staged) in spec
+            .file_blocks
+            .iter()
+            .zip(&retargeted.file_blocks)
+        {
+            let source = absolute(&block.path, cwd);
+            let target = &staged.path;
+
+            if source.exists()
+            {
+                if let Some(parent) = target.parent()
+                {
+                    fs::create_dir_all(parent)
+                        .with_context(|| format!("Failed to create {}", parent.display()))?;
+                }
+                fs::copy(&source, target)
+                    .with_context(|| format!("Failed to seed sandbox with {}", source.display()))?;
+            }
+            else if target.exists()
+            {
+                // Deleted in the working tree but still present at HEAD
+                fs::remove_file(target)
+                    .with_context(|| format!("Failed to remove {}", target.display()))?;
+            }
+        }
+        Ok(retargeted)
+    }
+
+    /// The spec with its paths rewritten into the worktree, leaving the
+    /// worktree's checked-out content as is
+    pub fn retarget(
+        &self,
+        spec: &EditSpec,
+        cwd: &Path,
+    ) -> Result<EditSpec>
+    {
+        let mut file_blocks = Vec::with_capacity(
+            spec.file_blocks
+                .len(),
+        );
+        for block in &spec.file_blocks
+        {
+            let rel = self.repo_relative(&absolute(&block.path, cwd))?;
+            file_blocks.push(FileBlock {
+                path: self
+                    .path
+                    .join(rel),
+                operations: block
+                    .operations
+                    .clone(),
+            });
+        }
+        Ok(EditSpec { file_blocks })
+    }
+
+    /// Run `command` through the platform shell inside the worktree
+    pub fn verify(
+        &self,
+        command: &str,
+    ) -> Result<Output>
+    {
+        run_verify_command(command, &self.path)
+    }
+
+    fn repo_relative(
+        &self,
+        path: &Path,
+    ) -> Result<PathBuf>
+    {
+        let normalized = crate::core::edit::lexical_normalize(path);
+        if let Ok(rel) = normalized.strip_prefix(&self.repo_root)
+        {
+            return Ok(rel.to_path_buf());
+        }
+
+        // Fall back to canonical forms (symlinked temp dirs, /private on macOS)
+        let root = self
+            .repo_root
+            .canonicalize()
+            .unwrap_or_else(|_| {
+                self.repo_root
+                    .clone()
+            });
+        let parent = normalized
+            .parent()
+            .and_then(|p| {
+                p.canonicalize()
+                    .ok()
+            });
+        if let (Some(parent), Some(name)) = (parent, normalized.file_name())
+            && let Ok(rel) = parent
+                .join(name)
+                .strip_prefix(&root)
+        {
+            return Ok(rel.to_path_buf());
+        }
+
+        bail!(
+            "cannot sandbox {}: outside the repository boundary",
+            path.display()
+        )
+    }
+}
+
+/// Run a verification `command` (tests, build) through the platform shell
+/// in `dir`
+pub fn run_verify_command(
+    command: &str,
+    dir: &Path,
+) -> Result<Output>
+{
+    #[cfg(windows)]
+    let mut cmd = {
+        let mut c = Command::new("cmd");
+        c.arg("/C");
+        c
+    };
+    #[cfg(not(windows))]
+    let mut cmd = {
+        let mut c = Command::new("sh");
+        c.arg("-c");
+        c
+    };
+    cmd.arg(command)
+        .current_dir(dir)
+        .output()
+        .with_context(|| format!("Failed to run verification command: {command}"))
+}
+
+fn absolute(
+    path: &Path,
+    cwd: &Path,
+) -> PathBuf
+{
+    if path.is_absolute()
+    {
+        path.to_path_buf()
+    }
+    else
+    {
+        cwd.join(path)
+    }
+}
+
+impl Drop for ScratchWorktree
+{
+    fn drop(&mut self)
+    {
+        // Best effort: unregister the worktree before the temp dir goes away
+        let _ = Command::new("git")
+            .arg("worktree")
+            .arg("remove")
+            .arg("--force")
+            .arg(&self.path)
+            .current_dir(&self.repo_root)
+            .output();
+        let _ = Command::new("git")
+            .args(["worktree", "prune"])
+            .current_dir(&self.repo_root)
+            .output();
+    }
+}
+
+#[cfg(test)]
+mod tests
+{
+    use super::*;
+    use crate::core::edit::EditOperation;
+
+    fn git(
+        dir: &Path,
+        args: &[&str],
+    )
+    {
+        let status = Command::new("git")
+            .args(["-c", "user.name=Test", "-c", "user.email=test@example.com"])
+            .args(args)
+            .current_dir(dir)
+            .output()
+            .unwrap()
+            .status;
+        assert!(status.success(), "git {args:?} failed");
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn test_stage_seeds_uncommitted_content_and_cleans_up()
+    {
+        let repo = TempDir::new().unwrap();
+        let root = repo
+            .path()
+            .canonicalize()
+            .unwrap();
+        fs::write(root.join("a.txt"), "committed\n").unwrap();
+        git(&root, &["init", "-q"]);
+        git(&root, &["add", "."]);
+        git(&root, &["commit", "-q", "-m", "init"]);
+
+        // Uncommitted edit must be visible in the sandbox
+        fs::write(root.join("a.txt"), "working\n").unwrap();
+
+        let spec = EditSpec {
+            file_blocks: vec![FileBlock {
+                path: PathBuf::from("a.txt"),
+                operations: vec![EditOperation::Delete {
+                    start_line: 1,
+                    end_line: 1,
+                    old_content: None,
+                }],
+            }],
+        };
+
+        let scratch_path;
+        {
+            let scratch = ScratchWorktree::create(&root).unwrap();
+            scratch_path = scratch
+                .path()
+                .to_path_buf();
+
+            let staged = scratch
+                .stage(&spec, &root)
+                .unwrap();
+            assert_eq!(staged.file_blocks[0].path, scratch_path.join("a.txt"));
+            assert_eq!(
+                fs::read_to_string(scratch_path.join("a.txt")).unwrap(),
+                "working\n"
+            );
+
+            let out = scratch
+                .verify("test -f a.txt")
+                .unwrap();
+            assert!(
+                out.status
+                    .success()
+            );
+        }
+
+        assert!(!scratch_path.exists(), "worktree removed on drop");
+    }
+}
@@ -0,0 +1,591 @@
+//! Fuzzy symbol lookup for `rup search`
+//!
+//! Each query term is matched as a case-insensitive subsequence against a
+//! symbol's name, qualified name, and file path; a symbol is a hit when
+//! every term matches one of them. Matches score higher when they are
+//! exact, prefixes, contiguous, or start at word boundaries (`_`, `::`,
+//! `/`, camelCase humps), and name matches outrank file matches. Unlike
+//! `rup context`, nothing is read beyond the index itself.
+
+use std::{collections::HashSet, path::Path};
+
+use anyhow::{Result, anyhow, bail};
+use serde::{Deserialize, Serialize, de::IntoDeserializer};
+
+use crate::{
+    cli::{AppContext, SearchArgs, SymbolsArgs, SymbolsFormat},
+    core::{
+        context::ContextAssembler,
+        symbol_index::{SymbolIndex, refs_path},
+        symbol_shards::{index_exists, manifest_path, probe_path, refresh_stale},
+        symbols::{Symbol, SymbolKind, Visibility},
+    },
+};
+
+/// One ranked search result
+#[derive(Debug, Clone, Serialize)]
+pub struct SearchHit
+{
+    /// Higher is better; only comparable within one query
+    pub score: u32,
+
+    #[serde(flatten)]
+    pub symbol: Symbol,
+}
+
+/// Kind and visibility filters for [`search`]
+#[derive(Debug, Clone, Default)]
+pub struct SearchFilter
+{
+    /// Keep only these kinds; empty keeps all
+    pub kinds: HashSet<SymbolKind>,
+
+    /// Keep only these visibilities; empty keeps all
+    pub visibilities: HashSet<Visibility>,
+}
+
+impl SearchFilter
+{
+    fn keeps(
+        &self,
+        symbol: &Symbol,
+    ) -> bool
+    {
+        (self
+            .kinds
+            .is_empty()
+            || self
+                .kinds
+                .contains(&symbol.kind))
+            && (self
+                .visibilities
+                .is_empty()
+                || symbol
+                    .visibility
+                    .as_ref()
+                    .is_some_and(|v| {
+                        self.visibilities
+                            .contains(v)
+                    }))
+    }
+}
+
+/// Entry point for `rup search`
+pub fn run(
+    args: &SearchArgs,
+    ctx: &AppContext,
+) -> Result<()>
+{
+    let filter = SearchFilter {
+        kinds: args
+            .kind
+            .iter()
+            .map(|k| SymbolKind::from_label(k))
+            .collect::<Result<_>>()?,
+        visibilities: parse_labels(&args.visibility, "visibility")?,
+    };
+    let query = args
+        .query
+        .join(" ");
+
+    let cfg = crate::infra::config::load_config().unwrap_or_default();
+    let symbols_path = if args
+        .symbols
+        .exists()
+    {
+        args.symbols
+            .clone()
+    }
+    else
+    {
+        cfg.symbols
+            .output_file
+            .clone()
+            .into()
+    };
+    refresh_index(args, ctx, &cfg, &symbols_path);
+    if !index_exists(&symbols_path)
+    {
+        bail!(
+            "no symbols index at {}; run `rup symbols` first",
+            symbols_path.display()
+        );
+    }
+
+    let index = SymbolIndex::load(&symbols_path)?;
+    let mut hits = search(index.all(), &query, &filter);
+    let total = hits.len();
+    hits.truncate(args.limit);
+
+    if args.json
+    {
+        let output = serde_json::json!({
+            "query": query,
+            "total": total,
+            "results": hits,
+        });
+        println!("{}", serde_json::to_string_pretty(&output)?);
+        return Ok(());
+    }
+
+    let width = hits
+        .iter()
+        .map(|h| location(&h.symbol).len())
+        .max()
+        .unwrap_or(0);
+    for hit in &hits
+    {
+        println!(
+            "{:<width$}  {:<11} {}",
+            location(&hit.symbol),
+            hit.symbol
+                .kind
+                .label(),
+            hit.symbol
+                .qualified_name
+        );
+    }
+    if !ctx.quiet
+    {
+        if total == 0
+        {
+            eprintln!("No symbols match '{query}'");
+        }
+        else if total > hits.len()
+        {
+            eprintln!(
+                "({} of {total} matches shown; raise --limit for more)",
+                hits.len()
+            );
+        }
+    }
+    Ok(())
+}
+
+/// Symbols matching every term of `query`, best first
+pub fn search(
+    symbols: &[Symbol],
+    query: &str,
+    filter: &SearchFilter,
+) -> Vec<SearchHit>
+{
+    let terms: Vec<String> = query
+        .split_whitespace()
+        .map(str::to_lowercase)
+        .collect();
+    if terms.is_empty()
+    {
+        return Vec::new();
+    }
+
+    let mut hits: Vec<SearchHit> = symbols
+        .iter()
+        .filter(|s| filter.keeps(s))
+        .filter_map(|s| {
+            let score = terms
+                .iter()
+                .map(|t| symbol_score(t, s))
+                .sum::<Option<u32>>()?;
+            Some(SearchHit { score, symbol: s.clone() })
+        })
+        .collect();
+    hits.sort_by(|a, b| {
+        b.score
+            .cmp(&a.score)
+            .then_with(|| {
+                a.symbol
+                    .name
+                    .len()
+                    .cmp(
+                        &b.symbol
+                            .name
+                            .len(),
+                    )
+            })
+            .then_with(|| {
+                a.symbol
+                    .file
+                    .cmp(
+                        &b.symbol
+                            .file,
+                    )
+            })
+            .then_with(|| {
+                a.symbol
+                    .start_line
+                    .cmp(
+                        &b.symbol
+                            .start_line,
+                    )
+            })
+    });
+    hits
+}
+
+/// Best score of one lowercase term against a symbol's fields
+fn symbol_score(
+    term: &str,
+    symbol: &Symbol,
+) -> Option<u32>
+{
+    let name = fuzzy_score(term, &symbol.name).map(|s| s * 3);
+    let qualified = fuzzy_score(term, &symbol.qualified_name).map(|s| s * 2);
+    let file = fuzzy_score(
+        term,
+        &symbol
+            .file
+            .to_string_lossy(),
+    );
+    [name, qualified, file]
+        .into_iter()
+        .flatten()
+        .max()
+}
+
+/// Subsequence score of a lowercase `term` in `text`, or `None` when some
+/// character of `term` is missing
+///
+/// Each matched character earns a base point, plus bonuses for following
+/// the previous match directly and for starting a word; exact and prefix
+/// matches add a flat bonus on top.
+fn fuzzy_score(
+    term: &str,
+    text: &str,
+) -> Option<u32>
+{
+    let chars: Vec<char> = text
+        .chars()
+        .collect();
+    let lower: Vec<char> = text
+        .to_lowercase()
+        .chars()
+        .collect();
+    // Lowercasing can change the length of some scripts; skip those
+    if chars.len() != lower.len()
+    {
+        return None;
+    }
+
+    // Jumping to word starts usually scores best, but can strand later
+    // characters, so plain left-to-right matching is the fallback
+    let walked = [true, false]
+        .into_iter()
+        .filter_map(|prefer_boundary| walk(term, &chars, &lower, prefer_boundary))
+        .max()?;
+
+    let text_lower: String = lower
+        .iter()
+        .collect();
+    let bonus = if text_lower == term
+    {
+        20
+    }
+    else if text_lower.starts_with(term)
+    {
+        10
+    }
+    else
+    {
+        0
+    };
+    Some(walked + bonus)
+}
+
+/// One greedy subsequence match of `term`, continuing a contiguous run
+/// when possible and otherwise taking the next occurrence (the next word
+/// start first, with `prefer_boundary`)
+fn walk(
+    term: &str,
+    chars: &[char],
+    lower: &[char],
+    prefer_boundary: bool,
+) -> Option<u32>
+{
+    const BASE: u32 = 2;
+    const CONSECUTIVE: u32 = 4;
+    const BOUNDARY: u32 = 6;
+
+    let mut score = 0;
+    let mut next = 0;
+    for want in term.chars()
+    {
+        let find = |boundary_only: bool| {
+            (next..lower.len())
+                .find(|&i| lower[i] == want && (!boundary_only || is_boundary(chars, i)))
+        };
+        let at = if next > 0 && lower.get(next) == Some(&want)
+        {
+            next
+        }
+        else
+        {
+            prefer_boundary
+                .then(|| find(true))
+                .flatten()
+                .or_else(|| find(false))?
+        };
+        score += BASE;
+        if next > 0 && at == next
+        {
+            score += CONSECUTIVE;
+        }
+        if is_boundary(chars, at)
+        {
+            score += BOUNDARY;
+        }
+        next = at + 1;
+    }
+    Some(score)
+}
+
+/// Whether `chars[i]` starts a word: the first character, one after a
+/// separator, or an uppercase letter after a lowercase one
+fn is_boundary(
+    chars: &[char],
+    i: usize,
+) -> bool
+{
+    let Some(prev) = i
+        .checked_sub(1)
+        .map(|p| chars[p])
+    else
+    {
+        return true;
+    };
+    !prev.is_alphanumeric() || prev.is_lowercase() && chars[i].is_uppercase()
+}
+
+/// `file:line` for a result row
+fn location(symbol: &Symbol) -> String
+{
+    format!(
+        "{}:{}",
+        symbol
+            .file
+            .display(),
+        symbol.start_line
+    )
+}
+
+/// Kinds or visibilities from their `symbols.jsonl` names
+fn parse_labels<T>(
+    labels: &[String],
+    what: &str,
+) -> Result<HashSet<T>>
+where
+    T: for<'de> Deserialize<'de> + Eq + std::hash::Hash,
+{
+    labels
+        .iter()
+        .map(|label| {
+            let label = label
+                .trim()
+                .to_lowercase()
+                .replace('-', "_");
+            T::deserialize(
+                label
+                    .as_str()
+                    .into_deserializer(),
+            )
+            .map_err(|e: serde::de::value::Error| anyhow!("unknown {what} '{label}': {e}"))
+        })
+        .collect()
+}
+
+/// Build the index when it is missing or older than the sources, as
+/// `rup context` does, unless `ROUGHUP_NO_AUTO_INDEX` is set
+fn refresh_index(
+    args: &SearchArgs,
+    ctx: &AppContext,
+    cfg: &crate::infra::config::Config,
+    symbols_path: &Path,
+)
+{
+    if std::env::var("ROUGHUP_NO_AUTO_INDEX").is_ok()
+    {
+        return;
+    }
+    // A sharded index records call sites and vectors per shard
+    let probe = probe_path(symbols_path);
+    let sym_args = SymbolsArgs {
+        command: None,
+        path: args
+            .path
+            .clone(),
+        // Empty selects the configured languages plus external ones
+        languages: Vec::new(),
+        output: symbols_path.to_path_buf(),
+        include_private: cfg
+            .symbols
+            .include_private,
+        // Keep an existing call-site index in step with the symbols
+        refs: refs_path(&probe).exists(),
+        signatures: cfg
+            .symbols
+            .signatures,
+        embeddings: crate::core::symbol_embeddings::embeddings_path(&probe).exists(),
+        format: SymbolsFormat::Jsonl,
+        watch: false,
+        shard: false,
+        kind: Vec::new(),
+        include: Vec::new(),
+        exclude: Vec::new(),
+    };
+    if manifest_path(symbols_path).exists()
+    {
+        if let Err(e) = refresh_stale(&sym_args, &[], ctx)
+            && !ctx.quiet
+        {
+            eprintln!("(warn) symbols shard regeneration failed: {e}");
+        }
+        return;
+    }
+    if symbols_path.exists() && ContextAssembler::index_is_fresh(&args.path, symbols_path)
+    {
+        return;
+    }
+    if let Some(parent) = symbols_path.parent()
+        && !parent
+            .as_os_str()
+            .is_empty()
+        && let Err(e) = std::fs::create_dir_all(parent)
+    {
+        if !ctx.quiet
+        {
+            eprintln!("(warn) cannot create {}: {e}", parent.display());
+        }
+        return;
+    }
+    if !ctx.quiet
+    {
+        let state = if symbols_path.exists()
+        {
+            "stale"
+        }
+        else
+        {
+            "missing"
+        };
+        eprintln!(
+            "(info) symbols index {state}; generating at {}",
+            symbols_path.display()
+        );
+    }
+    if let Err(e) = ContextAssembler::ensure_symbols_with_lock(&sym_args, ctx, symbols_path)
+        && !ctx.quiet
+    {
+        eprintln!("(warn) auto symbols generation failed: {e}");
+    }
+}
+
+#[cfg(test)]
+mod tests
+{
+    use std::path::PathBuf;
+
+    use super::*;
+
+    fn sym(
+        file: &str,
+        kind: SymbolKind,
+        qualified_name: &str,
+        visibility: Option<Visibility>,
+    ) -> Symbol
+    {
+        let name = qualified_name
+            .rsplit("::")
+            .next()
+            .unwrap();
+        Symbol {
+            file: PathBuf::from(file),
+            lang: "rust".into(),
+            kind,
+            name: name.into(),
+            qualified_name: qualified_name.into(),
+            byte_start: 0,
+            byte_end: 1,
+            start_line: 1,
+            end_line: 1,
+            visibility,
+            doc: None,
+            signature: None,
+            package: None,
+            module: None,
+        }
+    }
+
+    fn names(hits: &[SearchHit]) -> Vec<&str>
+    {
+        hits.iter()
+            .map(|h| {
+                h.symbol
+                    .qualified_name
+                    .as_str()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn boundaries_and_exact_names_rank_first()
+    {
+        let symbols = [
+            sym("src/misc.rs", SymbolKind::Function, "fallback_cache", None),
+            sym("src/cache.rs", SymbolKind::Function, "get_or_fetch", None),
+            sym("src/store.rs", SymbolKind::Struct, "FetchCache", None),
+            sym(
+                "src/store.rs",
+                SymbolKind::Method,
+                "FetchCache::fetch",
+                None,
+            ),
+        ];
+        let hits = search(&symbols, "fc", &SearchFilter::default());
+        assert_eq!(names(&hits)[0], "FetchCache");
+
+        let hits = search(&symbols, "fetch", &SearchFilter::default());
+        assert_eq!(names(&hits)[..2], ["FetchCache::fetch", "FetchCache"]);
+
+        // Every term must match; file paths count
+        let hits = search(&symbols, "cache get", &SearchFilter::default());
+        assert_eq!(names(&hits), ["get_or_fetch"]);
+        assert!(search(&symbols, "zzz", &SearchFilter::default()).is_empty());
+    }
+
+    #[test]
+    fn filters_and_labels()
+    {
+        let symbols = [
+            sym(
+                "a.rs",
+                SymbolKind::Function,
+                "load",
+                Some(Visibility::Public),
+            ),
+            sym(
+                "a.rs",
+                SymbolKind::Function,
+                "load_all",
+                Some(Visibility::Private),
+            ),
+            sym(
+                "a.rs",
+                SymbolKind::Struct,
+                "Loader",
+                Some(Visibility::Public),
+            ),
+        ];
+        let filter = SearchFilter {
+            kinds: parse_labels(&["function".into()], "kind").unwrap(),
+            visibilities: parse_labels(&["Public".into()], "visibility").unwrap(),
+        };
+        assert_eq!(names(&search(&symbols, "load", &filter)), ["load"]);
+        assert!(
+            parse_labels::<SymbolKind>(&["widget".into()], "kind")
+                .unwrap_err()
+                .to_string()
+                .contains("unknown kind 'widget'")
+        );
+        let kinds: HashSet<SymbolKind> = parse_labels(&["type-alias".into()], "kind").unwrap();
+        assert!(kinds.contains(&SymbolKind::TypeAlias));
+    }
+}
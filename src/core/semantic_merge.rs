@@ -0,0 +1,389 @@
+//! Item-level merge of conflict sides for `ResolveStrategy::Semantic`
+//!
+//! Both sides (and the base, when the markers carry one) are parsed with
+//! tree-sitter and split into top-level items: functions, impls, classes,
+//! imports, each with its leading comments and attributes. Items are keyed
+//! by kind and name; an item only one side added or changed is taken from
+//! that side, and items both sides changed differently make the merge give
+//! up. Hunks that are not whole items (a few lines inside a function) fail
+//! to parse cleanly and are left to SmartMerge.
+
+use std::{collections::HashMap, path::Path};
+
+use tree_sitter::{Language, Node, Parser};
+
+use crate::core::conflict::ConflictMarker;
+
+/// Node kinds that belong to the item after them rather than standing alone
+const LEADING_KINDS: &[&str] =
+    &["comment", "line_comment", "block_comment", "attribute_item", "inner_attribute_item"];
+
+/// A top-level item and its source text, without surrounding whitespace
+struct Item<'s>
+{
+    key: String,
+    text: &'s str,
+}
+
+/// Grammar for a file, by extension
+fn language_for(path: &Path) -> Option<Language>
+{
+    let ext = path
+        .extension()?
+        .to_str()?;
+    let lang = match ext
+    {
+        "rs" => tree_sitter_rust::LANGUAGE.into(),
+        "py" | "pyi" => tree_sitter_python::LANGUAGE.into(),
+        "js" | "jsx" | "mjs" | "cjs" => tree_sitter_javascript::LANGUAGE.into(),
+        "ts" => tree_sitter_typescript::LANGUAGE_TYPESCRIPT.into(),
+        "tsx" => tree_sitter_typescript::LANGUAGE_TSX.into(),
+        "go" => tree_sitter_go::LANGUAGE.into(),
+        "cc" | "cpp" | "cxx" | "hpp" | "hh" | "h" => tree_sitter_cpp::LANGUAGE.into(),
+        _ => return None,
+    };
+    Some(lang)
+}
+
+/// Merge the two sides of `conflict` item by item, or `None` when the file
+/// type is unsupported, a side is not a sequence of whole items, or both
+/// sides changed the same item
+pub fn merge_items(conflict: &ConflictMarker) -> Option<String>
+{
+    let lang = language_for(&conflict.file)?;
+    let mut parser = Parser::new();
+    parser
+        .set_language(&lang)
+        .ok()?;
+
+    let (ours, ours_separator) = split_items(&mut parser, &conflict.ours)?;
+    let (theirs, theirs_separator) = split_items(&mut parser, &conflict.theirs)?;
+    let base = match &conflict.base
+    {
+        Some(base) => split_items(&mut parser, base)?.0,
+        None => Vec::new(),
+    };
+    let separator = ours_separator
+        .or(theirs_separator)
+        .unwrap_or("\n");
+    let base: HashMap<&str, &str> = base
+        .iter()
+        .map(|item| {
+            (
+                item.key
+                    .as_str(),
+                item.text,
+            )
+        })
+        .collect();
+    let theirs_by_key: HashMap<&str, &str> = theirs
+        .iter()
+        .map(|item| {
+            (
+                item.key
+                    .as_str(),
+                item.text,
+            )
+        })
+        .collect();
+
+    // Walk ours in order, deciding each item against theirs and the base
+    let mut merged: Vec<(&str, &str)> = Vec::new();
+    for item in &ours
+    {
+        let key = item
+            .key
+            .as_str();
+        let kept = match (theirs_by_key.get(key), base.get(key))
+        {
+            (Some(t), _) if same(item.text, t) => Some(item.text),
+            (Some(t), Some(b)) if same(item.text, b) => Some(*t),
+            (Some(_), Some(b)) if same(theirs_by_key[key], b) => Some(item.text),
+            (Some(_), _) => return None,
+            // Theirs deleted it: fine if ours left it alone
+            (None, Some(b)) if same(item.text, b) => None,
+            (None, Some(_)) => return None,
+            (None, None) => Some(item.text),
+        };
+        if let Some(text) = kept
+        {
+            merged.push((key, text));
+        }
+    }
+
+    // Theirs-only items go after their nearest predecessor that survived,
+    // else before the first shared item, else after all of ours
+    let ours_keys: HashMap<&str, ()> = ours
+        .iter()
+        .map(|item| {
+            (
+                item.key
+                    .as_str(),
+                (),
+            )
+        })
+        .collect();
+    let mut anchor: Option<&str> = None;
+    for item in &theirs
+    {
+        let key = item
+            .key
+            .as_str();
+        if !ours_keys.contains_key(key)
+        {
+            match base.get(key)
+            {
+                // Ours deleted it and theirs left it alone
+                Some(b) if same(item.text, b) => continue,
+                Some(_) => return None,
+                None =>
+                {}
+            }
+            let at = anchor
+                .and_then(|a| {
+                    merged
+                        .iter()
+                        .position(|(k, _)| *k == a)
+                })
+                .map_or_else(
+                    || {
+                        merged
+                            .iter()
+                            .position(|(k, _)| theirs_by_key.contains_key(k))
+                            .unwrap_or(merged.len())
+                    },
+                    |i| i + 1,
+                );
+            merged.insert(at, (key, item.text));
+        }
+        if merged
+            .iter()
+            .any(|(k, _)| *k == key)
+        {
+            anchor = Some(key);
+        }
+    }
+
+    let mut text = merged
+        .iter()
+        .map(|(_, text)| *text)
+        .collect::<Vec<_>>()
+        .join(separator);
+    // Keep the hunk's own leading whitespace and trailing newline
+    let lead = conflict
+        .ours
+        .len()
+        - conflict
+            .ours
+            .trim_start()
+            .len();
+    text.insert_str(0, &conflict.ours[..lead]);
+    if conflict
+        .ours
+        .ends_with('\n')
+        && !text.ends_with('\n')
+    {
+        text.push('\n');
+    }
+
+    let reparsed = parser.parse(&text, None)?;
+    (!reparsed
+        .root_node()
+        .has_error())
+    .then_some(text)
+}
+
+/// Equal up to surrounding whitespace
+fn same(
+    a: &str,
+    b: &str,
+) -> bool
+{
+    a.trim() == b.trim()
+}
+
+/// Top-level items of `src`, each with its leading comments and attributes,
+/// plus the whitespace between the first two (the file's item separator).
+/// `None` on parse errors, duplicate keys, or no items at all.
+fn split_items<'s>(
+    parser: &mut Parser,
+    src: &'s str,
+) -> Option<(Vec<Item<'s>>, Option<&'s str>)>
+{
+    let tree = parser.parse(src, None)?;
+    let root = tree.root_node();
+    if root.has_error()
+    {
+        return None;
+    }
+
+    let mut items: Vec<Item<'s>> = Vec::new();
+    let mut separator = None;
+    let mut start = 0;
+    let mut cursor = root.walk();
+    for node in root.named_children(&mut cursor)
+    {
+        if LEADING_KINDS.contains(&node.kind())
+        {
+            continue;
+        }
+        let end = node.end_byte();
+        let segment = &src[start..end];
+        let text = segment.trim_start();
+        if items.len() == 1
+        {
+            separator = Some(&segment[..segment.len() - text.len()]);
+        }
+        let key = item_key(node, src);
+        if items
+            .iter()
+            .any(|item| item.key == key)
+        {
+            return None;
+        }
+        items.push(Item { key, text });
+        start = end;
+    }
+
+    // Trailing comments stay with the last item
+    let last = items.last_mut()?;
+    let from = start
+        - last
+            .text
+            .len();
+    last.text = src[from..].trim_end();
+    Some((items, separator))
+}
+
+/// `kind:name` for named items; the whitespace-normalized text otherwise,
+/// so identical anonymous items (imports, statements) still match up
+fn item_key(
+    node: Node,
+    src: &str,
+) -> String
+{
+    match item_name(node, src)
+    {
+        Some(name) => format!("{}:{}", node.kind(), name),
+        None =>
+        {
+            src[node.byte_range()]
+                .split_whitespace()
+                .collect::<Vec<_>>()
+                .join(" ")
+        }
+    }
+}
+
+fn item_name(
+    node: Node,
+    src: &str,
+) -> Option<String>
+{
+    let text = |n: Node| src[n.byte_range()].to_string();
+    if let Some(name) = node.child_by_field_name("name")
+    {
+        return Some(text(name));
+    }
+    // Wrappers: decorated definitions, exports, template declarations
+    for field in ["definition", "declaration"]
+    {
+        if let Some(inner) = node.child_by_field_name(field)
+        {
+            return item_name(inner, src).map(|name| format!("{}:{}", inner.kind(), name));
+        }
+    }
+    // impl blocks: `impl Trait for Type`
+    let ty = node.child_by_field_name("type")?;
+    Some(match node.child_by_field_name("trait")
+    {
+        Some(tr) => format!("{} for {}", text(tr), text(ty)),
+        None => text(ty),
+    })
+}
+
+#[cfg(test)]
+mod tests
+{
+    use std::path::PathBuf;
+
+    use super::*;
+    use crate::core::conflict::{ConflictOrigin, ConflictType};
+
+    fn conflict(
+        file: &str,
+        ours: &str,
+        theirs: &str,
+        base: Option<&str>,
+    ) -> ConflictMarker
+    {
+        ConflictMarker {
+            file: PathBuf::from(file),
+            origin: ConflictOrigin::GitMarkers,
+            conflict_type: ConflictType::GitMarkers {
+                ours_meta: "HEAD".into(),
+                theirs_meta: "topic".into(),
+                has_base: base.is_some(),
+            },
+            byte_range: (0, 0),
+            line_range: (1, 1),
+            ours: ours.into(),
+            theirs: theirs.into(),
+            base: base.map(Into::into),
+            confidence: 0.0,
+            attribution: None,
+        }
+    }
+
+    #[test]
+    fn test_keeps_items_added_on_both_sides()
+    {
+        let ours = "fn shared() {}\n\n/// Ours\nfn ours() {}\n";
+        let theirs = "fn shared() {}\n\n#[test]\nfn theirs() {}\n";
+        let merged = merge_items(&conflict("a.rs", ours, theirs, None)).unwrap();
+        assert_eq!(
+            merged,
+            "fn shared() {}\n\n#[test]\nfn theirs() {}\n\n/// Ours\nfn ours() {}\n"
+        );
+    }
+
+    #[test]
+    fn test_base_decides_one_sided_changes()
+    {
+        let base = "fn a() { 1 }\nfn b() { 2 }\n";
+        let ours = "fn a() { 10 }\nfn b() { 2 }\n";
+        let theirs = "fn a() { 1 }\n";
+        let merged = merge_items(&conflict("a.rs", ours, theirs, Some(base))).unwrap();
+        assert_eq!(merged, "fn a() { 10 }\n");
+    }
+
+    #[test]
+    fn test_gives_up_on_overlap_and_fragments()
+    {
+        let ours = "fn a() { 1 }\n";
+        let theirs = "fn a() { 2 }\n";
+        assert_eq!(merge_items(&conflict("a.rs", ours, theirs, None)), None);
+        assert_eq!(
+            merge_items(&conflict(
+                "a.rs",
+                "    run(1);\n}\n",
+                "    run(2);\n}\n",
+                None
+            )),
+            None
+        );
+        assert_eq!(merge_items(&conflict("a.txt", "x\n", "y\n", None)), None);
+    }
+
+    #[test]
+    fn test_python_classes()
+    {
+        let ours = "class A:\n    pass\n\n\ndef f():\n    return 1\n";
+        let theirs = "class A:\n    pass\n\n\nclass B:\n    pass\n";
+        let merged = merge_items(&conflict("m.py", ours, theirs, None)).unwrap();
+        assert_eq!(
+            merged,
+            "class A:\n    pass\n\n\nclass B:\n    pass\n\n\ndef f():\n    return 1\n"
+        );
+    }
+}
@@ -0,0 +1,462 @@
+//! Embedding vectors for the symbol index, for `rup symbols --embeddings`
+//!
+//! roughup ships no model. The embedder is the command configured under
+//! `[symbols.embeddings]`, or one an embedder of the library registers with
+//! [`register_embedder`] (a local model, say).
+//!
+//! The command gets one request line on stdin and closes it:
+//! `{"protocol":1,"texts":["function parse_config\nfn parse_config(path: &Path)"]}`
+//! and answers with one JSON array of numbers per text, in order, then
+//! exits 0. Blank lines are ignored; a non-zero exit or a missing vector
+//! fails the run with the command's own message.
+//!
+//! Each symbol is embedded from its kind, qualified name, signature, and
+//! doc. Vectors are written next to the index (`symbols.jsonl` →
+//! `symbols.embeddings.jsonl`) with a digest of that text, so a rebuild
+//! only embeds symbols whose text changed. With them, `rup context
+//! --semantic` ranks by cosine similarity to the embedded query.
+
+use std::{
+    collections::HashMap,
+    io::Write,
+    path::{Path, PathBuf},
+    process::{Command, Stdio},
+    sync::{Arc, OnceLock, RwLock},
+};
+
+use anyhow::{Context, Result, bail};
+use serde::{Deserialize, Serialize};
+
+use crate::{core::symbols::Symbol, infra::config::load_config};
+
+/// Protocol version sent with every request
+pub const PROTOCOL_VERSION: u32 = 1;
+
+/// Turns texts into vectors of one fixed length
+pub trait Embedder: Send + Sync
+{
+    /// One vector per text, in order
+    fn embed(
+        &self,
+        texts: &[String],
+    ) -> Result<Vec<Vec<f32>>>;
+}
+
+/// Runs a configured command as the embedder, once per batch
+pub struct CommandEmbedder
+{
+    /// Program and arguments
+    command: Vec<String>,
+    /// Most texts per request
+    batch: usize,
+}
+
+impl CommandEmbedder
+{
+    pub fn new(
+        command: Vec<String>,
+        batch: usize,
+    ) -> Result<Self>
+    {
+        if command.is_empty()
+        {
+            bail!("[symbols.embeddings] has an empty command");
+        }
+        Ok(Self { command, batch: batch.max(1) })
+    }
+
+    /// One request for `texts`
+    fn request(
+        &self,
+        texts: &[String],
+    ) -> Result<Vec<Vec<f32>>>
+    {
+        let program = &self.command[0];
+        let mut child = Command::new(program)
+            .args(&self.command[1..])
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .with_context(|| format!("spawn embedder '{program}'"))?;
+
+        let mut line = serde_json::to_string(&Request { protocol: PROTOCOL_VERSION, texts })?;
+        line.push('\n');
+        if let Some(mut stdin) = child
+            .stdin
+            .take()
+        {
+            // An embedder may exit without reading; its status says why
+            let _ = stdin.write_all(line.as_bytes());
+        }
+
+        let output = child
+            .wait_with_output()
+            .with_context(|| format!("wait for embedder '{program}'"))?;
+        if !output
+            .status
+            .success()
+        {
+            bail!(
+                "embedder '{program}' failed: {}",
+                String::from_utf8_lossy(&output.stderr).trim()
+            );
+        }
+        let vectors = String::from_utf8_lossy(&output.stdout)
+            .lines()
+            .filter(|l| {
+                !l.trim()
+                    .is_empty()
+            })
+            .map(|l| {
+                serde_json::from_str::<Vec<f32>>(l)
+                    .with_context(|| format!("embedder '{program}' sent a bad vector: {l}"))
+            })
+            .collect::<Result<Vec<_>>>()?;
+        if vectors.len() != texts.len()
+        {
+            bail!(
+                "embedder '{program}' sent {} vectors for {} texts",
+                vectors.len(),
+                texts.len()
+            );
+        }
+        Ok(vectors)
+    }
+}
+
+/// Request line written to the embedder's stdin
+#[derive(Serialize)]
+struct Request<'a>
+{
+    protocol: u32,
+    texts: &'a [String],
+}
+
+impl Embedder for CommandEmbedder
+{
+    fn embed(
+        &self,
+        texts: &[String],
+    ) -> Result<Vec<Vec<f32>>>
+    {
+        let mut out = Vec::with_capacity(texts.len());
+        for batch in texts.chunks(self.batch)
+        {
+            out.extend(self.request(batch)?);
+        }
+        Ok(out)
+    }
+}
+
+fn global() -> &'static RwLock<Option<Arc<dyn Embedder>>>
+{
+    static EMBEDDER: OnceLock<RwLock<Option<Arc<dyn Embedder>>>> = OnceLock::new();
+    EMBEDDER.get_or_init(|| {
+        let configured = load_config()
+            .ok()
+            .and_then(|cfg| {
+                cfg.symbols
+                    .embeddings
+            })
+            .and_then(|cfg| CommandEmbedder::new(cfg.command, cfg.batch).ok())
+            .map(|e| Arc::new(e) as Arc<dyn Embedder>);
+        RwLock::new(configured)
+    })
+}
+
+/// Use `embedder` for the rest of the process, replacing the configured one
+pub fn register_embedder(embedder: Arc<dyn Embedder>)
+{
+    *global()
+        .write()
+        .unwrap_or_else(|e| e.into_inner()) = Some(embedder);
+}
+
+/// The registered or configured embedder, if any
+pub fn embedder() -> Option<Arc<dyn Embedder>>
+{
+    global()
+        .read()
+        .unwrap_or_else(|e| e.into_inner())
+        .clone()
+}
+
+/// Vector of one symbol, as written next to the index
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SymbolEmbedding
+{
+    /// File path relative to project root
+    pub file: PathBuf,
+
+    /// Qualified name of the symbol
+    pub qualified_name: String,
+
+    /// 1-based start line, telling same-named symbols apart
+    pub start_line: usize,
+
+    /// xxh64 of the embedded text; a vector is reused while it matches
+    pub digest: String,
+
+    /// The embedding itself
+    pub vector: Vec<f32>,
+}
+
+/// Embeddings kept next to a JSONL index: `symbols.jsonl` →
+/// `symbols.embeddings.jsonl`
+pub fn embeddings_path(jsonl: &Path) -> PathBuf
+{
+    let stem = jsonl
+        .file_stem()
+        .map_or_else(|| "symbols".into(), |s| s.to_string_lossy());
+    jsonl.with_file_name(format!("{stem}.embeddings.jsonl"))
+}
+
+/// Text a symbol is embedded from: kind and qualified name, then its
+/// signature and doc when recorded
+pub fn embedding_text(s: &Symbol) -> String
+{
+    let mut text = format!(
+        "{} {}",
+        s.kind
+            .label(),
+        s.qualified_name
+    );
+    for part in [
+        s.signature
+            .as_deref(),
+        s.doc
+            .as_deref(),
+    ]
+    .into_iter()
+    .flatten()
+    {
+        text.push('\n');
+        text.push_str(part);
+    }
+    text
+}
+
+/// Vectors for `symbols`, in order, reusing the `previous` vector of any
+/// symbol whose text is unchanged and embedding the rest in one call
+pub fn embed_symbols(
+    symbols: &[Symbol],
+    previous: &[SymbolEmbedding],
+    embedder: &dyn Embedder,
+) -> Result<Vec<SymbolEmbedding>>
+{
+    let known: HashMap<(&Path, &str, &str), &[f32]> = previous
+        .iter()
+        .map(|e| {
+            (
+                (
+                    e.file
+                        .as_path(),
+                    e.qualified_name
+                        .as_str(),
+                    e.digest
+                        .as_str(),
+                ),
+                &e.vector[..],
+            )
+        })
+        .collect();
+
+    let mut out: Vec<SymbolEmbedding> = Vec::with_capacity(symbols.len());
+    let mut todo: Vec<(usize, String)> = Vec::new();
+    for s in symbols
+    {
+        let text = embedding_text(s);
+        let digest = format!("{:016x}", xxhash_rust::xxh64::xxh64(text.as_bytes(), 0));
+        let vector = known
+            .get(&(
+                s.file
+                    .as_path(),
+                s.qualified_name
+                    .as_str(),
+                digest.as_str(),
+            ))
+            .map(|v| v.to_vec());
+        if vector.is_none()
+        {
+            todo.push((out.len(), text));
+        }
+        out.push(SymbolEmbedding {
+            file: s
+                .file
+                .clone(),
+            qualified_name: s
+                .qualified_name
+                .clone(),
+            start_line: s.start_line,
+            digest,
+            vector: vector.unwrap_or_default(),
+        });
+    }
+
+    if !todo.is_empty()
+    {
+        let texts: Vec<String> = todo
+            .iter()
+            .map(|(_, text)| text.clone())
+            .collect();
+        let vectors = embedder.embed(&texts)?;
+        if vectors.len() != texts.len()
+        {
+            bail!(
+                "embedder returned {} vectors for {} texts",
+                vectors.len(),
+                texts.len()
+            );
+        }
+        for ((at, _), vector) in todo
+            .into_iter()
+            .zip(vectors)
+        {
+            out[at].vector = vector;
+        }
+    }
+
+    // Mixed lengths mean the model changed under reused vectors
+    if let Some(first) = out.first()
+        && out
+            .iter()
+            .any(|e| {
+                e.vector
+                    .len()
+                    != first
+                        .vector
+                        .len()
+            })
+    {
+        bail!("embedder returned vectors of different lengths; delete the embeddings and rebuild");
+    }
+    Ok(out)
+}
+
+/// Cosine similarity of two vectors; 0 when their lengths differ or either
+/// is all zeros
+pub fn cosine(
+    a: &[f32],
+    b: &[f32],
+) -> f32
+{
+    if a.len() != b.len()
+    {
+        return 0.0;
+    }
+    let (mut dot, mut na, mut nb) = (0.0f32, 0.0f32, 0.0f32);
+    for (x, y) in a
+        .iter()
+        .zip(b)
+    {
+        dot += x * y;
+        na += x * x;
+        nb += y * y;
+    }
+    if na == 0.0 || nb == 0.0
+    {
+        0.0
+    }
+    else
+    {
+        dot / (na.sqrt() * nb.sqrt())
+    }
+}
+
+#[cfg(test)]
+mod tests
+{
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    use super::*;
+    use crate::core::symbols::SymbolKind;
+
+    /// Counts texts embedded; the vector is the text's length and vowel count
+    #[derive(Default)]
+    struct Counting(AtomicUsize);
+
+    impl Embedder for Counting
+    {
+        fn embed(
+            &self,
+            texts: &[String],
+        ) -> Result<Vec<Vec<f32>>>
+        {
+            self.0
+                .fetch_add(texts.len(), Ordering::SeqCst);
+            Ok(texts
+                .iter()
+                .map(|t| {
+                    let vowels = t
+                        .chars()
+                        .filter(|c| "aeiou".contains(*c))
+                        .count();
+                    vec![t.len() as f32, vowels as f32]
+                })
+                .collect())
+        }
+    }
+
+    fn sym(
+        qualified_name: &str,
+        doc: Option<&str>,
+    ) -> Symbol
+    {
+        Symbol {
+            file: PathBuf::from("src/lib.rs"),
+            lang: "rust".into(),
+            kind: SymbolKind::Function,
+            name: qualified_name.into(),
+            qualified_name: qualified_name.into(),
+            byte_start: 0,
+            byte_end: 1,
+            start_line: 1,
+            end_line: 1,
+            visibility: None,
+            doc: doc.map(Into::into),
+            signature: None,
+            package: None,
+            module: None,
+        }
+    }
+
+    #[test]
+    fn unchanged_symbols_keep_their_vectors()
+    {
+        let embedder = Counting::default();
+        let first = embed_symbols(&[sym("load", None), sym("save", None)], &[], &embedder).unwrap();
+        assert_eq!(
+            embedder
+                .0
+                .load(Ordering::SeqCst),
+            2
+        );
+        assert_eq!(first[0].vector, [13.0, 5.0]);
+
+        // Only the symbol whose doc changed is embedded again
+        let second = embed_symbols(
+            &[sym("load", None), sym("save", Some("Write it out"))],
+            &first,
+            &embedder,
+        )
+        .unwrap();
+        assert_eq!(
+            embedder
+                .0
+                .load(Ordering::SeqCst),
+            3
+        );
+        assert_eq!(second[0], first[0]);
+        assert_ne!(second[1].digest, first[1].digest);
+    }
+
+    #[test]
+    fn cosine_is_scale_free_and_safe()
+    {
+        assert!((cosine(&[1.0, 2.0], &[2.0, 4.0]) - 1.0).abs() < 1e-6);
+        assert!(cosine(&[1.0, 0.0], &[0.0, 3.0]).abs() < 1e-6);
+        assert_eq!(cosine(&[0.0, 0.0], &[1.0, 1.0]), 0.0);
+        assert_eq!(cosine(&[1.0], &[1.0, 1.0]), 0.0);
+    }
+}
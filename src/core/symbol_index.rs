@@ -1,16 +1,35 @@
 use std::{
-    collections::{BTreeMap, HashMap, HashSet},
+    cmp::Ordering,
+    collections::{BTreeMap, BTreeSet, HashMap, HashSet},
     fs::File,
-    io::{BufRead, BufReader},
+    io::{BufRead, BufReader, BufWriter, Write},
     path::{Path, PathBuf},
+    time::UNIX_EPOCH,
 };
 
-use anyhow::{Context, Result};
+use anyhow::{Context, Result, anyhow, bail};
+use memmap2::Mmap;
 use rayon::prelude::*;
 use regex::Regex;
-use serde::{Deserialize, Serialize};
+use serde::{Deserialize, Serialize, de::IntoDeserializer};
 
-use crate::core::symbols::{Symbol, SymbolKind};
+use crate::core::{
+    symbol_embeddings::{SymbolEmbedding, cosine, embeddings_path},
+    symbol_shards::{ShardManifest, shard_index_path},
+    symbols::{Reference, Relation, RelationKind, Symbol, SymbolFilter, SymbolKind, Visibility},
+};
+
+/// Leading bytes of a binary symbol index; the last one is the format version
+const BINARY_MAGIC: &[u8; 8] = b"RUPSYMI\x03";
+
+/// String-table slot meaning "absent" (no visibility, doc, signature, ...)
+const NO_STRING: u32 = u32::MAX;
+
+/// Most symbols an embedded query adds as candidates
+const SIMILAR_CANDIDATES: usize = 50;
+
+/// Least cosine similarity for an embedded query to add a candidate
+const MIN_SIMILARITY: f32 = 0.3;
 
 /// Options for symbol lookup and ranking
 #[derive(Debug, Clone, Default)]
@@ -33,6 +52,17 @@ pub struct LookupOptions<'a>
 
     /// Optional kind filters
     pub kinds: Option<&'a [SymbolKind]>,
+
+    /// Optional `--include`/`--exclude` path scope
+    pub scope: Option<&'a SymbolFilter>,
+
+    /// The query's embedding; with `semantic` and an embedded index, ranks
+    /// by similarity instead of shared name tokens
+    pub embedding: Option<&'a [f32]>,
+
+    /// Package whose symbols get the scope boost; defaults to the package
+    /// recorded for the anchor file
+    pub package: Option<&'a str>,
 }
 
 impl<'a> LookupOptions<'a>
@@ -59,6 +89,22 @@ pub struct SymbolIndex
     /// Maps file paths to indices of symbols in those files (sorted by start line)
     file_to_idxs: BTreeMap<PathBuf, Vec<usize>>,
 
+    /// Call sites from `rup symbols --refs`, in file order; empty without one
+    refs: Vec<Reference>,
+
+    /// Maps callee names to indices in `refs`
+    callee_to_refs: HashMap<String, Vec<usize>>,
+
+    /// Maps `(file, caller qualified name)` to indices in `refs`: the
+    /// outgoing call edges of each function
+    caller_to_refs: HashMap<(PathBuf, String), Vec<usize>>,
+
+    /// Type relations from `rup symbols`, in file order; empty without them
+    relations: Vec<Relation>,
+
+    /// Vectors from `rup symbols --embeddings`, by index in `symbols`
+    embeddings: HashMap<usize, Vec<f32>>,
+
     /// Regex used for tokenizing symbol names (snake/camel case)
     snake_re: Regex,
 }
@@ -73,55 +119,364 @@ pub struct RankedSymbol
     pub score: (u8, u8, u8, u8),
 }
 
+impl RankedSymbol
+{
+    /// Qualified name of the type or module holding the symbol (`Cache`
+    /// for `Cache::new`); empty at the top level
+    pub fn container(&self) -> &str
+    {
+        container_of(
+            &self
+                .symbol
+                .qualified_name,
+        )
+    }
+}
+
 impl SymbolIndex
 {
     /// Loads symbols from a JSONL file and builds the index.
     ///
-    /// - Reads each line as a JSON-encoded `Symbol`.
-    /// - Skips empty lines.
+    /// - Memory-maps the binary index next to it (`symbols.bin`) when that
+    ///   was written from this exact JSONL file, skipping JSON parsing.
+    /// - Otherwise reads each line as a JSON-encoded `Symbol`, skipping
+    ///   empty lines, and refreshes the binary index for next time.
     /// - Sorts symbols deterministically by file path and start line.
+    /// - Loads the call sites (`symbols.refs.jsonl`), type relations
+    ///   (`symbols.relations.jsonl`), and embeddings
+    ///   (`symbols.embeddings.jsonl`) next to it, if any.
     /// - Builds lookup maps for fast queries.
     pub fn load(jsonl: &Path) -> Result<Self>
     {
-        // Open the symbols file
-        let f = File::open(jsonl)
-            .with_context(|| format!("Failed to open symbols file: {}", jsonl.display()))?;
-        let reader = BufReader::new(f);
-        let mut symbols: Vec<Symbol> = Vec::new();
+        Self::load_focused(jsonl, &[])
+    }
 
-        // Parse each line as a Symbol
-        for (i, line) in reader
-            .lines()
-            .enumerate()
+    /// Like [`Self::load`], but when `jsonl` was written with `rup symbols
+    /// --shard`, reads only the shards holding the `focus` paths (relative
+    /// to the indexed root), or every shard without a focus
+    pub fn load_focused(
+        jsonl: &Path,
+        focus: &[PathBuf],
+    ) -> Result<Self>
+    {
+        let parts = match ShardManifest::load(jsonl)
         {
-            let line = line.with_context(|| format!("Failed to read line {}", i + 1))?;
-            if line
-                .trim()
-                .is_empty()
+            Some(manifest) =>
             {
-                continue;
+                let mut all = IndexParts::default();
+                for root in manifest.select(focus)
+                {
+                    all.extend(IndexParts::read(&shard_index_path(jsonl, root))?);
+                }
+                all.symbols
+                    .sort_by(index_order);
+                all
             }
-            let s: Symbol = serde_json::from_str(&line)
-                .with_context(|| format!("Failed to parse JSON on line {}", i + 1))?;
-            symbols.push(s);
-        }
+            None => IndexParts::read(jsonl)?,
+        };
+        let mut index = Self::from_sorted(parts.symbols).with_refs(parts.refs);
+        index.relations = parts.relations;
+        index.attach_embeddings(parts.embeddings);
+        Ok(index)
+    }
+
+    /// Attach vectors to the symbols they were computed for; ones whose
+    /// symbol is gone are dropped
+    fn attach_embeddings(
+        &mut self,
+        embeddings: Vec<SymbolEmbedding>,
+    )
+    {
+        let by_key: HashMap<(&Path, &str, usize), usize> = self
+            .symbols
+            .iter()
+            .enumerate()
+            .map(|(i, s)| ((s.file.as_path(), s.qualified_name.as_str(), s.start_line), i))
+            .collect();
+        let attached: Vec<(usize, Vec<f32>)> = embeddings
+            .into_iter()
+            .filter_map(|e| {
+                by_key
+                    .get(&(e.file.as_path(), e.qualified_name.as_str(), e.start_line))
+                    .map(|&i| (i, e.vector))
+            })
+            .collect();
+        self.embeddings = attached
+            .into_iter()
+            .collect();
+    }
+
+    /// Whether `rup symbols --embeddings` recorded vectors for this index
+    pub fn has_embeddings(&self) -> bool
+    {
+        !self
+            .embeddings
+            .is_empty()
+    }
+
+    /// Parse and sort every symbol in a JSONL file
+    fn load_jsonl(jsonl: &Path) -> Result<Vec<Symbol>>
+    {
+        let mut symbols: Vec<Symbol> = read_jsonl(jsonl)?;
 
         // Sort symbols by file path, then start_line, then end_line for deterministic order
-        symbols.sort_by(|a, b| {
-            (
-                a.file
-                    .clone(),
-                a.start_line,
-                a.end_line,
-            )
-                .cmp(&(
-                    b.file
+        symbols.sort_by(index_order);
+        Ok(symbols)
+    }
+
+    /// Attach call sites and index them by callee and by caller
+    fn with_refs(
+        mut self,
+        refs: Vec<Reference>,
+    ) -> Self
+    {
+        for (idx, r) in refs
+            .iter()
+            .enumerate()
+        {
+            self.callee_to_refs
+                .entry(
+                    r.name
                         .clone(),
-                    b.start_line,
-                    b.end_line,
-                ))
-        });
+                )
+                .or_default()
+                .push(idx);
+            if let Some(caller) = &r.caller
+            {
+                self.caller_to_refs
+                    .entry((
+                        r.file
+                            .clone(),
+                        caller.clone(),
+                    ))
+                    .or_default()
+                    .push(idx);
+            }
+        }
+        self.refs = refs;
+        self
+    }
+
+    /// Call sites of `name` (simple, or qualified with `::` or `.`) recorded
+    /// by `rup symbols --refs`, in file order; empty without a refs index
+    pub fn usages(
+        &self,
+        name: &str,
+    ) -> Vec<&Reference>
+    {
+        let simple = name
+            .rsplit([':', '.'])
+            .next()
+            .unwrap_or(name);
+        self.callee_to_refs
+            .get(simple)
+            .map(|idxs| {
+                idxs.iter()
+                    .map(|&i| &self.refs[i])
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    /// Whether type relations were loaded alongside the symbols
+    pub fn has_relations(&self) -> bool
+    {
+        !self
+            .relations
+            .is_empty()
+    }
+
+    /// Relations naming `name` as the trait, interface, or base type
+    pub fn subtypes(
+        &self,
+        name: &str,
+    ) -> Vec<&Relation>
+    {
+        self.relations
+            .iter()
+            .filter(|r| r.kind != RelationKind::ReExports && r.target == name)
+            .collect()
+    }
+
+    /// Relations naming `name` as the implementing or inheriting type
+    pub fn supertypes(
+        &self,
+        name: &str,
+    ) -> Vec<&Relation>
+    {
+        self.relations
+            .iter()
+            .filter(|r| r.kind != RelationKind::ReExports && r.source == name)
+            .collect()
+    }
+
+    /// `name` with everything it implements or extends, transitively, and
+    /// every type implementing or extending any of those, sorted
+    pub fn type_family(
+        &self,
+        name: &str,
+    ) -> Vec<String>
+    {
+        // Up to the roots, then down from each type on the way
+        let mut up: BTreeSet<&str> = BTreeSet::from([name]);
+        let mut todo = vec![name];
+        while let Some(t) = todo.pop()
+        {
+            for r in self.supertypes(t)
+            {
+                if up.insert(&r.target)
+                {
+                    todo.push(&r.target);
+                }
+            }
+        }
+
+        let mut family = up.clone();
+        let mut todo: Vec<&str> = up
+            .into_iter()
+            .collect();
+        while let Some(t) = todo.pop()
+        {
+            for r in self.subtypes(t)
+            {
+                if family.insert(&r.source)
+                {
+                    todo.push(&r.source);
+                }
+            }
+        }
+        family
+            .into_iter()
+            .map(str::to_string)
+            .collect()
+    }
+
+    /// Names `name` is a re-export alias of, following alias chains
+    /// (`pub use a::B as C` then `pub use x::C as D`: `D` gives `C`, `B`)
+    pub fn reexport_targets(
+        &self,
+        name: &str,
+    ) -> Vec<String>
+    {
+        let mut seen: BTreeSet<&str> = BTreeSet::from([name]);
+        let mut out = Vec::new();
+        let mut todo = vec![name];
+        while let Some(alias) = todo.pop()
+        {
+            for r in self
+                .relations
+                .iter()
+                .filter(|r| r.kind == RelationKind::ReExports && r.source == alias)
+            {
+                if seen.insert(&r.target)
+                {
+                    out.push(
+                        r.target
+                            .clone(),
+                    );
+                    todo.push(&r.target);
+                }
+            }
+        }
+        out
+    }
+
+    /// Whether call edges were loaded alongside the symbols
+    pub fn has_call_graph(&self) -> bool
+    {
+        !self
+            .caller_to_refs
+            .is_empty()
+    }
+
+    /// Innermost function or method spanning `line` of `file`
+    pub fn function_at(
+        &self,
+        file: &Path,
+        line: usize,
+    ) -> Option<&Symbol>
+    {
+        self.symbols_in_file(file)
+            .iter()
+            .map(|&i| &self.symbols[i])
+            .filter(|s| is_callable(s) && s.start_line <= line && line <= s.end_line)
+            .min_by_key(|s| s.end_line - s.start_line)
+    }
+
+    /// Functions one call edge away from `func`: the definitions of what it
+    /// calls, then the functions calling it, each once and in index order.
+    ///
+    /// Callees resolve by name, preferring a definition in the caller's file,
+    /// then one in its language, then any.
+    pub fn call_neighbors(
+        &self,
+        func: &Symbol,
+    ) -> Vec<&Symbol>
+    {
+        let mut out: BTreeSet<usize> = BTreeSet::new();
+
+        let key = (
+            func.file
+                .clone(),
+            func.qualified_name
+                .clone(),
+        );
+        for &ri in self
+            .caller_to_refs
+            .get(&key)
+            .into_iter()
+            .flatten()
+        {
+            let name = &self.refs[ri].name;
+            let defs: Vec<usize> = self
+                .name_to_idxs
+                .get(&name.to_ascii_lowercase())
+                .into_iter()
+                .flatten()
+                .copied()
+                .filter(|&i| is_callable(&self.symbols[i]) && &self.symbols[i].name == name)
+                .collect();
+            let same_file: Vec<usize> = defs
+                .iter()
+                .copied()
+                .filter(|&i| self.symbols[i].file == func.file)
+                .collect();
+            let same_lang: Vec<usize> = defs
+                .iter()
+                .copied()
+                .filter(|&i| self.symbols[i].lang == func.lang)
+                .collect();
+            let chosen = [same_file, same_lang, defs]
+                .into_iter()
+                .find(|d| !d.is_empty())
+                .unwrap_or_default();
+            out.extend(chosen);
+        }
+
+        for r in self.usages(&func.name)
+        {
+            let Some(caller) = &r.caller
+            else
+            {
+                continue;
+            };
+            out.extend(
+                self.symbols_in_file(&r.file)
+                    .iter()
+                    .copied()
+                    .filter(|&i| is_callable(&self.symbols[i]))
+                    .filter(|&i| &self.symbols[i].qualified_name == caller),
+            );
+        }
+
+        out.into_iter()
+            .map(|i| &self.symbols[i])
+            .collect()
+    }
 
+    /// Build lookup maps over symbols already in index order
+    fn from_sorted(symbols: Vec<Symbol>) -> Self
+    {
         // Build name-to-indices and file-to-indices maps
         let mut name_to_idxs: HashMap<String, Vec<usize>> = HashMap::new();
         let mut file_to_idxs: BTreeMap<PathBuf, Vec<usize>> = BTreeMap::new();
@@ -146,12 +501,17 @@ impl SymbolIndex
         }
 
         // Regex for tokenizing symbol names (snake/camel case)
-        Ok(Self {
+        Self {
             symbols,
             name_to_idxs,
             file_to_idxs,
+            refs: Vec::new(),
+            callee_to_refs: HashMap::new(),
+            caller_to_refs: HashMap::new(),
+            relations: Vec::new(),
+            embeddings: HashMap::new(),
             snake_re: Regex::new(r"[A-Za-z0-9]+").unwrap(),
-        })
+        }
     }
 
     pub fn all(&self) -> &[Symbol]
@@ -160,6 +520,10 @@ impl SymbolIndex
     }
 
     /// Lookup by query string and options. Returns ranked matches.
+    ///
+    /// A `Type::method` query (any `::` path) resolves to the symbols whose
+    /// qualified name ends with that path, when there are any; otherwise it
+    /// falls back to substring matching like any other query.
     pub fn lookup<'a>(
         &'a self,
         query: &str,
@@ -173,82 +537,19 @@ impl SymbolIndex
         }
         let ql = q.to_ascii_lowercase();
 
-        // Pre-collect candidate indices using quick filters to keep it fast.
-        let mut candidates: Vec<usize> = Vec::new();
-
-        // 1) Fast path: exact simple-name match
-        if let Some(ix) = self
-            .name_to_idxs
-            .get(&ql)
-        {
-            candidates.extend(
-                ix.iter()
-                    .copied(),
-            );
-        }
-
-        // 2) Substring/prefix in simple or qualified names
-        let ql_clone = ql.clone();
-        let more: Vec<usize> = self
-            .symbols
-            .par_iter()
-            .enumerate()
-            .filter(|(_, s)| {
-                let name = s
-                    .name
-                    .to_ascii_lowercase();
-                let qn = s
-                    .qualified_name
-                    .to_ascii_lowercase();
-                // Keep old substring behavior for candidate collection
-                // Semantic scoring will filter out noise later
-                name.starts_with(&ql_clone)
-                    || name.contains(&ql_clone)
-                    || qn.ends_with(&ql_clone)
-                    || qn.contains(&ql_clone)
-            })
-            .map(|(i, _)| i)
-            .collect();
-        candidates.extend(more);
-
-        // 3) Include all symbols from anchor directory for scope/proximity scoring
-        if let Some(anchor_dir) = opts
-            .anchor_file
-            .and_then(|p| p.parent())
+        // Nearest symbols to an embedded query, with their similarity
+        let similar = match opts.embedding
         {
-            let anchor_more: Vec<usize> = self
-                .symbols
-                .par_iter()
-                .enumerate()
-                .filter(|(_, s)| {
-                    s.file
-                        .starts_with(anchor_dir)
-                })
-                .map(|(i, _)| i)
-                .collect();
-            candidates.extend(anchor_more);
-        }
+            Some(query) if opts.semantic => self.similar(query),
+            _ => HashMap::new(),
+        };
 
-        // 3) If semantic, include fuzzy token matches
-        if opts.semantic
+        let mut candidates = self.path_candidates(&ql);
+        if candidates.is_empty()
         {
-            let tokens = self.tokens(&ql);
-            let sem_more: Vec<usize> = self
-                .symbols
-                .par_iter()
-                .enumerate()
-                .filter(|(_, s)| {
-                    self.token_hit(&tokens, &s.name) || self.token_hit(&tokens, &s.qualified_name)
-                })
-                .map(|(i, _)| i)
-                .collect();
-            candidates.extend(sem_more);
+            candidates = self.name_candidates(&ql, &opts, &similar);
         }
 
-        // Dedup & stable sort
-        candidates.sort();
-        candidates.dedup();
-
         // Optional kind filter
         if let Some(kinds) = opts.kinds
         {
@@ -259,6 +560,12 @@ impl SymbolIndex
             candidates.retain(|&i| set.contains(&self.symbols[i].kind));
         }
 
+        // Optional path scope
+        if let Some(scope) = opts.scope
+        {
+            candidates.retain(|&i| scope.allows_path(&self.symbols[i].file));
+        }
+
         // Compute scores and rank
         let anchor_dir = opts
             .anchor_file
@@ -266,18 +573,33 @@ impl SymbolIndex
                 p.parent()
                     .map(|x| x.to_path_buf())
             });
+        let anchor_crate = opts
+            .anchor_file
+            .and_then(crate_root);
+        let anchor_package = opts
+            .package
+            .or_else(|| {
+                opts.anchor_file
+                    .and_then(|f| self.package_of(f))
+            });
         let anchor_file = opts
             .anchor_file
             .map(|p| p.to_path_buf());
         let anchor_line = opts.anchor_line;
         let history = opts.history;
 
-        let mut ranked: Vec<RankedSymbol> = candidates
+        let mut ranked: Vec<(RankedSymbol, i32)> = candidates
             .into_iter()
             .map(|i| {
                 let s = &self.symbols[i];
-                let semantic = self.semantic_score(&ql, s);
-                let scope = self.scope_score(anchor_dir.as_ref(), &s.file);
+                let similarity = similar
+                    .get(&i)
+                    .copied()
+                    .unwrap_or(0.0);
+                let semantic = self
+                    .semantic_score(&ql, s)
+                    .max(similarity_tier(similarity));
+                let scope = self.scope_score(anchor_dir.as_ref(), anchor_crate, anchor_package, s);
                 let proximity = self.proximity_score(anchor_file.as_ref(), anchor_line, s);
                 let hist = if let Some(h) = history
                 {
@@ -287,14 +609,14 @@ impl SymbolIndex
                 {
                     0
                 };
-                RankedSymbol {
-                    symbol: s.clone(),
-                    score: (semantic, scope, proximity, hist),
-                }
+                // Finer than the tier, to order hits within it
+                let closeness = (similarity * 1000.0) as i32;
+                let score = (semantic, scope, proximity, hist);
+                (RankedSymbol { symbol: s.clone(), score }, closeness)
             })
             .collect();
 
-        ranked.sort_by_key(|it| {
+        ranked.sort_by_key(|(it, closeness)| {
             (
                 std::cmp::Reverse(
                     it.score
@@ -312,6 +634,9 @@ impl SymbolIndex
                     it.score
                         .3,
                 ), // history: higher first
+                std::cmp::Reverse(*closeness), // embedding similarity: higher first
+                it.container()
+                    .to_string(), // tiebreak: same container together
                 it.symbol
                     .file
                     .clone(), // tiebreak: path asc
@@ -326,38 +651,180 @@ impl SymbolIndex
         let limit = opts
             .limit
             .max(1);
-        ranked.truncate(limit);
         ranked
+            .into_iter()
+            .take(limit)
+            .map(|(it, _)| it)
+            .collect()
     }
 
-    fn tokens(
+    /// The symbols most similar to an embedded query, above a floor
+    fn similar(
         &self,
-        s: &str,
-    ) -> Vec<String>
+        query: &[f32],
+    ) -> HashMap<usize, f32>
     {
-        self.snake_re
-            .find_iter(s)
-            .map(|m| {
-                m.as_str()
-                    .to_ascii_lowercase()
-            })
+        let mut scored: Vec<(usize, f32)> = self
+            .embeddings
+            .par_iter()
+            .map(|(&i, vector)| (i, cosine(query, vector)))
+            .filter(|(_, sim)| *sim >= MIN_SIMILARITY)
+            .collect();
+        scored.sort_by(|a, b| {
+            b.1.total_cmp(&a.1)
+                .then(a.0.cmp(&b.0))
+        });
+        scored.truncate(SIMILAR_CANDIDATES);
+        scored
+            .into_iter()
             .collect()
     }
 
-    fn token_hit(
+    /// Symbols whose qualified name is, or ends at a segment boundary
+    /// with, the `Type::method` path `ql` (lowercase); empty unless `ql` is
+    /// a path
+    fn path_candidates(
         &self,
-        tokens: &[String],
-        name: &str,
-    ) -> bool
+        ql: &str,
+    ) -> Vec<usize>
     {
-        let nl = name.to_ascii_lowercase();
-        tokens
+        if !ql.contains("::")
+        {
+            return Vec::new();
+        }
+        self.symbols
+            .iter()
+            .enumerate()
+            .filter(|(_, s)| path_matches(&s.qualified_name, ql))
+            .map(|(i, _)| i)
+            .collect()
+    }
+
+    /// Candidates by simple or qualified name, plus the `similar` ones or
+    /// fuzzy token matches, in index order
+    fn name_candidates(
+        &self,
+        ql: &str,
+        opts: &LookupOptions,
+        similar: &HashMap<usize, f32>,
+    ) -> Vec<usize>
+    {
+        // Pre-collect candidate indices using quick filters to keep it fast.
+        let mut candidates: Vec<usize> = Vec::new();
+
+        // 1) Fast path: exact simple-name match
+        if let Some(ix) = self
+            .name_to_idxs
+            .get(ql)
+        {
+            candidates.extend(
+                ix.iter()
+                    .copied(),
+            );
+        }
+
+        // 2) Substring/prefix in simple or qualified names
+        let ql_clone = ql.to_string();
+        let more: Vec<usize> = self
+            .symbols
+            .par_iter()
+            .enumerate()
+            .filter(|(_, s)| {
+                let name = s
+                    .name
+                    .to_ascii_lowercase();
+                let qn = s
+                    .qualified_name
+                    .to_ascii_lowercase();
+                // Keep old substring behavior for candidate collection
+                // Semantic scoring will filter out noise later
+                name.starts_with(&ql_clone)
+                    || name.contains(&ql_clone)
+                    || qn.ends_with(&ql_clone)
+                    || qn.contains(&ql_clone)
+            })
+            .map(|(i, _)| i)
+            .collect();
+        candidates.extend(more);
+
+        // 3) Include all symbols from anchor directory for scope/proximity scoring
+        if let Some(anchor_dir) = opts
+            .anchor_file
+            .and_then(|p| p.parent())
+        {
+            let anchor_more: Vec<usize> = self
+                .symbols
+                .par_iter()
+                .enumerate()
+                .filter(|(_, s)| {
+                    s.file
+                        .starts_with(anchor_dir)
+                })
+                .map(|(i, _)| i)
+                .collect();
+            candidates.extend(anchor_more);
+        }
+
+        // 3) If semantic, include the embedding neighbours when there are
+        //    any, fuzzy token matches otherwise
+        if !similar.is_empty()
+        {
+            candidates.extend(
+                similar
+                    .keys()
+                    .copied(),
+            );
+        }
+        else if opts.semantic
+        {
+            let tokens = self.tokens(ql);
+            let sem_more: Vec<usize> = self
+                .symbols
+                .par_iter()
+                .enumerate()
+                .filter(|(_, s)| {
+                    self.token_hit(&tokens, &s.name) || self.token_hit(&tokens, &s.qualified_name)
+                })
+                .map(|(i, _)| i)
+                .collect();
+            candidates.extend(sem_more);
+        }
+
+        // Dedup & stable sort
+        candidates.sort();
+        candidates.dedup();
+        candidates
+    }
+
+    fn tokens(
+        &self,
+        s: &str,
+    ) -> Vec<String>
+    {
+        self.snake_re
+            .find_iter(s)
+            .map(|m| {
+                m.as_str()
+                    .to_ascii_lowercase()
+            })
+            .collect()
+    }
+
+    fn token_hit(
+        &self,
+        tokens: &[String],
+        name: &str,
+    ) -> bool
+    {
+        let nl = name.to_ascii_lowercase();
+        tokens
             .iter()
             .all(|t| nl.contains(t))
     }
 
     /// Compute a conservative semantic score for a symbol name.
-    /// 3 = exact (case-insensitive)
+    /// 3 = exact (case-insensitive), or a `Type::method` query matching
+    ///     the qualified name
     /// 2 = prefix (len >= 2)
     /// 1 = token/segment match across non-alnum splits (len >= 2)
     /// 0 = otherwise
@@ -382,7 +849,7 @@ impl SymbolIndex
             return 0; // no score
         }
         // Exact match → strongest signal
-        if n == q
+        if n == q || path_matches(&s.qualified_name, &q)
         {
             // exact match
             return 3; // score 3
@@ -407,30 +874,61 @@ impl SymbolIndex
 
     /// Computes scope score based on anchor directory and symbol file.
     ///
-    /// - Returns 1 if the symbol's file is within the anchor directory.
+    /// - Returns 2 if the symbol's file is within the anchor directory.
+    /// - Returns 1 if it is in the anchor's package, when both packages are
+    ///   recorded; else if it is in the anchor's crate (the same tree above
+    ///   `src`).
     /// - Returns 0 otherwise.
     fn scope_score(
         &self,
         anchor_dir: Option<&PathBuf>,
-        file: &Path,
+        anchor_crate: Option<&Path>,
+        anchor_package: Option<&str>,
+        s: &Symbol,
     ) -> u8
     {
         if let Some(dir) = anchor_dir
         {
             // Use string-based comparison for relative paths to avoid canonicalize issues
             let dir_str = dir.to_string_lossy();
-            let file_str = file.to_string_lossy();
+            let file_str = s
+                .file
+                .to_string_lossy();
 
             // Check if file path starts with directory path
             if file_str.starts_with(dir_str.as_ref())
             {
-                return 1;
+                return 2;
             }
         }
+        if let (Some(anchor), Some(package)) = (anchor_package, s.package.as_deref())
+        {
+            return u8::from(anchor == package);
+        }
+        if anchor_crate.is_some() && crate_root(&s.file) == anchor_crate
+        {
+            return 1;
+        }
 
         0
     }
 
+    /// Package recorded for the symbols of `file`
+    fn package_of(
+        &self,
+        file: &Path,
+    ) -> Option<&str>
+    {
+        self.file_to_idxs
+            .get(file)?
+            .iter()
+            .find_map(|&i| {
+                self.symbols[i]
+                    .package
+                    .as_deref()
+            })
+    }
+
     /// Computes proximity score based on anchor file and line.
     ///
     /// - Returns 3 if symbol is in anchor file (regardless of line distance)
@@ -495,3 +993,828 @@ impl SymbolIndex
             .unwrap_or(&[])
     }
 }
+
+/// Distinct containers of the exact-name hits for a plain `query`, in rank
+/// order; more than one means a `Type::name` query would pick between them
+pub fn exact_containers<'a>(
+    query: &str,
+    hits: &'a [RankedSymbol],
+) -> Vec<&'a str>
+{
+    let mut out: Vec<&str> = Vec::new();
+    if query.contains("::")
+    {
+        return out;
+    }
+    for hit in hits
+    {
+        if hit
+            .symbol
+            .name
+            .eq_ignore_ascii_case(query.trim())
+            && !hit
+                .container()
+                .is_empty()
+            && !out.contains(&hit.container())
+        {
+            out.push(hit.container());
+        }
+    }
+    out
+}
+
+/// Semantic tier of an embedding similarity: 2 for a close match, 1 for
+/// a related one, 0 below that
+fn similarity_tier(similarity: f32) -> u8
+{
+    if similarity >= 0.8
+    {
+        2
+    }
+    else if similarity >= 0.5
+    {
+        1
+    }
+    else
+    {
+        0
+    }
+}
+
+/// Everything before the last `::` of a qualified name; empty at the top
+/// level
+fn container_of(qualified_name: &str) -> &str
+{
+    qualified_name
+        .rsplit_once("::")
+        .map_or("", |(container, _)| container)
+}
+
+/// Whether `qualified_name` is, or ends at a `::` boundary with, the
+/// lowercase path `query`
+fn path_matches(
+    qualified_name: &str,
+    query: &str,
+) -> bool
+{
+    if !query.contains("::")
+    {
+        return false;
+    }
+    let qn = qualified_name.to_ascii_lowercase();
+    qn == query
+        || qn
+            .strip_suffix(query)
+            .is_some_and(|rest| rest.ends_with("::"))
+}
+
+/// Root of the crate or package holding `file`: the directory above its
+/// nearest `src` ancestor, if it has one
+fn crate_root(file: &Path) -> Option<&Path>
+{
+    file.ancestors()
+        .skip(1)
+        .find(|dir| dir.file_name() == Some("src".as_ref()))
+        .and_then(Path::parent)
+}
+
+/// Functions and methods take part in the call graph
+fn is_callable(s: &Symbol) -> bool
+{
+    matches!(s.kind, SymbolKind::Function | SymbolKind::Method)
+}
+
+/// Every record of a JSONL file, skipping empty lines
+pub(crate) fn read_jsonl<T: for<'de> Deserialize<'de>>(path: &Path) -> Result<Vec<T>>
+{
+    // Open the file
+    let f = File::open(path)
+        .with_context(|| format!("Failed to open symbols file: {}", path.display()))?;
+    let reader = BufReader::new(f);
+    let mut out = Vec::new();
+
+    // Parse each line as a record
+    for (i, line) in reader
+        .lines()
+        .enumerate()
+    {
+        let line = line.with_context(|| format!("Failed to read line {}", i + 1))?;
+        if line
+            .trim()
+            .is_empty()
+        {
+            continue;
+        }
+        out.push(
+            serde_json::from_str(&line)
+                .with_context(|| format!("Failed to parse JSON on line {}", i + 1))?,
+        );
+    }
+    Ok(out)
+}
+
+/// Call sites kept next to a JSONL index: `symbols.jsonl` → `symbols.refs.jsonl`
+pub fn refs_path(jsonl: &Path) -> PathBuf
+{
+    let stem = jsonl
+        .file_stem()
+        .map_or_else(|| "symbols".into(), |s| s.to_string_lossy());
+    jsonl.with_file_name(format!("{stem}.refs.jsonl"))
+}
+
+/// Type relations kept next to a JSONL index: `symbols.jsonl` →
+/// `symbols.relations.jsonl`
+pub fn relations_path(jsonl: &Path) -> PathBuf
+{
+    let stem = jsonl
+        .file_stem()
+        .map_or_else(|| "symbols".into(), |s| s.to_string_lossy());
+    jsonl.with_file_name(format!("{stem}.relations.jsonl"))
+}
+
+/// Records behind one JSONL index: its symbols and the files next to it
+#[derive(Default)]
+struct IndexParts
+{
+    symbols: Vec<Symbol>,
+    refs: Vec<Reference>,
+    relations: Vec<Relation>,
+    embeddings: Vec<SymbolEmbedding>,
+}
+
+impl IndexParts
+{
+    fn read(jsonl: &Path) -> Result<Self>
+    {
+        let refs_file = refs_path(jsonl);
+        let refs = if refs_file.exists() { read_jsonl(&refs_file)? } else { Vec::new() };
+        let relations_file = relations_path(jsonl);
+        let relations =
+            if relations_file.exists() { read_jsonl(&relations_file)? } else { Vec::new() };
+        let embeddings_file = embeddings_path(jsonl);
+        let embeddings =
+            if embeddings_file.exists() { read_jsonl(&embeddings_file)? } else { Vec::new() };
+
+        // A fresh binary index is already sorted; a corrupt one is rebuilt
+        let symbols = match load_binary(jsonl)
+        {
+            Ok(Some(symbols)) => symbols,
+            _ =>
+            {
+                let symbols = SymbolIndex::load_jsonl(jsonl)?;
+                // Best effort: a read-only checkout still loads from JSONL
+                let _ = write_binary(&symbols, jsonl);
+                symbols
+            }
+        };
+        Ok(Self { symbols, refs, relations, embeddings })
+    }
+
+    fn extend(
+        &mut self,
+        other: Self,
+    )
+    {
+        self.symbols
+            .extend(other.symbols);
+        self.refs
+            .extend(other.refs);
+        self.relations
+            .extend(other.relations);
+        self.embeddings
+            .extend(other.embeddings);
+    }
+}
+
+/// Index order: file path, then start line, then end line
+fn index_order(
+    a: &Symbol,
+    b: &Symbol,
+) -> Ordering
+{
+    (&a.file, a.start_line, a.end_line).cmp(&(&b.file, b.start_line, b.end_line))
+}
+
+/// Binary index kept next to a JSONL one: `symbols.jsonl` → `symbols.bin`
+pub fn binary_path(jsonl: &Path) -> PathBuf
+{
+    jsonl.with_extension("bin")
+}
+
+/// Size and mtime of the JSONL index, recorded in the binary one so a copy
+/// left behind by an older `rup symbols` run is never trusted
+fn source_fingerprint(jsonl: &Path) -> Result<(u64, u64)>
+{
+    let meta = std::fs::metadata(jsonl)
+        .with_context(|| format!("Failed to stat symbols file: {}", jsonl.display()))?;
+    let mtime = meta
+        .modified()?
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_nanos() as u64)
+        .unwrap_or(0);
+    Ok((meta.len(), mtime))
+}
+
+/// Write the binary index for `symbols` (any order) next to `jsonl`.
+///
+/// Layout, little-endian:
+///
+/// ```text
+/// magic[8] source_len:u64 source_mtime:u64 strings:u32 symbols:u32
+/// strings × (len:u32 utf8[len])
+/// symbols × (file lang kind name qualified_name:u32
+///            byte_start byte_end:u64 start_line end_line:u32
+///            visibility doc signature package module:u32)
+/// ```
+///
+/// Every text field, kind and visibility included (by their JSONL names),
+/// is an index into the deduplicated string table, so symbols are fixed
+/// 64-byte records stored in index order.
+pub fn write_binary(
+    symbols: &[Symbol],
+    jsonl: &Path,
+) -> Result<()>
+{
+    let (source_len, source_mtime) = source_fingerprint(jsonl)?;
+
+    let mut order: Vec<usize> = (0..symbols.len()).collect();
+    order.sort_by(|&a, &b| index_order(&symbols[a], &symbols[b]));
+
+    let mut strings: Vec<String> = Vec::new();
+    let mut slots: HashMap<String, u32> = HashMap::new();
+    let mut intern = |text: String| -> u32 {
+        *slots
+            .entry(text)
+            .or_insert_with_key(|text| {
+                strings.push(text.clone());
+                (strings.len() - 1) as u32
+            })
+    };
+
+    let mut records = Vec::with_capacity(symbols.len() * 64);
+    for &i in &order
+    {
+        let s = &symbols[i];
+        let fields = [
+            intern(
+                s.file
+                    .to_string_lossy()
+                    .into_owned(),
+            ),
+            intern(
+                s.lang
+                    .clone(),
+            ),
+            intern(label(&s.kind)),
+            intern(
+                s.name
+                    .clone(),
+            ),
+            intern(
+                s.qualified_name
+                    .clone(),
+            ),
+        ];
+        for f in fields
+        {
+            records.extend_from_slice(&f.to_le_bytes());
+        }
+        records.extend_from_slice(&(s.byte_start as u64).to_le_bytes());
+        records.extend_from_slice(&(s.byte_end as u64).to_le_bytes());
+        records.extend_from_slice(&(s.start_line as u32).to_le_bytes());
+        records.extend_from_slice(&(s.end_line as u32).to_le_bytes());
+        let visibility = s
+            .visibility
+            .as_ref()
+            .map_or(NO_STRING, |v| intern(label(v)));
+        let doc = s
+            .doc
+            .clone()
+            .map_or(NO_STRING, &mut intern);
+        let signature = s
+            .signature
+            .clone()
+            .map_or(NO_STRING, &mut intern);
+        let package = s
+            .package
+            .clone()
+            .map_or(NO_STRING, &mut intern);
+        let module = s
+            .module
+            .clone()
+            .map_or(NO_STRING, &mut intern);
+        for slot in [visibility, doc, signature, package, module]
+        {
+            records.extend_from_slice(&slot.to_le_bytes());
+        }
+    }
+
+    // Write beside the target and rename, so readers never see half a file
+    let path = binary_path(jsonl);
+    let tmp = path.with_extension("bin.tmp");
+    let file =
+        File::create(&tmp).with_context(|| format!("Failed to create {}", tmp.display()))?;
+    let mut w = BufWriter::new(file);
+    w.write_all(BINARY_MAGIC)?;
+    w.write_all(&source_len.to_le_bytes())?;
+    w.write_all(&source_mtime.to_le_bytes())?;
+    w.write_all(&(strings.len() as u32).to_le_bytes())?;
+    w.write_all(&(symbols.len() as u32).to_le_bytes())?;
+    for s in &strings
+    {
+        w.write_all(&(s.len() as u32).to_le_bytes())?;
+        w.write_all(s.as_bytes())?;
+    }
+    w.write_all(&records)?;
+    w.flush()
+        .with_context(|| format!("Failed to write {}", tmp.display()))?;
+    drop(w);
+    std::fs::rename(&tmp, &path)
+        .with_context(|| format!("Failed to replace {}", path.display()))?;
+    Ok(())
+}
+
+/// Symbols from the binary index next to `jsonl`, in index order; `None`
+/// when there is none or it was written from a different JSONL file
+pub fn load_binary(jsonl: &Path) -> Result<Option<Vec<Symbol>>>
+{
+    let path = binary_path(jsonl);
+    let Ok(file) = File::open(&path)
+    else
+    {
+        return Ok(None);
+    };
+    // Safety: the index is only read, and is replaced by rename, not in place
+    let map = unsafe { Mmap::map(&file) }
+        .with_context(|| format!("Failed to memory-map {}", path.display()))?;
+
+    let mut r = ByteReader { bytes: &map, pos: 0 };
+    if r.take(BINARY_MAGIC.len())? != BINARY_MAGIC
+    {
+        bail!("{} is not a binary symbol index", path.display());
+    }
+    if (r.u64()?, r.u64()?) != source_fingerprint(jsonl)?
+    {
+        return Ok(None);
+    }
+    let string_count = r.u32()? as usize;
+    let symbol_count = r.u32()? as usize;
+
+    let mut strings: Vec<&str> = Vec::with_capacity(string_count);
+    for _ in 0..string_count
+    {
+        let len = r.u32()? as usize;
+        strings.push(std::str::from_utf8(r.take(len)?).context("invalid UTF-8 in symbol index")?);
+    }
+    let text = |slot: u32| -> Result<&str> {
+        strings
+            .get(slot as usize)
+            .copied()
+            .ok_or_else(|| anyhow!("string slot {slot} out of range in symbol index"))
+    };
+
+    // Kinds and visibilities repeat; parse each distinct label once
+    let mut kinds: HashMap<u32, SymbolKind> = HashMap::new();
+    let mut visibilities: HashMap<u32, Visibility> = HashMap::new();
+
+    let mut symbols = Vec::with_capacity(symbol_count);
+    for _ in 0..symbol_count
+    {
+        let (file, lang, kind, name, qualified_name) =
+            (r.u32()?, r.u32()?, r.u32()?, r.u32()?, r.u32()?);
+        let (byte_start, byte_end) = (r.u64()? as usize, r.u64()? as usize);
+        let (start_line, end_line) = (r.u32()? as usize, r.u32()? as usize);
+        let (visibility, doc, signature) = (r.u32()?, r.u32()?, r.u32()?);
+        let (package, module) = (r.u32()?, r.u32()?);
+        let optional = |slot: u32| -> Result<Option<String>> {
+            match slot
+            {
+                NO_STRING => Ok(None),
+                slot => Ok(Some(text(slot)?.to_string())),
+            }
+        };
+
+        let kind = match kinds.get(&kind)
+        {
+            Some(k) => k.clone(),
+            None =>
+            {
+                let parsed = parse_label::<SymbolKind>(text(kind)?)?;
+                kinds.insert(kind, parsed.clone());
+                parsed
+            }
+        };
+        let visibility = match visibility
+        {
+            NO_STRING => None,
+            slot => Some(match visibilities.get(&slot)
+            {
+                Some(v) => v.clone(),
+                None =>
+                {
+                    let parsed = parse_label::<Visibility>(text(slot)?)?;
+                    visibilities.insert(slot, parsed.clone());
+                    parsed
+                }
+            }),
+        };
+        symbols.push(Symbol {
+            file: PathBuf::from(text(file)?),
+            lang: text(lang)?.to_string(),
+            kind,
+            name: text(name)?.to_string(),
+            qualified_name: text(qualified_name)?.to_string(),
+            byte_start,
+            byte_end,
+            start_line,
+            end_line,
+            visibility,
+            doc: optional(doc)?,
+            signature: optional(signature)?,
+            package: optional(package)?,
+            module: optional(module)?,
+        });
+    }
+    Ok(Some(symbols))
+}
+
+/// JSONL name of a kind or visibility
+fn label<T: Serialize>(value: &T) -> String
+{
+    serde_json::to_value(value)
+        .ok()
+        .and_then(|v| {
+            v.as_str()
+                .map(str::to_string)
+        })
+        .unwrap_or_default()
+}
+
+/// Kind or visibility from its JSONL name
+fn parse_label<'de, T: Deserialize<'de>>(label: &'de str) -> Result<T>
+{
+    T::deserialize(label.into_deserializer())
+        .map_err(|e: serde::de::value::Error| {
+            anyhow!("unknown label '{label}' in symbol index: {e}")
+        })
+}
+
+/// Little-endian cursor over a binary index
+struct ByteReader<'a>
+{
+    bytes: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> ByteReader<'a>
+{
+    fn take(
+        &mut self,
+        n: usize,
+    ) -> Result<&'a [u8]>
+    {
+        let end = self
+            .pos
+            .checked_add(n)
+            .filter(|&end| end <= self.bytes.len())
+            .ok_or_else(|| anyhow!("truncated symbol index"))?;
+        let out = &self.bytes[self.pos..end];
+        self.pos = end;
+        Ok(out)
+    }
+
+    fn u32(&mut self) -> Result<u32>
+    {
+        Ok(u32::from_le_bytes(self.take(4)?.try_into()?))
+    }
+
+    fn u64(&mut self) -> Result<u64>
+    {
+        Ok(u64::from_le_bytes(self.take(8)?.try_into()?))
+    }
+}
+
+#[cfg(test)]
+mod tests
+{
+    use super::*;
+
+    fn sym(
+        file: &str,
+        name: &str,
+        start_line: usize,
+    ) -> Symbol
+    {
+        Symbol {
+            file: PathBuf::from(file),
+            lang: "rust".to_string(),
+            kind: SymbolKind::Function,
+            name: name.to_string(),
+            qualified_name: format!("crate::{name}"),
+            byte_start: start_line * 10,
+            byte_end: start_line * 10 + 9,
+            start_line,
+            end_line: start_line + 1,
+            visibility: None,
+            doc: None,
+            signature: None,
+            package: None,
+            module: None,
+        }
+    }
+
+    fn write_jsonl(
+        path: &Path,
+        symbols: &[Symbol],
+    )
+    {
+        let lines: Vec<String> = symbols
+            .iter()
+            .map(|s| serde_json::to_string(s).unwrap())
+            .collect();
+        std::fs::write(path, lines.join("\n") + "\n").unwrap();
+    }
+
+    #[test]
+    fn binary_index_round_trips_in_index_order()
+    {
+        let dir = tempfile::tempdir().unwrap();
+        let jsonl = dir
+            .path()
+            .join("symbols.jsonl");
+        let mut public = sym("src/b.rs", "beta", 3);
+        public.kind = SymbolKind::Struct;
+        public.visibility = Some(Visibility::Public);
+        public.doc = Some("The beta.".to_string());
+        public.signature = Some("pub struct Beta<T>".to_string());
+        public.package = Some("beta-crate".to_string());
+        public.module = Some("beta_crate::b".to_string());
+        let symbols = vec![public, sym("src/a.rs", "alpha", 7), sym("src/a.rs", "gamma", 1)];
+        write_jsonl(&jsonl, &symbols);
+
+        write_binary(&symbols, &jsonl).unwrap();
+        let loaded = load_binary(&jsonl)
+            .unwrap()
+            .expect("fresh binary index");
+
+        let mut expected = SymbolIndex::load_jsonl(&jsonl).unwrap();
+        expected.sort_by(index_order);
+        assert_eq!(loaded, expected);
+        assert_eq!(loaded[2].doc.as_deref(), Some("The beta."));
+        assert_eq!(loaded[2].visibility, Some(Visibility::Public));
+        assert_eq!(loaded[2].signature.as_deref(), Some("pub struct Beta<T>"));
+        assert_eq!(loaded[0].signature, None);
+    }
+
+    #[test]
+    fn stale_or_corrupt_binary_falls_back_to_jsonl()
+    {
+        let dir = tempfile::tempdir().unwrap();
+        let jsonl = dir
+            .path()
+            .join("symbols.jsonl");
+        write_jsonl(&jsonl, &[sym("src/a.rs", "alpha", 1)]);
+        write_binary(&[sym("src/a.rs", "alpha", 1)], &jsonl).unwrap();
+
+        // A later `rup symbols` run rewrites the JSONL; the binary is stale
+        write_jsonl(&jsonl, &[sym("src/a.rs", "alpha", 1), sym("src/a.rs", "omega", 5)]);
+        assert!(
+            load_binary(&jsonl)
+                .unwrap()
+                .is_none()
+        );
+        let index = SymbolIndex::load(&jsonl).unwrap();
+        assert_eq!(
+            index
+                .all()
+                .len(),
+            2
+        );
+        // Loading refreshed the binary index
+        assert_eq!(
+            load_binary(&jsonl)
+                .unwrap()
+                .map(|s| s.len()),
+            Some(2)
+        );
+
+        std::fs::write(binary_path(&jsonl), b"garbage").unwrap();
+        assert!(load_binary(&jsonl).is_err());
+        assert_eq!(
+            SymbolIndex::load(&jsonl)
+                .unwrap()
+                .all()
+                .len(),
+            2
+        );
+    }
+
+    #[test]
+    fn usages_come_from_the_refs_index()
+    {
+        let dir = tempfile::tempdir().unwrap();
+        let jsonl = dir
+            .path()
+            .join("symbols.jsonl");
+        write_jsonl(&jsonl, &[sym("src/a.rs", "alpha", 1)]);
+        let call = |caller: &str, line: usize| Reference {
+            file: PathBuf::from("src/a.rs"),
+            lang: "rust".to_string(),
+            name: "alpha".to_string(),
+            caller: Some(format!("crate::{caller}")),
+            byte_start: line * 10,
+            byte_end: line * 10 + 5,
+            line,
+        };
+        let refs = [call("beta", 4), call("gamma", 9)];
+        let lines: Vec<String> = refs
+            .iter()
+            .map(|r| serde_json::to_string(r).unwrap())
+            .collect();
+        std::fs::write(refs_path(&jsonl), lines.join("\n")).unwrap();
+        assert_eq!(refs_path(&jsonl), dir.path().join("symbols.refs.jsonl"));
+
+        let index = SymbolIndex::load(&jsonl).unwrap();
+        let callers: Vec<Option<&str>> = index
+            .usages("crate::alpha")
+            .iter()
+            .map(|r| r.caller.as_deref())
+            .collect();
+        assert_eq!(callers, [Some("crate::beta"), Some("crate::gamma")]);
+        assert!(
+            index
+                .usages("omega")
+                .is_empty()
+        );
+    }
+
+    #[test]
+    fn call_neighbors_follow_edges_both_ways()
+    {
+        let call = |file: &str, caller: &str, name: &str| Reference {
+            file: PathBuf::from(file),
+            lang: "rust".to_string(),
+            name: name.to_string(),
+            caller: Some(format!("crate::{caller}")),
+            ..Reference::default()
+        };
+        let mut symbols = vec![
+            sym("src/a.rs", "alpha", 1),
+            sym("src/a.rs", "helper", 5),
+            sym("src/b.rs", "helper", 1),
+            sym("src/b.rs", "beta", 5),
+        ];
+        symbols.sort_by(index_order);
+        let index = SymbolIndex::from_sorted(symbols).with_refs(vec![
+            call("src/a.rs", "alpha", "helper"),
+            call("src/b.rs", "beta", "alpha"),
+        ]);
+        assert!(index.has_call_graph());
+
+        let alpha = index
+            .function_at(Path::new("src/a.rs"), 2)
+            .expect("alpha spans line 2");
+        assert_eq!(alpha.name, "alpha");
+        let neighbors: Vec<(&Path, &str)> = index
+            .call_neighbors(alpha)
+            .iter()
+            .map(|s| (s.file.as_path(), s.name.as_str()))
+            .collect();
+        // The same-file `helper` wins over the one in src/b.rs
+        assert_eq!(neighbors, [(Path::new("src/a.rs"), "helper"), (Path::new("src/b.rs"), "beta")]);
+        assert!(
+            index
+                .function_at(Path::new("src/a.rs"), 3)
+                .is_none()
+        );
+    }
+
+    #[test]
+    fn type_family_walks_relations_both_ways()
+    {
+        let rel = |kind, source: &str, target: &str| {
+            let mut r = Relation::new(kind, source, target, 0, 0);
+            r.file = PathBuf::from("src/shapes.rs");
+            r
+        };
+        let dir = tempfile::tempdir().unwrap();
+        let jsonl = dir
+            .path()
+            .join("symbols.jsonl");
+        write_jsonl(&jsonl, &[sym("src/shapes.rs", "area", 1)]);
+        let relations = [
+            rel(RelationKind::Extends, "Shape", "Named"),
+            rel(RelationKind::Implements, "Circle", "Shape"),
+            rel(RelationKind::Implements, "Square", "Shape"),
+            rel(RelationKind::Extends, "Unit", "Square"),
+            rel(RelationKind::Implements, "Circle", "Debug"),
+            rel(RelationKind::ReExports, "Form", "Shape"),
+            rel(RelationKind::ReExports, "Figure", "Form"),
+            rel(RelationKind::ReExports, "Form", "Figure"),
+        ];
+        let lines: Vec<String> = relations
+            .iter()
+            .map(|r| serde_json::to_string(r).unwrap())
+            .collect();
+        std::fs::write(relations_path(&jsonl), lines.join("\n")).unwrap();
+
+        let index = SymbolIndex::load(&jsonl).unwrap();
+        assert!(index.has_relations());
+        assert_eq!(index.subtypes("Shape").len(), 2);
+        // Up from Square to Shape and Named, then down to every implementor;
+        // Debug is only reachable upward from a sibling, so it stays out
+        assert_eq!(index.type_family("Square"), ["Circle", "Named", "Shape", "Square", "Unit"]);
+        assert_eq!(index.type_family("Widget"), ["Widget"]);
+        // Aliases are not types: they resolve through chains, cycles and all
+        assert_eq!(index.subtypes("Figure").len(), 0);
+        assert_eq!(index.reexport_targets("Figure"), ["Form", "Shape"]);
+        assert!(
+            index
+                .reexport_targets("Shape")
+                .is_empty()
+        );
+    }
+
+    #[test]
+    fn type_paths_pick_one_of_many_same_named_symbols()
+    {
+        let method = |file: &str, qualified_name: &str, line| {
+            let mut s = sym(file, "new", line);
+            s.kind = SymbolKind::Method;
+            s.qualified_name = qualified_name.to_string();
+            s
+        };
+        let mut symbols = vec![
+            method("crates/pool/src/pool.rs", "pool::Pool::new", 3),
+            method("src/cache.rs", "Cache::new", 5),
+            method("src/store/disk.rs", "store::Disk::new", 8),
+            method("src/store/mem.rs", "store::MemCache::new", 2),
+        ];
+        symbols.sort_by(index_order);
+        let index = SymbolIndex::from_sorted(symbols);
+        let names = |query: &str, anchor: Option<&Path>| -> Vec<String> {
+            let opts = LookupOptions { anchor_file: anchor, ..Default::default() };
+            index
+                .lookup(query, opts.with_limit(10))
+                .into_iter()
+                .map(|r| r.symbol.qualified_name)
+                .collect()
+        };
+
+        // A path resolves on segment boundaries only
+        assert_eq!(names("Cache::new", None), ["Cache::new"]);
+        assert_eq!(names("store::disk::NEW", None), ["store::Disk::new"]);
+        // The anchor's file and directory come first, then its crate
+        assert_eq!(names("new", Some(Path::new("src/store/mem.rs"))), [
+            "store::MemCache::new",
+            "store::Disk::new",
+            "Cache::new",
+            "pool::Pool::new",
+        ]);
+
+        let hits = index.lookup("new", LookupOptions::default().with_limit(10));
+        assert_eq!(exact_containers("new", &hits).len(), 4);
+        assert!(exact_containers("Cache::new", &hits).is_empty());
+    }
+
+    #[test]
+    fn recorded_packages_scope_layouts_without_src()
+    {
+        let owned = |file: &str, package: &str, line| {
+            let mut s = sym(file, "handle", line);
+            s.qualified_name = format!("{package}::handle");
+            s.package = Some(package.to_string());
+            s
+        };
+        let mut symbols = vec![
+            owned("services/api/db/conn.go", "api", 1),
+            owned("services/api/routes/user.go", "api", 2),
+            owned("services/web/routes/user.go", "web", 3),
+        ];
+        symbols.sort_by(index_order);
+        let index = SymbolIndex::from_sorted(symbols);
+        let files = |opts: LookupOptions| -> Vec<String> {
+            index
+                .lookup("handle", opts.with_limit(10))
+                .into_iter()
+                .map(|r| {
+                    r.symbol
+                        .file
+                        .display()
+                        .to_string()
+                })
+                .collect()
+        };
+
+        // No `src` directory to go by; the anchor's recorded package decides
+        let anchor = Some(Path::new("services/api/db/conn.go"));
+        assert_eq!(files(LookupOptions { anchor_file: anchor, ..Default::default() }), [
+            "services/api/db/conn.go",
+            "services/api/routes/user.go",
+            "services/web/routes/user.go",
+        ]);
+        let opts =
+            LookupOptions { anchor_file: anchor, package: Some("web"), ..Default::default() };
+        assert_eq!(files(opts)[1], "services/web/routes/user.go");
+    }
+}
@@ -0,0 +1,292 @@
+//! Package and module path of each indexed symbol
+//!
+//! The package is named by the nearest project manifest above the file,
+//! up to the scanned root: `[package] name` in `Cargo.toml`, `name` in
+//! `package.json` or `composer.json`, `[project] name` (or Poetry's) in
+//! `pyproject.toml`, and `module` in `go.mod`. A workspace manifest with no
+//! package of its own is passed over.
+//!
+//! The module path is the file's place in its package, spelled the way the
+//! language imports it: `roughup::core::symbols` for
+//! `src/core/symbols.rs`, `pkg.util` for Python, the import path of the
+//! directory for Go, `pkg/lib/util` for JavaScript and TypeScript. Other
+//! languages record only the package.
+
+use std::{
+    collections::HashMap,
+    path::{Component, Path, PathBuf},
+};
+
+use crate::core::symbols::Symbol;
+
+/// Package declared by one manifest
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Package
+{
+    name: String,
+    /// Directory holding the manifest, relative to the scanned root
+    dir: PathBuf,
+}
+
+/// Set `package` and `module` on every symbol, reading each directory's
+/// manifests once
+pub(crate) fn fill_owners(
+    symbols: &mut [Symbol],
+    root: &Path,
+)
+{
+    let mut packages: HashMap<PathBuf, Option<Package>> = HashMap::new();
+    for s in symbols
+    {
+        let Some(package) = owning_package(root, &s.file, &mut packages)
+        else
+        {
+            continue;
+        };
+        let rel = s
+            .file
+            .strip_prefix(&package.dir)
+            .unwrap_or(&s.file);
+        s.module = module_path(&s.lang, &package.name, rel);
+        s.package = Some(package.name);
+    }
+}
+
+/// Package of the nearest manifest above `file`, cached per directory
+fn owning_package(
+    root: &Path,
+    file: &Path,
+    cache: &mut HashMap<PathBuf, Option<Package>>,
+) -> Option<Package>
+{
+    let mut walked = Vec::new();
+    let mut found = None;
+    for dir in file
+        .ancestors()
+        .skip(1)
+    {
+        if let Some(known) = cache.get(dir)
+        {
+            found = known.clone();
+            break;
+        }
+        walked.push(dir.to_path_buf());
+        if let Some(name) = manifest_package(&root.join(dir))
+        {
+            found = Some(Package { name, dir: dir.to_path_buf() });
+            break;
+        }
+    }
+    for dir in walked
+    {
+        cache.insert(dir, found.clone());
+    }
+    found
+}
+
+/// Name of the package whose manifest is in `dir`, if any
+fn manifest_package(dir: &Path) -> Option<String>
+{
+    let read = |name: &str| std::fs::read_to_string(dir.join(name)).ok();
+    let toml_name = |text: String, tables: &[&[&str]]| -> Option<String> {
+        let doc: toml::Table = text
+            .parse()
+            .ok()?;
+        tables
+            .iter()
+            .find_map(|path| {
+                let mut table = &doc;
+                for key in *path
+                {
+                    table = table
+                        .get(*key)?
+                        .as_table()?;
+                }
+                table
+                    .get("name")?
+                    .as_str()
+                    .map(str::to_string)
+            })
+    };
+    let json_name = |text: String| -> Option<String> {
+        serde_json::from_str::<serde_json::Value>(&text)
+            .ok()?
+            .get("name")?
+            .as_str()
+            .map(str::to_string)
+    };
+
+    read("Cargo.toml")
+        .and_then(|t| toml_name(t, &[&["package"]]))
+        .or_else(|| read("package.json").and_then(json_name))
+        .or_else(|| {
+            read("pyproject.toml").and_then(|t| toml_name(t, &[&["project"], &["tool", "poetry"]]))
+        })
+        .or_else(|| {
+            read("go.mod").and_then(|t| {
+                t.lines()
+                    .find_map(|l| {
+                        l.trim()
+                            .strip_prefix("module ")
+                    })
+                    .map(|m| {
+                        m.trim()
+                            .trim_matches('"')
+                            .to_string()
+                    })
+            })
+        })
+        .or_else(|| read("composer.json").and_then(json_name))
+        .filter(|name| !name.is_empty())
+}
+
+/// Module path of `rel`, a file path relative to its package's directory
+fn module_path(
+    lang: &str,
+    package: &str,
+    rel: &Path,
+) -> Option<String>
+{
+    let dirs: Vec<&str> = rel
+        .parent()
+        .into_iter()
+        .flat_map(Path::components)
+        .filter_map(|c| {
+            match c
+            {
+                Component::Normal(part) => part.to_str(),
+                _ => None,
+            }
+        })
+        .collect();
+    let stem = rel
+        .file_stem()?
+        .to_str()?;
+
+    // The source root is not part of the import path
+    let strip_src = |dirs: &[&str]| -> Vec<String> {
+        let from = usize::from(dirs.first() == Some(&"src"));
+        dirs[from..]
+            .iter()
+            .map(|d| d.to_string())
+            .collect()
+    };
+    match lang
+    {
+        "rust" =>
+        {
+            let mut parts = vec![package.replace('-', "_")];
+            parts.extend(strip_src(&dirs));
+            if !matches!(stem, "lib" | "main" | "mod")
+            {
+                parts.push(stem.to_string());
+            }
+            Some(parts.join("::"))
+        }
+        "python" =>
+        {
+            let mut parts = strip_src(&dirs);
+            if stem != "__init__"
+            {
+                parts.push(stem.to_string());
+            }
+            (!parts.is_empty()).then(|| parts.join("."))
+        }
+        "go" =>
+        {
+            let mut parts = vec![package.to_string()];
+            parts.extend(
+                dirs.iter()
+                    .map(|d| d.to_string()),
+            );
+            Some(parts.join("/"))
+        }
+        "javascript" | "typescript" | "vue" | "svelte" =>
+        {
+            let mut parts = vec![package.to_string()];
+            parts.extend(
+                dirs.iter()
+                    .map(|d| d.to_string()),
+            );
+            if stem != "index"
+            {
+                parts.push(stem.to_string());
+            }
+            Some(parts.join("/"))
+        }
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests
+{
+    use super::*;
+
+    #[test]
+    fn packages_come_from_the_nearest_manifest()
+    {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        for sub in ["crates/core/src/parse", "web/src/lib", "py/src/tool"]
+        {
+            std::fs::create_dir_all(root.join(sub)).unwrap();
+        }
+        std::fs::write(
+            root.join("Cargo.toml"),
+            "[workspace]\nmembers = [\"crates/*\"]\n",
+        )
+        .unwrap();
+        std::fs::write(
+            root.join("crates/core/Cargo.toml"),
+            "[package]\nname = \"my-core\"\n",
+        )
+        .unwrap();
+        std::fs::write(root.join("web/package.json"), r#"{"name": "@acme/web"}"#).unwrap();
+        std::fs::write(
+            root.join("py/pyproject.toml"),
+            "[project]\nname = \"tool\"\n",
+        )
+        .unwrap();
+
+        let mut cache = HashMap::new();
+        let owner = |file: &str, cache: &mut HashMap<_, _>| {
+            owning_package(root, Path::new(file), cache).map(|p| (p.name, p.dir))
+        };
+        assert_eq!(
+            owner("crates/core/src/parse/expr.rs", &mut cache),
+            Some(("my-core".into(), PathBuf::from("crates/core")))
+        );
+        assert_eq!(
+            owner("web/src/lib/index.ts", &mut cache)
+                .unwrap()
+                .0,
+            "@acme/web"
+        );
+        assert_eq!(owner("build.rs", &mut cache), None);
+
+        let module = |lang: &str, package: &str, rel: &str| {
+            module_path(lang, package, Path::new(rel)).unwrap_or_default()
+        };
+        assert_eq!(
+            module("rust", "my-core", "src/parse/expr.rs"),
+            "my_core::parse::expr"
+        );
+        assert_eq!(
+            module("rust", "my-core", "src/parse/mod.rs"),
+            "my_core::parse"
+        );
+        assert_eq!(module("rust", "my-core", "src/lib.rs"), "my_core");
+        assert_eq!(module("python", "tool", "src/tool/__init__.py"), "tool");
+        assert_eq!(module("python", "tool", "src/tool/cli.py"), "tool.cli");
+        assert_eq!(
+            module("typescript", "@acme/web", "src/lib/index.ts"),
+            "@acme/web/src/lib"
+        );
+        assert_eq!(
+            module("go", "example.com/svc", "api/server.go"),
+            "example.com/svc/api"
+        );
+        assert_eq!(module("markdown", "tool", "README.md"), "");
+    }
+}
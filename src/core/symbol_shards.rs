@@ -0,0 +1,536 @@
+//! Sharded symbol index for monorepos, for `rup symbols --shard`
+//!
+//! Instead of one index for the whole root, each workspace member gets its
+//! own: the nearest directory below the root holding a project manifest
+//! (`Cargo.toml`, `package.json`, `go.mod`, ...), else the file's top-level
+//! directory. Files directly under the root share one shard.
+//!
+//! A manifest next to the index lists the shards (`symbols.jsonl` →
+//! `symbols.shards.json`), and each shard is a complete index of its own
+//! under `shards/` beside it (`crates/core` → `shards/crates__core.jsonl`,
+//! with its `.refs.jsonl`, `.bin`, and so on). Readers load only the shards
+//! they need, and `rup context` refreshes only those, so working in one
+//! crate never reads or rebuilds the rest. A plain `rup symbols` run
+//! replaces the shards with a single index.
+
+use std::{
+    collections::BTreeMap,
+    path::{Path, PathBuf},
+};
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    cli::{AppContext, SymbolsArgs, SymbolsFormat},
+    core::{
+        context::ContextAssembler,
+        symbol_embeddings::embeddings_path,
+        symbol_index::{binary_path, refs_path, relations_path},
+        symbols::{FileExtraction, extract_files, source_files_in, write_index},
+    },
+};
+
+/// Version recorded in the manifest; bumped when its shape changes
+pub const MANIFEST_VERSION: u32 = 1;
+
+/// Files marking a directory as a workspace member
+const MEMBER_MARKERS: &[&str] = &[
+    "Cargo.toml",
+    "package.json",
+    "pyproject.toml",
+    "go.mod",
+    "pom.xml",
+    "build.gradle",
+    "build.gradle.kts",
+    "composer.json",
+];
+
+/// Deepest directory searched for members, counted from the root
+const MEMBER_DEPTH: usize = 4;
+
+/// Directories never searched for members
+const SKIP_DIRS: &[&str] = &["target", "node_modules", "build", "dist", "vendor", "venv"];
+
+/// Shards of an index, by root directory relative to the scanned root;
+/// the empty path is the shard of files directly under it
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ShardManifest
+{
+    pub version: u32,
+    pub shards: Vec<PathBuf>,
+}
+
+impl ShardManifest
+{
+    /// The manifest next to `jsonl`, if the index there is sharded
+    pub fn load(jsonl: &Path) -> Option<Self>
+    {
+        let text = std::fs::read_to_string(manifest_path(jsonl)).ok()?;
+        serde_json::from_str::<Self>(&text)
+            .ok()
+            .filter(|m| m.version == MANIFEST_VERSION)
+    }
+
+    fn write(
+        &self,
+        jsonl: &Path,
+    ) -> Result<()>
+    {
+        let path = manifest_path(jsonl);
+        std::fs::write(&path, serde_json::to_string_pretty(self)? + "\n")
+            .with_context(|| format!("Failed to write {}", path.display()))
+    }
+
+    /// Shard holding `rel`, a path relative to the scanned root: the
+    /// longest shard root it lies under
+    pub fn shard_of(
+        &self,
+        rel: &Path,
+    ) -> Option<&Path>
+    {
+        self.shards
+            .iter()
+            .filter(|root| rel.starts_with(root))
+            .max_by_key(|root| {
+                root.components()
+                    .count()
+            })
+            .map(PathBuf::as_path)
+    }
+
+    /// Shards a run focused on `focus` paths reads: the shard holding each
+    /// path and every shard under it; all of them when none matches
+    pub fn select(
+        &self,
+        focus: &[PathBuf],
+    ) -> Vec<&Path>
+    {
+        let all = self
+            .shards
+            .iter()
+            .map(PathBuf::as_path);
+        let chosen: Vec<&Path> = all
+            .clone()
+            .filter(|root| {
+                focus
+                    .iter()
+                    .any(|f| self.shard_of(f) == Some(*root) || root.starts_with(f))
+            })
+            .collect();
+        if chosen.is_empty()
+        {
+            all.collect()
+        }
+        else
+        {
+            chosen
+        }
+    }
+}
+
+/// Manifest kept next to a sharded index: `symbols.jsonl` →
+/// `symbols.shards.json`
+pub fn manifest_path(jsonl: &Path) -> PathBuf
+{
+    let stem = jsonl
+        .file_stem()
+        .map_or_else(|| "symbols".into(), |s| s.to_string_lossy());
+    jsonl.with_file_name(format!("{stem}.shards.json"))
+}
+
+/// Index of the shard rooted at `root`: `crates/core` →
+/// `shards/crates__core.jsonl` next to `jsonl`
+pub fn shard_index_path(
+    jsonl: &Path,
+    root: &Path,
+) -> PathBuf
+{
+    let parts: Vec<String> = root
+        .components()
+        .map(|c| {
+            c.as_os_str()
+                .to_string_lossy()
+                .into_owned()
+        })
+        .collect();
+    let name = if parts.is_empty()
+    {
+        "_root".to_string()
+    }
+    else
+    {
+        parts.join("__")
+    };
+    jsonl.with_file_name(format!("shards/{name}.jsonl"))
+}
+
+/// Index whose side files show what the index at `jsonl` records (call
+/// sites, vectors): `jsonl` itself, or its first shard when sharded
+pub fn probe_path(jsonl: &Path) -> PathBuf
+{
+    ShardManifest::load(jsonl)
+        .and_then(|m| {
+            m.shards
+                .first()
+                .map(|root| shard_index_path(jsonl, root))
+        })
+        .unwrap_or_else(|| jsonl.to_path_buf())
+}
+
+/// Whether there is an index at `jsonl`, whole or sharded
+pub fn index_exists(jsonl: &Path) -> bool
+{
+    jsonl.exists() || manifest_path(jsonl).exists()
+}
+
+/// Workspace members under `root`, relative to it, outermost first
+pub fn workspace_members(root: &Path) -> Vec<PathBuf>
+{
+    fn visit(
+        root: &Path,
+        rel: &Path,
+        depth: usize,
+        out: &mut Vec<PathBuf>,
+    )
+    {
+        let Ok(entries) = std::fs::read_dir(root.join(rel))
+        else
+        {
+            return;
+        };
+        let mut dirs: Vec<PathBuf> = entries
+            .filter_map(|e| e.ok())
+            .filter(|e| {
+                e.file_type()
+                    .is_ok_and(|t| t.is_dir())
+            })
+            .map(|e| e.file_name())
+            .filter(|name| {
+                let name = name.to_string_lossy();
+                !name.starts_with('.') && !SKIP_DIRS.contains(&name.as_ref())
+            })
+            .map(|name| rel.join(name))
+            .collect();
+        dirs.sort();
+        for dir in dirs
+        {
+            if MEMBER_MARKERS
+                .iter()
+                .any(|m| {
+                    root.join(&dir)
+                        .join(m)
+                        .is_file()
+                })
+            {
+                out.push(dir.clone());
+            }
+            if depth < MEMBER_DEPTH
+            {
+                visit(root, &dir, depth + 1, out);
+            }
+        }
+    }
+
+    let mut out = Vec::new();
+    visit(root, Path::new(""), 1, &mut out);
+    out
+}
+
+/// Shard root for `rel` given the workspace members: the innermost member
+/// holding it, else its top-level directory, else the root itself
+fn shard_root(
+    rel: &Path,
+    members: &[PathBuf],
+) -> PathBuf
+{
+    if let Some(member) = members
+        .iter()
+        .filter(|m| rel.starts_with(m))
+        .max_by_key(|m| {
+            m.components()
+                .count()
+        })
+    {
+        return member.clone();
+    }
+    let mut components = rel.components();
+    match (components.next(), components.next())
+    {
+        (Some(top), Some(_)) => PathBuf::from(top.as_os_str()),
+        _ => PathBuf::new(),
+    }
+}
+
+/// Arguments writing the index of one shard
+fn shard_args(
+    args: &SymbolsArgs,
+    root: &Path,
+) -> SymbolsArgs
+{
+    SymbolsArgs {
+        output: shard_index_path(&args.output, root),
+        format: SymbolsFormat::Jsonl,
+        shard: false,
+        ..args.clone()
+    }
+}
+
+/// Write one index per shard for `extraction`, replacing the shards of any
+/// earlier run; returns the shard and symbol counts
+pub(crate) fn write_shards(
+    extraction: FileExtraction,
+    args: &SymbolsArgs,
+) -> Result<(usize, usize)>
+{
+    let members = workspace_members(&args.path);
+    let mut shards: BTreeMap<PathBuf, FileExtraction> = BTreeMap::new();
+    for s in extraction.symbols
+    {
+        shards
+            .entry(shard_root(&s.file, &members))
+            .or_default()
+            .symbols
+            .push(s);
+    }
+    for r in extraction.references
+    {
+        shards
+            .entry(shard_root(&r.file, &members))
+            .or_default()
+            .references
+            .push(r);
+    }
+    for r in extraction.relations
+    {
+        shards
+            .entry(shard_root(&r.file, &members))
+            .or_default()
+            .relations
+            .push(r);
+    }
+
+    remove_shards(&args.output)?;
+    let dir = shard_index_path(&args.output, Path::new(""))
+        .parent()
+        .map(Path::to_path_buf)
+        .unwrap_or_default();
+    std::fs::create_dir_all(&dir)
+        .with_context(|| format!("Failed to create directory {}", dir.display()))?;
+
+    let mut symbols = 0;
+    for (root, one) in &shards
+    {
+        symbols += write_index(one.clone(), &shard_args(args, root))?.symbols;
+    }
+    let count = shards.len();
+    ShardManifest {
+        version: MANIFEST_VERSION,
+        shards: shards
+            .into_keys()
+            .collect(),
+    }
+    .write(&args.output)?;
+    Ok((count, symbols))
+}
+
+/// Delete the manifest next to `jsonl` and every shard it lists, if any
+pub(crate) fn remove_shards(jsonl: &Path) -> Result<()>
+{
+    let Some(manifest) = ShardManifest::load(jsonl)
+    else
+    {
+        return Ok(());
+    };
+    for root in &manifest.shards
+    {
+        let index = shard_index_path(jsonl, root);
+        for path in [
+            refs_path(&index),
+            relations_path(&index),
+            binary_path(&index),
+            embeddings_path(&index),
+            index,
+        ]
+        {
+            let _ = std::fs::remove_file(path);
+        }
+    }
+    let path = manifest_path(jsonl);
+    std::fs::remove_file(&path).with_context(|| format!("Failed to remove {}", path.display()))
+}
+
+/// Re-extract the files of the shard rooted at `root` and rewrite its
+/// index, walking only that shard's directory (only the top level for the
+/// root shard)
+fn refresh_shard(
+    args: &SymbolsArgs,
+    manifest: &ShardManifest,
+    root: &Path,
+) -> Result<()>
+{
+    let depth = root
+        .as_os_str()
+        .is_empty()
+        .then_some(1);
+    let (files, _) = source_files_in(
+        args,
+        &args
+            .path
+            .join(root),
+        depth,
+    )?;
+    let files: Vec<(PathBuf, String)> = files
+        .into_iter()
+        .filter(|(path, _)| {
+            let rel = path
+                .strip_prefix(&args.path)
+                .unwrap_or(path);
+            manifest.shard_of(rel) == Some(root)
+        })
+        .collect();
+    write_index(extract_files(&files, args)?, &shard_args(args, root))?;
+    Ok(())
+}
+
+/// Whether the shard rooted at `root` is newer than its files; the root
+/// shard only answers for files directly under the scanned root
+fn shard_fresh(
+    args: &SymbolsArgs,
+    root: &Path,
+) -> bool
+{
+    let index = shard_index_path(&args.output, root);
+    if root
+        .as_os_str()
+        .is_empty()
+    {
+        ContextAssembler::top_level_fresh(&args.path, &index)
+    }
+    else
+    {
+        ContextAssembler::index_is_fresh(
+            &args
+                .path
+                .join(root),
+            &index,
+        )
+    }
+}
+
+/// Rebuild the shards a run focused on `focus` reads that are missing or
+/// stale, each under its own lock; `args` describes the whole index
+pub(crate) fn refresh_stale(
+    args: &SymbolsArgs,
+    focus: &[PathBuf],
+    ctx: &AppContext,
+) -> Result<()>
+{
+    let Some(manifest) = ShardManifest::load(&args.output)
+    else
+    {
+        return Ok(());
+    };
+    for root in manifest.select(focus)
+    {
+        let index = shard_index_path(&args.output, root);
+        let satisfied = || {
+            index.exists() && shard_fresh(args, root) && (!args.refs || refs_path(&index).exists())
+        };
+        if satisfied()
+        {
+            continue;
+        }
+        if !ctx.quiet
+        {
+            eprintln!(
+                "(info) symbols shard '{}' stale; regenerating",
+                root.display()
+            );
+        }
+        ContextAssembler::regenerate_with_lock(&index, &satisfied, &|| {
+            refresh_shard(args, &manifest, root)
+        })?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests
+{
+    use super::*;
+
+    #[test]
+    fn files_go_to_their_innermost_member()
+    {
+        let dir = tempfile::tempdir().unwrap();
+        for member in ["crates/core", "crates/core/fuzz", "web"]
+        {
+            std::fs::create_dir_all(
+                dir.path()
+                    .join(member),
+            )
+            .unwrap();
+        }
+        std::fs::write(
+            dir.path()
+                .join("crates/core/Cargo.toml"),
+            "",
+        )
+        .unwrap();
+        std::fs::write(
+            dir.path()
+                .join("crates/core/fuzz/Cargo.toml"),
+            "",
+        )
+        .unwrap();
+        std::fs::write(
+            dir.path()
+                .join("web/package.json"),
+            "",
+        )
+        .unwrap();
+
+        let members = workspace_members(dir.path());
+        assert_eq!(members, [
+            PathBuf::from("crates/core"),
+            PathBuf::from("crates/core/fuzz"),
+            PathBuf::from("web"),
+        ]);
+        let root = |rel: &str| shard_root(Path::new(rel), &members);
+        assert_eq!(
+            root("crates/core/fuzz/src/main.rs"),
+            Path::new("crates/core/fuzz")
+        );
+        assert_eq!(root("crates/core/src/lib.rs"), Path::new("crates/core"));
+        assert_eq!(root("docs/guide/intro.md"), Path::new("docs"));
+        assert_eq!(root("README.md"), Path::new(""));
+
+        let manifest = ShardManifest {
+            version: MANIFEST_VERSION,
+            shards: ["", "crates/core", "crates/core/fuzz", "docs"]
+                .map(PathBuf::from)
+                .into(),
+        };
+        assert_eq!(
+            manifest.select(&[PathBuf::from("crates/core/src/lib.rs")]),
+            [Path::new("crates/core")]
+        );
+        assert_eq!(
+            manifest
+                .select(&[PathBuf::from("crates")])
+                .len(),
+            3
+        );
+        assert_eq!(
+            manifest
+                .select(&[])
+                .len(),
+            4
+        );
+        assert_eq!(
+            shard_index_path(Path::new(".rup/symbols.jsonl"), Path::new("crates/core")),
+            Path::new(".rup/shards/crates__core.jsonl")
+        );
+    }
+}
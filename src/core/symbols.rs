@@ -4,19 +4,27 @@
 //! module is feature-gated by `symbols` where relevant,
 //! and keeps responsibilities explicit and testable.
 use std::{
-    collections::HashSet,   // Fast language filter
-    fs::File,               // Output file handle
-    io::{BufWriter, Write}, // Buffered writer
-    path::{Path, PathBuf},  // Paths
+    collections::{HashMap, HashSet}, // Fast language filter, extractor cache
+    fs::File,                        // Output file handle
+    io::{BufWriter, Write},          // Buffered writer
+    path::{Path, PathBuf},           // Paths
+    sync::{Arc, OnceLock, RwLock},   // Shared extractors
 };
 
 use anyhow::{Context, Result}; // Error handling
+use globset::{Glob, GlobSet, GlobSetBuilder}; // --include/--exclude
 use rayon::prelude::*; // Parallelism
 use serde::{Deserialize, Serialize}; // JSONL records
 
 use crate::{
-    infra::walk::FileWalker,
-    parsers::{PythonExtractor, RustExtractor},
+    infra::{suppress::Suppressions, walk::FileWalker},
+    parsers::{
+        CSharpExtractor, ComponentExtractor, ConfigExtractor, GoExtractor, HclExtractor,
+        JavaExtractor, JavaScriptExtractor, KotlinExtractor, MarkdownExtractor, PhpExtractor,
+        ProtoExtractor, PythonExtractor, RubyExtractor, RustExtractor, ShellExtractor,
+        SqlExtractor, TypeScriptExtractor,
+        external_parser::{external_extractor, external_language_for, external_languages},
+    },
 };
 
 /// Configuration options for symbol extraction (future-proof extension point)
@@ -33,17 +41,20 @@ pub fn run(
     ctx: &crate::cli::AppContext,
 ) -> Result<()>
 {
-    // Load configuration with graceful fallback
-    let config = crate::infra::config::load_config().unwrap_or_default();
-
-    // Build a Gitignore-aware file walker with extra globs
-    let walker = FileWalker::new(&config.ignore_patterns)?;
+    // Subcommands replace the indexing pass
+    if let Some(crate::cli::SymbolsSubcommand::Diff(diff)) = &args.command
+    {
+        return crate::core::symbols_diff::run(diff, ctx);
+    }
 
-    // Resolve target languages from args or config
-    let langs = LanguageSelector::resolve(&args, &config);
+    // Keep the index up to date until interrupted
+    if args.watch
+    {
+        return crate::core::symbols_watch::run(&args, ctx);
+    }
 
     // Collect files under root filtered by language
-    let files = FileCollector::collect(&walker, &args.path, &langs);
+    let (files, langs) = source_files(&args)?;
 
     // Early exit if nothing to do
     if files.is_empty()
@@ -66,8 +77,121 @@ pub fn run(
         println!("Extracting symbols from {} files...", files.len());
     }
 
-    // Extract symbols in parallel and aggregate results
-    let mut all: Vec<Symbol> = SymbolsExecutor::extract_parallel(&files, &args)?;
+    // Extract symbols, type relations (and call sites with --refs) in parallel
+    let extraction = extract_files(&files, &args)?;
+
+    // One index per workspace member instead of one for the whole root
+    if args.shard
+    {
+        let (shards, symbols) = crate::core::symbol_shards::write_shards(extraction, &args)?;
+        if !ctx.quiet
+        {
+            println!(
+                "✓ Extracted {symbols} symbols into {shards} shards listed in {}",
+                crate::core::symbol_shards::manifest_path(&args.output).display()
+            );
+        }
+        return Ok(());
+    }
+
+    let summary = write_index(extraction, &args)?;
+
+    // Print a success message with the output path
+    if !ctx.quiet
+    {
+        println!(
+            "✓ Extracted {} symbols to {}",
+            summary.symbols,
+            args.output
+                .display()
+        );
+        if args.refs
+        {
+            println!("✓ Recorded {} call sites", summary.references);
+        }
+        if let Some(path) = &summary.export
+        {
+            println!("✓ Exported symbols to {}", path.display());
+        }
+    }
+
+    // Done
+    Ok(())
+}
+
+/// Files under the scanned root in the selected languages, with the
+/// language selection itself
+pub(crate) fn source_files(
+    args: &crate::cli::SymbolsArgs
+) -> Result<(Vec<(PathBuf, String)>, LanguageSelector)>
+{
+    source_files_in(args, &args.path, None)
+}
+
+/// Like [`source_files`], but walking only `dir`, a directory under the
+/// scanned root, at most `depth` levels down
+pub(crate) fn source_files_in(
+    args: &crate::cli::SymbolsArgs,
+    dir: &Path,
+    depth: Option<usize>,
+) -> Result<(Vec<(PathBuf, String)>, LanguageSelector)>
+{
+    // Load configuration with graceful fallback
+    let config = crate::infra::config::load_config().unwrap_or_default();
+
+    // Build a Gitignore-aware file walker with extra globs
+    let walker = FileWalker::new(&config.ignore_patterns)?
+        .with_suppressions(true)
+        .with_max_depth(depth);
+
+    // Resolve target languages from args or config
+    let langs = LanguageSelector::resolve(&args.languages, &config);
+
+    // Scope to --include/--exclude before anything is read
+    let filter = SymbolFilter::new(&[], &args.include, &args.exclude)?;
+    let mut files = FileCollector::collect(&walker, dir, &langs);
+    files.retain(|(path, _)| {
+        filter.allows_path(
+            path.strip_prefix(&args.path)
+                .unwrap_or(path),
+        )
+    });
+
+    Ok((files, langs))
+}
+
+/// Extract `files` in parallel, with line numbers filled in
+pub(crate) fn extract_files(
+    files: &[(PathBuf, String)],
+    args: &crate::cli::SymbolsArgs,
+) -> Result<FileExtraction>
+{
+    let mut extraction = SymbolsExecutor::extract_parallel(files, args)?;
+
+    // Compute line numbers efficiently for each file's symbols
+    LineNumberMapper::fill_lines(&mut extraction.symbols, &args.path)?;
+    Ok(extraction)
+}
+
+/// What one write of the index recorded
+pub(crate) struct IndexSummary
+{
+    pub symbols: usize,
+    pub references: usize,
+    /// Tags file or database written for `--format`
+    pub export: Option<PathBuf>,
+}
+
+/// Write the index files for `extraction`, whose line numbers are filled
+/// in: the JSONL index and its binary copy, relations, call sites with
+/// `--refs`, vectors with `--embeddings`, and any `--format` export
+pub(crate) fn write_index(
+    extraction: FileExtraction,
+    args: &crate::cli::SymbolsArgs,
+) -> Result<IndexSummary>
+{
+    let FileExtraction { symbols: mut all, references: mut refs, relations: mut rels } =
+        extraction;
 
     // Optionally filter private symbols based on flag
     if !args.include_private
@@ -75,8 +199,9 @@ pub fn run(
         VisibilityFilter::retain_public(&mut all);
     }
 
-    // Compute line numbers efficiently for each file's symbols
-    LineNumberMapper::fill_lines(&mut all, &args.path)?;
+    // Keep only the --kind selection
+    let filter = SymbolFilter::new(&args.kind, &[], &[])?;
+    all.retain(|s| filter.allows_kind(&s.kind));
 
     // Ensure deterministic output order across platforms/runs
     all.sort_by(|a, b| {
@@ -96,22 +221,70 @@ pub fn run(
             )
     });
 
-    // Write symbols to JSONL destination
+    // Record the package and module owning each symbol
+    crate::core::symbol_owners::fill_owners(&mut all, &args.path);
+
+    // A whole index replaces the shards of an earlier `--shard` run
+    crate::core::symbol_shards::remove_shards(&args.output)?;
+
+    // Write symbols to JSONL destination, plus the binary index that loads it fast
     JsonlWriter::write(&all, &args.output)?;
+    crate::core::symbol_index::write_binary(&all, &args.output)?;
 
-    // Print a success message with the output path
-    if !ctx.quiet
+    // Write type relations next to the index, in file order
+    rels.sort_by(|a, b| (&a.file, a.byte_start).cmp(&(&b.file, b.byte_start)));
+    JsonlWriter::write(
+        &rels,
+        &crate::core::symbol_index::relations_path(&args.output),
+    )?;
+
+    // Write call sites next to the index, in file order
+    if args.refs
     {
-        println!(
-            "✓ Extracted {} symbols to {}",
-            all.len(),
-            args.output
-                .display()
-        );
+        refs.sort_by(|a, b| (&a.file, a.byte_start).cmp(&(&b.file, b.byte_start)));
+        JsonlWriter::write(&refs, &crate::core::symbol_index::refs_path(&args.output))?;
     }
 
-    // Done
-    Ok(())
+    // Embed symbols next to the index, reusing the vectors of unchanged ones
+    if args.embeddings
+    {
+        use crate::core::symbol_embeddings::{embed_symbols, embedder, embeddings_path};
+        let embedder = embedder()
+            .context("--embeddings needs an embedder: set [symbols.embeddings] command")?;
+        let path = embeddings_path(&args.output);
+        let previous = crate::core::symbol_index::read_jsonl(&path).unwrap_or_default();
+        JsonlWriter::write(&embed_symbols(&all, &previous, embedder.as_ref())?, &path)?;
+    }
+
+    // Extra exports: editor tags in the scanned root, where editors look
+    // for them; the SQLite copy next to the index
+    let export = match args.format
+    {
+        crate::cli::SymbolsFormat::Jsonl => None,
+        crate::cli::SymbolsFormat::Ctags =>
+        {
+            let path = args.path.join("tags");
+            std::fs::write(&path, crate::core::tags::render_ctags(&all))
+                .with_context(|| format!("Failed to write {}", path.display()))?;
+            Some(path)
+        }
+        crate::cli::SymbolsFormat::Etags =>
+        {
+            let path = args.path.join("TAGS");
+            std::fs::write(&path, crate::core::tags::render_etags(&all, &args.path))
+                .with_context(|| format!("Failed to write {}", path.display()))?;
+            Some(path)
+        }
+        crate::cli::SymbolsFormat::Sqlite =>
+        {
+            use crate::core::symbols_sqlite::{render_sql, sqlite_path, write_sqlite};
+            let path = sqlite_path(&args.output);
+            write_sqlite(&render_sql(&all, &refs, &rels), &path)?;
+            Some(path)
+        }
+    };
+
+    Ok(IndexSummary { symbols: all.len(), references: refs.len(), export })
 }
 
 /// Normalized symbol record optimized for LLM consumption
@@ -150,6 +323,131 @@ pub struct Symbol
 
     /// Optional documentation preview
     pub doc: Option<String>,
+
+    /// Declaration header up to the body, whitespace collapsed (with
+    /// `--signatures`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub signature: Option<String>,
+
+    /// Package owning the file, named by its nearest project manifest
+    /// (`Cargo.toml`, `package.json`, `go.mod`, ...)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub package: Option<String>,
+
+    /// Module path of the file within its package, in the language's own
+    /// notation (`roughup::core::symbols`, `pkg.util`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub module: Option<String>,
+}
+
+/// Call site recorded by `rup symbols --refs`
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct Reference
+{
+    /// File path relative to project root
+    pub file: PathBuf,
+
+    /// Programming language label
+    pub lang: String,
+
+    /// Simple name of the called symbol
+    pub name: String,
+
+    /// Qualified name of the innermost function or method making the call
+    pub caller: Option<String>,
+
+    /// Start byte of the callee name
+    pub byte_start: usize,
+
+    /// End byte of the callee name
+    pub byte_end: usize,
+
+    /// 1-based line of the call
+    pub line: usize,
+}
+
+impl Reference
+{
+    /// Call of `name` at `byte_start..byte_end`; file, language, caller,
+    /// and line are filled in after extraction
+    pub fn call(
+        name: &str,
+        byte_start: usize,
+        byte_end: usize,
+    ) -> Self
+    {
+        Self { name: name.to_string(), byte_start, byte_end, ..Self::default() }
+    }
+}
+
+/// Type relationship recorded by `rup symbols`: `impl Trait for Type`,
+/// class inheritance, interface implementation, Rust re-export aliases
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct Relation
+{
+    /// File path relative to project root
+    pub file: PathBuf,
+
+    /// Programming language label
+    pub lang: String,
+
+    /// How `source` relates to `target`
+    pub kind: RelationKind,
+
+    /// Simple name of the implementing or inheriting type, or the alias
+    pub source: String,
+
+    /// Simple name of the trait, interface, or base type, or the aliased item
+    pub target: String,
+
+    /// Start byte of the target name
+    pub byte_start: usize,
+
+    /// End byte of the target name
+    pub byte_end: usize,
+
+    /// 1-based line of the target name
+    pub line: usize,
+}
+
+impl Relation
+{
+    /// `source` relating to `target` named at `byte_start..byte_end`; file,
+    /// language, and line are filled in after extraction
+    pub fn new(
+        kind: RelationKind,
+        source: &str,
+        target: &str,
+        byte_start: usize,
+        byte_end: usize,
+    ) -> Self
+    {
+        Self {
+            file: PathBuf::new(),
+            lang: String::new(),
+            kind,
+            source: source.to_string(),
+            target: target.to_string(),
+            byte_start,
+            byte_end,
+            line: 0,
+        }
+    }
+}
+
+/// Typed edge between two types, or between an alias and what it names
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash)]
+#[serde(rename_all = "snake_case")]
+pub enum RelationKind
+{
+    /// `impl Trait for Type`, `implements Interface`
+    Implements,
+
+    /// Class inheritance, interface or trait extension
+    Extends,
+
+    /// `pub use path::Original as Alias`: the alias names the original
+    ReExports,
 }
 
 /// Normalized symbol kinds across languages
@@ -169,7 +467,7 @@ pub enum SymbolKind
     /// Rust/TypeScript enum
     Enum,
 
-    /// Rust trait
+    /// Rust trait / PHP trait
     Trait,
 
     /// Python/TS/Java class
@@ -195,10 +493,83 @@ pub enum SymbolKind
 
     /// Constant definition
     Constant,
+
+    /// Java `@interface` / Kotlin `annotation class`
+    Annotation,
+
+    /// C# property (accessor-backed member) / Python `@property`
+    Property,
+
+    /// Markdown heading, spanning its section
+    Heading,
+
+    /// Markdown fenced code block
+    CodeBlock,
+
+    /// Markdown link reference definition
+    LinkDefinition,
+
+    /// TOML table / YAML or JSON object-valued key / SQL table
+    Table,
+
+    /// TOML/YAML/JSON key with a plain value
+    Key,
+
+    /// SQL view
+    View,
+
+    /// SQL index
+    Index,
+
+    /// Terraform resource or data source
+    Resource,
+
+    /// Shell `source`/`.` of another file
+    Import,
+
+    /// Vue/Svelte top-level `<template>`, `<script>`, or `<style>` block
+    Block,
+}
+
+impl SymbolKind
+{
+    /// Name used in `symbols.jsonl` (`function`, `type_alias`)
+    pub fn label(&self) -> String
+    {
+        serde_json::to_value(self)
+            .ok()
+            .and_then(|v| {
+                v.as_str()
+                    .map(str::to_string)
+            })
+            .unwrap_or_default()
+    }
+
+    /// Kind from its `symbols.jsonl` name or a common short form (`fn`,
+    /// `mod`, `const`, `type`); case and `-`/`_` are ignored
+    pub fn from_label(label: &str) -> Result<Self>
+    {
+        let label = label
+            .trim()
+            .to_lowercase()
+            .replace('-', "_");
+        let name = match label.as_str()
+        {
+            "fn" | "func" | "def" => "function",
+            "mod" | "namespace" => "module",
+            "const" => "constant",
+            "var" | "static" | "field" => "variable",
+            "type" | "typedef" => "type_alias",
+            "prop" => "property",
+            other => other,
+        };
+        serde_json::from_value(serde_json::Value::from(name))
+            .map_err(|_| anyhow::anyhow!("unknown kind '{label}'"))
+    }
 }
 
 /// Normalized visibility levels
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Hash)]
 #[serde(rename_all = "snake_case")]
 pub enum Visibility
 {
@@ -216,7 +587,7 @@ pub enum Visibility
 }
 
 /// Internal helper that selects target languages
-struct LanguageSelector
+pub(crate) struct LanguageSelector
 {
     /// Canonical lowercase language labels
     set: HashSet<String>,
@@ -227,25 +598,31 @@ struct LanguageSelector
 
 impl LanguageSelector
 {
-    /// Construct from CLI args and config fallback
-    fn resolve(
-        args: &crate::cli::SymbolsArgs,
+    /// Construct from a CLI language list, falling back to config
+    pub(crate) fn resolve(
+        languages: &[String],
         cfg: &crate::infra::config::Config,
     ) -> Self
     {
-        // Choose CLI-provided list or config default
-        let ordered = if args
-            .languages
-            .is_empty()
+        // Choose CLI-provided list or config default plus external languages
+        let ordered = if languages.is_empty()
         {
-            cfg.symbols
+            let mut langs = cfg
+                .symbols
                 .languages
-                .clone()
+                .clone();
+            for lang in external_languages()
+            {
+                if !langs.contains(&lang)
+                {
+                    langs.push(lang);
+                }
+            }
+            langs
         }
         else
         {
-            args.languages
-                .clone()
+            languages.to_vec()
         };
 
         // Normalize to lowercase for matching
@@ -259,7 +636,7 @@ impl LanguageSelector
     }
 
     /// Test if a language label is selected
-    fn contains(
+    pub(crate) fn contains(
         &self,
         lang: &str,
     ) -> bool
@@ -294,18 +671,7 @@ impl FileCollector
         // Detect languages and retain matched pairs (only for supported AND selected languages)
         files
             .into_iter()
-            .filter_map(|path| {
-                LanguageDetector::detect(&path).and_then(|lang| {
-                    if langs.contains(&lang) && is_supported_language(&lang)
-                    {
-                        Some((path, lang))
-                    }
-                    else
-                    {
-                        None
-                    }
-                })
-            })
+            .filter_map(|path| selected_language(&path, langs).map(|lang| (path, lang)))
             .collect()
     }
 }
@@ -318,28 +684,66 @@ impl LanguageDetector
     /// Map file extensions to canonical language labels
     fn detect(path: &Path) -> Option<String>
     {
-        // Get extension as lowercase string
         let ext = path
             .extension()?
-            .to_str()?
-            .to_lowercase();
+            .to_str()?;
+        language_for(ext)
+    }
+}
 
-        // Map common extensions to languages
-        let lang = match ext.as_str()
-        {
-            "rs" => "rust",
-            "py" => "python",
-            "js" | "jsx" => "javascript",
-            "ts" | "tsx" => "typescript",
-            "go" => "go",
-            "c" | "h" => "c",
-            "cpp" | "cxx" | "cc" | "hpp" => "cpp",
-            _ => return None,
-        };
+/// Canonical language label for a file extension (`rust` for `rs`), or
+/// `None` when roughup does not recognize it. Extensions claimed by a
+/// configured external extractor come first. This is the one extension
+/// table: indexing, chunking and index staleness all go through it.
+pub(crate) fn language_for(ext: &str) -> Option<String>
+{
+    let ext = ext.to_lowercase();
 
-        // Return owned language string
-        Some(lang.to_string())
+    // Extensions claimed by a configured external extractor come first
+    if let Some(lang) = external_language_for(&ext)
+    {
+        return Some(lang);
     }
+
+    // Map common extensions to languages
+    let lang = match ext.as_str()
+    {
+        "rs" => "rust",
+        "py" => "python",
+        "js" | "jsx" | "mjs" | "cjs" => "javascript",
+        "ts" | "tsx" | "mts" | "cts" => "typescript",
+        "go" => "go",
+        "java" => "java",
+        "kt" | "kts" => "kotlin",
+        "cs" => "csharp",
+        "rb" | "rake" => "ruby",
+        "php" => "php",
+        "md" | "markdown" => "markdown",
+        "toml" => "toml",
+        "yaml" | "yml" => "yaml",
+        "json" => "json",
+        "sql" => "sql",
+        "proto" => "proto",
+        "tf" | "hcl" => "hcl",
+        "sh" | "bash" => "bash",
+        "vue" => "vue",
+        "svelte" => "svelte",
+        "c" | "h" => "c",
+        "cpp" | "cxx" | "cc" | "hpp" => "cpp",
+        _ => return None,
+    };
+
+    // Return owned language string
+    Some(lang.to_string())
+}
+
+/// Everything one extraction pass records
+#[derive(Default, Clone)]
+pub(crate) struct FileExtraction
+{
+    pub symbols: Vec<Symbol>,
+    pub references: Vec<Reference>,
+    pub relations: Vec<Relation>,
 }
 
 /// Parallel symbol extraction coordinator
@@ -347,38 +751,44 @@ struct SymbolsExecutor;
 
 impl SymbolsExecutor
 {
-    /// Extract symbols from all files using rayon
+    /// Extract symbols (and call sites when `--refs`) from all files using rayon
     fn extract_parallel(
         files: &[(PathBuf, String)],
         args: &crate::cli::SymbolsArgs,
-    ) -> Result<Vec<Symbol>>
+    ) -> Result<FileExtraction>
     {
         // Convert to parallel iterator over file-language pairs
-        let results: Vec<Result<Vec<Symbol>>> = files
+        let results: Vec<Result<FileExtraction>> = files
             .par_iter()
-            .map(|(file, lang)| Self::extract_one(file, lang, &args.path))
+            .map(|(file, lang)| Self::extract_one(file, lang, args))
             .collect();
 
         // Aggregate, short-circuiting on first error
-        let mut out = Vec::new();
+        let mut out = FileExtraction::default();
         for r in results
         {
             // Propagate any error
-            let mut v = r?;
-            // Append the file’s symbols
-            out.append(&mut v);
+            let mut one = r?;
+            // Append the file’s symbols, call sites, and relations
+            out.symbols
+                .append(&mut one.symbols);
+            out.references
+                .append(&mut one.references);
+            out.relations
+                .append(&mut one.relations);
         }
 
-        // Return the aggregated symbols
+        // Return the aggregated records
         Ok(out)
     }
 
-    /// Extract symbols for a single file
+    /// Extract symbols and type relations, plus call sites and signatures
+    /// when `args` asks for them, for a single file
     fn extract_one(
         file_path: &Path,
         lang: &str,
-        root: &Path,
-    ) -> Result<Vec<Symbol>>
+        args: &crate::cli::SymbolsArgs,
+    ) -> Result<FileExtraction>
     {
         // Read file contents as a single UTF-8 String
         let content = std::fs::read_to_string(file_path)
@@ -386,28 +796,270 @@ impl SymbolsExecutor
 
         // Compute the path relative to the root, if possible
         let rel = file_path
-            .strip_prefix(root)
+            .strip_prefix(&args.path)
             .unwrap_or(file_path)
             .to_path_buf();
 
-        // Acquire a language-specific extractor
-        let extractor = get_extractor(lang)?;
+        Self::extract_content(&content, rel, lang, args.refs, args.signatures)
+    }
+
+    /// Extract the records of one file's text, labeled with `rel`
+    fn extract_content(
+        content: &str,
+        rel: PathBuf,
+        lang: &str,
+        refs: bool,
+        signatures: bool,
+    ) -> Result<FileExtraction>
+    {
+        // Acquire the shared language-specific extractor
+        let extractor = shared_extractor(lang)?;
 
         // Run the extractor to produce raw symbols
-        let mut symbols = extractor.extract_symbols(&content, &rel)?;
+        let mut symbols = extractor.extract_symbols(content, &rel)?;
 
         // NEW: canonicalize ordering right after extraction
         extractor.postprocess(&mut symbols);
 
+        // Call sites, attributed while private callers are still present
+        let mut references = Vec::new();
+        if refs
+        {
+            references = extractor.extract_references(content, &rel)?;
+            let li = LineIndex::new(content);
+            for r in &mut references
+            {
+                r.file = rel.clone();
+                r.lang = lang.to_string();
+                r.line = li.byte_to_line(r.byte_start);
+                r.caller = enclosing_callable(&symbols, r.byte_start, r.byte_end);
+            }
+        }
+
+        if signatures
+        {
+            for s in &mut symbols
+            {
+                s.signature = signature_of(content, lang, s);
+            }
+        }
+
+        // Type relations, located like call sites
+        let mut relations = extractor.extract_relations(content, &rel)?;
+        if !relations.is_empty()
+        {
+            let li = LineIndex::new(content);
+            for r in &mut relations
+            {
+                r.file = rel.clone();
+                r.lang = lang.to_string();
+                r.line = li.byte_to_line(r.byte_start);
+            }
+        }
+
+        // Honor inline `rup:` suppression directives
+        Suppressions::scan(content).apply(content, &mut symbols);
+
         // Populate language labels consistently
         for s in &mut symbols
         {
             s.lang = lang.to_string();
         }
 
-        // Return the file’s symbols
-        Ok(symbols)
+        // Return the file’s records
+        Ok(FileExtraction { symbols, references, relations })
+    }
+}
+
+/// Language of a path when it is selected and has an extractor
+pub(crate) fn selected_language(
+    path: &Path,
+    langs: &LanguageSelector,
+) -> Option<String>
+{
+    LanguageDetector::detect(path)
+        .filter(|lang| langs.contains(lang) && is_supported_language(lang))
+}
+
+/// Language label for a file path by extension (`rust` for `a.rs`), for
+/// any language roughup recognizes
+pub(crate) fn detect_language(path: &Path) -> Option<String>
+{
+    LanguageDetector::detect(path)
+}
+
+/// Symbols not nested inside another symbol of the same file
+pub(crate) fn top_level<'a>(symbols: &[&'a Symbol]) -> Vec<&'a Symbol>
+{
+    let mut out: Vec<&Symbol> = symbols
+        .iter()
+        .copied()
+        .filter(|s| {
+            !symbols
+                .iter()
+                .any(|o| {
+                    o.byte_start <= s.byte_start
+                        && s.byte_end <= o.byte_end
+                        && (o.byte_start, o.byte_end) != (s.byte_start, s.byte_end)
+                })
+        })
+        .collect();
+    out.sort_by_key(|s| (s.byte_start, s.byte_end));
+    out.dedup_by_key(|s| (s.byte_start, s.byte_end));
+    out
+}
+
+/// Symbols of one file's text that is not read from the worktree (a git
+/// blob for `rup symbols diff`), with line numbers filled in
+pub(crate) fn extract_text(
+    content: &str,
+    rel: &Path,
+    lang: &str,
+    signatures: bool,
+) -> Result<Vec<Symbol>>
+{
+    let mut symbols =
+        SymbolsExecutor::extract_content(content, rel.to_path_buf(), lang, false, signatures)?
+            .symbols;
+    let li = LineIndex::new(content);
+    for s in &mut symbols
+    {
+        (s.start_line, s.end_line) = li.byte_span_to_lines(s.byte_start, s.byte_end);
+    }
+    Ok(symbols)
+}
+
+/// Records of one worktree file with line numbers filled in, for
+/// `rup symbols --watch`, which re-extracts files one at a time
+pub(crate) fn extract_file(
+    file_path: &Path,
+    lang: &str,
+    args: &crate::cli::SymbolsArgs,
+) -> Result<FileExtraction>
+{
+    let content = std::fs::read_to_string(file_path)
+        .with_context(|| format!("Failed to read {}", file_path.display()))?;
+    let rel = file_path
+        .strip_prefix(&args.path)
+        .unwrap_or(file_path)
+        .to_path_buf();
+    let mut one =
+        SymbolsExecutor::extract_content(&content, rel, lang, args.refs, args.signatures)?;
+    let li = LineIndex::new(&content);
+    for s in &mut one.symbols
+    {
+        (s.start_line, s.end_line) = li.byte_span_to_lines(s.byte_start, s.byte_end);
+    }
+    Ok(one)
+}
+
+/// Qualified name of the innermost function or method spanning the range
+fn enclosing_callable(
+    symbols: &[Symbol],
+    byte_start: usize,
+    byte_end: usize,
+) -> Option<String>
+{
+    symbols
+        .iter()
+        .filter(|s| matches!(s.kind, SymbolKind::Function | SymbolKind::Method))
+        .filter(|s| s.byte_start <= byte_start && byte_end <= s.byte_end)
+        .min_by_key(|s| s.byte_end - s.byte_start)
+        .map(|s| {
+            s.qualified_name
+                .clone()
+        })
+}
+
+/// Longest signature kept, in bytes; longer headers are cut at a char
+/// boundary and end in `…`
+const MAX_SIGNATURE_LEN: usize = 300;
+
+/// Declaration header of a code symbol: its source up to the body, on one
+/// line. Brace languages stop at the opening `{`, a `;`, or an `=` outside
+/// parentheses; Python at the header's `:`; Ruby at the end of the line.
+/// Type aliases keep their whole definition.
+fn signature_of(
+    content: &str,
+    lang: &str,
+    symbol: &Symbol,
+) -> Option<String>
+{
+    let has_header = matches!(
+        symbol.kind,
+        SymbolKind::Function
+            | SymbolKind::Method
+            | SymbolKind::Struct
+            | SymbolKind::Enum
+            | SymbolKind::Trait
+            | SymbolKind::Class
+            | SymbolKind::Interface
+            | SymbolKind::Impl
+            | SymbolKind::TypeAlias
+            | SymbolKind::Constant
+            | SymbolKind::Annotation
+            | SymbolKind::Property
+    );
+    if !has_header || lang == "sql"
+    {
+        return None;
+    }
+    let text = content.get(symbol.byte_start..symbol.byte_end)?;
+
+    let bytes = text.as_bytes();
+    let mut depth = 0usize;
+    let mut end = text.len();
+    for (i, &b) in bytes
+        .iter()
+        .enumerate()
+    {
+        match b
+        {
+            b'(' | b'[' => depth += 1,
+            b')' | b']' => depth = depth.saturating_sub(1),
+            _ if depth > 0 || symbol.kind == SymbolKind::TypeAlias => continue,
+            b':' if lang == "python" =>
+            {
+                end = i;
+                break;
+            }
+            b'\n' if lang == "ruby" =>
+            {
+                end = i;
+                break;
+            }
+            b'{' | b';' if !matches!(lang, "python" | "ruby") =>
+            {
+                end = i;
+                break;
+            }
+            b'=' if !matches!(lang, "python" | "ruby")
+                && bytes.get(i + 1) != Some(&b'>')
+                && bytes.get(i + 1) != Some(&b'=')
+                && !matches!(bytes.get(i.wrapping_sub(1)), Some(b'!' | b'<' | b'>' | b'=')) =>
+            {
+                end = i;
+                break;
+            }
+            _ => {}
+        }
+    }
+
+    let mut sig = text[..end]
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ");
+    if sig.len() > MAX_SIGNATURE_LEN
+    {
+        let mut cut = MAX_SIGNATURE_LEN;
+        while !sig.is_char_boundary(cut)
+        {
+            cut -= 1;
+        }
+        sig.truncate(cut);
+        sig.push('…');
     }
+    (!sig.is_empty()).then_some(sig)
 }
 
 /// Map byte offsets to line numbers efficiently
@@ -543,26 +1195,187 @@ impl LineIndex
 }
 
 /// Filter utilities for visibility post-processing
-struct VisibilityFilter;
+pub(crate) struct VisibilityFilter;
 
 impl VisibilityFilter
 {
     /// Keep only symbols that are public or unspecified
-    fn retain_public(v: &mut Vec<Symbol>)
+    pub(crate) fn retain_public(v: &mut Vec<Symbol>)
     {
         // Retain when visibility is None or explicitly Public
         v.retain(|s| matches!(&s.visibility, None | Some(Visibility::Public)));
     }
 }
 
+/// `--kind`/`--include`/`--exclude` scoping for `rup symbols` and index
+/// lookups. Paths are matched relative to the scanned root; a pattern
+/// without glob characters also matches everything under it (`src`).
+#[derive(Debug, Clone, Default)]
+pub struct SymbolFilter
+{
+    kinds: Option<HashSet<SymbolKind>>,
+    include: Option<GlobSet>,
+    exclude: Option<GlobSet>,
+}
+
+impl SymbolFilter
+{
+    pub fn new(
+        kinds: &[String],
+        include: &[String],
+        exclude: &[String],
+    ) -> Result<Self>
+    {
+        let kinds = if kinds.is_empty()
+        {
+            None
+        }
+        else
+        {
+            Some(
+                kinds
+                    .iter()
+                    .map(|k| SymbolKind::from_label(k))
+                    .collect::<Result<HashSet<_>>>()?,
+            )
+        };
+        Ok(Self { kinds, include: Self::globs(include)?, exclude: Self::globs(exclude)? })
+    }
+
+    fn globs(patterns: &[String]) -> Result<Option<GlobSet>>
+    {
+        if patterns.is_empty()
+        {
+            return Ok(None);
+        }
+        let mut builder = GlobSetBuilder::new();
+        for p in patterns
+        {
+            let p = p.trim_end_matches('/');
+            builder.add(Glob::new(p).with_context(|| format!("invalid glob '{p}'"))?);
+            if !p.contains(['*', '?', '[', '{'])
+            {
+                builder.add(Glob::new(&format!("{p}/**"))?);
+            }
+        }
+        Ok(Some(builder.build()?))
+    }
+
+    /// Selected kinds, or `None` for all
+    pub fn kinds(&self) -> Option<Vec<SymbolKind>>
+    {
+        self.kinds
+            .as_ref()
+            .map(|k| {
+                k.iter()
+                    .cloned()
+                    .collect()
+            })
+    }
+
+    pub fn allows_kind(
+        &self,
+        kind: &SymbolKind,
+    ) -> bool
+    {
+        self.kinds
+            .as_ref()
+            .is_none_or(|k| k.contains(kind))
+    }
+
+    /// Whether a root-relative path is in scope
+    pub fn allows_path(
+        &self,
+        rel: &Path,
+    ) -> bool
+    {
+        self.include
+            .as_ref()
+            .is_none_or(|g| g.is_match(rel))
+            && !self
+                .exclude
+                .as_ref()
+                .is_some_and(|g| g.is_match(rel))
+    }
+
+    pub fn allows(
+        &self,
+        symbol: &Symbol,
+    ) -> bool
+    {
+        self.allows_kind(&symbol.kind) && self.allows_path(&symbol.file)
+    }
+}
+
+/// `--lang`, `--include`, and `--exclude` selection of files for `rup tree`
+/// and `rup chunk`, matched relative to the scanned root
+pub(crate) struct FileFilter
+{
+    /// Lowercased language labels or extensions; empty for all
+    langs: Vec<String>,
+    paths: SymbolFilter,
+}
+
+impl FileFilter
+{
+    pub(crate) fn new(
+        langs: &[String],
+        include: &[String],
+        exclude: &[String],
+    ) -> Result<Self>
+    {
+        let langs = langs
+            .iter()
+            .map(|l| {
+                l.trim()
+                    .trim_start_matches('.')
+                    .to_lowercase()
+            })
+            .filter(|l| !l.is_empty())
+            .collect();
+        Ok(Self { langs, paths: SymbolFilter::new(&[], include, exclude)? })
+    }
+
+    /// Whether a root-relative file is in scope
+    pub(crate) fn allows(
+        &self,
+        rel: &Path,
+    ) -> bool
+    {
+        self.paths
+            .allows_path(rel)
+            && (self
+                .langs
+                .is_empty()
+                || self.matches_language(rel))
+    }
+
+    fn matches_language(
+        &self,
+        rel: &Path,
+    ) -> bool
+    {
+        let ext = rel
+            .extension()
+            .map(|e| {
+                e.to_string_lossy()
+                    .to_lowercase()
+            });
+        let lang = detect_language(rel);
+        self.langs
+            .iter()
+            .any(|l| ext.as_deref() == Some(l) || lang.as_deref() == Some(l))
+    }
+}
+
 /// Stream symbols to a JSON Lines file
 struct JsonlWriter;
 
 impl JsonlWriter
 {
     /// Write one JSON object per line into `output_path`
-    fn write(
-        symbols: &[Symbol],
+    fn write<T: Serialize>(
+        symbols: &[T],
         output_path: &Path,
     ) -> Result<()>
     {
@@ -606,22 +1419,95 @@ impl JsonlWriter
 }
 
 // Check if a language has an available extractor
-fn is_supported_language(lang: &str) -> bool
+pub(crate) fn is_supported_language(lang: &str) -> bool
 {
-    matches!(lang, "rust" | "python")
+    matches!(
+        lang,
+        "rust"
+            | "python"
+            | "javascript"
+            | "typescript"
+            | "go"
+            | "java"
+            | "kotlin"
+            | "csharp"
+            | "ruby"
+            | "php"
+            | "markdown"
+            | "toml"
+            | "yaml"
+            | "json"
+            | "sql"
+            | "proto"
+            | "hcl"
+            | "bash"
+            | "vue"
+            | "svelte"
+    ) || external_extractor(lang).is_some()
 }
 
 // Simple extractor registry
 pub fn get_extractor(lang: &str) -> anyhow::Result<Box<dyn SymbolExtractor + Send + Sync>>
 {
+    // Configured subprocess extractors win over built-ins
+    if let Some(extractor) = external_extractor(lang)
+    {
+        return Ok(Box::new(extractor?));
+    }
+
     match lang
     {
         "rust" => Ok(Box::new(RustExtractor::new()?)),
         "python" => Ok(Box::new(PythonExtractor::new()?)),
+        "javascript" => Ok(Box::new(JavaScriptExtractor::new()?)),
+        "typescript" => Ok(Box::new(TypeScriptExtractor::new()?)),
+        "go" => Ok(Box::new(GoExtractor::new()?)),
+        "java" => Ok(Box::new(JavaExtractor::new()?)),
+        "kotlin" => Ok(Box::new(KotlinExtractor::new()?)),
+        "csharp" => Ok(Box::new(CSharpExtractor::new()?)),
+        "ruby" => Ok(Box::new(RubyExtractor::new()?)),
+        "php" => Ok(Box::new(PhpExtractor::new()?)),
+        "markdown" => Ok(Box::new(MarkdownExtractor::new()?)),
+        "toml" | "yaml" | "json" => Ok(Box::new(ConfigExtractor::new()?)),
+        "sql" => Ok(Box::new(SqlExtractor::new()?)),
+        "proto" => Ok(Box::new(ProtoExtractor::new()?)),
+        "hcl" => Ok(Box::new(HclExtractor::new()?)),
+        "bash" => Ok(Box::new(ShellExtractor::new()?)),
+        "vue" | "svelte" => Ok(Box::new(ComponentExtractor::new()?)),
         _ => Err(anyhow::anyhow!("Unsupported language: {}", lang)),
     }
 }
 
+/// Built-in extractor for `lang`, built once per process and shared by
+/// every thread, so its compiled queries are not rebuilt per file.
+/// Configured external extractors are looked up afresh each time.
+pub fn shared_extractor(lang: &str) -> anyhow::Result<Arc<dyn SymbolExtractor + Send + Sync>>
+{
+    static CACHE: OnceLock<RwLock<HashMap<String, Arc<dyn SymbolExtractor + Send + Sync>>>> =
+        OnceLock::new();
+
+    if let Some(extractor) = external_extractor(lang)
+    {
+        return Ok(Arc::new(extractor?));
+    }
+    let cache = CACHE.get_or_init(|| RwLock::new(HashMap::new()));
+    if let Some(extractor) = cache
+        .read()
+        .unwrap_or_else(|e| e.into_inner())
+        .get(lang)
+    {
+        return Ok(Arc::clone(extractor));
+    }
+    let extractor: Arc<dyn SymbolExtractor + Send + Sync> = Arc::from(get_extractor(lang)?);
+    Ok(Arc::clone(
+        cache
+            .write()
+            .unwrap_or_else(|e| e.into_inner())
+            .entry(lang.to_string())
+            .or_insert(extractor),
+    ))
+}
+
 pub trait SymbolExtractor: Send + Sync
 {
     /// Main extraction entrypoint (existing behavior).
@@ -642,6 +1528,28 @@ pub trait SymbolExtractor: Send + Sync
         self.extract_symbols(content, file_path)
     }
 
+    /// Call sites in the file, by callee name, for `rup symbols --refs`.
+    /// Defaults to a lexical scan; tree-sitter extractors override it.
+    fn extract_references(
+        &self,
+        content: &str,
+        _file_path: &std::path::Path,
+    ) -> anyhow::Result<Vec<Reference>>
+    {
+        Ok(scan_call_sites(content))
+    }
+
+    /// Type relations declared in the file; none unless the extractor
+    /// understands its language's inheritance syntax
+    fn extract_relations(
+        &self,
+        _content: &str,
+        _file_path: &std::path::Path,
+    ) -> anyhow::Result<Vec<Relation>>
+    {
+        Ok(Vec::new())
+    }
+
     /// Post-process extracted symbols; default enforces deterministic order.
     /// Sort by (file asc, byte_start asc, name asc).
     fn postprocess(
@@ -664,6 +1572,62 @@ pub trait SymbolExtractor: Send + Sync
     }
 }
 
+/// Words never treated as callees when followed by `(`
+const NOT_CALLEES: &[&str] = &[
+    "if",
+    "elif",
+    "for",
+    "while",
+    "until",
+    "match",
+    "switch",
+    "catch",
+    "return",
+    "sizeof",
+    "typeof",
+];
+
+/// Words that declare the name after them (`fn foo(` is not a call)
+const DECLARERS: &[&str] = &["fn", "def", "function", "func", "fun", "sub", "void"];
+
+/// Lexical call sites: identifiers followed by `(`, minus control-flow
+/// keywords and names being declared
+pub fn scan_call_sites(content: &str) -> Vec<Reference>
+{
+    let bytes = content.as_bytes();
+    let is_ident = |b: u8| b.is_ascii_alphanumeric() || b == b'_';
+    let mut out = Vec::new();
+    let mut prev_word = "";
+    let mut i = 0;
+    while i < bytes.len()
+    {
+        if !(bytes[i].is_ascii_alphabetic() || bytes[i] == b'_')
+        {
+            i += 1;
+            continue;
+        }
+        let start = i;
+        while i < bytes.len() && is_ident(bytes[i])
+        {
+            i += 1;
+        }
+        let word = &content[start..i];
+        let mut j = i;
+        while j < bytes.len() && (bytes[j] == b' ' || bytes[j] == b'\t')
+        {
+            j += 1;
+        }
+        if bytes.get(j) == Some(&b'(')
+            && !NOT_CALLEES.contains(&word)
+            && !DECLARERS.contains(&prev_word)
+        {
+            out.push(Reference::call(word, start, i));
+        }
+        prev_word = word;
+    }
+    out
+}
+
 // Helper function for qualified name building
 pub fn build_qualified_name(parts: &[&str]) -> String
 {
@@ -710,6 +1674,10 @@ mod tests
             Some("javascript".into())
         );
 
+        // Extensions share one table, case-insensitively
+        assert_eq!(language_for("MTS"), Some("typescript".into()));
+        assert_eq!(language_for("kts"), Some("kotlin".into()));
+
         // Negative case
         assert_eq!(LanguageDetector::detect(Path::new("e.unknown")), None);
     }
@@ -734,6 +1702,70 @@ mod tests
         assert_eq!(idx.byte_span_to_lines(6, 17), (2, 3));
     }
 
+    /// Verify signatures stop at each language's body
+    #[test]
+    fn signatures_stop_at_the_body() -> Result<()>
+    {
+        let signatures = |lang: &str, file: &str, src: &str| -> Result<Vec<(String, String)>> {
+            let symbols = get_extractor(lang)?.extract_symbols(src, Path::new(file))?;
+            Ok(symbols
+                .iter()
+                .filter_map(|s| signature_of(src, lang, s).map(|sig| (s.name.clone(), sig)))
+                .collect())
+        };
+        let get = |sigs: &[(String, String)], name: &str| {
+            sigs.iter()
+                .find(|(n, _)| n == name)
+                .map(|(_, sig)| sig.clone())
+                .expect("signature not found")
+        };
+
+        let rust = signatures(
+            "rust",
+            "lib.rs",
+            "pub fn parse<T: FromStr>(\n    input: &str,\n) -> Result<T, Error>\n\
+             where\n    T::Err: Debug,\n{\n    todo!()\n}\n\
+             pub struct Unit;\n\
+             pub const MAX: usize = 4;\n\
+             pub type Pair = (u8, u8);\n",
+        )?;
+        assert_eq!(
+            get(&rust, "parse"),
+            "pub fn parse<T: FromStr>( input: &str, ) -> Result<T, Error> where T::Err: Debug,"
+        );
+        assert_eq!(get(&rust, "Unit"), "pub struct Unit");
+        assert_eq!(get(&rust, "MAX"), "pub const MAX: usize");
+        assert_eq!(get(&rust, "Pair"), "pub type Pair = (u8, u8);");
+
+        let python = signatures(
+            "python",
+            "m.py",
+            "class Cache(Base):\n    \
+             def get(self, key: str, default: dict = {}) -> Optional[str]:\n        \
+             return None\n",
+        )?;
+        assert_eq!(get(&python, "Cache"), "class Cache(Base)");
+        assert_eq!(
+            get(&python, "get"),
+            "def get(self, key: str, default: dict = {}) -> Optional[str]"
+        );
+
+        let ts = signatures(
+            "typescript",
+            "a.ts",
+            "export function load({ id }: Opts, retry = 3): Promise<Item> {\n  \
+             return fetch(id)\n}\n",
+        )?;
+        assert_eq!(
+            get(&ts, "load"),
+            "export function load({ id }: Opts, retry = 3): Promise<Item>"
+        );
+
+        let ruby = signatures("ruby", "a.rb", "def greet(name, loud: false)\n  puts name\nend\n")?;
+        assert_eq!(get(&ruby, "greet"), "def greet(name, loud: false)");
+        Ok(())
+    }
+
     /// Verify JSONL writer produces one line per symbol
     #[test]
     fn jsonl_writer_emits_one_line_per_symbol() -> Result<()>
@@ -751,6 +1783,9 @@ mod tests
             end_line: 1,
             visibility: Some(Visibility::Public),
             doc: None,
+            signature: None,
+            package: None,
+            module: None,
         };
 
         // Clone with small changes
@@ -779,4 +1814,53 @@ mod tests
         // Done
         Ok(())
     }
+
+    /// Lexical call sites skip keywords and declared names
+    #[test]
+    fn lexical_call_sites()
+    {
+        let src = "def load(path):\n    if (ok):\n        return parse (read(path))\n";
+        let names: Vec<(String, usize)> = scan_call_sites(src)
+            .into_iter()
+            .map(|r| (r.name, r.byte_start))
+            .collect();
+        assert_eq!(names, vec![("parse".to_string(), 44), ("read".to_string(), 51)]);
+    }
+
+    #[test]
+    fn filter_by_kind_aliases_and_path_globs() -> Result<()>
+    {
+        assert_eq!(SymbolKind::from_label("fn")?, SymbolKind::Function);
+        assert_eq!(SymbolKind::from_label("Type-Alias")?, SymbolKind::TypeAlias);
+        assert!(SymbolKind::from_label("widget").is_err());
+
+        let filter = SymbolFilter::new(
+            &["fn".into(), "struct".into()],
+            &["src".into()],
+            &["src/gen/**".into()],
+        )?;
+        assert!(filter.allows_kind(&SymbolKind::Struct));
+        assert!(!filter.allows_kind(&SymbolKind::Method));
+        assert!(filter.allows_path(Path::new("src/a/b.rs")));
+        assert!(!filter.allows_path(Path::new("src/gen/out.rs")));
+        assert!(!filter.allows_path(Path::new("tests/it.rs")));
+
+        let all = SymbolFilter::default();
+        assert!(all.allows_kind(&SymbolKind::Heading) && all.allows_path(Path::new("x")));
+        assert!(
+            all.kinds()
+                .is_none()
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn builtin_extractors_are_shared() -> Result<()>
+    {
+        let first = shared_extractor("go")?;
+        assert!(Arc::ptr_eq(&first, &shared_extractor("go")?));
+        assert!(!Arc::ptr_eq(&first, &shared_extractor("ruby")?));
+        assert!(shared_extractor("cobol").is_err());
+        Ok(())
+    }
 }
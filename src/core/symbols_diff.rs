@@ -0,0 +1,464 @@
+//! Public API changes between two git revisions, for `rup symbols diff`
+//!
+//! Only files git reports as changed between the revisions are read, from
+//! the object store rather than the worktree, and their symbols extracted
+//! with signatures. Declarations are paired by file, kind, and qualified
+//! name; a pair whose signature differs is a change, and unpaired ones are
+//! additions or removals. Symbols without a signature (Markdown headings,
+//! config keys, locals) are not part of the API and are left out, as are
+//! private ones unless asked for (in Rust, items without `pub` other than
+//! methods of a public trait). A moved or renamed declaration shows up
+//! as one removal and one addition.
+
+use std::{collections::BTreeMap, path::Path, process::Command};
+
+use anyhow::{Context, Result, bail};
+use rayon::prelude::*;
+use serde::Serialize;
+
+use crate::{
+    cli::{AppContext, SymbolsDiffArgs},
+    core::symbols::{
+        LanguageSelector, Symbol, SymbolKind, Visibility, VisibilityFilter, extract_text,
+        selected_language,
+    },
+};
+
+/// Declarations added, removed, and changed from one revision to another
+#[derive(Debug, Default, Serialize)]
+pub struct ApiDiff
+{
+    pub added: Vec<Symbol>,
+    pub removed: Vec<Symbol>,
+    pub changed: Vec<ChangedSymbol>,
+}
+
+/// A declaration present in both revisions with a different signature
+#[derive(Debug, Serialize)]
+pub struct ChangedSymbol
+{
+    pub file: String,
+    pub kind: SymbolKind,
+    pub qualified_name: String,
+    /// 1-based start line in the newer revision
+    pub line: usize,
+    pub before: String,
+    pub after: String,
+}
+
+impl ApiDiff
+{
+    /// Pair the declarations of two revisions
+    pub fn between(
+        before: Vec<Symbol>,
+        after: Vec<Symbol>,
+    ) -> Self
+    {
+        // (file, kind, qualified name) -> (before, after); overloads share a key
+        type Sides = (Vec<Symbol>, Vec<Symbol>);
+        let mut groups: BTreeMap<(String, String, String), Sides> = BTreeMap::new();
+        let key = |s: &Symbol| {
+            (
+                s.file
+                    .to_string_lossy()
+                    .into_owned(),
+                s.kind
+                    .label(),
+                s.qualified_name
+                    .clone(),
+            )
+        };
+        for s in before
+        {
+            groups
+                .entry(key(&s))
+                .or_default()
+                .0
+                .push(s);
+        }
+        for s in after
+        {
+            groups
+                .entry(key(&s))
+                .or_default()
+                .1
+                .push(s);
+        }
+
+        let mut diff = Self::default();
+        for (_, (mut old, mut new)) in groups
+        {
+            // Unchanged overloads pair first, so one new overload is an addition
+            old.retain(|o| {
+                let same = new
+                    .iter()
+                    .position(|n| n.signature == o.signature);
+                same.map(|i| new.remove(i))
+                    .is_none()
+            });
+            let paired = old
+                .len()
+                .min(new.len());
+            for (o, n) in old
+                .drain(..paired)
+                .zip(new.drain(..paired))
+            {
+                diff.changed
+                    .push(ChangedSymbol {
+                        file: n
+                            .file
+                            .to_string_lossy()
+                            .into_owned(),
+                        kind: n.kind,
+                        qualified_name: n.qualified_name,
+                        line: n.start_line,
+                        before: o
+                            .signature
+                            .unwrap_or_default(),
+                        after: n
+                            .signature
+                            .unwrap_or_default(),
+                    });
+            }
+            diff.removed
+                .extend(old);
+            diff.added
+                .extend(new);
+        }
+        diff
+    }
+
+    pub fn is_empty(&self) -> bool
+    {
+        self.added
+            .is_empty()
+            && self
+                .removed
+                .is_empty()
+            && self
+                .changed
+                .is_empty()
+    }
+}
+
+/// Entry point for `rup symbols diff`
+pub fn run(
+    args: &SymbolsDiffArgs,
+    ctx: &AppContext,
+) -> Result<()>
+{
+    let repo = Path::new(".");
+    for rev in [&args.rev_a, &args.rev_b]
+    {
+        git(repo, &[
+            "rev-parse",
+            "--verify",
+            "--quiet",
+            &format!("{rev}^{{commit}}"),
+        ])
+        .with_context(|| format!("unknown revision '{rev}'"))?;
+    }
+
+    let cfg = crate::infra::config::load_config().unwrap_or_default();
+    let langs = LanguageSelector::resolve(&args.languages, &cfg);
+
+    // Names come back relative to the repository root, as `rev:path` expects
+    let mut diff_args = vec!["diff", "--name-only", "-z", "--no-renames"];
+    diff_args.extend([
+        args.rev_a
+            .as_str(),
+        args.rev_b
+            .as_str(),
+        "--",
+    ]);
+    diff_args.extend(
+        args.paths
+            .iter()
+            .map(String::as_str),
+    );
+    let changed = git(repo, &diff_args)?;
+    let files: Vec<(&str, String)> = changed
+        .split('\0')
+        .filter(|p| !p.is_empty())
+        .filter_map(|p| selected_language(Path::new(p), &langs).map(|lang| (p, lang)))
+        .collect();
+
+    let before = revision_symbols(repo, &args.rev_a, &files, args.include_private)?;
+    let after = revision_symbols(repo, &args.rev_b, &files, args.include_private)?;
+    let diff = ApiDiff::between(before, after);
+
+    if args.json
+    {
+        let output = serde_json::json!({
+            "from": args.rev_a,
+            "to": args.rev_b,
+            "added": diff.added,
+            "removed": diff.removed,
+            "changed": diff.changed,
+        });
+        println!("{}", serde_json::to_string_pretty(&output)?);
+        return Ok(());
+    }
+
+    if diff.is_empty()
+    {
+        if !ctx.quiet
+        {
+            println!("No API changes between {} and {}", args.rev_a, args.rev_b);
+        }
+        return Ok(());
+    }
+    print_section("Added", '+', &diff.added);
+    print_section("Removed", '-', &diff.removed);
+    if !diff
+        .changed
+        .is_empty()
+    {
+        println!(
+            "Changed ({})",
+            diff.changed
+                .len()
+        );
+        for c in &diff.changed
+        {
+            println!(
+                "  ~ {}  ({}, {}:{})",
+                c.qualified_name,
+                c.kind
+                    .label(),
+                c.file,
+                c.line
+            );
+            println!("      - {}", c.before);
+            println!("      + {}", c.after);
+        }
+    }
+    Ok(())
+}
+
+/// One text section of added or removed symbols
+fn print_section(
+    title: &str,
+    mark: char,
+    symbols: &[Symbol],
+)
+{
+    if symbols.is_empty()
+    {
+        return;
+    }
+    println!("{title} ({})", symbols.len());
+    for s in symbols
+    {
+        println!(
+            "  {mark} {}  ({}, {}:{})",
+            s.qualified_name,
+            s.kind
+                .label(),
+            s.file
+                .display(),
+            s.start_line
+        );
+        if let Some(sig) = &s.signature
+        {
+            println!("      {sig}");
+        }
+    }
+}
+
+/// API declarations of `files` as of `rev`; files missing there add none
+fn revision_symbols(
+    repo: &Path,
+    rev: &str,
+    files: &[(&str, String)],
+    include_private: bool,
+) -> Result<Vec<Symbol>>
+{
+    let per_file: Vec<Result<Vec<Symbol>>> = files
+        .par_iter()
+        .map(|(path, lang)| {
+            // Deleted or added on one side only
+            let Ok(bytes) = git_bytes(repo, &["cat-file", "blob", &format!("{rev}:{path}")])
+            else
+            {
+                return Ok(Vec::new());
+            };
+            let Ok(content) = String::from_utf8(bytes)
+            else
+            {
+                return Ok(Vec::new());
+            };
+            extract_text(&content, Path::new(path), lang, true)
+                .with_context(|| format!("extract symbols from {rev}:{path}"))
+        })
+        .collect();
+
+    let mut symbols = Vec::new();
+    for one in per_file
+    {
+        symbols.extend(one?);
+    }
+    symbols.retain(|s| {
+        s.signature
+            .is_some()
+    });
+    if !include_private
+    {
+        let trait_methods: Vec<bool> = symbols
+            .iter()
+            .map(|s| is_public_trait_method(s, &symbols))
+            .collect();
+        let mut keep = trait_methods.into_iter();
+        // Rust items are private without `pub`, except trait methods
+        symbols.retain(|s| {
+            keep.next()
+                .unwrap_or(false)
+                || s.lang != "rust"
+                || s.visibility == Some(Visibility::Public)
+        });
+        VisibilityFilter::retain_public(&mut symbols);
+    }
+    Ok(symbols)
+}
+
+/// Whether a Rust method belongs to a public trait declared in its file
+fn is_public_trait_method(
+    method: &Symbol,
+    symbols: &[Symbol],
+) -> bool
+{
+    let Some((owner, _)) = method
+        .qualified_name
+        .rsplit_once("::")
+    else
+    {
+        return false;
+    };
+    method.kind == SymbolKind::Method
+        && symbols
+            .iter()
+            .any(|t| {
+                t.kind == SymbolKind::Trait
+                    && t.name == owner
+                    && t.file == method.file
+                    && t.visibility == Some(Visibility::Public)
+            })
+}
+
+/// Run git and return its trimmed stdout
+fn git(
+    repo: &Path,
+    args: &[&str],
+) -> Result<String>
+{
+    let out = git_bytes(repo, args)?;
+    Ok(String::from_utf8_lossy(&out)
+        .trim_end()
+        .to_string())
+}
+
+/// Run git and return its raw stdout
+fn git_bytes(
+    repo: &Path,
+    args: &[&str],
+) -> Result<Vec<u8>>
+{
+    let out = Command::new("git")
+        .args(args)
+        .current_dir(repo)
+        .output()
+        .with_context(|| format!("run git {}", args[0]))?;
+    if !out
+        .status
+        .success()
+    {
+        bail!(
+            "git {} failed: {}",
+            args[0],
+            String::from_utf8_lossy(&out.stderr).trim()
+        );
+    }
+    Ok(out.stdout)
+}
+
+#[cfg(test)]
+mod tests
+{
+    use std::path::PathBuf;
+
+    use super::*;
+
+    fn sym(
+        qualified_name: &str,
+        signature: &str,
+    ) -> Symbol
+    {
+        Symbol {
+            file: PathBuf::from("src/lib.rs"),
+            lang: "rust".into(),
+            kind: SymbolKind::Function,
+            name: qualified_name.into(),
+            qualified_name: qualified_name.into(),
+            byte_start: 0,
+            byte_end: 1,
+            start_line: 1,
+            end_line: 1,
+            visibility: None,
+            doc: None,
+            signature: Some(signature.into()),
+            package: None,
+            module: None,
+        }
+    }
+
+    #[test]
+    fn pairs_by_name_and_compares_signatures()
+    {
+        let before = vec![
+            sym("keep", "fn keep()"),
+            sym("gone", "fn gone()"),
+            sym("get", "fn get(k: &str)"),
+            sym("put", "fn put(k: i32)"),
+        ];
+        let after = vec![
+            sym("keep", "fn keep()"),
+            sym("get", "fn get(k: &K)"),
+            sym("put", "fn put(k: i32)"),
+            sym("put", "fn put(k: &str)"),
+            sym("fresh", "fn fresh() -> u8"),
+        ];
+        let diff = ApiDiff::between(before, after);
+
+        let names = |v: &[Symbol]| {
+            v.iter()
+                .map(|s| {
+                    s.signature
+                        .clone()
+                        .unwrap()
+                })
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(names(&diff.removed), ["fn gone()"]);
+        // The untouched overload pairs with itself; the other one is new
+        assert_eq!(names(&diff.added), ["fn fresh() -> u8", "fn put(k: &str)"]);
+        assert_eq!(
+            diff.changed
+                .len(),
+            1
+        );
+        assert_eq!(diff.changed[0].qualified_name, "get");
+        assert_eq!(
+            (
+                diff.changed[0]
+                    .before
+                    .as_str(),
+                diff.changed[0]
+                    .after
+                    .as_str()
+            ),
+            ("fn get(k: &str)", "fn get(k: &K)")
+        );
+        assert!(!diff.is_empty());
+        assert!(ApiDiff::between(Vec::new(), Vec::new()).is_empty());
+    }
+}
@@ -0,0 +1,295 @@
+//! SQLite copy of the symbol index, for `rup symbols --format sqlite`
+//!
+//! The database is built by the `sqlite3` command-line tool from one SQL
+//! script, so roughup links no SQLite library; the tool must be on PATH.
+//! It is written next to the JSONL index (`symbols.jsonl` →
+//! `symbols.sqlite`) and replaced whole on every run.
+//!
+//! Schema (version 2), mirroring the JSONL records field for field:
+//!
+//! ```sql
+//! meta(key TEXT PRIMARY KEY, value TEXT)          -- schema_version, generator
+//! symbols(id INTEGER PRIMARY KEY, file, lang, kind, name, qualified_name,
+//!         byte_start, byte_end, start_line, end_line,
+//!         visibility, doc, signature, package, module) -- last five nullable
+//! refs(file, lang, name, caller, byte_start, byte_end, line)
+//! relations(file, lang, kind, source, target, byte_start, byte_end, line)
+//! ```
+//!
+//! `refs` is empty unless `--refs` was given. Symbols are indexed by name,
+//! qualified name, and file; refs by name; relations by target.
+
+use std::{
+    fmt::Write as _,
+    io::Write as _,
+    path::{Path, PathBuf},
+    process::{Command, Stdio},
+};
+
+use anyhow::{Context, Result, bail};
+
+use crate::core::symbols::{Reference, Relation, Symbol};
+
+/// Version recorded in `meta`; bumped when a table changes shape
+pub const SCHEMA_VERSION: u32 = 2;
+
+const SCHEMA: &str = "\
+CREATE TABLE meta(key TEXT PRIMARY KEY, value TEXT NOT NULL);
+CREATE TABLE symbols(
+  id INTEGER PRIMARY KEY,
+  file TEXT NOT NULL,
+  lang TEXT NOT NULL,
+  kind TEXT NOT NULL,
+  name TEXT NOT NULL,
+  qualified_name TEXT NOT NULL,
+  byte_start INTEGER NOT NULL,
+  byte_end INTEGER NOT NULL,
+  start_line INTEGER NOT NULL,
+  end_line INTEGER NOT NULL,
+  visibility TEXT,
+  doc TEXT,
+  signature TEXT,
+  package TEXT,
+  module TEXT
+);
+CREATE TABLE refs(
+  file TEXT NOT NULL,
+  lang TEXT NOT NULL,
+  name TEXT NOT NULL,
+  caller TEXT,
+  byte_start INTEGER NOT NULL,
+  byte_end INTEGER NOT NULL,
+  line INTEGER NOT NULL
+);
+CREATE TABLE relations(
+  file TEXT NOT NULL,
+  lang TEXT NOT NULL,
+  kind TEXT NOT NULL,
+  source TEXT NOT NULL,
+  target TEXT NOT NULL,
+  byte_start INTEGER NOT NULL,
+  byte_end INTEGER NOT NULL,
+  line INTEGER NOT NULL
+);
+";
+
+const INDEXES: &str = "\
+CREATE INDEX symbols_name ON symbols(name);
+CREATE INDEX symbols_qualified_name ON symbols(qualified_name);
+CREATE INDEX symbols_file ON symbols(file);
+CREATE INDEX refs_name ON refs(name);
+CREATE INDEX relations_target ON relations(target);
+";
+
+/// SQLite database kept next to a JSONL index: `symbols.jsonl` →
+/// `symbols.sqlite`
+pub fn sqlite_path(jsonl: &Path) -> PathBuf
+{
+    jsonl.with_extension("sqlite")
+}
+
+/// SQL script creating and filling the database
+pub fn render_sql(
+    symbols: &[Symbol],
+    refs: &[Reference],
+    relations: &[Relation],
+) -> String
+{
+    let mut sql = String::from("BEGIN;\n");
+    sql.push_str(SCHEMA);
+    let _ = writeln!(
+        sql,
+        "INSERT INTO meta VALUES('schema_version',{}),('generator',{});",
+        text(&SCHEMA_VERSION.to_string()),
+        text(&format!("roughup {}", env!("CARGO_PKG_VERSION")))
+    );
+    for (id, s) in symbols
+        .iter()
+        .enumerate()
+    {
+        let _ = writeln!(
+            sql,
+            "INSERT INTO symbols VALUES({id},{},{},{},{},{},{},{},{},{},{},{},{},{},{});",
+            text(
+                &s.file
+                    .to_string_lossy()
+            ),
+            text(&s.lang),
+            text(
+                &s.kind
+                    .label()
+            ),
+            text(&s.name),
+            text(&s.qualified_name),
+            s.byte_start,
+            s.byte_end,
+            s.start_line,
+            s.end_line,
+            nullable(
+                s.visibility
+                    .as_ref()
+                    .and_then(|v| {
+                        serde_json::to_value(v)
+                            .ok()?
+                            .as_str()
+                            .map(str::to_string)
+                    })
+                    .as_deref()
+            ),
+            nullable(
+                s.doc
+                    .as_deref()
+            ),
+            nullable(
+                s.signature
+                    .as_deref()
+            ),
+            nullable(
+                s.package
+                    .as_deref()
+            ),
+            nullable(
+                s.module
+                    .as_deref()
+            ),
+        );
+    }
+    for r in refs
+    {
+        let _ = writeln!(
+            sql,
+            "INSERT INTO refs VALUES({},{},{},{},{},{},{});",
+            text(
+                &r.file
+                    .to_string_lossy()
+            ),
+            text(&r.lang),
+            text(&r.name),
+            nullable(
+                r.caller
+                    .as_deref()
+            ),
+            r.byte_start,
+            r.byte_end,
+            r.line
+        );
+    }
+    for r in relations
+    {
+        let kind = serde_json::to_value(r.kind).unwrap_or_default();
+        let _ = writeln!(
+            sql,
+            "INSERT INTO relations VALUES({},{},{},{},{},{},{},{});",
+            text(
+                &r.file
+                    .to_string_lossy()
+            ),
+            text(&r.lang),
+            text(
+                kind.as_str()
+                    .unwrap_or_default()
+            ),
+            text(&r.source),
+            text(&r.target),
+            r.byte_start,
+            r.byte_end,
+            r.line
+        );
+    }
+    sql.push_str(INDEXES);
+    sql.push_str("COMMIT;\n");
+    sql
+}
+
+/// Build the database at `path` from `sql` with the `sqlite3` tool,
+/// replacing any earlier one only once the new one is complete
+pub fn write_sqlite(
+    sql: &str,
+    path: &Path,
+) -> Result<()>
+{
+    let tmp = path.with_extension("sqlite.tmp");
+    let _ = std::fs::remove_file(&tmp);
+    let mut child = Command::new("sqlite3")
+        .arg("-bail")
+        .arg(&tmp)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::null())
+        .stderr(Stdio::piped())
+        .spawn()
+        .context("Failed to run sqlite3 (is it on PATH?)")?;
+    if let Some(mut stdin) = child
+        .stdin
+        .take()
+    {
+        // sqlite3 exits early on a bad statement; its status says why
+        let _ = stdin.write_all(sql.as_bytes());
+    }
+    let out = child
+        .wait_with_output()
+        .context("Failed to wait for sqlite3")?;
+    if !out
+        .status
+        .success()
+    {
+        let _ = std::fs::remove_file(&tmp);
+        bail!(
+            "sqlite3 failed: {}",
+            String::from_utf8_lossy(&out.stderr).trim()
+        );
+    }
+    std::fs::rename(&tmp, path).with_context(|| format!("Failed to write {}", path.display()))
+}
+
+/// SQL string literal; NUL cannot appear in one and is dropped
+fn text(s: &str) -> String
+{
+    format!(
+        "'{}'",
+        s.replace('\0', "")
+            .replace('\'', "''")
+    )
+}
+
+/// SQL string literal or NULL
+fn nullable(s: Option<&str>) -> String
+{
+    s.map_or_else(|| "NULL".to_string(), text)
+}
+
+#[cfg(test)]
+mod tests
+{
+    use super::*;
+    use crate::core::symbols::{SymbolKind, Visibility};
+
+    #[test]
+    fn script_quotes_text_and_nulls()
+    {
+        let symbol = Symbol {
+            file: PathBuf::from("src/it's.rs"),
+            lang: "rust".into(),
+            kind: SymbolKind::TypeAlias,
+            name: "Id".into(),
+            qualified_name: "Id".into(),
+            byte_start: 0,
+            byte_end: 14,
+            start_line: 1,
+            end_line: 1,
+            visibility: Some(Visibility::Public),
+            doc: None,
+            signature: Some("pub type Id = u\0 32".into()),
+            package: Some("core".into()),
+            module: None,
+        };
+        let sql = render_sql(&[symbol], &[], &[]);
+        assert!(sql.starts_with("BEGIN;\nCREATE TABLE meta"));
+        assert!(sql.contains(
+            "INSERT INTO symbols \
+             VALUES(0,'src/it''s.rs','rust','type_alias','Id','Id',0,14,1,1,'public',NULL,'pub \
+             type Id = u 32','core',NULL);"
+        ));
+        assert!(sql.contains("('schema_version','2')"));
+        assert!(sql.ends_with("COMMIT;\n"));
+    }
+}
@@ -0,0 +1,441 @@
+//! Background indexing for `rup symbols --watch`
+//!
+//! After one full pass the watcher keeps every file's records in memory and
+//! listens for filesystem events under the root. A burst of events is
+//! debounced, then only the files it touched are re-extracted (plus any new
+//! ones the gitignore-aware walk finds) and the index files rewritten.
+//!
+//! While it runs, a marker next to the index (`symbols.jsonl` →
+//! `symbols.watch`) tells `rup context` and `rup search` the index is
+//! fresh, so they skip their freshness walk and lockfile. The marker reads
+//! `indexing` during a rebuild, which readers wait out, and is rewritten
+//! every couple of seconds; once stale (the watcher was killed) it is
+//! ignored and the usual checks apply again.
+
+use std::{
+    collections::{BTreeMap, HashSet},
+    path::{Path, PathBuf},
+    sync::mpsc::{RecvTimeoutError, channel},
+    time::{Duration, Instant, SystemTime},
+};
+
+use anyhow::{Context, Result};
+use notify::{EventKind, RecursiveMode, Watcher};
+use rayon::prelude::*;
+
+use crate::{
+    cli::{AppContext, SymbolsArgs},
+    core::symbols::{FileExtraction, extract_file, source_files, write_index},
+};
+
+/// Quiet period that ends a burst of events
+const DEBOUNCE: Duration = Duration::from_millis(150);
+
+/// How often an idle watcher rewrites its marker
+const HEARTBEAT: Duration = Duration::from_secs(2);
+
+/// Age after which a marker no longer counts as a running watcher
+const STALE_AFTER: Duration = Duration::from_secs(10);
+
+/// Longest a reader waits for a rebuild to finish
+const MAX_WAIT: Duration = Duration::from_secs(30);
+
+/// Marker kept next to a watched JSONL index: `symbols.jsonl` →
+/// `symbols.watch`
+pub fn watch_marker_path(jsonl: &Path) -> PathBuf
+{
+    jsonl.with_extension("watch")
+}
+
+/// Whether a live `rup symbols --watch` keeps `jsonl` fresh, waiting for a
+/// rebuild in progress to finish first
+pub fn watched_fresh(jsonl: &Path) -> bool
+{
+    let marker = watch_marker_path(jsonl);
+    let start = Instant::now();
+    loop
+    {
+        let live = std::fs::metadata(&marker)
+            .and_then(|m| m.modified())
+            .ok()
+            .and_then(|t| {
+                SystemTime::now()
+                    .duration_since(t)
+                    .ok()
+            })
+            .is_some_and(|age| age < STALE_AFTER);
+        if !live
+        {
+            return false;
+        }
+        // A marker caught mid-write reads empty; treat it like `indexing`
+        if std::fs::read_to_string(&marker).is_ok_and(|state| state.starts_with("idle"))
+        {
+            return true;
+        }
+        if start.elapsed() > MAX_WAIT
+        {
+            return false;
+        }
+        std::thread::sleep(Duration::from_millis(50));
+    }
+}
+
+/// Entry point for `rup symbols --watch`; runs until interrupted
+pub fn run(
+    args: &SymbolsArgs,
+    ctx: &AppContext,
+) -> Result<()>
+{
+    let root = args
+        .path
+        .canonicalize()
+        .with_context(|| {
+            format!(
+                "Failed to resolve {}",
+                args.path
+                    .display()
+            )
+        })?;
+    let marker = watch_marker_path(&args.output);
+
+    // Watch before the first pass, so no change slips in between
+    let (tx, rx) = channel();
+    let mut watcher = notify::recommended_watcher(tx).context("Failed to start file watcher")?;
+    watcher
+        .watch(&root, RecursiveMode::Recursive)
+        .with_context(|| format!("Failed to watch {}", root.display()))?;
+
+    let mut index = WatchedIndex::default();
+    set_state(&marker, "indexing")?;
+    let started = Instant::now();
+    let count = index.refresh(args, &HashSet::new(), ctx)?;
+    let symbols = index.write(args)?;
+    set_state(&marker, "idle")?;
+    // Events for the index files themselves never trigger a rebuild
+    let index_files = IndexFiles::of(&args.output);
+
+    if !ctx.quiet
+    {
+        println!(
+            "✓ Indexed {count} files ({symbols} symbols) in {}ms",
+            started
+                .elapsed()
+                .as_millis()
+        );
+        println!("Watching {} for changes (Ctrl-C to stop)", root.display());
+    }
+
+    let mut last_beat = Instant::now();
+    loop
+    {
+        let mut changed: HashSet<PathBuf> = HashSet::new();
+        match rx.recv_timeout(HEARTBEAT)
+        {
+            Ok(event) =>
+            {
+                collect_paths(event, &root, &index_files, &mut changed);
+            }
+            Err(RecvTimeoutError::Timeout) =>
+            {}
+            Err(RecvTimeoutError::Disconnected) => break,
+        }
+        // Let the burst settle: editors write, rename, and chmod in a row.
+        // Reads and index writes do not count, or they would never settle.
+        if !changed.is_empty()
+        {
+            let mut quiet_until = Instant::now() + DEBOUNCE;
+            while let Some(wait) = quiet_until.checked_duration_since(Instant::now())
+                && let Ok(event) = rx.recv_timeout(wait)
+            {
+                if collect_paths(event, &root, &index_files, &mut changed)
+                {
+                    quiet_until = Instant::now() + DEBOUNCE;
+                }
+            }
+        }
+        if !index.affected_by(&changed, args)
+        {
+            if last_beat.elapsed() >= HEARTBEAT
+            {
+                set_state(&marker, "idle")?;
+                last_beat = Instant::now();
+            }
+            continue;
+        }
+
+        set_state(&marker, "indexing")?;
+        let started = Instant::now();
+        let result = index
+            .refresh(args, &changed, ctx)
+            .and_then(|count| {
+                index
+                    .write(args)
+                    .map(|symbols| (count, symbols))
+            });
+        set_state(&marker, "idle")?;
+        last_beat = Instant::now();
+        match result
+        {
+            Ok((count, symbols)) if !ctx.quiet =>
+            {
+                println!(
+                    "↻ Re-indexed {count} file(s) ({symbols} symbols) in {}ms",
+                    started
+                        .elapsed()
+                        .as_millis()
+                );
+            }
+            Ok(_) =>
+            {}
+            Err(e) => eprintln!("(warn) re-indexing failed: {e:#}"),
+        }
+    }
+
+    let _ = std::fs::remove_file(&marker);
+    Ok(())
+}
+
+/// Record the watcher's state in its marker, which also refreshes its age
+fn set_state(
+    marker: &Path,
+    state: &str,
+) -> Result<()>
+{
+    if let Some(parent) = marker.parent()
+        && !parent
+            .as_os_str()
+            .is_empty()
+    {
+        std::fs::create_dir_all(parent)
+            .with_context(|| format!("Failed to create directory {}", parent.display()))?;
+    }
+    std::fs::write(marker, format!("{state} {}\n", std::process::id()))
+        .with_context(|| format!("Failed to write {}", marker.display()))
+}
+
+/// The index and the files kept next to it (`symbols.*`)
+struct IndexFiles
+{
+    dir: Option<PathBuf>,
+    prefix: String,
+}
+
+impl IndexFiles
+{
+    fn of(jsonl: &Path) -> Self
+    {
+        let dir = jsonl
+            .parent()
+            .filter(|p| {
+                !p.as_os_str()
+                    .is_empty()
+            })
+            .unwrap_or(Path::new("."))
+            .canonicalize()
+            .ok();
+        let stem = jsonl
+            .file_stem()
+            .unwrap_or_default()
+            .to_string_lossy();
+        Self { dir, prefix: format!("{stem}.") }
+    }
+
+    fn contains(
+        &self,
+        path: &Path,
+    ) -> bool
+    {
+        self.dir
+            .as_deref()
+            .is_some_and(|dir| path.parent() == Some(dir))
+            && path
+                .file_name()
+                .is_some_and(|n| {
+                    n.to_string_lossy()
+                        .starts_with(&self.prefix)
+                })
+    }
+}
+
+/// Add the paths an event touched, relative to `root`, minus reads and
+/// the index files themselves; returns whether any were added
+fn collect_paths(
+    event: notify::Result<notify::Event>,
+    root: &Path,
+    index_files: &IndexFiles,
+    out: &mut HashSet<PathBuf>,
+) -> bool
+{
+    let Ok(event) = event
+    else
+    {
+        return false;
+    };
+    if matches!(event.kind, EventKind::Access(_))
+    {
+        return false;
+    }
+    let mut added = false;
+    for path in event.paths
+    {
+        if !index_files.contains(&path)
+            && let Ok(rel) = path.strip_prefix(root)
+        {
+            out.insert(rel.to_path_buf());
+            added = true;
+        }
+    }
+    added
+}
+
+/// Records of every indexed file, by path relative to the root
+#[derive(Default)]
+struct WatchedIndex
+{
+    files: BTreeMap<PathBuf, FileExtraction>,
+}
+
+impl WatchedIndex
+{
+    /// Whether any changed path is a source file in the selected languages
+    /// or holds indexed files (a removed or renamed directory)
+    fn affected_by(
+        &self,
+        changed: &HashSet<PathBuf>,
+        args: &SymbolsArgs,
+    ) -> bool
+    {
+        let cfg = crate::infra::config::load_config().unwrap_or_default();
+        let langs = crate::core::symbols::LanguageSelector::resolve(&args.languages, &cfg);
+        changed
+            .iter()
+            .any(|rel| {
+                crate::core::symbols::selected_language(rel, &langs).is_some()
+                    || self
+                        .files
+                        .keys()
+                        .any(|f| f.starts_with(rel))
+            })
+    }
+
+    /// Walk the root again, forget vanished files, and extract new files
+    /// and the `changed` ones; returns how many were extracted. A file
+    /// that fails to extract (say, caught mid-save) is left out until its
+    /// next change.
+    fn refresh(
+        &mut self,
+        args: &SymbolsArgs,
+        changed: &HashSet<PathBuf>,
+        ctx: &AppContext,
+    ) -> Result<usize>
+    {
+        let (files, _) = source_files(args)?;
+        let current: BTreeMap<PathBuf, (PathBuf, String)> = files
+            .into_iter()
+            .map(|(path, lang)| {
+                let rel = path
+                    .strip_prefix(&args.path)
+                    .unwrap_or(&path)
+                    .to_path_buf();
+                (rel, (path, lang))
+            })
+            .collect();
+
+        self.files
+            .retain(|rel, _| current.contains_key(rel));
+        let todo: Vec<(&PathBuf, &(PathBuf, String))> = current
+            .iter()
+            .filter(|(rel, _)| {
+                changed.contains(*rel)
+                    || !self
+                        .files
+                        .contains_key(*rel)
+            })
+            .collect();
+
+        let extracted: Vec<(PathBuf, Result<FileExtraction>)> = todo
+            .par_iter()
+            .map(|(rel, (path, lang))| ((*rel).clone(), extract_file(path, lang, args)))
+            .collect();
+        let count = extracted.len();
+        for (rel, one) in extracted
+        {
+            match one
+            {
+                Ok(one) =>
+                {
+                    self.files
+                        .insert(rel, one);
+                }
+                Err(e) =>
+                {
+                    self.files
+                        .remove(&rel);
+                    if !ctx.quiet
+                    {
+                        eprintln!("(warn) skipped {}: {e:#}", rel.display());
+                    }
+                }
+            }
+        }
+        Ok(count)
+    }
+
+    /// Rewrite the index files from the records held; returns the number
+    /// of symbols written
+    fn write(
+        &self,
+        args: &SymbolsArgs,
+    ) -> Result<usize>
+    {
+        let mut all = FileExtraction::default();
+        for one in self
+            .files
+            .values()
+        {
+            all.symbols
+                .extend_from_slice(&one.symbols);
+            all.references
+                .extend_from_slice(&one.references);
+            all.relations
+                .extend_from_slice(&one.relations);
+        }
+        Ok(write_index(all, args)?.symbols)
+    }
+}
+
+#[cfg(test)]
+mod tests
+{
+    use super::*;
+
+    #[test]
+    fn marker_counts_only_while_fresh_and_idle()
+    {
+        let dir = tempfile::tempdir().unwrap();
+        let jsonl = dir
+            .path()
+            .join("symbols.jsonl");
+        let marker = watch_marker_path(&jsonl);
+        assert_eq!(
+            marker,
+            dir.path()
+                .join("symbols.watch")
+        );
+        assert!(!watched_fresh(&jsonl));
+
+        set_state(&marker, "idle").unwrap();
+        assert!(watched_fresh(&jsonl));
+
+        // A marker left behind by a killed watcher goes stale
+        let old = SystemTime::now() - STALE_AFTER * 2;
+        std::fs::File::options()
+            .write(true)
+            .open(&marker)
+            .unwrap()
+            .set_modified(old)
+            .unwrap();
+        assert!(!watched_fresh(&jsonl));
+    }
+}
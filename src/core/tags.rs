@@ -0,0 +1,192 @@
+//! Editor tags files from the symbol index, for `rup symbols --format`
+//!
+//! ctags output follows the extended format vim and Universal ctags read:
+//! a `!_TAG_` header, then one `name<TAB>file<TAB>line;"` line per tag,
+//! sorted by name so editors can binary-search it, with `kind:` and `line:`
+//! fields. Symbols whose qualified name differs from their name get a
+//! second tag under the qualified name (`Cache::get`).
+//!
+//! etags output is the Emacs `TAGS` layout: a form-feed section per file
+//! whose entries pair the source line up to the name with the explicit tag
+//! name, line number, and byte offset of the line.
+//!
+//! Paths stay relative to the scanned root, where both files are written.
+
+use std::{collections::BTreeMap, fmt::Write as _, path::Path};
+
+use crate::core::symbols::Symbol;
+
+/// Extended-format ctags file for `symbols`
+pub fn render_ctags(symbols: &[Symbol]) -> String
+{
+    // (tag, file, line, kind)
+    let mut tags: Vec<(&str, String, usize, String)> = Vec::new();
+    for s in symbols
+    {
+        let file = s
+            .file
+            .to_string_lossy()
+            .replace('\\', "/");
+        let mut names = vec![
+            s.name
+                .as_str(),
+        ];
+        if s.qualified_name != s.name
+        {
+            names.push(&s.qualified_name);
+        }
+        for name in names
+        {
+            // A tab or newline would break the line format
+            if name.is_empty() || name.contains(['\t', '\n', '\r'])
+            {
+                continue;
+            }
+            tags.push((
+                name,
+                file.clone(),
+                s.start_line,
+                s.kind
+                    .label(),
+            ));
+        }
+    }
+    tags.sort();
+    tags.dedup();
+
+    let mut out = String::from(
+        "!_TAG_FILE_FORMAT\t2\t/extended format; --format=1 will not append ;\" to \
+         lines/\n!_TAG_FILE_SORTED\t1\t/0=unsorted, 1=sorted, \
+         2=foldcase/\n!_TAG_PROGRAM_NAME\troughup\t//\n",
+    );
+    for (name, file, line, kind) in tags
+    {
+        let _ = writeln!(out, "{name}\t{file}\t{line};\"\tkind:{kind}\tline:{line}");
+    }
+    out
+}
+
+/// Emacs `TAGS` file for `symbols`, reading each file under `root` for the
+/// line text and offsets; files that can no longer be read are skipped
+pub fn render_etags(
+    symbols: &[Symbol],
+    root: &Path,
+) -> String
+{
+    let mut by_file: BTreeMap<&Path, Vec<&Symbol>> = BTreeMap::new();
+    for s in symbols
+    {
+        by_file
+            .entry(&s.file)
+            .or_default()
+            .push(s);
+    }
+
+    let mut out = String::new();
+    for (file, mut syms) in by_file
+    {
+        let Ok(content) = std::fs::read_to_string(root.join(file))
+        else
+        {
+            continue;
+        };
+        let line_starts: Vec<usize> = std::iter::once(0)
+            .chain(
+                content
+                    .match_indices('\n')
+                    .map(|(i, _)| i + 1),
+            )
+            .collect();
+        syms.sort_by_key(|s| (s.start_line, s.byte_start));
+
+        let mut section = String::new();
+        for s in syms
+        {
+            let Some(&start) = line_starts.get(s.start_line - 1)
+            else
+            {
+                continue;
+            };
+            let line = content[start..]
+                .lines()
+                .next()
+                .unwrap_or_default();
+            // Emacs matches on the line text up to the tag's name
+            let text = line
+                .find(&s.name)
+                .map_or(line, |at| {
+                    &line[..at
+                        + s.name
+                            .len()]
+                });
+            let _ = writeln!(section, "{text}\x7f{}\x01{},{start}", s.name, s.start_line);
+        }
+        let _ = write!(
+            out,
+            "\x0c\n{},{}\n{section}",
+            file.to_string_lossy()
+                .replace('\\', "/"),
+            section.len()
+        );
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests
+{
+    use std::path::PathBuf;
+
+    use super::*;
+    use crate::core::symbols::SymbolKind;
+
+    fn sym(
+        kind: SymbolKind,
+        qualified_name: &str,
+        start_line: usize,
+    ) -> Symbol
+    {
+        Symbol {
+            file: PathBuf::from("src/cache.rs"),
+            lang: "rust".into(),
+            kind,
+            name: qualified_name
+                .rsplit("::")
+                .next()
+                .unwrap()
+                .into(),
+            qualified_name: qualified_name.into(),
+            byte_start: 0,
+            byte_end: 1,
+            start_line,
+            end_line: start_line,
+            visibility: None,
+            doc: None,
+            signature: None,
+            package: None,
+            module: None,
+        }
+    }
+
+    #[test]
+    fn ctags_lines_are_sorted_with_qualified_tags()
+    {
+        let tags = render_ctags(&[
+            sym(SymbolKind::Struct, "Cache", 1),
+            sym(SymbolKind::Method, "Cache::get", 5),
+            sym(SymbolKind::TypeAlias, "Alias", 9),
+        ]);
+        let lines: Vec<&str> = tags
+            .lines()
+            .filter(|l| !l.starts_with("!_TAG_"))
+            .collect();
+        assert_eq!(lines, [
+            "Alias\tsrc/cache.rs\t9;\"\tkind:type_alias\tline:9",
+            "Cache\tsrc/cache.rs\t1;\"\tkind:struct\tline:1",
+            "Cache::get\tsrc/cache.rs\t5;\"\tkind:method\tline:5",
+            "get\tsrc/cache.rs\t5;\"\tkind:method\tline:5",
+        ]);
+        assert!(tags.starts_with("!_TAG_FILE_FORMAT\t2\t"));
+        assert!(tags.contains("!_TAG_FILE_SORTED\t1\t"));
+    }
+}
@@ -0,0 +1,86 @@
+//! Tokenizers the [`Budgeter`](crate::core::budgeter::Budgeter) counts and
+//! trims with
+//!
+//! GPT models and encodings use tiktoken. Local models (Llama, Mistral,
+//! Qwen) use the HuggingFace `tokenizer.json` shipped with their weights,
+//! passed as `--tokenizer-file`.
+
+use std::path::Path;
+
+use anyhow::{Result, anyhow};
+use tiktoken_rs::CoreBPE;
+
+/// Turns text into token IDs and back
+pub trait Tokenizer: Send + Sync
+{
+    /// Token IDs of `text`, without special tokens
+    fn encode(
+        &self,
+        text: &str,
+    ) -> Vec<u32>;
+
+    /// Text of `ids`; empty if they don't decode
+    fn decode(
+        &self,
+        ids: &[u32],
+    ) -> String;
+}
+
+impl Tokenizer for CoreBPE
+{
+    fn encode(
+        &self,
+        text: &str,
+    ) -> Vec<u32>
+    {
+        self.encode_ordinary(text)
+    }
+
+    fn decode(
+        &self,
+        ids: &[u32],
+    ) -> String
+    {
+        CoreBPE::decode(self, ids.to_vec()).unwrap_or_default()
+    }
+}
+
+/// Tokenizer loaded from a HuggingFace `tokenizer.json`
+pub struct HfTokenizer(tokenizers::Tokenizer);
+
+impl HfTokenizer
+{
+    pub fn from_file(path: &Path) -> Result<Self>
+    {
+        tokenizers::Tokenizer::from_file(path)
+            .map(Self)
+            .map_err(|e| anyhow!("load tokenizer {}: {e}", path.display()))
+    }
+}
+
+impl Tokenizer for HfTokenizer
+{
+    fn encode(
+        &self,
+        text: &str,
+    ) -> Vec<u32>
+    {
+        self.0
+            .encode(text, false)
+            .map(|e| {
+                e.get_ids()
+                    .to_vec()
+            })
+            .unwrap_or_default()
+    }
+
+    fn decode(
+        &self,
+        ids: &[u32],
+    ) -> String
+    {
+        self.0
+            .decode(ids, false)
+            .unwrap_or_default()
+    }
+}
@@ -2,6 +2,24 @@
 //! Tree view that appends per-file total line counts as `name:lines`
 //! e.g., `main.rs:100`. Directories are displayed as before.
 //!
+//! With `--tokens`, files and directories also show their token count
+//! (`main.rs:100 [812 tok]`, `src/ [9.4k tok]`); a directory's count sums
+//! every file below it, including those past `--depth`.
+//!
+//! `--lang` (a language or an extension) and `--include`/`--exclude`
+//! globs narrow the files shown; directories appear only when they hold
+//! one of them.
+//!
+//! `--symbols` lists each source file's top-level symbols from the index
+//! below it (`fn run:27`), a structural map of the codebase.
+//!
+//! `--collapse-over N` prints a directory with more than N entries as one
+//! line with its file count (`node_modules/ … 1,204 files`).
+//!
+//! With `--git-status`, changed files carry their `git status` marker
+//! (`M` modified, `A` added, `R` renamed, `U` conflicted, `?` untracked)
+//! and directories holding any of them a `*`.
+//!
 //! Performance notes:
 //! - Counts lines by scanning bytes for '\n' (CRLF-safe).
 //! - Memory-mapped for files > 1MB (configurable here).
@@ -11,14 +29,21 @@ use std::{
     collections::BTreeMap,
     fs,
     path::{Path, PathBuf},
+    time::SystemTime,
 };
 
 use anyhow::{Context, Result};
 use owo_colors::OwoColorize;
 use ptree::TreeBuilder;
+use rayon::prelude::*;
 
 use crate::{
-    cli::{AppContext, TreeArgs},
+    cli::{AppContext, TreeArgs, TreeFormat, TreeSort},
+    core::{
+        budgeter::Budgeter,
+        symbol_index::SymbolIndex,
+        symbols::{FileFilter, Symbol, top_level},
+    },
     infra::{config::load_config, walk::FileWalker},
 };
 
@@ -55,13 +80,73 @@ pub fn run(
         return Ok(());
     }
 
+    if args.sort == TreeSort::Tokens && args.tokens.is_none()
+    {
+        anyhow::bail!("--sort tokens needs --tokens");
+    }
+
+    // Token counts for the requested model, else the configured one
+    let budgeter = match args.tokens
+    {
+        Some(model) =>
+        {
+            let model = if model.is_empty() { config.chunk.model.clone() } else { model };
+            Some(Budgeter::for_model(&model, args.tokenizer_file.as_deref())?)
+        }
+        None => None,
+    };
+
+    // Narrow to the requested languages and paths
+    let filter = FileFilter::new(&args.lang, &args.include, &args.exclude)?;
+    let mut files = walker.walk_files(&args.path);
+    files.retain(|path| {
+        filter.allows(
+            path.strip_prefix(&args.path)
+                .unwrap_or(path),
+        )
+    });
+
     // Build file tree with per-file line counts
-    let tree = build_tree_with_counts(&args.path, &walker, args.depth)?;
+    let mut tree = build_tree_with_counts(&args.path, &files, args.depth)?;
+    if let Some(budgeter) = &budgeter
+    {
+        add_token_counts(&mut tree, &args.path, &files, budgeter);
+    }
+    if args.git_status
+    {
+        let mut marks = git_status(&args.path)?;
+        marks.retain(|rel, _| filter.allows(rel));
+        add_git_marks(&mut tree, &marks);
+    }
 
-    // Print tree (unless quiet)
-    if !ctx.quiet
+    if args.symbols
     {
-        print_tree(&tree)?;
+        let index_path = PathBuf::from(&config.symbols.output_file);
+        if !crate::core::symbol_shards::index_exists(&index_path)
+        {
+            anyhow::bail!(
+                "--symbols needs the symbol index at {}; run `rup symbols` first",
+                index_path.display()
+            );
+        }
+        let prefix = index_prefix(&args.path);
+        let index = SymbolIndex::load_focused(&index_path, std::slice::from_ref(&prefix))?;
+        add_symbols(&mut tree, &prefix, &files, &args.path, &index);
+    }
+
+    // Exports are the command's output; the text tree is skipped when quiet
+    let view = View {
+        collapse_over: args.collapse_over,
+        sort: args.sort,
+        du: args.du,
+        color: args.format == TreeFormat::Text,
+    };
+    match args.format
+    {
+        TreeFormat::Text if !ctx.quiet => print_tree(&tree, view)?,
+        TreeFormat::Text => {}
+        TreeFormat::Mermaid => print!("{}", render_mermaid(&outline(&tree, view))),
+        TreeFormat::Dot => print!("{}", render_dot(&outline(&tree, view))),
     }
 
     Ok(())
@@ -71,12 +156,24 @@ pub fn run(
 struct TreeNode
 {
     name: String,
-
-    #[expect(unused, reason = "TODO: MARKED FOR USE")]
     path: PathBuf,
     is_dir: bool,
     /// For files, total line count; None for directories.
     line_count: Option<usize>,
+    /// For directories, files below at any depth
+    files: usize,
+    /// Bytes of the file, or of every file below a directory
+    bytes: u64,
+    /// When the file, or the newest file below a directory, last changed
+    modified: Option<SystemTime>,
+    /// With `--tokens`: the file's tokens, or the sum below a directory
+    tokens: Option<usize>,
+    /// With `--git-status`: how the file changed, or whether anything
+    /// below a directory did
+    git: Option<GitMark>,
+    /// With `--symbols`: kind and `name:line` of the file's top-level
+    /// symbols
+    symbols: Vec<(String, String)>,
     children: BTreeMap<String, TreeNode>,
 }
 
@@ -93,6 +190,12 @@ impl TreeNode
             path,
             is_dir,
             line_count: None,
+            files: 0,
+            bytes: 0,
+            modified: None,
+            tokens: None,
+            git: None,
+            symbols: Vec::new(),
             children: BTreeMap::new(),
         }
     }
@@ -101,7 +204,6 @@ impl TreeNode
     /// it is applied to the leaf file node.
     fn insert_path(
         &mut self,
-        full_path: &Path,
         relative_path: &Path,
         max_depth: Option<usize>,
         current_depth: usize,
@@ -131,9 +233,8 @@ impl TreeNode
             .as_os_str()
             .to_string_lossy()
             .to_string();
-        let component_path = full_path
-            .parent()
-            .unwrap_or(full_path)
+        let component_path = self
+            .path
             .join(&component_name);
         let is_dir = component_path.is_dir();
 
@@ -157,21 +258,296 @@ impl TreeNode
         }
         else
         {
-            entry.insert_path(
-                &component_path,
-                &remaining,
-                max_depth,
-                current_depth + 1,
-                file_lines,
+            entry.insert_path(&remaining, max_depth, current_depth + 1, file_lines);
+        }
+    }
+}
+
+/// Count every file's tokens and add them to its node and to each
+/// directory above it that the tree shows
+fn add_token_counts(
+    tree: &mut TreeNode,
+    root: &Path,
+    files: &[PathBuf],
+    budgeter: &Budgeter,
+)
+{
+    let counts: Vec<(PathBuf, usize)> = files
+        .par_iter()
+        .filter_map(|path| {
+            // Binary files have no meaningful token count
+            let text = fs::read_to_string(path).ok()?;
+            let rel = path
+                .strip_prefix(root)
+                .ok()?;
+            Some((rel.to_path_buf(), budgeter.count(&text)))
+        })
+        .collect();
+
+    for (rel, n) in counts
+    {
+        for_each_on_path(tree, &rel, |node, _| {
+            *node
+                .tokens
+                .get_or_insert(0) += n
+        });
+    }
+}
+
+/// Call `f` on the root and on each node down to `rel` that the tree
+/// shows, telling it whether the node is `rel` itself
+fn for_each_on_path(
+    tree: &mut TreeNode,
+    rel: &Path,
+    mut f: impl FnMut(&mut TreeNode, bool),
+)
+{
+    let mut node = tree;
+    let mut remaining = rel
+        .components()
+        .count();
+    f(node, remaining == 0);
+    for component in rel.components()
+    {
+        let name = component
+            .as_os_str()
+            .to_string_lossy();
+        let Some(child) = node
+            .children
+            .get_mut(name.as_ref())
+        else
+        {
+            break;
+        };
+        node = child;
+        remaining -= 1;
+        f(node, remaining == 0);
+    }
+}
+
+/// How `git status` reports a file
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum GitMark
+{
+    Modified,
+    Added,
+    Renamed,
+    Conflicted,
+    Untracked,
+    /// A directory with changed files below it
+    Contains,
+}
+
+impl GitMark
+{
+    /// Marker from a porcelain `XY` status; the staged letter wins
+    fn from_xy(xy: &str) -> Option<Self>
+    {
+        if xy == "??"
+        {
+            return Some(Self::Untracked);
+        }
+        let mut letters = xy.chars();
+        let (x, y) = (letters.next()?, letters.next()?);
+        if x == 'U' || y == 'U' || xy == "AA" || xy == "DD"
+        {
+            return Some(Self::Conflicted);
+        }
+        match if x == ' ' { y } else { x }
+        {
+            'M' | 'T' => Some(Self::Modified),
+            'A' => Some(Self::Added),
+            'R' | 'C' => Some(Self::Renamed),
+            _ => None,
+        }
+    }
+
+    fn letter(self) -> &'static str
+    {
+        match self
+        {
+            Self::Modified => "M",
+            Self::Added => "A",
+            Self::Renamed => "R",
+            Self::Conflicted => "U",
+            Self::Untracked => "?",
+            Self::Contains => "*",
+        }
+    }
+
+    fn label(self) -> String
+    {
+        match self
+        {
+            Self::Modified => "M"
+                .yellow()
+                .to_string(),
+            Self::Added => "A"
+                .green()
+                .to_string(),
+            Self::Renamed => "R"
+                .cyan()
+                .to_string(),
+            Self::Conflicted => "U"
+                .magenta()
+                .to_string(),
+            Self::Untracked => "?"
+                .red()
+                .to_string(),
+            Self::Contains => "*"
+                .yellow()
+                .to_string(),
+        }
+    }
+}
+
+/// Changed files under `root` by path relative to it, from `git status`
+fn git_status(root: &Path) -> Result<BTreeMap<PathBuf, GitMark>>
+{
+    let git = |args: &[&str]| -> Result<String> {
+        let out = std::process::Command::new("git")
+            .args(args)
+            .current_dir(root)
+            .output()
+            .context("Failed to run git")?;
+        if !out
+            .status
+            .success()
+        {
+            anyhow::bail!(
+                "--git-status needs a git repository: {}",
+                String::from_utf8_lossy(&out.stderr).trim()
             );
         }
+        Ok(String::from_utf8_lossy(&out.stdout).into_owned())
+    };
+    // Porcelain paths are relative to the top level, not to `root`
+    let prefix = git(&["rev-parse", "--show-prefix"])?;
+    let status = git(&["status", "--porcelain=v1", "-z", "--untracked-files=all", "--", "."])?;
+    Ok(parse_status(&status, Path::new(prefix.trim_end())))
+}
+
+/// Entries of `git status --porcelain=v1 -z` under `prefix`, relative to it
+fn parse_status(
+    out: &str,
+    prefix: &Path,
+) -> BTreeMap<PathBuf, GitMark>
+{
+    let mut marks = BTreeMap::new();
+    let mut entries = out
+        .split('\0')
+        .filter(|e| !e.is_empty());
+    while let Some(entry) = entries.next()
+    {
+        let (Some(xy), Some(path)) = (entry.get(..2), entry.get(3..))
+        else
+        {
+            continue;
+        };
+        // A rename or copy is followed by the path it came from
+        if xy.contains(['R', 'C'])
+        {
+            entries.next();
+        }
+        if let Some(mark) = GitMark::from_xy(xy)
+            && let Ok(rel) = Path::new(path).strip_prefix(prefix)
+        {
+            marks.insert(rel.to_path_buf(), mark);
+        }
+    }
+    marks
+}
+
+/// Where the tree's root sits in the index: symbol paths are relative to
+/// the directory `rup symbols` ran in, taken to be the current one
+fn index_prefix(root: &Path) -> PathBuf
+{
+    let cwd = std::env::current_dir().unwrap_or_default();
+    let root = if root.is_absolute()
+    {
+        root.strip_prefix(&cwd)
+            .unwrap_or(root)
+            .to_path_buf()
+    }
+    else
+    {
+        root.to_path_buf()
+    };
+    root.components()
+        .filter(|c| !matches!(c, std::path::Component::CurDir))
+        .collect()
+}
+
+/// Attach the top-level symbols of each shown file, in source order
+fn add_symbols(
+    tree: &mut TreeNode,
+    prefix: &Path,
+    files: &[PathBuf],
+    root: &Path,
+    index: &SymbolIndex,
+)
+{
+    for path in files
+    {
+        let Ok(rel) = path.strip_prefix(root)
+        else
+        {
+            continue;
+        };
+        let all = index.all();
+        let in_file: Vec<&Symbol> = index
+            .symbols_in_file(&prefix.join(rel))
+            .iter()
+            .map(|&i| &all[i])
+            .collect();
+        let labels: Vec<(String, String)> = top_level(&in_file)
+            .into_iter()
+            .map(|s| {
+                (
+                    s.kind
+                        .label(),
+                    format!("{}:{}", s.name, s.start_line),
+                )
+            })
+            .collect();
+        if labels.is_empty()
+        {
+            continue;
+        }
+        for_each_on_path(tree, rel, |node, is_target| {
+            if is_target && !node.is_dir
+            {
+                node.symbols = labels.clone();
+            }
+        });
+    }
+}
+
+/// Mark each changed file, and each directory above one, in the tree
+fn add_git_marks(
+    tree: &mut TreeNode,
+    marks: &BTreeMap<PathBuf, GitMark>,
+)
+{
+    for (rel, &mark) in marks
+    {
+        for_each_on_path(tree, rel, |node, is_target| {
+            if is_target && !node.is_dir
+            {
+                node.git = Some(mark);
+            }
+            else if node.is_dir
+            {
+                node.git = Some(GitMark::Contains);
+            }
+        });
     }
 }
 
 /// Build the tree and attach line counts to file leaf nodes.
 fn build_tree_with_counts(
     root: &Path,
-    walker: &FileWalker,
+    files: &[PathBuf],
     max_depth: Option<usize>,
 ) -> Result<TreeNode>
 {
@@ -184,16 +560,35 @@ fn build_tree_with_counts(
         true,
     );
 
-    // Walk all files once, compute counts, and insert.
-    for file_path in walker.walk_files(root)
+    // Compute counts for the walked files and insert.
+    for file_path in files
     {
         // Compute total lines for this file quickly.
-        let lines = count_lines_fast(&file_path)
+        let lines = count_lines_fast(file_path)
             .with_context(|| format!("counting lines for {}", file_path.display()))?;
 
+        let meta = fs::metadata(file_path).ok();
+        let bytes = meta
+            .as_ref()
+            .map_or(0, |m| m.len());
+        let modified = meta.and_then(|m| {
+            m.modified()
+                .ok()
+        });
+
         if let Ok(relative_path) = file_path.strip_prefix(root)
         {
-            tree.insert_path(&file_path, relative_path, max_depth, 0, Some(lines));
+            tree.insert_path(relative_path, max_depth, 0, Some(lines));
+            for_each_on_path(&mut tree, relative_path, |node, _| {
+                if node.is_dir
+                {
+                    node.files += 1;
+                }
+                node.bytes += bytes;
+                node.modified = node
+                    .modified
+                    .max(modified);
+            });
         }
 
         // Also insert parent directories to ensure they exist in the tree.
@@ -206,7 +601,7 @@ fn build_tree_with_counts(
             }
             if let Ok(relative_path) = parent.strip_prefix(root)
             {
-                tree.insert_path(parent, relative_path, max_depth, 0, None);
+                tree.insert_path(relative_path, max_depth, 0, None);
             }
             current = parent.parent();
         }
@@ -215,12 +610,103 @@ fn build_tree_with_counts(
     Ok(tree)
 }
 
+/// One line of the printed tree, or one node of an exported diagram
+#[derive(Debug)]
+struct Outline
+{
+    label: String,
+    shape: Shape,
+    children: Vec<Outline>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Shape
+{
+    Dir,
+    File,
+    Symbol,
+}
+
+/// How the tree is drawn
+#[derive(Debug, Clone, Copy)]
+struct View
+{
+    collapse_over: Option<usize>,
+    sort: TreeSort,
+    /// Show sizes
+    du: bool,
+    /// Terminal colors
+    color: bool,
+}
+
+/// What `node` shows: its label, its symbols, then its children in the
+/// view's order, with bulky directories below it folded
+fn outline(
+    node: &TreeNode,
+    view: View,
+) -> Outline
+{
+    let color = view.color;
+    let shape = if node.is_dir { Shape::Dir } else { Shape::File };
+    let mut children: Vec<Outline> = node
+        .symbols
+        .iter()
+        .map(|(kind, rest)| Outline {
+            label: format!(
+                "{} {rest}",
+                paint(kind, color, |t| t
+                    .dimmed()
+                    .to_string())
+            ),
+            shape: Shape::Symbol,
+            children: Vec::new(),
+        })
+        .collect();
+    for child in sorted_children(node, view.sort)
+    {
+        children.push(match collapsed_label(child, view)
+        {
+            Some(label) => Outline { label, shape: Shape::Dir, children: Vec::new() },
+            None => outline(child, view),
+        });
+    }
+    Outline { label: format_node_label(node, view), shape, children }
+}
+
+/// Children of `node` in `sort` order; ties stay alphabetical
+fn sorted_children(
+    node: &TreeNode,
+    sort: TreeSort,
+) -> Vec<&TreeNode>
+{
+    let mut children: Vec<&TreeNode> = node
+        .children
+        .values()
+        .collect();
+    match sort
+    {
+        TreeSort::Name => {}
+        TreeSort::Size => children.sort_by_key(|c| std::cmp::Reverse(c.bytes)),
+        TreeSort::Mtime => children.sort_by_key(|c| std::cmp::Reverse(c.modified)),
+        TreeSort::Tokens => children.sort_by_key(|c| std::cmp::Reverse(c.tokens)),
+    }
+    children
+}
+
 /// Print the tree with formatted labels. Files show `name:lines`.
-fn print_tree(tree: &TreeNode) -> Result<()>
+fn print_tree(
+    tree: &TreeNode,
+    view: View,
+) -> Result<()>
 {
-    let mut builder = TreeBuilder::new(format_node_label(tree));
+    let outline = outline(tree, view);
+    let mut builder = TreeBuilder::new(
+        outline
+            .label
+            .clone(),
+    );
 
-    add_children_to_builder(&mut builder, &tree.children);
+    add_children_to_builder(&mut builder, &outline.children);
 
     let tree = builder.build();
     ptree::print_tree(&tree)?;
@@ -230,48 +716,242 @@ fn print_tree(tree: &TreeNode) -> Result<()>
 
 fn add_children_to_builder(
     builder: &mut TreeBuilder,
-    children: &BTreeMap<String, TreeNode>,
+    children: &[Outline],
 )
 {
-    for child in children.values()
+    for child in children
     {
         if child
             .children
             .is_empty()
         {
-            builder.add_empty_child(format_node_label(child));
+            builder.add_empty_child(
+                child
+                    .label
+                    .clone(),
+            );
         }
         else
         {
-            builder.begin_child(format_node_label(child));
+            builder.begin_child(
+                child
+                    .label
+                    .clone(),
+            );
             add_children_to_builder(builder, &child.children);
             builder.end_child();
         }
     }
 }
 
-/// Format node label with colors and, for files, appended `:lines`.
-fn format_node_label(node: &TreeNode) -> String
+/// Mermaid flowchart of the tree, one node per line of the text view
+fn render_mermaid(outline: &Outline) -> String
 {
-    if node.is_dir
+    fn walk(
+        o: &Outline,
+        parent: Option<usize>,
+        next: &mut usize,
+        out: &mut String,
+    )
     {
-        format!(
-            "{}/",
-            node.name
-                .blue()
-        )
+        let id = *next;
+        *next += 1;
+        let label = o
+            .label
+            .replace('"', "#quot;")
+            .replace('<', "#lt;")
+            .replace('>', "#gt;");
+        let node = match o.shape
+        {
+            Shape::Dir => format!("n{id}[\"{label}\"]"),
+            Shape::File => format!("n{id}(\"{label}\")"),
+            Shape::Symbol => format!("n{id}([\"{label}\"])"),
+        };
+        match parent
+        {
+            Some(p) => out.push_str(&format!("    n{p} --> {node}\n")),
+            None => out.push_str(&format!("    {node}\n")),
+        }
+        for child in &o.children
+        {
+            walk(child, Some(id), next, out);
+        }
+    }
+
+    let mut out = String::from("flowchart LR\n");
+    walk(outline, None, &mut 0, &mut out);
+    out
+}
+
+/// Graphviz digraph of the tree, one node per line of the text view
+fn render_dot(outline: &Outline) -> String
+{
+    fn walk(
+        o: &Outline,
+        parent: Option<usize>,
+        next: &mut usize,
+        out: &mut String,
+    )
+    {
+        let id = *next;
+        *next += 1;
+        let label = o
+            .label
+            .replace('\\', "\\\\")
+            .replace('"', "\\\"");
+        let shape = match o.shape
+        {
+            Shape::Dir => "folder",
+            Shape::File => "note",
+            Shape::Symbol => "plaintext",
+        };
+        out.push_str(&format!("    n{id} [label=\"{label}\", shape={shape}];\n"));
+        if let Some(p) = parent
+        {
+            out.push_str(&format!("    n{p} -> n{id};\n"));
+        }
+        for child in &o.children
+        {
+            walk(child, Some(id), next, out);
+        }
+    }
+
+    let mut out = String::from(
+        "digraph tree {\n    rankdir=LR;\n    node [fontname=\"monospace\"];\n",
+    );
+    walk(outline, None, &mut 0, &mut out);
+    out.push_str("}\n");
+    out
+}
+
+/// `text` through `style` when coloring, else as is
+fn paint(
+    text: &str,
+    color: bool,
+    style: impl Fn(&str) -> String,
+) -> String
+{
+    if color { style(text) } else { text.to_string() }
+}
+
+/// One-line summary of a directory with more than `collapse_over` entries
+fn collapsed_label(
+    node: &TreeNode,
+    view: View,
+) -> Option<String>
+{
+    let over = view.collapse_over?;
+    if !node.is_dir
+        || node
+            .children
+            .len()
+            <= over
+    {
+        return None;
+    }
+    let files = if node.files == 1 { "file" } else { "files" };
+    let summary = format!("… {} {files}", group_digits(node.files));
+    Some(format!(
+        "{} {}",
+        format_node_label(node, view),
+        paint(&summary, view.color, |t| t.dimmed().to_string())
+    ))
+}
+
+/// `1204` as `1,204`
+fn group_digits(n: usize) -> String
+{
+    let digits = n.to_string();
+    let mut out = String::with_capacity(digits.len() + digits.len() / 3);
+    for (i, c) in digits
+        .chars()
+        .enumerate()
+    {
+        if i > 0 && (digits.len() - i) % 3 == 0
+        {
+            out.push(',');
+        }
+        out.push(c);
+    }
+    out
+}
+
+/// Format node label with colors and, for files, appended `:lines`;
+/// token counts, sizes with `--du`, and git markers follow when known.
+fn format_node_label(
+    node: &TreeNode,
+    view: View,
+) -> String
+{
+    let color = view.color;
+    let label = if node.is_dir
+    {
+        format!("{}/", paint(&node.name, color, |t| t.blue().to_string()))
     }
     else
     {
-        let colored = color_by_ext(&node.name);
+        let name = paint(&node.name, color, color_by_ext);
         match node.line_count
         {
-            Some(n) => format!("{}:{}", colored, n),
-            None => colored,
+            Some(n) => format!("{}:{}", name, n),
+            None => name,
         }
+    };
+    let label = match node.tokens
+    {
+        Some(n) =>
+        {
+            let tokens = format!("[{} tok]", format_tokens(n));
+            format!("{label} {}", paint(&tokens, color, |t| t.dimmed().to_string()))
+        }
+        None => label,
+    };
+    let label = if view.du
+    {
+        let size = format!("[{}]", format_bytes(node.bytes));
+        format!("{label} {}", paint(&size, color, |t| t.dimmed().to_string()))
+    }
+    else
+    {
+        label
+    };
+    match node.git
+    {
+        Some(mark) if color => format!("{label} {}", mark.label()),
+        Some(mark) => format!("{label} {}", mark.letter()),
+        None => label,
     }
 }
 
+/// Compact token count: `812`, `9.4k`, `1.2M`
+fn format_tokens(n: usize) -> String
+{
+    match n
+    {
+        0..1_000 => n.to_string(),
+        1_000..1_000_000 => format!("{:.1}k", n as f64 / 1_000.0),
+        _ => format!("{:.1}M", n as f64 / 1_000_000.0),
+    }
+}
+
+/// Binary size: `812 B`, `9.4 KiB`, `1.2 MiB`
+fn format_bytes(n: u64) -> String
+{
+    const UNITS: [&str; 4] = ["KiB", "MiB", "GiB", "TiB"];
+    if n < 1024
+    {
+        return format!("{n} B");
+    }
+    let mut size = n as f64 / 1024.0;
+    let mut unit = 0;
+    while size >= 1024.0 && unit < UNITS.len() - 1
+    {
+        size /= 1024.0;
+        unit += 1;
+    }
+    format!("{size:.1} {}", UNITS[unit])
+}
+
 fn color_by_ext(name: &str) -> String
 {
     if let Some(ext) = std::path::Path::new(name)
@@ -374,6 +1054,13 @@ mod tests
     use tempfile::TempDir;
 
     use super::*;
+    use crate::core::symbols::SymbolKind;
+
+    /// Uncolored, alphabetical view without sizes
+    fn plain(collapse_over: Option<usize>) -> View
+    {
+        View { collapse_over, sort: TreeSort::Name, du: false, color: false }
+    }
 
     #[test]
     fn test_tree_building_and_counts() -> Result<()>
@@ -388,8 +1075,8 @@ mod tests
         fs::write(root.join("src/main.rs"), b"fn main() {}\n")?; // 1 line (ends with \n)
         fs::write(root.join("README.md"), b"# Test\nSecond line")?; // 2 lines (no trailing \n)
 
-        let walker = FileWalker::new(&[])?;
-        let tree = build_tree_with_counts(root, &walker, None)?;
+        let files = FileWalker::new(&[])?.walk_files(root);
+        let tree = build_tree_with_counts(root, &files, None)?;
 
         // Ensure structure
         let src = tree
@@ -410,4 +1097,257 @@ mod tests
 
         Ok(())
     }
+
+    #[test]
+    fn directories_sum_tokens_below_them() -> Result<()>
+    {
+        let tmp = TempDir::new()?;
+        let root = tmp.path();
+        fs::create_dir_all(root.join("src/deep"))?;
+        fs::write(root.join("src/main.rs"), b"fn main() {}\n")?;
+        fs::write(root.join("src/deep/lib.rs"), b"pub fn helper() -> u32 { 42 }\n")?;
+        fs::write(root.join("README.md"), b"# Test\n")?;
+
+        let files = FileWalker::new(&[])?.walk_files(root);
+        let budgeter = Budgeter::new("o200k_base")?;
+        // Depth 1 hides `src/deep`, but `src/` still counts its tokens
+        let mut tree = build_tree_with_counts(root, &files, Some(1))?;
+        add_token_counts(&mut tree, root, &files, &budgeter);
+
+        let count = |path: &str| budgeter.count(&fs::read_to_string(root.join(path)).unwrap());
+        let (main, lib) = (count("src/main.rs"), count("src/deep/lib.rs"));
+        let readme = count("README.md");
+        let src = &tree.children["src"];
+        assert!(src.children.is_empty());
+        assert_eq!(src.tokens, Some(main + lib));
+        assert_eq!(tree.children["README.md"].tokens, Some(readme));
+        assert_eq!(tree.tokens, Some(main + lib + readme));
+
+        assert_eq!(format_tokens(812), "812");
+        assert_eq!(format_tokens(9_412), "9.4k");
+        assert_eq!(format_tokens(1_250_000), "1.2M");
+        Ok(())
+    }
+
+    #[test]
+    fn bulky_directories_collapse_to_a_file_count() -> Result<()>
+    {
+        let tmp = TempDir::new()?;
+        let root = tmp.path();
+        fs::create_dir_all(root.join("vendor/pkg"))?;
+        fs::create_dir_all(root.join("src"))?;
+        for i in 0..4
+        {
+            fs::write(root.join(format!("vendor/dep{i}.js")), b"x\n")?;
+        }
+        fs::write(root.join("vendor/pkg/index.js"), b"x\n")?;
+        fs::write(root.join("src/main.rs"), b"fn main() {}\n")?;
+        let files = FileWalker::new(&[])?.walk_files(root);
+        let tree = build_tree_with_counts(root, &files, Some(2))?;
+
+        // Counts reach past --depth; entries are what the tree shows
+        let vendor = &tree.children["vendor"];
+        assert_eq!(vendor.files, 5);
+        assert_eq!(vendor.children["pkg"].files, 1);
+        assert!(vendor.children["pkg"].children.is_empty());
+        assert_eq!(tree.files, 6);
+        let summary = collapsed_label(vendor, plain(Some(4))).expect("vendor collapses");
+        assert_eq!(summary, "vendor/ … 5 files");
+        assert!(collapsed_label(vendor, plain(Some(5))).is_none());
+        assert!(collapsed_label(vendor, plain(None)).is_none());
+        assert!(collapsed_label(&tree.children["src"], plain(Some(0))).is_some());
+
+        assert_eq!(group_digits(7), "7");
+        assert_eq!(group_digits(1_204), "1,204");
+        assert_eq!(group_digits(1_234_567), "1,234,567");
+        Ok(())
+    }
+
+    #[test]
+    fn files_list_their_top_level_symbols() -> Result<()>
+    {
+        let tmp = TempDir::new()?;
+        let root = tmp.path();
+        fs::create_dir_all(root.join("src"))?;
+        fs::write(root.join("src/lib.rs"), b"x\n")?;
+        let files = FileWalker::new(&[])?.walk_files(root);
+        let mut tree = build_tree_with_counts(root, &files, None)?;
+
+        let sym = |kind: SymbolKind, name: &str, span: (usize, usize), line| Symbol {
+            file: PathBuf::from("crate/src/lib.rs"),
+            lang: "rust".into(),
+            kind,
+            name: name.into(),
+            qualified_name: name.into(),
+            byte_start: span.0,
+            byte_end: span.1,
+            start_line: line,
+            end_line: line,
+            visibility: None,
+            doc: None,
+            signature: None,
+            package: None,
+            module: None,
+        };
+        let jsonl = root.join("symbols.jsonl");
+        let lines: Vec<String> = [
+            sym(SymbolKind::Impl, "Cache", (50, 90), 6),
+            sym(SymbolKind::Method, "get", (60, 80), 7),
+            sym(SymbolKind::Struct, "Cache", (0, 40), 1),
+        ]
+        .iter()
+        .map(|s| serde_json::to_string(s).unwrap())
+        .collect();
+        fs::write(&jsonl, lines.join("\n"))?;
+        let index = SymbolIndex::load(&jsonl)?;
+        add_symbols(&mut tree, Path::new("crate"), &files, root, &index);
+
+        let lib = &tree.children["src"].children["lib.rs"];
+        assert_eq!(lib.symbols.len(), 2);
+        assert_eq!(lib.symbols, [
+            ("struct".to_string(), "Cache:1".to_string()),
+            ("impl".to_string(), "Cache:6".to_string()),
+        ]);
+        assert_eq!(index_prefix(Path::new("./src/core")), Path::new("src/core"));
+        assert_eq!(index_prefix(Path::new(".")), Path::new(""));
+        Ok(())
+    }
+
+    #[test]
+    fn exports_draw_the_same_outline() -> Result<()>
+    {
+        let tmp = TempDir::new()?;
+        let root = tmp.path();
+        fs::create_dir_all(root.join("src"))?;
+        fs::write(root.join("src/lib.rs"), b"one\ntwo\n")?;
+        fs::write(root.join("src/say \"hi\".rs"), b"x\n")?;
+        let files = FileWalker::new(&[])?.walk_files(root);
+        let mut tree = build_tree_with_counts(root, &files, None)?;
+        tree.children
+            .get_mut("src")
+            .and_then(|src| src.children.get_mut("lib.rs"))
+            .expect("lib.rs")
+            .symbols
+            .push(("fn".into(), "run:2".into()));
+
+        let mermaid = render_mermaid(&outline(&tree, plain(None)));
+        assert!(mermaid.starts_with("flowchart LR\n"), "{mermaid}");
+        assert!(mermaid.contains("    n0 --> n1[\"src/\"]\n"), "{mermaid}");
+        assert!(mermaid.contains("    n1 --> n2(\"lib.rs:2\")\n"), "{mermaid}");
+        assert!(mermaid.contains("    n2 --> n3([\"fn run:2\"])\n"), "{mermaid}");
+        assert!(mermaid.contains("(\"say #quot;hi#quot;.rs:1\")"), "{mermaid}");
+        assert!(!mermaid.contains('\x1b'), "{mermaid}");
+
+        let dot = render_dot(&outline(&tree, plain(None)));
+        assert!(dot.starts_with("digraph tree {\n"), "{dot}");
+        assert!(dot.contains("    n1 [label=\"src/\", shape=folder];\n"), "{dot}");
+        assert!(dot.contains("    n3 [label=\"fn run:2\", shape=plaintext];\n"), "{dot}");
+        assert!(dot.contains("label=\"say \\\"hi\\\".rs:1\", shape=note"), "{dot}");
+        assert!(dot.contains("    n2 -> n3;\n"), "{dot}");
+        assert!(dot.ends_with("}\n"), "{dot}");
+
+        // A collapsed directory is one node; the root never collapses
+        let folded = render_dot(&outline(&tree, plain(Some(1))));
+        assert!(folded.contains("label=\"src/ … 2 files\", shape=folder"), "{folded}");
+        assert!(!folded.contains("lib.rs"), "{folded}");
+        Ok(())
+    }
+
+    #[test]
+    fn filters_pick_languages_and_paths() -> Result<()>
+    {
+        let allowed = |langs: &[&str], include: &[&str], exclude: &[&str]| -> Result<Vec<&str>> {
+            let owned = |v: &[&str]| -> Vec<String> { v.iter().map(|s| s.to_string()).collect() };
+            let filter = FileFilter::new(&owned(langs), &owned(include), &owned(exclude))?;
+            Ok(["src/main.rs", "src/app.py", "web/index.tsx", "docs/guide.md", "Makefile"]
+                .into_iter()
+                .filter(|f| filter.allows(Path::new(f)))
+                .collect())
+        };
+
+        // Extensions and language labels both work
+        assert_eq!(allowed(&["rs", "PY"], &[], &[])?, ["src/main.rs", "src/app.py"]);
+        assert_eq!(allowed(&["typescript", ".md"], &[], &[])?, ["web/index.tsx", "docs/guide.md"]);
+        assert_eq!(allowed(&[], &["src"], &["**/*.py"])?, ["src/main.rs"]);
+        assert_eq!(allowed(&["rs"], &["web/**"], &[])?, Vec::<&str>::new());
+        assert_eq!(allowed(&[], &[], &[])?.len(), 5);
+        Ok(())
+    }
+
+    #[test]
+    fn git_marks_land_on_files_and_their_directories() -> Result<()>
+    {
+        let tmp = TempDir::new()?;
+        let root = tmp.path();
+        fs::create_dir_all(root.join("src/deep"))?;
+        for file in ["src/main.rs", "src/deep/new.rs", "src/old.rs", "notes.md", "Cargo.toml"]
+        {
+            fs::write(root.join(file), b"x\n")?;
+        }
+        let files = FileWalker::new(&[])?.walk_files(root);
+        let mut tree = build_tree_with_counts(root, &files, None)?;
+
+        // Run from `app/` in the repository; `other/` is outside the tree
+        let out = " M app/src/main.rs\0A  app/src/deep/new.rs\0R  app/src/old.rs\0app/src/was.rs\0\
+                   ?? app/notes.md\0 M other/lib.rs\0 D app/gone.rs\0";
+        let marks = parse_status(out, Path::new("app/"));
+        assert_eq!(marks.len(), 4);
+        add_git_marks(&mut tree, &marks);
+
+        let src = &tree.children["src"];
+        assert_eq!(tree.git, Some(GitMark::Contains));
+        assert_eq!(src.git, Some(GitMark::Contains));
+        assert_eq!(src.children["main.rs"].git, Some(GitMark::Modified));
+        assert_eq!(src.children["old.rs"].git, Some(GitMark::Renamed));
+        assert_eq!(src.children["deep"].children["new.rs"].git, Some(GitMark::Added));
+        assert_eq!(tree.children["notes.md"].git, Some(GitMark::Untracked));
+        assert_eq!(tree.children["Cargo.toml"].git, None);
+        assert_eq!(GitMark::from_xy("UU"), Some(GitMark::Conflicted));
+        Ok(())
+    }
+
+    #[test]
+    fn entries_sort_by_size_mtime_and_tokens() -> Result<()>
+    {
+        let tmp = TempDir::new()?;
+        let root = tmp.path();
+        fs::create_dir_all(root.join("docs"))?;
+        fs::write(root.join("a.rs"), vec![b'x'; 10])?;
+        fs::write(root.join("b.rs"), vec![b'x'; 3_000])?;
+        fs::write(root.join("docs/one.md"), vec![b'x'; 1_000])?;
+        fs::write(root.join("docs/two.md"), vec![b'x'; 1_000])?;
+        let old = SystemTime::UNIX_EPOCH + std::time::Duration::from_secs(1_000_000);
+        for name in ["b.rs", "docs/one.md", "docs/two.md"]
+        {
+            fs::File::options()
+                .write(true)
+                .open(root.join(name))?
+                .set_modified(old)?;
+        }
+        let files = FileWalker::new(&[])?.walk_files(root);
+        let mut tree = build_tree_with_counts(root, &files, None)?;
+        assert_eq!(tree.children["docs"].bytes, 2_000);
+        assert_eq!(tree.bytes, 5_010);
+
+        let order = |tree: &TreeNode, sort| -> Vec<String> {
+            sorted_children(tree, sort)
+                .iter()
+                .map(|c| c.name.clone())
+                .collect()
+        };
+        assert_eq!(order(&tree, TreeSort::Name), ["a.rs", "b.rs", "docs"]);
+        assert_eq!(order(&tree, TreeSort::Size), ["b.rs", "docs", "a.rs"]);
+        assert_eq!(order(&tree, TreeSort::Mtime), ["a.rs", "b.rs", "docs"]);
+        tree.children
+            .get_mut("docs")
+            .expect("docs")
+            .tokens = Some(50);
+        assert_eq!(order(&tree, TreeSort::Tokens), ["docs", "a.rs", "b.rs"]);
+
+        let du = View { du: true, ..plain(None) };
+        assert_eq!(format_node_label(&tree.children["docs"], du), "docs/ [50 tok] [2.0 KiB]");
+        assert_eq!(format_node_label(&tree.children["a.rs"], du), "a.rs:1 [10 B]");
+        assert_eq!(format_bytes(3 * 1024 * 1024), "3.0 MiB");
+        Ok(())
+    }
 }
@@ -1,4 +1,7 @@
-use std::path::{Path, PathBuf};
+use std::{
+    collections::BTreeMap,
+    path::{Path, PathBuf},
+};
 
 use anyhow::{Context, Result};
 use serde::{Deserialize, Serialize};
@@ -6,6 +9,7 @@ use serde::{Deserialize, Serialize};
 use crate::cli::{AppContext, InitArgs};
 
 #[derive(Debug, Serialize, Deserialize)]
+#[serde(default)] // partial config files fall back to defaults per section
 pub struct Config
 {
     /// Default ignore patterns (in addition to .gitignore)
@@ -25,6 +29,15 @@ pub struct Config
 
     /// Default chunking settings
     pub chunk: ChunkConfig,
+
+    /// Backup session settings
+    pub backup: BackupConfig,
+
+    /// Apply and patch generation settings
+    pub apply: ApplyConfig,
+
+    /// Conflict resolution settings
+    pub resolve: ResolveConfig,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -43,19 +56,225 @@ pub struct TreeConfig
 }
 
 #[derive(Debug, Serialize, Deserialize)]
+#[serde(default)]
 pub struct SymbolsConfig
 {
     pub languages: Vec<String>,
     pub include_private: bool,
     pub output_file: String,
+
+    /// Record declaration signatures, as `rup symbols --signatures` does
+    pub signatures: bool,
+
+    /// Subprocess extractors keyed by language label
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub external: BTreeMap<String, ExternalExtractorConfig>,
+
+    /// Command embedding symbols for `rup symbols --embeddings`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub embeddings: Option<EmbeddingsConfig>,
+}
+
+/// Third-party binary that extracts symbols for a language roughup doesn't
+/// build in, speaking the NDJSON protocol in `parsers::external_parser`
+///
+/// ```toml
+/// [symbols.external.elixir]
+/// command = ["elixir-symbols", "--ndjson"]
+/// extensions = ["ex", "exs"]
+/// timeout_secs = 30
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ExternalExtractorConfig
+{
+    /// Program and its arguments, run once per file
+    pub command: Vec<String>,
+
+    /// File extensions (without the dot) routed to this extractor
+    pub extensions: Vec<String>,
+
+    /// Seconds one file may take before the extractor is killed
+    #[serde(default = "default_external_timeout")]
+    pub timeout_secs: u64,
+}
+
+fn default_external_timeout() -> u64
+{
+    30
+}
+
+/// Command turning text into vectors, speaking the NDJSON protocol in
+/// `core::symbol_embeddings`
+///
+/// ```toml
+/// [symbols.embeddings]
+/// command = ["embed-local", "--model", "small"]
+/// batch = 32
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct EmbeddingsConfig
+{
+    /// Program and its arguments, run once per batch of texts
+    pub command: Vec<String>,
+
+    /// Most texts sent in one request
+    #[serde(default = "default_embedding_batch")]
+    pub batch: usize,
+}
+
+fn default_embedding_batch() -> usize
+{
+    64
 }
 
 #[derive(Debug, Serialize, Deserialize)]
+#[serde(default)]
 pub struct ChunkConfig
 {
     pub max_tokens: usize,
     pub model: String,
     pub output_dir: String,
+
+    /// Profiles for `rup chunk --preset`, replacing built-in ones of the
+    /// same name
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub presets: BTreeMap<String, ChunkPreset>,
+}
+
+impl Default for ChunkConfig
+{
+    fn default() -> Self
+    {
+        Self {
+            max_tokens: 4000,
+            model: "gpt-4".to_string(),
+            output_dir: "chunks".to_string(),
+            presets: BTreeMap::new(),
+        }
+    }
+}
+
+/// Model, chunk size, and overlap picked together by `rup chunk --preset`;
+/// a field left out keeps the command's default
+///
+/// ```toml
+/// [chunk.presets.docs-1k]
+/// model = "gpt-4o"
+/// max_tokens = 1000
+/// overlap = "2lines"
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ChunkPreset
+{
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub model: Option<String>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_tokens: Option<usize>,
+
+    /// Same syntax as `--overlap`: `N`, `Ntok`, `Nl`, or `Nlines`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub overlap: Option<String>,
+}
+
+/// Apply and patch generation settings
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct ApplyConfig
+{
+    /// Per-file context lines for generated patches; `--context-for` rules
+    /// are applied after these
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub context: Vec<crate::core::patch::ContextRule>,
+}
+
+/// Conflict resolution settings
+///
+/// ```toml
+/// [resolve]
+/// trivial_side = "theirs"
+/// ```
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct ResolveConfig
+{
+    /// Side kept for whitespace- or comment-only conflicts; `--trivial-side`
+    /// overrides it
+    #[serde(default)]
+    pub trivial_side: crate::core::resolve::TrivialSide,
+}
+
+/// Backup session settings
+///
+/// Retention limits are enforced after every finalized session and by
+/// `rup backup gc`; sessions matching any limit are removed.
+///
+/// ```toml
+/// [backup]
+/// dir = "~/.cache/roughup/backups/<repo-hash>"
+/// keep_latest = 50
+/// max_age = "30d"
+/// max_total_size = "500M"
+/// ```
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct BackupConfig
+{
+    /// Keep at most this many sessions, newest first
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub keep_latest: Option<usize>,
+
+    /// Remove sessions older than this span: 30d, 12h, 2w
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_age: Option<String>,
+
+    /// Remove the oldest sessions once all together exceed this size:
+    /// bytes or a K/M/G suffix
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_total_size: Option<String>,
+
+    /// Secret for encrypting stored payloads; `ROUGHUP_BACKUP_KEY` wins
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub encryption_key: Option<String>,
+
+    /// File holding the encryption secret, e.g. `~/.config/roughup/backup.key`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub encryption_key_file: Option<PathBuf>,
+
+    /// Where sessions live instead of `.rup/backups`; `~`, `$VARS`, and
+    /// `<repo-hash>` expand, relative paths resolve against the repo root
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub dir: Option<String>,
+
+    /// Off-machine mirror for completed sessions
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub remote: Option<RemoteConfig>,
+}
+
+/// Where completed backup sessions are mirrored, selected by `kind`
+///
+/// ```toml
+/// [backup.remote]
+/// kind = "s3"
+/// url = "s3://bucket/roughup"
+/// endpoint = "https://minio.internal:9000"
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "lowercase")]
+pub enum RemoteConfig
+{
+    /// S3-compatible bucket via the `aws` CLI
+    S3
+    {
+        /// Destination prefix, e.g. `s3://bucket/roughup`
+        url: String,
+        /// Custom endpoint for MinIO, R2, etc.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        endpoint: Option<String>,
+    },
+    /// Local path or `host:path` over SSH via `rsync`
+    Rsync
+    {
+        /// Destination directory, e.g. `backup@host:/srv/roughup`
+        target: String,
+    },
 }
 
 impl Default for Config
@@ -81,21 +300,58 @@ impl Default for Config
                 output_file: "extracted_source.txt".to_string(),
             },
             tree: TreeConfig { max_depth: None, show_hidden: false },
-            symbols: SymbolsConfig {
-                languages: vec!["rust".to_string(), "python".to_string(), "javascript".to_string()],
-                include_private: false,
-                output_file: ".rup/symbols.jsonl".to_string(),
-            },
-            chunk: ChunkConfig {
-                max_tokens: 4000,
-                model: "gpt-4".to_string(),
-                output_dir: "chunks".to_string(),
-            },
+            symbols: SymbolsConfig::default(),
+            chunk: ChunkConfig::default(),
+            backup: BackupConfig::default(),
+            apply: ApplyConfig::default(),
+            resolve: ResolveConfig::default(),
+        }
+    }
+}
+
+impl Default for SymbolsConfig
+{
+    fn default() -> Self
+    {
+        Self {
+            languages: vec![
+                "rust".to_string(),
+                "python".to_string(),
+                "javascript".to_string(),
+                "typescript".to_string(),
+                "go".to_string(),
+                "java".to_string(),
+                "kotlin".to_string(),
+                "csharp".to_string(),
+                "ruby".to_string(),
+                "php".to_string(),
+                "markdown".to_string(),
+                "toml".to_string(),
+                "yaml".to_string(),
+                "json".to_string(),
+                "sql".to_string(),
+                "proto".to_string(),
+                "hcl".to_string(),
+                "bash".to_string(),
+                "vue".to_string(),
+                "svelte".to_string(),
+            ],
+            include_private: false,
+            output_file: ".rup/symbols.jsonl".to_string(),
+            signatures: false,
+            external: BTreeMap::new(),
+            embeddings: None,
         }
     }
 }
 
 pub fn load_config() -> Result<Config>
+{
+    load_config_in(Path::new(""))
+}
+
+/// Load configuration from files in `dir` (plus the environment)
+pub fn load_config_in(dir: &Path) -> Result<Config>
 {
     let mut builder = config::Config::builder();
 
@@ -104,9 +360,10 @@ pub fn load_config() -> Result<Config>
 
     for path in &config_paths
     {
-        if Path::new(path).exists()
+        let path = dir.join(path);
+        if path.exists()
         {
-            builder = builder.add_source(config::File::with_name(path));
+            builder = builder.add_source(config::File::from(path));
             break;
         }
     }
@@ -0,0 +1,339 @@
+//! Filepath: src/infra/suppress.rs
+//! Inline suppression directives honored by the walker, the symbol
+//! extractors, and context assembly.
+//!
+//! Two directives are recognized inside any line comment style
+//! (`//`, `///`, `#`, `--`, `/* */`, `<!-- -->`):
+//!   - `rup: ignore-file`        — drop the whole file (header only)
+//!   - `rup: ignore-next-symbol` — drop the next declared symbol
+//!
+//! The space after `rup:` is optional. `ignore-file` is only honored
+//! within the first `HEADER_LINES` lines so that files mentioning the
+//! directive in prose deeper in the body are not silently dropped.
+
+use std::{fs::File, io::Read, path::Path};
+
+use crate::core::symbols::Symbol;
+
+/// Number of leading lines searched for `rup: ignore-file`
+pub const HEADER_LINES: usize = 32;
+
+/// Maximum bytes read from disk when probing a file header
+const HEADER_PROBE_BYTES: usize = 8 * 1024;
+
+/// Supported directive kinds
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Directive
+{
+    /// Exclude the whole file
+    IgnoreFile,
+
+    /// Exclude the next declared symbol (and anything nested in it)
+    IgnoreNextSymbol,
+}
+
+impl Directive
+{
+    /// Parse a single source line into a directive, if present
+    pub fn parse(line: &str) -> Option<Self>
+    {
+        // Strip the comment opener; only comment lines may carry directives
+        let t = line.trim_start();
+        let body = ["///", "//", "#", "--", "/*", "<!--", "*"]
+            .iter()
+            .find_map(|p| t.strip_prefix(p))?
+            .trim_start();
+
+        // Require the `rup:` namespace, then an optional space
+        let rest = body
+            .strip_prefix("rup:")?
+            .trim_start();
+
+        // Keyword ends at whitespace or a closing comment marker
+        let word = rest
+            .split(|c: char| !(c.is_ascii_alphanumeric() || c == '-'))
+            .next()
+            .unwrap_or("")
+            .trim_end_matches('-');
+
+        match word
+        {
+            "ignore-file" => Some(Directive::IgnoreFile),
+            "ignore-next-symbol" => Some(Directive::IgnoreNextSymbol),
+            _ => None,
+        }
+    }
+}
+
+/// Suppression state computed once per file
+#[derive(Debug, Clone, Default)]
+pub struct Suppressions
+{
+    /// `rup: ignore-file` found in the header
+    ignore_file: bool,
+
+    /// 1-based line numbers carrying `rup: ignore-next-symbol`
+    next_symbol_lines: Vec<usize>,
+}
+
+impl Suppressions
+{
+    /// Scan file content for directives
+    pub fn scan(content: &str) -> Self
+    {
+        let mut out = Self::default();
+
+        for (i, line) in content
+            .lines()
+            .enumerate()
+        {
+            match Directive::parse(line)
+            {
+                Some(Directive::IgnoreFile) if i < HEADER_LINES => out.ignore_file = true,
+                Some(Directive::IgnoreNextSymbol) =>
+                {
+                    out.next_symbol_lines
+                        .push(i + 1)
+                }
+                _ =>
+                {}
+            }
+        }
+
+        out
+    }
+
+    /// Whether the whole file is excluded
+    pub fn ignores_file(&self) -> bool
+    {
+        self.ignore_file
+    }
+
+    /// Whether no directive of any kind was found
+    pub fn is_empty(&self) -> bool
+    {
+        !self.ignore_file
+            && self
+                .next_symbol_lines
+                .is_empty()
+    }
+
+    /// Check if a symbol starting at `start_line` directly follows a
+    /// `rup: ignore-next-symbol` directive. Blank lines, comments, doc
+    /// comments, attributes, and decorators may sit in between.
+    pub fn suppresses_line(
+        &self,
+        lines: &[&str],
+        start_line: usize,
+    ) -> bool
+    {
+        if self
+            .next_symbol_lines
+            .is_empty()
+        {
+            return false;
+        }
+
+        // Walk upward from the line above the declaration
+        let mut cur = start_line.saturating_sub(1);
+        while cur >= 1
+        {
+            if self
+                .next_symbol_lines
+                .contains(&cur)
+            {
+                return true;
+            }
+
+            let t = lines
+                .get(cur - 1)
+                .map(|l| l.trim())
+                .unwrap_or("");
+
+            // Stop at the first line that is actual code
+            if !is_preamble_line(t)
+            {
+                return false;
+            }
+            cur -= 1;
+        }
+
+        false
+    }
+
+    /// Drop suppressed symbols and every symbol nested inside them.
+    /// `content` must be the same text the symbols were extracted from.
+    pub fn apply(
+        &self,
+        content: &str,
+        symbols: &mut Vec<Symbol>,
+    )
+    {
+        if self.ignore_file
+        {
+            symbols.clear();
+            return;
+        }
+        if self
+            .next_symbol_lines
+            .is_empty()
+        {
+            return;
+        }
+
+        let lines: Vec<&str> = content
+            .lines()
+            .collect();
+
+        // Byte spans of directly suppressed symbols
+        let dropped: Vec<(usize, usize)> = symbols
+            .iter()
+            .filter(|s| self.suppresses_line(&lines, s.start_line))
+            .map(|s| (s.byte_start, s.byte_end))
+            .collect();
+
+        // Remove the suppressed symbols and anything they contain
+        symbols.retain(|s| {
+            !dropped
+                .iter()
+                .any(|(lo, hi)| s.byte_start >= *lo && s.byte_end <= *hi)
+        });
+    }
+}
+
+/// Lines allowed between a directive and the declaration it targets
+fn is_preamble_line(t: &str) -> bool
+{
+    t.is_empty()
+        || t.starts_with("//")
+        || t.starts_with('#')
+        || t.starts_with("/*")
+        || t.starts_with('*')
+        || t.starts_with('@')
+}
+
+/// Probe the head of a file on disk for `rup: ignore-file`.
+/// Unreadable files are reported as not ignored.
+pub fn file_is_ignored(path: &Path) -> bool
+{
+    let Ok(f) = File::open(path)
+    else
+    {
+        return false;
+    };
+
+    let mut buf = Vec::with_capacity(HEADER_PROBE_BYTES);
+    if f.take(HEADER_PROBE_BYTES as u64)
+        .read_to_end(&mut buf)
+        .is_err()
+    {
+        return false;
+    }
+
+    String::from_utf8_lossy(&buf)
+        .lines()
+        .take(HEADER_LINES)
+        .any(|l| Directive::parse(l) == Some(Directive::IgnoreFile))
+}
+
+#[cfg(test)]
+mod tests
+{
+    use std::path::PathBuf;
+
+    use super::*;
+    use crate::core::symbols::SymbolKind;
+
+    fn sym(
+        name: &str,
+        start_line: usize,
+        byte_start: usize,
+        byte_end: usize,
+    ) -> Symbol
+    {
+        Symbol {
+            file: PathBuf::from("lib.rs"),
+            lang: "rust".into(),
+            kind: SymbolKind::Function,
+            name: name.into(),
+            qualified_name: name.into(),
+            byte_start,
+            byte_end,
+            start_line,
+            end_line: start_line,
+            visibility: None,
+            doc: None,
+            signature: None,
+            package: None,
+            module: None,
+        }
+    }
+
+    #[test]
+    fn parses_directive_variants()
+    {
+        assert_eq!(
+            Directive::parse("// rup:ignore-next-symbol"),
+            Some(Directive::IgnoreNextSymbol)
+        );
+        assert_eq!(
+            Directive::parse("# rup: ignore-file"),
+            Some(Directive::IgnoreFile)
+        );
+        assert_eq!(
+            Directive::parse("/* rup: ignore-file */"),
+            Some(Directive::IgnoreFile)
+        );
+        assert_eq!(
+            Directive::parse("<!-- rup: ignore-file -->"),
+            Some(Directive::IgnoreFile)
+        );
+        assert_eq!(Directive::parse("let s = \"rup: ignore-file\";"), None);
+        assert_eq!(Directive::parse("// rup: ignore-everything"), None);
+    }
+
+    #[test]
+    fn ignore_file_only_in_header()
+    {
+        let mut deep = "fn a() {}\n".repeat(HEADER_LINES + 1);
+        deep.push_str("// rup: ignore-file\n");
+        assert!(!Suppressions::scan(&deep).ignores_file());
+
+        let head = "// @generated\n// rup: ignore-file\nfn a() {}\n";
+        assert!(Suppressions::scan(head).ignores_file());
+    }
+
+    #[test]
+    fn drops_next_symbol_and_nested()
+    {
+        let src = "fn keep() {}\n// rup:ignore-next-symbol\n#[derive(Debug)]\nimpl X {\n    fn \
+                   inner() {}\n}\nfn after() {}\n";
+        let mut syms = vec![
+            sym("keep", 1, 0, 12),
+            sym("X", 4, 53, 80),
+            sym("inner", 5, 62, 78),
+            sym("after", 7, 81, 94),
+        ];
+
+        Suppressions::scan(src).apply(src, &mut syms);
+
+        let names: Vec<_> = syms
+            .iter()
+            .map(|s| {
+                s.name
+                    .as_str()
+            })
+            .collect();
+        assert_eq!(names, vec!["keep", "after"]);
+    }
+
+    #[test]
+    fn directive_does_not_reach_past_code()
+    {
+        let src = "// rup:ignore-next-symbol\nlet x = 1;\nfn f() {}\n";
+        let lines: Vec<&str> = src
+            .lines()
+            .collect();
+        assert!(!Suppressions::scan(src).suppresses_line(&lines, 3));
+    }
+}
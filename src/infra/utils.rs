@@ -3,8 +3,13 @@
 //! All functions are associated fns to keep call sites
 //! ergonomic, testable, and discoverable.
 
+use std::{cell::RefCell, collections::HashMap};
+
 // Tree-sitter types for node helpers
-use tree_sitter::{Node, Point};
+use tree_sitter::{Language, Node, Parser, Point, Tree};
+
+// Call-site records produced by the tree-sitter helpers
+use crate::core::symbols::{Reference, Relation, RelationKind};
 
 /// Qualified-name helpers
 pub struct NameUtils;
@@ -83,11 +88,47 @@ impl Utf8Utils
     }
 }
 
+/// `(node kind, name field, targets)` for [`TsNodeUtils::relations`]: a
+/// declaration, the field naming its type, and the clauses naming the types
+/// it relates to, each with the kind of relation it spells
+pub type RelationRule<'a> = (&'a str, &'a str, &'a [(&'a str, RelationKind)]);
+
 /// Common Tree-sitter node helpers
 pub struct TsNodeUtils;
 
 impl TsNodeUtils
 {
+    /// Parse `content` as `language` with this thread's parser for it,
+    /// created on first use and reused for every later file
+    pub fn parse(
+        language: &Language,
+        content: &str,
+    ) -> anyhow::Result<Tree>
+    {
+        thread_local! {
+            static PARSERS: RefCell<HashMap<Language, Parser>> = RefCell::new(HashMap::new());
+        }
+
+        PARSERS.with_borrow_mut(|parsers| {
+            if !parsers.contains_key(language)
+            {
+                let mut parser = Parser::new();
+                parser.set_language(language)?;
+                parsers.insert(language.clone(), parser);
+            }
+            let parser = parsers
+                .get_mut(language)
+                .expect("parser inserted above");
+            // No tree means the parse was cut short; start clean next time
+            parser
+                .parse(content, None)
+                .ok_or_else(|| {
+                    parser.reset();
+                    anyhow::anyhow!("parser produced no tree")
+                })
+        })
+    }
+
     /// Check if `node` has an ancestor of the given kind
     pub fn has_ancestor(
         mut node: Node,
@@ -156,6 +197,246 @@ impl TsNodeUtils
         // Convert to 1-based rows
         (s.row + 1, e.row + 1)
     }
+
+    /// `/** ... */` doc block (JSDoc, Javadoc, KDoc) ending on the line
+    /// above `node`, without the comment framing and leading `*`s
+    pub fn doc_block(
+        node: Node,
+        bytes: &[u8],
+    ) -> Option<String>
+    {
+        // The comment must sit directly above the declaration
+        let prev = node.prev_named_sibling()?;
+        if !prev
+            .kind()
+            .ends_with("comment")
+            || prev
+                .end_position()
+                .row
+                + 1
+                < node
+                    .start_position()
+                    .row
+        {
+            return None;
+        }
+        let inner = prev
+            .utf8_text(bytes)
+            .ok()?
+            .strip_prefix("/**")?
+            .strip_suffix("*/")?;
+
+        // Drop the `*` gutter on each line
+        let lines: Vec<&str> = inner
+            .lines()
+            .map(|l| {
+                let l = l.trim();
+                l.strip_prefix('*')
+                    .map_or(l, str::trim_start)
+            })
+            .collect();
+        let doc = lines
+            .join("\n")
+            .trim()
+            .to_string();
+        (!doc.is_empty()).then_some(doc)
+    }
+
+    /// Parse `content` and collect its call sites: every node of a
+    /// `(kind, field)` pair is a call whose `field` child names the callee
+    pub fn call_sites(
+        language: &Language,
+        content: &str,
+        calls: &[(&str, &str)],
+    ) -> anyhow::Result<Vec<Reference>>
+    {
+        let tree = Self::parse(language, content)
+            .map_err(|e| e.context("Failed to parse source for call sites"))?;
+        let bytes = content.as_bytes();
+
+        // Depth-first, so call sites come out in source order
+        let mut out = Vec::new();
+        let mut stack = vec![tree.root_node()];
+        while let Some(node) = stack.pop()
+        {
+            if let Some((_, field)) = calls
+                .iter()
+                .find(|(kind, _)| node.kind() == *kind)
+                && let Some(callee) = node.child_by_field_name(field)
+                && let Some((s, e)) = callee
+                    .utf8_text(bytes)
+                    .ok()
+                    .and_then(Self::callee_name_span)
+            {
+                let base = callee.start_byte();
+                out.push(Reference::call(&content[base + s..base + e], base + s, base + e));
+            }
+            let mut cursor = node.walk();
+            let children: Vec<Node> = node
+                .named_children(&mut cursor)
+                .collect();
+            stack.extend(
+                children
+                    .into_iter()
+                    .rev(),
+            );
+        }
+        Ok(out)
+    }
+
+    /// Parse `content` and collect the type relations its declarations spell
+    /// out. A node matching a rule names its type in the rule's field, and
+    /// every child matching a target (by field name or node kind) lists the
+    /// types it relates to.
+    pub fn relations(
+        language: &Language,
+        content: &str,
+        rules: &[RelationRule],
+    ) -> anyhow::Result<Vec<Relation>>
+    {
+        let tree = Self::parse(language, content)
+            .map_err(|e| e.context("Failed to parse source for relations"))?;
+        let bytes = content.as_bytes();
+
+        let mut out = Vec::new();
+        let mut stack = vec![tree.root_node()];
+        while let Some(node) = stack.pop()
+        {
+            if let Some((_, field, targets)) = rules
+                .iter()
+                .find(|(kind, ..)| node.kind() == *kind)
+                && let Some((ss, se)) = node
+                    .child_by_field_name(field)
+                    .and_then(|n| Self::type_name_span(n, bytes))
+            {
+                let mut found = Vec::new();
+                Self::relation_targets(node, targets, None, &mut found);
+                for (kind, target) in found
+                {
+                    if let Some((s, e)) = Self::type_name_span(target, bytes)
+                    {
+                        out.push(Relation::new(kind, &content[ss..se], &content[s..e], s, e));
+                    }
+                }
+            }
+            let mut cursor = node.walk();
+            let children: Vec<Node> = node
+                .named_children(&mut cursor)
+                .collect();
+            stack.extend(
+                children
+                    .into_iter()
+                    .rev(),
+            );
+        }
+        Ok(out)
+    }
+
+    /// Type nodes under `node` in relation clauses, with the kind of the
+    /// innermost clause holding each; bodies are never entered
+    fn relation_targets<'t>(
+        node: Node<'t>,
+        targets: &[(&str, RelationKind)],
+        kind: Option<RelationKind>,
+        out: &mut Vec<(RelationKind, Node<'t>)>,
+    )
+    {
+        // Clause wrappers and type lists hold types; anything else is one
+        const CONTAINERS: &[&str] = &[
+            "argument_list",
+            "class_heritage",
+            "extends_clause",
+            "extends_interfaces",
+            "extends_type_clause",
+            "implements_clause",
+            "super_interfaces",
+            "superclass",
+            "trait_bounds",
+            "type_list",
+        ];
+        const NOT_TYPES: &[&str] =
+            &["comment", "keyword_argument", "lifetime", "removed_trait_bound", "type_arguments"];
+
+        let mut cursor = node.walk();
+        if !cursor.goto_first_child()
+        {
+            return;
+        }
+        loop
+        {
+            let child = cursor.node();
+            let field = cursor.field_name();
+            if child.is_named() && field != Some("body")
+            {
+                let matched = targets
+                    .iter()
+                    .find(|(t, _)| field == Some(*t) || child.kind() == *t)
+                    .map(|&(_, k)| k)
+                    .or(kind);
+                if CONTAINERS.contains(&child.kind())
+                {
+                    Self::relation_targets(child, targets, matched, out);
+                }
+                else if let Some(k) = matched
+                    && !NOT_TYPES.contains(&child.kind())
+                {
+                    out.push((k, child));
+                }
+            }
+            if !cursor.goto_next_sibling()
+            {
+                break;
+            }
+        }
+    }
+
+    /// Span of the simple name in a type as written: `fmt::Display` →
+    /// `Display`, `Base<T>` → `Base`
+    fn type_name_span(
+        node: Node,
+        bytes: &[u8],
+    ) -> Option<(usize, usize)>
+    {
+        let text = node
+            .utf8_text(bytes)
+            .ok()?;
+        let base = node.start_byte();
+        Self::callee_name_span(text).map(|(s, e)| (base + s, base + e))
+    }
+
+    /// Span of the last identifier in a callee expression, outside generic
+    /// arguments and subscripts: `self.save` → `save`, `Vec::<u8>::new` →
+    /// `new`, `Generic[T]` → `Generic`
+    fn callee_name_span(text: &str) -> Option<(usize, usize)>
+    {
+        let bytes = text.as_bytes();
+        let is_ident = |b: u8| b.is_ascii_alphanumeric() || b == b'_' || b == b'$';
+        let mut depth = 0usize;
+        let mut last = None;
+        let mut i = 0;
+        while i < bytes.len()
+        {
+            match bytes[i]
+            {
+                b'<' | b'[' => depth += 1,
+                b'>' | b']' => depth = depth.saturating_sub(1),
+                b if depth == 0 && is_ident(b) && !b.is_ascii_digit() =>
+                {
+                    let start = i;
+                    while i < bytes.len() && is_ident(bytes[i])
+                    {
+                        i += 1;
+                    }
+                    last = Some((start, i));
+                    continue;
+                }
+                _ =>
+                {}
+            }
+            i += 1;
+        }
+        last
+    }
 }
 
 /// Python docstring helpers
@@ -729,4 +1010,41 @@ mod tests
         // Validate ancestor check
         assert!(TsNodeUtils::has_ancestor(m, "class_definition"));
     }
+
+    #[test]
+    fn call_sites_name_the_callee()
+    {
+        let src = "fn main() {\n    let v = Vec::<u8>::new();\n    self.save(v);\n    run();\n}\n";
+        let language: Language = tree_sitter_rust::LANGUAGE.into();
+        let refs = TsNodeUtils::call_sites(&language, src, &[("call_expression", "function")])
+            .expect("call sites");
+        let names: Vec<&str> = refs
+            .iter()
+            .map(|r| {
+                r.name
+                    .as_str()
+            })
+            .collect();
+        assert_eq!(names, ["new", "save", "run"]);
+        assert_eq!(&src[refs[1].byte_start..refs[1].byte_end], "save");
+    }
+
+    #[test]
+    fn pooled_parsers_switch_languages_cleanly()
+    {
+        let rust: Language = tree_sitter_rust::LANGUAGE.into();
+        let python = unsafe { tree_sitter_python() };
+        let sexp = |language: &Language, src: &str| {
+            TsNodeUtils::parse(language, src)
+                .expect("parse")
+                .root_node()
+                .to_sexp()
+        };
+
+        // Interleaved languages each get their own reused parser
+        let first = sexp(&rust, "fn a() {}");
+        assert_eq!(sexp(&python, "def a():\n    pass\n"), sexp(&python, "def a():\n    pass\n"));
+        assert_eq!(sexp(&rust, "fn a() {}"), first);
+        assert!(first.starts_with("(source_file (function_item"));
+    }
 }
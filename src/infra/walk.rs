@@ -45,6 +45,9 @@ pub struct FileWalker
 
     /// Maximum recursion depth; default None (unbounded)
     max_depth: Option<usize>,
+
+    /// Drop files whose header carries `rup: ignore-file`; default false
+    honor_suppressions: bool,
 }
 
 impl FileWalker
@@ -66,6 +69,7 @@ impl FileWalker
             include_hidden: true,
             follow_symlinks: false,
             max_depth: None,
+            honor_suppressions: false,
         })
     }
 
@@ -132,6 +136,17 @@ impl FileWalker
         self
     }
 
+    /// (Optional) Skip files that opt out via a `rup: ignore-file` header
+    /// directive (default false). Costs one small header read per file.
+    pub fn with_suppressions(
+        mut self,
+        honor: bool,
+    ) -> Self
+    {
+        self.honor_suppressions = honor;
+        self
+    }
+
     /// Internal: construct a configured WalkBuilder for `root`.
     fn build_walk(
         &self,
@@ -213,6 +228,8 @@ impl FileWalker
                     .ignore_patterns
                     .is_match(rel)
             })
+            // Source-level opt-out via `rup: ignore-file`
+            .filter(|abs| !self.honor_suppressions || !crate::infra::suppress::file_is_ignored(abs))
             .collect();
 
         // Deterministic order (stable CLI & tests)
@@ -435,6 +452,28 @@ mod tests
         Ok(())
     }
 
+    #[test]
+    fn test_suppressed_files_skipped_when_enabled() -> Result<()>
+    {
+        let tmp = TempDir::new()?;
+        let root = tmp.path();
+
+        write_file(root, "gen.rs", "// @generated\n// rup: ignore-file\nfn g() {}\n")?;
+        write_file(root, "lib.rs", "fn f() {}\n")?;
+
+        // Default walker ignores directives
+        let files = FileWalker::new(&[])?.walk_files(root);
+        assert_eq!(files.len(), 2);
+
+        // Opt-in walker drops the suppressed file
+        let files = FileWalker::new(&[])?
+            .with_suppressions(true)
+            .walk_files(root);
+        assert_eq!(files.len(), 1);
+        assert!(files[0].ends_with("lib.rs"));
+        Ok(())
+    }
+
     #[test]
     fn test_max_depth_and_symlinks() -> Result<()>
     {
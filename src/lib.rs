@@ -22,46 +22,123 @@ pub mod core
 
     /// Edit format parsing and application system for LLM collaboration
     pub mod edit;
-    pub use edit::{EditConflict, EditEngine, EditOperation, EditResult, EditSpec};
+    pub use edit::{
+        ApplyStats, EditConflict, EditEngine, EditOperation, EditResult, EditSpec, EolPolicy,
+    };
 
     /// Centralized backup system with session-scoped storage
     pub mod backup;
     pub use backup::{BackupManager, SessionManifest, list_sessions, read_session_manifest};
 
-    /// Backup session management operations (list, show, restore, cleanup)
+    /// Backup session management operations (list, show, restore, cleanup, gc)
     pub mod backup_ops;
     pub use backup_ops::{ListRequest, SessionInfo, ShowRequest, ShowResponse};
 
+    /// Portable `.tar.zst` session archives with integrity-checked import
+    pub mod backup_archive;
+
+    /// Background mirroring of finalized sessions to rsync/S3 remotes
+    pub mod backup_remote;
+
+    /// XChaCha20-Poly1305 at-rest encryption of backup payloads
+    pub mod backup_crypt;
+
+    /// Conversion between backup sessions and `git stash` entries
+    pub mod backup_stash;
+
+    /// Indentation detection and tab/space normalization for `--whitespace auto`
+    pub mod indent;
+
     /// EBNF to unified diff patch converter for Git integration
     pub mod patch;
     pub use patch::{
-        FilePatch, Hunk, PatchConfig, PatchSet, generate_patches, render_unified_diff,
+        FilePatch, Hunk, MailOptions, PatchConfig, PatchSet, generate_patches, render_mbox,
+        render_unified_diff,
     };
 
+    /// Syntax-highlighted, word-level diff rendering for `preview --color`
+    pub mod diff_color;
+    pub use diff_color::render_colored_diff;
+
     /// Git apply integration with 3-way merge and error mapping
     pub mod git;
-    pub use git::{GitConflict, GitEngine, GitMode, GitOptions, GitOutcome, Whitespace};
+    pub use git::{GitBackend, GitConflict, GitEngine, GitMode, GitOptions, GitOutcome, Whitespace};
+
+    /// `rup git install-hooks` pre-commit/pre-push checks
+    pub mod git_hooks;
+
+    /// Jujutsu snapshots and 3-way merges for the `jj` engine
+    pub mod jj;
 
     /// Unified apply engine trait for hybrid architecture
     pub mod apply_engine;
-    pub use apply_engine::{ApplyEngine, ApplyReport, Engine, Preview, create_engine};
+    pub use apply_engine::{ApplyEngine, ApplyReport, Engine, EngineAttempt, Preview, create_engine};
+
+    /// Scratch git worktree for trial applies with `--sandbox`
+    pub mod sandbox;
+
+    /// Named apply engine registry with capability flags for `--engine=<name>`
+    pub mod engine_registry;
+    pub use engine_registry::{
+        EngineCapabilities, EngineConfig, EngineRegistration, EngineRegistry, register_engine,
+    };
 
     /// Git conflict marker detection and resolution (Phase 3.5)
     pub mod conflict;
     pub use conflict::{
-        ConflictMarker, ConflictOrigin, ConflictType, parse_conflicts, score_conflict,
+        ConflictMarker, ConflictOrigin, ConflictType, ParseDiagnostic, parse_conflicts,
+        parse_conflicts_with_diagnostics, score_conflict,
     };
 
+    /// Commit and author behind each side of a conflict (`git blame`)
+    pub mod conflict_blame;
+
     /// Conflict resolution strategies with SmartMerge pipeline
     pub mod resolve;
     pub use resolve::{
         Resolution, ResolveStrategy, resolve, resolve_batch, resolve_no_check, run as resolve_run,
     };
 
+    /// Recorded conflict resolutions replayed by `resolve` (`.rup/rerere`)
+    pub mod rerere;
+
+    /// Side-by-side conflict panes and key prompt for `resolve --interactive`
+    pub mod resolve_interactive;
+
+    /// Paste-ready LLM prompt and edit-spec skeleton for `resolve --emit-prompt`
+    pub mod resolve_prompt;
+
+    /// Tree-sitter item-level merge behind `--strategy semantic`
+    pub mod semantic_merge;
+
     /// Tree-sitter symbol extraction pipeline (Rust+Python locked, 572 lines)
     pub mod symbols;
     pub use symbols::{Symbol, SymbolKind, Visibility, run as symbols_run};
 
+    /// Public API changes between two git revisions (`rup symbols diff`)
+    pub mod symbols_diff;
+
+    /// ctags/etags export of the symbol index (`rup symbols --format`)
+    pub mod tags;
+
+    /// SQLite export of the symbol index built with the `sqlite3` tool
+    pub mod symbols_sqlite;
+
+    /// Filesystem-event driven index updates for `rup symbols --watch`
+    pub mod symbols_watch;
+
+    /// Pluggable symbol embeddings for similarity lookup (`--embeddings`)
+    pub mod symbol_embeddings;
+
+    /// Per-member symbol index shards for monorepos (`rup symbols --shard`)
+    pub mod symbol_shards;
+
+    /// Package and module path recorded for each indexed symbol
+    pub mod symbol_owners;
+
+    /// Fuzzy symbol lookup over the index for `rup search`
+    pub mod search;
+
     /// Directory tree visualization with depth control and parallel processing
     pub mod tree;
     pub use tree::run as tree_run;
@@ -70,6 +147,9 @@ pub mod core
     pub mod chunk;
     pub use chunk::run as chunk_run;
 
+    /// Tiktoken and HuggingFace tokenizers behind the budgeter
+    pub mod tokenizer;
+
     pub mod budgeter;
     pub mod context;
     /// Smart context assembly (Phase 3)
@@ -94,6 +174,70 @@ pub mod parsers
     pub mod python_parser;
     pub use python_parser::PythonExtractor;
 
+    /// JavaScript symbol extraction with tree-sitter (functions, classes, function consts)
+    pub mod javascript_parser;
+    pub use javascript_parser::JavaScriptExtractor;
+
+    /// TypeScript/TSX symbol extraction with tree-sitter (adds interfaces, types, enums)
+    pub mod typescript_parser;
+    pub use typescript_parser::TypeScriptExtractor;
+
+    /// Go symbol extraction with tree-sitter (packages, funcs, receiver methods, types)
+    pub mod go_parser;
+    pub use go_parser::GoExtractor;
+
+    /// Java symbol extraction with tree-sitter (packages, types, methods, annotations)
+    pub mod java_parser;
+    pub use java_parser::JavaExtractor;
+
+    /// Kotlin symbol extraction with tree-sitter (classes, objects, companions, functions)
+    pub mod kotlin_parser;
+    pub use kotlin_parser::KotlinExtractor;
+
+    /// C# symbol extraction with tree-sitter (namespaces, partial types, properties, fields)
+    pub mod csharp_parser;
+    pub use csharp_parser::CSharpExtractor;
+
+    /// Ruby symbol extraction with tree-sitter (modules, classes, methods, attr_* macros)
+    pub mod ruby_parser;
+    pub use ruby_parser::RubyExtractor;
+
+    /// PHP symbol extraction with tree-sitter (namespaces, classes, traits, functions)
+    pub mod php_parser;
+    pub use php_parser::PhpExtractor;
+
+    /// Markdown structure extraction with tree-sitter (headings, code blocks, link definitions)
+    pub mod markdown_parser;
+    pub use markdown_parser::MarkdownExtractor;
+
+    /// TOML/YAML/JSON structure extraction with tree-sitter (tables and keys)
+    pub mod config_parser;
+    pub use config_parser::ConfigExtractor;
+
+    /// SQL schema extraction with tree-sitter (tables, views, indexes, functions)
+    pub mod sql_parser;
+    pub use sql_parser::SqlExtractor;
+
+    /// Protobuf schema extraction with tree-sitter (messages, enums, services, rpcs)
+    pub mod proto_parser;
+    pub use proto_parser::ProtoExtractor;
+
+    /// Terraform/HCL extraction with tree-sitter (resources, modules, variables)
+    pub mod hcl_parser;
+    pub use hcl_parser::HclExtractor;
+
+    /// Shell script extraction with tree-sitter (functions, sourced files)
+    pub mod shell_parser;
+    pub use shell_parser::ShellExtractor;
+
+    /// Vue/Svelte single-file components (blocks plus their script symbols)
+    pub mod component_parser;
+    pub use component_parser::ComponentExtractor;
+
+    /// Third-party subprocess extractors speaking NDJSON (`[symbols.external]`)
+    pub mod external_parser;
+    pub use external_parser::{ExternalExtractor, register_external_extractor};
+
     // Re-export common extractor interface
     pub use crate::core::symbols::{SymbolExtractor, get_extractor};
 }
@@ -103,7 +247,7 @@ pub mod infra
 {
     /// Configuration management with TOML support and feature flags
     pub mod config;
-    pub use config::{Config, init as config_init, load_config};
+    pub use config::{Config, init as config_init, load_config, load_config_in};
 
     /// Memory-mapped file I/O for large files (>1MB threshold)
     pub mod io;
@@ -117,6 +261,10 @@ pub mod infra
     pub mod walk;
     pub use walk::FileWalker;
 
+    /// Inline `rup:` suppression directives (ignore-file, ignore-next-symbol)
+    pub mod suppress;
+    pub use suppress::Suppressions;
+
     /// Utility functions and helpers for common operations
     pub mod utils;
     // Keep utils private - not part of the public API
@@ -136,8 +284,15 @@ pub mod cli;
 
 pub mod cli_ext
 {
+    /// `--agent-mode` JSON result envelope and stdout redirection
+    pub mod agent;
     pub mod anchor_cmd;
 }
 
 pub use infra::{Config, FileWalker, load_config};
-pub use parsers::{PythonExtractor, RustExtractor, SymbolExtractor};
+pub use parsers::{
+    CSharpExtractor, ComponentExtractor, ConfigExtractor, GoExtractor, HclExtractor,
+    JavaExtractor, JavaScriptExtractor, KotlinExtractor, MarkdownExtractor, PhpExtractor,
+    ProtoExtractor, PythonExtractor, RubyExtractor, RustExtractor, ShellExtractor, SqlExtractor,
+    SymbolExtractor, TypeScriptExtractor,
+};
@@ -33,6 +33,23 @@ fn main() -> Result<()>
         quiet: cli.quiet,
         no_color: cli.no_color,
         dry_run: cli.dry_run,
+        agent: cli
+            .agent_mode
+            .then(roughup::cli_ext::agent::AgentReport::new),
+    };
+
+    // Agent mode: human text goes to stderr, stdout carries only the envelope
+    let started = std::time::Instant::now();
+    let command_name = cli
+        .command
+        .name();
+    let redirect = if cli.agent_mode
+    {
+        Some(roughup::cli_ext::agent::StdoutRedirect::begin()?)
+    }
+    else
+    {
+        None
     };
 
     let result = match cli.command
@@ -52,6 +69,11 @@ fn main() -> Result<()>
             info!("Running symbols command");
             roughup::symbols_run(args, &ctx)
         }
+        Commands::Search(args) =>
+        {
+            info!("Running search command");
+            roughup::core::search::run(&args, &ctx)
+        }
         Commands::Chunk(args) =>
         {
             info!("Running chunk command");
@@ -72,6 +94,11 @@ fn main() -> Result<()>
             info!("Running check-syntax command");
             roughup::core::edit::check_syntax_run(args, &ctx)
         }
+        Commands::Patch(args) =>
+        {
+            info!("Running patch command");
+            roughup::core::edit::patch_run(args, &ctx)
+        }
         Commands::Backup(args) =>
         {
             info!("Running backup command");
@@ -102,6 +129,11 @@ fn main() -> Result<()>
             info!("Running anchor command");
             roughup::cli_ext::anchor_cmd::run_anchor_command(&args, &ctx)
         }
+        Commands::Git(args) =>
+        {
+            info!("Running git command");
+            roughup::core::git_hooks::run(args, &ctx)
+        }
     };
 
     match &result
@@ -133,9 +165,43 @@ fn main() -> Result<()>
         }
     }
 
+    if let (Some(redirect), Some(report)) = (redirect, &ctx.agent)
+    {
+        finish_agent(redirect, report, command_name, result, started);
+    }
+
     result
 }
 
+/// Emit the agent envelope on the real stdout and exit with its code
+fn finish_agent(
+    redirect: roughup::cli_ext::agent::StdoutRedirect,
+    report: &roughup::cli_ext::agent::AgentReport,
+    command: &str,
+    result: Result<()>,
+    started: std::time::Instant,
+) -> !
+{
+    let outcome = result.map_err(|e| {
+        let msg = format!("{e:#}");
+        // Edit commands share the apply exit-code taxonomy (2..=5)
+        let code = match command
+        {
+            "apply" | "preview" | "check-syntax" => roughup::core::edit::normalize_err_typed(e).1,
+            _ => 1,
+        };
+        (msg, code)
+    });
+
+    let envelope = report.finish(command, outcome, started);
+    if let Err(e) = redirect.emit(&envelope)
+    {
+        eprintln!("Error: failed to write agent envelope: {e}");
+        std::process::exit(5);
+    }
+    std::process::exit(envelope.exit_code)
+}
+
 /// Initialize tracing with appropriate configuration based on CLI args and environment.
 fn init_tracing(cli: &Cli) -> Result<()>
 {
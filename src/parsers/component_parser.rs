@@ -0,0 +1,468 @@
+//! Filepath: src/parsers/component_parser.rs
+//!
+//! Vue and Svelte single-file component extractor. Finds the top-level
+//! `<template>`, `<script>`, and `<style>` blocks and runs the matching
+//! script extractor over each script block.
+//!
+//! Notes:
+//!   - Every top-level block is a Block symbol named by its label: `template`, `script`,
+//!     `style`, and `script-setup` (Vue) or `script-module` (Svelte `context="module"` /
+//!     `module`) for the special script blocks.
+//!   - Script symbols come from the TypeScript extractor for `lang="ts"` or `lang="tsx"`,
+//!     the JavaScript one otherwise, with spans moved to their place in the component and
+//!     names qualified by the block: `script-setup::increment`, `script::Counter::reset`.
+//!   - Templates and styles have no sub-parser; only their blocks index.
+//!   - Blocks are found by a tag scan, not a full HTML parse. Nested `<template>` tags
+//!     inside a Vue template are balanced; comments are skipped.
+
+use std::path::{Path, PathBuf};
+
+use anyhow::Result;
+
+use crate::{
+    core::symbols::{
+        Reference, Relation, Symbol, SymbolExtractor, SymbolKind, build_qualified_name,
+    },
+    parsers::{JavaScriptExtractor, TypeScriptExtractor},
+};
+
+/// Extracts component blocks and their script symbols.
+pub struct ComponentExtractor
+{
+    javascript: JavaScriptExtractor,
+    typescript: TypeScriptExtractor,
+}
+
+impl ComponentExtractor
+{
+    pub fn new() -> Result<Self>
+    {
+        Ok(Self {
+            javascript: JavaScriptExtractor::new()?,
+            typescript: TypeScriptExtractor::new()?,
+        })
+    }
+}
+
+impl SymbolExtractor for ComponentExtractor
+{
+    fn extract_symbols(
+        &self,
+        content: &str,
+        file_path: &Path,
+    ) -> Result<Vec<Symbol>>
+    {
+        let mut out = Vec::new();
+        for block in find_blocks(content)
+        {
+            let label = block.label();
+            let line_of = |byte: usize| {
+                content[..byte]
+                    .matches('\n')
+                    .count()
+                    + 1
+            };
+            out.push(Symbol {
+                file: file_path.to_path_buf(),
+                lang: String::new(),
+                kind: SymbolKind::Block,
+                name: label.clone(),
+                qualified_name: label.clone(),
+                byte_start: block.start,
+                byte_end: block.end,
+                start_line: line_of(block.start),
+                end_line: line_of(block.end),
+                visibility: None,
+                doc: block
+                    .attr("lang")
+                    .map(|lang| format!("lang: {lang}")),
+                signature: None,
+                package: None,
+                module: None,
+            });
+            if block.tag != "script"
+            {
+                continue;
+            }
+
+            let (extractor, script_path) = self.script_extractor(&block);
+            let body = &content[block.body_start..block.body_end];
+            let line_offset = line_of(block.body_start) - 1;
+            let mut symbols = extractor.extract_symbols(body, &script_path)?;
+            for s in &mut symbols
+            {
+                s.file = file_path.to_path_buf();
+                s.qualified_name = build_qualified_name(&[&label, &s.qualified_name]);
+                s.byte_start += block.body_start;
+                s.byte_end += block.body_start;
+                s.start_line += line_offset;
+                s.end_line += line_offset;
+            }
+            out.extend(symbols);
+        }
+        Ok(out)
+    }
+
+    fn extract_references(
+        &self,
+        content: &str,
+        _file_path: &Path,
+    ) -> Result<Vec<Reference>>
+    {
+        let mut out = Vec::new();
+        for block in find_blocks(content)
+            .into_iter()
+            .filter(|b| b.tag == "script")
+        {
+            let (extractor, script_path) = self.script_extractor(&block);
+            let body = &content[block.body_start..block.body_end];
+            for mut r in extractor.extract_references(body, &script_path)?
+            {
+                r.byte_start += block.body_start;
+                r.byte_end += block.body_start;
+                out.push(r);
+            }
+        }
+        Ok(out)
+    }
+
+    fn extract_relations(
+        &self,
+        content: &str,
+        _file_path: &Path,
+    ) -> Result<Vec<Relation>>
+    {
+        let mut out = Vec::new();
+        for block in find_blocks(content)
+            .into_iter()
+            .filter(|b| b.tag == "script")
+        {
+            let (extractor, script_path) = self.script_extractor(&block);
+            let body = &content[block.body_start..block.body_end];
+            for mut r in extractor.extract_relations(body, &script_path)?
+            {
+                r.byte_start += block.body_start;
+                r.byte_end += block.body_start;
+                out.push(r);
+            }
+        }
+        Ok(out)
+    }
+}
+
+impl ComponentExtractor
+{
+    /// Extractor for a script block's `lang`, with a stand-in path whose
+    /// extension selects the grammar
+    fn script_extractor(
+        &self,
+        block: &Block,
+    ) -> (&dyn SymbolExtractor, PathBuf)
+    {
+        let (extractor, ext): (&dyn SymbolExtractor, &str) = match block.attr("lang")
+        {
+            Some("ts") => (&self.typescript, "ts"),
+            Some("tsx") => (&self.typescript, "tsx"),
+            _ => (&self.javascript, "js"),
+        };
+        (extractor, PathBuf::from(format!("component.{ext}")))
+    }
+}
+
+/// One top-level component block
+struct Block<'a>
+{
+    /// `template`, `script`, or `style`
+    tag: &'a str,
+    /// Raw attribute text of the opening tag
+    attrs: &'a str,
+    /// Span of the whole block, tags included
+    start: usize,
+    end: usize,
+    /// Span between the opening and closing tags
+    body_start: usize,
+    body_end: usize,
+}
+
+impl Block<'_>
+{
+    /// Tag name, suffixed for Vue `setup` and Svelte module scripts
+    fn label(&self) -> String
+    {
+        if self.tag == "script"
+        {
+            if self.has_flag("setup")
+            {
+                return "script-setup".to_string();
+            }
+            if self.has_flag("module") || self.attr("context") == Some("module")
+            {
+                return "script-module".to_string();
+            }
+        }
+        self.tag
+            .to_string()
+    }
+
+    /// Value of a quoted `name="value"` attribute
+    fn attr(
+        &self,
+        name: &str,
+    ) -> Option<&str>
+    {
+        let mut rest = self.attrs;
+        while let Some(at) = rest.find(name)
+        {
+            let before = rest[..at]
+                .chars()
+                .next_back();
+            let after = rest[at + name.len()..].trim_start();
+            rest = &rest[at + name.len()..];
+            if before.is_some_and(|c| !c.is_whitespace())
+            {
+                continue;
+            }
+            let Some(value) = after
+                .strip_prefix('=')
+                .map(str::trim_start)
+            else
+            {
+                continue;
+            };
+            let quote = value
+                .chars()
+                .next()?;
+            if quote != '"' && quote != '\''
+            {
+                return value
+                    .split_whitespace()
+                    .next();
+            }
+            return value[1..]
+                .split(quote)
+                .next();
+        }
+        None
+    }
+
+    /// Whether a bare attribute such as `setup` is present
+    fn has_flag(
+        &self,
+        name: &str,
+    ) -> bool
+    {
+        self.attrs
+            .split(|c: char| c.is_whitespace() || c == '/')
+            .any(|a| a == name)
+    }
+}
+
+/// Top-level `<template>`, `<script>`, and `<style>` blocks, in order
+fn find_blocks(content: &str) -> Vec<Block<'_>>
+{
+    let mut blocks = Vec::new();
+    let mut pos = 0;
+    while let Some(offset) = content[pos..].find('<')
+    {
+        let start = pos + offset;
+        let rest = &content[start + 1..];
+        if rest.starts_with("!--")
+        {
+            pos = rest
+                .find("-->")
+                .map_or(content.len(), |end| start + 1 + end + 3);
+            continue;
+        }
+        let Some(tag) = ["template", "script", "style"]
+            .into_iter()
+            .find(|tag| {
+                rest.strip_prefix(tag)
+                    .and_then(|r| {
+                        r.chars()
+                            .next()
+                    })
+                    .is_some_and(|c| c == '>' || c == '/' || c.is_whitespace())
+            })
+        else
+        {
+            pos = start + 1;
+            continue;
+        };
+        let Some(open_end) = rest.find('>')
+        else
+        {
+            break;
+        };
+        let attrs = rest[tag.len()..open_end].trim_end_matches('/');
+        let body_start = start + 1 + open_end + 1;
+        let Some((body_end, end)) = closing_tag(content, tag, body_start)
+        else
+        {
+            break;
+        };
+        blocks.push(Block {
+            tag,
+            attrs: attrs.trim(),
+            start,
+            end,
+            body_start,
+            body_end,
+        });
+        pos = end;
+    }
+    blocks
+}
+
+/// `(body_end, block_end)` for the tag closing a block whose body starts
+/// at `from`, balancing nested tags of the same name
+fn closing_tag(
+    content: &str,
+    tag: &str,
+    from: usize,
+) -> Option<(usize, usize)>
+{
+    let open = format!("<{tag}");
+    let close = format!("</{tag}");
+    let mut depth = 0;
+    let mut pos = from;
+    loop
+    {
+        let next_close = pos + content[pos..].find(&close)?;
+        // Script and style bodies are raw text; only templates nest
+        let next_open = (tag == "template")
+            .then(|| {
+                content[pos..next_close]
+                    .find(&open)
+                    .map(|o| pos + o)
+            })
+            .flatten();
+        match next_open
+        {
+            Some(o) =>
+            {
+                depth += 1;
+                pos = o + open.len();
+            }
+            None if depth > 0 =>
+            {
+                depth -= 1;
+                pos = next_close + close.len();
+            }
+            None =>
+            {
+                let end = next_close + content[next_close..].find('>')? + 1;
+                return Some((next_close, end));
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests
+{
+    use super::*;
+
+    fn extract(
+        src: &str,
+        file: &str,
+    ) -> Vec<Symbol>
+    {
+        ComponentExtractor::new()
+            .unwrap()
+            .extract_symbols(src, Path::new(file))
+            .unwrap()
+    }
+
+    fn get<'a>(
+        syms: &'a [Symbol],
+        qualified: &str,
+    ) -> &'a Symbol
+    {
+        syms.iter()
+            .find(|s| s.qualified_name == qualified)
+            .expect("symbol not found")
+    }
+
+    #[test]
+    fn vue_blocks_and_script_symbols()
+    {
+        let src = r#"<template>
+  <div>
+    <template v-if="ok"><span>{{ count }}</span></template>
+  </div>
+</template>
+
+<script setup lang="ts">
+import { ref } from 'vue'
+
+const count = ref(0)
+
+function increment(): void {
+  count.value++
+}
+</script>
+
+<!-- <script>not a block</script> -->
+<style scoped>
+.a { color: red; }
+</style>
+"#;
+        let syms = extract(src, "Counter.vue");
+
+        let template = get(&syms, "template");
+        assert_eq!(template.kind, SymbolKind::Block);
+        assert_eq!((template.start_line, template.end_line), (1, 5));
+
+        let setup = get(&syms, "script-setup");
+        assert_eq!(
+            setup
+                .doc
+                .as_deref(),
+            Some("lang: ts")
+        );
+        assert_eq!((setup.start_line, setup.end_line), (7, 15));
+
+        let increment = get(&syms, "script-setup::increment");
+        assert_eq!(increment.kind, SymbolKind::Function);
+        assert_eq!((increment.start_line, increment.end_line), (12, 14));
+        assert_eq!(
+            &src[increment.byte_start..increment.byte_start + 8],
+            "function"
+        );
+
+        assert_eq!(get(&syms, "style").start_line, 18);
+        assert!(
+            !syms
+                .iter()
+                .any(|s| s.qualified_name == "script")
+        );
+    }
+
+    #[test]
+    fn svelte_module_and_instance_scripts()
+    {
+        let src = r#"<script context="module">
+  export function preload() {}
+</script>
+
+<script>
+  class Store {
+    reset() {}
+  }
+</script>
+
+<h1>Hello</h1>
+"#;
+        let syms = extract(src, "Page.svelte");
+
+        assert_eq!(get(&syms, "script-module::preload").start_line, 2);
+        let reset = get(&syms, "script::Store::reset");
+        assert_eq!(reset.kind, SymbolKind::Method);
+        assert_eq!(reset.start_line, 7);
+        assert!(
+            !syms
+                .iter()
+                .any(|s| s.name == "template")
+        );
+    }
+}
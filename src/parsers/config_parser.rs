@@ -0,0 +1,467 @@
+//! Filepath: src/parsers/config_parser.rs
+//!
+//! TOML, YAML, and JSON structure extractor built on Tree-sitter 0.25.x,
+//! so manifests and CI files index alongside code.
+//!
+//! Notes:
+//!   - TOML: every `[table]` and `[[array]]` header is a Table spanning its pairs, and
+//!     each key directly in a table (or before the first one) is a Key.
+//!   - YAML and JSON: top-level keys and the keys one level below them; object-valued
+//!     keys are Tables, the rest Keys.
+//!   - Names are dotted paths, as written in these formats: `dependencies`,
+//!     `dependencies.serde`, `jobs.build`.
+//!   - A Key's doc is a one-line preview of its value.
+//!   - Generated lockfiles (`package-lock.json`, `pnpm-lock.yaml`, ...) yield nothing;
+//!     their thousands of keys would drown real matches.
+
+use std::path::Path;
+
+use anyhow::{Context, Result};
+use tree_sitter::{Language, Node};
+
+use crate::{
+    core::symbols::{Symbol, SymbolExtractor, SymbolKind},
+    infra::utils::TsNodeUtils,
+};
+
+/// Longest value preview kept as a Key's doc
+const PREVIEW_CHARS: usize = 80;
+
+/// Extracts tables and keys from TOML, YAML, and JSON files.
+pub struct ConfigExtractor
+{
+    /// TOML language handle for Tree-sitter.
+    toml: Language,
+    /// YAML language handle for Tree-sitter.
+    yaml: Language,
+    /// JSON language handle for Tree-sitter.
+    json: Language,
+}
+
+impl ConfigExtractor
+{
+    pub fn new() -> Result<Self>
+    {
+        Ok(Self {
+            toml: tree_sitter_toml_ng::LANGUAGE.into(),
+            yaml: tree_sitter_yaml::LANGUAGE.into(),
+            json: tree_sitter_json::LANGUAGE.into(),
+        })
+    }
+}
+
+impl SymbolExtractor for ConfigExtractor
+{
+    fn extract_symbols(
+        &self,
+        content: &str,
+        file_path: &Path,
+    ) -> Result<Vec<Symbol>>
+    {
+        let is_lockfile = file_path
+            .file_stem()
+            .and_then(|s| s.to_str())
+            .is_some_and(|s| s.ends_with("-lock") || s.ends_with(".lock"));
+        if is_lockfile
+        {
+            return Ok(Vec::new());
+        }
+
+        let (lang, language) = match file_path
+            .extension()
+            .and_then(|e| e.to_str())
+        {
+            Some("yaml" | "yml") => ("yaml", &self.yaml),
+            Some("json") => ("json", &self.json),
+            _ => ("toml", &self.toml),
+        };
+        let tree = TsNodeUtils::parse(language, content)
+            .with_context(|| format!("Failed to parse {lang} source"))?;
+
+        let mut walker = Walker {
+            bytes: content.as_bytes(),
+            file: file_path,
+            lang,
+            out: Vec::new(),
+        };
+        if lang == "toml"
+        {
+            walker.walk_toml(tree.root_node());
+        }
+        else
+        {
+            walker.walk_mapping(tree.root_node(), None, 0);
+        }
+        Ok(walker.out)
+    }
+}
+
+struct Walker<'a>
+{
+    bytes: &'a [u8],
+    file: &'a Path,
+    lang: &'static str,
+    out: Vec<Symbol>,
+}
+
+impl Walker<'_>
+{
+    /// Top-level pairs, then each table header and its pairs
+    fn walk_toml(
+        &mut self,
+        root: Node,
+    )
+    {
+        let mut cursor = root.walk();
+        for child in root.named_children(&mut cursor)
+        {
+            match child.kind()
+            {
+                "pair" => self.push_toml_pair(child, None),
+                "table" | "table_array_element" =>
+                {
+                    let Some(name) = child
+                        .named_child(0)
+                        .and_then(|k| self.text(k))
+                    else
+                    {
+                        continue;
+                    };
+                    let mut c = child.walk();
+                    let pairs: Vec<Node> = child
+                        .named_children(&mut c)
+                        .filter(|p| p.kind() == "pair")
+                        .collect();
+
+                    // The table node runs on over blank lines up to the next
+                    // header; end it at its last pair instead
+                    self.push(SymbolKind::Table, child, name.clone(), name.clone(), None);
+                    if let Some(last) = pairs.last()
+                        && let Some(table) = self
+                            .out
+                            .last_mut()
+                    {
+                        table.byte_end = last.end_byte();
+                        table.end_line = last
+                            .end_position()
+                            .row
+                            + 1;
+                    }
+                    for pair in pairs
+                    {
+                        self.push_toml_pair(pair, Some(&name));
+                    }
+                }
+                _ =>
+                {}
+            }
+        }
+    }
+
+    fn push_toml_pair(
+        &mut self,
+        pair: Node,
+        table: Option<&str>,
+    )
+    {
+        let Some(key) = pair
+            .named_child(0)
+            .and_then(|k| self.text(k))
+            .map(|k| unquote(&k).to_string())
+        else
+        {
+            return;
+        };
+        let value = pair.named_child(1);
+        let kind = if value.is_some_and(|v| v.kind() == "inline_table") && table.is_none()
+        {
+            SymbolKind::Table
+        }
+        else
+        {
+            SymbolKind::Key
+        };
+        let doc = value.and_then(|v| self.preview(v));
+        self.push(kind, pair, key.clone(), dotted(table, &key), doc);
+    }
+
+    /// Keys of the first mapping under `node`, recursing one level
+    fn walk_mapping(
+        &mut self,
+        node: Node,
+        parent: Option<&str>,
+        depth: usize,
+    )
+    {
+        let Some(mapping) = find_mapping(node)
+        else
+        {
+            return;
+        };
+        let mut cursor = mapping.walk();
+        let pairs: Vec<Node> = mapping
+            .named_children(&mut cursor)
+            .filter(|p| matches!(p.kind(), "block_mapping_pair" | "flow_pair" | "pair"))
+            .collect();
+        for pair in pairs
+        {
+            let Some(key) = pair
+                .child_by_field_name("key")
+                .and_then(|k| self.text(k))
+                .map(|k| unquote(&k).to_string())
+            else
+            {
+                continue;
+            };
+            let value = pair.child_by_field_name("value");
+            let nested = value.and_then(find_mapping);
+            let qualified_name = dotted(parent, &key);
+            if nested.is_some()
+            {
+                self.push(SymbolKind::Table, pair, key, qualified_name.clone(), None);
+                if depth == 0
+                    && let Some(value) = value
+                {
+                    self.walk_mapping(value, Some(&qualified_name), depth + 1);
+                }
+            }
+            else
+            {
+                let doc = value.and_then(|v| self.preview(v));
+                self.push(SymbolKind::Key, pair, key, qualified_name, doc);
+            }
+        }
+    }
+
+    fn push(
+        &mut self,
+        kind: SymbolKind,
+        node: Node,
+        name: String,
+        qualified_name: String,
+        doc: Option<String>,
+    )
+    {
+        let start = node.start_position();
+        let end = node.end_position();
+        let end_line = if end.column == 0 && end.row > start.row
+        {
+            end.row
+        }
+        else
+        {
+            end.row + 1
+        };
+        self.out
+            .push(Symbol {
+                file: self
+                    .file
+                    .to_path_buf(),
+                lang: self
+                    .lang
+                    .to_string(),
+                kind,
+                name,
+                qualified_name,
+                byte_start: node.start_byte(),
+                byte_end: node.end_byte(),
+                start_line: start.row + 1,
+                end_line,
+                visibility: None,
+                doc,
+                signature: None,
+                package: None,
+                module: None,
+            });
+    }
+
+    /// First line of a value, cut to `PREVIEW_CHARS`
+    fn preview(
+        &self,
+        value: Node,
+    ) -> Option<String>
+    {
+        let text = self.text(value)?;
+        let line = text
+            .lines()
+            .next()?
+            .trim();
+        if line.is_empty()
+        {
+            return None;
+        }
+        let mut preview: String = line
+            .chars()
+            .take(PREVIEW_CHARS)
+            .collect();
+        if line
+            .chars()
+            .count()
+            > PREVIEW_CHARS
+        {
+            preview.push('…');
+        }
+        Some(preview)
+    }
+
+    fn text(
+        &self,
+        node: Node,
+    ) -> Option<String>
+    {
+        node.utf8_text(self.bytes)
+            .ok()
+            .map(|t| {
+                t.trim()
+                    .to_string()
+            })
+    }
+}
+
+/// The mapping a YAML/JSON node holds, looking through documents and the
+/// `block_node`/`flow_node` wrappers
+fn find_mapping(node: Node) -> Option<Node>
+{
+    match node.kind()
+    {
+        "block_mapping" | "flow_mapping" | "object" => Some(node),
+        "stream" | "document" | "block_node" | "flow_node" =>
+        {
+            let mut cursor = node.walk();
+            node.named_children(&mut cursor)
+                .find_map(find_mapping)
+        }
+        _ => None,
+    }
+}
+
+fn dotted(
+    parent: Option<&str>,
+    key: &str,
+) -> String
+{
+    match parent
+    {
+        Some(parent) => format!("{parent}.{key}"),
+        None => key.to_string(),
+    }
+}
+
+fn unquote(key: &str) -> &str
+{
+    key.strip_prefix('"')
+        .and_then(|k| k.strip_suffix('"'))
+        .or_else(|| {
+            key.strip_prefix('\'')
+                .and_then(|k| k.strip_suffix('\''))
+        })
+        .unwrap_or(key)
+}
+
+#[cfg(test)]
+mod tests
+{
+    use super::*;
+
+    fn extract(
+        src: &str,
+        file: &str,
+    ) -> Vec<Symbol>
+    {
+        ConfigExtractor::new()
+            .unwrap()
+            .extract_symbols(src, Path::new(file))
+            .unwrap()
+    }
+
+    fn get<'a>(
+        syms: &'a [Symbol],
+        qualified: &str,
+    ) -> &'a Symbol
+    {
+        syms.iter()
+            .find(|s| s.qualified_name == qualified)
+            .expect("symbol not found")
+    }
+
+    #[test]
+    fn toml_tables_and_keys()
+    {
+        let src = r#"name = "x"
+
+[package]
+version = "0.1"
+
+[dependencies]
+serde = { version = "1", features = ["derive"] }
+"quoted-key" = "2"
+
+[[bin]]
+name = "rup"
+"#;
+        let syms = extract(src, "Cargo.toml");
+
+        assert_eq!(get(&syms, "name").kind, SymbolKind::Key);
+        let deps = get(&syms, "dependencies");
+        assert_eq!(deps.kind, SymbolKind::Table);
+        assert_eq!(deps.lang, "toml");
+        assert_eq!((deps.start_line, deps.end_line), (6, 8));
+
+        let serde = get(&syms, "dependencies.serde");
+        assert_eq!(serde.name, "serde");
+        assert_eq!(serde.kind, SymbolKind::Key);
+        assert_eq!(
+            serde
+                .doc
+                .as_deref(),
+            Some(r#"{ version = "1", features = ["derive"] }"#)
+        );
+        assert_eq!(get(&syms, "dependencies.quoted-key").start_line, 8);
+        assert_eq!(get(&syms, "bin.name").kind, SymbolKind::Key);
+    }
+
+    #[test]
+    fn yaml_and_json_keys_two_levels_deep()
+    {
+        let yaml = "name: CI\njobs:\n  build:\n    runs-on: ubuntu\n    steps:\n      - run: \
+                    cargo test\n  lint: {}\n";
+        let syms = extract(yaml, "ci.yml");
+        assert_eq!(
+            get(&syms, "name")
+                .doc
+                .as_deref(),
+            Some("CI")
+        );
+        assert_eq!(get(&syms, "jobs").kind, SymbolKind::Table);
+        let build = get(&syms, "jobs.build");
+        assert_eq!(build.kind, SymbolKind::Table);
+        assert_eq!((build.start_line, build.end_line), (3, 6));
+        assert_eq!(get(&syms, "jobs.lint").lang, "yaml");
+        assert!(
+            !syms
+                .iter()
+                .any(|s| s.name == "runs-on")
+        );
+
+        let json =
+            "{\n  \"name\": \"app\",\n  \"dependencies\": {\n    \"react\": \"^18\"\n  }\n}\n";
+        let syms = extract(json, "package.json");
+        assert_eq!(get(&syms, "dependencies").kind, SymbolKind::Table);
+        let react = get(&syms, "dependencies.react");
+        assert_eq!(
+            react
+                .doc
+                .as_deref(),
+            Some("\"^18\"")
+        );
+        assert_eq!(react.start_line, 4);
+    }
+
+    #[test]
+    fn lockfiles_are_skipped()
+    {
+        let json = "{\n  \"packages\": {\n    \"a\": {}\n  }\n}\n";
+        assert!(extract(json, "package-lock.json").is_empty());
+        assert!(extract("lockfileVersion: 9\n", "pnpm-lock.yaml").is_empty());
+    }
+}
@@ -0,0 +1,522 @@
+//! Filepath: src/parsers/csharp_parser.rs
+//!
+//! C# symbol extractor built on Tree-sitter 0.25.x.
+//!
+//! Notes:
+//!   - Namespaces (block and file-scoped), classes, records, structs, interfaces, enums,
+//!     methods, constructors, properties, and fields; `const` fields are Constants.
+//!   - Names are qualified as `Acme.Widgets::Widget::Render`.
+//!   - The parts of a `partial` type in one file merge into the first part's symbol;
+//!     parts in other files share its qualified name.
+//!   - Without a modifier, types are Internal, members Private, and interface members
+//!     Public. A partial type whose modifier may sit on a part in another file keeps
+//!     visibility unspecified.
+//!   - The `///` lines right above a declaration become its doc, with the `<summary>`
+//!     wrapper removed.
+
+use std::{collections::HashMap, path::Path};
+
+use anyhow::{Context, Result};
+use tree_sitter::{Language, Node};
+
+use crate::{
+    core::symbols::{
+        Symbol, SymbolExtractor, SymbolKind, Visibility, build_qualified_name, parse_visibility,
+    },
+    infra::utils::TsNodeUtils,
+};
+
+/// Extracts C# symbols (namespaces, types, methods, properties, fields).
+pub struct CSharpExtractor
+{
+    /// C# language handle for Tree-sitter.
+    language: Language,
+}
+
+impl CSharpExtractor
+{
+    pub fn new() -> Result<Self>
+    {
+        Ok(Self { language: tree_sitter_c_sharp::LANGUAGE.into() })
+    }
+}
+
+impl SymbolExtractor for CSharpExtractor
+{
+    fn extract_symbols(
+        &self,
+        content: &str,
+        file_path: &Path,
+    ) -> Result<Vec<Symbol>>
+    {
+        let tree =
+            TsNodeUtils::parse(&self.language, content).context("Failed to parse C# source")?;
+
+        let mut walker = Walker {
+            bytes: content.as_bytes(),
+            file: file_path,
+            owners: Vec::new(),
+            partials: HashMap::new(),
+            out: Vec::new(),
+        };
+        walker.walk(tree.root_node(), false);
+        Ok(walker.out)
+    }
+}
+
+struct Walker<'a>
+{
+    bytes: &'a [u8],
+    file: &'a Path,
+    /// Namespaces and enclosing types, outermost first
+    owners: Vec<String>,
+    /// Partial types seen so far, as indices into `out`
+    partials: HashMap<String, usize>,
+    out: Vec<Symbol>,
+}
+
+impl Walker<'_>
+{
+    /// Visit declarations under `node`; `in_interface` makes members Public
+    fn walk(
+        &mut self,
+        node: Node,
+        in_interface: bool,
+    )
+    {
+        // A file-scoped namespace owns every declaration after it
+        let depth = self
+            .owners
+            .len();
+        let mut cursor = node.walk();
+        for child in node.named_children(&mut cursor)
+        {
+            let kind = match child.kind()
+            {
+                "namespace_declaration" | "file_scoped_namespace_declaration" =>
+                {
+                    let Some(name) = self.name_of(child)
+                    else
+                    {
+                        continue;
+                    };
+                    self.push(SymbolKind::Module, child, name.clone(), None);
+                    self.owners
+                        .push(name);
+                    if let Some(body) = child.child_by_field_name("body")
+                    {
+                        self.walk(body, false);
+                        self.owners
+                            .pop();
+                    }
+                    continue;
+                }
+                "class_declaration" => SymbolKind::Class,
+                "record_declaration" if has_token(child, "struct") => SymbolKind::Struct,
+                "record_declaration" => SymbolKind::Class,
+                "struct_declaration" => SymbolKind::Struct,
+                "interface_declaration" => SymbolKind::Interface,
+                "enum_declaration" => SymbolKind::Enum,
+                "method_declaration" | "constructor_declaration" => SymbolKind::Method,
+                "property_declaration" => SymbolKind::Property,
+                "field_declaration" =>
+                {
+                    self.push_fields(child, in_interface);
+                    continue;
+                }
+                _ => continue,
+            };
+            let Some(name) = self.name_of(child)
+            else
+            {
+                continue;
+            };
+
+            let declared = modifier_visibility(child, self.bytes);
+            let is_type = !matches!(kind, SymbolKind::Method | SymbolKind::Property);
+            if is_type && has_modifier(child, self.bytes, "partial")
+            {
+                self.push_partial(kind.clone(), child, name.clone(), declared);
+            }
+            else
+            {
+                let visibility =
+                    declared.unwrap_or_else(|| default_visibility(is_type, in_interface));
+                self.push(kind.clone(), child, name.clone(), Some(visibility));
+            }
+
+            if is_type && let Some(body) = child.child_by_field_name("body")
+            {
+                self.owners
+                    .push(name);
+                self.walk(body, kind == SymbolKind::Interface);
+                self.owners
+                    .pop();
+            }
+        }
+        self.owners
+            .truncate(depth);
+    }
+
+    /// One symbol per declarator of a field; `const` fields are Constants
+    fn push_fields(
+        &mut self,
+        field: Node,
+        in_interface: bool,
+    )
+    {
+        let kind = if has_modifier(field, self.bytes, "const")
+        {
+            SymbolKind::Constant
+        }
+        else
+        {
+            SymbolKind::Variable
+        };
+        let visibility = modifier_visibility(field, self.bytes)
+            .unwrap_or_else(|| default_visibility(false, in_interface));
+
+        let mut cursor = field.walk();
+        let Some(decl) = field
+            .named_children(&mut cursor)
+            .find(|c| c.kind() == "variable_declaration")
+        else
+        {
+            return;
+        };
+        let mut cursor = decl.walk();
+        for declarator in decl.named_children(&mut cursor)
+        {
+            if declarator.kind() != "variable_declarator"
+            {
+                continue;
+            }
+            if let Some(name) = self.name_of(declarator)
+            {
+                self.push(kind.clone(), field, name, Some(visibility.clone()));
+            }
+        }
+    }
+
+    /// Record a part of a `partial` type, folding later parts into the first
+    fn push_partial(
+        &mut self,
+        kind: SymbolKind,
+        node: Node,
+        name: String,
+        declared: Option<Visibility>,
+    )
+    {
+        let qualified_name = self.qualify(&name);
+        if let Some(&index) = self
+            .partials
+            .get(&qualified_name)
+        {
+            let first = &mut self.out[index];
+            if first
+                .visibility
+                .is_none()
+            {
+                first.visibility = declared;
+            }
+            if first
+                .doc
+                .is_none()
+            {
+                first.doc = csharp_doc(node, self.bytes);
+            }
+            return;
+        }
+
+        self.partials
+            .insert(
+                qualified_name,
+                self.out
+                    .len(),
+            );
+        self.push(kind, node, name, declared);
+    }
+
+    fn push(
+        &mut self,
+        kind: SymbolKind,
+        node: Node,
+        name: String,
+        visibility: Option<Visibility>,
+    )
+    {
+        let qualified_name = self.qualify(&name);
+        let start = node.start_position();
+        let end = node.end_position();
+        self.out
+            .push(Symbol {
+                file: self
+                    .file
+                    .to_path_buf(),
+                lang: "csharp".to_string(),
+                kind,
+                name,
+                qualified_name,
+                byte_start: node.start_byte(),
+                byte_end: node.end_byte(),
+                start_line: start.row + 1,
+                end_line: end.row + 1,
+                visibility,
+                doc: csharp_doc(node, self.bytes),
+                signature: None,
+                package: None,
+                module: None,
+            });
+    }
+
+    fn qualify(
+        &self,
+        name: &str,
+    ) -> String
+    {
+        let mut parts: Vec<&str> = self
+            .owners
+            .iter()
+            .map(String::as_str)
+            .collect();
+        parts.push(name);
+        build_qualified_name(&parts)
+    }
+
+    fn name_of(
+        &self,
+        node: Node,
+    ) -> Option<String>
+    {
+        node.child_by_field_name("name")?
+            .utf8_text(self.bytes)
+            .ok()
+            .map(str::to_string)
+    }
+}
+
+/// C# defaults: types are internal, interface members public, others private
+fn default_visibility(
+    is_type: bool,
+    in_interface: bool,
+) -> Visibility
+{
+    if in_interface
+    {
+        Visibility::Public
+    }
+    else if is_type
+    {
+        Visibility::Internal
+    }
+    else
+    {
+        Visibility::Private
+    }
+}
+
+/// First access modifier on the declaration (`protected internal` is Protected)
+fn modifier_visibility(
+    node: Node,
+    bytes: &[u8],
+) -> Option<Visibility>
+{
+    modifiers(node, bytes).find_map(|m| {
+        match m
+        {
+            "internal" => Some(Visibility::Internal),
+            _ => parse_visibility(m),
+        }
+    })
+}
+
+fn has_modifier(
+    node: Node,
+    bytes: &[u8],
+    modifier: &str,
+) -> bool
+{
+    modifiers(node, bytes).any(|m| m == modifier)
+}
+
+fn modifiers<'b>(
+    node: Node<'b>,
+    bytes: &'b [u8],
+) -> impl Iterator<Item = &'b str>
+{
+    let mut cursor = node.walk();
+    node.named_children(&mut cursor)
+        .filter(|c| c.kind() == "modifier")
+        .filter_map(|c| {
+            c.utf8_text(bytes)
+                .ok()
+        })
+        .collect::<Vec<_>>()
+        .into_iter()
+}
+
+/// Whether `node` has an anonymous `token` child (`record struct`)
+fn has_token(
+    node: Node,
+    token: &str,
+) -> bool
+{
+    let mut cursor = node.walk();
+    node.children(&mut cursor)
+        .any(|c| !c.is_named() && c.kind() == token)
+}
+
+/// Consecutive `///` lines ending right above `node`, minus `<summary>` tags
+fn csharp_doc(
+    node: Node,
+    bytes: &[u8],
+) -> Option<String>
+{
+    let mut lines = Vec::new();
+    let mut row = node
+        .start_position()
+        .row;
+    let mut prev = node.prev_named_sibling();
+    while let Some(comment) = prev
+    {
+        if comment.kind() != "comment"
+            || comment
+                .end_position()
+                .row
+                + 1
+                != row
+        {
+            break;
+        }
+        let Some(line) = comment
+            .utf8_text(bytes)
+            .ok()
+            .and_then(|t| t.strip_prefix("///"))
+        else
+        {
+            break;
+        };
+        let line = line
+            .trim()
+            .trim_start_matches("<summary>")
+            .trim_end_matches("</summary>")
+            .trim();
+        if !line.is_empty()
+        {
+            lines.push(line);
+        }
+        row = comment
+            .start_position()
+            .row;
+        prev = comment.prev_named_sibling();
+    }
+    lines.reverse();
+    (!lines.is_empty()).then(|| lines.join("\n"))
+}
+
+#[cfg(test)]
+mod tests
+{
+    use super::*;
+
+    fn extract(src: &str) -> Vec<Symbol>
+    {
+        CSharpExtractor::new()
+            .unwrap()
+            .extract_symbols(src, Path::new("Widget.cs"))
+            .unwrap()
+    }
+
+    fn get<'a>(
+        syms: &'a [Symbol],
+        name: &str,
+    ) -> &'a Symbol
+    {
+        syms.iter()
+            .find(|s| s.name == name)
+            .expect("symbol not found")
+    }
+
+    #[test]
+    fn csharp_namespaces_members_and_visibility()
+    {
+        let src = r#"namespace Acme.Widgets;
+
+/// <summary>
+/// A widget.
+/// </summary>
+[Serializable]
+public class Widget
+{
+    int count;
+    public const string Kind = "w", Other = "x";
+    public string Name { get; set; }
+    public Widget() {}
+    protected internal void Render() {}
+}
+record struct Point(int X);
+interface IShape { double Area(); }
+"#;
+        let syms = extract(src);
+
+        assert_eq!(get(&syms, "Acme.Widgets").kind, SymbolKind::Module);
+        let widget = get(&syms, "Widget");
+        assert_eq!(widget.kind, SymbolKind::Class);
+        assert_eq!(widget.qualified_name, "Acme.Widgets::Widget");
+        assert_eq!(
+            widget
+                .doc
+                .as_deref(),
+            Some("A widget.")
+        );
+        assert_eq!(widget.start_line, 6);
+
+        let count = get(&syms, "count");
+        assert_eq!(count.kind, SymbolKind::Variable);
+        assert_eq!(count.visibility, Some(Visibility::Private));
+        assert_eq!(get(&syms, "Other").kind, SymbolKind::Constant);
+        let name = get(&syms, "Name");
+        assert_eq!(name.kind, SymbolKind::Property);
+        assert_eq!(name.qualified_name, "Acme.Widgets::Widget::Name");
+        assert_eq!(get(&syms, "Render").visibility, Some(Visibility::Protected));
+
+        let point = get(&syms, "Point");
+        assert_eq!(point.kind, SymbolKind::Struct);
+        assert_eq!(point.visibility, Some(Visibility::Internal));
+        assert_eq!(get(&syms, "Area").visibility, Some(Visibility::Public));
+    }
+
+    #[test]
+    fn csharp_partial_parts_merge_into_one_symbol()
+    {
+        let src = r#"namespace Acme
+{
+    partial class Widget
+    {
+        void Draw() {}
+    }
+
+    /// Public half.
+    public partial class Widget
+    {
+        void Layout() {}
+    }
+}
+"#;
+        let syms = extract(src);
+        let widgets: Vec<&Symbol> = syms
+            .iter()
+            .filter(|s| s.name == "Widget")
+            .collect();
+        assert_eq!(widgets.len(), 1);
+        assert_eq!(widgets[0].start_line, 3);
+        assert_eq!(widgets[0].visibility, Some(Visibility::Public));
+        assert_eq!(
+            widgets[0]
+                .doc
+                .as_deref(),
+            Some("Public half.")
+        );
+        assert_eq!(get(&syms, "Layout").qualified_name, "Acme::Widget::Layout");
+    }
+}
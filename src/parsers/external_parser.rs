@@ -0,0 +1,460 @@
+//! Filepath: src/parsers/external_parser.rs
+//!
+//! Subprocess extractors: third-party binaries that index languages
+//! roughup doesn't build in, registered under `[symbols.external]`.
+//!
+//! Notes:
+//!   - One process per file. roughup writes a single request line to the extractor's
+//!     stdin and closes it, draining stdout meanwhile so a large file cannot deadlock:
+//!     `{"protocol":1,"language":"elixir","path":"lib/app.ex","content":"..."}`
+//!   - The extractor answers with one symbol per line on stdout and exits 0:
+//!     `{"kind":"function","name":"start","qualified_name":"App::start",` `
+//!     "byte_start":10,"byte_end":52,"visibility":"public","doc":"..."}` `kind` and
+//!     `visibility` take the snake_case names used in `symbols.jsonl`; `qualified_name`
+//!     defaults to `name`; `visibility` and `doc` are optional. Blank lines are ignored
+//!     and line numbers are derived from the byte span.
+//!   - A non-zero exit, an unknown kind, or a span outside the file fails the extraction
+//!     with the extractor's own message. An extractor still running after its
+//!     `timeout_secs` (default 30) is killed and fails it too.
+//!   - Registrations come from the working directory's config on first use and take
+//!     precedence over built-in extractors, so a team can swap in its own. Embedders can
+//!     add more with [`register_external_extractor`].
+
+use std::{
+    collections::BTreeMap,
+    io::{ErrorKind, Read, Write},
+    path::Path,
+    process::{Command, Stdio},
+    sync::{OnceLock, RwLock},
+    thread,
+    time::{Duration, Instant},
+};
+
+use anyhow::{Context, Result, anyhow, bail};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    core::symbols::{Symbol, SymbolExtractor, SymbolKind, Visibility},
+    infra::config::{ExternalExtractorConfig, load_config},
+};
+
+/// Protocol version sent with every request
+pub const PROTOCOL_VERSION: u32 = 1;
+
+/// Runs a configured binary as the symbol extractor for one language.
+pub struct ExternalExtractor
+{
+    /// Language label the binary was registered under
+    language: String,
+    /// Program and arguments
+    command: Vec<String>,
+    /// Longest one file may take before the process is killed
+    timeout: Duration,
+}
+
+impl ExternalExtractor
+{
+    pub fn new(
+        language: &str,
+        command: Vec<String>,
+    ) -> Result<Self>
+    {
+        if command.is_empty()
+        {
+            bail!("external extractor '{language}' has an empty command");
+        }
+        Ok(Self {
+            language: language.to_string(),
+            command,
+            timeout: Duration::from_secs(30),
+        })
+    }
+
+    /// Kill the extractor when one file takes longer than `timeout`
+    pub fn with_timeout(
+        mut self,
+        timeout: Duration,
+    ) -> Self
+    {
+        self.timeout = timeout;
+        self
+    }
+}
+
+/// Read all of `pipe` on its own thread
+fn drain(mut pipe: impl Read + Send + 'static) -> thread::JoinHandle<std::io::Result<Vec<u8>>>
+{
+    thread::spawn(move || {
+        let mut buf = Vec::new();
+        pipe.read_to_end(&mut buf)
+            .map(|_| buf)
+    })
+}
+
+/// Request line written to the extractor's stdin
+#[derive(Serialize)]
+struct Request<'a>
+{
+    protocol: u32,
+    language: &'a str,
+    path: &'a Path,
+    content: &'a str,
+}
+
+/// Symbol line read from the extractor's stdout
+#[derive(Deserialize)]
+struct Reply
+{
+    kind: SymbolKind,
+    name: String,
+    #[serde(default)]
+    qualified_name: Option<String>,
+    byte_start: usize,
+    byte_end: usize,
+    #[serde(default)]
+    visibility: Option<Visibility>,
+    #[serde(default)]
+    doc: Option<String>,
+}
+
+impl SymbolExtractor for ExternalExtractor
+{
+    fn extract_symbols(
+        &self,
+        content: &str,
+        file_path: &Path,
+    ) -> Result<Vec<Symbol>>
+    {
+        let program = &self.command[0];
+        let mut child = Command::new(program)
+            .args(&self.command[1..])
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .with_context(|| format!("spawn external extractor '{program}'"))?;
+
+        let request = Request {
+            protocol: PROTOCOL_VERSION,
+            language: &self.language,
+            path: file_path,
+            content,
+        };
+        let mut line = serde_json::to_string(&request)?;
+        line.push('\n');
+
+        // Feed stdin and drain both outputs on their own threads, so an
+        // extractor answering while it still reads never blocks on a full pipe
+        let (Some(mut stdin), Some(stdout), Some(stderr)) = (
+            child
+                .stdin
+                .take(),
+            child
+                .stdout
+                .take(),
+            child
+                .stderr
+                .take(),
+        )
+        else
+        {
+            bail!("external extractor '{program}' has no stdio pipes");
+        };
+        let writer = thread::spawn(move || stdin.write_all(line.as_bytes()));
+        let stdout = drain(stdout);
+        let stderr = drain(stderr);
+
+        let deadline = Instant::now() + self.timeout;
+        let status = loop
+        {
+            if let Some(status) = child
+                .try_wait()
+                .with_context(|| format!("wait for external extractor '{program}'"))?
+            {
+                break status;
+            }
+            if Instant::now() >= deadline
+            {
+                let _ = child.kill();
+                let _ = child.wait();
+                bail!(
+                    "external extractor '{program}' timed out after {}s on {}",
+                    self.timeout
+                        .as_secs_f64(),
+                    file_path.display()
+                );
+            }
+            thread::sleep(Duration::from_millis(5));
+        };
+
+        let joined = |handle: thread::JoinHandle<std::io::Result<Vec<u8>>>| {
+            handle
+                .join()
+                .map_err(|_| anyhow!("external extractor '{program}': output reader panicked"))?
+                .with_context(|| format!("read output of external extractor '{program}'"))
+        };
+        let stdout = joined(stdout)?;
+        let stderr = joined(stderr)?;
+        if !status.success()
+        {
+            let stderr = String::from_utf8_lossy(&stderr);
+            bail!(
+                "external extractor '{program}' failed on {} ({}): {}",
+                file_path.display(),
+                status,
+                stderr.trim()
+            );
+        }
+        match writer.join()
+        {
+            // An extractor may exit without reading all of it; its status says why
+            Ok(Ok(())) =>
+            {}
+            Ok(Err(e)) if e.kind() == ErrorKind::BrokenPipe =>
+            {}
+            Ok(Err(e)) =>
+            {
+                return Err(e)
+                    .with_context(|| format!("write request to external extractor '{program}'"));
+            }
+            Err(_) => bail!("external extractor '{program}': request writer panicked"),
+        }
+
+        let stdout = String::from_utf8(stdout)
+            .with_context(|| format!("external extractor '{program}' wrote non-UTF-8 output"))?;
+        let mut out = Vec::new();
+        for (i, line) in stdout
+            .lines()
+            .enumerate()
+            .filter(|(_, l)| {
+                !l.trim()
+                    .is_empty()
+            })
+        {
+            let reply: Reply = serde_json::from_str(line).with_context(|| {
+                format!(
+                    "external extractor '{program}': bad symbol on output line {}",
+                    i + 1
+                )
+            })?;
+            if reply.byte_start > reply.byte_end || reply.byte_end > content.len()
+            {
+                return Err(anyhow!(
+                    "external extractor '{program}': span {}..{} of '{}' is outside {} ({} bytes)",
+                    reply.byte_start,
+                    reply.byte_end,
+                    reply.name,
+                    file_path.display(),
+                    content.len()
+                ));
+            }
+            let line_of = |byte: usize| {
+                content.as_bytes()[..byte]
+                    .iter()
+                    .filter(|&&b| b == b'\n')
+                    .count()
+                    + 1
+            };
+            out.push(Symbol {
+                file: file_path.to_path_buf(),
+                lang: self
+                    .language
+                    .clone(),
+                kind: reply.kind,
+                qualified_name: reply
+                    .qualified_name
+                    .unwrap_or_else(|| {
+                        reply
+                            .name
+                            .clone()
+                    }),
+                name: reply.name,
+                byte_start: reply.byte_start,
+                byte_end: reply.byte_end,
+                start_line: line_of(reply.byte_start),
+                end_line: line_of(reply.byte_end),
+                visibility: reply.visibility,
+                doc: reply.doc,
+                signature: None,
+                package: None,
+                module: None,
+            });
+        }
+        Ok(out)
+    }
+}
+
+fn global() -> &'static RwLock<BTreeMap<String, ExternalExtractorConfig>>
+{
+    static REGISTRY: OnceLock<RwLock<BTreeMap<String, ExternalExtractorConfig>>> = OnceLock::new();
+    REGISTRY.get_or_init(|| {
+        let configured = load_config()
+            .map(|cfg| {
+                cfg.symbols
+                    .external
+            })
+            .unwrap_or_default();
+        RwLock::new(configured)
+    })
+}
+
+/// Register an extractor in the process-wide registry, replacing any
+/// earlier one for the same language
+pub fn register_external_extractor(
+    language: &str,
+    config: ExternalExtractorConfig,
+)
+{
+    global()
+        .write()
+        .unwrap_or_else(|e| e.into_inner())
+        .insert(language.to_lowercase(), config);
+}
+
+/// Registered language claiming files with this extension, if any
+pub fn external_language_for(ext: &str) -> Option<String>
+{
+    global()
+        .read()
+        .unwrap_or_else(|e| e.into_inner())
+        .iter()
+        .find(|(_, cfg)| {
+            cfg.extensions
+                .iter()
+                .any(|e| e.eq_ignore_ascii_case(ext))
+        })
+        .map(|(lang, _)| lang.clone())
+}
+
+/// Labels of all registered external languages
+pub fn external_languages() -> Vec<String>
+{
+    global()
+        .read()
+        .unwrap_or_else(|e| e.into_inner())
+        .keys()
+        .cloned()
+        .collect()
+}
+
+/// Extractor for a registered external language
+pub fn external_extractor(language: &str) -> Option<Result<ExternalExtractor>>
+{
+    global()
+        .read()
+        .unwrap_or_else(|e| e.into_inner())
+        .get(language)
+        .map(|cfg| {
+            ExternalExtractor::new(
+                language,
+                cfg.command
+                    .clone(),
+            )
+            .map(|e| e.with_timeout(Duration::from_secs(cfg.timeout_secs)))
+        })
+}
+
+#[cfg(all(test, unix))]
+mod tests
+{
+    use super::*;
+
+    fn shell(script: &str) -> ExternalExtractor
+    {
+        ExternalExtractor::new("elixir", vec!["sh".into(), "-c".into(), script.into()]).unwrap()
+    }
+
+    #[test]
+    fn replies_become_symbols()
+    {
+        let content = "defmodule App do\n  def start, do: :ok\nend\n";
+        let extractor = shell(
+            r#"grep -q '"protocol":1' || exit 3
+echo '{"kind":"module","name":"App","byte_start":0,"byte_end":41}'
+echo
+echo '{"kind":"function","name":"start","qualified_name":"App::start","byte_start":19,"byte_end":37,"visibility":"public","doc":"Boot."}'"#,
+        );
+        let syms = extractor
+            .extract_symbols(content, Path::new("lib/app.ex"))
+            .unwrap();
+
+        assert_eq!(syms.len(), 2);
+        assert_eq!(syms[0].qualified_name, "App");
+        assert_eq!((syms[0].start_line, syms[0].end_line), (1, 3));
+        assert_eq!(syms[1].kind, SymbolKind::Function);
+        assert_eq!(syms[1].lang, "elixir");
+        assert_eq!(syms[1].start_line, 2);
+        assert_eq!(syms[1].visibility, Some(Visibility::Public));
+        assert_eq!(
+            syms[1]
+                .doc
+                .as_deref(),
+            Some("Boot.")
+        );
+    }
+
+    #[test]
+    fn failures_carry_the_extractor_message()
+    {
+        let err = shell("echo 'cannot parse' >&2; exit 2")
+            .extract_symbols("x", Path::new("a.ex"))
+            .unwrap_err();
+        assert!(
+            err.to_string()
+                .contains("cannot parse")
+        );
+
+        let err = shell(r#"echo '{"kind":"widget","name":"x","byte_start":0,"byte_end":1}'"#)
+            .extract_symbols("x", Path::new("a.ex"))
+            .unwrap_err();
+        assert!(format!("{err:#}").contains("line 1"));
+
+        let err = shell(r#"echo '{"kind":"function","name":"x","byte_start":0,"byte_end":9}'"#)
+            .extract_symbols("x", Path::new("a.ex"))
+            .unwrap_err();
+        assert!(
+            err.to_string()
+                .contains("outside a.ex")
+        );
+    }
+
+    #[test]
+    fn large_files_stream_without_deadlock()
+    {
+        // Echoing the request back fills a pipe long before it is all written
+        let content = "x".repeat(1 << 20);
+        let syms =
+            shell(r#"cat >&2; echo '{"kind":"module","name":"Big","byte_start":0,"byte_end":1}'"#)
+                .extract_symbols(&content, Path::new("big.ex"))
+                .unwrap();
+        assert_eq!(syms[0].name, "Big");
+    }
+
+    #[test]
+    fn hung_extractors_time_out()
+    {
+        let err = shell("sleep 5")
+            .with_timeout(Duration::from_millis(200))
+            .extract_symbols("x", Path::new("a.ex"))
+            .unwrap_err();
+        assert!(
+            err.to_string()
+                .contains("timed out"),
+            "{err:#}"
+        );
+    }
+
+    #[test]
+    fn registrations_route_by_extension()
+    {
+        register_external_extractor("Zig", ExternalExtractorConfig {
+            command: vec!["zig-symbols".into()],
+            extensions: vec!["zig".into()],
+            timeout_secs: 5,
+        });
+        assert_eq!(external_language_for("ZIG").as_deref(), Some("zig"));
+        assert!(external_languages().contains(&"zig".to_string()));
+        assert!(external_extractor("zig").is_some_and(|e| e.is_ok()));
+        assert!(external_extractor("cobol").is_none());
+    }
+}
@@ -0,0 +1,380 @@
+//! Filepath: src/parsers/go_parser.rs
+//!
+//! Go symbol extractor built on Tree-sitter 0.25.x.
+//!
+//! Notes:
+//!   - Go declarations are all top-level, so this walks the file's direct children
+//!     instead of running a query.
+//!   - Names are qualified by package: `pkg::Func`, `pkg::Type`, and `pkg::Type::Method`
+//!     for methods (pointer and generic receivers are reduced to the base type name).
+//!   - Visibility follows Go's export rule: a leading capital is Public, anything else
+//!     Private.
+//!   - The `//` comment block directly above a declaration is its doc.
+
+use std::path::Path;
+
+use anyhow::{Context, Result};
+use tree_sitter::{Language, Node};
+
+use crate::{
+    core::symbols::{
+        Reference, Symbol, SymbolExtractor, SymbolKind, Visibility, build_qualified_name,
+    },
+    infra::utils::TsNodeUtils,
+};
+
+/// Extracts Go symbols (package, funcs, methods, structs, interfaces, types).
+pub struct GoExtractor
+{
+    /// Go language handle for Tree-sitter.
+    language: Language,
+}
+
+impl GoExtractor
+{
+    pub fn new() -> Result<Self>
+    {
+        Ok(Self { language: tree_sitter_go::LANGUAGE.into() })
+    }
+}
+
+impl SymbolExtractor for GoExtractor
+{
+    fn extract_symbols(
+        &self,
+        content: &str,
+        file_path: &Path,
+    ) -> Result<Vec<Symbol>>
+    {
+        let tree =
+            TsNodeUtils::parse(&self.language, content).context("Failed to parse Go source")?;
+        let bytes = content.as_bytes();
+        let root = tree.root_node();
+
+        let text = |n: Node| {
+            n.utf8_text(bytes)
+                .ok()
+                .map(str::to_string)
+        };
+        let mut cursor = root.walk();
+        let package = root
+            .named_children(&mut cursor)
+            .find(|n| n.kind() == "package_clause")
+            .and_then(|n| {
+                let mut c = n.walk();
+                n.named_children(&mut c)
+                    .find(|c| c.kind() == "package_identifier")
+                    .and_then(text)
+                    .map(|name| (n, name))
+            });
+
+        let mut out = Vec::new();
+        let pkg = package
+            .as_ref()
+            .map(|(_, name)| name.as_str());
+        let symbol = |kind: SymbolKind, span: Node, name: String, owner: Option<&str>| {
+            let mut parts: Vec<&str> = pkg
+                .into_iter()
+                .collect();
+            parts.extend(owner);
+            parts.push(&name);
+            let qualified_name = build_qualified_name(&parts);
+            let visibility = match kind
+            {
+                SymbolKind::Package => None,
+                _ if name.starts_with(char::is_uppercase) => Some(Visibility::Public),
+                _ => Some(Visibility::Private),
+            };
+            let start = span.start_position();
+            let end = span.end_position();
+            Symbol {
+                file: file_path.to_path_buf(),
+                lang: "go".to_string(),
+                kind,
+                qualified_name,
+                byte_start: span.start_byte(),
+                byte_end: span.end_byte(),
+                start_line: start.row + 1,
+                end_line: end.row + 1,
+                visibility,
+                doc: go_doc(span, bytes),
+                name,
+                signature: None,
+                package: None,
+                module: None,
+            }
+        };
+
+        if let Some((node, name)) = &package
+        {
+            let start = node.start_position();
+            let end = node.end_position();
+            out.push(Symbol {
+                file: file_path.to_path_buf(),
+                lang: "go".to_string(),
+                kind: SymbolKind::Package,
+                name: name.clone(),
+                qualified_name: name.clone(),
+                byte_start: node.start_byte(),
+                byte_end: node.end_byte(),
+                start_line: start.row + 1,
+                end_line: end.row + 1,
+                visibility: None,
+                doc: go_doc(*node, bytes),
+                signature: None,
+                package: None,
+                module: None,
+            });
+        }
+
+        let mut cursor = root.walk();
+        for node in root.named_children(&mut cursor)
+        {
+            match node.kind()
+            {
+                "function_declaration" =>
+                {
+                    if let Some(name) = node
+                        .child_by_field_name("name")
+                        .and_then(text)
+                    {
+                        out.push(symbol(SymbolKind::Function, node, name, None));
+                    }
+                }
+                "method_declaration" =>
+                {
+                    let name = node
+                        .child_by_field_name("name")
+                        .and_then(text);
+                    let receiver = node
+                        .child_by_field_name("receiver")
+                        .and_then(|r| receiver_type(r, bytes));
+                    if let Some(name) = name
+                    {
+                        out.push(symbol(SymbolKind::Method, node, name, receiver.as_deref()));
+                    }
+                }
+                "type_declaration" =>
+                {
+                    let mut c = node.walk();
+                    let specs: Vec<Node> = node
+                        .named_children(&mut c)
+                        .filter(|s| matches!(s.kind(), "type_spec" | "type_alias"))
+                        .collect();
+                    for spec in &specs
+                    {
+                        let kind = match spec
+                            .child_by_field_name("type")
+                            .map(|t| t.kind())
+                        {
+                            _ if spec.kind() == "type_alias" => SymbolKind::TypeAlias,
+                            Some("struct_type") => SymbolKind::Struct,
+                            Some("interface_type") => SymbolKind::Interface,
+                            _ => SymbolKind::TypeAlias,
+                        };
+                        // A lone spec spans its `type` keyword and doc comment
+                        let span = if specs.len() == 1 { node } else { *spec };
+                        if let Some(name) = spec
+                            .child_by_field_name("name")
+                            .and_then(text)
+                        {
+                            out.push(symbol(kind, span, name, None));
+                        }
+                    }
+                }
+                _ =>
+                {}
+            }
+        }
+
+        Ok(out)
+    }
+
+    fn extract_references(
+        &self,
+        content: &str,
+        _file_path: &Path,
+    ) -> Result<Vec<Reference>>
+    {
+        TsNodeUtils::call_sites(&self.language, content, &[("call_expression", "function")])
+    }
+}
+
+/// Base type name of a method receiver: `(s *Server)` and `(l List[T])`
+/// give `Server` and `List`
+fn receiver_type(
+    receiver: Node,
+    bytes: &[u8],
+) -> Option<String>
+{
+    let param = receiver.named_child(0)?;
+    let mut ty = param.child_by_field_name("type")?;
+    loop
+    {
+        match ty.kind()
+        {
+            "pointer_type" | "parenthesized_type" => ty = ty.named_child(0)?,
+            "generic_type" => ty = ty.child_by_field_name("type")?,
+            _ => break,
+        }
+    }
+    ty.utf8_text(bytes)
+        .ok()
+        .map(str::to_string)
+}
+
+/// Consecutive `//` comment lines ending right above `node`
+fn go_doc(
+    node: Node,
+    bytes: &[u8],
+) -> Option<String>
+{
+    let mut lines = Vec::new();
+    let mut row = node
+        .start_position()
+        .row;
+    let mut prev = node.prev_named_sibling();
+    while let Some(comment) = prev
+    {
+        if comment.kind() != "comment"
+            || comment
+                .end_position()
+                .row
+                + 1
+                != row
+        {
+            break;
+        }
+        let text = comment
+            .utf8_text(bytes)
+            .ok()?;
+        let Some(line) = text.strip_prefix("//")
+        else
+        {
+            break;
+        };
+        lines.push(
+            line.strip_prefix(' ')
+                .unwrap_or(line),
+        );
+        row = comment
+            .start_position()
+            .row;
+        prev = comment.prev_named_sibling();
+    }
+    lines.reverse();
+    (!lines.is_empty()).then(|| lines.join("\n"))
+}
+
+#[cfg(test)]
+mod tests
+{
+    use super::*;
+
+    fn extract(src: &str) -> Vec<Symbol>
+    {
+        GoExtractor::new()
+            .unwrap()
+            .extract_symbols(src, Path::new("server.go"))
+            .unwrap()
+    }
+
+    fn get<'a>(
+        syms: &'a [Symbol],
+        name: &str,
+    ) -> &'a Symbol
+    {
+        syms.iter()
+            .find(|s| s.name == name)
+            .expect("symbol not found")
+    }
+
+    #[test]
+    fn go_package_funcs_and_methods()
+    {
+        let src = r#"package server
+
+// Server handles requests.
+// It is safe for concurrent use.
+type Server struct {
+	addr string
+}
+
+// New builds a Server.
+func New(addr string) *Server { return &Server{addr: addr} }
+
+func (s *Server) Start() error { return nil }
+
+func (l List[T]) len() int { return 0 }
+"#;
+        let syms = extract(src);
+
+        let pkg = get(&syms, "server");
+        assert_eq!(pkg.kind, SymbolKind::Package);
+        assert_eq!(pkg.visibility, None);
+
+        let server = get(&syms, "Server");
+        assert_eq!(server.kind, SymbolKind::Struct);
+        assert_eq!(server.qualified_name, "server::Server");
+        assert_eq!(
+            server
+                .doc
+                .as_deref(),
+            Some("Server handles requests.\nIt is safe for concurrent use.")
+        );
+        assert_eq!(server.start_line, 5);
+
+        let new = get(&syms, "New");
+        assert_eq!(new.kind, SymbolKind::Function);
+        assert_eq!(new.visibility, Some(Visibility::Public));
+        assert_eq!(
+            new.doc
+                .as_deref(),
+            Some("New builds a Server.")
+        );
+
+        let start = get(&syms, "Start");
+        assert_eq!(start.kind, SymbolKind::Method);
+        assert_eq!(start.qualified_name, "server::Server::Start");
+        assert_eq!(start.doc, None);
+
+        let len = get(&syms, "len");
+        assert_eq!(len.qualified_name, "server::List::len");
+        assert_eq!(len.visibility, Some(Visibility::Private));
+    }
+
+    #[test]
+    fn go_type_groups_and_interfaces()
+    {
+        let src = r#"package shapes
+
+type (
+	Shape interface { Area() float64 }
+	point struct{ x, y int }
+	ID = string
+	Meters float64
+)
+"#;
+        let syms = extract(src);
+        let kinds: Vec<(&str, SymbolKind)> = syms
+            .iter()
+            .skip(1)
+            .map(|s| {
+                (
+                    s.name
+                        .as_str(),
+                    s.kind
+                        .clone(),
+                )
+            })
+            .collect();
+        assert_eq!(kinds, [
+            ("Shape", SymbolKind::Interface),
+            ("point", SymbolKind::Struct),
+            ("ID", SymbolKind::TypeAlias),
+            ("Meters", SymbolKind::TypeAlias),
+        ]);
+        assert_eq!(get(&syms, "point").visibility, Some(Visibility::Private));
+        assert_eq!(get(&syms, "Shape").start_line, 4);
+    }
+}
@@ -0,0 +1,312 @@
+//! Filepath: src/parsers/hcl_parser.rs
+//!
+//! Terraform/HCL extractor built on Tree-sitter 0.25.x, for infra repos.
+//!
+//! Notes:
+//!   - Top-level `resource`, `data`, `module`, `variable`, and `output` blocks, plus each
+//!     attribute of a `locals` block.
+//!   - Names are Terraform addresses, so a symbol reads the way the config refers to it:
+//!     `aws_s3_bucket.logs`, `data.aws_iam_policy_document.read`, `module.vpc`,
+//!     `var.env`, `output.bucket_arn`, `local.name`.
+//!   - A block's `description` attribute is its doc; failing that, the `#` or `//`
+//!     comment lines right above it.
+
+use std::path::Path;
+
+use anyhow::{Context, Result};
+use tree_sitter::{Language, Node};
+
+use crate::{
+    core::symbols::{Symbol, SymbolExtractor, SymbolKind},
+    infra::utils::TsNodeUtils,
+};
+
+/// Extracts Terraform symbols (resources, data sources, modules, variables).
+pub struct HclExtractor
+{
+    /// HCL language handle for Tree-sitter.
+    language: Language,
+}
+
+impl HclExtractor
+{
+    pub fn new() -> Result<Self>
+    {
+        Ok(Self { language: tree_sitter_hcl::LANGUAGE.into() })
+    }
+}
+
+impl SymbolExtractor for HclExtractor
+{
+    fn extract_symbols(
+        &self,
+        content: &str,
+        file_path: &Path,
+    ) -> Result<Vec<Symbol>>
+    {
+        let tree =
+            TsNodeUtils::parse(&self.language, content).context("Failed to parse HCL source")?;
+        let bytes = content.as_bytes();
+
+        let root = tree.root_node();
+        let mut cursor = root.walk();
+        let Some(body) = root
+            .named_children(&mut cursor)
+            .find(|n| n.kind() == "body")
+        else
+        {
+            return Ok(Vec::new());
+        };
+
+        let symbol = |kind: SymbolKind, node: Node, name: String, doc: Option<String>| {
+            let start = node.start_position();
+            let end = node.end_position();
+            Symbol {
+                file: file_path.to_path_buf(),
+                lang: "hcl".to_string(),
+                kind,
+                qualified_name: name.clone(),
+                name,
+                byte_start: node.start_byte(),
+                byte_end: node.end_byte(),
+                start_line: start.row + 1,
+                end_line: end.row + 1,
+                visibility: None,
+                doc: doc.or_else(|| hcl_doc(content, start.row)),
+                signature: None,
+                package: None,
+                module: None,
+            }
+        };
+
+        let mut out = Vec::new();
+        let mut cursor = body.walk();
+        for block in body
+            .named_children(&mut cursor)
+            .filter(|n| n.kind() == "block")
+        {
+            let Some(block_type) = block
+                .named_child(0)
+                .and_then(|n| {
+                    n.utf8_text(bytes)
+                        .ok()
+                })
+            else
+            {
+                continue;
+            };
+            let labels = block_labels(block, bytes);
+            let labels: Vec<&str> = labels
+                .iter()
+                .map(String::as_str)
+                .collect();
+            let description = attribute(block, bytes, "description").map(|d| unquote(&d));
+
+            let found = match (block_type, labels.as_slice())
+            {
+                ("resource", [ty, name]) => Some((SymbolKind::Resource, format!("{ty}.{name}"))),
+                ("data", [ty, name]) => Some((SymbolKind::Resource, format!("data.{ty}.{name}"))),
+                ("module", [name]) => Some((SymbolKind::Module, format!("module.{name}"))),
+                ("variable", [name]) => Some((SymbolKind::Variable, format!("var.{name}"))),
+                ("output", [name]) => Some((SymbolKind::Variable, format!("output.{name}"))),
+                ("locals", []) =>
+                {
+                    for (name, attr) in attributes(block, bytes)
+                    {
+                        out.push(symbol(
+                            SymbolKind::Variable,
+                            attr,
+                            format!("local.{name}"),
+                            None,
+                        ));
+                    }
+                    None
+                }
+                _ => None,
+            };
+            if let Some((kind, name)) = found
+            {
+                out.push(symbol(kind, block, name, description));
+            }
+        }
+        Ok(out)
+    }
+}
+
+/// String labels after the block type: `resource "a" "b"` gives `[a, b]`
+fn block_labels(
+    block: Node,
+    bytes: &[u8],
+) -> Vec<String>
+{
+    let mut cursor = block.walk();
+    block
+        .named_children(&mut cursor)
+        .skip(1)
+        .take_while(|n| matches!(n.kind(), "string_lit" | "identifier"))
+        .filter_map(|n| {
+            n.utf8_text(bytes)
+                .ok()
+        })
+        .map(unquote)
+        .collect()
+}
+
+/// `(name, node)` for each attribute directly in the block's body
+fn attributes<'t>(
+    block: Node<'t>,
+    bytes: &[u8],
+) -> Vec<(String, Node<'t>)>
+{
+    let mut cursor = block.walk();
+    let Some(body) = block
+        .named_children(&mut cursor)
+        .find(|n| n.kind() == "body")
+    else
+    {
+        return Vec::new();
+    };
+    let mut cursor = body.walk();
+    body.named_children(&mut cursor)
+        .filter(|n| n.kind() == "attribute")
+        .filter_map(|attr| {
+            let name = attr
+                .named_child(0)?
+                .utf8_text(bytes)
+                .ok()?;
+            Some((name.to_string(), attr))
+        })
+        .collect()
+}
+
+/// Source text of the value of attribute `name` in the block's body
+fn attribute(
+    block: Node,
+    bytes: &[u8],
+    name: &str,
+) -> Option<String>
+{
+    attributes(block, bytes)
+        .into_iter()
+        .find(|(n, _)| n == name)
+        .and_then(|(_, attr)| attr.named_child(1))
+        .and_then(|value| {
+            value
+                .utf8_text(bytes)
+                .ok()
+        })
+        .map(str::to_string)
+}
+
+fn unquote(text: &str) -> String
+{
+    text.strip_prefix('"')
+        .and_then(|t| t.strip_suffix('"'))
+        .unwrap_or(text)
+        .to_string()
+}
+
+/// Consecutive `#` or `//` comment lines ending right above `row`
+fn hcl_doc(
+    source: &str,
+    row: usize,
+) -> Option<String>
+{
+    let above: Vec<&str> = source
+        .lines()
+        .take(row)
+        .collect();
+    let mut lines: Vec<&str> = above
+        .iter()
+        .rev()
+        .map(|l| l.trim())
+        .map_while(|l| {
+            l.strip_prefix('#')
+                .or_else(|| l.strip_prefix("//"))
+        })
+        .map(str::trim)
+        .collect();
+    lines.reverse();
+    (!lines.is_empty()).then(|| lines.join("\n"))
+}
+
+#[cfg(test)]
+mod tests
+{
+    use super::*;
+
+    fn extract(src: &str) -> Vec<Symbol>
+    {
+        HclExtractor::new()
+            .unwrap()
+            .extract_symbols(src, Path::new("main.tf"))
+            .unwrap()
+    }
+
+    fn get<'a>(
+        syms: &'a [Symbol],
+        name: &str,
+    ) -> &'a Symbol
+    {
+        syms.iter()
+            .find(|s| s.name == name)
+            .expect("symbol not found")
+    }
+
+    #[test]
+    fn terraform_blocks_become_addresses()
+    {
+        let src = r#"# Log bucket.
+resource "aws_s3_bucket" "logs" {
+  bucket = "acme-logs"
+}
+
+data "aws_iam_policy_document" "read" {}
+
+module "vpc" {
+  source = "./modules/vpc"
+}
+
+variable "env" {
+  description = "Deployment environment"
+  type        = string
+}
+
+output "bucket_arn" {
+  value = aws_s3_bucket.logs.arn
+}
+
+locals {
+  name = "acme"
+}
+
+provider "aws" {}
+"#;
+        let syms = extract(src);
+        assert_eq!(syms.len(), 6);
+
+        let logs = get(&syms, "aws_s3_bucket.logs");
+        assert_eq!(logs.kind, SymbolKind::Resource);
+        assert_eq!((logs.start_line, logs.end_line), (2, 4));
+        assert_eq!(
+            logs.doc
+                .as_deref(),
+            Some("Log bucket.")
+        );
+
+        assert_eq!(
+            get(&syms, "data.aws_iam_policy_document.read").kind,
+            SymbolKind::Resource
+        );
+        assert_eq!(get(&syms, "module.vpc").kind, SymbolKind::Module);
+        let env = get(&syms, "var.env");
+        assert_eq!(env.kind, SymbolKind::Variable);
+        assert_eq!(
+            env.doc
+                .as_deref(),
+            Some("Deployment environment")
+        );
+        assert_eq!(get(&syms, "output.bucket_arn").start_line, 17);
+        assert_eq!(get(&syms, "local.name").start_line, 22);
+    }
+}
@@ -0,0 +1,320 @@
+//! Filepath: src/parsers/java_parser.rs
+//!
+//! Java symbol extractor built on Tree-sitter 0.25.x.
+//!
+//! Notes:
+//!   - Package, classes, records, interfaces, enums, annotation types, and their methods
+//!     and constructors, including nested types.
+//!   - Names are qualified as `com.example::Outer::Inner::method`.
+//!   - `public`/`private`/`protected` map directly; interface members are Public;
+//!     package-private members keep visibility unspecified.
+//!   - Annotations are part of a declaration's span; a Javadoc block right above it
+//!     becomes its doc.
+
+use std::path::Path;
+
+use anyhow::{Context, Result};
+use tree_sitter::{Language, Node};
+
+use crate::{
+    core::symbols::{
+        Reference, Relation, RelationKind, Symbol, SymbolExtractor, SymbolKind, Visibility,
+        build_qualified_name, parse_visibility,
+    },
+    infra::utils::TsNodeUtils,
+};
+
+/// Extracts Java symbols (package, types, methods, constructors).
+pub struct JavaExtractor
+{
+    /// Java language handle for Tree-sitter.
+    language: Language,
+}
+
+impl JavaExtractor
+{
+    pub fn new() -> Result<Self>
+    {
+        Ok(Self { language: tree_sitter_java::LANGUAGE.into() })
+    }
+}
+
+impl SymbolExtractor for JavaExtractor
+{
+    fn extract_symbols(
+        &self,
+        content: &str,
+        file_path: &Path,
+    ) -> Result<Vec<Symbol>>
+    {
+        let tree =
+            TsNodeUtils::parse(&self.language, content).context("Failed to parse Java source")?;
+
+        let mut walker = Walker {
+            bytes: content.as_bytes(),
+            file: file_path,
+            owners: Vec::new(),
+            out: Vec::new(),
+        };
+        walker.walk(tree.root_node(), false);
+        Ok(walker.out)
+    }
+
+    fn extract_references(
+        &self,
+        content: &str,
+        _file_path: &Path,
+    ) -> Result<Vec<Reference>>
+    {
+        TsNodeUtils::call_sites(&self.language, content, &[
+            ("method_invocation", "name"),
+            ("object_creation_expression", "type"),
+        ])
+    }
+
+    fn extract_relations(
+        &self,
+        content: &str,
+        _file_path: &Path,
+    ) -> Result<Vec<Relation>>
+    {
+        const SUPERTYPES: &[(&str, RelationKind)] = &[
+            ("superclass", RelationKind::Extends),
+            ("super_interfaces", RelationKind::Implements),
+            ("extends_interfaces", RelationKind::Extends),
+        ];
+        TsNodeUtils::relations(&self.language, content, &[
+            ("class_declaration", "name", SUPERTYPES),
+            ("interface_declaration", "name", SUPERTYPES),
+            ("enum_declaration", "name", SUPERTYPES),
+            ("record_declaration", "name", SUPERTYPES),
+        ])
+    }
+}
+
+struct Walker<'a>
+{
+    bytes: &'a [u8],
+    file: &'a Path,
+    /// Package, then enclosing type names, outermost first
+    owners: Vec<String>,
+    out: Vec<Symbol>,
+}
+
+impl Walker<'_>
+{
+    /// Visit declarations under `node`; `in_interface` makes members Public
+    fn walk(
+        &mut self,
+        node: Node,
+        in_interface: bool,
+    )
+    {
+        let mut cursor = node.walk();
+        for child in node.named_children(&mut cursor)
+        {
+            let kind = match child.kind()
+            {
+                "package_declaration" =>
+                {
+                    let mut c = child.walk();
+                    let name = child
+                        .named_children(&mut c)
+                        .find(|n| matches!(n.kind(), "scoped_identifier" | "identifier"))
+                        .and_then(|n| {
+                            n.utf8_text(self.bytes)
+                                .ok()
+                        })
+                        .map(str::to_string);
+                    if let Some(name) = name
+                    {
+                        self.push(SymbolKind::Package, child, name.clone(), None);
+                        self.owners
+                            .push(name);
+                    }
+                    continue;
+                }
+                "class_declaration" | "record_declaration" => SymbolKind::Class,
+                "interface_declaration" => SymbolKind::Interface,
+                "enum_declaration" => SymbolKind::Enum,
+                "annotation_type_declaration" => SymbolKind::Annotation,
+                "method_declaration" | "constructor_declaration" => SymbolKind::Method,
+                "enum_body_declarations" =>
+                {
+                    self.walk(child, in_interface);
+                    continue;
+                }
+                _ => continue,
+            };
+            let Some(name) = child
+                .child_by_field_name("name")
+                .and_then(|n| {
+                    n.utf8_text(self.bytes)
+                        .ok()
+                })
+                .map(str::to_string)
+            else
+            {
+                continue;
+            };
+
+            let visibility =
+                modifier_visibility(child).or(in_interface.then_some(Visibility::Public));
+            self.push(kind.clone(), child, name.clone(), visibility);
+            if kind != SymbolKind::Method
+                && let Some(body) = child.child_by_field_name("body")
+            {
+                self.owners
+                    .push(name);
+                self.walk(
+                    body,
+                    matches!(kind, SymbolKind::Interface | SymbolKind::Annotation),
+                );
+                self.owners
+                    .pop();
+            }
+        }
+    }
+
+    fn push(
+        &mut self,
+        kind: SymbolKind,
+        node: Node,
+        name: String,
+        visibility: Option<Visibility>,
+    )
+    {
+        let qualified_name = if kind == SymbolKind::Package
+        {
+            name.clone()
+        }
+        else
+        {
+            let mut parts: Vec<&str> = self
+                .owners
+                .iter()
+                .map(String::as_str)
+                .collect();
+            parts.push(&name);
+            build_qualified_name(&parts)
+        };
+        let start = node.start_position();
+        let end = node.end_position();
+        self.out
+            .push(Symbol {
+                file: self
+                    .file
+                    .to_path_buf(),
+                lang: "java".to_string(),
+                kind,
+                name,
+                qualified_name,
+                byte_start: node.start_byte(),
+                byte_end: node.end_byte(),
+                start_line: start.row + 1,
+                end_line: end.row + 1,
+                visibility,
+                doc: TsNodeUtils::doc_block(node, self.bytes),
+                signature: None,
+                package: None,
+                module: None,
+            });
+    }
+}
+
+/// `public`/`private`/`protected` among the declaration's modifiers
+fn modifier_visibility(node: Node) -> Option<Visibility>
+{
+    let mut cursor = node.walk();
+    let modifiers = node
+        .named_children(&mut cursor)
+        .find(|c| c.kind() == "modifiers")?;
+    let mut cursor = modifiers.walk();
+    modifiers
+        .children(&mut cursor)
+        .find_map(|m| parse_visibility(m.kind()))
+}
+
+#[cfg(test)]
+mod tests
+{
+    use super::*;
+
+    fn extract(src: &str) -> Vec<Symbol>
+    {
+        JavaExtractor::new()
+            .unwrap()
+            .extract_symbols(src, Path::new("Widget.java"))
+            .unwrap()
+    }
+
+    fn get<'a>(
+        syms: &'a [Symbol],
+        name: &str,
+    ) -> &'a Symbol
+    {
+        syms.iter()
+            .find(|s| s.name == name)
+            .expect("symbol not found")
+    }
+
+    #[test]
+    fn java_types_methods_and_visibility()
+    {
+        let src = r#"package com.example;
+
+/** A widget. */
+@Deprecated
+public class Widget {
+    public String render() { return ""; }
+    private void hidden() {}
+    void local() {}
+    static class Inner { protected void m() {} }
+}
+interface Shape { double area(); }
+@interface Marker {}
+"#;
+        let syms = extract(src);
+
+        let pkg = get(&syms, "com.example");
+        assert_eq!(pkg.kind, SymbolKind::Package);
+
+        let widget = get(&syms, "Widget");
+        assert_eq!(widget.kind, SymbolKind::Class);
+        assert_eq!(widget.qualified_name, "com.example::Widget");
+        assert_eq!(widget.visibility, Some(Visibility::Public));
+        assert_eq!(
+            widget
+                .doc
+                .as_deref(),
+            Some("A widget.")
+        );
+        assert_eq!(widget.start_line, 4);
+
+        assert_eq!(
+            get(&syms, "render").qualified_name,
+            "com.example::Widget::render"
+        );
+        assert_eq!(get(&syms, "hidden").visibility, Some(Visibility::Private));
+        assert_eq!(get(&syms, "local").visibility, None);
+        let m = get(&syms, "m");
+        assert_eq!(m.qualified_name, "com.example::Widget::Inner::m");
+        assert_eq!(m.visibility, Some(Visibility::Protected));
+
+        assert_eq!(get(&syms, "Shape").kind, SymbolKind::Interface);
+        assert_eq!(get(&syms, "area").visibility, Some(Visibility::Public));
+        assert_eq!(get(&syms, "Marker").kind, SymbolKind::Annotation);
+    }
+
+    #[test]
+    fn java_enum_methods_and_records()
+    {
+        let src = "enum Color { RED; void x() {} }\nrecord Point(int x, int y) { int sum() { \
+                   return x + y; } }\n";
+        let syms = extract(src);
+        assert_eq!(get(&syms, "Color").kind, SymbolKind::Enum);
+        assert_eq!(get(&syms, "x").qualified_name, "Color::x");
+        assert_eq!(get(&syms, "Point").kind, SymbolKind::Class);
+        assert_eq!(get(&syms, "sum").qualified_name, "Point::sum");
+    }
+}
@@ -0,0 +1,396 @@
+//! Filepath: src/parsers/javascript_parser.rs
+//!
+//! JavaScript symbol extractor built on Tree-sitter 0.25.x, plus the tree
+//! walk it shares with the TypeScript extractor.
+//!
+//! Notes:
+//!   - Functions, classes, methods, and `const f = () => ...` style function consts at
+//!     module level; TypeScript adds interfaces, type aliases, enums, and namespaces.
+//!   - Exported declarations span their `export` statement and are Public; other
+//!     top-level items keep visibility unspecified (module-local).
+//!   - `#private` members and TS `private`/`protected` modifiers map to
+//!     Private/Protected.
+//!   - A `/** ... */` block right above a declaration becomes its doc.
+
+use std::path::Path;
+
+use anyhow::{Context, Result};
+use tree_sitter::{Language, Node};
+
+use crate::{
+    core::symbols::{
+        Reference, Relation, RelationKind, Symbol, SymbolExtractor, SymbolKind, Visibility,
+        build_qualified_name, parse_visibility,
+    },
+    infra::utils::{RelationRule, TsNodeUtils},
+};
+
+/// Extracts JavaScript symbols (functions, classes, methods, function consts).
+pub struct JavaScriptExtractor
+{
+    /// JavaScript (and JSX) language handle for Tree-sitter.
+    language: Language,
+}
+
+impl JavaScriptExtractor
+{
+    pub fn new() -> Result<Self>
+    {
+        Ok(Self { language: tree_sitter_javascript::LANGUAGE.into() })
+    }
+}
+
+impl SymbolExtractor for JavaScriptExtractor
+{
+    fn extract_symbols(
+        &self,
+        content: &str,
+        file_path: &Path,
+    ) -> Result<Vec<Symbol>>
+    {
+        extract_ecma_symbols(&self.language, "javascript", content, file_path)
+    }
+
+    fn extract_references(
+        &self,
+        content: &str,
+        _file_path: &Path,
+    ) -> Result<Vec<Reference>>
+    {
+        TsNodeUtils::call_sites(&self.language, content, ECMA_CALLS)
+    }
+
+    fn extract_relations(
+        &self,
+        content: &str,
+        _file_path: &Path,
+    ) -> Result<Vec<Relation>>
+    {
+        TsNodeUtils::relations(&self.language, content, ECMA_RELATIONS)
+    }
+}
+
+/// Call and `new` nodes with the field naming their callee; shared by the
+/// JavaScript and TypeScript extractors
+pub(crate) const ECMA_CALLS: &[(&str, &str)] =
+    &[("call_expression", "function"), ("new_expression", "constructor")];
+
+/// Class and interface declarations with the clauses naming their
+/// supertypes; TypeScript's `implements` sits inside `class_heritage`
+const ECMA_HERITAGE: &[(&str, RelationKind)] = &[
+    ("class_heritage", RelationKind::Extends),
+    ("extends_clause", RelationKind::Extends),
+    ("implements_clause", RelationKind::Implements),
+    ("extends_type_clause", RelationKind::Extends),
+];
+
+/// Relation rules shared by the JavaScript and TypeScript extractors
+pub(crate) const ECMA_RELATIONS: &[RelationRule] = &[
+    ("class_declaration", "name", ECMA_HERITAGE),
+    ("abstract_class_declaration", "name", ECMA_HERITAGE),
+    ("class", "name", ECMA_HERITAGE),
+    ("interface_declaration", "name", ECMA_HERITAGE),
+];
+
+/// Parse `content` with `language` and collect declarations; shared by the
+/// JavaScript and TypeScript extractors
+pub(crate) fn extract_ecma_symbols(
+    language: &Language,
+    lang: &str,
+    content: &str,
+    file_path: &Path,
+) -> Result<Vec<Symbol>>
+{
+    let tree = TsNodeUtils::parse(language, content)
+        .with_context(|| format!("Failed to parse {lang} source"))?;
+
+    let mut walker = Walker {
+        bytes: content.as_bytes(),
+        file: file_path,
+        lang,
+        out: Vec::new(),
+    };
+    walker.walk(tree.root_node(), None);
+    Ok(walker.out)
+}
+
+struct Walker<'a>
+{
+    bytes: &'a [u8],
+    file: &'a Path,
+    lang: &'a str,
+    out: Vec<Symbol>,
+}
+
+impl Walker<'_>
+{
+    /// Visit the named children of `node`; `owner` is the enclosing class
+    fn walk(
+        &mut self,
+        node: Node,
+        owner: Option<&str>,
+    )
+    {
+        let mut cursor = node.walk();
+        for child in node.named_children(&mut cursor)
+        {
+            match child.kind()
+            {
+                "function_declaration" | "generator_function_declaration" =>
+                {
+                    self.push(SymbolKind::Function, child, None);
+                    self.walk(child, None);
+                }
+                "class_declaration" | "abstract_class_declaration" =>
+                {
+                    let name = self.push(SymbolKind::Class, child, None);
+                    if let Some(body) = child.child_by_field_name("body")
+                    {
+                        self.walk(body, name.as_deref());
+                    }
+                }
+                "method_definition" | "abstract_method_signature" if owner.is_some() =>
+                {
+                    self.push(SymbolKind::Method, child, owner);
+                    self.walk(child, None);
+                }
+                "interface_declaration" =>
+                {
+                    self.push(SymbolKind::Interface, child, None);
+                }
+                "type_alias_declaration" =>
+                {
+                    self.push(SymbolKind::TypeAlias, child, None);
+                }
+                "enum_declaration" =>
+                {
+                    self.push(SymbolKind::Enum, child, None);
+                }
+                "internal_module" | "module" =>
+                {
+                    self.push(SymbolKind::Module, child, None);
+                    self.walk(child, None);
+                }
+                "lexical_declaration" | "variable_declaration"
+                    if matches!(node.kind(), "program" | "export_statement") =>
+                {
+                    self.push_function_consts(child);
+                }
+                _ => self.walk(child, owner),
+            }
+        }
+    }
+
+    /// `const f = () => ...` and `const f = function () ...` declarators
+    fn push_function_consts(
+        &mut self,
+        decl: Node,
+    )
+    {
+        let mut cursor = decl.walk();
+        for declarator in decl.named_children(&mut cursor)
+        {
+            let is_function = declarator
+                .child_by_field_name("value")
+                .is_some_and(|v| {
+                    matches!(
+                        v.kind(),
+                        "arrow_function"
+                            | "function_expression"
+                            | "function"
+                            | "generator_function"
+                    )
+                });
+            if declarator.kind() != "variable_declarator" || !is_function
+            {
+                continue;
+            }
+            let Some(name) = declarator
+                .child_by_field_name("name")
+                .filter(|n| n.kind() == "identifier")
+                .and_then(|n| {
+                    n.utf8_text(self.bytes)
+                        .ok()
+                })
+            else
+            {
+                continue;
+            };
+            let name = name.to_string();
+            self.push_named(SymbolKind::Function, decl, name, None);
+        }
+    }
+
+    /// Record `node` under its `name` field; returns the name
+    fn push(
+        &mut self,
+        kind: SymbolKind,
+        node: Node,
+        owner: Option<&str>,
+    ) -> Option<String>
+    {
+        let name = node
+            .child_by_field_name("name")?
+            .utf8_text(self.bytes)
+            .ok()?
+            .to_string();
+        self.push_named(kind, node, name.clone(), owner);
+        Some(name)
+    }
+
+    fn push_named(
+        &mut self,
+        kind: SymbolKind,
+        node: Node,
+        name: String,
+        owner: Option<&str>,
+    )
+    {
+        // Exported items span the whole `export ...` statement
+        let exported = node
+            .parent()
+            .filter(|p| p.kind() == "export_statement");
+        let span = exported.unwrap_or(node);
+
+        let visibility = if exported.is_some()
+        {
+            Some(Visibility::Public)
+        }
+        else if name.starts_with('#')
+        {
+            Some(Visibility::Private)
+        }
+        else
+        {
+            member_visibility(node, self.bytes)
+        };
+        let qualified_name = match owner
+        {
+            Some(owner) => build_qualified_name(&[owner, &name]),
+            None => name.clone(),
+        };
+        let start = span.start_position();
+        let end = span.end_position();
+
+        self.out
+            .push(Symbol {
+                file: self
+                    .file
+                    .to_path_buf(),
+                lang: self
+                    .lang
+                    .to_string(),
+                kind,
+                name,
+                qualified_name,
+                byte_start: span.start_byte(),
+                byte_end: span.end_byte(),
+                start_line: start.row + 1,
+                end_line: end.row + 1,
+                visibility,
+                doc: TsNodeUtils::doc_block(span, self.bytes),
+                signature: None,
+                package: None,
+                module: None,
+            });
+    }
+}
+
+/// TypeScript `public`/`private`/`protected` on a class member
+fn member_visibility(
+    node: Node,
+    bytes: &[u8],
+) -> Option<Visibility>
+{
+    let mut cursor = node.walk();
+    node.named_children(&mut cursor)
+        .find(|c| c.kind() == "accessibility_modifier")
+        .and_then(|c| {
+            c.utf8_text(bytes)
+                .ok()
+        })
+        .and_then(parse_visibility)
+}
+
+#[cfg(test)]
+mod tests
+{
+    use super::*;
+
+    fn extract(src: &str) -> Vec<Symbol>
+    {
+        JavaScriptExtractor::new()
+            .unwrap()
+            .extract_symbols(src, Path::new("m.js"))
+            .unwrap()
+    }
+
+    fn get<'a>(
+        syms: &'a [Symbol],
+        name: &str,
+    ) -> &'a Symbol
+    {
+        syms.iter()
+            .find(|s| s.name == name)
+            .expect("symbol not found")
+    }
+
+    #[test]
+    fn functions_classes_and_methods()
+    {
+        let src = r#"
+/** Adds things. */
+export function add(a, b) { return a + b; }
+
+function local() {}
+
+class Shape {
+  area() { return 0; }
+  #secret() {}
+}
+"#;
+        let syms = extract(src);
+
+        let add = get(&syms, "add");
+        assert_eq!(add.kind, SymbolKind::Function);
+        assert_eq!(add.visibility, Some(Visibility::Public));
+        assert_eq!(
+            add.doc
+                .as_deref(),
+            Some("Adds things.")
+        );
+        assert_eq!(add.start_line, 3);
+
+        assert_eq!(get(&syms, "local").visibility, None);
+        assert_eq!(get(&syms, "Shape").kind, SymbolKind::Class);
+        let area = get(&syms, "area");
+        assert_eq!(area.kind, SymbolKind::Method);
+        assert_eq!(area.qualified_name, "Shape::area");
+        assert_eq!(get(&syms, "#secret").visibility, Some(Visibility::Private));
+    }
+
+    #[test]
+    fn arrow_function_consts_at_module_level_only()
+    {
+        let src = r#"
+export const double = (x) => x * 2;
+const triple = function (x) { return x * 3; };
+const answer = 42;
+function outer() {
+  const inner = () => 1;
+}
+"#;
+        let syms = extract(src);
+        let names: Vec<&str> = syms
+            .iter()
+            .map(|s| {
+                s.name
+                    .as_str()
+            })
+            .collect();
+        assert_eq!(names, ["double", "triple", "outer"]);
+        let double = get(&syms, "double");
+        assert_eq!(double.visibility, Some(Visibility::Public));
+        assert!(src[double.byte_start..double.byte_end].starts_with("export const"));
+    }
+}
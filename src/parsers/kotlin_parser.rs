@@ -0,0 +1,353 @@
+//! Filepath: src/parsers/kotlin_parser.rs
+//!
+//! Kotlin symbol extractor built on Tree-sitter 0.25.x
+//! (tree-sitter-kotlin-ng grammar).
+//!
+//! Notes:
+//!   - Package, classes (data/sealed/enum/annotation), interfaces, objects, companion
+//!     objects, and functions; functions inside a type are Methods.
+//!   - Names are qualified as `com.example::Widget::Companion::create`; an unnamed
+//!     companion object is `Companion`, as in Kotlin itself.
+//!   - Visibility defaults to Public; `internal` maps to Internal.
+//!   - Annotations are part of a declaration's span; a KDoc block right above it becomes
+//!     its doc.
+
+use std::path::Path;
+
+use anyhow::{Context, Result};
+use tree_sitter::{Language, Node};
+
+use crate::{
+    core::symbols::{
+        Symbol, SymbolExtractor, SymbolKind, Visibility, build_qualified_name, parse_visibility,
+    },
+    infra::utils::TsNodeUtils,
+};
+
+/// Extracts Kotlin symbols (package, classes, objects, functions).
+pub struct KotlinExtractor
+{
+    /// Kotlin language handle for Tree-sitter.
+    language: Language,
+}
+
+impl KotlinExtractor
+{
+    pub fn new() -> Result<Self>
+    {
+        Ok(Self { language: tree_sitter_kotlin_ng::LANGUAGE.into() })
+    }
+}
+
+impl SymbolExtractor for KotlinExtractor
+{
+    fn extract_symbols(
+        &self,
+        content: &str,
+        file_path: &Path,
+    ) -> Result<Vec<Symbol>>
+    {
+        let tree =
+            TsNodeUtils::parse(&self.language, content).context("Failed to parse Kotlin source")?;
+
+        let mut walker = Walker {
+            bytes: content.as_bytes(),
+            file: file_path,
+            package: None,
+            owners: Vec::new(),
+            out: Vec::new(),
+        };
+        walker.walk(tree.root_node());
+        Ok(walker.out)
+    }
+}
+
+struct Walker<'a>
+{
+    bytes: &'a [u8],
+    file: &'a Path,
+    package: Option<String>,
+    /// Enclosing classes and objects, outermost first
+    owners: Vec<String>,
+    out: Vec<Symbol>,
+}
+
+impl Walker<'_>
+{
+    fn walk(
+        &mut self,
+        node: Node,
+    )
+    {
+        let mut cursor = node.walk();
+        for child in node.named_children(&mut cursor)
+        {
+            let kind = match child.kind()
+            {
+                "package_header" =>
+                {
+                    let mut c = child.walk();
+                    let name = child
+                        .named_children(&mut c)
+                        .find(|n| matches!(n.kind(), "qualified_identifier" | "identifier"))
+                        .and_then(|n| {
+                            n.utf8_text(self.bytes)
+                                .ok()
+                        })
+                        .map(str::to_string);
+                    if let Some(name) = name
+                    {
+                        self.push(SymbolKind::Package, child, name.clone());
+                        self.package = Some(name);
+                    }
+                    continue;
+                }
+                "class_declaration" => class_kind(child, self.bytes),
+                "object_declaration" | "companion_object" => SymbolKind::Class,
+                "function_declaration"
+                    if self
+                        .owners
+                        .is_empty() =>
+                {
+                    SymbolKind::Function
+                }
+                "function_declaration" => SymbolKind::Method,
+                _ => continue,
+            };
+            let name = child
+                .child_by_field_name("name")
+                .and_then(|n| {
+                    n.utf8_text(self.bytes)
+                        .ok()
+                })
+                .map(str::to_string)
+                .or_else(|| (child.kind() == "companion_object").then(|| "Companion".to_string()));
+            let Some(name) = name
+            else
+            {
+                continue;
+            };
+
+            self.push(kind.clone(), child, name.clone());
+            if !matches!(kind, SymbolKind::Function | SymbolKind::Method)
+            {
+                let mut c = child.walk();
+                let body = child
+                    .named_children(&mut c)
+                    .find(|n| matches!(n.kind(), "class_body" | "enum_class_body"));
+                if let Some(body) = body
+                {
+                    self.owners
+                        .push(name);
+                    self.walk(body);
+                    self.owners
+                        .pop();
+                }
+            }
+        }
+    }
+
+    fn push(
+        &mut self,
+        kind: SymbolKind,
+        node: Node,
+        name: String,
+    )
+    {
+        let (qualified_name, visibility) = if kind == SymbolKind::Package
+        {
+            (name.clone(), None)
+        }
+        else
+        {
+            let mut parts: Vec<&str> = self
+                .package
+                .as_deref()
+                .into_iter()
+                .chain(
+                    self.owners
+                        .iter()
+                        .map(String::as_str),
+                )
+                .collect();
+            parts.push(&name);
+            (
+                build_qualified_name(&parts),
+                Some(kotlin_visibility(node, self.bytes)),
+            )
+        };
+        let start = node.start_position();
+        let end = node.end_position();
+        self.out
+            .push(Symbol {
+                file: self
+                    .file
+                    .to_path_buf(),
+                lang: "kotlin".to_string(),
+                kind,
+                name,
+                qualified_name,
+                byte_start: node.start_byte(),
+                byte_end: node.end_byte(),
+                start_line: start.row + 1,
+                end_line: end.row + 1,
+                visibility,
+                doc: TsNodeUtils::doc_block(node, self.bytes),
+                signature: None,
+                package: None,
+                module: None,
+            });
+    }
+}
+
+/// `interface`, `enum class`, `annotation class`, or any other class
+fn class_kind(
+    node: Node,
+    bytes: &[u8],
+) -> SymbolKind
+{
+    let mut cursor = node.walk();
+    if node
+        .children(&mut cursor)
+        .any(|c| c.kind() == "interface")
+    {
+        return SymbolKind::Interface;
+    }
+    match modifier_texts(node, bytes, "class_modifier").as_slice()
+    {
+        m if m.contains(&"enum") => SymbolKind::Enum,
+        m if m.contains(&"annotation") => SymbolKind::Annotation,
+        _ => SymbolKind::Class,
+    }
+}
+
+/// Declared visibility, Kotlin's default being public
+fn kotlin_visibility(
+    node: Node,
+    bytes: &[u8],
+) -> Visibility
+{
+    match modifier_texts(node, bytes, "visibility_modifier").first()
+    {
+        Some(&"internal") => Visibility::Internal,
+        Some(text) => parse_visibility(text).unwrap_or(Visibility::Public),
+        None => Visibility::Public,
+    }
+}
+
+/// Texts of the `kind` modifiers in the declaration's modifier list
+fn modifier_texts<'b>(
+    node: Node,
+    bytes: &'b [u8],
+    kind: &str,
+) -> Vec<&'b str>
+{
+    let mut cursor = node.walk();
+    let Some(modifiers) = node
+        .named_children(&mut cursor)
+        .find(|c| c.kind() == "modifiers")
+    else
+    {
+        return Vec::new();
+    };
+    let mut cursor = modifiers.walk();
+    modifiers
+        .named_children(&mut cursor)
+        .filter(|m| m.kind() == kind)
+        .filter_map(|m| {
+            m.utf8_text(bytes)
+                .ok()
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests
+{
+    use super::*;
+
+    fn extract(src: &str) -> Vec<Symbol>
+    {
+        KotlinExtractor::new()
+            .unwrap()
+            .extract_symbols(src, Path::new("Widget.kt"))
+            .unwrap()
+    }
+
+    fn get<'a>(
+        syms: &'a [Symbol],
+        name: &str,
+    ) -> &'a Symbol
+    {
+        syms.iter()
+            .find(|s| s.name == name)
+            .expect("symbol not found")
+    }
+
+    #[test]
+    fn kotlin_classes_companions_and_functions()
+    {
+        let src = r#"package com.example.app
+
+/** A widget. */
+@Serializable
+data class Widget(val id: String) {
+    fun render(): String = id
+    private fun hidden() {}
+    companion object {
+        fun create(): Widget = Widget("x")
+    }
+}
+
+fun topLevel() {}
+"#;
+        let syms = extract(src);
+
+        assert_eq!(get(&syms, "com.example.app").kind, SymbolKind::Package);
+        let widget = get(&syms, "Widget");
+        assert_eq!(widget.kind, SymbolKind::Class);
+        assert_eq!(widget.qualified_name, "com.example.app::Widget");
+        assert_eq!(
+            widget
+                .doc
+                .as_deref(),
+            Some("A widget.")
+        );
+        assert_eq!(widget.start_line, 4);
+
+        let render = get(&syms, "render");
+        assert_eq!(render.kind, SymbolKind::Method);
+        assert_eq!(render.visibility, Some(Visibility::Public));
+        assert_eq!(get(&syms, "hidden").visibility, Some(Visibility::Private));
+
+        assert_eq!(get(&syms, "Companion").kind, SymbolKind::Class);
+        assert_eq!(
+            get(&syms, "create").qualified_name,
+            "com.example.app::Widget::Companion::create"
+        );
+        assert_eq!(get(&syms, "topLevel").kind, SymbolKind::Function);
+    }
+
+    #[test]
+    fn kotlin_interfaces_objects_enums_and_annotations()
+    {
+        let src = r#"interface Shape {
+    fun area(): Double
+}
+object Registry {
+    internal fun add() {}
+}
+enum class Color {
+    RED
+}
+annotation class Marker
+"#;
+        let syms = extract(src);
+        assert_eq!(get(&syms, "Shape").kind, SymbolKind::Interface);
+        assert_eq!(get(&syms, "area").qualified_name, "Shape::area");
+        assert_eq!(get(&syms, "Registry").kind, SymbolKind::Class);
+        assert_eq!(get(&syms, "add").visibility, Some(Visibility::Internal));
+        assert_eq!(get(&syms, "Color").kind, SymbolKind::Enum);
+        assert_eq!(get(&syms, "Marker").kind, SymbolKind::Annotation);
+    }
+}
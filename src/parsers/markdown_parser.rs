@@ -0,0 +1,553 @@
+//! Filepath: src/parsers/markdown_parser.rs
+//!
+//! Markdown structure extractor built on Tree-sitter 0.25.x
+//! (tree-sitter-md block grammar).
+//!
+//! Notes:
+//!   - Headings (ATX and setext), fenced code blocks, and link reference definitions
+//!     become symbols so docs index and budget like code.
+//!   - A heading spans its whole section: everything up to the next heading of the same
+//!     or a higher level, minus trailing blank lines.
+//!   - Names are qualified by the enclosing headings (`Roughup::Installation::From
+//!     source`); a code block is named after its info-string language.
+//!   - A heading's doc is the first line of its first paragraph; a link definition's doc
+//!     is its destination.
+//!   - `find_section` resolves `#installation` style anchors (GitHub slugs) for `rup
+//!     extract README.md:#installation`.
+
+use std::path::Path;
+
+use anyhow::{Context, Result};
+use tree_sitter::{Language, Node};
+
+use crate::{
+    core::symbols::{Symbol, SymbolExtractor, SymbolKind},
+    infra::utils::TsNodeUtils,
+};
+
+/// Extracts Markdown structure (headings, code blocks, link definitions).
+pub struct MarkdownExtractor
+{
+    /// Markdown block-grammar handle for Tree-sitter.
+    language: Language,
+}
+
+impl MarkdownExtractor
+{
+    pub fn new() -> Result<Self>
+    {
+        Ok(Self { language: tree_sitter_md::LANGUAGE.into() })
+    }
+}
+
+impl SymbolExtractor for MarkdownExtractor
+{
+    fn extract_symbols(
+        &self,
+        content: &str,
+        file_path: &Path,
+    ) -> Result<Vec<Symbol>>
+    {
+        let tree = TsNodeUtils::parse(&self.language, content)
+            .context("Failed to parse Markdown source")?;
+
+        let mut items = Vec::new();
+        collect(tree.root_node(), content.as_bytes(), &mut items);
+        Ok(build_symbols(items, content, file_path))
+    }
+}
+
+/// Line range of the section whose heading matches `anchor`: a GitHub-style
+/// slug (`installation`, with or without `#`) or the heading text itself
+pub fn find_section(
+    content: &str,
+    anchor: &str,
+) -> Result<Option<(usize, usize)>>
+{
+    let wanted = anchor
+        .trim()
+        .trim_start_matches('#');
+    let symbols = MarkdownExtractor::new()?.extract_symbols(content, Path::new(""))?;
+    let found = symbols
+        .iter()
+        .filter(|s| s.kind == SymbolKind::Heading)
+        .find(|s| {
+            slugify(&s.name) == wanted
+                || s.name
+                    .eq_ignore_ascii_case(wanted)
+        })
+        .map(|s| (s.start_line, s.end_line));
+    Ok(found)
+}
+
+/// GitHub-style heading slug: lowercase, punctuation dropped, spaces to `-`
+pub fn slugify(heading: &str) -> String
+{
+    heading
+        .trim()
+        .to_lowercase()
+        .chars()
+        .filter_map(|c| {
+            match c
+            {
+                ' ' => Some('-'),
+                c if c.is_alphanumeric() || c == '-' || c == '_' => Some(c),
+                _ => None,
+            }
+        })
+        .collect()
+}
+
+/// A structural element found in the tree, before section spans are known
+enum Item
+{
+    Heading
+    {
+        level: usize,
+        text: String,
+        start_line: usize,
+        byte_start: usize,
+        doc: Option<String>,
+    },
+    Block
+    {
+        kind: SymbolKind,
+        name: String,
+        doc: Option<String>,
+        start_line: usize,
+        end_line: usize,
+        byte_start: usize,
+        byte_end: usize,
+    },
+}
+
+impl Item
+{
+    fn start_line(&self) -> usize
+    {
+        match self
+        {
+            Item::Heading { start_line, .. } | Item::Block { start_line, .. } => *start_line,
+        }
+    }
+}
+
+fn collect(
+    node: Node,
+    bytes: &[u8],
+    out: &mut Vec<Item>,
+)
+{
+    let text = |n: Node| {
+        n.utf8_text(bytes)
+            .ok()
+            .map(str::trim)
+            .unwrap_or_default()
+            .to_string()
+    };
+    let mut cursor = node.walk();
+    for child in node.named_children(&mut cursor)
+    {
+        match child.kind()
+        {
+            "atx_heading" | "setext_heading" =>
+            {
+                let Some(content) = child.child_by_field_name("heading_content")
+                else
+                {
+                    continue;
+                };
+                let heading = text(content)
+                    .trim_end_matches('#')
+                    .trim_end()
+                    .to_string();
+                out.push(Item::Heading {
+                    level: heading_level(child),
+                    text: heading,
+                    start_line: child
+                        .start_position()
+                        .row
+                        + 1,
+                    byte_start: child.start_byte(),
+                    doc: first_paragraph(child, bytes),
+                });
+            }
+            "fenced_code_block" =>
+            {
+                let mut c = child.walk();
+                let lang = child
+                    .named_children(&mut c)
+                    .find(|n| n.kind() == "info_string")
+                    .map(|info| {
+                        text(info)
+                            .split_whitespace()
+                            .next()
+                            .unwrap_or_default()
+                            .to_string()
+                    })
+                    .filter(|l| !l.is_empty());
+                out.push(block(
+                    child,
+                    SymbolKind::CodeBlock,
+                    lang.unwrap_or_else(|| "code".into()),
+                    None,
+                ));
+            }
+            "link_reference_definition" =>
+            {
+                let mut c = child.walk();
+                let parts: Vec<Node> = child
+                    .named_children(&mut c)
+                    .collect();
+                let label = parts
+                    .iter()
+                    .find(|n| n.kind() == "link_label")
+                    .map(|n| {
+                        text(*n)
+                            .trim_matches(|c| c == '[' || c == ']')
+                            .to_string()
+                    });
+                let destination = parts
+                    .iter()
+                    .find(|n| n.kind() == "link_destination")
+                    .map(|n| text(*n));
+                if let Some(label) = label
+                {
+                    out.push(block(child, SymbolKind::LinkDefinition, label, destination));
+                }
+            }
+            _ => collect(child, bytes, out),
+        }
+    }
+}
+
+fn block(
+    node: Node,
+    kind: SymbolKind,
+    name: String,
+    doc: Option<String>,
+) -> Item
+{
+    Item::Block {
+        kind,
+        name,
+        doc,
+        start_line: node
+            .start_position()
+            .row
+            + 1,
+        end_line: last_line(node),
+        byte_start: node.start_byte(),
+        byte_end: node.end_byte(),
+    }
+}
+
+/// 1-based last line of `node`, not counting a trailing newline
+fn last_line(node: Node) -> usize
+{
+    let start = node.start_position();
+    let end = node.end_position();
+    if end.column == 0 && end.row > start.row
+    {
+        end.row
+    }
+    else
+    {
+        end.row + 1
+    }
+}
+
+/// `#`..`######` for ATX headings; `=` and `-` underlines are 1 and 2
+fn heading_level(node: Node) -> usize
+{
+    let mut cursor = node.walk();
+    node.named_children(&mut cursor)
+        .find_map(|c| {
+            match c.kind()
+            {
+                "setext_h1_underline" => Some(1),
+                "setext_h2_underline" => Some(2),
+                k =>
+                {
+                    k.strip_prefix("atx_h")
+                        .and_then(|k| k.strip_suffix("_marker"))
+                        .and_then(|n| {
+                            n.parse()
+                                .ok()
+                        })
+                }
+            }
+        })
+        .unwrap_or(1)
+}
+
+/// First line of the paragraph right after a heading
+fn first_paragraph(
+    heading: Node,
+    bytes: &[u8],
+) -> Option<String>
+{
+    let next = heading.next_named_sibling()?;
+    if next.kind() != "paragraph"
+    {
+        return None;
+    }
+    next.utf8_text(bytes)
+        .ok()?
+        .lines()
+        .map(str::trim)
+        .find(|l| !l.is_empty())
+        .map(str::to_string)
+}
+
+/// Turn collected items into symbols, giving each heading its section span
+/// and qualifying everything by the headings above it
+fn build_symbols(
+    mut items: Vec<Item>,
+    content: &str,
+    file_path: &Path,
+) -> Vec<Symbol>
+{
+    items.sort_by_key(Item::start_line);
+    let lines: Vec<&str> = content
+        .lines()
+        .collect();
+    // Byte offset just past each line, newline included
+    let mut line_ends = Vec::with_capacity(lines.len());
+    let mut offset = 0;
+    for line in content.split_inclusive('\n')
+    {
+        offset += line.len();
+        line_ends.push(offset);
+    }
+
+    let headings: Vec<(usize, usize)> = items
+        .iter()
+        .filter_map(|i| {
+            match i
+            {
+                Item::Heading { level, start_line, .. } => Some((*level, *start_line)),
+                Item::Block { .. } => None,
+            }
+        })
+        .collect();
+
+    let mut out = Vec::with_capacity(items.len());
+    // Enclosing headings as (level, name), outermost first
+    let mut path: Vec<(usize, String)> = Vec::new();
+    for item in items
+    {
+        match item
+        {
+            Item::Heading { level, text, start_line, byte_start, doc } =>
+            {
+                let next = headings
+                    .iter()
+                    .find(|(l, s)| *s > start_line && *l <= level)
+                    .map_or(lines.len(), |(_, s)| s - 1);
+                let mut end_line = next.max(start_line);
+                while end_line > start_line
+                    && lines
+                        .get(end_line - 1)
+                        .is_some_and(|l| {
+                            l.trim()
+                                .is_empty()
+                        })
+                {
+                    end_line -= 1;
+                }
+
+                while path
+                    .last()
+                    .is_some_and(|(l, _)| *l >= level)
+                {
+                    path.pop();
+                }
+                path.push((level, text.clone()));
+                let qualified_name = qualify(&path, None);
+                out.push(symbol(
+                    file_path,
+                    SymbolKind::Heading,
+                    text,
+                    qualified_name,
+                    (start_line, end_line),
+                    (
+                        byte_start,
+                        line_ends
+                            .get(end_line - 1)
+                            .copied()
+                            .unwrap_or(content.len()),
+                    ),
+                    doc,
+                ));
+            }
+            Item::Block {
+                kind,
+                name,
+                doc,
+                start_line,
+                end_line,
+                byte_start,
+                byte_end,
+            } =>
+            {
+                let qualified_name = qualify(&path, Some(&name));
+                out.push(symbol(
+                    file_path,
+                    kind,
+                    name,
+                    qualified_name,
+                    (start_line, end_line),
+                    (byte_start, byte_end),
+                    doc,
+                ));
+            }
+        }
+    }
+    out
+}
+
+fn qualify(
+    path: &[(usize, String)],
+    leaf: Option<&str>,
+) -> String
+{
+    let parts: Vec<&str> = path
+        .iter()
+        .map(|(_, name)| name.as_str())
+        .chain(leaf)
+        .collect();
+    crate::core::symbols::build_qualified_name(&parts)
+}
+
+fn symbol(
+    file_path: &Path,
+    kind: SymbolKind,
+    name: String,
+    qualified_name: String,
+    (start_line, end_line): (usize, usize),
+    (byte_start, byte_end): (usize, usize),
+    doc: Option<String>,
+) -> Symbol
+{
+    Symbol {
+        file: file_path.to_path_buf(),
+        lang: "markdown".to_string(),
+        kind,
+        name,
+        qualified_name,
+        byte_start,
+        byte_end,
+        start_line,
+        end_line,
+        visibility: None,
+        doc,
+        signature: None,
+        package: None,
+        module: None,
+    }
+}
+
+#[cfg(test)]
+mod tests
+{
+    use super::*;
+
+    const README: &str = r#"# Roughup
+
+Intro text.
+
+## Installation
+
+Run this:
+
+```bash
+cargo install roughup
+```
+
+### From source
+
+Clone it.
+
+[docs]: https://example.com/docs "Docs"
+
+## Usage
+
+Use it.
+"#;
+
+    fn extract(src: &str) -> Vec<Symbol>
+    {
+        MarkdownExtractor::new()
+            .unwrap()
+            .extract_symbols(src, Path::new("README.md"))
+            .unwrap()
+    }
+
+    fn get<'a>(
+        syms: &'a [Symbol],
+        name: &str,
+    ) -> &'a Symbol
+    {
+        syms.iter()
+            .find(|s| s.name == name)
+            .expect("symbol not found")
+    }
+
+    #[test]
+    fn headings_span_their_sections()
+    {
+        let syms = extract(README);
+
+        let top = get(&syms, "Roughup");
+        assert_eq!(top.kind, SymbolKind::Heading);
+        assert_eq!((top.start_line, top.end_line), (1, 21));
+        assert_eq!(
+            top.doc
+                .as_deref(),
+            Some("Intro text.")
+        );
+
+        let install = get(&syms, "Installation");
+        assert_eq!(install.qualified_name, "Roughup::Installation");
+        assert_eq!((install.start_line, install.end_line), (5, 17));
+        assert!(README[install.byte_start..install.byte_end].ends_with("\"Docs\"\n"));
+
+        let source = get(&syms, "From source");
+        assert_eq!(source.qualified_name, "Roughup::Installation::From source");
+        assert_eq!((source.start_line, source.end_line), (13, 17));
+        assert_eq!(get(&syms, "Usage").qualified_name, "Roughup::Usage");
+    }
+
+    #[test]
+    fn code_blocks_and_link_definitions()
+    {
+        let syms = extract(README);
+
+        let code = get(&syms, "bash");
+        assert_eq!(code.kind, SymbolKind::CodeBlock);
+        assert_eq!(code.qualified_name, "Roughup::Installation::bash");
+        assert_eq!((code.start_line, code.end_line), (9, 11));
+
+        let docs = get(&syms, "docs");
+        assert_eq!(docs.kind, SymbolKind::LinkDefinition);
+        assert_eq!(
+            docs.doc
+                .as_deref(),
+            Some("https://example.com/docs")
+        );
+    }
+
+    #[test]
+    fn sections_resolve_by_slug_or_text()
+    {
+        assert_eq!(
+            find_section(README, "#installation").unwrap(),
+            Some((5, 17))
+        );
+        assert_eq!(find_section(README, "from-source").unwrap(), Some((13, 17)));
+        assert_eq!(find_section(README, "Usage").unwrap(), Some((19, 21)));
+        assert_eq!(find_section(README, "#missing").unwrap(), None);
+        assert_eq!(slugify("What's new in v2.0?"), "whats-new-in-v20");
+    }
+}
@@ -0,0 +1,370 @@
+//! Filepath: src/parsers/php_parser.rs
+//!
+//! PHP symbol extractor built on Tree-sitter 0.25.x.
+//!
+//! Notes:
+//!   - Namespaces (`namespace X;` and braced), classes, interfaces, traits, enums,
+//!     functions, methods, properties, and class constants.
+//!   - Names are qualified as `App\Billing::Invoice::total`; properties drop their `$`.
+//!   - Members without a visibility modifier are Public, as in PHP; top-level
+//!     declarations keep visibility unspecified.
+//!   - A `/** ... */` block right above a declaration becomes its doc.
+
+use std::path::Path;
+
+use anyhow::{Context, Result};
+use tree_sitter::{Language, Node};
+
+use crate::{
+    core::symbols::{
+        Symbol, SymbolExtractor, SymbolKind, Visibility, build_qualified_name, parse_visibility,
+    },
+    infra::utils::TsNodeUtils,
+};
+
+/// Extracts PHP symbols (namespaces, classes, traits, functions, members).
+pub struct PhpExtractor
+{
+    /// PHP (with inline HTML) language handle for Tree-sitter.
+    language: Language,
+}
+
+impl PhpExtractor
+{
+    pub fn new() -> Result<Self>
+    {
+        Ok(Self { language: tree_sitter_php::LANGUAGE_PHP.into() })
+    }
+}
+
+impl SymbolExtractor for PhpExtractor
+{
+    fn extract_symbols(
+        &self,
+        content: &str,
+        file_path: &Path,
+    ) -> Result<Vec<Symbol>>
+    {
+        let tree =
+            TsNodeUtils::parse(&self.language, content).context("Failed to parse PHP source")?;
+
+        let mut walker = Walker {
+            bytes: content.as_bytes(),
+            file: file_path,
+            owners: Vec::new(),
+            out: Vec::new(),
+        };
+        walker.walk(tree.root_node());
+        Ok(walker.out)
+    }
+}
+
+struct Walker<'a>
+{
+    bytes: &'a [u8],
+    file: &'a Path,
+    /// Namespace, then the enclosing type, outermost first
+    owners: Vec<String>,
+    out: Vec<Symbol>,
+}
+
+impl Walker<'_>
+{
+    fn walk(
+        &mut self,
+        node: Node,
+    )
+    {
+        // `namespace X;` owns every declaration after it
+        let depth = self
+            .owners
+            .len();
+        let in_type = node.kind() == "declaration_list";
+        let mut cursor = node.walk();
+        for child in node.named_children(&mut cursor)
+        {
+            let kind = match child.kind()
+            {
+                "namespace_definition" =>
+                {
+                    self.visit_namespace(child, depth);
+                    continue;
+                }
+                "class_declaration" => SymbolKind::Class,
+                "interface_declaration" => SymbolKind::Interface,
+                "trait_declaration" => SymbolKind::Trait,
+                "enum_declaration" => SymbolKind::Enum,
+                "function_definition" => SymbolKind::Function,
+                "method_declaration" => SymbolKind::Method,
+                "property_declaration" =>
+                {
+                    self.push_members(child, "property_element", SymbolKind::Property);
+                    continue;
+                }
+                "const_declaration" if in_type =>
+                {
+                    self.push_members(child, "const_element", SymbolKind::Constant);
+                    continue;
+                }
+                _ => continue,
+            };
+            let Some(name) = self.name_of(child)
+            else
+            {
+                continue;
+            };
+            let visibility = if in_type
+            {
+                Some(member_visibility(child, self.bytes))
+            }
+            else
+            {
+                None
+            };
+            self.push(kind.clone(), child, name.clone(), visibility);
+
+            if !matches!(kind, SymbolKind::Function | SymbolKind::Method)
+                && let Some(body) = child.child_by_field_name("body")
+            {
+                self.owners
+                    .push(name);
+                self.walk(body);
+                self.owners
+                    .pop();
+            }
+        }
+        self.owners
+            .truncate(depth);
+    }
+
+    /// `namespace X;` applies to the rest of the file, `namespace X { }`
+    /// to its block; either replaces any earlier namespace
+    fn visit_namespace(
+        &mut self,
+        node: Node,
+        depth: usize,
+    )
+    {
+        self.owners
+            .truncate(depth);
+        let name = node
+            .child_by_field_name("name")
+            .and_then(|n| {
+                n.utf8_text(self.bytes)
+                    .ok()
+            })
+            .map(str::to_string);
+        if let Some(name) = &name
+        {
+            self.push(SymbolKind::Module, node, name.clone(), None);
+        }
+        self.owners
+            .extend(name);
+        if let Some(body) = node.child_by_field_name("body")
+        {
+            self.walk(body);
+            self.owners
+                .truncate(depth);
+        }
+    }
+
+    /// One symbol per `property_element`/`const_element` of a declaration
+    fn push_members(
+        &mut self,
+        decl: Node,
+        element: &str,
+        kind: SymbolKind,
+    )
+    {
+        let visibility = member_visibility(decl, self.bytes);
+        let mut cursor = decl.walk();
+        let elements: Vec<Node> = decl
+            .named_children(&mut cursor)
+            .filter(|c| c.kind() == element)
+            .collect();
+        for el in elements
+        {
+            // `$total` is found under the element's name field, a const's
+            // name is its first named child
+            let name_node = el
+                .child_by_field_name("name")
+                .or_else(|| el.named_child(0));
+            let Some(name) = name_node
+                .and_then(|n| {
+                    n.utf8_text(self.bytes)
+                        .ok()
+                })
+                .map(|t| t.trim_start_matches('$'))
+                .map(str::to_string)
+            else
+            {
+                continue;
+            };
+            self.push(kind.clone(), decl, name, Some(visibility.clone()));
+        }
+    }
+
+    fn push(
+        &mut self,
+        kind: SymbolKind,
+        node: Node,
+        name: String,
+        visibility: Option<Visibility>,
+    )
+    {
+        let qualified_name = if kind == SymbolKind::Module
+        {
+            name.clone()
+        }
+        else
+        {
+            let mut parts: Vec<&str> = self
+                .owners
+                .iter()
+                .map(String::as_str)
+                .collect();
+            parts.push(&name);
+            build_qualified_name(&parts)
+        };
+        let start = node.start_position();
+        let end = node.end_position();
+        self.out
+            .push(Symbol {
+                file: self
+                    .file
+                    .to_path_buf(),
+                lang: "php".to_string(),
+                kind,
+                name,
+                qualified_name,
+                byte_start: node.start_byte(),
+                byte_end: node.end_byte(),
+                start_line: start.row + 1,
+                end_line: end.row + 1,
+                visibility,
+                doc: TsNodeUtils::doc_block(node, self.bytes),
+                signature: None,
+                package: None,
+                module: None,
+            });
+    }
+
+    fn name_of(
+        &self,
+        node: Node,
+    ) -> Option<String>
+    {
+        node.child_by_field_name("name")?
+            .utf8_text(self.bytes)
+            .ok()
+            .map(str::to_string)
+    }
+}
+
+/// Declared member visibility, PHP's default being public
+fn member_visibility(
+    node: Node,
+    bytes: &[u8],
+) -> Visibility
+{
+    let mut cursor = node.walk();
+    node.named_children(&mut cursor)
+        .find(|c| c.kind() == "visibility_modifier")
+        .and_then(|c| {
+            c.utf8_text(bytes)
+                .ok()
+        })
+        .and_then(parse_visibility)
+        .unwrap_or(Visibility::Public)
+}
+
+#[cfg(test)]
+mod tests
+{
+    use super::*;
+
+    fn extract(src: &str) -> Vec<Symbol>
+    {
+        PhpExtractor::new()
+            .unwrap()
+            .extract_symbols(src, Path::new("Invoice.php"))
+            .unwrap()
+    }
+
+    fn get<'a>(
+        syms: &'a [Symbol],
+        name: &str,
+    ) -> &'a Symbol
+    {
+        syms.iter()
+            .find(|s| s.name == name)
+            .expect("symbol not found")
+    }
+
+    #[test]
+    fn php_namespaces_classes_and_members()
+    {
+        let src = r#"<?php
+namespace App\Billing;
+
+/**
+ * An invoice.
+ */
+final class Invoice extends Base
+{
+    private int $total;
+    public const RATE = 2;
+    protected static function build(): self {}
+    function implicit() {}
+}
+
+function helper() {}
+"#;
+        let syms = extract(src);
+
+        assert_eq!(get(&syms, "App\\Billing").kind, SymbolKind::Module);
+        let invoice = get(&syms, "Invoice");
+        assert_eq!(invoice.kind, SymbolKind::Class);
+        assert_eq!(invoice.qualified_name, "App\\Billing::Invoice");
+        assert_eq!(
+            invoice
+                .doc
+                .as_deref(),
+            Some("An invoice.")
+        );
+        assert_eq!(invoice.start_line, 7);
+
+        let total = get(&syms, "total");
+        assert_eq!(total.kind, SymbolKind::Property);
+        assert_eq!(total.visibility, Some(Visibility::Private));
+        assert_eq!(get(&syms, "RATE").kind, SymbolKind::Constant);
+        assert_eq!(get(&syms, "build").visibility, Some(Visibility::Protected));
+        assert_eq!(get(&syms, "implicit").visibility, Some(Visibility::Public));
+
+        let helper = get(&syms, "helper");
+        assert_eq!(helper.kind, SymbolKind::Function);
+        assert_eq!(helper.qualified_name, "App\\Billing::helper");
+    }
+
+    #[test]
+    fn php_traits_interfaces_and_braced_namespaces()
+    {
+        let src = r#"<?php
+namespace App {
+    trait Loggable
+    {
+        public function log(string $m): void {}
+    }
+    interface Payable { public function pay(); }
+}
+namespace Other {
+    enum Status { case Paid; }
+}
+"#;
+        let syms = extract(src);
+        assert_eq!(get(&syms, "Loggable").kind, SymbolKind::Trait);
+        assert_eq!(get(&syms, "log").qualified_name, "App::Loggable::log");
+        assert_eq!(get(&syms, "Payable").kind, SymbolKind::Interface);
+        assert_eq!(get(&syms, "Status").qualified_name, "Other::Status");
+    }
+}
@@ -0,0 +1,306 @@
+//! Filepath: src/parsers/proto_parser.rs
+//!
+//! Protocol Buffers extractor built on Tree-sitter 0.25.x, so API
+//! contracts index alongside the services that implement them.
+//!
+//! Notes:
+//!   - Package, messages (nested included) as Structs, enums, services as Interfaces, and
+//!     their rpcs as Methods.
+//!   - Names are qualified as `acme.billing.v1::Invoice::Line`.
+//!   - Proto has no visibility; every symbol keeps it unspecified.
+//!   - The `//` comment lines right above a definition become its doc.
+
+use std::path::Path;
+
+use anyhow::{Context, Result};
+use tree_sitter::{Language, Node};
+
+use crate::{
+    core::symbols::{Symbol, SymbolExtractor, SymbolKind, build_qualified_name},
+    infra::utils::TsNodeUtils,
+};
+
+/// Extracts Protobuf symbols (package, messages, enums, services, rpcs).
+pub struct ProtoExtractor
+{
+    /// Protobuf language handle for Tree-sitter.
+    language: Language,
+}
+
+impl ProtoExtractor
+{
+    pub fn new() -> Result<Self>
+    {
+        Ok(Self { language: tree_sitter_proto::LANGUAGE.into() })
+    }
+}
+
+impl SymbolExtractor for ProtoExtractor
+{
+    fn extract_symbols(
+        &self,
+        content: &str,
+        file_path: &Path,
+    ) -> Result<Vec<Symbol>>
+    {
+        let tree = TsNodeUtils::parse(&self.language, content)
+            .context("Failed to parse Protobuf source")?;
+
+        let mut walker = Walker {
+            bytes: content.as_bytes(),
+            file: file_path,
+            owners: Vec::new(),
+            out: Vec::new(),
+        };
+        walker.walk(tree.root_node());
+        Ok(walker.out)
+    }
+}
+
+struct Walker<'a>
+{
+    bytes: &'a [u8],
+    file: &'a Path,
+    /// Package, then enclosing messages and services, outermost first
+    owners: Vec<String>,
+    out: Vec<Symbol>,
+}
+
+impl Walker<'_>
+{
+    fn walk(
+        &mut self,
+        node: Node,
+    )
+    {
+        let mut cursor = node.walk();
+        for child in node.named_children(&mut cursor)
+        {
+            let (kind, name_kind, body_kind) = match child.kind()
+            {
+                "package" =>
+                {
+                    if let Some(name) = self.named_text(child, "full_ident")
+                    {
+                        self.push(SymbolKind::Package, child, name.clone(), name.clone());
+                        self.owners
+                            .push(name);
+                    }
+                    continue;
+                }
+                "message" => (SymbolKind::Struct, "message_name", Some("message_body")),
+                "enum" => (SymbolKind::Enum, "enum_name", None),
+                "service" => (SymbolKind::Interface, "service_name", Some("service")),
+                "rpc" => (SymbolKind::Method, "rpc_name", None),
+                _ => continue,
+            };
+            let Some(name) = self.named_text(child, name_kind)
+            else
+            {
+                continue;
+            };
+            let mut parts: Vec<&str> = self
+                .owners
+                .iter()
+                .map(String::as_str)
+                .collect();
+            parts.push(&name);
+            let qualified_name = build_qualified_name(&parts);
+            self.push(kind, child, name.clone(), qualified_name);
+
+            // Services hold their rpcs directly; messages nest in a body
+            let body = match body_kind
+            {
+                Some("service") => Some(child),
+                Some(body_kind) =>
+                {
+                    let mut c = child.walk();
+                    child
+                        .named_children(&mut c)
+                        .find(|n| n.kind() == body_kind)
+                }
+                None => None,
+            };
+            if let Some(body) = body
+            {
+                self.owners
+                    .push(name);
+                self.walk(body);
+                self.owners
+                    .pop();
+            }
+        }
+    }
+
+    fn push(
+        &mut self,
+        kind: SymbolKind,
+        node: Node,
+        name: String,
+        qualified_name: String,
+    )
+    {
+        let start = node.start_position();
+        let end = node.end_position();
+        self.out
+            .push(Symbol {
+                file: self
+                    .file
+                    .to_path_buf(),
+                lang: "proto".to_string(),
+                kind,
+                name,
+                qualified_name,
+                byte_start: node.start_byte(),
+                byte_end: node.end_byte(),
+                start_line: start.row + 1,
+                end_line: end.row + 1,
+                visibility: None,
+                doc: proto_doc(node, self.bytes),
+                signature: None,
+                package: None,
+                module: None,
+            });
+    }
+
+    /// Text of the first named child of `kind`
+    fn named_text(
+        &self,
+        node: Node,
+        kind: &str,
+    ) -> Option<String>
+    {
+        let mut cursor = node.walk();
+        node.named_children(&mut cursor)
+            .find(|c| c.kind() == kind)
+            .and_then(|c| {
+                c.utf8_text(self.bytes)
+                    .ok()
+            })
+            .map(str::to_string)
+    }
+}
+
+/// Consecutive `//` comment lines ending right above `node`
+fn proto_doc(
+    node: Node,
+    bytes: &[u8],
+) -> Option<String>
+{
+    let mut lines = Vec::new();
+    let mut row = node
+        .start_position()
+        .row;
+    let mut prev = node.prev_named_sibling();
+    while let Some(comment) = prev
+    {
+        if comment.kind() != "comment"
+            || comment
+                .end_position()
+                .row
+                + 1
+                != row
+        {
+            break;
+        }
+        let Some(line) = comment
+            .utf8_text(bytes)
+            .ok()
+            .and_then(|t| t.strip_prefix("//"))
+        else
+        {
+            break;
+        };
+        lines.push(line.trim());
+        row = comment
+            .start_position()
+            .row;
+        prev = comment.prev_named_sibling();
+    }
+    lines.reverse();
+    (!lines.is_empty()).then(|| lines.join("\n"))
+}
+
+#[cfg(test)]
+mod tests
+{
+    use super::*;
+
+    fn extract(src: &str) -> Vec<Symbol>
+    {
+        ProtoExtractor::new()
+            .unwrap()
+            .extract_symbols(src, Path::new("billing.proto"))
+            .unwrap()
+    }
+
+    fn get<'a>(
+        syms: &'a [Symbol],
+        name: &str,
+    ) -> &'a Symbol
+    {
+        syms.iter()
+            .find(|s| s.name == name)
+            .expect("symbol not found")
+    }
+
+    #[test]
+    fn messages_enums_services_and_rpcs()
+    {
+        let src = r#"syntax = "proto3";
+
+package acme.billing.v1;
+
+// An invoice.
+message Invoice {
+  string id = 1;
+  message Line {
+    int64 cents = 1;
+  }
+  enum State {
+    STATE_UNSPECIFIED = 0;
+  }
+}
+
+service Billing {
+  // Create one.
+  rpc CreateInvoice(CreateInvoiceRequest) returns (Invoice);
+  rpc Watch(stream Req) returns (stream Invoice) {}
+}
+"#;
+        let syms = extract(src);
+
+        assert_eq!(get(&syms, "acme.billing.v1").kind, SymbolKind::Package);
+        let invoice = get(&syms, "Invoice");
+        assert_eq!(invoice.kind, SymbolKind::Struct);
+        assert_eq!(invoice.qualified_name, "acme.billing.v1::Invoice");
+        assert_eq!(
+            invoice
+                .doc
+                .as_deref(),
+            Some("An invoice.")
+        );
+        assert_eq!((invoice.start_line, invoice.end_line), (6, 14));
+
+        assert_eq!(
+            get(&syms, "Line").qualified_name,
+            "acme.billing.v1::Invoice::Line"
+        );
+        assert_eq!(get(&syms, "State").kind, SymbolKind::Enum);
+
+        assert_eq!(get(&syms, "Billing").kind, SymbolKind::Interface);
+        let create = get(&syms, "CreateInvoice");
+        assert_eq!(create.kind, SymbolKind::Method);
+        assert_eq!(
+            create.qualified_name,
+            "acme.billing.v1::Billing::CreateInvoice"
+        );
+        assert_eq!(
+            create
+                .doc
+                .as_deref(),
+            Some("Create one.")
+        );
+        assert_eq!(get(&syms, "Watch").start_line, 19);
+    }
+}
@@ -6,6 +6,9 @@
 //!   - Classify methods by ancestry (avoid duplicate matches).
 //!   - Extract PEP 257 docstrings (first statement string).
 //!   - Build qualified names for methods (A::B::m).
+//!   - Spans include decorators; `@property` methods are properties.
+//!   - Visibility follows `__all__` when the module defines it, else the
+//!     leading-underscore convention (dunder names stay public).
 //!   - Be careful with allocations and streaming iteration.
 //!
 //! Notes:
@@ -20,14 +23,17 @@
 
 use std::path::Path;
 
-use anyhow::{Context, Result, anyhow};
-use tree_sitter::{Language, Node, Parser, Query, QueryCursor, StreamingIterator};
+use anyhow::{Context, Result};
+use tree_sitter::{Language, Node, Query, QueryCursor, StreamingIterator};
 
-use crate::core::symbols::{Symbol, SymbolExtractor, SymbolKind, Visibility};
+use crate::core::symbols::{
+    Reference, Relation, RelationKind, Symbol, SymbolExtractor, SymbolKind, Visibility,
+};
 // Reuse the shared helper to avoid drift
 use crate::infra::utils::TsNodeUtils;
 
-/// Extracts Python symbols (functions, classes, methods).
+/// Extracts Python symbols (functions, classes, methods, properties, and
+/// annotated class fields).
 pub struct PythonExtractor
 {
     /// Python language handle for Tree-sitter.
@@ -72,16 +78,9 @@ impl SymbolExtractor for PythonExtractor
         file_path: &Path,
     ) -> Result<Vec<Symbol>>
     {
-        // Create a parser instance and set the language.
-        let mut parser = Parser::new();
-        parser
-            .set_language(&self.language)
-            .context("set Python language")?;
-
-        // Parse the source; fail if no tree is produced.
-        let tree = parser
-            .parse(content, None)
-            .ok_or_else(|| anyhow!("Failed to parse Python source"))?;
+        // Parse with this thread's pooled parser; fail if no tree is produced.
+        let tree = TsNodeUtils::parse(&self.language, content)
+            .context("Failed to parse Python source")?;
 
         // Use the same bytes slice for all utf8_text calls.
         let bytes = content.as_bytes();
@@ -147,26 +146,39 @@ impl SymbolExtractor for PythonExtractor
             // Function for function_definition or Class for
             // class_definition. Avoid duplicates by not having
             // a separate "method" query pattern.
+            // Decorators belong to the definition's span.
+            let outer = node
+                .parent()
+                .filter(|p| p.kind() == "decorated_definition")
+                .unwrap_or(node);
+            let decorators = python_decorator_names(outer, bytes);
+
             let kind = match node.kind()
             {
                 "function_definition" =>
                 {
-                    if TsNodeUtils::has_ancestor(node, "class_definition")
+                    if !TsNodeUtils::has_ancestor(node, "class_definition")
                     {
-                        SymbolKind::Method
+                        SymbolKind::Function
+                    }
+                    else if decorators
+                        .iter()
+                        .any(|d| is_property_decorator(d))
+                    {
+                        SymbolKind::Property
                     }
                     else
                     {
-                        SymbolKind::Function
+                        SymbolKind::Method
                     }
                 }
                 "class_definition" => SymbolKind::Class,
                 _ => continue,
             };
 
-            // Build a qualified name. For methods we climb the
+            // Build a qualified name. For class members we climb the
             // class chain. For top-level items, keep simple name.
-            let qualified_name = if matches!(kind, SymbolKind::Method)
+            let qualified_name = if matches!(kind, SymbolKind::Method | SymbolKind::Property)
             {
                 python_qualified_name_method(node, bytes, &name)
             }
@@ -175,19 +187,9 @@ impl SymbolExtractor for PythonExtractor
                 name.clone()
             };
 
-            // Python visibility by leading underscore policy.
-            let visibility = if name.starts_with('_')
-            {
-                Some(Visibility::Private)
-            }
-            else
-            {
-                Some(Visibility::Public)
-            };
-
             // Compute line and byte spans.
-            let start = node.start_position();
-            let end = node.end_position();
+            let start = outer.start_position();
+            let end = outer.end_position();
 
             // Collect PEP 257-style docstring where present.
             let doc = python_docstring_extract(node, bytes);
@@ -197,20 +199,239 @@ impl SymbolExtractor for PythonExtractor
                 file: file_path.to_path_buf(),
                 lang: "python".to_string(),
                 kind,
+                visibility: Some(python_visibility(&name)),
                 name,
                 qualified_name,
-                byte_start: node.start_byte(),
-                byte_end: node.end_byte(),
+                byte_start: outer.start_byte(),
+                byte_end: outer.end_byte(),
                 start_line: start.row + 1,
                 end_line: end.row + 1,
-                visibility,
                 doc,
+                signature: None,
+                package: None,
+                module: None,
             });
+
+            // Annotated class attributes: dataclass, attrs, pydantic,
+            // NamedTuple, and TypedDict fields.
+            if node.kind() == "class_definition"
+            {
+                out.extend(python_class_fields(node, bytes, file_path));
+            }
+        }
+
+        // `__all__` decides which top-level names are public.
+        if let Some(exports) = python_module_exports(tree.root_node(), bytes)
+        {
+            for s in &mut out
+            {
+                if !s
+                    .qualified_name
+                    .contains("::")
+                {
+                    let listed = exports.contains(&s.name);
+                    s.visibility =
+                        Some(if listed { Visibility::Public } else { Visibility::Private });
+                }
+            }
         }
 
         // Return the final symbol list.
         Ok(out)
     }
+
+    fn extract_references(
+        &self,
+        content: &str,
+        _file_path: &Path,
+    ) -> Result<Vec<Reference>>
+    {
+        TsNodeUtils::call_sites(&self.language, content, &[("call", "function")])
+    }
+
+    fn extract_relations(
+        &self,
+        content: &str,
+        _file_path: &Path,
+    ) -> Result<Vec<Relation>>
+    {
+        TsNodeUtils::relations(&self.language, content, &[("class_definition", "name", &[
+            ("superclasses", RelationKind::Extends),
+        ])])
+    }
+}
+
+/// Python visibility by naming convention: a leading underscore marks a
+/// private name, except dunder names such as `__init__`.
+fn python_visibility(name: &str) -> Visibility
+{
+    let dunder = name.len() > 4 && name.starts_with("__") && name.ends_with("__");
+    if name.starts_with('_') && !dunder { Visibility::Private } else { Visibility::Public }
+}
+
+/// Decorator names on a `decorated_definition`, without `@` or call
+/// arguments (`@app.route("/")` gives `app.route`).
+fn python_decorator_names(
+    outer: Node,
+    bytes: &[u8],
+) -> Vec<String>
+{
+    let mut cursor = outer.walk();
+    outer
+        .named_children(&mut cursor)
+        .filter(|c| c.kind() == "decorator")
+        .filter_map(|d| {
+            let text = d
+                .utf8_text(bytes)
+                .ok()?
+                .trim_start_matches('@');
+            let name = text
+                .split('(')
+                .next()
+                .unwrap_or(text);
+            Some(
+                name.trim()
+                    .to_string(),
+            )
+        })
+        .collect()
+}
+
+/// Whether a decorator makes a method a property or one of its accessors.
+fn is_property_decorator(name: &str) -> bool
+{
+    let last = name
+        .rsplit('.')
+        .next()
+        .unwrap_or(name);
+    matches!(last, "property" | "cached_property" | "setter" | "getter" | "deleter")
+}
+
+/// Annotated assignments directly in a class body (`x: int = 0`), with
+/// the string literal right after one as its docstring.
+fn python_class_fields(
+    class: Node,
+    bytes: &[u8],
+    file_path: &Path,
+) -> Vec<Symbol>
+{
+    let Some(body) = class.child_by_field_name("body")
+    else
+    {
+        return Vec::new();
+    };
+    let mut out = Vec::new();
+    let mut cursor = body.walk();
+    let stmts: Vec<Node> = body
+        .named_children(&mut cursor)
+        .collect();
+    for (i, stmt) in stmts
+        .iter()
+        .enumerate()
+    {
+        let Some(assign) = stmt
+            .named_child(0)
+            .filter(|a| stmt.kind() == "expression_statement" && a.kind() == "assignment")
+        else
+        {
+            continue;
+        };
+        if assign
+            .child_by_field_name("type")
+            .is_none()
+        {
+            continue;
+        }
+        let Some(name) = assign
+            .child_by_field_name("left")
+            .filter(|l| l.kind() == "identifier")
+            .and_then(|l| {
+                l.utf8_text(bytes)
+                    .ok()
+            })
+        else
+        {
+            continue;
+        };
+
+        let doc = stmts
+            .get(i + 1)
+            .filter(|n| n.kind() == "expression_statement")
+            .and_then(|n| n.named_child(0))
+            .filter(|lit| lit.kind() == "string")
+            .and_then(|lit| {
+                lit.utf8_text(bytes)
+                    .ok()
+            })
+            .map(unquote_python_string);
+
+        out.push(Symbol {
+            file: file_path.to_path_buf(),
+            lang: "python".to_string(),
+            kind: SymbolKind::Variable,
+            name: name.to_string(),
+            qualified_name: python_qualified_name_method(*stmt, bytes, name),
+            byte_start: stmt.start_byte(),
+            byte_end: stmt.end_byte(),
+            start_line: stmt.start_position().row + 1,
+            end_line: stmt.end_position().row + 1,
+            visibility: Some(python_visibility(name)),
+            doc,
+            signature: None,
+            package: None,
+            module: None,
+        });
+    }
+    out
+}
+
+/// Names listed in a module-level `__all__` (assigned or extended with
+/// `+=`), or `None` when the module does not define one.
+fn python_module_exports(
+    root: Node,
+    bytes: &[u8],
+) -> Option<std::collections::HashSet<String>>
+{
+    let mut exports = None;
+    let mut cursor = root.walk();
+    for stmt in root.named_children(&mut cursor)
+    {
+        let Some(assign) = stmt
+            .named_child(0)
+            .filter(|a| {
+                stmt.kind() == "expression_statement"
+                    && matches!(a.kind(), "assignment" | "augmented_assignment")
+            })
+        else
+        {
+            continue;
+        };
+        let is_all = assign
+            .child_by_field_name("left")
+            .and_then(|l| {
+                l.utf8_text(bytes)
+                    .ok()
+            })
+            == Some("__all__");
+        let Some(value) = assign
+            .child_by_field_name("right")
+            .filter(|_| is_all)
+        else
+        {
+            continue;
+        };
+        let names = exports.get_or_insert_with(std::collections::HashSet::new);
+        let mut items = value.walk();
+        for item in value.named_children(&mut items)
+        {
+            if item.kind() == "string"
+                && let Ok(raw) = item.utf8_text(bytes)
+            {
+                names.insert(unquote_python_string(raw));
+            }
+        }
+    }
+    exports
 }
 
 /// Build qualified method names of the form
@@ -648,6 +869,108 @@ class Outer:
         Ok(())
     }
 
+    #[test]
+    fn python_decorators_properties_and_async() -> Result<()>
+    {
+        let ex = PythonExtractor::new()?;
+        let src = r#"
+class Circle:
+    def __init__(self, r):
+        self.r = r
+
+    @property
+    def area(self):
+        """Area"""
+        return 3.14 * self.r ** 2
+
+    @area.setter
+    def area(self, value):
+        pass
+
+@app.route("/fetch")
+async def fetch():
+    pass
+"#;
+        let file = PathBuf::from("t.py");
+        let syms = ex.extract_symbols(src, &file)?;
+
+        let init = get(&syms, SymbolKind::Method, "__init__");
+        assert_eq!(init.visibility, Some(Visibility::Public));
+
+        let props: Vec<&Symbol> = syms
+            .iter()
+            .filter(|s| has(s, SymbolKind::Property, "area"))
+            .collect();
+        assert_eq!(props.len(), 2);
+        assert_eq!(props[0].qualified_name, "Circle::area");
+        assert_eq!(props[0].start_line, 6);
+        assert_eq!(
+            props[0]
+                .doc
+                .as_deref(),
+            Some("Area")
+        );
+
+        let fetch = get(&syms, SymbolKind::Function, "fetch");
+        assert!(
+            src[fetch.byte_start..fetch.byte_end].starts_with("@app.route(\"/fetch\")\nasync def")
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn python_dataclass_fields_and_all_exports() -> Result<()>
+    {
+        let ex = PythonExtractor::new()?;
+        let src = r#"
+__all__ = ["Point", "_make"]
+__all__ += ("helper",)
+
+@dataclass(frozen=True)
+class Point:
+    x: int
+    """Horizontal"""
+    y: int = 0
+    label = "plain class attribute"
+
+def _make():
+    pass
+
+def helper():
+    pass
+
+def unlisted():
+    pass
+"#;
+        let file = PathBuf::from("t.py");
+        let syms = ex.extract_symbols(src, &file)?;
+
+        let x = get(&syms, SymbolKind::Variable, "x");
+        assert_eq!(x.qualified_name, "Point::x");
+        assert_eq!(
+            x.doc
+                .as_deref(),
+            Some("Horizontal")
+        );
+        assert_eq!(get(&syms, SymbolKind::Variable, "y").start_line, 9);
+        assert!(
+            !syms
+                .iter()
+                .any(|s| s.name == "label")
+        );
+
+        let vis = |kind, name| {
+            get(&syms, kind, name)
+                .visibility
+                .clone()
+        };
+        assert_eq!(vis(SymbolKind::Class, "Point"), Some(Visibility::Public));
+        assert_eq!(vis(SymbolKind::Function, "_make"), Some(Visibility::Public));
+        assert_eq!(vis(SymbolKind::Function, "helper"), Some(Visibility::Public));
+        assert_eq!(vis(SymbolKind::Function, "unlisted"), Some(Visibility::Private));
+        Ok(())
+    }
+
     #[test]
     fn python_non_first_string_is_not_docstring() -> Result<()>
     {
@@ -0,0 +1,441 @@
+//! Filepath: src/parsers/ruby_parser.rs
+//!
+//! Ruby symbol extractor built on Tree-sitter 0.25.x.
+//!
+//! Notes:
+//!   - Modules, classes, methods (`def self.x` included), and the attributes declared by
+//!     `attr_reader`/`attr_writer`/`attr_accessor`, which become Properties spanning the
+//!     macro call.
+//!   - Names are qualified as `Billing::Invoice::total`; `class A::B` is qualified by its
+//!     written scope.
+//!   - Inside a class or module, a bare `private`/`protected`/`public` applies to the
+//!     definitions after it, and `private def x` to that one method; top-level methods
+//!     keep visibility unspecified.
+//!   - The `#` comment lines right above a definition become its doc.
+
+use std::path::Path;
+
+use anyhow::{Context, Result};
+use tree_sitter::{Language, Node};
+
+use crate::{
+    core::symbols::{
+        Symbol, SymbolExtractor, SymbolKind, Visibility, build_qualified_name, parse_visibility,
+    },
+    infra::utils::TsNodeUtils,
+};
+
+/// Extracts Ruby symbols (modules, classes, methods, attributes).
+pub struct RubyExtractor
+{
+    /// Ruby language handle for Tree-sitter.
+    language: Language,
+}
+
+impl RubyExtractor
+{
+    pub fn new() -> Result<Self>
+    {
+        Ok(Self { language: tree_sitter_ruby::LANGUAGE.into() })
+    }
+}
+
+impl SymbolExtractor for RubyExtractor
+{
+    fn extract_symbols(
+        &self,
+        content: &str,
+        file_path: &Path,
+    ) -> Result<Vec<Symbol>>
+    {
+        let tree =
+            TsNodeUtils::parse(&self.language, content).context("Failed to parse Ruby source")?;
+
+        let mut walker = Walker {
+            source: content,
+            file: file_path,
+            owners: Vec::new(),
+            out: Vec::new(),
+        };
+        walker.walk(tree.root_node());
+        Ok(walker.out)
+    }
+}
+
+struct Walker<'a>
+{
+    source: &'a str,
+    file: &'a Path,
+    /// Enclosing modules and classes, outermost first
+    owners: Vec<String>,
+    out: Vec<Symbol>,
+}
+
+impl Walker<'_>
+{
+    /// Visit the statements of a program or a class/module body
+    fn walk(
+        &mut self,
+        node: Node,
+    )
+    {
+        let in_type = !self
+            .owners
+            .is_empty();
+        let mut visibility = in_type.then_some(Visibility::Public);
+
+        let mut cursor = node.walk();
+        for child in node.named_children(&mut cursor)
+        {
+            match child.kind()
+            {
+                "module" | "class" =>
+                {
+                    let kind = if child.kind() == "module"
+                    {
+                        SymbolKind::Module
+                    }
+                    else
+                    {
+                        SymbolKind::Class
+                    };
+                    self.push_scope(kind, child);
+                }
+                "method" | "singleton_method" =>
+                {
+                    self.push_method(child, visibility.clone());
+                }
+                // A bare `private` switches the default for what follows
+                "identifier" if in_type =>
+                {
+                    if let Some(v) = self
+                        .text(child)
+                        .and_then(parse_visibility)
+                    {
+                        visibility = Some(v);
+                    }
+                }
+                "call" if in_type => self.visit_macro(child),
+                _ =>
+                {}
+            }
+        }
+    }
+
+    /// `module`/`class`, qualified by any `A::B` scope in its name
+    fn push_scope(
+        &mut self,
+        kind: SymbolKind,
+        node: Node,
+    )
+    {
+        let Some(name_node) = node.child_by_field_name("name")
+        else
+        {
+            return;
+        };
+        let Some(path) = self
+            .text(name_node)
+            .map(str::to_string)
+        else
+        {
+            return;
+        };
+        let name = path
+            .rsplit("::")
+            .next()
+            .unwrap_or(&path)
+            .to_string();
+        let scope: Vec<String> = path
+            .split("::")
+            .map(str::to_string)
+            .collect();
+
+        let visibility = (!self
+            .owners
+            .is_empty())
+        .then_some(Visibility::Public);
+        let qualified_name = self.qualify(&scope);
+        self.push(kind, node, name, qualified_name, visibility);
+
+        if let Some(body) = node.child_by_field_name("body")
+        {
+            let depth = self
+                .owners
+                .len();
+            self.owners
+                .extend(scope);
+            self.walk(body);
+            self.owners
+                .truncate(depth);
+        }
+    }
+
+    fn push_method(
+        &mut self,
+        node: Node,
+        visibility: Option<Visibility>,
+    )
+    {
+        let Some(name) = node
+            .child_by_field_name("name")
+            .and_then(|n| self.text(n))
+            .map(str::to_string)
+        else
+        {
+            return;
+        };
+        let kind = if self
+            .owners
+            .is_empty()
+        {
+            SymbolKind::Function
+        }
+        else
+        {
+            SymbolKind::Method
+        };
+        let qualified_name = self.qualify(std::slice::from_ref(&name));
+        self.push(kind, node, name, qualified_name, visibility);
+    }
+
+    /// `attr_*` macros and `private def ...` inside a class or module
+    fn visit_macro(
+        &mut self,
+        call: Node,
+    )
+    {
+        let Some(method) = call
+            .child_by_field_name("method")
+            .and_then(|n| self.text(n))
+        else
+        {
+            return;
+        };
+        let Some(args) = call.child_by_field_name("arguments")
+        else
+        {
+            return;
+        };
+        let mut cursor = args.walk();
+        let args: Vec<Node> = args
+            .named_children(&mut cursor)
+            .collect();
+
+        if matches!(method, "attr_reader" | "attr_writer" | "attr_accessor")
+        {
+            for arg in args
+            {
+                let name = self
+                    .text(arg)
+                    .map(|t| {
+                        t.trim_start_matches(':')
+                            .trim_matches('"')
+                            .to_string()
+                    })
+                    .filter(|t| !t.is_empty());
+                if let Some(name) = name
+                {
+                    let qualified_name = self.qualify(std::slice::from_ref(&name));
+                    self.push(
+                        SymbolKind::Property,
+                        call,
+                        name,
+                        qualified_name,
+                        Some(Visibility::Public),
+                    );
+                }
+            }
+        }
+        else if let Some(visibility) = parse_visibility(method)
+        {
+            for arg in args
+                .into_iter()
+                .filter(|a| matches!(a.kind(), "method" | "singleton_method"))
+            {
+                self.push_method(arg, Some(visibility.clone()));
+            }
+        }
+    }
+
+    fn push(
+        &mut self,
+        kind: SymbolKind,
+        node: Node,
+        name: String,
+        qualified_name: String,
+        visibility: Option<Visibility>,
+    )
+    {
+        let start = node.start_position();
+        let end = node.end_position();
+        self.out
+            .push(Symbol {
+                file: self
+                    .file
+                    .to_path_buf(),
+                lang: "ruby".to_string(),
+                kind,
+                name,
+                qualified_name,
+                byte_start: node.start_byte(),
+                byte_end: node.end_byte(),
+                start_line: start.row + 1,
+                end_line: end.row + 1,
+                visibility,
+                doc: ruby_doc(self.source, start.row),
+                signature: None,
+                package: None,
+                module: None,
+            });
+    }
+
+    fn qualify(
+        &self,
+        tail: &[String],
+    ) -> String
+    {
+        let parts: Vec<&str> = self
+            .owners
+            .iter()
+            .chain(tail)
+            .map(String::as_str)
+            .collect();
+        build_qualified_name(&parts)
+    }
+
+    fn text(
+        &self,
+        node: Node,
+    ) -> Option<&str>
+    {
+        node.utf8_text(
+            self.source
+                .as_bytes(),
+        )
+        .ok()
+    }
+}
+
+/// Consecutive `#` comment lines ending right above `row`; read from the
+/// source because Ruby comments are not always siblings of what they document
+fn ruby_doc(
+    source: &str,
+    row: usize,
+) -> Option<String>
+{
+    let above: Vec<&str> = source
+        .lines()
+        .take(row)
+        .collect();
+    let mut lines: Vec<&str> = above
+        .iter()
+        .rev()
+        .map(|l| l.trim())
+        .take_while(|l| l.starts_with('#') && !l.starts_with("#!"))
+        .map(|l| {
+            l[1..]
+                .strip_prefix(' ')
+                .unwrap_or(&l[1..])
+        })
+        .collect();
+    lines.reverse();
+    (!lines.is_empty()).then(|| lines.join("\n"))
+}
+
+#[cfg(test)]
+mod tests
+{
+    use super::*;
+
+    fn extract(src: &str) -> Vec<Symbol>
+    {
+        RubyExtractor::new()
+            .unwrap()
+            .extract_symbols(src, Path::new("invoice.rb"))
+            .unwrap()
+    }
+
+    fn get<'a>(
+        syms: &'a [Symbol],
+        name: &str,
+    ) -> &'a Symbol
+    {
+        syms.iter()
+            .find(|s| s.name == name)
+            .expect("symbol not found")
+    }
+
+    #[test]
+    fn ruby_modules_classes_methods_and_attrs()
+    {
+        let src = r#"module Billing
+  # An invoice.
+  # Second line.
+  class Invoice < Base
+    attr_reader :total, :items
+    attr_accessor :note
+
+    def initialize(total)
+      @total = total
+    end
+
+    def self.build; end
+
+    private
+
+    def secret; end
+  end
+end
+
+def helper; end
+"#;
+        let syms = extract(src);
+
+        assert_eq!(get(&syms, "Billing").kind, SymbolKind::Module);
+        let invoice = get(&syms, "Invoice");
+        assert_eq!(invoice.kind, SymbolKind::Class);
+        assert_eq!(invoice.qualified_name, "Billing::Invoice");
+        assert_eq!(
+            invoice
+                .doc
+                .as_deref(),
+            Some("An invoice.\nSecond line.")
+        );
+        assert_eq!(invoice.start_line, 4);
+
+        let items = get(&syms, "items");
+        assert_eq!(items.kind, SymbolKind::Property);
+        assert_eq!(items.qualified_name, "Billing::Invoice::items");
+        assert_eq!(get(&syms, "note").start_line, 6);
+
+        let init = get(&syms, "initialize");
+        assert_eq!(init.kind, SymbolKind::Method);
+        assert_eq!(init.visibility, Some(Visibility::Public));
+        assert_eq!(
+            get(&syms, "build").qualified_name,
+            "Billing::Invoice::build"
+        );
+        assert_eq!(get(&syms, "secret").visibility, Some(Visibility::Private));
+
+        let helper = get(&syms, "helper");
+        assert_eq!(helper.kind, SymbolKind::Function);
+        assert_eq!(helper.visibility, None);
+    }
+
+    #[test]
+    fn ruby_scoped_names_and_inline_private()
+    {
+        let src = r#"class Billing::Line
+  private def cost; end
+  def price; end
+end
+"#;
+        let syms = extract(src);
+        let line = get(&syms, "Line");
+        assert_eq!(line.qualified_name, "Billing::Line");
+        assert_eq!(get(&syms, "cost").visibility, Some(Visibility::Private));
+        assert_eq!(get(&syms, "price").qualified_name, "Billing::Line::price");
+        assert_eq!(get(&syms, "price").visibility, Some(Visibility::Public));
+    }
+}
@@ -6,12 +6,13 @@
 
 use std::path::Path;
 
-use anyhow::{Result, anyhow};
-use tree_sitter::{Language, Node, Parser, Query, QueryCursor, StreamingIterator};
+use anyhow::{Context, Result, anyhow};
+use tree_sitter::{Language, Node, Query, QueryCursor, StreamingIterator};
 
 use crate::{
     core::symbols::{
-        Symbol, SymbolExtractor, SymbolKind, Visibility, build_qualified_name, parse_visibility,
+        Reference, Relation, RelationKind, Symbol, SymbolExtractor, SymbolKind, Visibility,
+        build_qualified_name, parse_visibility,
     },
     infra::utils::TsNodeUtils,
 };
@@ -92,6 +93,59 @@ impl SymbolExtractor for RustExtractor
         }
     }
     // postprocess() inherited: keeps deterministic sorting.
+
+    fn extract_references(
+        &self,
+        content: &str,
+        _file_path: &Path,
+    ) -> Result<Vec<Reference>>
+    {
+        match &self.backend
+        {
+            RustBackend::TreeSitter { language, .. } =>
+            {
+                TsNodeUtils::call_sites(language, content, &[("call_expression", "function")])
+            }
+        }
+    }
+
+    fn extract_relations(
+        &self,
+        content: &str,
+        _file_path: &Path,
+    ) -> Result<Vec<Relation>>
+    {
+        match &self.backend
+        {
+            // `impl Trait for Type`, and supertraits in `trait A: B + C`
+            RustBackend::TreeSitter { language, .. } =>
+            {
+                let mut out = TsNodeUtils::relations(language, content, &[
+                    ("impl_item", "type", &[("trait", RelationKind::Implements)]),
+                    ("trait_item", "name", &[("bounds", RelationKind::Extends)]),
+                ])?;
+
+                // `pub use path::Original as Alias`, so aliases resolve
+                let tree = TsNodeUtils::parse(language, content)
+                    .context("Failed to parse Rust source")?;
+                for item in pub_use_items(tree.root_node(), content.as_bytes())
+                {
+                    if item.exported != item.original
+                    {
+                        let (s, e) = item.original_span;
+                        out.push(Relation::new(
+                            RelationKind::ReExports,
+                            &item.exported,
+                            &item.original,
+                            s,
+                            e,
+                        ));
+                    }
+                }
+                Ok(out)
+            }
+        }
+    }
 }
 
 // === Tree-sitter implementation ===
@@ -103,12 +157,7 @@ fn tree_sitter_extract_symbols(
     file_path: &Path,
 ) -> Result<Vec<Symbol>>
 {
-    let mut parser = Parser::new();
-    parser.set_language(language)?; // takes &Language on 0.25+  :contentReference[oaicite:3]{index=3}
-
-    let tree = parser
-        .parse(content, None)
-        .ok_or_else(|| anyhow!("Failed to parse Rust source"))?;
+    let tree = TsNodeUtils::parse(language, content).context("Failed to parse Rust source")?;
     let bytes = content.as_bytes();
 
     let mut cursor = QueryCursor::new();
@@ -180,9 +229,304 @@ fn tree_sitter_extract_symbols(
         }
     }
 
+    // `pub use` re-exports, under the name they are exported as
+    let root = tree.root_node();
+    for item in pub_use_items(root, bytes)
+    {
+        out.push(reexport_symbol(&item, bytes, file_path));
+    }
+
+    out.extend(macro_item_symbols(language, items_query, root, content, file_path)?);
+
+    Ok(out)
+}
+
+/// A name made public by `pub use`
+struct ReExport<'t>
+{
+    /// The whole `use` declaration
+    decl: Node<'t>,
+
+    /// The use-tree item naming this export
+    item: Node<'t>,
+
+    /// Name the item is exported as (the alias, if any)
+    exported: String,
+
+    /// Name at the definition: the last path segment
+    original: String,
+
+    /// Byte span of `original` in the declaration
+    original_span: (usize, usize),
+}
+
+/// Every item of every `use` declaration carrying a visibility modifier;
+/// globs and `self`/`super`/`crate` imports name nothing and are skipped
+fn pub_use_items<'t>(
+    root: Node<'t>,
+    bytes: &[u8],
+) -> Vec<ReExport<'t>>
+{
+    let mut out = Vec::new();
+    let mut stack = vec![root];
+    while let Some(node) = stack.pop()
+    {
+        if node.kind() == "use_declaration"
+        {
+            if first_named_child_text(node, bytes, &["visibility_modifier"]).is_some()
+                && let Some(arg) = node.child_by_field_name("argument")
+            {
+                use_tree_items(node, arg, bytes, &mut out);
+            }
+            continue;
+        }
+        let mut cursor = node.walk();
+        let children: Vec<Node> = node
+            .named_children(&mut cursor)
+            .collect();
+        stack.extend(
+            children
+                .into_iter()
+                .rev(),
+        );
+    }
+    out
+}
+
+/// Collect the items of one use tree, recursing into `{...}` lists
+fn use_tree_items<'t>(
+    decl: Node<'t>,
+    tree: Node<'t>,
+    bytes: &[u8],
+    out: &mut Vec<ReExport<'t>>,
+)
+{
+    let (path, alias) = match tree.kind()
+    {
+        "identifier" | "scoped_identifier" => (Some(tree), None),
+        "use_as_clause" => (tree.child_by_field_name("path"), tree.child_by_field_name("alias")),
+        "scoped_use_list" =>
+        {
+            if let Some(list) = tree.child_by_field_name("list")
+            {
+                use_tree_items(decl, list, bytes, out);
+            }
+            return;
+        }
+        "use_list" =>
+        {
+            let mut cursor = tree.walk();
+            let items: Vec<Node> = tree
+                .named_children(&mut cursor)
+                .collect();
+            for item in items
+            {
+                use_tree_items(decl, item, bytes, out);
+            }
+            return;
+        }
+        _ => return,
+    };
+
+    // `a::b::Name` defines `Name`; `self`, `super`, and `crate` define nothing
+    let Some(last) = path.and_then(|p| match p.kind()
+    {
+        "identifier" => Some(p),
+        "scoped_identifier" => p
+            .child_by_field_name("name")
+            .filter(|n| n.kind() == "identifier"),
+        _ => None,
+    })
+    else
+    {
+        return;
+    };
+    let Ok(original) = last.utf8_text(bytes)
+    else
+    {
+        return;
+    };
+    let exported = alias
+        .and_then(|a| {
+            a.utf8_text(bytes)
+                .ok()
+        })
+        .unwrap_or(original);
+    out.push(ReExport {
+        decl,
+        item: tree,
+        exported: exported.to_string(),
+        original: original.to_string(),
+        original_span: (last.start_byte(), last.end_byte()),
+    });
+}
+
+/// Import symbol for a re-export, spanning the whole declaration unless it
+/// lists several items
+fn reexport_symbol(
+    item: &ReExport,
+    bytes: &[u8],
+    file: &Path,
+) -> Symbol
+{
+    let node = if item
+        .decl
+        .child_by_field_name("argument")
+        == Some(item.item)
+    {
+        item.decl
+    }
+    else
+    {
+        item.item
+    };
+    let module_path = enclosing_module_path(item.decl, bytes);
+    let qualified_name = if module_path.is_empty()
+    {
+        item.exported
+            .clone()
+    }
+    else
+    {
+        build_qualified_name(&[&module_path, &item.exported])
+    };
+
+    Symbol {
+        file: file.to_path_buf(),
+        lang: "rust".to_string(),
+        kind: SymbolKind::Import,
+        name: item
+            .exported
+            .clone(),
+        qualified_name,
+        byte_start: node.start_byte(),
+        byte_end: node.end_byte(),
+        start_line: node.start_position().row + 1,
+        end_line: node.end_position().row + 1,
+        visibility: visibility_of(item.decl, bytes),
+        doc: gather_leading_rust_docs(item.decl, bytes),
+        signature: None,
+        package: None,
+        module: None,
+    }
+}
+
+/// Items declared inside `lazy_static!`, `thread_local!`, and `cfg_if!`,
+/// which Tree-sitter leaves as unparsed token trees. Each body (each branch
+/// of a `cfg_if!`) is parsed on its own and its spans shifted back in place.
+fn macro_item_symbols(
+    language: &Language,
+    items_query: &Query,
+    root: Node,
+    content: &str,
+    file: &Path,
+) -> Result<Vec<Symbol>>
+{
+    let bytes = content.as_bytes();
+    let mut out = Vec::new();
+    let mut stack = vec![root];
+    while let Some(node) = stack.pop()
+    {
+        if node.kind() == "macro_invocation"
+        {
+            let name = node
+                .child_by_field_name("macro")
+                .and_then(|m| {
+                    m.utf8_text(bytes)
+                        .ok()
+                })
+                .and_then(|m| {
+                    m.rsplit("::")
+                        .next()
+                })
+                .unwrap_or_default();
+            let mut cursor = node.walk();
+            let body = node
+                .named_children(&mut cursor)
+                .find(|c| c.kind() == "token_tree");
+            let blocks: Vec<Node> = match (name, body)
+            {
+                ("lazy_static" | "thread_local", Some(body)) => vec![body],
+                ("cfg_if", Some(body)) =>
+                {
+                    let mut cursor = body.walk();
+                    body.named_children(&mut cursor)
+                        .filter(|c| c.kind() == "token_tree" && bytes[c.start_byte()] == b'{')
+                        .collect()
+                }
+                _ => Vec::new(),
+            };
+
+            let module_path = enclosing_module_path(node, bytes);
+            for block in blocks
+            {
+                // Inside the delimiters
+                let (start, end) = (block.start_byte() + 1, block.end_byte() - 1);
+                if start >= end
+                {
+                    continue;
+                }
+                let inner = blank_static_refs(&content[start..end]);
+                let row = block
+                    .start_position()
+                    .row;
+                for mut sym in tree_sitter_extract_symbols(language, items_query, &inner, file)?
+                {
+                    sym.byte_start += start;
+                    sym.byte_end += start;
+                    sym.start_line += row;
+                    sym.end_line += row;
+                    if !module_path.is_empty() && sym.kind != SymbolKind::Method
+                    {
+                        let local = sym
+                            .qualified_name
+                            .strip_prefix("crate::")
+                            .unwrap_or(&sym.qualified_name);
+                        sym.qualified_name = build_qualified_name(&[&module_path, local]);
+                    }
+                    out.push(sym);
+                }
+            }
+            continue;
+        }
+        let mut cursor = node.walk();
+        let children: Vec<Node> = node
+            .named_children(&mut cursor)
+            .collect();
+        stack.extend(
+            children
+                .into_iter()
+                .rev(),
+        );
+    }
     Ok(out)
 }
 
+/// Blank the `ref` of lazy_static's `static ref NAME` so the body parses as
+/// plain statics; byte offsets are unchanged
+fn blank_static_refs(text: &str) -> String
+{
+    let mut out = text.to_string();
+    let mut from = 0;
+    while let Some(at) = text[from..].find("static")
+    {
+        from += at + "static".len();
+        let rest = &text[from..];
+        let gap = rest.len()
+            - rest
+                .trim_start()
+                .len();
+        if gap > 0
+            && rest[gap..].starts_with("ref")
+            && rest[gap + 3..].starts_with(char::is_whitespace)
+        {
+            let s = from + gap;
+            out.replace_range(s..s + 3, "   ");
+        }
+    }
+    out
+}
+
 // === Helpers (Tree-sitter) ===
 
 fn build_symbol(
@@ -236,6 +580,9 @@ fn build_symbol(
         end_line: end.row + 1,
         visibility,
         doc,
+        signature: None,
+        package: None,
+        module: None,
     })
 }
 
@@ -688,5 +1035,82 @@ mod a { mod b { fn f() {} } }
             );
             Ok(())
         }
+
+        #[test]
+        fn pub_use_reexports_and_aliases() -> Result<()>
+        {
+            let extractor = RustExtractor::new()?;
+            let src = r#"
+mod api {
+    /// Engine under its public name
+    pub use crate::engine::Engine as Motor;
+}
+pub use self::shapes::{Circle, square::Square as Block, *};
+use crate::private::Hidden;
+"#;
+            let file = PathBuf::from("test.rs");
+            let syms = extractor.extract_symbols(src, &file)?;
+            let motor = super::get(&syms, SymbolKind::Import, "Motor");
+            assert_eq!(motor.qualified_name, "crate::api::Motor");
+            assert_eq!(motor.visibility, Some(Visibility::Public));
+            assert_eq!(motor.doc.as_deref(), Some("Engine under its public name"));
+            assert_eq!(motor.start_line, 4);
+            super::get(&syms, SymbolKind::Import, "Circle");
+            super::get(&syms, SymbolKind::Import, "Block");
+            assert!(
+                !syms
+                    .iter()
+                    .any(|s| s.name == "Hidden" || s.name == "Square")
+            );
+
+            let rels = extractor.extract_relations(src, &file)?;
+            let aliases: Vec<(&str, &str)> = rels
+                .iter()
+                .filter(|r| r.kind == RelationKind::ReExports)
+                .map(|r| (r.source.as_str(), r.target.as_str()))
+                .collect();
+            assert_eq!(aliases, [("Motor", "Engine"), ("Block", "Square")]);
+            Ok(())
+        }
+
+        #[test]
+        fn items_inside_declarative_macros() -> Result<()>
+        {
+            let extractor = RustExtractor::new()?;
+            let src = r#"
+lazy_static! {
+    /// Shared registry
+    pub static ref REGISTRY: Mutex<Vec<u8>> = Mutex::new(Vec::new());
+}
+mod sys {
+    cfg_if::cfg_if! {
+        if #[cfg(unix)] {
+            pub fn page_size() -> usize { 4096 }
+        } else {
+            thread_local! { static DEPTH: Cell<u8> = Cell::new(0); }
+        }
+    }
+}
+"#;
+            let file = PathBuf::from("test.rs");
+            let syms = extractor.extract_symbols(src, &file)?;
+
+            let registry = super::get(&syms, SymbolKind::Variable, "REGISTRY");
+            assert_eq!(registry.visibility, Some(Visibility::Public));
+            assert_eq!(registry.doc.as_deref(), Some("Shared registry"));
+            assert_eq!(registry.start_line, 4);
+            assert!(
+                src[registry.byte_start..registry.byte_end].starts_with("pub static ref REGISTRY")
+            );
+
+            let page = super::get(&syms, SymbolKind::Function, "page_size");
+            assert_eq!(page.qualified_name, "crate::sys::page_size");
+            assert_eq!(page.start_line, 9);
+
+            let depth = super::get(&syms, SymbolKind::Variable, "DEPTH");
+            assert_eq!(depth.qualified_name, "crate::sys::DEPTH");
+            assert_eq!(&src[depth.byte_start..depth.byte_start + 13], "static DEPTH:");
+            Ok(())
+        }
     }
 }
@@ -0,0 +1,248 @@
+//! Filepath: src/parsers/shell_parser.rs
+//!
+//! Bash/sh extractor built on Tree-sitter 0.25.x, so build scripts and
+//! tooling glue index alongside the code they drive.
+//!
+//! Notes:
+//!   - Both `name() { ... }` and `function name { ... }` definitions become Functions,
+//!     wherever they appear (inside `if` blocks included). Shell functions are global
+//!     once defined, so names are never qualified.
+//!   - `source file` and `. file` commands become Imports named by the path as written,
+//!     quotes dropped (`./lib/common.sh`, `$ROOT/env.sh`).
+//!   - The `#` comment lines right above a definition become its doc; a shebang line
+//!     never does.
+
+use std::path::Path;
+
+use anyhow::{Context, Result};
+use tree_sitter::{Language, Node};
+
+use crate::{
+    core::symbols::{Symbol, SymbolExtractor, SymbolKind},
+    infra::utils::TsNodeUtils,
+};
+
+/// Extracts shell symbols (functions and sourced files).
+pub struct ShellExtractor
+{
+    /// Bash language handle for Tree-sitter.
+    language: Language,
+}
+
+impl ShellExtractor
+{
+    pub fn new() -> Result<Self>
+    {
+        Ok(Self { language: tree_sitter_bash::LANGUAGE.into() })
+    }
+}
+
+impl SymbolExtractor for ShellExtractor
+{
+    fn extract_symbols(
+        &self,
+        content: &str,
+        file_path: &Path,
+    ) -> Result<Vec<Symbol>>
+    {
+        let tree =
+            TsNodeUtils::parse(&self.language, content).context("Failed to parse shell source")?;
+
+        let mut out = Vec::new();
+        collect(tree.root_node(), content.as_bytes(), file_path, &mut out);
+        Ok(out)
+    }
+}
+
+/// Functions and `source` commands anywhere under `node`, in source order
+fn collect(
+    node: Node,
+    bytes: &[u8],
+    file: &Path,
+    out: &mut Vec<Symbol>,
+)
+{
+    let found = match node.kind()
+    {
+        "function_definition" =>
+        {
+            node.child_by_field_name("name")
+                .and_then(|n| {
+                    n.utf8_text(bytes)
+                        .ok()
+                })
+                .map(|name| (SymbolKind::Function, name.to_string()))
+        }
+        "command" => sourced_path(node, bytes).map(|path| (SymbolKind::Import, path)),
+        _ => None,
+    };
+    if let Some((kind, name)) = found
+    {
+        let start = node.start_position();
+        let end = node.end_position();
+        out.push(Symbol {
+            file: file.to_path_buf(),
+            lang: "bash".to_string(),
+            kind,
+            qualified_name: name.clone(),
+            name,
+            byte_start: node.start_byte(),
+            byte_end: node.end_byte(),
+            start_line: start.row + 1,
+            end_line: end.row + 1,
+            visibility: None,
+            doc: shell_doc(node, bytes),
+            signature: None,
+            package: None,
+            module: None,
+        });
+    }
+
+    let mut cursor = node.walk();
+    for child in node.named_children(&mut cursor)
+    {
+        collect(child, bytes, file, out);
+    }
+}
+
+/// The file a `source file` or `. file` command reads, quotes dropped
+fn sourced_path(
+    command: Node,
+    bytes: &[u8],
+) -> Option<String>
+{
+    let name = command
+        .child_by_field_name("name")?
+        .utf8_text(bytes)
+        .ok()?;
+    if !matches!(name, "source" | ".")
+    {
+        return None;
+    }
+    let arg = command
+        .child_by_field_name("argument")?
+        .utf8_text(bytes)
+        .ok()?;
+    let path = arg.trim_matches(|c| c == '"' || c == '\'');
+    (!path.is_empty()).then(|| path.to_string())
+}
+
+/// Consecutive `#` comment lines ending right above `node`
+fn shell_doc(
+    node: Node,
+    bytes: &[u8],
+) -> Option<String>
+{
+    let mut lines = Vec::new();
+    let mut row = node
+        .start_position()
+        .row;
+    let mut prev = node.prev_named_sibling();
+    while let Some(comment) = prev
+    {
+        if comment.kind() != "comment"
+            || comment
+                .end_position()
+                .row
+                + 1
+                != row
+        {
+            break;
+        }
+        let Some(line) = comment
+            .utf8_text(bytes)
+            .ok()
+            .filter(|t| !t.starts_with("#!"))
+            .and_then(|t| t.strip_prefix('#'))
+        else
+        {
+            break;
+        };
+        lines.push(line.trim());
+        row = comment
+            .start_position()
+            .row;
+        prev = comment.prev_named_sibling();
+    }
+    lines.reverse();
+    (!lines.is_empty()).then(|| lines.join("\n"))
+}
+
+#[cfg(test)]
+mod tests
+{
+    use super::*;
+
+    fn extract(src: &str) -> Vec<Symbol>
+    {
+        ShellExtractor::new()
+            .unwrap()
+            .extract_symbols(src, Path::new("build.sh"))
+            .unwrap()
+    }
+
+    fn get<'a>(
+        syms: &'a [Symbol],
+        name: &str,
+    ) -> &'a Symbol
+    {
+        syms.iter()
+            .find(|s| s.name == name)
+            .expect("symbol not found")
+    }
+
+    #[test]
+    fn functions_and_sourced_files()
+    {
+        let src = r#"#!/usr/bin/env bash
+# Shared helpers.
+source ./lib/common.sh
+. "$ROOT/env.sh"
+
+# Build it.
+# Twice if needed.
+build() {
+  echo hi
+}
+
+function deploy {
+  build
+}
+
+if [ -n "$CI" ]; then
+  ci_only() { :; }
+fi
+"#;
+        let syms = extract(src);
+        assert_eq!(syms.len(), 5);
+
+        let common = get(&syms, "./lib/common.sh");
+        assert_eq!(common.kind, SymbolKind::Import);
+        assert_eq!(
+            common
+                .doc
+                .as_deref(),
+            Some("Shared helpers.")
+        );
+        assert_eq!(get(&syms, "$ROOT/env.sh").start_line, 4);
+
+        let build = get(&syms, "build");
+        assert_eq!(build.kind, SymbolKind::Function);
+        assert_eq!((build.start_line, build.end_line), (8, 10));
+        assert_eq!(
+            build
+                .doc
+                .as_deref(),
+            Some("Build it.\nTwice if needed.")
+        );
+        assert_eq!(get(&syms, "deploy").start_line, 12);
+        assert_eq!(get(&syms, "ci_only").start_line, 17);
+    }
+
+    #[test]
+    fn shebang_is_not_a_doc()
+    {
+        let syms = extract("#!/bin/sh\nmain() { :; }\n");
+        assert_eq!(get(&syms, "main").doc, None);
+    }
+}
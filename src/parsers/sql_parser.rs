@@ -0,0 +1,341 @@
+//! Filepath: src/parsers/sql_parser.rs
+//!
+//! SQL schema extractor built on Tree-sitter 0.25.x (tree-sitter-sequel
+//! grammar), for schema files and migrations.
+//!
+//! Notes:
+//!   - `CREATE TABLE`, `CREATE VIEW`, `CREATE INDEX`, and `CREATE FUNCTION` statements
+//!     become symbols spanning the statement.
+//!   - Names are qualified as written in SQL: `public.users`, and indexes under their
+//!     table, `public.users.users_email_idx`. An unnamed index gets PostgreSQL's default
+//!     `<table>_<columns>_idx` name.
+//!   - A table's doc lists its columns; otherwise the `--` comment lines right above a
+//!     statement become its doc.
+//!   - The grammar is dialect-tolerant, and a statement it cannot read (vendor syntax in
+//!     a function body, say) only loses that statement.
+
+use std::path::Path;
+
+use anyhow::{Context, Result};
+use tree_sitter::{Language, Node};
+
+use crate::{
+    core::symbols::{Symbol, SymbolExtractor, SymbolKind},
+    infra::utils::TsNodeUtils,
+};
+
+/// Extracts SQL schema symbols (tables, views, indexes, functions).
+pub struct SqlExtractor
+{
+    /// SQL language handle for Tree-sitter.
+    language: Language,
+}
+
+impl SqlExtractor
+{
+    pub fn new() -> Result<Self>
+    {
+        Ok(Self { language: tree_sitter_sequel::LANGUAGE.into() })
+    }
+}
+
+impl SymbolExtractor for SqlExtractor
+{
+    fn extract_symbols(
+        &self,
+        content: &str,
+        file_path: &Path,
+    ) -> Result<Vec<Symbol>>
+    {
+        let tree =
+            TsNodeUtils::parse(&self.language, content).context("Failed to parse SQL source")?;
+        let bytes = content.as_bytes();
+
+        let mut out = Vec::new();
+        let root = tree.root_node();
+        let mut cursor = root.walk();
+        for statement in root
+            .named_children(&mut cursor)
+            .filter(|n| n.kind() == "statement")
+        {
+            let Some(create) = statement.named_child(0)
+            else
+            {
+                continue;
+            };
+            let found = match create.kind()
+            {
+                "create_table" =>
+                {
+                    object_name(create, bytes).map(|(name, qualified)| {
+                        (
+                            SymbolKind::Table,
+                            name,
+                            qualified,
+                            column_list(create, bytes),
+                        )
+                    })
+                }
+                "create_view" =>
+                {
+                    object_name(create, bytes)
+                        .map(|(name, qualified)| (SymbolKind::View, name, qualified, None))
+                }
+                "create_function" =>
+                {
+                    object_name(create, bytes)
+                        .map(|(name, qualified)| (SymbolKind::Function, name, qualified, None))
+                }
+                "create_index" =>
+                {
+                    index_name(create, bytes)
+                        .map(|(name, qualified)| (SymbolKind::Index, name, qualified, None))
+                }
+                _ => None,
+            };
+            let Some((kind, name, qualified_name, doc)) = found
+            else
+            {
+                continue;
+            };
+
+            let start = statement.start_position();
+            let end = statement.end_position();
+            out.push(Symbol {
+                file: file_path.to_path_buf(),
+                lang: "sql".to_string(),
+                kind,
+                name,
+                qualified_name,
+                byte_start: statement.start_byte(),
+                byte_end: statement.end_byte(),
+                start_line: start.row + 1,
+                end_line: end.row + 1,
+                visibility: None,
+                doc: doc.or_else(|| sql_doc(statement, bytes)),
+                signature: None,
+                package: None,
+                module: None,
+            });
+        }
+        Ok(out)
+    }
+}
+
+/// `(name, schema.name)` of the statement's first object reference
+fn object_name(
+    node: Node,
+    bytes: &[u8],
+) -> Option<(String, String)>
+{
+    let mut cursor = node.walk();
+    let reference = node
+        .named_children(&mut cursor)
+        .find(|c| c.kind() == "object_reference")?;
+    let name = identifier(reference.child_by_field_name("name")?, bytes)?;
+    let qualified = match reference
+        .child_by_field_name("schema")
+        .and_then(|s| identifier(s, bytes))
+    {
+        Some(schema) => format!("{schema}.{name}"),
+        None => name.clone(),
+    };
+    Some((name, qualified))
+}
+
+/// Index name (or PostgreSQL's default for an unnamed one), qualified by
+/// the indexed table
+fn index_name(
+    node: Node,
+    bytes: &[u8],
+) -> Option<(String, String)>
+{
+    let (table, table_qualified) = object_name(node, bytes)?;
+    let name = match node
+        .child_by_field_name("column")
+        .and_then(|n| identifier(n, bytes))
+    {
+        Some(name) => name,
+        None =>
+        {
+            let mut cursor = node.walk();
+            let fields = node
+                .named_children(&mut cursor)
+                .find(|c| c.kind() == "index_fields")?;
+            let mut cursor = fields.walk();
+            let columns: Vec<String> = fields
+                .named_children(&mut cursor)
+                .filter_map(|f| f.child_by_field_name("column"))
+                .filter_map(|c| identifier(c, bytes))
+                .collect();
+            format!("{table}_{}_idx", columns.join("_"))
+        }
+    };
+    let qualified = format!("{table_qualified}.{name}");
+    Some((name, qualified))
+}
+
+/// `columns: id, email` for a table with column definitions
+fn column_list(
+    node: Node,
+    bytes: &[u8],
+) -> Option<String>
+{
+    let mut cursor = node.walk();
+    let defs = node
+        .named_children(&mut cursor)
+        .find(|c| c.kind() == "column_definitions")?;
+    let mut cursor = defs.walk();
+    let columns: Vec<String> = defs
+        .named_children(&mut cursor)
+        .filter(|c| c.kind() == "column_definition")
+        .filter_map(|c| c.child_by_field_name("name"))
+        .filter_map(|n| identifier(n, bytes))
+        .collect();
+    (!columns.is_empty()).then(|| format!("columns: {}", columns.join(", ")))
+}
+
+/// Identifier text without `"`, `` ` ``, or `[]` quoting
+fn identifier(
+    node: Node,
+    bytes: &[u8],
+) -> Option<String>
+{
+    let text = node
+        .utf8_text(bytes)
+        .ok()?
+        .trim_matches(|c| matches!(c, '"' | '`' | '[' | ']'));
+    (!text.is_empty()).then(|| text.to_string())
+}
+
+/// Consecutive `--` comment lines ending right above `node`
+fn sql_doc(
+    node: Node,
+    bytes: &[u8],
+) -> Option<String>
+{
+    let mut lines = Vec::new();
+    let mut row = node
+        .start_position()
+        .row;
+    let mut prev = node.prev_named_sibling();
+    while let Some(comment) = prev
+    {
+        if comment.kind() != "comment"
+            || comment
+                .end_position()
+                .row
+                + 1
+                != row
+        {
+            break;
+        }
+        let Some(line) = comment
+            .utf8_text(bytes)
+            .ok()
+            .and_then(|t| t.strip_prefix("--"))
+        else
+        {
+            break;
+        };
+        lines.push(line.trim());
+        row = comment
+            .start_position()
+            .row;
+        prev = comment.prev_named_sibling();
+    }
+    lines.reverse();
+    (!lines.is_empty()).then(|| lines.join("\n"))
+}
+
+#[cfg(test)]
+mod tests
+{
+    use super::*;
+
+    fn extract(src: &str) -> Vec<Symbol>
+    {
+        SqlExtractor::new()
+            .unwrap()
+            .extract_symbols(src, Path::new("0001_init.sql"))
+            .unwrap()
+    }
+
+    fn get<'a>(
+        syms: &'a [Symbol],
+        name: &str,
+    ) -> &'a Symbol
+    {
+        syms.iter()
+            .find(|s| s.name == name)
+            .expect("symbol not found")
+    }
+
+    #[test]
+    fn tables_indexes_and_functions()
+    {
+        let src = r#"-- Users of the app.
+CREATE TABLE IF NOT EXISTS public.users (
+    id BIGSERIAL PRIMARY KEY,
+    email TEXT NOT NULL UNIQUE
+);
+
+CREATE UNIQUE INDEX users_email_idx ON public.users (email);
+CREATE INDEX ON users (created_at);
+
+-- Bumps updated_at.
+CREATE FUNCTION app.add(a integer, b integer) RETURNS integer AS 'select $1 + $2;' LANGUAGE SQL;
+
+CREATE VIEW active_users AS SELECT * FROM users;
+ALTER TABLE users ADD COLUMN name TEXT;
+"#;
+        let syms = extract(src);
+        assert_eq!(syms.len(), 5);
+
+        let users = get(&syms, "users");
+        assert_eq!(users.kind, SymbolKind::Table);
+        assert_eq!(users.qualified_name, "public.users");
+        assert_eq!((users.start_line, users.end_line), (2, 5));
+        assert_eq!(
+            users
+                .doc
+                .as_deref(),
+            Some("columns: id, email")
+        );
+
+        let idx = get(&syms, "users_email_idx");
+        assert_eq!(idx.kind, SymbolKind::Index);
+        assert_eq!(idx.qualified_name, "public.users.users_email_idx");
+        assert_eq!(
+            get(&syms, "users_created_at_idx").qualified_name,
+            "users.users_created_at_idx"
+        );
+
+        let add = get(&syms, "add");
+        assert_eq!(add.kind, SymbolKind::Function);
+        assert_eq!(add.qualified_name, "app.add");
+        assert_eq!(
+            add.doc
+                .as_deref(),
+            Some("Bumps updated_at.")
+        );
+        assert_eq!(get(&syms, "active_users").kind, SymbolKind::View);
+    }
+
+    #[test]
+    fn unreadable_statements_do_not_hide_the_rest()
+    {
+        let src = r#"CREATE OR REPLACE FUNCTION touch() RETURNS trigger AS $$
+BEGIN
+  NEW.updated_at = now();
+  RETURN NEW;
+END;
+$$ LANGUAGE plpgsql;
+
+CREATE TABLE `orders` (id INT);
+"#;
+        let syms = extract(src);
+        assert_eq!(get(&syms, "orders").start_line, 8);
+        assert_eq!(get(&syms, "touch").kind, SymbolKind::Function);
+    }
+}
@@ -0,0 +1,165 @@
+//! Filepath: src/parsers/typescript_parser.rs
+//!
+//! TypeScript symbol extractor built on Tree-sitter 0.25.x. Shares the
+//! JavaScript tree walk and adds interfaces, type aliases, enums,
+//! namespaces, and member accessibility. `.tsx` files use the TSX grammar.
+
+use std::path::Path;
+
+use anyhow::Result;
+use tree_sitter::Language;
+
+use crate::{
+    core::symbols::{Reference, Relation, Symbol, SymbolExtractor},
+    infra::utils::TsNodeUtils,
+    parsers::javascript_parser::{ECMA_CALLS, ECMA_RELATIONS, extract_ecma_symbols},
+};
+
+/// Extracts TypeScript symbols (functions, classes, methods, interfaces,
+/// type aliases, enums, function consts).
+pub struct TypeScriptExtractor
+{
+    typescript: Language,
+    tsx: Language,
+}
+
+impl TypeScriptExtractor
+{
+    pub fn new() -> Result<Self>
+    {
+        Ok(Self {
+            typescript: tree_sitter_typescript::LANGUAGE_TYPESCRIPT.into(),
+            tsx: tree_sitter_typescript::LANGUAGE_TSX.into(),
+        })
+    }
+}
+
+impl SymbolExtractor for TypeScriptExtractor
+{
+    fn extract_symbols(
+        &self,
+        content: &str,
+        file_path: &Path,
+    ) -> Result<Vec<Symbol>>
+    {
+        extract_ecma_symbols(
+            self.language_for(file_path),
+            "typescript",
+            content,
+            file_path,
+        )
+    }
+
+    fn extract_references(
+        &self,
+        content: &str,
+        file_path: &Path,
+    ) -> Result<Vec<Reference>>
+    {
+        TsNodeUtils::call_sites(self.language_for(file_path), content, ECMA_CALLS)
+    }
+
+    fn extract_relations(
+        &self,
+        content: &str,
+        file_path: &Path,
+    ) -> Result<Vec<Relation>>
+    {
+        TsNodeUtils::relations(self.language_for(file_path), content, ECMA_RELATIONS)
+    }
+}
+
+impl TypeScriptExtractor
+{
+    /// TSX grammar for `.tsx` files, plain TypeScript otherwise
+    fn language_for(
+        &self,
+        file_path: &Path,
+    ) -> &Language
+    {
+        let is_tsx = file_path
+            .extension()
+            .is_some_and(|ext| ext.eq_ignore_ascii_case("tsx"));
+        if is_tsx { &self.tsx } else { &self.typescript }
+    }
+}
+
+#[cfg(test)]
+mod tests
+{
+    use super::*;
+    use crate::core::symbols::{SymbolKind, Visibility};
+
+    fn extract(
+        src: &str,
+        file: &str,
+    ) -> Vec<Symbol>
+    {
+        TypeScriptExtractor::new()
+            .unwrap()
+            .extract_symbols(src, Path::new(file))
+            .unwrap()
+    }
+
+    #[test]
+    fn interfaces_types_enums_and_members()
+    {
+        let src = r#"
+export interface Shape { area(): number; }
+type Id = string;
+export enum Color { Red, Green }
+
+export abstract class Base implements Shape {
+  /** Area in square units. */
+  public area(): number { return 0; }
+  private helper(): void {}
+  protected abstract kind(): string;
+}
+
+export const make = <T,>(x: T): T => x;
+"#;
+        let syms = extract(src, "m.ts");
+        let kinds: Vec<(&str, SymbolKind)> = syms
+            .iter()
+            .map(|s| {
+                (
+                    s.name
+                        .as_str(),
+                    s.kind
+                        .clone(),
+                )
+            })
+            .collect();
+        assert_eq!(kinds, [
+            ("Shape", SymbolKind::Interface),
+            ("Id", SymbolKind::TypeAlias),
+            ("Color", SymbolKind::Enum),
+            ("Base", SymbolKind::Class),
+            ("area", SymbolKind::Method),
+            ("helper", SymbolKind::Method),
+            ("kind", SymbolKind::Method),
+            ("make", SymbolKind::Function),
+        ]);
+        let area = &syms[4];
+        assert_eq!(area.qualified_name, "Base::area");
+        assert_eq!(area.visibility, Some(Visibility::Public));
+        assert_eq!(
+            area.doc
+                .as_deref(),
+            Some("Area in square units.")
+        );
+        assert_eq!(syms[5].visibility, Some(Visibility::Private));
+        assert_eq!(syms[6].visibility, Some(Visibility::Protected));
+        assert_eq!(syms[1].visibility, None);
+    }
+
+    #[test]
+    fn tsx_components()
+    {
+        let src = "export function App() { return <div>hi</div>; }\n";
+        let syms = extract(src, "App.tsx");
+        assert_eq!(syms.len(), 1);
+        assert_eq!(syms[0].name, "App");
+        assert_eq!(syms[0].lang, "typescript");
+    }
+}
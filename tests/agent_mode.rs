@@ -0,0 +1,141 @@
+//! Integration tests for `--agent-mode` result envelopes.
+//!
+//! Verifies that stdout carries exactly one JSON envelope while human
+//! text is routed to stderr, for both successful and failing commands.
+
+use std::{fs, process::Command};
+
+use assert_cmd::prelude::*;
+use serde_json::Value;
+
+/// Parse the single stdout line as the envelope
+fn envelope(stdout: &[u8]) -> Value
+{
+    let text = String::from_utf8_lossy(stdout);
+    let lines: Vec<&str> = text
+        .lines()
+        .collect();
+    assert_eq!(
+        lines.len(),
+        1,
+        "stdout must hold exactly one line: {text:?}"
+    );
+    serde_json::from_str(lines[0]).expect("envelope is valid JSON")
+}
+
+#[test]
+fn extract_reports_output_artifact()
+{
+    let dir = tempfile::tempdir().unwrap();
+    fs::write(
+        dir.path()
+            .join("a.rs"),
+        "fn a() {}\nfn b() {}\n",
+    )
+    .unwrap();
+
+    let output = Command::cargo_bin("rup")
+        .unwrap()
+        .current_dir(dir.path())
+        .args(["--agent-mode", "extract", "a.rs:1-2", "-o", "out.txt"])
+        .output()
+        .unwrap();
+
+    assert!(
+        output
+            .status
+            .success()
+    );
+    let v = envelope(&output.stdout);
+    assert_eq!(v["command"], "extract");
+    assert_eq!(v["status"], "ok");
+    assert_eq!(v["exit_code"], 0);
+    assert_eq!(v["artifacts"][0]["kind"], "output");
+    assert_eq!(v["artifacts"][0]["path"], "out.txt");
+
+    // Human progress text went to stderr
+    assert!(String::from_utf8_lossy(&output.stderr).contains("Writing out.txt"));
+}
+
+#[test]
+fn apply_failure_maps_exit_code()
+{
+    let dir = tempfile::tempdir().unwrap();
+    fs::create_dir(
+        dir.path()
+            .join(".git"),
+    )
+    .unwrap();
+    let spec = "FILE: /etc/hosts\nINSERT at 1:\nNEW:\n```\nx\n```\n";
+    fs::write(
+        dir.path()
+            .join("edit.txt"),
+        spec,
+    )
+    .unwrap();
+
+    let output = Command::cargo_bin("rup")
+        .unwrap()
+        .current_dir(dir.path())
+        .args(["--agent-mode", "apply", "edit.txt"])
+        .output()
+        .unwrap();
+
+    assert_eq!(
+        output
+            .status
+            .code(),
+        Some(4)
+    );
+    let v = envelope(&output.stdout);
+    assert_eq!(v["status"], "error");
+    assert_eq!(v["exit_code"], 4);
+    assert!(
+        v["error"]
+            .as_str()
+            .unwrap()
+            .contains("absolute path")
+    );
+}
+
+#[test]
+fn check_syntax_error_reports_envelope()
+{
+    let dir = tempfile::tempdir().unwrap();
+    fs::write(
+        dir.path()
+            .join("a.txt"),
+        "one\n",
+    )
+    .unwrap();
+    fs::write(
+        dir.path()
+            .join("fix.ebnf"),
+        "FILE: a.txt\nREPLACE lines x-y:\n",
+    )
+    .unwrap();
+
+    let output = Command::cargo_bin("rup")
+        .unwrap()
+        .current_dir(dir.path())
+        .args(["--agent-mode", "check-syntax", "fix.ebnf"])
+        .output()
+        .unwrap();
+
+    assert_eq!(
+        output
+            .status
+            .code(),
+        Some(3)
+    );
+    let v = envelope(&output.stdout);
+    assert_eq!(v["command"], "check-syntax");
+    assert_eq!(v["status"], "error");
+    assert_eq!(v["exit_code"], 3);
+    assert!(
+        v["error"]
+            .as_str()
+            .unwrap()
+            .contains("Edit syntax error")
+    );
+}
@@ -43,7 +43,7 @@ fn anchor_good_start_json() {
       structural_importance: high
     function:
       confidence: "[redacted]"
-      end_line: 137
+      end_line: 174
       file: src/main.rs
       kind: Function
       name: main
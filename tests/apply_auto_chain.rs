@@ -0,0 +1,203 @@
+//! Integration tests for the `--engine auto` fallback chain report
+
+#![cfg(unix)]
+
+use std::{fs, path::Path, process::Command};
+
+use assert_cmd::prelude::*;
+use roughup::{
+    cli::WhitespaceMode,
+    core::{
+        apply_engine::{ApplyContext, ApplyEngine, Engine, HybridEngine},
+        edit::{EditOperation, EditSpec, FileBlock},
+        git::{CombinedConflictError, GitOptions},
+    },
+};
+
+fn git(
+    dir: &Path,
+    args: &[&str],
+)
+{
+    let status = Command::new("git")
+        .args(["-c", "user.name=Test", "-c", "user.email=test@example.com"])
+        .args(args)
+        .current_dir(dir)
+        .status()
+        .unwrap();
+    assert!(status.success(), "git {args:?} failed");
+}
+
+fn apply_json(
+    dir: &Path,
+    extra: &[&str],
+) -> serde_json::Value
+{
+    let out = Command::cargo_bin("rup")
+        .unwrap()
+        .current_dir(dir)
+        .args(["--quiet", "apply", "edit.txt", "--apply", "--json", "--engine", "auto"])
+        .args(extra)
+        .output()
+        .unwrap();
+    assert!(
+        out.status
+            .success(),
+        "{}",
+        String::from_utf8_lossy(&out.stderr)
+    );
+    serde_json::from_slice(&out.stdout).unwrap()
+}
+
+fn setup_repo() -> tempfile::TempDir
+{
+    let dir = tempfile::tempdir().unwrap();
+    fs::write(
+        dir.path()
+            .join("a.txt"),
+        "one\ntwo\n",
+    )
+    .unwrap();
+    git(dir.path(), &["init", "-q"]);
+    git(dir.path(), &["add", "."]);
+    git(dir.path(), &["commit", "-q", "-m", "init"]);
+    let spec = "FILE: a.txt\nREPLACE lines 2-2:\nOLD:\n```\ntwo\n```\nNEW:\n```\nTWO\n```\n";
+    fs::write(
+        dir.path()
+            .join("edit.txt"),
+        spec,
+    )
+    .unwrap();
+    dir
+}
+
+#[test]
+fn auto_reports_internal_success()
+{
+    let dir = setup_repo();
+    let report = apply_json(dir.path(), &[]);
+    assert_eq!(
+        report["attempts"],
+        serde_json::json!([{ "engine": "Internal", "files": ["a.txt"] }])
+    );
+}
+
+#[test]
+fn auto_reports_why_internal_handed_off_to_git()
+{
+    let dir = setup_repo();
+    // The internal engine refuses files over the size cap; git has no cap
+    let report = apply_json(dir.path(), &[
+        "--max-file-size",
+        "1",
+        "--force",
+        "--git-mode",
+        "index",
+    ]);
+    let attempts = report["attempts"]
+        .as_array()
+        .unwrap();
+    assert_eq!(attempts.len(), 2, "{report}");
+    assert_eq!(attempts[0]["engine"], "Internal");
+    assert!(
+        attempts[0]["failure"]
+            .as_str()
+            .unwrap()
+            .contains("file_too_large"),
+        "{report}"
+    );
+    assert_eq!(attempts[1]["engine"], "Git");
+    assert!(
+        attempts[1]
+            .get("failure")
+            .is_none(),
+        "{report}"
+    );
+    assert_eq!(report["engine_used"], "Auto");
+    assert_eq!(
+        fs::read_to_string(
+            dir.path()
+                .join("a.txt")
+        )
+        .unwrap(),
+        "one\nTWO\n"
+    );
+}
+
+#[test]
+fn failed_chain_names_every_engine()
+{
+    let dir = setup_repo();
+    // Neither engine can match the stale OLD text
+    let spec = EditSpec {
+        file_blocks: vec![FileBlock {
+            path: dir
+                .path()
+                .join("a.txt"),
+            operations: vec![EditOperation::Replace {
+                start_line: 2,
+                end_line: 2,
+                old_content: "stale".to_string(),
+                new_content: "TWO".to_string(),
+                guard_cid: None,
+            }],
+        }],
+    };
+    let engine = HybridEngine::new(
+        false,
+        false,
+        GitOptions {
+            repo_root: dir
+                .path()
+                .to_path_buf(),
+            ..GitOptions::default()
+        },
+        true,
+    )
+    .unwrap();
+    let ctx = ApplyContext {
+        repo_root: dir.path(),
+        backup: None,
+        whitespace: WhitespaceMode::Nowarn,
+        context_lines: 3,
+        force: false,
+    };
+
+    let err = engine
+        .apply_with_ctx(&spec, ctx)
+        .unwrap_err();
+    let combined = err
+        .downcast_ref::<CombinedConflictError>()
+        .expect("combined conflict error");
+    let engines: Vec<&Engine> = combined
+        .attempts
+        .iter()
+        .map(|a| &a.engine)
+        .collect();
+    assert!(
+        matches!(engines[..], [Engine::Internal, Engine::Git]),
+        "{combined:?}"
+    );
+    assert!(
+        combined
+            .attempts
+            .iter()
+            .all(|a| {
+                a.failure
+                    .is_some()
+            }),
+        "{combined:?}"
+    );
+    assert!(
+        combined.attempts[1]
+            .failure
+            .as_deref()
+            .unwrap()
+            .contains("git_apply_failed"),
+        "{combined:?}"
+    );
+    assert!(
+        err.to_string()
+            .contains("Git gave up")
+    );
+}
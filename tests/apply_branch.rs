@@ -0,0 +1,159 @@
+//! Integration tests for `rup apply --branch`
+//!
+//! The spec lands as a commit on the named branch; the current branch and
+//! working tree stay as they were.
+
+#![cfg(unix)]
+
+use std::{fs, path::Path, process::Command};
+
+use assert_cmd::prelude::*;
+
+fn git(
+    dir: &Path,
+    args: &[&str],
+) -> String
+{
+    let out = Command::new("git")
+        .args(args)
+        .current_dir(dir)
+        .output()
+        .unwrap();
+    assert!(
+        out.status
+            .success(),
+        "git {args:?} failed"
+    );
+    String::from_utf8_lossy(&out.stdout)
+        .trim()
+        .to_string()
+}
+
+fn setup_repo() -> tempfile::TempDir
+{
+    let dir = tempfile::tempdir().unwrap();
+    fs::write(
+        dir.path()
+            .join("a.txt"),
+        "one\ntwo\n",
+    )
+    .unwrap();
+    git(dir.path(), &["init", "-q", "-b", "main"]);
+    git(dir.path(), &["config", "user.name", "Test"]);
+    git(dir.path(), &["config", "user.email", "test@example.com"]);
+    git(dir.path(), &["add", "."]);
+    git(dir.path(), &["commit", "-q", "-m", "init"]);
+    dir
+}
+
+fn write_spec(
+    dir: &Path,
+    name: &str,
+    line: usize,
+    old: &str,
+    new: &str,
+)
+{
+    let spec = format!(
+        "FILE: a.txt\nREPLACE lines {line}-{line}:\nOLD:\n```\n{old}\n```\nNEW:\n```\n{new}\n```\n"
+    );
+    fs::write(dir.join(name), spec).unwrap();
+}
+
+fn rup_apply(
+    dir: &Path,
+    args: &[&str],
+) -> assert_cmd::assert::Assert
+{
+    Command::cargo_bin("rup")
+        .unwrap()
+        .current_dir(dir)
+        .args(["--quiet", "apply"])
+        .args(args)
+        .assert()
+}
+
+#[test]
+fn branch_apply_commits_on_branch_and_leaves_current_tree()
+{
+    let dir = setup_repo();
+    let head = git(dir.path(), &["rev-parse", "HEAD"]);
+    // Uncommitted work in the current tree is neither used nor disturbed
+    fs::write(
+        dir.path()
+            .join("a.txt"),
+        "one\ntwo\nlocal\n",
+    )
+    .unwrap();
+
+    write_spec(dir.path(), "e1.txt", 2, "two", "TWO");
+    rup_apply(dir.path(), &[
+        "e1.txt",
+        "--apply",
+        "--branch",
+        "proposal/1",
+        "-m",
+        "first",
+    ])
+    .success();
+
+    assert_eq!(git(dir.path(), &["rev-parse", "HEAD"]), head);
+    assert_eq!(
+        git(dir.path(), &["symbolic-ref", "--short", "HEAD"]),
+        "main"
+    );
+    assert_eq!(
+        fs::read_to_string(
+            dir.path()
+                .join("a.txt")
+        )
+        .unwrap(),
+        "one\ntwo\nlocal\n"
+    );
+    assert_eq!(git(dir.path(), &["show", "proposal/1:a.txt"]), "one\nTWO");
+    assert_eq!(
+        git(dir.path(), &["log", "-1", "--format=%s", "proposal/1"]),
+        "first"
+    );
+    assert_eq!(git(dir.path(), &["rev-parse", "proposal/1^"]), head);
+    assert_eq!(
+        git(dir.path(), &["worktree", "list"])
+            .lines()
+            .count(),
+        1
+    );
+
+    // Reusing the branch stacks a commit on its tip
+    write_spec(dir.path(), "e2.txt", 1, "one", "ONE");
+    let tip = git(dir.path(), &["rev-parse", "proposal/1"]);
+    rup_apply(dir.path(), &["e2.txt", "--apply", "--branch", "proposal/1"]).success();
+    assert_eq!(git(dir.path(), &["show", "proposal/1:a.txt"]), "ONE\nTWO");
+    assert_eq!(git(dir.path(), &["rev-parse", "proposal/1^"]), tip);
+}
+
+#[test]
+fn branch_preview_writes_nothing()
+{
+    let dir = setup_repo();
+    write_spec(dir.path(), "e1.txt", 2, "two", "TWO");
+    rup_apply(dir.path(), &["e1.txt", "--branch", "proposal/1"]).success();
+    assert_eq!(git(dir.path(), &["branch", "--list", "proposal/1"]), "");
+}
+
+#[test]
+fn branch_apply_refuses_checked_out_branch()
+{
+    let dir = setup_repo();
+    write_spec(dir.path(), "e1.txt", 2, "two", "TWO");
+    rup_apply(dir.path(), &["e1.txt", "--apply", "--branch", "main"]).failure();
+    rup_apply(dir.path(), &["e1.txt", "--apply", "--branch", "bad..name"]).failure();
+    assert_eq!(
+        fs::read_to_string(
+            dir.path()
+                .join("a.txt")
+        )
+        .unwrap(),
+        "one\ntwo\n"
+    );
+    assert_eq!(git(dir.path(), &["rev-list", "--count", "--all"]), "1");
+}
@@ -0,0 +1,107 @@
+//! Integration tests for `rup apply --commit`
+//!
+//! Only the files the apply touched are committed; anything else already
+//! staged stays staged.
+
+#![cfg(unix)]
+
+use std::{fs, path::Path, process::Command};
+
+use assert_cmd::prelude::*;
+
+fn git(
+    dir: &Path,
+    args: &[&str],
+) -> String
+{
+    let out = Command::new("git")
+        .args(args)
+        .current_dir(dir)
+        .output()
+        .unwrap();
+    assert!(
+        out.status
+            .success(),
+        "git {args:?} failed"
+    );
+    String::from_utf8_lossy(&out.stdout).into_owned()
+}
+
+fn setup_repo() -> tempfile::TempDir
+{
+    let dir = tempfile::tempdir().unwrap();
+    fs::write(
+        dir.path()
+            .join("a.txt"),
+        "one\ntwo\n",
+    )
+    .unwrap();
+    fs::write(
+        dir.path()
+            .join("b.txt"),
+        "bee\n",
+    )
+    .unwrap();
+    git(dir.path(), &["init", "-q"]);
+    git(dir.path(), &["config", "user.name", "Test"]);
+    git(dir.path(), &["config", "user.email", "test@example.com"]);
+    git(dir.path(), &["add", "."]);
+    git(dir.path(), &["commit", "-q", "-m", "init"]);
+
+    let spec = "FILE: a.txt\nREPLACE lines 2-2:\nOLD:\n```\ntwo\n```\nNEW:\n```\nTWO\n```\n";
+    fs::write(
+        dir.path()
+            .join("edit.txt"),
+        spec,
+    )
+    .unwrap();
+    dir
+}
+
+#[test]
+fn commits_only_touched_files_with_default_template()
+{
+    let dir = setup_repo();
+    fs::write(
+        dir.path()
+            .join("b.txt"),
+        "staged elsewhere\n",
+    )
+    .unwrap();
+    git(dir.path(), &["add", "b.txt"]);
+
+    Command::cargo_bin("rup")
+        .unwrap()
+        .current_dir(dir.path())
+        .args(["--quiet", "apply", "edit.txt", "--apply", "--backup", "--commit", "--signoff"])
+        .assert()
+        .success();
+
+    let body = git(dir.path(), &["log", "-1", "--format=%B"]);
+    assert!(body.starts_with("rup apply: update a.txt\n"), "{body}");
+    assert!(body.contains("1 file changed, +1 -1"));
+    assert!(body.contains("Backup-Session: "));
+    assert!(body.contains("Signed-off-by: Test <test@example.com>"));
+
+    let files = git(dir.path(), &["show", "--name-only", "--format=", "HEAD"]);
+    assert_eq!(files.trim(), "a.txt");
+    let staged = git(dir.path(), &["diff", "--cached", "--name-only"]);
+    assert_eq!(staged.trim(), "b.txt");
+}
+
+#[test]
+fn custom_template_drops_session_line_without_backup()
+{
+    let dir = setup_repo();
+
+    Command::cargo_bin("rup")
+        .unwrap()
+        .current_dir(dir.path())
+        .args(["--quiet", "apply", "edit.txt", "--apply", "--commit"])
+        .args(["-m", "fix: {summary}\n\nsession {session}\nvia {engine}"])
+        .assert()
+        .success();
+
+    let body = git(dir.path(), &["log", "-1", "--format=%B"]);
+    assert_eq!(body.trim(), "fix: update a.txt\n\nvia internal");
+}
@@ -0,0 +1,116 @@
+//! Integration tests for `rup apply --stage`
+//!
+//! Hunks land in the index only; the worktree keeps its content.
+
+#![cfg(unix)]
+
+use std::{fs, path::Path, process::Command};
+
+use assert_cmd::prelude::*;
+
+fn git(
+    dir: &Path,
+    args: &[&str],
+) -> String
+{
+    let out = Command::new("git")
+        .args(args)
+        .current_dir(dir)
+        .output()
+        .unwrap();
+    assert!(
+        out.status
+            .success(),
+        "git {args:?} failed"
+    );
+    String::from_utf8_lossy(&out.stdout).into_owned()
+}
+
+fn setup_repo() -> tempfile::TempDir
+{
+    let dir = tempfile::tempdir().unwrap();
+    fs::write(
+        dir.path()
+            .join("a.txt"),
+        "one\ntwo\n",
+    )
+    .unwrap();
+    git(dir.path(), &["init", "-q"]);
+    git(dir.path(), &["config", "user.name", "Test"]);
+    git(dir.path(), &["config", "user.email", "test@example.com"]);
+    git(dir.path(), &["add", "."]);
+    git(dir.path(), &["commit", "-q", "-m", "init"]);
+
+    let spec = "FILE: a.txt\nREPLACE lines 2-2:\nOLD:\n```\ntwo\n```\nNEW:\n```\nTWO\n```\n";
+    fs::write(
+        dir.path()
+            .join("edit.txt"),
+        spec,
+    )
+    .unwrap();
+    dir
+}
+
+#[test]
+fn stage_updates_index_but_not_worktree()
+{
+    for backend in ["cli", "lib"]
+    {
+        let dir = setup_repo();
+        Command::cargo_bin("rup")
+            .unwrap()
+            .current_dir(dir.path())
+            .args([
+                "--quiet",
+                "apply",
+                "edit.txt",
+                "--apply",
+                "--engine",
+                "git",
+                "--git-backend",
+                backend,
+                "--stage",
+            ])
+            .assert()
+            .success();
+
+        assert_eq!(
+            fs::read_to_string(
+                dir.path()
+                    .join("a.txt")
+            )
+            .unwrap(),
+            "one\ntwo\n"
+        );
+        assert_eq!(
+            git(dir.path(), &["show", ":a.txt"]),
+            "one\nTWO\n",
+            "backend {backend}"
+        );
+        assert_eq!(
+            git(dir.path(), &["diff", "--cached", "--name-only"]).trim(),
+            "a.txt"
+        );
+    }
+}
+
+#[test]
+fn stage_requires_git_engine()
+{
+    let dir = setup_repo();
+    Command::cargo_bin("rup")
+        .unwrap()
+        .current_dir(dir.path())
+        .args(["--quiet", "apply", "edit.txt", "--apply", "--stage"])
+        .assert()
+        .failure();
+    assert_eq!(git(dir.path(), &["diff", "--cached", "--name-only"]), "");
+    assert_eq!(
+        fs::read_to_string(
+            dir.path()
+                .join("a.txt")
+        )
+        .unwrap(),
+        "one\ntwo\n"
+    );
+}
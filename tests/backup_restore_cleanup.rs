@@ -3,8 +3,12 @@
 use std::{fs, path::Path, time::Duration};
 
 use roughup::core::{
-    backup::BackupManager,
-    backup_ops::{CleanupRequest, RestoreRequest, cleanup_sessions, restore_session},
+    backup::{BackupManager, object_path, read_session_manifest},
+    backup_crypt::is_encrypted,
+    backup_ops::{
+        CleanupRequest, DiffRequest, GcRequest, RestoreRequest, cleanup_sessions, diff_sessions,
+        gc_objects, restore_session,
+    },
 };
 use tempfile::tempdir;
 
@@ -244,8 +248,10 @@ fn test_cleanup_by_age_and_keep_latest()
     let req = CleanupRequest {
         older_than: Some("2024-01-01T00:00:00Z".to_string()),
         keep_latest: Some(2),
+        max_total_size: None,
         include_incomplete: false,
         dry_run: true,
+        protect: None,
     };
 
     let out = cleanup_sessions(repo, req).unwrap();
@@ -266,8 +272,10 @@ fn test_cleanup_by_age_and_keep_latest()
     let req2 = CleanupRequest {
         older_than: None,
         keep_latest: Some(1),
+        max_total_size: None,
         include_incomplete: true,
         dry_run: false,
+        protect: None,
     };
 
     let out2 = cleanup_sessions(repo, req2).unwrap();
@@ -291,3 +299,313 @@ fn test_cleanup_by_age_and_keep_latest()
             .exists()
     );
 }
+
+#[test]
+fn test_retention_config_prunes_after_each_session()
+{
+    let tmp = tempdir().unwrap();
+    let repo = tmp.path();
+    fs::write(repo.join("roughup.toml"), "[backup]\nkeep_latest = 2\n").unwrap();
+
+    let mut ids = Vec::new();
+    for (i, rel) in ["a.txt", "b.txt", "c.txt"]
+        .iter()
+        .enumerate()
+    {
+        write_file(repo, rel, &format!("{i}\n"));
+        ids.push(make_session(repo, rel, "apply"));
+        std::thread::sleep(Duration::from_millis(10));
+    }
+
+    let backups = repo.join(".rup/backups");
+    assert!(
+        !backups
+            .join(&ids[0])
+            .exists()
+    );
+    assert!(
+        backups
+            .join(&ids[1])
+            .exists()
+    );
+    assert!(
+        backups
+            .join(&ids[2])
+            .exists()
+    );
+
+    // The pruned session's object was collected along with it
+    let objects = fs::read_dir(backups.join("objects"))
+        .unwrap()
+        .map(|fan| {
+            fs::read_dir(
+                fan.unwrap()
+                    .path(),
+            )
+            .unwrap()
+            .count()
+        })
+        .sum::<usize>();
+    assert_eq!(objects, 2);
+}
+
+#[test]
+fn test_cleanup_by_total_size_keeps_newest()
+{
+    let tmp = tempdir().unwrap();
+    let repo = tmp.path();
+
+    write_file(repo, "big.txt", &"x".repeat(4096));
+    let s1 = make_session(repo, "big.txt", "apply");
+    std::thread::sleep(Duration::from_millis(10));
+    write_file(repo, "big.txt", &"y".repeat(4096));
+    let s2 = make_session(repo, "big.txt", "apply");
+
+    let out = cleanup_sessions(
+        repo,
+        CleanupRequest {
+            older_than: None,
+            keep_latest: None,
+            max_total_size: Some(1),
+            include_incomplete: false,
+            dry_run: false,
+            protect: None,
+        },
+    )
+    .unwrap();
+    assert_eq!(out.sessions_removed, vec![s1]);
+    assert!(
+        repo.join(".rup/backups")
+            .join(&s2)
+            .exists()
+    );
+}
+
+#[test]
+fn test_encrypted_session_restores_with_key_only()
+{
+    let tmp = tempdir().unwrap();
+    let repo = tmp.path();
+    let config = repo.join("roughup.toml");
+    fs::write(&config, "[backup]\nencryption_key = \"s3cret-test-key\"\n").unwrap();
+
+    write_file(repo, "fixtures/creds.env", "API_TOKEN=hunter2\n");
+    let sid = make_session(repo, "fixtures/creds.env", "apply");
+
+    // Nothing under .rup/backups holds the plaintext
+    let stored = fs::read(
+        repo.join(".rup/backups")
+            .join(&sid)
+            .join("fixtures/creds.env"),
+    )
+    .unwrap();
+    assert!(
+        !stored
+            .windows(7)
+            .any(|w| w == b"hunter2")
+    );
+
+    write_file(repo, "fixtures/creds.env", "API_TOKEN=changed\n");
+    let restore = |repo: &Path| {
+        restore_session(
+            repo,
+            RestoreRequest {
+                session_id: sid.clone(),
+                path: None,
+                dry_run: false,
+                force: true,
+                show_diff: false,
+                verify_checksum: true,
+                backup_current: false,
+            },
+        )
+    };
+
+    // Without the key the payload can't be read back
+    fs::write(&config, "").unwrap();
+    assert!(restore(repo).is_err());
+
+    fs::write(&config, "[backup]\nencryption_key = \"s3cret-test-key\"\n").unwrap();
+    restore(repo).unwrap();
+    assert_eq!(read_file(repo, "fixtures/creds.env"), "API_TOKEN=hunter2\n");
+}
+
+#[test]
+fn test_encrypted_session_never_shares_plaintext_objects()
+{
+    let tmp = tempdir().unwrap();
+    let repo = tmp.path();
+    let config = repo.join("roughup.toml");
+    let with_key = "[backup]\nencryption_key = \"s3cret-test-key\"\n";
+    write_file(repo, "fixtures/creds.env", "API_TOKEN=hunter2\n");
+
+    let plain_sid = make_session(repo, "fixtures/creds.env", "apply");
+    fs::write(&config, with_key).unwrap();
+    let sealed_sid = make_session(repo, "fixtures/creds.env", "apply");
+    fs::write(&config, "").unwrap();
+    let plain_again_sid = make_session(repo, "fixtures/creds.env", "apply");
+
+    let file = |sid: &str| {
+        read_session_manifest(repo, sid)
+            .unwrap()
+            .files
+            .remove(0)
+    };
+    let (plain, sealed, plain_again) =
+        (file(&plain_sid), file(&sealed_sid), file(&plain_again_sid));
+    assert!(sealed.encrypted && !plain.encrypted);
+    assert_ne!(sealed.blob, plain.blob);
+    assert_eq!(plain_again.blob, plain.blob);
+
+    // The encrypted session's object and session copy hold only ciphertext
+    let object = object_path(repo, sealed.blob.as_deref().unwrap(), true);
+    let session_copy = repo
+        .join(".rup/backups")
+        .join(&sealed_sid)
+        .join("fixtures/creds.env");
+    for stored in [fs::read(&object).unwrap(), fs::read(session_copy).unwrap()]
+    {
+        assert!(is_encrypted(&stored));
+    }
+
+    // The plaintext sessions still read back without the key
+    let object = object_path(repo, plain_again.blob.as_deref().unwrap(), false);
+    assert_eq!(fs::read(object).unwrap(), b"API_TOKEN=hunter2\n");
+
+    // gc keeps the objects of both namespaces
+    let gc = gc_objects(repo, GcRequest { dry_run: true, retention: false }).unwrap();
+    assert!(gc.objects_removed.is_empty());
+    assert_eq!(gc.objects_kept, 2);
+}
+
+#[test]
+fn test_plaintext_payload_with_magic_header_restores_verbatim()
+{
+    let tmp = tempdir().unwrap();
+    let repo = tmp.path();
+    // No key is configured, so the manifest records the payload as plaintext
+    write_file(repo, "fixtures/blob.bin", "RUPENC1\0not actually sealed\n");
+    let sid = make_session(repo, "fixtures/blob.bin", "apply");
+
+    write_file(repo, "fixtures/blob.bin", "changed\n");
+    restore_session(
+        repo,
+        RestoreRequest {
+            session_id: sid,
+            path: None,
+            dry_run: false,
+            force: true,
+            show_diff: false,
+            verify_checksum: true,
+            backup_current: false,
+        },
+    )
+    .unwrap();
+    assert_eq!(read_file(repo, "fixtures/blob.bin"), "RUPENC1\0not actually sealed\n");
+}
+
+#[test]
+fn test_backup_dir_outside_worktree()
+{
+    let tmp = tempdir().unwrap();
+    let store = tempdir().unwrap();
+    let repo = tmp.path();
+    fs::write(
+        repo.join("roughup.toml"),
+        format!(
+            "[backup]\ndir = \"{}/<repo-hash>\"\n",
+            store
+                .path()
+                .display()
+        ),
+    )
+    .unwrap();
+
+    write_file(repo, "src/lib.rs", "pub fn v1() {}\n");
+    let sid = make_session(repo, "src/lib.rs", "apply");
+
+    // Nothing lands in the worktree; the session sits under the hashed store
+    assert!(!repo.join(".rup").exists());
+    let base = roughup::core::backup::backups_dir(repo);
+    assert!(base.starts_with(store.path()));
+    assert!(
+        base.join(&sid)
+            .join("DONE")
+            .exists()
+    );
+
+    write_file(repo, "src/lib.rs", "pub fn v2() {}\n");
+    restore_session(
+        repo,
+        RestoreRequest {
+            session_id: "latest".into(),
+            path: None,
+            dry_run: false,
+            force: true,
+            show_diff: false,
+            verify_checksum: true,
+            backup_current: false,
+        },
+    )
+    .unwrap();
+    assert_eq!(read_file(repo, "src/lib.rs"), "pub fn v1() {}\n");
+}
+
+#[test]
+fn test_diff_between_sessions_and_worktree()
+{
+    let tmp = tempdir().unwrap();
+    let repo = tmp.path();
+
+    write_file(repo, "a.txt", "one\ntwo\n");
+    write_file(repo, "b.txt", "bee\n");
+    let first = {
+        let mut mgr = BackupManager::begin(repo, "apply").unwrap();
+        mgr.backup_file(Path::new("a.txt"))
+            .unwrap();
+        mgr.backup_file(Path::new("b.txt"))
+            .unwrap();
+        mgr.finalize(true)
+            .unwrap();
+        mgr.session_id()
+            .to_string()
+    };
+
+    write_file(repo, "a.txt", "one\nTWO\n");
+    write_file(repo, "c.txt", "sea\n");
+    let second = {
+        let mut mgr = BackupManager::begin(repo, "apply").unwrap();
+        mgr.backup_file(Path::new("a.txt"))
+            .unwrap();
+        mgr.backup_file(Path::new("c.txt"))
+            .unwrap();
+        mgr.finalize(true)
+            .unwrap();
+        mgr.session_id()
+            .to_string()
+    };
+
+    let res = diff_sessions(repo, DiffRequest {
+        from: first.clone(),
+        to: Some(second.clone()),
+        path: None,
+    })
+    .unwrap();
+    assert_eq!(res.to, second);
+    assert_eq!(
+        res.diffs
+            .len(),
+        1
+    );
+    let unified = &res.diffs[0].unified;
+    assert!(unified.contains("-two\n") && unified.contains("+TWO\n"), "{unified}");
+    assert_eq!(res.only_in_from, vec![Path::new("b.txt").to_path_buf()]);
+    assert_eq!(res.only_in_to, vec![Path::new("c.txt").to_path_buf()]);
+
+    // Against the worktree every file of the session is compared
+    write_file(repo, "a.txt", "one\nthree\n");
+    let res = diff_sessions(repo, DiffRequest { from: first, to: None, path: None }).unwrap();
+    assert_eq!(res.to, "worktree");
+    assert_eq!(res.unchanged, 1);
+    assert!(res.diffs[0].unified.contains("+three\n"));
+}
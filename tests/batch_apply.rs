@@ -0,0 +1,140 @@
+//! Integration tests for applying several edit specs as one batch
+
+use std::{fs, process::Command};
+
+use assert_cmd::prelude::*;
+
+fn write_spec(
+    dir: &std::path::Path,
+    name: &str,
+    file: &str,
+    line: usize,
+    old: &str,
+    new: &str,
+)
+{
+    let spec = format!(
+        "FILE: {file}\nREPLACE lines \
+         {line}-{line}:\nOLD:\n```\n{old}\n```\nNEW:\n```\n{new}\n```\n"
+    );
+    fs::write(dir.join(name), spec).unwrap();
+}
+
+#[test]
+fn batch_applies_in_one_backup_session()
+{
+    let dir = tempfile::tempdir().unwrap();
+    fs::create_dir(
+        dir.path()
+            .join(".git"),
+    )
+    .unwrap();
+    fs::write(
+        dir.path()
+            .join("a.txt"),
+        "a1\na2\n",
+    )
+    .unwrap();
+    fs::write(
+        dir.path()
+            .join("b.txt"),
+        "b1\nb2\n",
+    )
+    .unwrap();
+    write_spec(dir.path(), "one.edit", "a.txt", 1, "a1", "A1");
+    write_spec(dir.path(), "two.edit", "b.txt", 2, "b2", "B2");
+    write_spec(dir.path(), "three.edit", "a.txt", 2, "a2", "A2");
+
+    let output = Command::cargo_bin("rup")
+        .unwrap()
+        .current_dir(dir.path())
+        .args(["apply", "one.edit", "two.edit", "three.edit", "--apply", "--backup"])
+        .output()
+        .unwrap();
+    assert!(
+        output
+            .status
+            .success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    assert_eq!(
+        fs::read_to_string(
+            dir.path()
+                .join("a.txt")
+        )
+        .unwrap(),
+        "A1\nA2\n"
+    );
+    assert_eq!(
+        fs::read_to_string(
+            dir.path()
+                .join("b.txt")
+        )
+        .unwrap(),
+        "b1\nB2\n"
+    );
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("Batch: 3 specs (one.edit, two.edit, three.edit)"));
+    assert!(stdout.contains("2 files changed, 3 hunks applied"));
+
+    let sessions: Vec<_> = fs::read_dir(
+        dir.path()
+            .join(".rup/backups"),
+    )
+    .unwrap()
+    .filter_map(Result::ok)
+    .filter(|e| {
+        e.file_type()
+            .unwrap()
+            .is_dir()
+    })
+    .filter(|e| e.file_name() != "tmp" && e.file_name() != "objects")
+    .collect();
+    assert_eq!(sessions.len(), 1);
+}
+
+#[test]
+fn batch_rejects_cross_spec_overlap()
+{
+    let dir = tempfile::tempdir().unwrap();
+    fs::create_dir(
+        dir.path()
+            .join(".git"),
+    )
+    .unwrap();
+    fs::write(
+        dir.path()
+            .join("a.txt"),
+        "a1\na2\n",
+    )
+    .unwrap();
+    write_spec(dir.path(), "one.edit", "a.txt", 1, "a1", "X");
+    write_spec(dir.path(), "two.edit", "./a.txt", 1, "a1", "Y");
+
+    let output = Command::cargo_bin("rup")
+        .unwrap()
+        .current_dir(dir.path())
+        .args(["apply", "one.edit", "two.edit", "--apply"])
+        .output()
+        .unwrap();
+
+    assert!(
+        !output
+            .status
+            .success()
+    );
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("Cross-spec overlap"), "{stderr}");
+    assert!(stderr.contains("one.edit lines 1-1 overlaps two.edit lines 1-1"));
+    assert_eq!(
+        fs::read_to_string(
+            dir.path()
+                .join("a.txt")
+        )
+        .unwrap(),
+        "a1\na2\n"
+    );
+}
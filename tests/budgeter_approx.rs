@@ -0,0 +1,76 @@
+//! `--approx-tokens`: estimates pick what fits, the output is counted
+//! exactly and never exceeds the budget
+use roughup::core::budgeter::{Budgeter, Item, Priority};
+
+fn item(
+    id: &str,
+    content: &str,
+    hard: bool,
+) -> Item
+{
+    Item {
+        id: id.to_string(),
+        content: content.to_string(),
+        priority: if hard
+        {
+            Priority::high()
+        }
+        else
+        {
+            Priority::medium()
+        },
+        hard,
+        min_tokens: 40,
+    }
+}
+
+#[test]
+fn estimates_run_high_and_fits_are_counted_exactly()
+{
+    let exact = Budgeter::new("gpt-4o").expect("budgeter");
+    let approx = Budgeter::new("gpt-4o")
+        .expect("budgeter")
+        .approximate();
+
+    let source = include_str!("../src/core/budgeter.rs");
+    assert!(approx.count(source) >= exact.count(source));
+
+    let items: Vec<Item> = source
+        .split("\n\n")
+        .take(60)
+        .enumerate()
+        .map(|(i, block)| item(&format!("block:{i:02}"), block, i % 7 == 0))
+        .collect();
+    for budget in [50, 300, 1200]
+    {
+        let fit = approx
+            .fit(items.clone(), budget)
+            .expect("fit");
+        assert!(
+            fit.total_tokens <= budget,
+            "{} > {budget}",
+            fit.total_tokens
+        );
+        // Trimmed items count the token IDs kept, which re-encoding the
+        // cut text can only merge
+        for it in &fit.items
+        {
+            let recount = exact.count(&it.content);
+            if it.content == it.full_content
+            {
+                assert_eq!(it.tokens, recount, "{}", it.id);
+            }
+            else
+            {
+                assert!(it.tokens >= recount, "{}", it.id);
+            }
+        }
+        assert_eq!(
+            fit.total_tokens,
+            fit.items
+                .iter()
+                .map(|it| it.tokens)
+                .sum::<usize>()
+        );
+    }
+}
@@ -0,0 +1,184 @@
+//! Tests for the on-disk token count cache under `.rup/cache`
+
+use anyhow::Result;
+use assert_cmd::Command;
+use roughup::core::budgeter::Budgeter;
+use xxhash_rust::xxh64::xxh64;
+
+/// Cache file holding one made-up count for `text`
+fn seed(
+    dir: &std::path::Path,
+    encoding: &str,
+    text: &str,
+    count: u32,
+) -> Result<()>
+{
+    let mut bytes = b"ruptok1\n".to_vec();
+    bytes.extend_from_slice(&xxh64(text.as_bytes(), 0).to_le_bytes());
+    bytes.extend_from_slice(&count.to_le_bytes());
+    std::fs::write(dir.join(format!("tokens-{encoding}.bin")), bytes)?;
+    Ok(())
+}
+
+#[test]
+fn counts_come_from_the_cache_of_the_same_encoding() -> Result<()>
+{
+    let dir = assert_fs::TempDir::new()?;
+    seed(dir.path(), "cl100k_base", "hello world", 100)?;
+
+    let gpt4 = Budgeter::new("gpt-4")?.with_disk_cache(dir.path());
+    assert_eq!(gpt4.count("hello world"), 100);
+
+    // Claude shares the cl100k counts, scaled after the lookup
+    let claude = Budgeter::new("claude-3-5-sonnet")?.with_disk_cache(dir.path());
+    assert_eq!(claude.count("hello world"), 120);
+
+    // Other encodings keep their own file
+    let gpt4o = Budgeter::new("gpt-4o")?.with_disk_cache(dir.path());
+    assert_eq!(gpt4o.count("hello world"), 2);
+    Ok(())
+}
+
+#[test]
+fn save_writes_new_counts_for_the_next_run() -> Result<()>
+{
+    let dir = assert_fs::TempDir::new()?;
+    let cache = dir
+        .path()
+        .join("cache");
+    let file = cache.join("tokens-o200k_base.bin");
+
+    let first = Budgeter::new("gpt-4o")?.with_disk_cache(&cache);
+    first.save_cache()?;
+    assert!(!file.exists(), "nothing counted, nothing saved");
+
+    let n = first.count("fn main() {}");
+    first.save_cache()?;
+    assert_eq!(std::fs::metadata(&file)?.len(), 8 + 12);
+
+    let second = Budgeter::new("gpt-4o")?.with_disk_cache(&cache);
+    assert_eq!(second.count("fn main() {}"), n);
+
+    // A corrupt file is ignored rather than trusted
+    std::fs::write(&file, b"garbage")?;
+    let third = Budgeter::new("gpt-4o")?.with_disk_cache(&cache);
+    assert_eq!(third.count("fn main() {}"), n);
+    Ok(())
+}
+
+#[test]
+fn extract_persists_counts_unless_disabled() -> Result<()>
+{
+    let dir = assert_fs::TempDir::new()?;
+    std::fs::write(
+        dir.path()
+            .join("lib.rs"),
+        "fn a() {}\nfn b() {}\n",
+    )?;
+    let cache = dir
+        .path()
+        .join(".rup/cache/tokens-o200k_base.bin");
+
+    let extract = |extra: &[&str]| {
+        Command::cargo_bin("rup")
+            .unwrap()
+            .current_dir(dir.path())
+            .args(["extract", "lib.rs:1-2", "--budget", "100", "-o", "out.txt"])
+            .args(extra)
+            .assert()
+            .success();
+    };
+
+    extract(&["--no-token-cache"]);
+    assert!(!cache.exists());
+
+    extract(&[]);
+    assert!(cache.exists());
+    Ok(())
+}
+
+#[test]
+fn unwritable_cache_fails_save_but_not_the_run() -> Result<()>
+{
+    let dir = assert_fs::TempDir::new()?;
+    std::fs::write(
+        dir.path()
+            .join("lib.rs"),
+        "fn a() {}\n",
+    )?;
+    // A file where the cache directory belongs
+    std::fs::create_dir(
+        dir.path()
+            .join(".rup"),
+    )?;
+    std::fs::write(
+        dir.path()
+            .join(".rup/cache"),
+        "",
+    )?;
+
+    let budgeter = Budgeter::new("gpt-4o")?.with_disk_cache(
+        &dir.path()
+            .join(".rup/cache"),
+    );
+    budgeter.count("fn a() {}");
+    assert!(
+        budgeter
+            .save_cache()
+            .is_err()
+    );
+
+    let out = Command::cargo_bin("rup")?
+        .current_dir(dir.path())
+        .args(["extract", "lib.rs:1-1", "--budget", "100", "-o", "out.txt"])
+        .assert()
+        .success()
+        .get_output()
+        .clone();
+    assert!(String::from_utf8_lossy(&out.stderr).contains("Token cache not saved"));
+    assert!(
+        dir.path()
+            .join("out.txt")
+            .exists()
+    );
+    Ok(())
+}
+
+#[test]
+fn cache_lives_at_the_repository_root() -> Result<()>
+{
+    let dir = assert_fs::TempDir::new()?;
+    std::fs::create_dir_all(
+        dir.path()
+            .join(".git"),
+    )?;
+    std::fs::create_dir_all(
+        dir.path()
+            .join("sub"),
+    )?;
+    std::fs::write(
+        dir.path()
+            .join("sub/lib.rs"),
+        "fn a() {}\n",
+    )?;
+
+    Command::cargo_bin("rup")?
+        .current_dir(
+            dir.path()
+                .join("sub"),
+        )
+        .args(["extract", "lib.rs:1-1", "--budget", "100", "-o", "out.txt"])
+        .assert()
+        .success();
+    assert!(
+        dir.path()
+            .join(".rup/cache/tokens-o200k_base.bin")
+            .exists()
+    );
+    assert!(
+        !dir.path()
+            .join("sub/.rup")
+            .exists()
+    );
+    Ok(())
+}
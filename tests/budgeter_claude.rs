@@ -0,0 +1,37 @@
+//! Claude models are budgeted with cl100k scaled up, so counts and trims
+//! stay within the Claude budget instead of erroring
+use roughup::core::budgeter::{Budgeter, CLAUDE_TOKENS_PER_100_CL100K, Item, Priority};
+
+#[test]
+fn claude_counts_scale_cl100k_and_trims_stay_within_budget()
+{
+    let claude = Budgeter::new("claude-3-5-sonnet").expect("claude budgeter");
+    let cl100k = Budgeter::new("cl100k_base").expect("cl100k budgeter");
+    assert!(Budgeter::new("Claude-Sonnet-4").is_ok());
+
+    let text = "pub fn parse_config(path: &Path) -> Result<Config> { todo!() }\n".repeat(20);
+    let base = cl100k.count(&text);
+    assert_eq!(
+        claude.count(&text),
+        (base * CLAUDE_TOKENS_PER_100_CL100K).div_ceil(100)
+    );
+    assert!(claude.count(&text) > base);
+
+    let item = Item {
+        id: "src/config.rs:1-20".to_string(),
+        content: text,
+        priority: Priority::high(),
+        hard: true,
+        min_tokens: 50,
+    };
+    let fit = claude
+        .fit(vec![item], 100)
+        .expect("fit");
+    assert!(fit.total_tokens <= 100, "{}", fit.total_tokens);
+    assert!(
+        fit.items[0]
+            .content
+            .ends_with("…\n")
+    );
+    assert_eq!(claude.count(&fit.items[0].content), fit.items[0].tokens);
+}
@@ -0,0 +1,160 @@
+//! Tests for the budget decision report behind `--explain-budget`
+
+use std::collections::HashSet;
+
+use anyhow::Result;
+use assert_cmd::Command;
+use roughup::core::budgeter::{
+    BucketCaps, BudgetDecision, BudgetReason, Budgeter, DedupeConfig, Item, Priority, SpanTag,
+    TaggedItem, fit_with_buckets,
+};
+
+fn item(
+    id: &str,
+    content: &str,
+    priority: Priority,
+) -> Item
+{
+    Item {
+        id: id.to_string(),
+        content: content.to_string(),
+        priority,
+        hard: false,
+        min_tokens: 4,
+    }
+}
+
+fn body(name: &str) -> String
+{
+    (0..20)
+        .map(|i| format!("let {name}_{i} = compute_{name}({i});\n"))
+        .collect()
+}
+
+#[test]
+fn report_explains_kept_trimmed_deduped_and_dropped_items() -> Result<()>
+{
+    let budgeter = Budgeter::new("gpt-4o")?;
+    let items = vec![
+        item("a", &body("alpha"), Priority::high()),
+        item("a_copy", &body("alpha"), Priority::medium()),
+        item("b", &body("beta"), Priority::medium()),
+        item(
+            "c",
+            "/// Unrelated docs about configuration files\n",
+            Priority::low(),
+        ),
+    ];
+    let a_tokens = budgeter.count(&body("alpha"));
+
+    let budget = a_tokens + 4;
+
+    let (fit, report) = budgeter.fit_with_report(items, budget, Some(DedupeConfig::default()))?;
+    assert_eq!(report.total_tokens, fit.total_tokens);
+    assert!(fit.total_tokens <= budget);
+
+    let entry = |id: &str| {
+        report
+            .entries
+            .iter()
+            .find(|e| e.id == id)
+            .unwrap()
+            .clone()
+    };
+    assert_eq!(entry("a").decision, BudgetDecision::Kept);
+    assert_eq!(entry("a").kept_tokens, a_tokens);
+    assert_eq!(entry("a_copy").reason, Some(BudgetReason::Dedupe));
+    assert_eq!(entry("b").decision, BudgetDecision::Trimmed);
+    assert!(entry("b").kept_tokens < entry("b").tokens);
+    assert_eq!(entry("c").decision, BudgetDecision::Dropped);
+    assert_eq!(entry("c").reason, Some(BudgetReason::Budget));
+
+    // Entries come in priority order and the text form summarizes them
+    assert_eq!(report.entries[0].id, "a");
+    assert!(
+        report
+            .render()
+            .starts_with(&format!(
+                "Budget: {} of {} tokens; 1 kept, 1 trimmed, 2 dropped\n",
+                fit.total_tokens, budget
+            ))
+    );
+    Ok(())
+}
+
+#[test]
+fn bucket_report_names_bucket_and_cap() -> Result<()>
+{
+    let budgeter = Budgeter::new("gpt-4o")?;
+    let tagged = |id: &str, tag: SpanTag| {
+        let mut it = TaggedItem::from(item(id, &body(id), Priority::medium()));
+        it.tags = HashSet::from([tag]);
+        it
+    };
+    let candidates = vec![
+        tagged("code_one", SpanTag::Code),
+        tagged("code_two", SpanTag::Code),
+        tagged("iface", SpanTag::Interface),
+    ];
+    let caps = BucketCaps {
+        code: budgeter.count(&body("code_one")) + 2,
+        interfaces: 400,
+        tests: 0,
+    };
+
+    let fit = fit_with_buckets(&budgeter, candidates.clone(), caps.clone(), None)?;
+    let report = fit.report(&budgeter, &candidates, &caps);
+    let json = serde_json::to_value(&report)?;
+    let entries = json["entries"]
+        .as_array()
+        .unwrap();
+
+    let by_id = |id: &str| {
+        entries
+            .iter()
+            .find(|e| e["id"] == id)
+            .unwrap()
+    };
+    assert_eq!(by_id("code_one")["decision"], "kept");
+    assert_eq!(by_id("code_one")["bucket"], "code");
+    assert_eq!(by_id("code_two")["decision"], "dropped");
+    assert_eq!(by_id("code_two")["reason"], "bucket-cap");
+    assert_eq!(by_id("iface")["bucket"], "interfaces");
+    assert!(
+        by_id("iface")
+            .get("reason")
+            .is_none()
+    );
+    Ok(())
+}
+
+#[test]
+fn explain_budget_json_goes_to_stderr() -> Result<()>
+{
+    let dir = assert_fs::TempDir::new()?;
+    let src = dir
+        .path()
+        .join("lib.rs");
+    std::fs::write(&src, body("delta"))?;
+
+    let out = Command::cargo_bin("rup")?
+        .current_dir(dir.path())
+        .args(["extract", "lib.rs:1-20", "--budget", "30", "--explain-budget", "json"])
+        .args(["--output", "out.txt"])
+        .assert()
+        .success()
+        .get_output()
+        .clone();
+
+    let stderr = String::from_utf8(out.stderr)?;
+    let line = stderr
+        .lines()
+        .find(|l| l.starts_with('{'))
+        .expect("json report on stderr");
+    let report: serde_json::Value = serde_json::from_str(line)?;
+    assert_eq!(report["budget"], 30);
+    assert_eq!(report["entries"][0]["id"], "lib.rs:1-20");
+    assert_eq!(report["entries"][0]["decision"], "dropped");
+    assert_eq!(report["entries"][0]["reason"], "budget");
+    Ok(())
+}
@@ -0,0 +1,88 @@
+//! A HuggingFace `tokenizer.json` given as `--tokenizer-file` replaces the
+//! model's tokenizer for counting and trimming
+use std::process::Command;
+
+use assert_cmd::prelude::*;
+use assert_fs::prelude::*;
+use roughup::core::budgeter::{Budgeter, Item, Priority};
+
+/// Word-level tokenizer: one token per whitespace-separated word
+const TOKENIZER_JSON: &str = r#"{
+  "version": "1.0",
+  "truncation": null,
+  "padding": null,
+  "added_tokens": [],
+  "normalizer": null,
+  "pre_tokenizer": { "type": "Whitespace" },
+  "post_processor": null,
+  "decoder": null,
+  "model": {
+    "type": "WordLevel",
+    "vocab": { "[UNK]": 0, "alpha": 1, "beta": 2, "gamma": 3 },
+    "unk_token": "[UNK]"
+  }
+}"#;
+
+#[test]
+fn tokenizer_file_counts_and_trims()
+{
+    let tmp = assert_fs::TempDir::new().expect("tempdir");
+    let file = tmp.child("tokenizer.json");
+    file.write_str(TOKENIZER_JSON)
+        .expect("write tokenizer");
+
+    let budgeter = Budgeter::for_model("llama-3", Some(file.path())).expect("hf budgeter");
+    assert_eq!(budgeter.count("alpha beta gamma delta"), 4);
+    assert_eq!(budgeter.count("alphabeta"), 1);
+
+    let item = Item {
+        id: "notes".to_string(),
+        content: "alpha beta gamma ".repeat(10),
+        priority: Priority::high(),
+        hard: true,
+        min_tokens: 5,
+    };
+    let fit = budgeter
+        .fit(vec![item], 12)
+        .expect("fit");
+    assert!(fit.total_tokens <= 12, "{}", fit.total_tokens);
+    assert!(fit.items[0].tokens < 30);
+
+    assert!(
+        Budgeter::for_model(
+            "gpt-4o",
+            Some(
+                &tmp.path()
+                    .join("missing.json")
+            )
+        )
+        .is_err()
+    );
+}
+
+#[test]
+fn tree_counts_with_the_tokenizer_file()
+{
+    let tmp = assert_fs::TempDir::new().expect("tempdir");
+    tmp.child("tokenizer.json")
+        .write_str(TOKENIZER_JSON)
+        .expect("write tokenizer");
+    tmp.child("src/notes.txt")
+        .write_str("alpha beta gamma alpha beta\n")
+        .expect("write notes");
+
+    let out = Command::cargo_bin("rup")
+        .expect("bin")
+        .current_dir(tmp.path())
+        .args(["--no-color", "tree", "src", "--tokens", "--tokenizer-file", "tokenizer.json"])
+        .output()
+        .expect("run rup");
+    let stdout = String::from_utf8_lossy(&out.stdout);
+    assert!(
+        out.status
+            .success(),
+        "{}",
+        String::from_utf8_lossy(&out.stderr)
+    );
+    assert!(stdout.contains("5 tok"), "{stdout}");
+}
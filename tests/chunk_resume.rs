@@ -0,0 +1,329 @@
+//! Integration tests for `rup chunk` over a directory: the manifest maps
+//! chunk IDs to spans and files, `--resume` keeps finished sources,
+//! `--partition` routes chunk files into subdirectories, filters skip
+//! files, and parallel chunking keeps source order
+
+use std::process::Command;
+
+use assert_cmd::prelude::*;
+use assert_fs::prelude::*;
+use serde_json::Value;
+
+mod util;
+use util::read_jsonl;
+
+fn make_sources() -> assert_fs::TempDir
+{
+    let tmp = assert_fs::TempDir::new().expect("tempdir");
+    for (name, value) in [("a", 5101), ("b", 5202)]
+    {
+        tmp.child(format!("src/{name}.rs"))
+            .write_str(&format!("pub fn {name}() -> u32\n{{\n    {value}\n}}\n"))
+            .expect("write source");
+    }
+    tmp
+}
+
+fn rup(
+    tmp: &assert_fs::TempDir,
+    args: &[&str],
+) -> std::process::Output
+{
+    Command::cargo_bin("rup")
+        .expect("bin")
+        .current_dir(tmp.path())
+        .args(args)
+        .output()
+        .expect("run rup")
+}
+
+#[test]
+fn resume_keeps_finished_sources_and_rechunks_the_rest()
+{
+    let tmp = make_sources();
+    let out = tmp
+        .path()
+        .join("out");
+    assert!(
+        rup(&tmp, &["--quiet", "chunk", "src", "-o", "out"])
+            .status
+            .success()
+    );
+    let first = read_jsonl(&out.join("chunks_manifest.jsonl"));
+    assert_eq!(first.len(), 2);
+    assert_eq!(first[0]["source_file"], "src/a.rs");
+    assert_eq!(first[0]["file"], "chunk_001.txt");
+    assert_eq!(first[0]["start_line"], 1);
+    assert_eq!(first[0]["end_line"], 4);
+    assert!(first[1]["source_digest"].is_string());
+    let text = std::fs::read_to_string(out.join("chunk_002.txt")).expect("chunk file");
+    assert!(text.contains("5202"), "{text}");
+
+    // Only b changed since; a's chunk is kept as written
+    tmp.child("src/b.rs")
+        .write_str("pub fn b() -> u32\n{\n    5303\n}\n")
+        .expect("edit b");
+    let kept = std::fs::metadata(out.join("chunk_001.txt"))
+        .and_then(|m| m.modified())
+        .expect("mtime");
+    std::thread::sleep(std::time::Duration::from_millis(20));
+    assert!(
+        rup(&tmp, &["--quiet", "chunk", "src", "-o", "out", "--resume"])
+            .status
+            .success()
+    );
+
+    let second = read_jsonl(&out.join("chunks_manifest.jsonl"));
+    assert_eq!(second.len(), 2);
+    assert_eq!(second[0], first[0]);
+    assert_eq!(second[1]["source_file"], "src/b.rs");
+    assert_eq!(second[1]["chunk_id"], 2);
+    assert_ne!(second[1]["id"], first[1]["id"]);
+    let after = std::fs::metadata(out.join("chunk_001.txt"))
+        .and_then(|m| m.modified())
+        .expect("mtime");
+    assert_eq!(kept, after);
+    let text = std::fs::read_to_string(out.join("chunk_002.txt")).expect("chunk file");
+    assert!(text.contains("5303"), "{text}");
+
+    let changes: Value = serde_json::from_str(
+        &std::fs::read_to_string(out.join("chunks_changes.json")).expect("changes"),
+    )
+    .expect("changes json");
+    assert_eq!(changes["added"], serde_json::json!([second[1]["id"]]));
+    assert_eq!(changes["removed"], serde_json::json!([first[1]["id"]]));
+    assert_eq!(changes["unchanged"], 1);
+
+    let jsonl = rup(&tmp, &["chunk", "src", "--format", "jsonl", "--resume"]);
+    assert!(
+        !jsonl
+            .status
+            .success()
+    );
+}
+
+#[test]
+fn jsonl_since_prints_new_chunks_and_removals()
+{
+    let tmp = make_sources();
+    let out = rup(&tmp, &["chunk", "src", "--format", "jsonl"]);
+    assert!(
+        out.status
+            .success()
+    );
+    tmp.child("earlier.jsonl")
+        .write_binary(&out.stdout)
+        .expect("write earlier");
+    let earlier: Vec<Value> = String::from_utf8_lossy(&out.stdout)
+        .lines()
+        .map(|l| serde_json::from_str(l).expect("json line"))
+        .collect();
+    assert_eq!(earlier.len(), 2);
+    assert_eq!(earlier[1]["path"], "src/b.rs");
+
+    tmp.child("src/b.rs")
+        .write_str("pub fn b() -> u32\n{\n    5404\n}\n")
+        .expect("edit b");
+    let out = rup(&tmp, &[
+        "chunk",
+        "src",
+        "--format",
+        "jsonl",
+        "--since",
+        "earlier.jsonl",
+    ]);
+    let records: Vec<Value> = String::from_utf8_lossy(&out.stdout)
+        .lines()
+        .map(|l| serde_json::from_str(l).expect("json line"))
+        .collect();
+    assert_eq!(records.len(), 2, "{records:?}");
+    assert!(
+        records[0]["text"]
+            .as_str()
+            .is_some_and(|t| t.contains("5404"))
+    );
+    assert_eq!(records[1]["id"], earlier[1]["id"]);
+    assert_eq!(records[1]["removed"], true);
+}
+
+#[test]
+fn partitions_route_chunk_files_into_subdirectories()
+{
+    let tmp = make_sources();
+    tmp.child("docs/guide.md")
+        .write_str("# Guide\n\nRead me.\n")
+        .expect("write guide");
+    tmp.child("notes.txt")
+        .write_str("loose notes\n")
+        .expect("write notes");
+
+    assert!(
+        rup(&tmp, &[
+            "--quiet",
+            "chunk",
+            ".",
+            "--out-dir",
+            "out",
+            "--partition",
+            "top-dir"
+        ])
+        .status
+        .success()
+    );
+    let files: Vec<String> = read_jsonl(
+        &tmp.path()
+            .join("out/chunks_manifest.jsonl"),
+    )
+    .iter()
+    .map(|r| {
+        r["file"]
+            .as_str()
+            .expect("file")
+            .to_string()
+    })
+    .collect();
+    assert!(
+        files
+            .iter()
+            .any(|f| f.starts_with("docs/")),
+        "{files:?}"
+    );
+    assert!(
+        files
+            .iter()
+            .any(|f| f.starts_with("src/")),
+        "{files:?}"
+    );
+    assert!(
+        files
+            .iter()
+            .any(|f| f.starts_with("_root/")),
+        "{files:?}"
+    );
+    for f in &files
+    {
+        assert!(
+            tmp.path()
+                .join("out")
+                .join(f)
+                .exists(),
+            "{f}"
+        );
+    }
+
+    assert!(
+        rup(&tmp, &[
+            "--quiet",
+            "chunk",
+            "src",
+            "-o",
+            "by-lang",
+            "--partition",
+            "lang"
+        ])
+        .status
+        .success()
+    );
+    assert!(
+        tmp.path()
+            .join("by-lang/rust/chunk_001.txt")
+            .exists()
+    );
+    assert!(
+        !rup(&tmp, &[
+            "chunk",
+            "src",
+            "--format",
+            "jsonl",
+            "--partition",
+            "lang"
+        ])
+        .status
+        .success()
+    );
+}
+
+#[test]
+fn parallel_chunking_keeps_source_order()
+{
+    let tmp = make_sources();
+    for i in 0..12
+    {
+        tmp.child(format!("src/m{i:02}.rs"))
+            .write_str(&format!("pub fn m{i}() -> u32\n{{\n    {i}\n}}\n"))
+            .expect("write source");
+    }
+    let wide = rup(&tmp, &["chunk", "src", "--format", "jsonl"]);
+    let narrow = rup(&tmp, &[
+        "chunk",
+        "src",
+        "--format",
+        "jsonl",
+        "--in-flight-mb",
+        "1",
+    ]);
+    assert!(
+        wide.status
+            .success()
+    );
+    assert_eq!(wide.stdout, narrow.stdout);
+    let paths: Vec<String> = String::from_utf8_lossy(&wide.stdout)
+        .lines()
+        .map(|l| {
+            serde_json::from_str::<Value>(l).expect("json line")["path"]
+                .as_str()
+                .expect("path")
+                .to_string()
+        })
+        .collect();
+    let mut sorted = paths.clone();
+    sorted.sort();
+    assert_eq!(paths.len(), 14);
+    assert_eq!(paths, sorted);
+}
+
+#[test]
+fn filters_skip_paths_languages_and_sizes()
+{
+    let tmp = make_sources();
+    tmp.child("vendor/dep.rs")
+        .write_str("pub fn dep() {}\n")
+        .expect("write vendored");
+    tmp.child("docs/guide.md")
+        .write_str("# Guide\n\nRead me.\n")
+        .expect("write guide");
+    tmp.child("src/big.rs")
+        .write_str(&"// filler line for a large generated file\n".repeat(100))
+        .expect("write big");
+    let paths = |args: &[&str]| -> Vec<String> {
+        let mut all = vec!["chunk", ".", "--format", "jsonl"];
+        all.extend(args);
+        let out = rup(&tmp, &all);
+        assert!(
+            out.status
+                .success()
+        );
+        let mut paths: Vec<String> = String::from_utf8_lossy(&out.stdout)
+            .lines()
+            .map(|l| {
+                serde_json::from_str::<Value>(l).expect("json line")["path"]
+                    .as_str()
+                    .expect("path")
+                    .trim_start_matches("./")
+                    .to_string()
+            })
+            .collect();
+        paths.dedup();
+        paths
+    };
+
+    assert_eq!(
+        paths(&["--exclude", "vendor", "--lang", "rs", "--max-file-bytes", "1000"]),
+        ["src/a.rs", "src/b.rs"]
+    );
+    assert_eq!(paths(&["--include", "docs/**"]), ["docs/guide.md"]);
+    assert_eq!(
+        paths(&["--lang", "markdown,rust", "--min-tokens", "200"]),
+        ["src/big.rs"]
+    );
+}
@@ -1,5 +1,5 @@
 use clap::Parser;
-use roughup::cli::{Cli, Commands, ContextArgs}; // adjust crate path if needed
+use roughup::cli::{ApplyArgs, Cli, Commands, ContextArgs}; // adjust crate path if needed
 
 #[test]
 fn fail_signal_flag_parsing() {
@@ -29,4 +29,45 @@ fn fail_signal_flag_parsing() {
         }
         _ => panic!("expected Context command"),
     }
-}
\ No newline at end of file
+}
+
+#[test]
+fn engine_flag_accepts_registered_names() {
+    // Engine names resolve through the registry at run time, not in clap
+    let cmd = Cli::parse_from(["rup", "apply", "--engine", "jj", "edit.txt"]);
+
+    match cmd.command {
+        Commands::Apply(ApplyArgs { engine, list_engines, .. }) => {
+            assert_eq!(engine, "jj");
+            assert!(!list_engines);
+        }
+        _ => panic!("expected Apply command"),
+    }
+}
+
+#[test]
+fn backup_list_columns_split_on_commas() {
+    use roughup::cli::{BackupListColumn, BackupListFormat, BackupSubcommand};
+
+    let cmd = Cli::parse_from(["rup", "backup", "list", "--format", "csv", "--columns", "id,label"]);
+    let Commands::Backup(args) = cmd.command else {
+        panic!("expected Backup command");
+    };
+    match args.command {
+        BackupSubcommand::List(list) => {
+            assert_eq!(list.format, BackupListFormat::Csv);
+            assert_eq!(list.columns, vec![BackupListColumn::Id, BackupListColumn::Label]);
+        }
+        _ => panic!("expected List subcommand"),
+    }
+
+    // Defaults go through the same delimiter
+    let cmd = Cli::parse_from(["rup", "backup", "list"]);
+    let Commands::Backup(args) = cmd.command else {
+        panic!("expected Backup command");
+    };
+    let BackupSubcommand::List(list) = args.command else {
+        panic!("expected List subcommand");
+    };
+    assert_eq!(list.columns.len(), 6);
+}
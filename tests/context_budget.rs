@@ -200,6 +200,9 @@ fn test_enhanced_priority_system()
         end_line: 15,
         visibility: Some(Visibility::Public),
         doc: None,
+        signature: None,
+        package: None,
+        module: None,
     };
 
     let anchor = PathBuf::from("src/lib.rs");
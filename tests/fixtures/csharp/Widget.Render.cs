@@ -0,0 +1,10 @@
+namespace Acme.Widgets
+{
+    partial class Widget
+    {
+        public string Render()
+        {
+            return $"<div id='{id}'>{Title}</div>";
+        }
+    }
+}
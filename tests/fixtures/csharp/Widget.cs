@@ -0,0 +1,22 @@
+namespace Acme.Widgets;
+
+/// <summary>
+/// A widget shown on the dashboard.
+/// </summary>
+public partial class Widget
+{
+    private readonly string id;
+    public const int MaxSize = 64;
+
+    public Widget(string id)
+    {
+        this.id = id;
+    }
+
+    public string Title { get; set; } = "";
+}
+
+public partial class Widget
+{
+    public int Size => MaxSize / 2;
+}
@@ -0,0 +1,209 @@
+//! Integration tests for `--git-backend lib`
+//!
+//! The libgit2 backend applies patches with no `git` binary on PATH.
+
+#![cfg(unix)]
+
+use std::{fs, path::Path, process::Command};
+
+use assert_cmd::prelude::*;
+
+fn git(
+    dir: &Path,
+    args: &[&str],
+)
+{
+    let ok = Command::new("git")
+        .args(["-c", "user.name=Test", "-c", "user.email=test@example.com"])
+        .args(args)
+        .current_dir(dir)
+        .output()
+        .unwrap()
+        .status
+        .success();
+    assert!(ok, "git {args:?} failed");
+}
+
+fn setup_repo(spec: &str) -> tempfile::TempDir
+{
+    let dir = tempfile::tempdir().unwrap();
+    fs::write(
+        dir.path()
+            .join("a.txt"),
+        "one\ntwo\nthree\n",
+    )
+    .unwrap();
+    git(dir.path(), &["init", "-q"]);
+    git(dir.path(), &["add", "."]);
+    git(dir.path(), &["commit", "-q", "-m", "init"]);
+    fs::write(
+        dir.path()
+            .join("edit.txt"),
+        spec,
+    )
+    .unwrap();
+    dir
+}
+
+/// `rup apply` with the libgit2 backend and an empty PATH
+fn rup_lib(dir: &Path) -> Command
+{
+    let mut cmd = Command::cargo_bin("rup").unwrap();
+    cmd.current_dir(dir)
+        .env("PATH", "")
+        .args([
+            "--quiet",
+            "apply",
+            "edit.txt",
+            "--apply",
+            "--engine",
+            "git",
+            "--git-backend",
+            "lib",
+        ]);
+    cmd
+}
+
+#[test]
+fn applies_without_git_binary()
+{
+    let spec = "FILE: a.txt\nREPLACE lines 2-2:\nOLD:\n```\ntwo\n```\nNEW:\n```\nTWO\n```\n";
+    let dir = setup_repo(spec);
+
+    rup_lib(dir.path())
+        .assert()
+        .success();
+
+    assert_eq!(
+        fs::read_to_string(
+            dir.path()
+                .join("a.txt")
+        )
+        .unwrap(),
+        "one\nTWO\nthree\n"
+    );
+}
+
+#[test]
+fn stale_preimage_is_a_conflict()
+{
+    let spec = "FILE: a.txt\nREPLACE lines 2-2:\nOLD:\n```\ntwo\n```\nNEW:\n```\nTWO\n```\n";
+    let dir = setup_repo(spec);
+    fs::write(
+        dir.path()
+            .join("a.txt"),
+        "one\nchanged\nthree\n",
+    )
+    .unwrap();
+
+    let output = rup_lib(dir.path())
+        .output()
+        .unwrap();
+
+    assert!(
+        !output
+            .status
+            .success()
+    );
+    assert_eq!(
+        fs::read_to_string(
+            dir.path()
+                .join("a.txt")
+        )
+        .unwrap(),
+        "one\nchanged\nthree\n"
+    );
+}
+
+const SEVEN_LINES: &str = "one\ntwo\nthree\nfour\nfive\nsix\nseven\n";
+
+/// Repo whose committed a.txt has seven lines and an unstaged edit to
+/// `line`, with a spec replacing line 2
+fn setup_unstaged(
+    line: usize,
+    edited: &str,
+) -> tempfile::TempDir
+{
+    let spec = "FILE: a.txt\nREPLACE lines 2-2:\nOLD:\n```\ntwo\n```\nNEW:\n```\nTWO\n```\n";
+    let dir = setup_repo(spec);
+    let a = dir
+        .path()
+        .join("a.txt");
+    fs::write(&a, SEVEN_LINES).unwrap();
+    git(dir.path(), &["commit", "-q", "-am", "seven"]);
+    let mut lines: Vec<&str> = SEVEN_LINES
+        .lines()
+        .collect();
+    lines[line - 1] = edited;
+    fs::write(&a, lines.join("\n") + "\n").unwrap();
+    dir
+}
+
+fn index_blob(dir: &Path) -> String
+{
+    let out = Command::new("git")
+        .args(["show", ":a.txt"])
+        .current_dir(dir)
+        .output()
+        .unwrap();
+    String::from_utf8(out.stdout).unwrap()
+}
+
+#[test]
+fn three_way_merges_around_unstaged_edits()
+{
+    let dir = setup_unstaged(7, "SEVEN");
+
+    rup_lib(dir.path())
+        .assert()
+        .success();
+
+    assert_eq!(
+        fs::read_to_string(
+            dir.path()
+                .join("a.txt")
+        )
+        .unwrap(),
+        "one\nTWO\nthree\nfour\nfive\nsix\nSEVEN\n"
+    );
+    // The index takes the edit alone; the local change stays unstaged
+    assert_eq!(
+        index_blob(dir.path()),
+        "one\nTWO\nthree\nfour\nfive\nsix\nseven\n"
+    );
+}
+
+#[test]
+fn three_way_reports_overlapping_edits()
+{
+    let dir = setup_unstaged(3, "THREE");
+
+    let output = rup_lib(dir.path())
+        .output()
+        .unwrap();
+
+    assert!(
+        !output
+            .status
+            .success()
+    );
+    assert_eq!(index_blob(dir.path()), SEVEN_LINES);
+}
+
+#[test]
+fn index_mode_has_no_three_way()
+{
+    let dir = setup_unstaged(7, "SEVEN");
+
+    let output = rup_lib(dir.path())
+        .args(["--git-mode", "index"])
+        .output()
+        .unwrap();
+
+    assert!(
+        !output
+            .status
+            .success()
+    );
+    assert_eq!(index_blob(dir.path()), SEVEN_LINES);
+}
@@ -0,0 +1,187 @@
+//! Integration tests for `rup git install-hooks`
+//!
+//! The installed pre-commit hook calls back into `rup`, so the test puts
+//! the built binary on PATH for the `git commit` it runs.
+
+#![cfg(unix)]
+
+use std::{fs, path::Path, process::Command};
+
+use assert_cmd::prelude::*;
+
+fn git(
+    dir: &Path,
+    args: &[&str],
+) -> std::process::Output
+{
+    let bin_dir = assert_cmd::cargo::cargo_bin("rup")
+        .parent()
+        .unwrap()
+        .to_path_buf();
+    let path = format!(
+        "{}:{}",
+        bin_dir.display(),
+        std::env::var("PATH").unwrap_or_default()
+    );
+    Command::new("git")
+        .args(["-c", "user.name=Test", "-c", "user.email=test@example.com"])
+        .args(args)
+        .current_dir(dir)
+        .env("PATH", path)
+        .output()
+        .unwrap()
+}
+
+fn setup_repo() -> tempfile::TempDir
+{
+    let dir = tempfile::tempdir().unwrap();
+    fs::write(
+        dir.path()
+            .join("a.txt"),
+        "one\ntwo\n",
+    )
+    .unwrap();
+    assert!(
+        git(dir.path(), &["init", "-q"])
+            .status
+            .success()
+    );
+    assert!(
+        git(dir.path(), &["add", "."])
+            .status
+            .success()
+    );
+    assert!(
+        git(dir.path(), &["commit", "-q", "-m", "init"])
+            .status
+            .success()
+    );
+    dir
+}
+
+#[test]
+fn pre_commit_blocks_conflict_markers_and_bad_specs()
+{
+    let dir = setup_repo();
+    Command::cargo_bin("rup")
+        .unwrap()
+        .current_dir(dir.path())
+        .args(["--quiet", "git", "install-hooks"])
+        .assert()
+        .success();
+    let hook = dir
+        .path()
+        .join(".git/hooks/pre-commit");
+    assert!(
+        fs::read_to_string(&hook)
+            .unwrap()
+            .contains("git run-hook pre-commit")
+    );
+    assert!(
+        dir.path()
+            .join(".git/hooks/pre-push")
+            .exists()
+    );
+
+    // Unresolved conflict markers are rejected
+    fs::write(
+        dir.path()
+            .join("a.txt"),
+        "<<<<<<< HEAD\none\n=======\nuno\n>>>>>>> other\n",
+    )
+    .unwrap();
+    assert!(
+        git(dir.path(), &["add", "a.txt"])
+            .status
+            .success()
+    );
+    let out = git(dir.path(), &["commit", "-q", "-m", "conflicted"]);
+    assert!(
+        !out.status
+            .success()
+    );
+    assert!(String::from_utf8_lossy(&out.stderr).contains("a.txt:1: unresolved conflict markers"));
+
+    // So is an edit spec that fails check-syntax
+    fs::write(
+        dir.path()
+            .join("a.txt"),
+        "one\nTWO\n",
+    )
+    .unwrap();
+    fs::write(
+        dir.path()
+            .join("fix.ebnf"),
+        "FILE: a.txt\nREPLACE lines x-y:\n",
+    )
+    .unwrap();
+    assert!(
+        git(dir.path(), &["add", "."])
+            .status
+            .success()
+    );
+    let out = git(dir.path(), &["commit", "-q", "-m", "bad spec"]);
+    assert!(
+        !out.status
+            .success()
+    );
+    assert!(String::from_utf8_lossy(&out.stderr).contains("fix.ebnf:"));
+
+    // A valid spec commits
+    fs::write(
+        dir.path()
+            .join("fix.ebnf"),
+        "FILE: a.txt\nREPLACE lines 2-2:\nOLD:\n```\nTWO\n```\nNEW:\n```\n2\n```\n",
+    )
+    .unwrap();
+    assert!(
+        git(dir.path(), &["add", "."])
+            .status
+            .success()
+    );
+    let out = git(dir.path(), &["commit", "-q", "-m", "good spec"]);
+    assert!(
+        out.status
+            .success(),
+        "{}",
+        String::from_utf8_lossy(&out.stderr)
+    );
+}
+
+#[test]
+fn install_refuses_foreign_hook_without_force()
+{
+    let dir = setup_repo();
+    let hook = dir
+        .path()
+        .join(".git/hooks/pre-commit");
+    fs::write(&hook, "#!/bin/sh\nexit 0\n").unwrap();
+
+    Command::cargo_bin("rup")
+        .unwrap()
+        .current_dir(dir.path())
+        .args(["--quiet", "git", "install-hooks", "--hook", "pre-commit"])
+        .assert()
+        .failure();
+    assert_eq!(fs::read_to_string(&hook).unwrap(), "#!/bin/sh\nexit 0\n");
+
+    Command::cargo_bin("rup")
+        .unwrap()
+        .current_dir(dir.path())
+        .args(["--quiet", "git", "install-hooks", "--hook", "pre-commit", "--force"])
+        .assert()
+        .success();
+    assert!(
+        fs::read_to_string(&hook)
+            .unwrap()
+            .contains("git run-hook")
+    );
+    assert_eq!(
+        fs::read_to_string(
+            dir.path()
+                .join(".git/hooks/pre-commit.rup-backup")
+        )
+        .unwrap(),
+        "#!/bin/sh\nexit 0\n"
+    );
+}
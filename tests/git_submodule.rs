@@ -0,0 +1,131 @@
+//! Integration tests for submodule-aware git applies
+//!
+//! Edits below a submodule are applied inside the submodule's repository;
+//! a submodule that is not checked out is refused up front.
+
+#![cfg(unix)]
+
+use std::{fs, path::Path, process::Command};
+
+use assert_cmd::prelude::*;
+
+fn git(
+    dir: &Path,
+    args: &[&str],
+) -> String
+{
+    let out = Command::new("git")
+        .args(["-c", "user.name=Test", "-c", "user.email=test@example.com"])
+        .args(["-c", "protocol.file.allow=always"])
+        .args(args)
+        .current_dir(dir)
+        .output()
+        .unwrap();
+    assert!(
+        out.status
+            .success(),
+        "git {args:?} failed: {}",
+        String::from_utf8_lossy(&out.stderr)
+    );
+    String::from_utf8_lossy(&out.stdout).into_owned()
+}
+
+/// Superproject at `<tmp>/super` with submodule `lib` cloned from `<tmp>/lib`
+fn setup() -> tempfile::TempDir
+{
+    let tmp = tempfile::tempdir().unwrap();
+    let lib = tmp
+        .path()
+        .join("lib");
+    let sup = tmp
+        .path()
+        .join("super");
+    for dir in [&lib, &sup]
+    {
+        fs::create_dir(dir).unwrap();
+        git(dir, &["init", "-q"]);
+    }
+    fs::write(lib.join("x.txt"), "ex\nwhy\n").unwrap();
+    git(&lib, &["add", "."]);
+    git(&lib, &["commit", "-q", "-m", "lib"]);
+
+    fs::write(sup.join("a.txt"), "one\ntwo\n").unwrap();
+    git(&sup, &["add", "."]);
+    git(&sup, &[
+        "submodule",
+        "add",
+        "-q",
+        lib.to_str()
+            .unwrap(),
+        "lib",
+    ]);
+    git(&sup, &["commit", "-q", "-m", "super"]);
+    tmp
+}
+
+const SPEC: &str = "FILE: a.txt\nREPLACE lines \
+                    2-2:\nOLD:\n```\ntwo\n```\nNEW:\n```\nTWO\n```\n\nFILE: lib/x.txt\nREPLACE \
+                    lines 2-2:\nOLD:\n```\nwhy\n```\nNEW:\n```\nWHY\n```\n";
+
+#[test]
+fn edits_below_submodule_apply_in_the_submodule()
+{
+    let tmp = setup();
+    let sup = tmp
+        .path()
+        .join("super");
+    fs::write(sup.join("edit.txt"), SPEC).unwrap();
+
+    Command::cargo_bin("rup")
+        .unwrap()
+        .current_dir(&sup)
+        .args(["--quiet", "apply", "edit.txt", "--apply", "--engine", "git", "--git-mode", "index"])
+        .assert()
+        .success();
+
+    assert_eq!(fs::read_to_string(sup.join("a.txt")).unwrap(), "one\nTWO\n");
+    assert_eq!(
+        fs::read_to_string(sup.join("lib/x.txt")).unwrap(),
+        "ex\nWHY\n"
+    );
+    // Index mode updates each repository's own index
+    assert_eq!(
+        git(&sup.join("lib"), &["diff", "--cached", "--name-only"]).trim(),
+        "x.txt"
+    );
+    assert_eq!(
+        git(&sup, &["diff", "--cached", "--name-only"]).trim(),
+        "a.txt"
+    );
+}
+
+#[test]
+fn uninitialized_submodule_is_refused()
+{
+    let tmp = setup();
+    let clone = tmp
+        .path()
+        .join("clone");
+    git(tmp.path(), &["clone", "-q", "super", "clone"]);
+    // Not checked out: only a stray file where the submodule would be
+    fs::write(clone.join("lib/x.txt"), "ex\nwhy\n").unwrap();
+    fs::write(clone.join("edit.txt"), SPEC).unwrap();
+
+    let out = Command::cargo_bin("rup")
+        .unwrap()
+        .current_dir(&clone)
+        .args(["--quiet", "apply", "edit.txt", "--apply", "--engine", "git"])
+        .output()
+        .unwrap();
+    assert!(
+        !out.status
+            .success()
+    );
+    assert!(
+        String::from_utf8_lossy(&out.stderr).contains("submodule lib, which is not checked out")
+    );
+    assert_eq!(
+        fs::read_to_string(clone.join("a.txt")).unwrap(),
+        "one\ntwo\n"
+    );
+}
@@ -61,6 +61,7 @@ fn test_list_engine_filter_case_insensitive_and_aliases() -> Result<()>
         since: None,
         limit: 50,
         sort_desc: true,
+        label: None,
     };
     let listed = list_sessions_filtered(root, req)?;
     // Should include the Internal session even with uppercase filter
@@ -118,3 +119,38 @@ fn test_show_verbose_payload_size_excludes_metadata() -> Result<()>
 
     Ok(())
 }
+
+#[test]
+fn test_labels_resolve_and_filter() -> Result<()>
+{
+    let temp = setup_repo()?;
+    let root = temp.path();
+    fs::write(root.join("auth.rs"), "fn login() {}")?;
+
+    let mut m1 = BackupManager::begin(root, "internal")?;
+    m1.annotate(Some("pre-refactor auth".into()), Some("before token rework".into()));
+    m1.backup_file(std::path::Path::new("auth.rs"))?;
+    m1.finalize(true)?;
+
+    let mut m2 = BackupManager::begin(root, "internal")?;
+    m2.backup_file(std::path::Path::new("auth.rs"))?;
+    m2.finalize(true)?;
+
+    // A label resolves like an ID, even though m2 is newer
+    let resp = show_session(root, ShowRequest { id: "pre-refactor auth".into(), verbose: false })?;
+    assert_eq!(resp.manifest.id, m1.session_id());
+    assert_eq!(resp.manifest.note.as_deref(), Some("before token rework"));
+
+    let listed = list_sessions_filtered(root, ListRequest {
+        successful: false,
+        engine: None,
+        since: None,
+        limit: 50,
+        sort_desc: true,
+        label: Some("REFACTOR".into()),
+    })?;
+    assert_eq!(listed.len(), 1);
+    assert_eq!(listed[0].label.as_deref(), Some("pre-refactor auth"));
+
+    Ok(())
+}
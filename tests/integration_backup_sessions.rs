@@ -277,6 +277,8 @@ fn test_auto_fallback_shared_session() -> Result<()>
         whitespace: roughup::core::git::Whitespace::Nowarn,
         context_lines: 3,
         allow_outside_repo: false,
+        backend: roughup::core::git::GitBackend::Cli,
+        cached: false,
     };
     let engine = HybridEngine::new(true, false, git_options, true)?;
 
@@ -494,3 +496,64 @@ fn test_session_artifacts() -> Result<()>
 
     Ok(())
 }
+
+/// Test 6: a session round-trips through `git stash`
+#[test]
+fn test_session_stash_round_trip() -> Result<()>
+{
+    use roughup::core::{
+        backup::{backups_dir, payload_path, read_session_manifest},
+        backup_stash::{session_to_stash, stash_to_session},
+    };
+
+    let temp_repo = setup_test_repo()?;
+    let repo_root = temp_repo.path();
+    let lib = repo_root
+        .join("src")
+        .join("lib.rs");
+    let edited = "fn main() {\n    println!(\"stashed\");\n}\n";
+    fs::write(&lib, edited)?;
+
+    let mut mgr = BackupManager::begin(repo_root, "internal")?;
+    mgr.backup_file(std::path::Path::new("src/lib.rs"))?;
+    mgr.finalize(true)?;
+    let session_id = mgr
+        .session_id()
+        .to_string();
+
+    std::process::Command::new("git")
+        .args(["checkout", "--", "src/lib.rs"])
+        .current_dir(repo_root)
+        .output()?;
+
+    let stashed = session_to_stash(repo_root, &session_id, None)?;
+    assert_eq!(stashed.files, 1);
+    let list = std::process::Command::new("git")
+        .args(["stash", "list"])
+        .current_dir(repo_root)
+        .output()?;
+    assert!(String::from_utf8_lossy(&list.stdout).contains(&session_id));
+
+    let status = std::process::Command::new("git")
+        .args(["stash", "apply"])
+        .current_dir(repo_root)
+        .status()?;
+    assert!(status.success());
+    assert_eq!(fs::read_to_string(&lib)?, edited);
+
+    let back = stash_to_session(repo_root, "stash@{0}", Some("from-stash".into()))?;
+    assert_eq!(back.stash_commit, stashed.stash_commit);
+    let manifest = read_session_manifest(repo_root, &back.session_id)?;
+    assert_eq!(manifest.operation, "from-stash");
+    assert_eq!(
+        manifest
+            .label
+            .as_deref(),
+        Some("from-stash")
+    );
+    assert_eq!(manifest.files.len(), 1);
+    let session_dir = backups_dir(repo_root).join(&back.session_id);
+    let payload = payload_path(repo_root, &session_dir, &manifest.files[0]);
+    assert_eq!(fs::read_to_string(payload)?, edited);
+    Ok(())
+}
@@ -0,0 +1,69 @@
+//! Integration tests for Markdown docs: `rup extract README.md:#section`
+//! pulls a section next to code
+
+use std::process::Command;
+
+use assert_cmd::prelude::*;
+use assert_fs::prelude::*;
+use predicates::prelude::*;
+
+const README: &str = "# Widgets\n\nA widget toolkit.\n\n## Installation\n\n```bash\ncargo install \
+                      widgets\n```\n\n## Usage\n\nCall `render`.\n";
+
+fn make_fixture() -> assert_fs::TempDir
+{
+    let tmp = assert_fs::TempDir::new().expect("tempdir");
+    tmp.child("README.md")
+        .write_str(README)
+        .expect("write README.md");
+    tmp.child("src/lib.rs")
+        .write_str("pub fn render() -> String\n{\n    String::new()\n}\n")
+        .expect("write lib.rs");
+    tmp
+}
+
+#[test]
+fn extract_pulls_markdown_section_with_code()
+{
+    let tmp = make_fixture();
+    Command::cargo_bin("rup")
+        .expect("bin")
+        .current_dir(tmp.path())
+        .args([
+            "--quiet",
+            "extract",
+            "README.md:#installation",
+            "src/lib.rs:1-4",
+            "--budget",
+            "500",
+            "-o",
+            "out.txt",
+        ])
+        .assert()
+        .success();
+
+    let out = std::fs::read_to_string(
+        tmp.path()
+            .join("out.txt"),
+    )
+    .expect("read output");
+    assert!(
+        out.contains("## Installation\n\n```bash\ncargo install widgets\n```"),
+        "{out}"
+    );
+    assert!(!out.contains("## Usage"), "{out}");
+    assert!(out.contains("pub fn render()"), "{out}");
+}
+
+#[test]
+fn extract_reports_unknown_section()
+{
+    let tmp = make_fixture();
+    Command::cargo_bin("rup")
+        .expect("bin")
+        .current_dir(tmp.path())
+        .args(["--quiet", "extract", "README.md:#faq", "-o", "out.txt"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("no section '#faq' in README.md"));
+}
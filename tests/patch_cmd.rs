@@ -0,0 +1,291 @@
+//! Integration tests for `rup patch`
+//!
+//! Patches are written without touching the worktree and apply cleanly
+//! with `git apply`; mbox output applies as a series with `git am`.
+
+#![cfg(unix)]
+
+use std::{fs, path::Path, process::Command};
+
+use assert_cmd::prelude::*;
+
+fn git(
+    dir: &Path,
+    args: &[&str],
+) -> bool
+{
+    Command::new("git")
+        .args(["-c", "user.name=Test", "-c", "user.email=test@example.com"])
+        .args(args)
+        .current_dir(dir)
+        .output()
+        .unwrap()
+        .status
+        .success()
+}
+
+fn setup_repo() -> tempfile::TempDir
+{
+    let dir = tempfile::tempdir().unwrap();
+    fs::create_dir(
+        dir.path()
+            .join("src"),
+    )
+    .unwrap();
+    fs::write(
+        dir.path()
+            .join("a.txt"),
+        "one\ntwo\n",
+    )
+    .unwrap();
+    fs::write(
+        dir.path()
+            .join("src/b.txt"),
+        "alpha\nbeta\n",
+    )
+    .unwrap();
+    assert!(git(dir.path(), &["init", "-q"]));
+    assert!(git(dir.path(), &["add", "."]));
+    assert!(git(dir.path(), &["commit", "-q", "-m", "init"]));
+    dir
+}
+
+#[test]
+fn combined_patch_applies_with_git()
+{
+    let dir = setup_repo();
+    let spec = "FILE: a.txt\nREPLACE lines \
+                2-2:\nOLD:\n```\ntwo\n```\nNEW:\n```\nTWO\n```\n\nFILE: src/b.txt\nINSERT at \
+                0:\nNEW:\n```\nzero\n```\n";
+    fs::write(
+        dir.path()
+            .join("edit.txt"),
+        spec,
+    )
+    .unwrap();
+
+    Command::cargo_bin("rup")
+        .unwrap()
+        .current_dir(dir.path())
+        .args(["--quiet", "patch", "edit.txt", "-o", "all.patch"])
+        .assert()
+        .success();
+
+    // Worktree untouched until git applies the patch
+    assert_eq!(
+        fs::read_to_string(
+            dir.path()
+                .join("a.txt")
+        )
+        .unwrap(),
+        "one\ntwo\n"
+    );
+    assert!(git(dir.path(), &["apply", "all.patch"]));
+    assert_eq!(
+        fs::read_to_string(
+            dir.path()
+                .join("a.txt")
+        )
+        .unwrap(),
+        "one\nTWO\n"
+    );
+    assert_eq!(
+        fs::read_to_string(
+            dir.path()
+                .join("src/b.txt")
+        )
+        .unwrap(),
+        "zero\nalpha\nbeta\n"
+    );
+}
+
+#[test]
+fn split_writes_one_patch_per_file_from_json()
+{
+    let dir = setup_repo();
+    let spec = r#"{"file_blocks": [
+        {"path": "a.txt", "operations": [
+            {"op": "replace", "start_line": 2, "end_line": 2, "old_content": "two", "new_content": "TWO"}
+        ]},
+        {"path": "src/b.txt", "operations": [
+            {"op": "delete", "start_line": 1, "end_line": 1}
+        ]}
+    ]}"#;
+    fs::write(
+        dir.path()
+            .join("edit.json"),
+        spec,
+    )
+    .unwrap();
+
+    Command::cargo_bin("rup")
+        .unwrap()
+        .current_dir(dir.path())
+        .args(["--quiet", "patch", "edit.json", "--split", "-o", "out"])
+        .assert()
+        .success();
+
+    let mut names: Vec<String> = fs::read_dir(
+        dir.path()
+            .join("out"),
+    )
+    .unwrap()
+    .map(|e| {
+        e.unwrap()
+            .file_name()
+            .to_string_lossy()
+            .into_owned()
+    })
+    .collect();
+    names.sort();
+    assert_eq!(names, ["0001-a.txt.patch", "0002-src-b.txt.patch"]);
+
+    assert!(git(dir.path(), &["apply", "out/0002-src-b.txt.patch"]));
+    assert_eq!(
+        fs::read_to_string(
+            dir.path()
+                .join("src/b.txt")
+        )
+        .unwrap(),
+        "beta\n"
+    );
+    assert_eq!(
+        fs::read_to_string(
+            dir.path()
+                .join("a.txt")
+        )
+        .unwrap(),
+        "one\ntwo\n"
+    );
+}
+
+#[test]
+fn mbox_series_applies_with_git_am()
+{
+    let dir = setup_repo();
+    let spec = "FILE: a.txt\nREPLACE lines \
+                2-2:\nOLD:\n```\ntwo\n```\nNEW:\n```\nTWO\n```\n\nFILE: src/b.txt\nDELETE lines \
+                1-1\n";
+    fs::write(
+        dir.path()
+            .join("edit.txt"),
+        spec,
+    )
+    .unwrap();
+
+    Command::cargo_bin("rup")
+        .unwrap()
+        .current_dir(dir.path())
+        .args([
+            "--quiet",
+            "patch",
+            "edit.txt",
+            "--format",
+            "mbox",
+            "--subject",
+            "Edit {path} (+{added} -{removed})",
+            "--author",
+            "Jane Roe <jane@example.com>",
+            "-o",
+            "series.mbox",
+        ])
+        .assert()
+        .success();
+
+    let mbox = fs::read_to_string(
+        dir.path()
+            .join("series.mbox"),
+    )
+    .unwrap();
+    assert!(mbox.contains("Subject: [PATCH 1/2] Edit a.txt (+1 -1)"));
+    assert!(mbox.contains("Subject: [PATCH 2/2] Edit src/b.txt (+0 -1)"));
+    assert!(mbox.contains(" 1 file changed, 1 insertion(+), 1 deletion(-)"));
+
+    assert!(git(dir.path(), &["am", "-q", "series.mbox"]));
+    let log = Command::new("git")
+        .args(["log", "--format=%an <%ae>|%s", "-2"])
+        .current_dir(dir.path())
+        .output()
+        .unwrap();
+    assert_eq!(
+        String::from_utf8_lossy(&log.stdout),
+        "Jane Roe <jane@example.com>|Edit src/b.txt (+0 -1)\nJane Roe <jane@example.com>|Edit \
+         a.txt (+1 -1)\n"
+    );
+    assert_eq!(
+        fs::read_to_string(
+            dir.path()
+                .join("src/b.txt")
+        )
+        .unwrap(),
+        "beta\n"
+    );
+}
+
+#[test]
+fn context_rules_from_config_and_cli_shape_each_file()
+{
+    let dir = setup_repo();
+    fs::write(
+        dir.path()
+            .join("roughup.toml"),
+        "[[apply.context]]\nglob = \"*.txt\"\nlines = 1\n",
+    )
+    .unwrap();
+    let spec = "FILE: a.txt\nREPLACE lines \
+                2-2:\nOLD:\n```\ntwo\n```\nNEW:\n```\nTWO\n```\n\nFILE: src/b.txt\nREPLACE lines \
+                2-2:\nOLD:\n```\nbeta\n```\nNEW:\n```\nBETA\n```\n";
+    fs::write(
+        dir.path()
+            .join("edit.txt"),
+        spec,
+    )
+    .unwrap();
+
+    // The later command-line rule wins for src/
+    Command::cargo_bin("rup")
+        .unwrap()
+        .current_dir(dir.path())
+        .args(["--quiet", "patch", "edit.txt", "--context-for", "src/*=0", "-o", "all.patch"])
+        .assert()
+        .success();
+    let patch = fs::read_to_string(
+        dir.path()
+            .join("all.patch"),
+    )
+    .unwrap();
+    assert!(
+        patch.contains("@@ -1,2 +1,2 @@\n one\n-two\n+TWO\n"),
+        "{patch}"
+    );
+    assert!(patch.contains("@@ -2,1 +2,1 @@\n-beta\n+BETA\n"), "{patch}");
+    assert!(git(dir.path(), &["apply", "--unidiff-zero", "all.patch"]));
+
+    // The git engine passes --unidiff-zero for minimal-context patches itself
+    assert!(git(dir.path(), &["checkout", "--", "."]));
+    Command::cargo_bin("rup")
+        .unwrap()
+        .current_dir(dir.path())
+        .args([
+            "--quiet",
+            "apply",
+            "edit.txt",
+            "--apply",
+            "--engine",
+            "git",
+            "--git-mode",
+            "index",
+            "--context-for",
+            "*=0",
+        ])
+        .assert()
+        .success();
+    assert_eq!(
+        fs::read_to_string(
+            dir.path()
+                .join("src/b.txt")
+        )
+        .unwrap(),
+        "alpha\nBETA\n"
+    );
+}
@@ -0,0 +1,116 @@
+//! Integration tests for `git blame` attribution in `rup resolve`
+
+use std::{fs, path::Path, process::Command};
+
+use assert_cmd::prelude::*;
+
+fn git(
+    dir: &Path,
+    author: &str,
+    args: &[&str],
+)
+{
+    let status = Command::new("git")
+        .args(["-c", &format!("user.name={author}"), "-c", "user.email=test@example.com"])
+        .args(["-c", "init.defaultBranch=main"])
+        .args(args)
+        .current_dir(dir)
+        .output()
+        .unwrap()
+        .status;
+    assert!(status.success() || args[0] == "merge", "git {args:?}");
+}
+
+/// Repo mid-merge: Ann changed `run` on main, Bo on topic
+fn conflicted_repo() -> tempfile::TempDir
+{
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path();
+    fs::write(path.join("a.rs"), "fn main() {\n    run(0);\n}\n").unwrap();
+    git(path, "Init", &["init", "-q"]);
+    git(path, "Init", &["add", "."]);
+    git(path, "Init", &["commit", "-q", "-m", "init"]);
+    git(path, "Init", &["checkout", "-q", "-b", "topic"]);
+    fs::write(path.join("a.rs"), "fn main() {\n    run(2);\n}\n").unwrap();
+    git(path, "Bo", &["commit", "-q", "-am", "Run two"]);
+    git(path, "Init", &["checkout", "-q", "main"]);
+    fs::write(path.join("a.rs"), "fn main() {\n    run(1);\n}\n").unwrap();
+    git(path, "Ann", &["commit", "-q", "-am", "Run one"]);
+    git(path, "Ann", &["merge", "-q", "topic"]);
+    assert!(
+        fs::read_to_string(path.join("a.rs"))
+            .unwrap()
+            .contains("<<<<<<<")
+    );
+    dir
+}
+
+#[test]
+fn attributes_both_sides_in_json_and_prompt()
+{
+    let dir = conflicted_repo();
+    let out = Command::cargo_bin("rup")
+        .unwrap()
+        .current_dir(dir.path())
+        .args(["resolve", "a.rs", "--json", "--strategy", "interactive", "--emit-prompt", "out.md"])
+        .assert()
+        .success()
+        .get_output()
+        .stdout
+        .clone();
+    let json: serde_json::Value = serde_json::from_slice(&out).unwrap();
+    let attribution = &json["files"][0]["resolutions"][0]["attribution"];
+    assert_eq!(attribution["ours"][0]["author"], "Ann");
+    assert_eq!(attribution["ours"][0]["summary"], "Run one");
+    assert_eq!(attribution["theirs"][0]["author"], "Bo");
+    assert_eq!(attribution["theirs"][0]["lines"], 1);
+
+    let prompt = fs::read_to_string(
+        dir.path()
+            .join("out.md"),
+    )
+    .unwrap();
+    assert!(
+        prompt.contains("Ours (`HEAD`), last changed in "),
+        "{prompt}"
+    );
+    assert!(prompt.contains(" Ann: Run one"), "{prompt}");
+    assert!(
+        prompt.contains("Theirs (`topic`), last changed in "),
+        "{prompt}"
+    );
+    assert!(prompt.contains(" Bo: Run two"), "{prompt}");
+}
+
+#[test]
+fn text_output_lists_commits_unless_disabled()
+{
+    let dir = conflicted_repo();
+    let run = |extra: &[&str]| {
+        let out = Command::cargo_bin("rup")
+            .unwrap()
+            .current_dir(dir.path())
+            .args(["resolve", "a.rs", "--strategy", "interactive"])
+            .args(extra)
+            .assert()
+            .code(2)
+            .get_output()
+            .stdout
+            .clone();
+        String::from_utf8(out).unwrap()
+    };
+
+    let text = run(&[]);
+    assert!(text.contains("  Lines 2-6 (git-markers"), "{text}");
+    assert!(
+        text.contains("    ours:   ") && text.contains(" Ann: Run one"),
+        "{text}"
+    );
+    assert!(
+        text.contains("    theirs: ") && text.contains(" Bo: Run two"),
+        "{text}"
+    );
+
+    let text = run(&["--no-blame"]);
+    assert!(!text.contains("Ann"), "{text}");
+}
@@ -0,0 +1,96 @@
+//! Integration tests for `rup resolve --emit-prompt`: the skeleton in the
+//! prompt, once filled in, applies cleanly with `rup apply`
+
+use std::{fs, process::Command};
+
+use assert_cmd::prelude::*;
+
+const FILE: &str = "fn main() {\n    setup();\n<<<<<<< HEAD\n    run(1);\n=======\n    \
+                    run(2);\n>>>>>>> topic\n    done();\n}\n";
+
+#[test]
+fn filled_skeleton_applies()
+{
+    let dir = tempfile::tempdir().unwrap();
+    fs::write(
+        dir.path()
+            .join("a.rs"),
+        FILE,
+    )
+    .unwrap();
+    Command::cargo_bin("rup")
+        .unwrap()
+        .current_dir(dir.path())
+        .args([
+            "--quiet",
+            "resolve",
+            "a.rs",
+            "--strategy",
+            "interactive",
+            "--emit-prompt",
+            "out.md",
+        ])
+        .assert()
+        .code(2);
+
+    let prompt = fs::read_to_string(
+        dir.path()
+            .join("out.md"),
+    )
+    .unwrap();
+    assert!(
+        prompt.contains("## Conflict 1: `a.rs` lines 3-7"),
+        "{prompt}"
+    );
+    assert!(prompt.contains("Theirs (`topic`):\n```rs\n    run(2);\n```"));
+
+    // Pretend to be the model: fill the NEW block of the skeleton
+    let spec = prompt
+        .split("````\n")
+        .nth(1)
+        .unwrap()
+        .replace("<resolved code for conflict 1>", "    run(1);\n    run(2);");
+    fs::write(
+        dir.path()
+            .join("reply.txt"),
+        spec,
+    )
+    .unwrap();
+    Command::cargo_bin("rup")
+        .unwrap()
+        .current_dir(dir.path())
+        .args(["--quiet", "apply", "reply.txt", "--apply", "--engine", "internal"])
+        .assert()
+        .success();
+    assert_eq!(
+        fs::read_to_string(
+            dir.path()
+                .join("a.rs")
+        )
+        .unwrap(),
+        "fn main() {\n    setup();\n    run(1);\n    run(2);\n    done();\n}\n"
+    );
+}
+
+#[test]
+fn nothing_unresolved_writes_no_prompt()
+{
+    let dir = tempfile::tempdir().unwrap();
+    fs::write(
+        dir.path()
+            .join("a.rs"),
+        FILE,
+    )
+    .unwrap();
+    Command::cargo_bin("rup")
+        .unwrap()
+        .current_dir(dir.path())
+        .args(["--quiet", "resolve", "a.rs", "--strategy", "take-ours", "--emit-prompt", "out.md"])
+        .assert()
+        .success();
+    assert!(
+        !dir.path()
+            .join("out.md")
+            .exists()
+    );
+}
@@ -0,0 +1,92 @@
+//! Integration tests for `rup resolve --explain`
+
+use std::{fs, process::Command};
+
+use assert_cmd::prelude::*;
+
+const CONFLICT: &str =
+    "fn main() {\n<<<<<<< HEAD\n    run(1);\n=======\n    run(2);\n>>>>>>> topic\n}\n";
+
+#[test]
+fn json_explains_scores_and_decision()
+{
+    let dir = tempfile::tempdir().unwrap();
+    fs::write(
+        dir.path()
+            .join("a.rs"),
+        CONFLICT,
+    )
+    .unwrap();
+    let out = Command::cargo_bin("rup")
+        .unwrap()
+        .current_dir(dir.path())
+        .args(["resolve", "a.rs", "--json", "--explain", "--no-rerere"])
+        .assert()
+        .success()
+        .get_output()
+        .stdout
+        .clone();
+    let json: serde_json::Value = serde_json::from_slice(&out).unwrap();
+    let resolution = &json["files"][0]["resolutions"][0];
+    assert_eq!(resolution["conflict_type"], "git-markers");
+    assert_eq!(resolution["origin"], "git-markers");
+
+    let explain = &resolution["explain"];
+    assert_eq!(explain["labels"], serde_json::json!(["HEAD", "topic"]));
+    assert_eq!(explain["scores"]["whitespace_only"], 0.0);
+    assert_eq!(
+        explain["scores"]["balanced_delimiters"]
+            .as_f64()
+            .map(|v| (v * 100.0).round()),
+        Some(10.0)
+    );
+    let factors: Vec<&str> = explain["factors"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|f| {
+            f.as_str()
+                .unwrap()
+        })
+        .collect();
+    assert!(
+        factors
+            .iter()
+            .any(|f| f.starts_with("confidence 0.10 below threshold")),
+        "{factors:?}"
+    );
+    assert!(
+        factors
+            .last()
+            .unwrap()
+            .ends_with("(left for manual resolution)"),
+        "{factors:?}"
+    );
+}
+
+#[test]
+fn plain_json_has_no_explain_objects()
+{
+    let dir = tempfile::tempdir().unwrap();
+    fs::write(
+        dir.path()
+            .join("a.rs"),
+        CONFLICT,
+    )
+    .unwrap();
+    let out = Command::cargo_bin("rup")
+        .unwrap()
+        .current_dir(dir.path())
+        .args(["resolve", "a.rs", "--json", "--no-rerere"])
+        .assert()
+        .success()
+        .get_output()
+        .stdout
+        .clone();
+    let json: serde_json::Value = serde_json::from_slice(&out).unwrap();
+    assert!(
+        json["files"][0]["resolutions"][0]
+            .get("explain")
+            .is_none()
+    );
+}
@@ -0,0 +1,84 @@
+//! Integration tests for `rup resolve --interactive`
+
+use std::fs;
+
+use assert_cmd::Command;
+
+const CONFLICTS: &str = "a\n<<<<<<< HEAD\none\n=======\nuno\n>>>>>>> topic\nb\n<<<<<<< \
+                         HEAD\ntwo\n=======\ndos\n>>>>>>> topic\nc\n<<<<<<< \
+                         HEAD\nthree\n=======\ntres\n>>>>>>> topic\n";
+
+#[test]
+fn picks_are_written_with_a_backup_session()
+{
+    let dir = tempfile::tempdir().unwrap();
+    fs::write(
+        dir.path()
+            .join("f.txt"),
+        CONFLICTS,
+    )
+    .unwrap();
+
+    // Theirs, an inline edit, then skip the last one
+    let out = Command::cargo_bin("rup")
+        .unwrap()
+        .current_dir(dir.path())
+        .env("COLUMNS", "40")
+        .args(["resolve", "f.txt", "--interactive", "--repo-root", ".", "--no-rerere"])
+        .write_stdin("t\ne\nDOS\n.\ns\n")
+        .assert()
+        .code(2)
+        .get_output()
+        .stdout
+        .clone();
+    let out = String::from_utf8(out).unwrap();
+    assert!(out.contains("f.txt:2-6 (conflict 1/3)"), "{out}");
+    assert!(out.contains("one                │ uno"), "{out}");
+
+    assert_eq!(
+        fs::read_to_string(
+            dir.path()
+                .join("f.txt")
+        )
+        .unwrap(),
+        "a\nuno\nb\nDOS\nc\n<<<<<<< HEAD\nthree\n=======\ntres\n>>>>>>> topic\n"
+    );
+    let index = fs::read_to_string(
+        dir.path()
+            .join(".rup/backups/index.jsonl"),
+    )
+    .unwrap();
+    assert!(index.contains("\"resolve\""), "{index}");
+}
+
+#[test]
+fn quit_leaves_the_file_untouched()
+{
+    let dir = tempfile::tempdir().unwrap();
+    fs::write(
+        dir.path()
+            .join("f.txt"),
+        CONFLICTS,
+    )
+    .unwrap();
+    Command::cargo_bin("rup")
+        .unwrap()
+        .current_dir(dir.path())
+        .args(["--quiet", "resolve", "f.txt", "--interactive", "--repo-root", "."])
+        .write_stdin("q\n")
+        .assert()
+        .code(2);
+    assert_eq!(
+        fs::read_to_string(
+            dir.path()
+                .join("f.txt")
+        )
+        .unwrap(),
+        CONFLICTS
+    );
+    assert!(
+        !dir.path()
+            .join(".rup/backups/index.jsonl")
+            .exists()
+    );
+}
@@ -0,0 +1,50 @@
+//! Integration tests for malformed conflict blocks in `rup resolve`
+
+use std::{fs, process::Command};
+
+use assert_cmd::prelude::*;
+
+#[test]
+fn malformed_block_is_reported_and_left_alone()
+{
+    let dir = tempfile::tempdir().unwrap();
+    let content =
+        "<<<<<<< HEAD\na\n>>>>>>> topic\nmid\n<<<<<<< HEAD\nb\n=======\nc\n>>>>>>> topic\n";
+    fs::write(
+        dir.path()
+            .join("f.txt"),
+        content,
+    )
+    .unwrap();
+    let out = Command::cargo_bin("rup")
+        .unwrap()
+        .current_dir(dir.path())
+        .args(["resolve", "f.txt", "--json", "--strategy", "take-theirs", "--apply", "--no-rerere"])
+        .args(["--repo-root", "."])
+        .assert()
+        .success()
+        .get_output()
+        .stdout
+        .clone();
+    let json: serde_json::Value = serde_json::from_slice(&out).unwrap();
+    assert_eq!(json["diagnostics"][0]["line"], 3);
+    assert_eq!(json["files"][0]["total_conflicts"], 1);
+    assert_eq!(
+        fs::read_to_string(
+            dir.path()
+                .join("f.txt")
+        )
+        .unwrap(),
+        "<<<<<<< HEAD\na\n>>>>>>> topic\nmid\nc\n"
+    );
+
+    Command::cargo_bin("rup")
+        .unwrap()
+        .current_dir(dir.path())
+        .args(["resolve", "f.txt", "--no-rerere"])
+        .assert()
+        .success()
+        .stderr(predicates::str::contains(
+            "f.txt:3: conflict at line 1 closes without a ======= separator",
+        ));
+}
@@ -0,0 +1,99 @@
+//! Integration tests for `rup resolve --preview`
+
+use std::{fs, process::Command};
+
+use assert_cmd::prelude::*;
+
+const CONFLICT: &str = "a\n<<<<<<< HEAD\none\n=======\nuno\n>>>>>>> topic\nb\n";
+
+#[test]
+fn preview_compares_strategies_without_writing()
+{
+    let dir = tempfile::tempdir().unwrap();
+    fs::write(
+        dir.path()
+            .join("f.txt"),
+        CONFLICT,
+    )
+    .unwrap();
+    let out = Command::cargo_bin("rup")
+        .unwrap()
+        .current_dir(dir.path())
+        .args(["resolve", "f.txt", "--preview", "--json", "--no-rerere"])
+        .assert()
+        .success()
+        .get_output()
+        .stdout
+        .clone();
+    assert_eq!(
+        fs::read_to_string(
+            dir.path()
+                .join("f.txt")
+        )
+        .unwrap(),
+        CONFLICT
+    );
+
+    let json: serde_json::Value = serde_json::from_slice(&out).unwrap();
+    let entries = json["preview"]
+        .as_array()
+        .unwrap();
+    let strategies: Vec<&str> = entries
+        .iter()
+        .map(|e| {
+            e["strategy"]
+                .as_str()
+                .unwrap()
+        })
+        .collect();
+    assert_eq!(strategies, ["take-ours", "take-theirs", "union", "smart"]);
+    assert!(
+        entries[1]["diff"]
+            .as_str()
+            .unwrap()
+            .contains("-one\n-=======\n uno\n->>>>>>> topic\n")
+    );
+    assert!(
+        entries[2]["diff"]
+            .as_str()
+            .unwrap()
+            .contains(" one\n-=======\n uno\n")
+    );
+    assert_eq!(entries[3]["unresolved"], 1);
+    assert_eq!(entries[3]["diff"], "");
+
+    // The union the preview showed is one --strategy away
+    Command::cargo_bin("rup")
+        .unwrap()
+        .current_dir(dir.path())
+        .args(["--quiet", "resolve", "f.txt", "--strategy", "union", "--apply", "--no-rerere"])
+        .args(["--repo-root", "."])
+        .assert()
+        .success();
+    assert_eq!(
+        fs::read_to_string(
+            dir.path()
+                .join("f.txt")
+        )
+        .unwrap(),
+        "a\none\nuno\nb\n"
+    );
+}
+
+#[test]
+fn preview_refuses_apply()
+{
+    let dir = tempfile::tempdir().unwrap();
+    fs::write(
+        dir.path()
+            .join("f.txt"),
+        CONFLICT,
+    )
+    .unwrap();
+    Command::cargo_bin("rup")
+        .unwrap()
+        .current_dir(dir.path())
+        .args(["resolve", "f.txt", "--preview", "--apply"])
+        .assert()
+        .failure();
+}
@@ -0,0 +1,120 @@
+//! Integration tests for recorded conflict resolutions (`.rup/rerere`)
+
+use std::{fs, path::Path, process::Command};
+
+use assert_cmd::prelude::*;
+
+const CONFLICT: &str =
+    "fn main() {\n<<<<<<< HEAD\n    run(1);\n=======\n    run(2);\n>>>>>>> topic\n}\n";
+
+fn resolve(
+    dir: &Path,
+    file: &str,
+    extra: &[&str],
+) -> assert_cmd::assert::Assert
+{
+    Command::cargo_bin("rup")
+        .unwrap()
+        .current_dir(dir)
+        .args(["--quiet", "resolve", file, "--apply", "--repo-root", "."])
+        .args(extra)
+        .assert()
+}
+
+#[test]
+fn recorded_resolution_replays_in_later_session()
+{
+    let dir = tempfile::tempdir().unwrap();
+    fs::write(
+        dir.path()
+            .join("a.rs"),
+        CONFLICT,
+    )
+    .unwrap();
+    resolve(dir.path(), "a.rs", &["--strategy", "take-theirs"]).success();
+    assert_eq!(
+        fs::read_to_string(
+            dir.path()
+                .join("a.rs")
+        )
+        .unwrap(),
+        "fn main() {\n    run(2);\n}\n"
+    );
+    assert!(
+        dir.path()
+            .join(".rup/rerere")
+            .is_dir()
+    );
+
+    // Same conflict elsewhere, where the default strategy would need a human
+    fs::write(
+        dir.path()
+            .join("b.rs"),
+        CONFLICT,
+    )
+    .unwrap();
+    resolve(dir.path(), "b.rs", &[]).success();
+    assert_eq!(
+        fs::read_to_string(
+            dir.path()
+                .join("b.rs")
+        )
+        .unwrap(),
+        "fn main() {\n    run(2);\n}\n"
+    );
+
+    // Opting out, or asking for a strategy, leaves the conflict for a human
+    for (file, extra) in [("c.rs", "--no-rerere"), ("d.rs", "--strategy=interactive")]
+    {
+        fs::write(
+            dir.path()
+                .join(file),
+            CONFLICT,
+        )
+        .unwrap();
+        resolve(dir.path(), file, &[extra]).code(2);
+        assert_eq!(
+            fs::read_to_string(
+                dir.path()
+                    .join(file)
+            )
+            .unwrap(),
+            CONFLICT
+        );
+    }
+}
+
+#[test]
+fn explicit_strategy_is_reported_as_applied()
+{
+    let dir = tempfile::tempdir().unwrap();
+    for file in ["a.rs", "b.rs"]
+    {
+        fs::write(
+            dir.path()
+                .join(file),
+            CONFLICT,
+        )
+        .unwrap();
+    }
+    resolve(dir.path(), "a.rs", &["--strategy", "take-theirs"]).success();
+
+    // A recording exists, but take-ours is what was asked for and applied
+    let out = resolve(dir.path(), "b.rs", &["--strategy", "take-ours", "--json"])
+        .success()
+        .get_output()
+        .stdout
+        .clone();
+    let json: serde_json::Value = serde_json::from_slice(&out).unwrap();
+    let resolution = &json["files"][0]["resolutions"][0];
+    assert_eq!(resolution["strategy"], "take-ours");
+    assert_eq!(resolution["reason"], "Explicit choice: take ours");
+    assert_eq!(
+        fs::read_to_string(
+            dir.path()
+                .join("b.rs")
+        )
+        .unwrap(),
+        "fn main() {\n    run(1);\n}\n"
+    );
+}
@@ -0,0 +1,90 @@
+//! Integration tests for whitespace- and comment-only conflict resolution
+
+use std::{fs, path::Path, process::Command};
+
+use assert_cmd::prelude::*;
+
+const CONFLICT: &str = "fn main() {\n<<<<<<< HEAD\n    run(); // go\n=======\n    // Start\n    \
+                        run();\n>>>>>>> topic\n}\n";
+
+fn resolve(
+    dir: &Path,
+    extra: &[&str],
+) -> serde_json::Value
+{
+    let out = Command::cargo_bin("rup")
+        .unwrap()
+        .current_dir(dir)
+        .args(["resolve", "a.rs", "--apply", "--json", "--repo-root", ".", "--no-rerere"])
+        .args(extra)
+        .assert()
+        .success()
+        .get_output()
+        .stdout
+        .clone();
+    serde_json::from_slice(&out).unwrap()
+}
+
+#[test]
+fn trivial_side_comes_from_flag_then_config()
+{
+    let dir = tempfile::tempdir().unwrap();
+    fs::write(
+        dir.path()
+            .join("a.rs"),
+        CONFLICT,
+    )
+    .unwrap();
+    let json = resolve(dir.path(), &["--trivial-side", "theirs"]);
+    assert_eq!(json["files"][0]["trivial_resolved"], 1);
+    assert_eq!(
+        json["files"][0]["resolutions"][0]["reason"],
+        "comment-only-resolved"
+    );
+    assert_eq!(
+        fs::read_to_string(
+            dir.path()
+                .join("a.rs")
+        )
+        .unwrap(),
+        "fn main() {\n    // Start\n    run();\n}\n"
+    );
+
+    fs::write(
+        dir.path()
+            .join("a.rs"),
+        CONFLICT,
+    )
+    .unwrap();
+    fs::write(
+        dir.path()
+            .join("roughup.toml"),
+        "[resolve]\ntrivial_side = \"theirs\"\n",
+    )
+    .unwrap();
+    resolve(dir.path(), &[]);
+    assert_eq!(
+        fs::read_to_string(
+            dir.path()
+                .join("a.rs")
+        )
+        .unwrap(),
+        "fn main() {\n    // Start\n    run();\n}\n"
+    );
+
+    fs::write(
+        dir.path()
+            .join("a.rs"),
+        CONFLICT,
+    )
+    .unwrap();
+    resolve(dir.path(), &["--trivial-side", "ours"]);
+    assert_eq!(
+        fs::read_to_string(
+            dir.path()
+                .join("a.rs")
+        )
+        .unwrap(),
+        "fn main() {\n    run(); // go\n}\n"
+    );
+}
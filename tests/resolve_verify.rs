@@ -0,0 +1,182 @@
+//! Integration tests for `rup resolve --verify-cmd`: files the command
+//! fails on are restored with their conflicts from the backup session
+
+#![cfg(unix)]
+
+use std::{fs, path::Path, process::Command};
+
+use assert_cmd::prelude::*;
+
+fn conflict(theirs: &str) -> String
+{
+    format!("fn main() {{\n<<<<<<< HEAD\n    run(1);\n=======\n    {theirs}\n>>>>>>> topic\n}}\n")
+}
+
+fn setup() -> tempfile::TempDir
+{
+    let dir = tempfile::tempdir().unwrap();
+    fs::write(
+        dir.path()
+            .join("a.rs"),
+        conflict("bad(2);"),
+    )
+    .unwrap();
+    fs::write(
+        dir.path()
+            .join("b.rs"),
+        conflict("run(2);"),
+    )
+    .unwrap();
+    dir
+}
+
+fn resolve(
+    dir: &Path,
+    extra: &[&str],
+) -> serde_json::Value
+{
+    let out = Command::cargo_bin("rup")
+        .unwrap()
+        .current_dir(dir)
+        .args([
+            "--quiet",
+            "resolve",
+            "a.rs",
+            "b.rs",
+            "--strategy",
+            "take-theirs",
+            "--apply",
+            "--json",
+        ])
+        .args(extra)
+        .assert()
+        .success()
+        .get_output()
+        .stdout
+        .clone();
+    serde_json::from_slice(&out).unwrap()
+}
+
+#[test]
+fn rolls_back_only_the_files_the_command_names()
+{
+    let dir = setup();
+    // Fails, naming a.rs, while a.rs still contains `bad`
+    let json = resolve(dir.path(), &["--verify-cmd", "! grep -l bad a.rs b.rs"]);
+
+    assert_eq!(json["verify"]["success"], false);
+    assert_eq!(json["verify"]["rolled_back"], serde_json::json!(["a.rs"]));
+    assert_eq!(json["resolved_files"], serde_json::json!(["b.rs"]));
+    assert_eq!(json["files"][0]["rolled_back"], true);
+    assert_eq!(json["files"][0]["interactive_required"], 1);
+    assert_eq!(json["exit_code"], 2);
+    assert_eq!(
+        fs::read_to_string(
+            dir.path()
+                .join("a.rs")
+        )
+        .unwrap(),
+        conflict("bad(2);")
+    );
+    assert_eq!(
+        fs::read_to_string(
+            dir.path()
+                .join("b.rs")
+        )
+        .unwrap(),
+        "fn main() {\n    run(2);\n}\n"
+    );
+
+    // The rolled-back resolution was not recorded for replay
+    Command::cargo_bin("rup")
+        .unwrap()
+        .current_dir(dir.path())
+        .args(["--quiet", "resolve", "a.rs", "--apply"])
+        .assert()
+        .code(2);
+    assert_eq!(
+        fs::read_to_string(
+            dir.path()
+                .join("a.rs")
+        )
+        .unwrap(),
+        conflict("bad(2);")
+    );
+}
+
+#[test]
+fn rolled_back_replay_is_forgotten()
+{
+    let dir = setup();
+    // Record a.rs's resolution, then bring the same conflict back
+    resolve(dir.path(), &["--verify-cmd", "true"]);
+    fs::write(
+        dir.path()
+            .join("a.rs"),
+        conflict("bad(2);"),
+    )
+    .unwrap();
+    let replay = |extra: &[&str]| {
+        Command::cargo_bin("rup")
+            .unwrap()
+            .current_dir(dir.path())
+            .args(["--quiet", "resolve", "a.rs", "--apply"])
+            .args(extra)
+            .assert()
+            .code(2)
+    };
+
+    // The replay fails verification, so its recording goes too
+    replay(&["--verify-cmd", "! grep -l bad a.rs"]);
+    replay(&[]);
+    assert_eq!(
+        fs::read_to_string(
+            dir.path()
+                .join("a.rs")
+        )
+        .unwrap(),
+        conflict("bad(2);")
+    );
+}
+
+#[test]
+fn unnamed_failure_rolls_back_everything()
+{
+    let dir = setup();
+    let json = resolve(dir.path(), &["--verify-cmd", "exit 3", "--backup", "false"]);
+    assert_eq!(json["verify"]["status"], 3);
+    assert_eq!(json["resolved_files"], serde_json::json!([]));
+    assert_eq!(
+        fs::read_to_string(
+            dir.path()
+                .join("a.rs")
+        )
+        .unwrap(),
+        conflict("bad(2);")
+    );
+    assert_eq!(
+        fs::read_to_string(
+            dir.path()
+                .join("b.rs")
+        )
+        .unwrap(),
+        conflict("run(2);")
+    );
+}
+
+#[test]
+fn passing_command_keeps_resolutions()
+{
+    let dir = setup();
+    let json = resolve(dir.path(), &["--verify-cmd", "true"]);
+    assert_eq!(json["verify"]["success"], true);
+    assert_eq!(json["resolved_files"], serde_json::json!(["a.rs", "b.rs"]));
+    assert!(
+        !fs::read_to_string(
+            dir.path()
+                .join("a.rs")
+        )
+        .unwrap()
+        .contains("<<<<<<<")
+    );
+}
@@ -0,0 +1,114 @@
+//! Integration tests for `rup apply --sandbox`
+//!
+//! The trial apply and `--verify` run in a scratch worktree; the real tree
+//! is only written when both succeed.
+
+#![cfg(unix)]
+
+use std::{fs, path::Path, process::Command};
+
+use assert_cmd::prelude::*;
+
+fn git(
+    dir: &Path,
+    args: &[&str],
+)
+{
+    let ok = Command::new("git")
+        .args(["-c", "user.name=Test", "-c", "user.email=test@example.com"])
+        .args(args)
+        .current_dir(dir)
+        .output()
+        .unwrap()
+        .status
+        .success();
+    assert!(ok, "git {args:?} failed");
+}
+
+fn setup_repo() -> tempfile::TempDir
+{
+    let dir = tempfile::tempdir().unwrap();
+    fs::write(
+        dir.path()
+            .join("a.txt"),
+        "one\ntwo\n",
+    )
+    .unwrap();
+    git(dir.path(), &["init", "-q"]);
+    git(dir.path(), &["add", "."]);
+    git(dir.path(), &["commit", "-q", "-m", "init"]);
+
+    let spec = "FILE: a.txt\nREPLACE lines 2-2:\nOLD:\n```\ntwo\n```\nNEW:\n```\nTWO\n```\n";
+    fs::write(
+        dir.path()
+            .join("edit.txt"),
+        spec,
+    )
+    .unwrap();
+    dir
+}
+
+#[test]
+fn failing_verify_leaves_tree_untouched()
+{
+    let dir = setup_repo();
+
+    let output = Command::cargo_bin("rup")
+        .unwrap()
+        .current_dir(dir.path())
+        .args(["--quiet", "apply", "edit.txt", "--apply", "--sandbox"])
+        .args(["--verify", "grep -q NOPE a.txt"])
+        .output()
+        .unwrap();
+
+    assert!(
+        !output
+            .status
+            .success()
+    );
+    assert!(String::from_utf8_lossy(&output.stderr).contains("sandbox verification"));
+    assert_eq!(
+        fs::read_to_string(
+            dir.path()
+                .join("a.txt")
+        )
+        .unwrap(),
+        "one\ntwo\n"
+    );
+}
+
+#[test]
+fn passing_verify_replays_onto_tree()
+{
+    let dir = setup_repo();
+
+    Command::cargo_bin("rup")
+        .unwrap()
+        .current_dir(dir.path())
+        .args(["--quiet", "apply", "edit.txt", "--apply", "--sandbox"])
+        .args(["--verify", "grep -q TWO a.txt"])
+        .assert()
+        .success();
+
+    assert_eq!(
+        fs::read_to_string(
+            dir.path()
+                .join("a.txt")
+        )
+        .unwrap(),
+        "one\nTWO\n"
+    );
+
+    // The scratch worktree was unregistered
+    let list = Command::new("git")
+        .args(["worktree", "list"])
+        .current_dir(dir.path())
+        .output()
+        .unwrap();
+    assert_eq!(
+        String::from_utf8_lossy(&list.stdout)
+            .lines()
+            .count(),
+        1
+    );
+}
@@ -0,0 +1,144 @@
+//! Integration tests for `rup search`: fuzzy lookups over the symbol index
+//! with kind/visibility filters and JSON output
+
+use std::process::Command;
+
+use assert_cmd::prelude::*;
+use assert_fs::prelude::*;
+use serde_json::Value;
+
+fn make_fixture() -> assert_fs::TempDir
+{
+    let tmp = assert_fs::TempDir::new().expect("tempdir");
+    tmp.child("src/cache.rs")
+        .write_str(
+            "pub struct FetchCache;\n\nimpl FetchCache\n{\n    pub fn fetch_entry(&self) {}\n    \
+             fn evict(&self) {}\n}\n\npub fn clear_all() {}\n",
+        )
+        .expect("write cache.rs");
+    tmp.child("app/loader.py")
+        .write_str("def fetch_config(path):\n    return path\n")
+        .expect("write loader.py");
+    tmp
+}
+
+fn search_json(
+    tmp: &assert_fs::TempDir,
+    args: &[&str],
+) -> Value
+{
+    let out = Command::cargo_bin("rup")
+        .expect("bin")
+        .current_dir(tmp.path())
+        .args(["--quiet", "search", "--json"])
+        .args(args)
+        .assert()
+        .success()
+        .get_output()
+        .stdout
+        .clone();
+    serde_json::from_slice(&out).expect("json output")
+}
+
+fn names(output: &Value) -> Vec<&str>
+{
+    output["results"]
+        .as_array()
+        .expect("results")
+        .iter()
+        .map(|r| {
+            r["qualified_name"]
+                .as_str()
+                .unwrap()
+        })
+        .collect()
+}
+
+#[test]
+fn search_builds_the_index_and_ranks_matches()
+{
+    let tmp = make_fixture();
+    let output = search_json(&tmp, &["fetch"]);
+    assert_eq!(output["query"], "fetch");
+    assert_eq!(names(&output)[..3], [
+        "FetchCache",
+        "FetchCache::fetch_entry",
+        "fetch_config"
+    ]);
+    assert!(
+        tmp.path()
+            .join(".rup/symbols.jsonl")
+            .exists()
+    );
+
+    let first = &output["results"][0];
+    assert_eq!(first["file"], "src/cache.rs");
+    assert_eq!(first["kind"], "struct");
+    assert!(
+        first["score"]
+            .as_u64()
+            .unwrap()
+            > 0
+    );
+
+    // Terms can match the file path as well as the name
+    let output = search_json(&tmp, &["cache", "clr"]);
+    assert_eq!(names(&output), ["clear_all"]);
+}
+
+#[test]
+fn search_filters_by_kind_and_visibility()
+{
+    let tmp = make_fixture();
+    let output = search_json(&tmp, &["fetch", "--kind", "function,method"]);
+    assert_eq!(names(&output), [
+        "FetchCache::fetch_entry",
+        "fetch_config",
+        "FetchCache::evict"
+    ]);
+    let output = search_json(&tmp, &["e", "--kind", "method", "--visibility", "public"]);
+    assert_eq!(names(&output), ["FetchCache::fetch_entry"]);
+
+    let output = search_json(&tmp, &["e", "-n", "1"]);
+    assert_eq!(
+        output["results"]
+            .as_array()
+            .unwrap()
+            .len(),
+        1
+    );
+    assert!(
+        output["total"]
+            .as_u64()
+            .unwrap()
+            > 1
+    );
+
+    Command::cargo_bin("rup")
+        .expect("bin")
+        .current_dir(tmp.path())
+        .args(["search", "fetch", "--kind", "widget"])
+        .assert()
+        .failure()
+        .stderr(predicates::str::contains("unknown kind 'widget'"));
+}
+
+#[test]
+fn search_prints_one_row_per_match()
+{
+    let tmp = make_fixture();
+    let out = Command::cargo_bin("rup")
+        .expect("bin")
+        .current_dir(tmp.path())
+        .args(["--quiet", "search", "evict"])
+        .assert()
+        .success()
+        .get_output()
+        .stdout
+        .clone();
+    let stdout = String::from_utf8(out).expect("utf8");
+    assert_eq!(
+        stdout.trim_end(),
+        "src/cache.rs:6  method      FetchCache::evict"
+    );
+}
@@ -0,0 +1,69 @@
+//! Integration tests for C#: `rup symbols` indexes the partial classes
+//! under tests/fixtures/csharp one part per file
+
+use std::path::PathBuf;
+
+use assert_fs::prelude::*;
+use serde_json::Value;
+
+mod util;
+use util::index_symbols;
+
+fn make_fixture() -> assert_fs::TempDir
+{
+    let tmp = assert_fs::TempDir::new().expect("tempdir");
+    let fixtures = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/csharp");
+    for name in ["Widget.cs", "Widget.Render.cs"]
+    {
+        tmp.child(format!("src/{name}"))
+            .write_file(&fixtures.join(name))
+            .expect("copy fixture");
+    }
+    tmp
+}
+
+/// Index with private members, since C# fields default to private
+fn index(tmp: &assert_fs::TempDir) -> Vec<Value>
+{
+    index_symbols(tmp.path(), &["--include-private"])
+}
+
+#[test]
+fn partial_parts_merge_per_file()
+{
+    let tmp = make_fixture();
+    let symbols = index(&tmp);
+    let files: Vec<&str> = symbols
+        .iter()
+        .filter(|s| s["qualified_name"] == "Acme.Widgets::Widget")
+        .filter_map(|s| s["file"].as_str())
+        .collect();
+    // Two parts in Widget.cs merge; Widget.Render.cs keeps its own part
+    assert_eq!(files.len(), 2, "{files:?}");
+    assert!(
+        files
+            .iter()
+            .any(|f| f.ends_with("Widget.cs"))
+    );
+    assert!(
+        files
+            .iter()
+            .any(|f| f.ends_with("Widget.Render.cs"))
+    );
+
+    // The modifier-less part cannot know the type's visibility
+    let render_part = symbols
+        .iter()
+        .find(|s| {
+            s["qualified_name"] == "Acme.Widgets::Widget"
+                && s["file"]
+                    .as_str()
+                    .is_some_and(|f| f.ends_with("Widget.Render.cs"))
+        })
+        .expect("Render part");
+    assert!(
+        render_part
+            .get("visibility")
+            .is_none_or(Value::is_null)
+    );
+}
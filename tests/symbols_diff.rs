@@ -0,0 +1,126 @@
+//! Integration tests for `rup symbols diff`: public declarations added,
+//! removed, and changed between two git revisions
+
+use std::{path::Path, process::Command};
+
+use assert_cmd::prelude::*;
+use assert_fs::prelude::*;
+use serde_json::Value;
+
+fn git(
+    dir: &Path,
+    args: &[&str],
+)
+{
+    let status = Command::new("git")
+        .args(["-c", "user.name=Test", "-c", "user.email=test@example.com"])
+        .args(args)
+        .current_dir(dir)
+        .status()
+        .expect("run git");
+    assert!(status.success(), "git {args:?}");
+}
+
+/// Two tagged releases of a small library
+fn make_repo() -> assert_fs::TempDir
+{
+    let tmp = assert_fs::TempDir::new().expect("tempdir");
+    tmp.child("src/lib.rs")
+        .write_str(
+            "pub fn get(key: &str) -> Option<u32>\n{\n    None\n}\n\npub fn legacy() {}\n\npub fn \
+             stable() {}\n\nfn helper() {}\n\npub trait Store\n{\n    fn load(&self) {}\n}\n",
+        )
+        .expect("write lib.rs");
+    tmp.child("README.md")
+        .write_str("# Lib\n")
+        .expect("write README.md");
+    git(tmp.path(), &["init", "-q"]);
+    git(tmp.path(), &["add", "."]);
+    git(tmp.path(), &["commit", "-q", "-m", "v1"]);
+    git(tmp.path(), &["tag", "v1"]);
+
+    tmp.child("src/lib.rs")
+        .write_str(
+            "pub fn get<K: AsRef<str>>(key: K) -> Option<u32>\n{\n    None\n}\n\npub fn stable() \
+             {}\n\nfn helper(extra: u8) {}\n\npub trait Store\n{\n    fn load(&self, fresh: bool) \
+             {}\n}\n\npub struct Cache;\n",
+        )
+        .expect("rewrite lib.rs");
+    tmp.child("README.md")
+        .write_str("# Lib\n\n## Usage\n")
+        .expect("rewrite README.md");
+    git(tmp.path(), &["commit", "-q", "-am", "v2"]);
+    tmp
+}
+
+#[test]
+fn diff_reports_api_changes_between_revisions()
+{
+    let tmp = make_repo();
+    let out = Command::cargo_bin("rup")
+        .expect("bin")
+        .current_dir(tmp.path())
+        .args(["--quiet", "symbols", "diff", "v1", "HEAD", "--json"])
+        .assert()
+        .success()
+        .get_output()
+        .stdout
+        .clone();
+    let diff: Value = serde_json::from_slice(&out).expect("json output");
+    let names = |key: &str| -> Vec<String> {
+        diff[key]
+            .as_array()
+            .expect("array")
+            .iter()
+            .map(|s| {
+                s["qualified_name"]
+                    .as_str()
+                    .unwrap()
+                    .to_string()
+            })
+            .collect()
+    };
+
+    // Private helpers and Markdown headings are not API
+    assert_eq!(names("added"), ["Cache"]);
+    assert_eq!(names("removed"), ["legacy"]);
+    assert_eq!(names("changed"), ["get", "Store::load"]);
+    let get = &diff["changed"][0];
+    assert_eq!(get["before"], "pub fn get(key: &str) -> Option<u32>");
+    assert_eq!(
+        get["after"],
+        "pub fn get<K: AsRef<str>>(key: K) -> Option<u32>"
+    );
+    assert_eq!(diff["removed"][0]["signature"], "pub fn legacy()");
+}
+
+#[test]
+fn diff_prints_sections_and_rejects_unknown_revisions()
+{
+    let tmp = make_repo();
+    let out = Command::cargo_bin("rup")
+        .expect("bin")
+        .current_dir(tmp.path())
+        .args(["--quiet", "symbols", "diff", "v1", "HEAD"])
+        .assert()
+        .success()
+        .get_output()
+        .stdout
+        .clone();
+    let stdout = String::from_utf8(out).expect("utf8");
+    assert!(
+        stdout.contains("Added (1)\n  + Cache  (struct, src/lib.rs:15)\n      pub struct Cache")
+    );
+    assert!(stdout.contains("Removed (1)\n  - legacy  (function, src/lib.rs:6)"));
+    assert!(stdout.contains(
+        "  ~ get  (function, src/lib.rs:1)\n      - pub fn get(key: &str) -> Option<u32>\n"
+    ));
+
+    Command::cargo_bin("rup")
+        .expect("bin")
+        .current_dir(tmp.path())
+        .args(["symbols", "diff", "v1", "no-such-rev"])
+        .assert()
+        .failure()
+        .stderr(predicates::str::contains("unknown revision 'no-such-rev'"));
+}
@@ -0,0 +1,83 @@
+//! Integration tests for `rup symbols --embeddings`: a configured command
+//! embeds the symbols, and `rup context --semantic` ranks by similarity
+#![cfg(unix)]
+
+use std::process::Command;
+
+use assert_cmd::prelude::*;
+use assert_fs::prelude::*;
+
+mod util;
+use util::{read_jsonl, run_symbols};
+
+/// Embedder with two topics, storage and rendering, plus a constant axis
+const EMBEDDER: &str = r#"awk '
+NR == 1 {
+  n = split(substr($0, index($0, "\"texts\":[") + 9), texts, "\",\"")
+  for (i = 1; i <= n; i++) {
+    t = tolower(texts[i])
+    printf "[%d,%d,0.1]\n", t ~ /persist|storage|disk/, t ~ /draw|render|html|page/
+  }
+}'"#;
+
+fn make_fixture() -> assert_fs::TempDir
+{
+    let tmp = assert_fs::TempDir::new().expect("tempdir");
+    tmp.child("roughup.toml")
+        .write_str(&format!(
+            "[symbols.embeddings]\ncommand = [\"sh\", \"-c\", '''{EMBEDDER}''']\n"
+        ))
+        .expect("write config");
+    tmp.child("src/lib.rs")
+        .write_str(
+            "/// Write the record to persistent storage\npub fn save_to_disk(record: &str) -> \
+             usize\n{\n    record.len() + 7001\n}\n\n/// Draw the page as HTML\npub fn \
+             render_html(page: &str) -> String\n{\n    format!(\"<p>{page}</p>\")\n}\n",
+        )
+        .expect("write lib.rs");
+    tmp
+}
+
+#[test]
+fn semantic_context_ranks_by_embedding_similarity()
+{
+    let tmp = make_fixture();
+    run_symbols(tmp.path(), &["-l", "rust", "--embeddings"]);
+    let records = read_jsonl(
+        &tmp.path()
+            .join(".rup/symbols.embeddings.jsonl"),
+    );
+    assert_eq!(records.len(), 2, "{records:?}");
+    let save = records
+        .iter()
+        .find(|r| r["qualified_name"] == "save_to_disk")
+        .expect("save_to_disk");
+    assert_eq!(save["vector"], serde_json::json!([1.0, 0.0, 0.1]));
+
+    // No name shares a token with the query; only the vectors connect them
+    let out = Command::cargo_bin("rup")
+        .expect("bin")
+        .current_dir(tmp.path())
+        .args(["context", "--semantic", "persist records", "--budget", "2000"])
+        .output()
+        .expect("run context");
+    let stdout = String::from_utf8_lossy(&out.stdout);
+    assert!(stdout.contains("7001"), "{stdout}");
+    assert!(!stdout.contains("<p>"), "{stdout}");
+}
+
+#[test]
+fn embeddings_need_a_configured_embedder()
+{
+    let tmp = assert_fs::TempDir::new().expect("tempdir");
+    tmp.child("src/lib.rs")
+        .write_str("pub fn one() {}\n")
+        .expect("write lib.rs");
+    Command::cargo_bin("rup")
+        .expect("bin")
+        .current_dir(tmp.path())
+        .args(["--quiet", "symbols", "--embeddings"])
+        .assert()
+        .failure()
+        .stderr(predicates::str::contains("[symbols.embeddings]"));
+}
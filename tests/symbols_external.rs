@@ -0,0 +1,114 @@
+//! Integration tests for `[symbols.external]`: a configured binary indexes
+//! a language roughup doesn't build in, through the NDJSON protocol
+#![cfg(unix)]
+
+use std::{
+    fs::File,
+    process::Command,
+    time::{Duration, SystemTime},
+};
+
+use assert_cmd::prelude::*;
+use assert_fs::prelude::*;
+
+mod util;
+use util::{index_symbols, read_jsonl};
+
+/// Extractor reporting a fixed `start` function documented with the request path
+const EXTRACTOR: &str = r#"awk '
+NR == 1 { match($0, /"path":"[^"]*"/); path = substr($0, RSTART + 8, RLENGTH - 9) }
+END { printf "{\"kind\":\"function\",\"name\":\"start\",\"byte_start\":0,\"byte_end\":9,\"doc\":\"%s\"}\n", path }
+'"#;
+
+fn make_fixture(command: &str) -> assert_fs::TempDir
+{
+    let tmp = assert_fs::TempDir::new().expect("tempdir");
+    tmp.child("roughup.toml")
+        .write_str(&format!(
+            "[symbols.external.elixir]\ncommand = [\"sh\", \"-c\", {}]\nextensions = [\"ex\"]\n",
+            toml_string(command)
+        ))
+        .expect("write config");
+    tmp.child("lib/app.ex")
+        .write_str("def start\n  :ok\nend\n")
+        .expect("write app.ex");
+    tmp
+}
+
+fn toml_string(s: &str) -> String
+{
+    format!("'''{s}'''")
+}
+
+#[test]
+fn configured_extractor_feeds_the_symbol_index()
+{
+    let tmp = make_fixture(EXTRACTOR);
+    let symbols = index_symbols(tmp.path(), &[]);
+    let start = symbols
+        .iter()
+        .find(|s| s["name"] == "start")
+        .expect("start");
+    assert_eq!(start["lang"], "elixir", "{symbols:?}");
+    assert_eq!(start["kind"], "function");
+    assert_eq!(start["file"], "lib/app.ex");
+    assert_eq!(start["doc"], "lib/app.ex");
+    assert_eq!(start["start_line"], 1);
+}
+
+/// Editing a file claimed by an external extractor makes `rup context`
+/// rebuild the index, just like a built-in extension
+#[test]
+fn context_reindexes_after_edit_to_external_file()
+{
+    let tmp = make_fixture(EXTRACTOR);
+    index_symbols(tmp.path(), &[]);
+
+    // Only lib/app.ex now reads as edited after the index was written
+    let built = SystemTime::now() - Duration::from_secs(3600);
+    for (path, mtime) in
+        [(".rup/symbols.jsonl", built), ("roughup.toml", built - Duration::from_secs(60))]
+    {
+        File::options()
+            .write(true)
+            .open(
+                tmp.path()
+                    .join(path),
+            )
+            .and_then(|f| f.set_modified(mtime))
+            .expect("backdate");
+    }
+
+    Command::cargo_bin("rup")
+        .expect("bin")
+        .current_dir(tmp.path())
+        .args(["--quiet", "context", "start", "--json", "--budget", "2000"])
+        .assert()
+        .success();
+
+    let index = tmp
+        .path()
+        .join(".rup/symbols.jsonl");
+    let rebuilt = std::fs::metadata(&index)
+        .and_then(|m| m.modified())
+        .expect("index mtime");
+    assert!(rebuilt > built, "editing lib/app.ex left the index stale");
+    assert!(
+        read_jsonl(&index)
+            .iter()
+            .any(|s| s["lang"] == "elixir")
+    );
+}
+
+#[test]
+fn failing_extractor_reports_its_message()
+{
+    let tmp = make_fixture("echo 'no beam here' >&2; exit 1");
+    Command::cargo_bin("rup")
+        .expect("bin")
+        .current_dir(tmp.path())
+        .args(["--quiet", "symbols"])
+        .assert()
+        .failure()
+        .stderr(predicates::str::contains("no beam here"));
+}
@@ -0,0 +1,72 @@
+//! Integration tests for `--kind`/`--include`/`--exclude`: `rup symbols`
+//! indexes only the selection, and `rup context` picks only from it
+
+use std::process::Command;
+
+use assert_cmd::prelude::*;
+use assert_fs::prelude::*;
+
+mod util;
+use util::index_symbols;
+
+fn make_fixture() -> assert_fs::TempDir
+{
+    let tmp = assert_fs::TempDir::new().expect("tempdir");
+    tmp.child("src/cache.rs")
+        .write_str("pub struct Cache;\n\npub fn cache_get() -> u8\n{\n    7\n}\n")
+        .expect("write cache.rs");
+    tmp.child("src/gen/cache_table.rs")
+        .write_str("pub fn cache_generated() {}\n")
+        .expect("write cache_table.rs");
+    tmp.child("tests/cache_it.rs")
+        .write_str("pub fn cache_test_helper() {}\n")
+        .expect("write cache_it.rs");
+    tmp
+}
+
+#[test]
+fn symbols_indexes_only_selected_kinds_and_paths()
+{
+    let tmp = make_fixture();
+    let symbols = index_symbols(tmp.path(), &[
+        "--kind",
+        "fn",
+        "--include",
+        "src",
+        "--exclude",
+        "src/gen/**",
+    ]);
+    let names: Vec<&str> = symbols
+        .iter()
+        .filter_map(|s| s["name"].as_str())
+        .collect();
+    assert_eq!(names, ["cache_get"]);
+
+    Command::cargo_bin("rup")
+        .expect("bin")
+        .current_dir(tmp.path())
+        .args(["symbols", "--kind", "widget"])
+        .assert()
+        .failure()
+        .stderr(predicates::str::contains("unknown kind 'widget'"));
+}
+
+#[test]
+fn context_lookups_honor_kind_and_path_scope()
+{
+    let tmp = make_fixture();
+    let out = Command::cargo_bin("rup")
+        .expect("bin")
+        .current_dir(tmp.path())
+        .args(["--quiet", "context", "cache", "--kind", "fn", "--exclude", "src/gen", "--json"])
+        .assert()
+        .success()
+        .get_output()
+        .stdout
+        .clone();
+    let stdout = String::from_utf8(out).expect("utf8");
+    assert!(stdout.contains("cache_get"), "{stdout}");
+    assert!(stdout.contains("cache_test_helper"), "{stdout}");
+    assert!(!stdout.contains("cache_generated"), "{stdout}");
+    assert!(!stdout.contains("pub struct Cache"), "{stdout}");
+}
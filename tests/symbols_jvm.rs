@@ -0,0 +1,61 @@
+//! Integration tests for Java: `rup anchor --why` resolves the enclosing
+//! method from the parse tree
+
+use std::process::Command;
+
+use assert_cmd::prelude::*;
+use assert_fs::prelude::*;
+use serde_json::Value;
+
+fn make_fixture() -> assert_fs::TempDir
+{
+    let tmp = assert_fs::TempDir::new().expect("tempdir");
+    tmp.child("src/main/java/com/example/Widget.java")
+        .write_str(
+            "package com.example;\n\npublic class Widget {\n    public String render() {\n        \
+             return \"widget\";\n    }\n}\n",
+        )
+        .expect("write Widget.java");
+    tmp.child("src/main/kotlin/Registry.kt")
+        .write_str(
+            "package com.example\n\nclass Registry {\n    companion object {\n        fun \
+             create(): Registry = Registry()\n    }\n}\n",
+        )
+        .expect("write Registry.kt");
+    tmp
+}
+
+#[test]
+fn anchor_why_finds_java_method()
+{
+    let tmp = make_fixture();
+    let out = Command::cargo_bin("rup")
+        .expect("bin")
+        .current_dir(tmp.path())
+        .args([
+            "--quiet",
+            "anchor",
+            "--why",
+            "src/main/java/com/example/Widget.java:5",
+            "--format",
+            "json",
+        ])
+        .assert()
+        .success()
+        .get_output()
+        .stdout
+        .clone();
+    let stdout = String::from_utf8(out).expect("utf8");
+    let json = &stdout[stdout
+        .find('{')
+        .expect("json output")..];
+    let v: Value = serde_json::from_str(json).expect("valid json");
+    assert_eq!(v["status"], "Good", "{stdout}");
+    assert_eq!(
+        v["function"]["qualified_name"],
+        "com.example::Widget::render"
+    );
+    assert_eq!(v["function"]["kind"], "Method");
+    assert_eq!(v["function"]["start_line"], 4);
+    assert_eq!(v["function"]["end_line"], 6);
+}
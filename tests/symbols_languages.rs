@@ -0,0 +1,357 @@
+//! Integration tests over every indexed file extension: `rup symbols`
+//! indexes a small source per extension, and an edit to any of them makes
+//! `rup context` refresh the index before pulling the symbol in
+
+use std::{
+    fs::File,
+    path::Path,
+    process::Command,
+    time::{Duration, SystemTime},
+};
+
+use assert_cmd::prelude::*;
+use assert_fs::prelude::*;
+
+mod util;
+use util::{index_symbols, read_jsonl};
+
+/// One extension: a file to index and a symbol it must yield
+struct Case
+{
+    path: &'static str,
+    source: &'static str,
+    lang: &'static str,
+    kind: &'static str,
+    name: &'static str,
+}
+
+const CASES: &[Case] = &[
+    Case {
+        path: "src/lib.rs",
+        source: "pub fn render() -> String\n{\n    String::new()\n}\n",
+        lang: "rust",
+        kind: "function",
+        name: "render",
+    },
+    Case {
+        path: "scripts/report.py",
+        source: "def report():\n    return 1\n",
+        lang: "python",
+        kind: "function",
+        name: "report",
+    },
+    Case {
+        path: "src/widget.ts",
+        source: "export function makeWidget(id: string) {\n  return { id };\n}\n",
+        lang: "typescript",
+        kind: "function",
+        name: "makeWidget",
+    },
+    Case {
+        path: "src/view.tsx",
+        source: "export function View() {\n  return <div />;\n}\n",
+        lang: "typescript",
+        kind: "function",
+        name: "View",
+    },
+    Case {
+        path: "src/config.mts",
+        source: "export function loadConfig() {\n  return {};\n}\n",
+        lang: "typescript",
+        kind: "function",
+        name: "loadConfig",
+    },
+    Case {
+        path: "src/legacy.cts",
+        source: "export function legacyInit() {\n  return 1;\n}\n",
+        lang: "typescript",
+        kind: "function",
+        name: "legacyInit",
+    },
+    Case {
+        path: "lib/util.js",
+        source: "export const slugify = (s) => s.toLowerCase();\n",
+        lang: "javascript",
+        kind: "function",
+        name: "slugify",
+    },
+    Case {
+        path: "lib/button.jsx",
+        source: "export function Button() {\n  return <button />;\n}\n",
+        lang: "javascript",
+        kind: "function",
+        name: "Button",
+    },
+    Case {
+        path: "lib/entry.mjs",
+        source: "export function esmEntry() {\n  return 1;\n}\n",
+        lang: "javascript",
+        kind: "function",
+        name: "esmEntry",
+    },
+    Case {
+        path: "lib/common.cjs",
+        source: "function commonEntry() {\n  return 1;\n}\nmodule.exports = { commonEntry };\n",
+        lang: "javascript",
+        kind: "function",
+        name: "commonEntry",
+    },
+    Case {
+        path: "server/server.go",
+        source: "package server\n\ntype Server struct{ addr string }\n\nfunc (s *Server) Start() \
+                 error { return nil }\n",
+        lang: "go",
+        kind: "method",
+        name: "Start",
+    },
+    Case {
+        path: "src/Widget.java",
+        source:
+            "package com.example;\n\npublic class Widget {\n    public String render() {\n        \
+             return \"widget\";\n    }\n}\n",
+        lang: "java",
+        kind: "method",
+        name: "render",
+    },
+    Case {
+        path: "src/Registry.kt",
+        source: "package com.example\n\nclass Registry {\n    fun create(): Registry = \
+                 Registry()\n}\n",
+        lang: "kotlin",
+        kind: "method",
+        name: "create",
+    },
+    Case {
+        path: "build.gradle.kts",
+        source: "fun configureBuild() {\n    println(\"build\")\n}\n",
+        lang: "kotlin",
+        kind: "function",
+        name: "configureBuild",
+    },
+    Case {
+        path: "src/Gadget.cs",
+        source: "namespace Acme;\n\npublic class Gadget\n{\n    public string Render() { return \
+                 \"g\"; }\n}\n",
+        lang: "csharp",
+        kind: "method",
+        name: "Render",
+    },
+    Case {
+        path: "app/invoice.rb",
+        source: "class Invoice\n  def settle\n    :paid\n  end\nend\n",
+        lang: "ruby",
+        kind: "method",
+        name: "settle",
+    },
+    Case {
+        path: "lib/tasks/deploy.rake",
+        source: "class Deploy\n  def run_all\n    :ok\n  end\nend\n",
+        lang: "ruby",
+        kind: "method",
+        name: "run_all",
+    },
+    Case {
+        path: "src/Cart.php",
+        source: "<?php\nnamespace Shop;\n\nclass Cart\n{\n    public function applyDiscount(int \
+                 $cents): int\n    {\n        return $cents - 100;\n    }\n}\n",
+        lang: "php",
+        kind: "method",
+        name: "applyDiscount",
+    },
+    Case {
+        path: "README.md",
+        source: "# Widgets\n\n## Installation\n\nRun it.\n",
+        lang: "markdown",
+        kind: "heading",
+        name: "Installation",
+    },
+    Case {
+        path: "docs/guide.markdown",
+        source: "# Guide\n\n## Deployment\n\nShip it.\n",
+        lang: "markdown",
+        kind: "heading",
+        name: "Deployment",
+    },
+    Case {
+        path: "Cargo.toml",
+        source: "[package]\nname = \"demo\"\n\n[dependencies]\nserde = \"1\"\n",
+        lang: "toml",
+        kind: "key",
+        name: "serde",
+    },
+    Case {
+        path: "ci.yml",
+        source: "jobs:\n  build:\n    runs-on: ubuntu-latest\n",
+        lang: "yaml",
+        kind: "table",
+        name: "build",
+    },
+    Case {
+        path: "compose.yaml",
+        source: "services:\n  web:\n    image: nginx\n",
+        lang: "yaml",
+        kind: "table",
+        name: "web",
+    },
+    Case {
+        path: "package.json",
+        source: "{\n  \"scripts\": {\n    \"test\": \"jest\"\n  }\n}\n",
+        lang: "json",
+        kind: "key",
+        name: "test",
+    },
+    Case {
+        path: "migrations/0001_create_accounts.sql",
+        source: "CREATE TABLE accounts (\n    id BIGINT PRIMARY KEY\n);\n",
+        lang: "sql",
+        kind: "table",
+        name: "accounts",
+    },
+    Case {
+        path: "proto/billing.proto",
+        source: "syntax = \"proto3\";\n\npackage billing;\n\nservice Billing {\n  rpc \
+                 GetInvoice(Req) returns (Invoice);\n}\n",
+        lang: "proto",
+        kind: "method",
+        name: "GetInvoice",
+    },
+    Case {
+        path: "infra/main.tf",
+        source: "resource \"aws_s3_bucket\" \"logs\" {\n  bucket = \"acme-logs\"\n}\n",
+        lang: "hcl",
+        kind: "resource",
+        name: "aws_s3_bucket.logs",
+    },
+    Case {
+        path: "infra/terragrunt.hcl",
+        source: "variable \"region\" {\n  description = \"AWS region\"\n}\n",
+        lang: "hcl",
+        kind: "variable",
+        name: "var.region",
+    },
+    Case {
+        path: "scripts/release.sh",
+        source: "publish_release() {\n  git push --tags\n}\n",
+        lang: "bash",
+        kind: "function",
+        name: "publish_release",
+    },
+    Case {
+        path: "scripts/env.bash",
+        source: "setup_env() {\n  export READY=1\n}\n",
+        lang: "bash",
+        kind: "function",
+        name: "setup_env",
+    },
+    Case {
+        path: "src/Counter.vue",
+        source: "<template>\n  <p>{{ n }}</p>\n</template>\n\n<script setup \
+                 lang=\"ts\">\nfunction increment() {\n  return 1\n}\n</script>\n",
+        lang: "vue",
+        kind: "function",
+        name: "increment",
+    },
+    Case {
+        path: "src/Page.svelte",
+        source: "<script>\n  export function load() {}\n</script>\n",
+        lang: "svelte",
+        kind: "function",
+        name: "load",
+    },
+];
+
+/// Write `case`'s source into `tmp`
+fn write_case(
+    tmp: &assert_fs::TempDir,
+    case: &Case,
+)
+{
+    tmp.child(case.path)
+        .write_str(case.source)
+        .unwrap_or_else(|e| panic!("write {}: {e}", case.path));
+}
+
+fn modified(path: &Path) -> SystemTime
+{
+    std::fs::metadata(path)
+        .and_then(|m| m.modified())
+        .unwrap_or_else(|e| panic!("mtime of {}: {e}", path.display()))
+}
+
+#[test]
+fn symbols_index_covers_every_extension()
+{
+    let tmp = assert_fs::TempDir::new().expect("tempdir");
+    for case in CASES
+    {
+        write_case(&tmp, case);
+    }
+    let symbols = index_symbols(tmp.path(), &[]);
+    for case in CASES
+    {
+        assert!(
+            symbols
+                .iter()
+                .any(|s| {
+                    s["file"] == case.path
+                        && s["lang"] == case.lang
+                        && s["kind"] == case.kind
+                        && s["name"] == case.name
+                }),
+            "no {} {} `{}` in {}: {symbols:?}",
+            case.lang,
+            case.kind,
+            case.name,
+            case.path
+        );
+    }
+}
+
+/// Editing a file of any indexed extension after the index was built
+/// makes `rup context` rebuild it and pull the file's symbol in
+#[test]
+fn context_reindexes_after_edit_to_every_extension()
+{
+    for case in CASES
+    {
+        let tmp = assert_fs::TempDir::new().expect("tempdir");
+        write_case(&tmp, case);
+        index_symbols(tmp.path(), &[]);
+
+        // The source now reads as edited after the index was written
+        let index = tmp
+            .path()
+            .join(".rup/symbols.jsonl");
+        let built = SystemTime::now() - Duration::from_secs(3600);
+        File::options()
+            .write(true)
+            .open(&index)
+            .and_then(|f| f.set_modified(built))
+            .expect("backdate index");
+
+        let out = Command::cargo_bin("rup")
+            .expect("bin")
+            .current_dir(tmp.path())
+            .args(["--quiet", "context", case.name, "--json", "--budget", "2000"])
+            .assert()
+            .success()
+            .get_output()
+            .stdout
+            .clone();
+        let stdout = String::from_utf8(out).expect("utf8");
+
+        assert!(
+            modified(&index) > built,
+            "editing {} left the index stale",
+            case.path
+        );
+        assert!(
+            read_jsonl(&index)
+                .iter()
+                .any(|s| s["file"] == case.path && s["name"] == case.name),
+            "{} missing from the rebuilt index",
+            case.name
+        );
+        assert!(stdout.contains(case.path), "{}: {stdout}", case.path);
+    }
+}
@@ -0,0 +1,128 @@
+//! Integration tests for `rup symbols --refs`: call sites are recorded with
+//! their callers, and `rup context --callgraph` walks those edges
+
+use std::process::Command;
+
+use assert_cmd::prelude::*;
+use assert_fs::prelude::*;
+
+mod util;
+use util::{read_jsonl, run_symbols};
+
+fn make_fixture() -> assert_fs::TempDir
+{
+    let tmp = assert_fs::TempDir::new().expect("tempdir");
+    tmp.child("src/hash.rs")
+        .write_str("pub fn checksum(data: &[u8]) -> u32\n{\n    data.len() as u32\n}\n")
+        .expect("write hash.rs");
+    // A caller outside the anchor's directory, so only the refs index finds it
+    tmp.child("tools/upload.rs")
+        .write_str("pub fn verify_upload(blob: &[u8]) -> bool\n{\n    checksum(blob) == 42\n}\n")
+        .expect("write upload.rs");
+    tmp.child("app/main.py")
+        .write_str("def ship(blob):\n    return store(checksum(blob))\n")
+        .expect("write main.py");
+    tmp
+}
+
+#[test]
+fn refs_index_records_callers()
+{
+    let tmp = make_fixture();
+    run_symbols(tmp.path(), &["--refs"]);
+    let refs = read_jsonl(
+        &tmp.path()
+            .join(".rup/symbols.refs.jsonl"),
+    );
+    let callers: Vec<(&str, &str)> = refs
+        .iter()
+        .filter(|r| r["name"] == "checksum")
+        .map(|r| {
+            (
+                r["file"]
+                    .as_str()
+                    .unwrap(),
+                r["caller"]
+                    .as_str()
+                    .unwrap(),
+            )
+        })
+        .collect();
+    assert_eq!(callers, [
+        ("app/main.py", "ship"),
+        ("tools/upload.rs", "verify_upload")
+    ]);
+    let rust_call = refs
+        .iter()
+        .find(|r| r["file"] == "tools/upload.rs")
+        .unwrap();
+    assert_eq!(rust_call["line"], 3);
+}
+
+#[test]
+fn callgraph_context_includes_callers()
+{
+    let tmp = make_fixture();
+    Command::cargo_bin("rup")
+        .expect("bin")
+        .current_dir(tmp.path())
+        .args(["--quiet", "symbols", "--refs"])
+        .assert()
+        .success();
+
+    let out = Command::cargo_bin("rup")
+        .expect("bin")
+        .current_dir(tmp.path())
+        .args([
+            "--quiet",
+            "context",
+            "checksum",
+            "--callgraph",
+            "anchor=src/hash.rs:1 depth=1",
+            "--json",
+            "--budget",
+            "4000",
+        ])
+        .assert()
+        .success()
+        .get_output()
+        .stdout
+        .clone();
+    let stdout = String::from_utf8(out).expect("utf8");
+    assert!(stdout.contains("checksum(blob) == 42"), "{stdout}");
+}
+
+#[test]
+fn callgraph_walks_stored_edges_across_files()
+{
+    // No `rup symbols` run: --callgraph indexes with call sites on its own
+    let tmp = make_fixture();
+    let out = Command::cargo_bin("rup")
+        .expect("bin")
+        .current_dir(tmp.path())
+        .args([
+            "--quiet",
+            "context",
+            "verify_upload",
+            "--callgraph",
+            "anchor=tools/upload.rs:3 depth=2",
+            "--json",
+            "--budget",
+            "4000",
+        ])
+        .assert()
+        .success()
+        .get_output()
+        .stdout
+        .clone();
+    assert!(
+        tmp.path()
+            .join(".rup/symbols.refs.jsonl")
+            .exists()
+    );
+
+    // verify_upload -> checksum (hop 1) <- ship in Python (hop 2)
+    let stdout = String::from_utf8(out).expect("utf8");
+    assert!(stdout.contains("data.len() as u32"), "{stdout}");
+    assert!(stdout.contains("return store(checksum(blob))"), "{stdout}");
+}
@@ -0,0 +1,144 @@
+//! Integration tests for type relations: `rup symbols` records impl and
+//! inheritance edges, and `rup context --trait-resolve` walks them; Rust
+//! re-export aliases resolve to what they name
+
+use std::process::Command;
+
+use assert_cmd::prelude::*;
+use assert_fs::prelude::*;
+
+mod util;
+use util::{read_jsonl, run_symbols};
+
+fn make_fixture() -> assert_fs::TempDir
+{
+    let tmp = assert_fs::TempDir::new().expect("tempdir");
+    tmp.child("src/shape.rs")
+        .write_str("pub trait Shape: std::fmt::Debug\n{\n    fn area(&self) -> f64;\n}\n")
+        .expect("write shape.rs");
+    // Implementors live elsewhere, so only the relation edges reach them
+    tmp.child("geometry/circle.rs")
+        .write_str(
+            "pub struct Circle;\n\n\
+             impl Shape for Circle\n{\n    \
+                 fn area(&self) -> f64\n    {\n        \
+                     3.14159 * 2.0\n    \
+                 }\n\
+             }\n",
+        )
+        .expect("write circle.rs");
+    tmp.child("app/Square.java")
+        .write_str(
+            "class Square extends Polygon implements Shape {\n    double area() { return side * \
+             side; }\n}\n",
+        )
+        .expect("write Square.java");
+    tmp
+}
+
+#[test]
+fn symbols_records_impl_and_inheritance_edges()
+{
+    let tmp = make_fixture();
+    run_symbols(tmp.path(), &[]);
+    let edges: Vec<(String, String, String, u64)> = read_jsonl(
+        &tmp.path()
+            .join(".rup/symbols.relations.jsonl"),
+    )
+    .into_iter()
+    .map(|r| {
+        let text = |k: &str| {
+            r[k].as_str()
+                .unwrap()
+                .to_string()
+        };
+        (
+            text("kind"),
+            text("source"),
+            text("target"),
+            r["line"]
+                .as_u64()
+                .unwrap(),
+        )
+    })
+    .collect();
+    let edge = |kind: &str, source: &str, target: &str, line: u64| {
+        (
+            kind.to_string(),
+            source.to_string(),
+            target.to_string(),
+            line,
+        )
+    };
+    assert_eq!(edges, [
+        edge("extends", "Square", "Polygon", 1),
+        edge("implements", "Square", "Shape", 1),
+        edge("implements", "Circle", "Shape", 3),
+        edge("extends", "Shape", "Debug", 1),
+    ]);
+}
+
+#[test]
+fn trait_resolve_reaches_implementors()
+{
+    let tmp = make_fixture();
+    let out = Command::cargo_bin("rup")
+        .expect("bin")
+        .current_dir(tmp.path())
+        .args([
+            "--quiet",
+            "context",
+            "Shape",
+            "--trait-resolve",
+            "Shape::area",
+            "--json",
+            "--budget",
+            "4000",
+        ])
+        .assert()
+        .success()
+        .get_output()
+        .stdout
+        .clone();
+    let stdout = String::from_utf8(out).expect("utf8");
+    assert!(stdout.contains("3.14159 * 2.0"), "{stdout}");
+    assert!(stdout.contains("return side * side"), "{stdout}");
+}
+
+#[test]
+fn context_query_for_reexport_alias_reaches_definition()
+{
+    let tmp = assert_fs::TempDir::new().expect("tempdir");
+    tmp.child("src/engine.rs")
+        .write_str(
+            "pub struct Engine
+{
+    pub torque_newton_metres: u32,
+}
+",
+        )
+        .expect("write engine.rs");
+    tmp.child("src/lib.rs")
+        .write_str(
+            "mod engine;
+
+pub use crate::engine::Engine as Motor;
+",
+        )
+        .expect("write lib.rs");
+    let out = Command::cargo_bin("rup")
+        .expect("bin")
+        .current_dir(tmp.path())
+        .args(["--quiet", "context", "Motor", "--json", "--budget", "4000"])
+        .assert()
+        .success()
+        .get_output()
+        .stdout
+        .clone();
+    let stdout = String::from_utf8(out).expect("utf8");
+    assert!(
+        stdout.contains("pub use crate::engine::Engine as Motor"),
+        "{stdout}"
+    );
+    assert!(stdout.contains("torque_newton_metres"), "{stdout}");
+}
@@ -0,0 +1,155 @@
+//! Integration tests for `rup symbols --shard`: one index per workspace
+//! member, of which `rup context` loads and refreshes only what it needs
+
+use std::process::Command;
+
+use assert_cmd::prelude::*;
+use assert_fs::prelude::*;
+use serde_json::Value;
+
+fn make_workspace() -> assert_fs::TempDir
+{
+    let tmp = assert_fs::TempDir::new().expect("tempdir");
+    tmp.child("Cargo.toml")
+        .write_str("[workspace]\nmembers = [\"crates/*\"]\n")
+        .expect("write Cargo.toml");
+    for (member, name, value) in [("a", "alpha_marker", 4101), ("b", "beta_marker", 4202)]
+    {
+        tmp.child(format!("crates/{member}/Cargo.toml"))
+            .write_str(&format!("[package]\nname = \"{member}\"\n"))
+            .expect("write member Cargo.toml");
+        tmp.child(format!("crates/{member}/src/lib.rs"))
+            .write_str(&format!("pub fn {name}() -> u32\n{{\n    {value}\n}}\n"))
+            .expect("write lib.rs");
+    }
+    tmp
+}
+
+fn rup(
+    tmp: &assert_fs::TempDir,
+    args: &[&str],
+) -> std::process::Output
+{
+    Command::cargo_bin("rup")
+        .expect("bin")
+        .current_dir(tmp.path())
+        .args(args)
+        .output()
+        .expect("run rup")
+}
+
+#[test]
+fn context_reads_and_refreshes_only_the_anchored_shard()
+{
+    let tmp = make_workspace();
+    assert!(
+        rup(&tmp, &["--quiet", "symbols", "--shard"])
+            .status
+            .success()
+    );
+
+    let manifest: Value = serde_json::from_str(
+        &std::fs::read_to_string(
+            tmp.path()
+                .join(".rup/symbols.shards.json"),
+        )
+        .expect("read manifest"),
+    )
+    .expect("manifest json");
+    assert_eq!(
+        manifest["shards"],
+        serde_json::json!(["", "crates/a", "crates/b"])
+    );
+    let shard_b = tmp
+        .path()
+        .join(".rup/shards/crates__b.jsonl");
+    assert!(shard_b.exists());
+    assert!(
+        !tmp.path()
+            .join(".rup/symbols.jsonl")
+            .exists()
+    );
+
+    // Anchored in crate a, symbols of crate b are never loaded
+    let out = rup(&tmp, &[
+        "context",
+        "--anchor",
+        "crates/a/src/lib.rs",
+        "alpha_marker",
+        "beta_marker",
+    ]);
+    let stdout = String::from_utf8_lossy(&out.stdout);
+    assert!(stdout.contains("4101"), "{stdout}");
+    assert!(!stdout.contains("4202"), "{stdout}");
+
+    // An edit in crate b leaves its shard alone until a run needs it
+    let before = std::fs::metadata(&shard_b)
+        .and_then(|m| m.modified())
+        .expect("shard mtime");
+    std::thread::sleep(std::time::Duration::from_millis(20));
+    tmp.child("crates/b/src/lib.rs")
+        .write_str("pub fn beta_marker() -> u32\n{\n    4303\n}\n")
+        .expect("edit lib.rs");
+    let out = rup(&tmp, &[
+        "context",
+        "--anchor",
+        "crates/a/src/lib.rs",
+        "alpha_marker",
+    ]);
+    assert!(
+        out.status
+            .success()
+    );
+    let after = std::fs::metadata(&shard_b)
+        .and_then(|m| m.modified())
+        .expect("shard mtime");
+    assert_eq!(before, after);
+
+    let out = rup(&tmp, &[
+        "context",
+        "--anchor",
+        "crates/b/src/lib.rs",
+        "beta_marker",
+    ]);
+    let stderr = String::from_utf8_lossy(&out.stderr);
+    let stdout = String::from_utf8_lossy(&out.stdout);
+    assert!(stderr.contains("shard 'crates/b' stale"), "{stderr}");
+    assert!(stdout.contains("4303"), "{stdout}");
+}
+
+#[test]
+fn whole_index_replaces_shards()
+{
+    let tmp = make_workspace();
+    assert!(
+        rup(&tmp, &["--quiet", "symbols", "--shard"])
+            .status
+            .success()
+    );
+    assert!(
+        rup(&tmp, &["--quiet", "symbols"])
+            .status
+            .success()
+    );
+    assert!(
+        !tmp.path()
+            .join(".rup/symbols.shards.json")
+            .exists()
+    );
+    assert!(
+        !tmp.path()
+            .join(".rup/shards/crates__a.jsonl")
+            .exists()
+    );
+
+    // Search reads every shard of a sharded index
+    assert!(
+        rup(&tmp, &["--quiet", "symbols", "--shard"])
+            .status
+            .success()
+    );
+    let out = rup(&tmp, &["search", "marker", "--json"]);
+    let stdout = String::from_utf8_lossy(&out.stdout);
+    assert!(stdout.contains("alpha_marker"), "{stdout}");
+    assert!(stdout.contains("beta_marker"), "{stdout}");
+}
@@ -0,0 +1,35 @@
+//! Integration tests for `rup symbols --signatures`: declaration headers
+//! and docs land in the index, so outlines need no source reads
+
+use assert_fs::prelude::*;
+
+mod util;
+use util::index_symbols;
+
+#[test]
+fn signatures_are_opt_in()
+{
+    let tmp = assert_fs::TempDir::new().expect("tempdir");
+    tmp.child("src/store.rs")
+        .write_str(
+            "/// Fetch one entry.\npub fn fetch<K: Hash>(\n    key: &K,\n    ttl: Duration,\n) -> \
+             Option<Entry>\n{\n    None\n}\n",
+        )
+        .expect("write store.rs");
+
+    let plain = index_symbols(tmp.path(), &[]);
+    assert!(
+        plain[0]
+            .get("signature")
+            .is_none()
+    );
+
+    let symbols = index_symbols(tmp.path(), &["--signatures"]);
+    let fetch = &symbols[0];
+    assert_eq!(fetch["name"], "fetch");
+    assert_eq!(
+        fetch["signature"],
+        "pub fn fetch<K: Hash>( key: &K, ttl: Duration, ) -> Option<Entry>"
+    );
+    assert_eq!(fetch["doc"], "Fetch one entry.");
+}
@@ -0,0 +1,101 @@
+//! Integration tests for `rup symbols --format sqlite`: the index is also
+//! written as a SQLite database in the documented schema
+//!
+//! The export shells out to `sqlite3`; without it on PATH the test is a
+//! no-op.
+
+use std::{path::Path, process::Command};
+
+use assert_fs::prelude::*;
+
+mod util;
+use util::run_symbols;
+
+fn query(
+    db: &Path,
+    sql: &str,
+) -> String
+{
+    let out = Command::new("sqlite3")
+        .arg(db)
+        .arg(sql)
+        .output()
+        .expect("run sqlite3");
+    assert!(
+        out.status
+            .success(),
+        "{}",
+        String::from_utf8_lossy(&out.stderr)
+    );
+    String::from_utf8(out.stdout).expect("utf8")
+}
+
+#[test]
+fn sqlite_export_mirrors_the_index()
+{
+    if Command::new("sqlite3")
+        .arg("-version")
+        .output()
+        .is_err()
+    {
+        eprintln!("sqlite3 not on PATH; skipping");
+        return;
+    }
+
+    let tmp = assert_fs::TempDir::new().expect("tempdir");
+    tmp.child("src/shape.rs")
+        .write_str(
+            "/// A shape's area.\npub trait Area\n{\n    fn area(&self) -> f64 { 0.0 }\n}\n\npub \
+             struct Square;\n\nimpl Area for Square {}\n\npub fn total(s: &Square) -> f64\n{\n    \
+             s.area()\n}\n",
+        )
+        .expect("write shape.rs");
+    tmp.child("Cargo.toml")
+        .write_str("[package]\nname = \"shapes\"\n")
+        .expect("write Cargo.toml");
+    run_symbols(tmp.path(), &[
+        "--refs",
+        "--signatures",
+        "--format",
+        "sqlite",
+    ]);
+
+    let db = tmp
+        .path()
+        .join(".rup/symbols.sqlite");
+    assert!(
+        tmp.path()
+            .join(".rup/symbols.jsonl")
+            .exists()
+    );
+    assert_eq!(
+        query(&db, "SELECT value FROM meta WHERE key = 'schema_version'"),
+        "2\n"
+    );
+    assert_eq!(
+        query(
+            &db,
+            "SELECT kind, qualified_name, doc FROM symbols WHERE name = 'Area'"
+        ),
+        "trait|Area|A shape's area.\n"
+    );
+    assert_eq!(
+        query(&db, "SELECT signature FROM symbols WHERE name = 'total'"),
+        "pub fn total(s: &Square) -> f64\n"
+    );
+    assert_eq!(
+        query(
+            &db,
+            "SELECT package, module FROM symbols WHERE name = 'total'"
+        ),
+        "shapes|shapes::shape\n"
+    );
+    assert_eq!(
+        query(&db, "SELECT caller FROM refs WHERE name = 'area'"),
+        "total\n"
+    );
+    assert_eq!(
+        query(&db, "SELECT kind, source, target FROM relations"),
+        "implements|Square|Area\n"
+    );
+}
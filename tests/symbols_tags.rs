@@ -0,0 +1,77 @@
+//! Integration tests for `rup symbols --format ctags|etags`: the index is
+//! also written as an editor tags file in the scanned root
+
+use assert_fs::prelude::*;
+
+mod util;
+use util::run_symbols;
+
+fn make_fixture() -> assert_fs::TempDir
+{
+    let tmp = assert_fs::TempDir::new().expect("tempdir");
+    tmp.child("src/cache.rs")
+        .write_str("pub struct Cache;\n\nimpl Cache\n{\n    pub fn get(&self) {}\n}\n")
+        .expect("write cache.rs");
+    tmp
+}
+
+fn index(
+    tmp: &assert_fs::TempDir,
+    format: &str,
+)
+{
+    run_symbols(tmp.path(), &["--format", format]);
+    assert!(
+        tmp.path()
+            .join(".rup/symbols.jsonl")
+            .exists()
+    );
+}
+
+#[test]
+fn ctags_file_lists_symbols_by_name()
+{
+    let tmp = make_fixture();
+    index(&tmp, "ctags");
+    let tags = std::fs::read_to_string(
+        tmp.path()
+            .join("tags"),
+    )
+    .expect("read tags");
+    let lines: Vec<&str> = tags
+        .lines()
+        .filter(|l| !l.starts_with("!_TAG_"))
+        .collect();
+    assert!(
+        lines.contains(&"Cache\tsrc/cache.rs\t1;\"\tkind:struct\tline:1"),
+        "{tags}"
+    );
+    assert!(
+        lines.contains(&"get\tsrc/cache.rs\t5;\"\tkind:method\tline:5"),
+        "{tags}"
+    );
+    assert!(
+        lines.contains(&"Cache::get\tsrc/cache.rs\t5;\"\tkind:method\tline:5"),
+        "{tags}"
+    );
+    let mut sorted = lines.clone();
+    sorted.sort();
+    assert_eq!(lines, sorted);
+}
+
+#[test]
+fn etags_file_has_one_section_per_file()
+{
+    let tmp = make_fixture();
+    index(&tmp, "etags");
+    let tags = std::fs::read_to_string(
+        tmp.path()
+            .join("TAGS"),
+    )
+    .expect("read TAGS");
+    let entries = "pub struct Cache\x7fCache\x011,0\n    pub fn get\x7fget\x015,32\n";
+    assert_eq!(
+        tags,
+        format!("\x0c\nsrc/cache.rs,{}\n{entries}", entries.len())
+    );
+}
@@ -0,0 +1,89 @@
+//! Integration test for `rup symbols --watch`: the index follows file
+//! changes, and `rup context` uses it as is
+
+use std::{
+    process::{Command, Stdio},
+    time::{Duration, Instant},
+};
+
+use assert_cmd::prelude::*;
+use assert_fs::prelude::*;
+
+/// Poll `check` for up to 20 seconds
+fn wait_for(
+    what: &str,
+    check: impl Fn() -> bool,
+)
+{
+    let start = Instant::now();
+    while !check()
+    {
+        assert!(
+            start.elapsed() < Duration::from_secs(20),
+            "timed out waiting for {what}"
+        );
+        std::thread::sleep(Duration::from_millis(50));
+    }
+}
+
+#[test]
+fn watch_reindexes_changed_files()
+{
+    let tmp = assert_fs::TempDir::new().expect("tempdir");
+    tmp.child("src/lib.rs")
+        .write_str("pub fn first() {}\n")
+        .expect("write lib.rs");
+    let index = tmp
+        .path()
+        .join(".rup/symbols.jsonl");
+    let marker = tmp
+        .path()
+        .join(".rup/symbols.watch");
+
+    let mut watcher = Command::cargo_bin("rup")
+        .expect("bin")
+        .current_dir(tmp.path())
+        .args(["--quiet", "symbols", "--watch"])
+        .stdout(Stdio::null())
+        .spawn()
+        .expect("spawn watcher");
+    let read = |path: &std::path::Path| std::fs::read_to_string(path).unwrap_or_default();
+    wait_for("the first pass", || read(&marker).starts_with("idle"));
+    assert!(read(&index).contains("\"first\""));
+
+    tmp.child("src/extra.rs")
+        .write_str("pub fn second_added_while_watching() -> u32\n{\n    4242\n}\n")
+        .expect("write extra.rs");
+    wait_for("the new file", || {
+        read(&index).contains("second_added_while_watching")
+    });
+
+    std::fs::remove_file(
+        tmp.path()
+            .join("src/lib.rs"),
+    )
+    .expect("remove lib.rs");
+    wait_for("the removal", || !read(&index).contains("\"first\""));
+    wait_for("the rebuild to finish", || {
+        read(&marker).starts_with("idle")
+    });
+
+    // A watched index is fresh without a check or regeneration
+    let out = Command::cargo_bin("rup")
+        .expect("bin")
+        .current_dir(tmp.path())
+        .args(["context", "second_added_while_watching", "--budget", "2000"])
+        .output()
+        .expect("run context");
+    let stderr = String::from_utf8_lossy(&out.stderr);
+    assert!(
+        !stderr.contains("symbols index"),
+        "context regenerated a watched index: {stderr}"
+    );
+    assert!(String::from_utf8_lossy(&out.stdout).contains("4242"));
+
+    watcher
+        .kill()
+        .expect("stop watcher");
+    let _ = watcher.wait();
+}
@@ -3,7 +3,50 @@
 //! Provides common fixture creation and helper functions
 //! used across multiple test files.
 
+// Each test crate uses only some of these helpers
+#![allow(dead_code)]
+
+use std::{path::Path, process::Command};
+
+use assert_cmd::prelude::*;
 use assert_fs::prelude::*;
+use serde_json::Value;
+
+/// Run `rup --quiet symbols` with `args` in `dir`, expecting success.
+pub fn run_symbols(
+    dir: &Path,
+    args: &[&str],
+)
+{
+    Command::cargo_bin("rup")
+        .expect("bin")
+        .current_dir(dir)
+        .args(["--quiet", "symbols"])
+        .args(args)
+        .assert()
+        .success();
+}
+
+/// Parse each line of the JSONL file at `path`.
+pub fn read_jsonl(path: &Path) -> Vec<Value>
+{
+    std::fs::read_to_string(path)
+        .unwrap_or_else(|e| panic!("read {}: {e}", path.display()))
+        .lines()
+        .map(|l| serde_json::from_str(l).expect("json line"))
+        .collect()
+}
+
+/// Index `dir` with `rup --quiet symbols` plus `args`, and return the
+/// records of `.rup/symbols.jsonl`.
+pub fn index_symbols(
+    dir: &Path,
+    args: &[&str],
+) -> Vec<Value>
+{
+    run_symbols(dir, args);
+    read_jsonl(&dir.join(".rup/symbols.jsonl"))
+}
 
 /// Create a larger fixture to force trimming at small budgets.
 /// Synthesizes multiple moderately sized files to exceed tight
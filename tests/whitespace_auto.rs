@@ -0,0 +1,79 @@
+//! Integration tests for `--whitespace auto`
+//!
+//! Space-indented NEW content lands tab-indented in a tab-indented file.
+
+#![cfg(unix)]
+
+use std::{fs, path::Path, process::Command};
+
+use assert_cmd::prelude::*;
+
+fn git(
+    dir: &Path,
+    args: &[&str],
+)
+{
+    let out = Command::new("git")
+        .args(["-c", "user.name=Test", "-c", "user.email=test@example.com"])
+        .args(args)
+        .current_dir(dir)
+        .output()
+        .unwrap();
+    assert!(
+        out.status
+            .success(),
+        "git {args:?} failed"
+    );
+}
+
+#[test]
+fn auto_reindents_new_content_to_tabs()
+{
+    let dir = tempfile::tempdir().unwrap();
+    fs::write(
+        dir.path()
+            .join("main.go"),
+        "func f() {\n\tif x {\n\t\ty()\n\t}\n}\n",
+    )
+    .unwrap();
+    git(dir.path(), &["init", "-q"]);
+    git(dir.path(), &["add", "."]);
+    git(dir.path(), &["commit", "-q", "-m", "init"]);
+
+    let spec = "FILE: main.go\nREPLACE lines 2-4:\nOLD:\n```\n\tif x \
+                {\n\t\ty()\n\t}\n```\nNEW:\n```\n    if x {\n        y()\n        z()\n    \
+                }\n```\n";
+    fs::write(
+        dir.path()
+            .join("edit.txt"),
+        spec,
+    )
+    .unwrap();
+
+    Command::cargo_bin("rup")
+        .unwrap()
+        .current_dir(dir.path())
+        .args([
+            "--quiet",
+            "apply",
+            "edit.txt",
+            "--apply",
+            "--engine",
+            "git",
+            "--git-mode",
+            "index",
+            "--whitespace",
+            "auto",
+        ])
+        .assert()
+        .success();
+
+    assert_eq!(
+        fs::read_to_string(
+            dir.path()
+                .join("main.go")
+        )
+        .unwrap(),
+        "func f() {\n\tif x {\n\t\ty()\n\t\tz()\n\t}\n}\n"
+    );
+}